@@ -13,7 +13,7 @@
 // last synced with: cat (GNU coreutils) 8.13
 use clap::{crate_version, Arg, ArgAction, Command};
 use std::fs::{metadata, File};
-use std::io::{self, Read, Write};
+use std::io::{self, BufWriter, Read, Write};
 use thiserror::Error;
 use uucore::display::Quotable;
 use uucore::error::UResult;
@@ -451,6 +451,8 @@ fn write_fast<R: FdReadable>(handle: &mut InputHandle<R>) -> CatResult<()> {
         }
         stdout_lock.write_all(&buf[..n])?;
     }
+    // The next file may be written to the file descriptor directly again.
+    stdout_lock.flush()?;
     Ok(())
 }
 
@@ -463,74 +465,124 @@ fn write_lines<R: FdReadable>(
 ) -> CatResult<()> {
     let mut in_buf = [0; 1024 * 31];
     let stdout = io::stdout();
-    let mut writer = stdout.lock();
+    let mut writer = BufWriter::with_capacity(1024 * 64, stdout.lock());
+    let may_block = may_block(&handle.reader);
 
-    while let Ok(n) = handle.reader.read(&mut in_buf) {
-        if n == 0 {
-            break;
+    loop {
+        // Don't hold back the output while waiting for more input.
+        if may_block {
+            writer.flush()?;
         }
-        let in_buf = &in_buf[..n];
-        let mut pos = 0;
-        while pos < n {
-            // skip empty line_number enumerating them if needed
-            if in_buf[pos] == b'\n' {
-                // \r followed by \n is printed as ^M when show_ends is enabled, so that \r\n prints as ^M$
-                if state.skipped_carriage_return && options.show_ends {
-                    writer.write_all(b"^M")?;
-                    state.skipped_carriage_return = false;
-                }
-                if !state.at_line_start || !options.squeeze_blank || !state.one_blank_kept {
-                    state.one_blank_kept = true;
-                    if state.at_line_start && options.number == NumberingMode::All {
-                        write!(writer, "{0:6}\t", state.line_number)?;
-                        state.line_number += 1;
-                    }
-                    writer.write_all(options.end_of_line().as_bytes())?;
-                    if handle.is_interactive {
-                        writer.flush()?;
-                    }
-                }
-                state.at_line_start = true;
-                pos += 1;
-                continue;
-            }
-            if state.skipped_carriage_return {
-                writer.write_all(b"\r")?;
+        let n = match handle.reader.read(&mut in_buf) {
+            Ok(0) => break,
+            Ok(n) => n,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e.into()),
+        };
+        write_formatted(
+            &in_buf[..n],
+            options,
+            state,
+            handle.is_interactive,
+            &mut writer,
+        )?;
+    }
+    writer.flush()?;
+
+    Ok(())
+}
+
+/// Whether reading from `reader` may wait for more input, unlike reading a regular file.
+#[cfg(unix)]
+fn may_block<R: FdReadable>(reader: &R) -> bool {
+    use nix::sys::stat::{fstat, SFlag};
+    fstat(reader.as_raw_fd()).map_or(true, |stat| {
+        SFlag::from_bits_truncate(stat.st_mode) & SFlag::S_IFMT != SFlag::S_IFREG
+    })
+}
+
+#[cfg(not(unix))]
+fn may_block<R: FdReadable>(_reader: &R) -> bool {
+    true
+}
+
+/// Writes the formatted contents of `in_buf`, which continue the output described by `state`.
+///
+/// Lines are never collected, so the input may be split anywhere, and lines of any length are
+/// formatted with the memory of a single buffer.
+fn write_formatted<W: Write>(
+    in_buf: &[u8],
+    options: &OutputOptions,
+    state: &mut OutputState,
+    is_interactive: bool,
+    writer: &mut W,
+) -> io::Result<()> {
+    let mut pos = 0;
+    while pos < in_buf.len() {
+        // skip empty line_number enumerating them if needed
+        if in_buf[pos] == b'\n' {
+            // \r followed by \n is printed as ^M when show_ends is enabled, so that \r\n prints as ^M$
+            if state.skipped_carriage_return && options.show_ends {
+                writer.write_all(b"^M")?;
                 state.skipped_carriage_return = false;
-                state.at_line_start = false;
             }
-            state.one_blank_kept = false;
-            if state.at_line_start && options.number != NumberingMode::None {
-                write!(writer, "{0:6}\t", state.line_number)?;
-                state.line_number += 1;
+            if !state.at_line_start || !options.squeeze_blank || !state.one_blank_kept {
+                // The newline may also end a line started in the previous buffer.
+                state.one_blank_kept = state.at_line_start;
+                if state.at_line_start && options.number == NumberingMode::All {
+                    write!(writer, "{0:6}\t", state.line_number)?;
+                    state.line_number += 1;
+                }
+                writer.write_all(options.end_of_line().as_bytes())?;
+                if is_interactive {
+                    writer.flush()?;
+                }
             }
+            state.at_line_start = true;
+            pos += 1;
+            continue;
+        }
+        if state.skipped_carriage_return {
+            writer.write_all(b"\r")?;
+            state.skipped_carriage_return = false;
+            state.at_line_start = false;
+        }
+        state.one_blank_kept = false;
+        if state.at_line_start && options.number != NumberingMode::None {
+            write!(writer, "{0:6}\t", state.line_number)?;
+            state.line_number += 1;
+        }
+        state.at_line_start = false;
 
-            // print to end of line or end of buffer
-            let offset = if options.show_nonprint {
-                write_nonprint_to_end(&in_buf[pos..], &mut writer, options.tab().as_bytes())
-            } else if options.show_tabs {
-                write_tab_to_end(&in_buf[pos..], &mut writer)
-            } else {
-                write_to_end(&in_buf[pos..], &mut writer)
-            };
-            // end of buffer?
-            if offset + pos == in_buf.len() {
-                state.at_line_start = false;
-                break;
-            }
-            if in_buf[pos + offset] == b'\r' {
+        // print to end of line or end of buffer
+        let offset = if options.show_nonprint {
+            write_nonprint_to_end(&in_buf[pos..], writer, options.tab().as_bytes())?
+        } else if options.show_tabs {
+            write_tab_to_end(&in_buf[pos..], writer)?
+        } else {
+            write_to_end(&in_buf[pos..], writer)?
+        };
+        // end of buffer?
+        if offset + pos == in_buf.len() {
+            break;
+        }
+        if in_buf[pos + offset] == b'\r' {
+            // Only a \r right before a \n is shown differently, as ^M$ for -E.
+            if options.show_ends {
                 state.skipped_carriage_return = true;
             } else {
-                assert_eq!(in_buf[pos + offset], b'\n');
-                // print suitable end of line
-                writer.write_all(options.end_of_line().as_bytes())?;
-                if handle.is_interactive {
-                    writer.flush()?;
-                }
-                state.at_line_start = true;
+                writer.write_all(b"\r")?;
             }
-            pos += offset + 1;
+        } else {
+            assert_eq!(in_buf[pos + offset], b'\n');
+            // print suitable end of line
+            writer.write_all(options.end_of_line().as_bytes())?;
+            if is_interactive {
+                writer.flush()?;
+            }
+            state.at_line_start = true;
         }
+        pos += offset + 1;
     }
 
     Ok(())
@@ -541,20 +593,20 @@ fn write_lines<R: FdReadable>(
 // We need to stop at \r because it may be written as ^M depending on the byte after and settings;
 // however, write_nonprint_to_end doesn't need to stop at \r because it will always write \r as ^M.
 // Return the number of written symbols
-fn write_to_end<W: Write>(in_buf: &[u8], writer: &mut W) -> usize {
+fn write_to_end<W: Write>(in_buf: &[u8], writer: &mut W) -> io::Result<usize> {
     match in_buf.iter().position(|c| *c == b'\n' || *c == b'\r') {
         Some(p) => {
-            writer.write_all(&in_buf[..p]).unwrap();
-            p
+            writer.write_all(&in_buf[..p])?;
+            Ok(p)
         }
         None => {
-            writer.write_all(in_buf).unwrap();
-            in_buf.len()
+            writer.write_all(in_buf)?;
+            Ok(in_buf.len())
         }
     }
 }
 
-fn write_tab_to_end<W: Write>(mut in_buf: &[u8], writer: &mut W) -> usize {
+fn write_tab_to_end<W: Write>(mut in_buf: &[u8], writer: &mut W) -> io::Result<usize> {
     let mut count = 0;
     loop {
         match in_buf
@@ -562,56 +614,107 @@ fn write_tab_to_end<W: Write>(mut in_buf: &[u8], writer: &mut W) -> usize {
             .position(|c| *c == b'\n' || *c == b'\t' || *c == b'\r')
         {
             Some(p) => {
-                writer.write_all(&in_buf[..p]).unwrap();
+                writer.write_all(&in_buf[..p])?;
                 if in_buf[p] == b'\t' {
-                    writer.write_all(b"^I").unwrap();
+                    writer.write_all(b"^I")?;
                     in_buf = &in_buf[p + 1..];
                     count += p + 1;
                 } else {
                     // b'\n' or b'\r'
-                    return count + p;
+                    return Ok(count + p);
                 }
             }
             None => {
-                writer.write_all(in_buf).unwrap();
-                return in_buf.len();
+                writer.write_all(in_buf)?;
+                return Ok(count + in_buf.len());
             }
         };
     }
 }
 
-fn write_nonprint_to_end<W: Write>(in_buf: &[u8], writer: &mut W, tab: &[u8]) -> usize {
-    let mut count = 0;
+fn write_nonprint_to_end<W: Write>(in_buf: &[u8], writer: &mut W, tab: &[u8]) -> io::Result<usize> {
+    let end = in_buf
+        .iter()
+        .position(|c| *c == b'\n')
+        .unwrap_or(in_buf.len());
+    let mut rest = &in_buf[..end];
 
-    for byte in in_buf.iter().copied() {
-        if byte == b'\n' {
-            break;
-        }
+    while !rest.is_empty() {
+        // Runs of printable characters are written as they are, in one go.
+        let printable = rest
+            .iter()
+            .position(|c| !(32..=126).contains(c))
+            .unwrap_or(rest.len());
+        writer.write_all(&rest[..printable])?;
+        let byte = match rest.get(printable) {
+            Some(byte) => *byte,
+            None => break,
+        };
         match byte {
             9 => writer.write_all(tab),
             0..=8 | 10..=31 => writer.write_all(&[b'^', byte + 64]),
-            32..=126 => writer.write_all(&[byte]),
             127 => writer.write_all(&[b'^', b'?']),
             128..=159 => writer.write_all(&[b'M', b'-', b'^', byte - 64]),
             160..=254 => writer.write_all(&[b'M', b'-', byte - 128]),
             _ => writer.write_all(&[b'M', b'-', b'^', b'?']),
-        }
-        .unwrap();
-        count += 1;
+        }?;
+        rest = &rest[printable + 1..];
     }
-    count
+    Ok(end)
 }
 
 #[cfg(test)]
 mod tests {
     use std::io::{stdout, BufWriter};
 
+    use super::{NumberingMode, OutputOptions, OutputState};
+
+    fn format_in_chunks(input: &[u8], options: &OutputOptions, chunk_size: usize) -> Vec<u8> {
+        let mut state = OutputState {
+            line_number: 1,
+            at_line_start: true,
+            skipped_carriage_return: false,
+            one_blank_kept: false,
+        };
+        let mut output = Vec::new();
+        for chunk in input.chunks(chunk_size) {
+            super::write_formatted(chunk, options, &mut state, false, &mut output).unwrap();
+        }
+        output
+    }
+
+    #[test]
+    fn test_write_formatted_in_chunks() {
+        let input = b"a\tb\r\n\n\n\nline\r\x01\x7f\xc3\xa9\n\tlast\r";
+        for flags in 0..48 {
+            let options = OutputOptions {
+                number: match flags / 16 {
+                    0 => NumberingMode::None,
+                    1 => NumberingMode::NonEmpty,
+                    _ => NumberingMode::All,
+                },
+                squeeze_blank: flags & 1 != 0,
+                show_tabs: flags & 2 != 0,
+                show_ends: flags & 4 != 0,
+                show_nonprint: flags & 8 != 0,
+            };
+            let expected = format_in_chunks(input, &options, input.len());
+            for chunk_size in 1..input.len() {
+                assert_eq!(
+                    format_in_chunks(input, &options, chunk_size),
+                    expected,
+                    "{flags} {chunk_size}"
+                );
+            }
+        }
+    }
+
     #[test]
     fn test_write_nonprint_to_end_new_line() {
         let mut writer = BufWriter::with_capacity(1024 * 64, stdout());
         let in_buf = b"\n";
         let tab = b"";
-        super::write_nonprint_to_end(in_buf, &mut writer, tab);
+        super::write_nonprint_to_end(in_buf, &mut writer, tab).unwrap();
         assert_eq!(writer.buffer().len(), 0);
     }
 
@@ -620,7 +723,7 @@ mod tests {
         let mut writer = BufWriter::with_capacity(1024 * 64, stdout());
         let in_buf = &[9u8];
         let tab = b"tab";
-        super::write_nonprint_to_end(in_buf, &mut writer, tab);
+        super::write_nonprint_to_end(in_buf, &mut writer, tab).unwrap();
         assert_eq!(writer.buffer(), tab);
     }
 
@@ -630,7 +733,7 @@ mod tests {
             let mut writer = BufWriter::with_capacity(1024 * 64, stdout());
             let in_buf = &[byte];
             let tab = b"";
-            super::write_nonprint_to_end(in_buf, &mut writer, tab);
+            super::write_nonprint_to_end(in_buf, &mut writer, tab).unwrap();
             assert_eq!(writer.buffer(), [b'^', byte + 64]);
         }
     }
@@ -641,7 +744,7 @@ mod tests {
             let mut writer = BufWriter::with_capacity(1024 * 64, stdout());
             let in_buf = &[byte];
             let tab = b"";
-            super::write_nonprint_to_end(in_buf, &mut writer, tab);
+            super::write_nonprint_to_end(in_buf, &mut writer, tab).unwrap();
             assert_eq!(writer.buffer(), [b'^', byte + 64]);
         }
     }
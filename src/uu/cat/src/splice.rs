@@ -1,5 +1,8 @@
 use super::{CatResult, FdReadable, InputHandle};
 
+use nix::fcntl::copy_file_range;
+use nix::sys::sendfile::sendfile;
+use nix::sys::stat::{fstat, FileStat, SFlag};
 use nix::unistd;
 use std::os::unix::io::{AsRawFd, RawFd};
 
@@ -7,11 +10,24 @@ use uucore::pipes::{pipe, splice, splice_exact};
 
 const SPLICE_SIZE: usize = 1024 * 128;
 const BUF_SIZE: usize = 1024 * 16;
+/// The number of bytes `copy_file_range()` and `sendfile()` are asked to copy at once, which
+/// only return early at the end of the input.
+const COPY_SIZE: usize = 1024 * 1024 * 1024;
 
 /// This function is called from `write_fast()` on Linux and Android. The
-/// function `splice()` is used to move data between two file descriptors
-/// without copying between kernel and user spaces. This results in a large
-/// speedup.
+/// data is moved between the two file descriptors without copying it between
+/// kernel and user spaces, which results in a large speedup:
+///
+/// - `copy_file_range()` copies from a regular file to another one, which
+///   some file systems do without even reading the data.
+/// - `sendfile()` copies from a regular file to anything else.
+/// - `splice()` moves the data directly if either side is a pipe, or else
+///   through an intermediate pipe.
+///
+/// Each of them falls back to the next one if it fails, e.g. because the
+/// output is opened for appending, which `copy_file_range()` doesn't support.
+/// Both the input and the output are read and written at their current
+/// positions, so a fallback continues where the previous method stopped.
 ///
 /// The `bool` in the result value indicates if we need to fall back to normal
 /// copying or not. False means we don't have to.
@@ -20,21 +36,68 @@ pub(super) fn write_fast_using_splice<R: FdReadable>(
     handle: &mut InputHandle<R>,
     write_fd: &impl AsRawFd,
 ) -> CatResult<bool> {
+    let read_fd = handle.reader.as_raw_fd();
+    let write_fd = write_fd.as_raw_fd();
+    let input = fstat(read_fd).ok();
+    let input_type = input.map(file_type);
+    let output_type = fstat(write_fd).ok().map(file_type);
+
+    // Files like those in /proc claim to be empty, and `copy_file_range()`
+    // copies nothing from them.
+    if input_type == Some(SFlag::S_IFREG)
+        && output_type == Some(SFlag::S_IFREG)
+        && input.map_or(false, |input| input.st_size > 0)
+        && copy_in_kernel(|| copy_file_range(read_fd, None, write_fd, None, COPY_SIZE))
+    {
+        return Ok(false);
+    }
+    if input_type == Some(SFlag::S_IFREG)
+        && copy_in_kernel(|| sendfile(write_fd, read_fd, None, COPY_SIZE))
+    {
+        return Ok(false);
+    }
+    if (input_type == Some(SFlag::S_IFIFO) || output_type == Some(SFlag::S_IFIFO))
+        && copy_in_kernel(|| splice(&read_fd, &write_fd, SPLICE_SIZE))
+    {
+        return Ok(false);
+    }
+    splice_through_pipe(read_fd, write_fd)
+}
+
+fn file_type(stat: FileStat) -> SFlag {
+    SFlag::from_bits_truncate(stat.st_mode) & SFlag::S_IFMT
+}
+
+/// Calls `copy` until it reaches the end of the input, and returns whether it did, i.e. false
+/// if it failed.
+fn copy_in_kernel(mut copy: impl FnMut() -> nix::Result<usize>) -> bool {
+    loop {
+        match copy() {
+            Ok(0) => return true,
+            Ok(_) => {}
+            Err(_) => return false,
+        }
+    }
+}
+
+/// Moves the data from `read_fd` to `write_fd` through an intermediate pipe, for when neither
+/// of them is a pipe.
+fn splice_through_pipe(read_fd: RawFd, write_fd: RawFd) -> CatResult<bool> {
     let (pipe_rd, pipe_wr) = pipe()?;
 
     loop {
-        match splice(&handle.reader, &pipe_wr, SPLICE_SIZE) {
+        match splice(&read_fd, &pipe_wr, SPLICE_SIZE) {
             Ok(n) => {
                 if n == 0 {
                     return Ok(false);
                 }
-                if splice_exact(&pipe_rd, write_fd, n).is_err() {
+                if splice_exact(&pipe_rd, &write_fd, n).is_err() {
                     // If the first splice manages to copy to the intermediate
                     // pipe, but the second splice to stdout fails for some reason
                     // we can recover by copying the data that we have from the
                     // intermediate pipe to stdout using normal read/write. Then
                     // we tell the caller to fall back.
-                    copy_exact(pipe_rd.as_raw_fd(), write_fd.as_raw_fd(), n)?;
+                    copy_exact(pipe_rd.as_raw_fd(), write_fd, n)?;
                     return Ok(true);
                 }
             }
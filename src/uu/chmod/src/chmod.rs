@@ -16,7 +16,7 @@ use uucore::error::{ExitCode, UResult, USimpleError, UUsageError};
 use uucore::fs::display_permissions_unix;
 use uucore::libc::mode_t;
 #[cfg(not(windows))]
-use uucore::mode;
+use uucore::mode::{self, Mode};
 use uucore::{format_usage, show_error};
 
 static ABOUT: &str = "Change the mode of each FILE to MODE.
@@ -97,6 +97,19 @@ pub fn uumain(args: impl uucore::Args) -> UResult<()> {
         return Err(UUsageError::new(1, "missing operand".to_string()));
     }
 
+    let cmode = match cmode {
+        Some(cmode) => match Mode::parse(&cmode) {
+            Ok(mode) => Some(mode),
+            Err(_) => {
+                return Err(UUsageError::new(
+                    1,
+                    format!("invalid mode: {}", cmode.quote()),
+                ))
+            }
+        },
+        None => None,
+    };
+
     let chmoder = Chmoder {
         changes,
         quiet,
@@ -105,6 +118,7 @@ pub fn uumain(args: impl uucore::Args) -> UResult<()> {
         recursive,
         fmode,
         cmode,
+        umask: mode::get_umask(),
     };
 
     chmoder.chmod(&files)
@@ -183,7 +197,8 @@ struct Chmoder {
     preserve_root: bool,
     recursive: bool,
     fmode: Option<u32>,
-    cmode: Option<String>,
+    cmode: Option<Mode>,
+    umask: u32,
 }
 
 impl Chmoder {
@@ -256,8 +271,6 @@ impl Chmoder {
     }
     #[cfg(unix)]
     fn chmod_file(&self, file: &Path) -> UResult<()> {
-        use uucore::mode::get_umask;
-
         let fperm = match fs::metadata(file) {
             Ok(meta) => meta.mode() & 0o7777,
             Err(err) => {
@@ -284,41 +297,12 @@ impl Chmoder {
         match self.fmode {
             Some(mode) => self.change_file(fperm, mode, file)?,
             None => {
-                let cmode_unwrapped = self.cmode.clone().unwrap();
-                let mut new_mode = fperm;
-                let mut naively_expected_new_mode = new_mode;
-                for mode in cmode_unwrapped.split(',') {
-                    // cmode is guaranteed to be Some in this case
-                    let arr: &[char] = &['0', '1', '2', '3', '4', '5', '6', '7', '8', '9'];
-                    let result = if mode.contains(arr) {
-                        mode::parse_numeric(new_mode, mode, file.is_dir()).map(|v| (v, v))
-                    } else {
-                        mode::parse_symbolic(new_mode, mode, get_umask(), file.is_dir()).map(|m| {
-                            // calculate the new mode as if umask was 0
-                            let naive_mode = mode::parse_symbolic(
-                                naively_expected_new_mode,
-                                mode,
-                                0,
-                                file.is_dir(),
-                            )
-                            .unwrap(); // we know that mode must be valid, so this cannot fail
-                            (m, naive_mode)
-                        })
-                    };
-                    match result {
-                        Ok((mode, naive_mode)) => {
-                            new_mode = mode;
-                            naively_expected_new_mode = naive_mode;
-                        }
-                        Err(f) => {
-                            if !self.quiet {
-                                return Err(USimpleError::new(1, f));
-                            } else {
-                                return Err(ExitCode::new(1));
-                            }
-                        }
-                    }
-                }
+                // cmode is guaranteed to be Some in this case
+                let cmode = self.cmode.as_ref().unwrap();
+                let is_dir = file.is_dir();
+                let new_mode = cmode.apply(fperm, is_dir, self.umask);
+                // calculate the new mode as if umask was 0
+                let naively_expected_new_mode = cmode.apply(fperm, is_dir, 0);
                 self.change_file(fperm, new_mode, file)?;
                 // if a permission would have been removed if umask was 0, but it wasn't because umask was not 0, print an error and fail
                 if (new_mode & !naively_expected_new_mode) != 0 {
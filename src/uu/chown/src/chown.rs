@@ -188,6 +188,7 @@ pub fn uu_app() -> Command {
 ///
 /// * `"owner:group"`,
 /// * `"owner"`,
+/// * `"owner:"`, meaning the login group of the owner,
 /// * `":group"`,
 ///
 /// and the owner or group can be specified either as an ID or a
@@ -198,11 +199,19 @@ fn parse_spec(spec: &str, sep: char) -> UResult<(Option<u32>, Option<u32>)> {
     assert!(['.', ':'].contains(&sep));
     let mut args = spec.splitn(2, sep);
     let user = args.next().unwrap_or("");
-    let group = args.next().unwrap_or("");
+    let group = args.next();
+    // "OWNER:" stands for the login group of OWNER
+    let login_group = group == Some("");
+    let group = group.unwrap_or("");
+    let mut login_gid = None;
 
     let uid = if !user.is_empty() {
         Some(match Passwd::locate(user) {
-            Ok(u) => u.uid, // We have been able to get the uid
+            Ok(u) => {
+                // We have been able to get the uid
+                login_gid = Some(u.gid);
+                u.uid
+            }
             Err(_) =>
             // we have NOT been able to find the uid
             // but we could be in the case where we have user.group
@@ -243,6 +252,8 @@ fn parse_spec(spec: &str, sep: char) -> UResult<(Option<u32>, Option<u32>)> {
                 }
             },
         })
+    } else if login_group {
+        login_gid
     } else {
         None
     };
@@ -287,4 +298,10 @@ mod test {
             Ok((Some(12345), Some(54321)))
         ));
     }
+
+    #[test]
+    fn test_parse_spec_login_group() {
+        assert!(matches!(parse_spec("root:", ':'), Ok((Some(0), Some(0)))));
+        assert!(matches!(parse_spec("root", ':'), Ok((Some(0), None))));
+    }
 }
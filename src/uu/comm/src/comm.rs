@@ -9,10 +9,10 @@
 
 use std::cmp::Ordering;
 use std::fs::File;
-use std::io::{self, stdin, BufRead, BufReader, Stdin};
+use std::io::{self, stdin, stdout, BufRead, BufReader, BufWriter, Stdin, Write};
 use std::path::Path;
 use uucore::error::FromIo;
-use uucore::error::UResult;
+use uucore::error::{UResult, USimpleError};
 use uucore::format_usage;
 
 use clap::{crate_version, Arg, ArgAction, ArgMatches, Command};
@@ -30,11 +30,12 @@ mod options {
     pub const FILE_1: &str = "FILE1";
     pub const FILE_2: &str = "FILE2";
     pub const TOTAL: &str = "total";
+    pub const ZERO_TERMINATED: &str = "zero-terminated";
 }
 
-fn mkdelim(col: usize, opts: &ArgMatches) -> String {
+fn mkdelim(col: usize, delim: &str, opts: &ArgMatches) -> String {
     let mut s = String::new();
-    let delim = match opts.get_one::<String>(options::DELIMITER).unwrap().as_str() {
+    let delim = match delim {
         "" => "\0",
         delim => delim,
     };
@@ -49,87 +50,107 @@ fn mkdelim(col: usize, opts: &ArgMatches) -> String {
     s
 }
 
-fn ensure_nl(line: &mut String) {
-    if !line.ends_with('\n') {
-        line.push('\n');
-    }
-}
-
 enum LineReader {
     Stdin(Stdin),
     FileIn(BufReader<File>),
 }
 
 impl LineReader {
-    fn read_line(&mut self, buf: &mut String) -> io::Result<usize> {
-        match *self {
-            Self::Stdin(ref mut r) => r.read_line(buf),
-            Self::FileIn(ref mut r) => r.read_line(buf),
+    /// Reads the next line into `buf`, without its line ending, and returns whether there was one.
+    fn read_line(&mut self, line_ending: u8, buf: &mut Vec<u8>) -> io::Result<bool> {
+        buf.clear();
+        let n = match *self {
+            Self::Stdin(ref mut r) => r.lock().read_until(line_ending, buf)?,
+            Self::FileIn(ref mut r) => r.read_until(line_ending, buf)?,
+        };
+        if buf.last() == Some(&line_ending) {
+            buf.pop();
         }
+        Ok(n > 0)
     }
 }
 
-fn comm(a: &mut LineReader, b: &mut LineReader, opts: &ArgMatches) {
-    let delim: Vec<String> = (0..4).map(|col| mkdelim(col, opts)).collect();
+fn comm(
+    a: &mut LineReader,
+    b: &mut LineReader,
+    names: (&str, &str),
+    delim: &str,
+    opts: &ArgMatches,
+) -> UResult<()> {
+    let delims: Vec<String> = (0..4).map(|col| mkdelim(col, delim, opts)).collect();
+    let line_ending = if opts.get_flag(options::ZERO_TERMINATED) {
+        b'\0'
+    } else {
+        b'\n'
+    };
+    let stdout = stdout();
+    let mut writer = BufWriter::new(stdout.lock());
+    let mut write_line = |col: usize, line: &[u8]| -> io::Result<()> {
+        writer.write_all(delims[col].as_bytes())?;
+        writer.write_all(line)?;
+        writer.write_all(&[line_ending])
+    };
 
-    let ra = &mut String::new();
-    let mut na = a.read_line(ra);
-    let rb = &mut String::new();
-    let mut nb = b.read_line(rb);
+    let mut ra = Vec::new();
+    let mut rb = Vec::new();
+    let mut read_a = |ra: &mut Vec<u8>| {
+        a.read_line(line_ending, ra)
+            .map_err_context(|| names.0.to_string())
+    };
+    let mut read_b = |rb: &mut Vec<u8>| {
+        b.read_line(line_ending, rb)
+            .map_err_context(|| names.1.to_string())
+    };
+    let mut has_a = read_a(&mut ra)?;
+    let mut has_b = read_b(&mut rb)?;
 
     let mut total_col_1 = 0;
     let mut total_col_2 = 0;
     let mut total_col_3 = 0;
 
-    while na.is_ok() || nb.is_ok() {
-        let ord = match (na.is_ok(), nb.is_ok()) {
-            (false, true) => Ordering::Greater,
+    while has_a || has_b {
+        let ord = match (has_a, has_b) {
+            (true, true) => ra.cmp(&rb),
             (true, false) => Ordering::Less,
-            (true, true) => match (&na, &nb) {
-                (&Ok(0), &Ok(0)) => break,
-                (&Ok(0), _) => Ordering::Greater,
-                (_, &Ok(0)) => Ordering::Less,
-                _ => ra.cmp(&rb),
-            },
-            _ => unreachable!(),
+            _ => Ordering::Greater,
         };
 
         match ord {
             Ordering::Less => {
                 if !opts.get_flag(options::COLUMN_1) {
-                    ensure_nl(ra);
-                    print!("{}{}", delim[1], ra);
+                    write_line(1, &ra)?;
                 }
-                ra.clear();
-                na = a.read_line(ra);
+                has_a = read_a(&mut ra)?;
                 total_col_1 += 1;
             }
             Ordering::Greater => {
                 if !opts.get_flag(options::COLUMN_2) {
-                    ensure_nl(rb);
-                    print!("{}{}", delim[2], rb);
+                    write_line(2, &rb)?;
                 }
-                rb.clear();
-                nb = b.read_line(rb);
+                has_b = read_b(&mut rb)?;
                 total_col_2 += 1;
             }
             Ordering::Equal => {
                 if !opts.get_flag(options::COLUMN_3) {
-                    ensure_nl(ra);
-                    print!("{}{}", delim[3], ra);
+                    write_line(3, &ra)?;
                 }
-                ra.clear();
-                rb.clear();
-                na = a.read_line(ra);
-                nb = b.read_line(rb);
+                has_a = read_a(&mut ra)?;
+                has_b = read_b(&mut rb)?;
                 total_col_3 += 1;
             }
         }
     }
 
     if opts.get_flag(options::TOTAL) {
-        println!("{total_col_1}\t{total_col_2}\t{total_col_3}\ttotal");
+        // An empty delimiter separates the columns with NULs, but not the totals.
+        write!(
+            writer,
+            "{total_col_1}{delim}{total_col_2}{delim}{total_col_3}{delim}total"
+        )?;
+        writer.write_all(&[line_ending])?;
     }
+    writer.flush()?;
+    Ok(())
 }
 
 fn open_file(name: &str) -> io::Result<LineReader> {
@@ -152,8 +173,13 @@ pub fn uumain(args: impl uucore::Args) -> UResult<()> {
     let mut f1 = open_file(filename1).map_err_context(|| filename1.to_string())?;
     let mut f2 = open_file(filename2).map_err_context(|| filename2.to_string())?;
 
-    comm(&mut f1, &mut f2, &matches);
-    Ok(())
+    let mut delims = matches.get_many::<String>(options::DELIMITER).unwrap();
+    let delim = delims.next().unwrap();
+    if delims.any(|d| d != delim) {
+        return Err(USimpleError::new(1, "multiple output delimiters specified"));
+    }
+
+    comm(&mut f1, &mut f2, (filename1, filename2), delim, &matches)
 }
 
 pub fn uu_app() -> Command {
@@ -187,7 +213,15 @@ pub fn uu_app() -> Command {
                 .help("separate columns with STR")
                 .value_name("STR")
                 .default_value(options::DELIMITER_DEFAULT)
-                .hide_default_value(true),
+                .hide_default_value(true)
+                .action(ArgAction::Append),
+        )
+        .arg(
+            Arg::new(options::ZERO_TERMINATED)
+                .long(options::ZERO_TERMINATED)
+                .short('z')
+                .help("line delimiter is NUL, not newline")
+                .action(ArgAction::SetTrue),
         )
         .arg(
             Arg::new(options::FILE_1)
@@ -13,6 +13,7 @@ use std::env;
 use std::fs;
 use std::io;
 use std::path::{Path, PathBuf, StripPrefixError};
+use std::sync::Mutex;
 
 use indicatif::ProgressBar;
 use uucore::display::Quotable;
@@ -25,7 +26,7 @@ use walkdir::{DirEntry, WalkDir};
 
 use crate::{
     aligned_ancestors, context_for, copy_attributes, copy_file, copy_link, preserve_hardlinks,
-    CopyResult, Error, Options, TargetSlice,
+    show_error_if_needed, CopyResult, Error, Options, TargetSlice,
 };
 
 /// Ensure a Windows path starts with a `\\?`.
@@ -383,32 +384,90 @@ pub(crate) fn copy_directory(
         Err(e) => return Err(format!("failed to get current directory {e}").into()),
     };
 
-    // Traverse the contents of the directory, copying each one.
-    for direntry_result in WalkDir::new(root)
-        .same_file_system(options.one_file_system)
-        .follow_links(options.dereference)
-    {
-        match direntry_result {
-            Ok(direntry) => {
-                let entry = Entry::new(&context, &direntry)?;
-                copy_direntry(
-                    progress_bar,
-                    entry,
-                    options,
-                    symlinked_files,
-                    preserve_hard_links,
-                    &mut hard_links,
-                )?;
+    // The first error of the files copied concurrently, which is returned once all of them
+    // have been copied.
+    let job_error = Mutex::new(None);
+
+    // Traverse the contents of the directory, copying each one. Directories are created (and
+    // symbolic links copied) in order during the traversal, while regular files may be handed
+    // off to the thread pool of `scope`.
+    let mut traverse = with_scope(|scope| {
+        for direntry_result in WalkDir::new(root)
+            .same_file_system(options.one_file_system)
+            .follow_links(options.dereference)
+        {
+            match direntry_result {
+                Ok(direntry) => {
+                    let entry = Entry::new(&context, &direntry)?;
+                    match scope {
+                        Some(scope) if direntry.file_type().is_file() => {
+                            let job_error = &job_error;
+                            scope.spawn(move |_| {
+                                // Only symbolic links are added to `symlinked_files`, and
+                                // those are copied during the traversal.
+                                let result = copy_direntry(
+                                    progress_bar,
+                                    entry,
+                                    options,
+                                    &mut HashSet::new(),
+                                    false,
+                                    &mut vec![],
+                                );
+                                if let Err(err) = result {
+                                    let mut job_error = job_error.lock().unwrap();
+                                    if job_error.is_none() {
+                                        *job_error = Some(err);
+                                    } else {
+                                        show_error_if_needed(&err);
+                                    }
+                                }
+                            });
+                        }
+                        _ => copy_direntry(
+                            progress_bar,
+                            entry,
+                            options,
+                            symlinked_files,
+                            preserve_hard_links,
+                            &mut hard_links,
+                        )?,
+                    }
+                }
+                // Print an error message, but continue traversing the directory.
+                Err(e) => show_error!("{}", e),
             }
-            // Print an error message, but continue traversing the directory.
-            Err(e) => show_error!("{}", e),
         }
+        Ok(())
+    });
+
+    if options.copy_files_concurrently() {
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(options.jobs)
+            .build()
+            .map_err(|e| format!("failed to start copying threads: {e}"))?;
+        pool.scope(|scope| traverse(Some(scope)))?;
+        if let Some(err) = job_error.into_inner().unwrap() {
+            return Err(err);
+        }
+    } else {
+        traverse(None)?;
     }
+
     // Copy the attributes from the root directory to the target directory.
     copy_attributes(root, target, &options.attributes)?;
     Ok(())
 }
 
+/// Returns `f`, which is given the scope of the thread pool copying files concurrently, if any.
+///
+/// This only helps the compiler to infer that the scope does not outlive the traversal.
+fn with_scope<'scope, F>(f: F) -> F
+where
+    F: FnMut(Option<&rayon::Scope<'scope>>) -> CopyResult<()>,
+{
+    f
+}
+
 /// Decide whether the second path is a prefix of the first.
 ///
 /// This function canonicalizes the paths via
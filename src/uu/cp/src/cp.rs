@@ -28,7 +28,7 @@ use std::string::ToString;
 
 use clap::{crate_version, Arg, ArgAction, ArgMatches, Command};
 use filetime::FileTime;
-use indicatif::{ProgressBar, ProgressStyle};
+use indicatif::ProgressBar;
 #[cfg(unix)]
 use libc::mkfifo;
 use quick_error::ResultExt;
@@ -40,6 +40,7 @@ use uucore::error::{set_exit_code, UClapError, UError, UResult, UUsageError};
 use uucore::fs::{
     canonicalize, paths_refer_to_same_file, FileInformation, MissingHandling, ResolveMode,
 };
+use uucore::progress;
 use uucore::{crash, format_usage, prompt_yes, show_error, show_warning};
 
 use crate::copydir::copy_directory;
@@ -226,6 +227,9 @@ pub struct Options {
     update: bool,
     verbose: bool,
     progress_bar: bool,
+    /// The number of regular files copied at once when copying a directory, or 0 to use one
+    /// thread per CPU.
+    jobs: usize,
 }
 
 static ABOUT: &str = "Copy SOURCE to DEST, or multiple SOURCE(s) to DIRECTORY.";
@@ -246,6 +250,7 @@ mod options {
     pub const DEREFERENCE: &str = "dereference";
     pub const FORCE: &str = "force";
     pub const INTERACTIVE: &str = "interactive";
+    pub const JOBS: &str = "jobs";
     pub const LINK: &str = "link";
     pub const NO_CLOBBER: &str = "no-clobber";
     pub const NO_DEREFERENCE: &str = "no-dereference";
@@ -523,7 +528,10 @@ pub fn uu_app() -> Command {
                 .long(options::SPARSE)
                 .value_name("WHEN")
                 .value_parser(["never", "auto", "always"])
-                .help("NotImplemented: control creation of sparse files. See below"),
+                .help(
+                    "control creation of sparse files: 'auto' keeps the holes of sparse \
+                     files, 'always' also turns blocks of zeros into holes",
+                ),
         )
         // TODO: implement the following args
         .arg(
@@ -555,6 +563,19 @@ pub fn uu_app() -> Command {
                 Note: this feature is not supported by GNU coreutils.",
                 ),
         )
+        .arg(
+            Arg::new(options::JOBS)
+                .long(options::JOBS)
+                .short('j')
+                .value_name("N")
+                .value_parser(clap::value_parser!(usize))
+                .default_value("1")
+                .help(
+                    "Copy up to N regular files at once when copying directories recursively, \
+                     or as many as there are CPUs if N is 0. \n\
+                Note: this feature is not supported by GNU coreutils.",
+                ),
+        )
         .arg(
             Arg::new(options::PATHS)
                 .action(ArgAction::Append)
@@ -881,6 +902,7 @@ impl Options {
             recursive,
             target_dir,
             progress_bar: matches.get_flag(options::PROGRESS_BAR),
+            jobs: *matches.get_one::<usize>(options::JOBS).unwrap(),
         };
 
         Ok(options)
@@ -890,6 +912,17 @@ impl Options {
         self.dereference || (in_command_line && self.cli_dereference)
     }
 
+    /// Whether the regular files in a directory can be copied concurrently.
+    ///
+    /// This is not the case if copying a file can depend on those copied before, i.e. if hard
+    /// links are preserved, or if the user may be asked for confirmation.
+    fn copy_files_concurrently(&self) -> bool {
+        self.jobs != 1
+            && matches!(self.copy_mode, CopyMode::Copy | CopyMode::Update)
+            && !matches!(self.overwrite, OverwriteMode::Interactive(_))
+            && !self.preserve_hard_links()
+    }
+
     fn preserve_hard_links(&self) -> bool {
         match self.attributes.links {
             Preserve::No => false,
@@ -1050,14 +1083,7 @@ fn copy(sources: &[Source], target: &TargetSlice, options: &Options) -> CopyResu
     let mut symlinked_files = HashSet::new();
 
     let progress_bar = if options.progress_bar {
-        let pb = ProgressBar::new(disk_usage(sources, options.recursive)?)
-            .with_style(
-                ProgressStyle::with_template(
-                    "{msg}: [{elapsed_precise}] {wide_bar} {bytes:>7}/{total_bytes:7}",
-                )
-                .unwrap(),
-            )
-            .with_message(uucore::util_name());
+        let pb = progress::bytes_progress_bar(disk_usage(sources, options.recursive)?);
         pb.tick();
         Some(pb)
     } else {
@@ -1172,9 +1198,15 @@ impl OverwriteMode {
     }
 }
 
-/// Handles errors for attributes preservation. If the attribute is not required, and
-/// errored, tries to show error (see `show_error_if_needed` for additional behavior details).
-/// If it's required, then the error is thrown.
+/// Preserves an attribute with `f` if requested. A failure is returned if the attribute is
+/// required, and silently ignored otherwise.
+///
+/// The documentation for GNU cp states for the attributes that are preserved without being
+/// required (by `-a`):
+///
+/// > Try to preserve SELinux security context and
+/// > extended attributes (xattr), but ignore any failure
+/// > to do that and print no corresponding diagnostic.
 fn handle_preserve<F: Fn() -> CopyResult<()>>(p: &Preserve, f: F) -> CopyResult<()> {
     match p {
         Preserve::No => {}
@@ -1182,8 +1214,6 @@ fn handle_preserve<F: Fn() -> CopyResult<()>>(p: &Preserve, f: F) -> CopyResult<
             let result = f();
             if *required {
                 result?;
-            } else if let Err(error) = result {
-                show_error_if_needed(&error);
             }
         }
     };
@@ -1280,27 +1310,11 @@ pub(crate) fn copy_attributes(
 
     handle_preserve(&attributes.xattr, || -> CopyResult<()> {
         #[cfg(unix)]
-        {
-            let xattrs = xattr::list(source)?;
-            for attr in xattrs {
-                if let Some(attr_value) = xattr::get(source, attr.clone())? {
-                    xattr::set(dest, attr, &attr_value[..])?;
-                }
-            }
-        }
-        #[cfg(not(unix))]
-        {
-            // The documentation for GNU cp states:
-            //
-            // > Try to preserve SELinux security context and
-            // > extended attributes (xattr), but ignore any failure
-            // > to do that and print no corresponding diagnostic.
-            //
-            // so we simply do nothing here.
-            //
-            // TODO Silently ignore failures in the `#[cfg(unix)]`
-            // block instead of terminating immediately on errors.
-        }
+        copy_xattrs(
+            source,
+            dest,
+            attributes.xattr == Preserve::Yes { required: true },
+        )?;
 
         Ok(())
     })?;
@@ -1308,6 +1322,37 @@ pub(crate) fn copy_attributes(
     Ok(())
 }
 
+/// Copies the extended attributes of `source` to `dest`, including its POSIX ACLs, which are
+/// stored as the `system.posix_acl_*` attributes.
+///
+/// Failing to copy one attribute doesn't prevent copying the others. If `report_failures` is set,
+/// each failure is reported and makes cp exit with an error once it is done.
+#[cfg(unix)]
+fn copy_xattrs(source: &Path, dest: &Path, report_failures: bool) -> CopyResult<()> {
+    let xattrs = xattr::list(source).context(&*format!(
+        "failed to list the extended attributes of {}",
+        source.quote()
+    ))?;
+    for attr in xattrs {
+        let result = xattr::get(source, &attr).and_then(|value| match value {
+            Some(value) => xattr::set(dest, &attr, &value),
+            None => Ok(()),
+        });
+        if let Err(err) = result {
+            if report_failures {
+                show_error!(
+                    "failed to preserve extended attribute {} for {}: {}",
+                    attr.quote(),
+                    dest.quote(),
+                    uucore::error::strip_errno(&err)
+                );
+                set_exit_code(EXIT_ERR);
+            }
+        }
+    }
+    Ok(())
+}
+
 fn symlink_file(
     source: &Path,
     dest: &Path,
@@ -1543,6 +1588,14 @@ fn copy_file(
         }
     }
 
+    let source_len = if let Some(progress_bar) = progress_bar {
+        let len = fs::metadata(source)?.len();
+        progress::set_current_file(progress_bar, source, len);
+        len
+    } else {
+        0
+    };
+
     // Calculate the context upfront before canonicalizing the path
     let context = context_for(source, dest);
     let context = context.as_str();
@@ -1680,7 +1733,7 @@ fn copy_file(
     copy_attributes(source, dest, &options.attributes)?;
 
     if let Some(progress_bar) = progress_bar {
-        progress_bar.inc(fs::metadata(source)?.len());
+        progress_bar.inc(source_len);
     }
 
     Ok(())
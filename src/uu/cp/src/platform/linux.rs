@@ -2,15 +2,16 @@
 //  *
 //  * For the full copyright and license information, please view the LICENSE
 //  * file that was distributed with this source code.
-// spell-checker:ignore ficlone reflink ftruncate pwrite fiemap
+// spell-checker:ignore ficlone reflink ftruncate pwrite fiemap ENXIO
 use std::fs::{File, OpenOptions};
-use std::io::Read;
 use std::os::unix::fs::OpenOptionsExt;
 use std::os::unix::io::AsRawFd;
 use std::path::Path;
 
 use quick_error::ResultExt;
 
+use uucore::display::Quotable;
+use uucore::error::strip_errno;
 use uucore::mode::get_umask;
 
 use crate::{CopyResult, ReflinkMode, SparseMode};
@@ -30,8 +31,12 @@ enum CloneFallback {
     /// Raise an error.
     Error,
 
-    /// Use [`std::fs::copy`].
+    /// Use [`std::fs::copy`], which uses `copy_file_range(2)` if the kernel supports it, so
+    /// that the copy can still be done by the file system.
     FSCopy,
+
+    /// Use [`sparse_copy`], keeping the holes of the source.
+    SparseCopy,
 }
 
 /// Use the Linux `ioctl_ficlone` API to do a copy-on-write clone.
@@ -53,46 +58,90 @@ where
     match fallback {
         CloneFallback::Error => Err(std::io::Error::last_os_error()),
         CloneFallback::FSCopy => std::fs::copy(source, dest).map(|_| ()),
+        CloneFallback::SparseCopy => sparse_copy(source, dest, false),
+    }
+}
+
+/// Returns whether the file at `path` has holes, i.e. uses fewer blocks than its size requires.
+#[cfg(any(target_os = "linux", target_os = "android"))]
+fn is_sparse(path: &Path) -> bool {
+    use std::os::unix::prelude::MetadataExt;
+
+    // `st_blocks` is counted in 512-byte units.
+    std::fs::metadata(path).map_or(false, |md| md.blocks() * 512 < md.size())
+}
+
+/// Returns the offset of the first byte of data at or after `offset` (`whence` is `SEEK_DATA`),
+/// or of the first hole (`whence` is `SEEK_HOLE`), or `None` if there is no data after `offset`.
+#[cfg(any(target_os = "linux", target_os = "android"))]
+fn seek(file: &File, offset: u64, whence: libc::c_int) -> std::io::Result<Option<u64>> {
+    let result = unsafe { libc::lseek(file.as_raw_fd(), offset as libc::off_t, whence) };
+    if result >= 0 {
+        Ok(Some(result as u64))
+    } else {
+        let err = std::io::Error::last_os_error();
+        if err.raw_os_error() == Some(libc::ENXIO) {
+            Ok(None)
+        } else {
+            Err(err)
+        }
     }
 }
 
 /// Perform a sparse copy from one file to another.
+///
+/// Only the data of the source is copied (found with `SEEK_DATA` and `SEEK_HOLE`, if the file
+/// system supports them), so that its holes become holes of the destination. If
+/// `punch_zeros` is set, blocks of zeros in the data become holes as well.
 #[cfg(any(target_os = "linux", target_os = "android"))]
-fn sparse_copy<P>(source: P, dest: P) -> std::io::Result<()>
+fn sparse_copy<P>(source: P, dest: P, punch_zeros: bool) -> std::io::Result<()>
 where
     P: AsRef<Path>,
 {
+    use std::os::unix::fs::FileExt;
     use std::os::unix::prelude::MetadataExt;
 
-    let mut src_file = File::open(source)?;
+    let src_file = File::open(source)?;
     let dst_file = File::create(dest)?;
-    let dst_fd = dst_file.as_raw_fd();
 
-    let size: usize = src_file.metadata()?.size().try_into().unwrap();
-    if unsafe { libc::ftruncate(dst_fd, size.try_into().unwrap()) } < 0 {
-        return Err(std::io::Error::last_os_error());
-    }
+    let size = src_file.metadata()?.size();
+    dst_file.set_len(size)?;
 
     let blksize = dst_file.metadata()?.blksize();
     let mut buf: Vec<u8> = vec![0; blksize.try_into().unwrap()];
-    let mut current_offset: usize = 0;
-
-    // TODO Perhaps we can employ the "fiemap ioctl" API to get the
-    // file extent mappings:
-    // https://www.kernel.org/doc/html/latest/filesystems/fiemap.html
-    while current_offset < size {
-        let this_read = src_file.read(&mut buf)?;
-        if buf.iter().any(|&x| x != 0) {
-            unsafe {
-                libc::pwrite(
-                    dst_fd,
-                    buf.as_ptr() as *const libc::c_void,
-                    this_read,
-                    current_offset.try_into().unwrap(),
-                )
-            };
+
+    let mut offset = 0;
+    while offset < size {
+        // File systems without SEEK_DATA support are treated as if they had no holes.
+        let data_start = match seek(&src_file, offset, libc::SEEK_DATA) {
+            Ok(Some(data_start)) => data_start,
+            Ok(None) => break,
+            Err(err) if err.raw_os_error() == Some(libc::EINVAL) => offset,
+            Err(err) => return Err(err),
+        };
+        let data_end = match seek(&src_file, data_start, libc::SEEK_HOLE) {
+            Ok(hole_start) => hole_start.unwrap_or(size),
+            Err(err) if err.raw_os_error() == Some(libc::EINVAL) => size,
+            Err(err) => return Err(err),
+        }
+        .min(size);
+
+        offset = data_start;
+        while offset < data_end {
+            let len = buf
+                .len()
+                .min((data_end - offset).try_into().unwrap_or(usize::MAX));
+            let this_read = src_file.read_at(&mut buf[..len], offset)?;
+            if this_read == 0 {
+                // The file was truncated while being copied.
+                return Ok(());
+            }
+            let chunk = &buf[..this_read];
+            if !punch_zeros || chunk.iter().any(|&x| x != 0) {
+                dst_file.write_all_at(chunk, offset)?;
+            }
+            offset += this_read as u64;
         }
-        current_offset += this_read;
     }
     Ok(())
 }
@@ -146,19 +195,36 @@ pub(crate) fn copy_on_write(
     context: &str,
     source_is_fifo: bool,
 ) -> CopyResult<()> {
+    // Like GNU cp, `--sparse=auto` only recreates the holes of sources that have some.
+    let keep_holes = sparse_mode == SparseMode::Auto && !source_is_fifo && is_sparse(source);
     let result = match (reflink_mode, sparse_mode) {
-        (ReflinkMode::Never, SparseMode::Always) => sparse_copy(source, dest),
+        (ReflinkMode::Never, SparseMode::Always) => sparse_copy(source, dest, true),
+        (ReflinkMode::Never, _) if keep_holes => sparse_copy(source, dest, false),
         (ReflinkMode::Never, _) => std::fs::copy(source, dest).map(|_| ()),
-        (ReflinkMode::Auto, SparseMode::Always) => sparse_copy(source, dest),
+        (ReflinkMode::Auto, SparseMode::Always) => sparse_copy(source, dest, true),
 
         (ReflinkMode::Auto, _) => {
             if source_is_fifo {
                 copy_fifo_contents(source, dest).map(|_| ())
+            } else if keep_holes {
+                clone(source, dest, CloneFallback::SparseCopy)
             } else {
                 clone(source, dest, CloneFallback::FSCopy)
             }
         }
-        (ReflinkMode::Always, SparseMode::Auto) => clone(source, dest, CloneFallback::Error),
+        (ReflinkMode::Always, SparseMode::Auto) => {
+            // Like GNU cp, report why cloning failed, e.g. because the files are on different
+            // file systems, or the file system does not support it.
+            return clone(source, dest, CloneFallback::Error).map_err(|err| {
+                format!(
+                    "failed to clone {} from {}: {}",
+                    dest.quote(),
+                    source.quote(),
+                    strip_errno(&err)
+                )
+                .into()
+            });
+        }
         (ReflinkMode::Always, _) => {
             return Err("`--reflink=always` can be used only with --sparse=auto".into())
         }
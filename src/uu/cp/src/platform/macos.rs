@@ -10,6 +10,8 @@ use std::os::unix::ffi::OsStrExt;
 use std::path::Path;
 
 use quick_error::ResultExt;
+use uucore::display::Quotable;
+use uucore::error::strip_errno;
 
 use crate::{CopyResult, ReflinkMode, SparseMode};
 
@@ -69,7 +71,18 @@ pub(crate) fn copy_on_write(
         // support COW).
         match reflink_mode {
             ReflinkMode::Always => {
-                return Err(format!("failed to clone {source:?} from {dest:?}: {error}").into())
+                let err = if raw_pfn.is_null() {
+                    io::Error::from_raw_os_error(libc::ENOTSUP)
+                } else {
+                    io::Error::last_os_error()
+                };
+                return Err(format!(
+                    "failed to clone {} from {}: {}",
+                    dest.quote(),
+                    source.quote(),
+                    strip_errno(&err)
+                )
+                .into());
             }
             _ => {
                 if source_is_fifo {
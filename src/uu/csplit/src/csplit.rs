@@ -2,7 +2,7 @@
 // spell-checker:ignore rustdoc
 #![allow(rustdoc::private_intra_doc_links)]
 
-use std::cmp::Ordering;
+use std::collections::VecDeque;
 use std::io::{self, BufReader};
 use std::{
     fs::{remove_file, File},
@@ -10,7 +10,6 @@ use std::{
 };
 
 use clap::{crate_version, Arg, ArgAction, ArgMatches, Command};
-use regex::Regex;
 use uucore::display::Quotable;
 use uucore::error::{FromIo, UResult};
 use uucore::{crash_if_err, format_usage};
@@ -83,7 +82,8 @@ impl CsplitOptions {
 ///
 /// - [`io::Error`] if there is some problem reading/writing from/to a file.
 /// - [`CsplitError::LineOutOfRange`] if the line number pattern is larger than the number of input
-///   lines.
+///   lines, or if the offset of a regular expression goes beyond the input or before the lines
+///   already split.
 /// - [`CsplitError::LineOutOfRangeOnRepetition`], like previous but after applying the pattern
 ///   more than once.
 /// - [`CsplitError::MatchNotFound`] if no line matched a regular expression.
@@ -97,95 +97,150 @@ pub fn csplit<T>(
 where
     T: BufRead,
 {
-    let mut input_iter = InputSplitter::new(input.lines().enumerate());
+    let mut input = InputBuffer::new(input);
     let mut split_writer = SplitWriter::new(options);
-    let ret = do_csplit(&mut split_writer, patterns, &mut input_iter);
-
-    // consume the rest
-    input_iter.rewind_buffer();
-    if let Some((_, line)) = input_iter.next() {
-        split_writer.new_writer()?;
-        split_writer.writeln(&line?)?;
-        for (_, line) in input_iter {
-            split_writer.writeln(&line?)?;
+    let ret = do_csplit(&mut split_writer, patterns, &mut input);
+    if ret.is_err() {
+        // the split being written is kept as is
+        split_writer.finish_split()?;
+        // delete files on error by default
+        if !options.keep_files {
+            split_writer.delete_all_splits()?;
         }
-        split_writer.finish_split();
-    }
-    // delete files on error by default
-    if ret.is_err() && !options.keep_files {
-        split_writer.delete_all_splits()?;
     }
     ret
 }
 
-fn do_csplit<I>(
+fn do_csplit<T>(
     split_writer: &mut SplitWriter,
     patterns: Vec<patterns::Pattern>,
-    input_iter: &mut InputSplitter<I>,
+    input: &mut InputBuffer<T>,
 ) -> Result<(), CsplitError>
 where
-    I: Iterator<Item = (usize, io::Result<String>)>,
+    T: BufRead,
 {
+    let suppress_matched = split_writer.options.suppress_matched;
+
     // split the file based on patterns
     for pattern in patterns.into_iter() {
         let pattern_as_str = pattern.to_string();
         let is_skip = matches!(pattern, patterns::Pattern::SkipToMatch(_, _, _));
         match pattern {
             patterns::Pattern::UpToLine(n, ex) => {
-                let mut up_to_line = n;
                 for (_, ith) in ex.iter() {
-                    split_writer.new_writer()?;
-                    match split_writer.do_to_line(&pattern_as_str, up_to_line, input_iter) {
-                        // the error happened when applying the pattern more than once
-                        Err(CsplitError::LineOutOfRange(_)) if ith != 1 => {
-                            return Err(CsplitError::LineOutOfRangeOnRepetition(
+                    let line_error = || {
+                        if ith == 1 {
+                            CsplitError::LineOutOfRange(pattern_as_str.to_string())
+                        } else {
+                            CsplitError::LineOutOfRangeOnRepetition(
                                 pattern_as_str.to_string(),
                                 ith - 1,
-                            ));
+                            )
                         }
-                        Err(err) => return Err(err),
-                        // continue the splitting process
-                        Ok(()) => (),
+                    };
+                    split_writer.new_writer()?;
+                    if suppress_matched && input.no_more_lines()? {
+                        return Err(line_error());
+                    }
+                    // If the line number is smaller than the current position in the input, then
+                    // an empty split is created.
+                    let last_line = n * ith;
+                    while input.first_line() < last_line {
+                        match input.remove_line()? {
+                            Some(line) => split_writer.write_line(&line)?,
+                            None => return Err(line_error()),
+                        }
+                    }
+                    split_writer.finish_split()?;
+                    if suppress_matched {
+                        input.remove_line()?;
+                    } else if input.no_more_lines()? {
+                        return Err(line_error());
                     }
-                    up_to_line += n;
                 }
             }
             patterns::Pattern::UpToMatch(regex, offset, ex)
             | patterns::Pattern::SkipToMatch(regex, offset, ex) => {
                 for (max, ith) in ex.iter() {
-                    if is_skip {
-                        // when skipping a part of the input, no writer is created
-                        split_writer.as_dev_null();
-                    } else {
+                    // when skipping a part of the input, no split is created
+                    if !is_skip {
                         split_writer.new_writer()?;
                     }
-                    match (
-                        split_writer.do_to_match(&pattern_as_str, &regex, offset, input_iter),
-                        max,
-                    ) {
-                        // in case of ::pattern::ExecutePattern::Always, then it's fine not to find a
-                        // matching line
-                        (Err(CsplitError::MatchNotFound(_)), None) => {
-                            return Ok(());
+                    loop {
+                        input.current_line += 1;
+                        match input.find_line(input.current_line)? {
+                            Some(line) if regex.is_match(strip_newline(line)) => break,
+                            Some(_) => (),
+                            None => {
+                                // the input up to the end belongs to the current split
+                                if !is_skip {
+                                    while let Some(line) = input.remove_line()? {
+                                        split_writer.write_line(&line)?;
+                                    }
+                                    split_writer.finish_split()?;
+                                }
+                                return match max {
+                                    // with {*}, the pattern is simply applied until the end of the
+                                    // input
+                                    None => Ok(()),
+                                    Some(_) if ith != 1 => {
+                                        Err(CsplitError::MatchNotFoundOnRepetition(
+                                            pattern_as_str.to_string(),
+                                            ith - 1,
+                                        ))
+                                    }
+                                    Some(_) => {
+                                        Err(CsplitError::MatchNotFound(pattern_as_str.to_string()))
+                                    }
+                                };
+                            }
                         }
-                        // the error happened when applying the pattern more than once
-                        (Err(CsplitError::MatchNotFound(_)), Some(m)) if m != 1 && ith != 1 => {
-                            return Err(CsplitError::MatchNotFoundOnRepetition(
-                                pattern_as_str.to_string(),
-                                ith - 1,
-                            ));
+                    }
+
+                    // The split ends before the line at the offset from the matched line, which
+                    // may be a line before the matched one but not one already split.
+                    let break_line = input.current_line as i64 + i64::from(offset);
+                    if (input.first_line() as i64) > break_line {
+                        return Err(CsplitError::LineOutOfRange(pattern_as_str.to_string()));
+                    }
+                    while (input.first_line() as i64) < break_line {
+                        match input.remove_line()? {
+                            Some(line) if !is_skip => split_writer.write_line(&line)?,
+                            Some(_) => (),
+                            None => {
+                                return Err(CsplitError::LineOutOfRange(pattern_as_str.to_string()))
+                            }
                         }
-                        (Err(err), _) => return Err(err),
-                        // continue the splitting process
-                        (Ok(()), _) => (),
-                    };
+                    }
+                    if !is_skip {
+                        split_writer.finish_split()?;
+                    }
+                    // the lines up to the split are not searched again
+                    if offset > 0 {
+                        input.current_line = break_line as usize;
+                    }
+                    if suppress_matched {
+                        input.remove_line()?;
+                    }
                 }
             }
         };
     }
+
+    // the rest of the input goes into a last split, even if it is empty
+    split_writer.new_writer()?;
+    while let Some(line) = input.remove_line()? {
+        split_writer.write_line(&line)?;
+    }
+    split_writer.finish_split()?;
     Ok(())
 }
 
+/// Returns `line` without its trailing newline character, if any.
+fn strip_newline(line: &[u8]) -> &[u8] {
+    line.strip_suffix(b"\n").unwrap_or(line)
+}
+
 /// Write a portion of the input file into a split which filename is based on an incrementing
 /// counter.
 struct SplitWriter<'a> {
@@ -197,17 +252,6 @@ struct SplitWriter<'a> {
     current_writer: Option<BufWriter<File>>,
     /// the size in bytes of the current split
     size: usize,
-    /// flag to indicate that no content should be written to a split
-    dev_null: bool,
-}
-
-impl<'a> Drop for SplitWriter<'a> {
-    fn drop(&mut self) {
-        if self.options.elide_empty_files && self.size == 0 {
-            let file_name = self.options.split_name.get(self.counter);
-            remove_file(file_name).expect("Failed to elide split");
-        }
-    }
 }
 
 impl<'a> SplitWriter<'a> {
@@ -217,7 +261,6 @@ impl<'a> SplitWriter<'a> {
             counter: 0,
             current_writer: None,
             size: 0,
-            dev_null: false,
         }
     }
 
@@ -232,32 +275,21 @@ impl<'a> SplitWriter<'a> {
         self.current_writer = Some(BufWriter::new(file));
         self.counter += 1;
         self.size = 0;
-        self.dev_null = false;
         Ok(())
     }
 
-    /// The current split will not keep any of the read input lines.
-    fn as_dev_null(&mut self) {
-        self.dev_null = true;
-    }
-
-    /// Writes the line to the current split, appending a newline character.
-    /// If [`self.dev_null`] is true, then the line is discarded.
+    /// Writes the line, with its newline character if it has one, to the current split.
     ///
     /// # Errors
     ///
     /// Some [`io::Error`] may occur when attempting to write the line.
-    fn writeln(&mut self, line: &str) -> io::Result<()> {
-        if !self.dev_null {
-            match self.current_writer {
-                Some(ref mut current_writer) => {
-                    let bytes = line.as_bytes();
-                    current_writer.write_all(bytes)?;
-                    current_writer.write_all(b"\n")?;
-                    self.size += bytes.len() + 1;
-                }
-                None => panic!("trying to write to a split that was not created"),
+    fn write_line(&mut self, line: &[u8]) -> io::Result<()> {
+        match self.current_writer {
+            Some(ref mut current_writer) => {
+                current_writer.write_all(line)?;
+                self.size += line.len();
             }
+            None => panic!("trying to write to a split that was not created"),
         }
         Ok(())
     }
@@ -268,15 +300,19 @@ impl<'a> SplitWriter<'a> {
     ///
     /// # Errors
     ///
-    /// Some [`io::Error`] if the split could not be removed in case it should be elided.
-    fn finish_split(&mut self) {
-        if !self.dev_null {
+    /// Some [`io::Error`] if the split could not be written or removed in case it should be
+    /// elided.
+    fn finish_split(&mut self) -> io::Result<()> {
+        if let Some(mut current_writer) = self.current_writer.take() {
+            current_writer.flush()?;
             if self.options.elide_empty_files && self.size == 0 {
                 self.counter -= 1;
+                remove_file(self.options.split_name.get(self.counter))?;
             } else if !self.options.quiet {
                 println!("{}", self.size);
             }
         }
+        Ok(())
     }
 
     /// Removes all the split files that were created.
@@ -284,7 +320,9 @@ impl<'a> SplitWriter<'a> {
     /// # Errors
     ///
     /// Returns an [`io::Error`] if there was a problem removing a split.
-    fn delete_all_splits(&self) -> io::Result<()> {
+    fn delete_all_splits(&mut self) -> io::Result<()> {
+        // the current split is closed before being removed
+        self.current_writer = None;
         let mut ret = Ok(());
         for ith in 0..self.counter {
             let file_name = self.options.split_name.get(ith);
@@ -294,260 +332,89 @@ impl<'a> SplitWriter<'a> {
         }
         ret
     }
-
-    /// Split the input stream up to the line number `n`.
-    ///
-    /// If the line number `n` is smaller than the current position in the input, then an empty
-    /// split is created.
-    ///
-    /// # Errors
-    ///
-    /// In addition to errors reading/writing from/to a file, if the line number
-    /// `n` is greater than the total available lines, then a
-    /// [`CsplitError::LineOutOfRange`] error is returned.
-    fn do_to_line<I>(
-        &mut self,
-        pattern_as_str: &str,
-        n: usize,
-        input_iter: &mut InputSplitter<I>,
-    ) -> Result<(), CsplitError>
-    where
-        I: Iterator<Item = (usize, io::Result<String>)>,
-    {
-        input_iter.rewind_buffer();
-        input_iter.set_size_of_buffer(1);
-
-        let mut ret = Err(CsplitError::LineOutOfRange(pattern_as_str.to_string()));
-        while let Some((ln, line)) = input_iter.next() {
-            let l = line?;
-            match n.cmp(&(&ln + 1)) {
-                Ordering::Less => {
-                    assert!(
-                        input_iter.add_line_to_buffer(ln, l).is_none(),
-                        "the buffer is big enough to contain 1 line"
-                    );
-                    ret = Ok(());
-                    break;
-                }
-                Ordering::Equal => {
-                    assert!(
-                        self.options.suppress_matched
-                            || input_iter.add_line_to_buffer(ln, l).is_none(),
-                        "the buffer is big enough to contain 1 line"
-                    );
-                    ret = Ok(());
-                    break;
-                }
-                Ordering::Greater => (),
-            }
-            self.writeln(&l)?;
-        }
-        self.finish_split();
-        ret
-    }
-
-    /// Read lines up to the line matching a [`Regex`]. With a non-zero offset,
-    /// the block of relevant lines can be extended (if positive), or reduced
-    /// (if negative).
-    ///
-    /// # Errors
-    ///
-    /// In addition to errors reading/writing from/to a file, the following errors may be returned:
-    /// - if no line matched, an [`CsplitError::MatchNotFound`].
-    /// - if there are not enough lines to accommodate the offset, an
-    /// [`CsplitError::LineOutOfRange`].
-    fn do_to_match<I>(
-        &mut self,
-        pattern_as_str: &str,
-        regex: &Regex,
-        mut offset: i32,
-        input_iter: &mut InputSplitter<I>,
-    ) -> Result<(), CsplitError>
-    where
-        I: Iterator<Item = (usize, io::Result<String>)>,
-    {
-        if offset >= 0 {
-            // The offset is zero or positive, no need for a buffer on the lines read.
-            // NOTE: drain the buffer of input_iter, no match should be done within.
-            for line in input_iter.drain_buffer() {
-                self.writeln(&line)?;
-            }
-            // retain the matching line
-            input_iter.set_size_of_buffer(1);
-
-            while let Some((ln, line)) = input_iter.next() {
-                let l = line?;
-                if regex.is_match(&l) {
-                    match (self.options.suppress_matched, offset) {
-                        // no offset, add the line to the next split
-                        (false, 0) => {
-                            assert!(
-                                input_iter.add_line_to_buffer(ln, l).is_none(),
-                                "the buffer is big enough to contain 1 line"
-                            );
-                        }
-                        // a positive offset, some more lines need to be added to the current split
-                        (false, _) => self.writeln(&l)?,
-                        _ => (),
-                    };
-                    offset -= 1;
-
-                    // write the extra lines required by the offset
-                    while offset > 0 {
-                        match input_iter.next() {
-                            Some((_, line)) => {
-                                self.writeln(&line?)?;
-                            }
-                            None => {
-                                self.finish_split();
-                                return Err(CsplitError::LineOutOfRange(
-                                    pattern_as_str.to_string(),
-                                ));
-                            }
-                        };
-                        offset -= 1;
-                    }
-                    self.finish_split();
-                    return Ok(());
-                }
-                self.writeln(&l)?;
-            }
-        } else {
-            // With a negative offset we use a buffer to keep the lines within the offset.
-            // NOTE: do not drain the buffer of input_iter, in case of an LineOutOfRange error
-            // but do not rewind it either since no match should be done within.
-            // The consequence is that the buffer may already be full with lines from a previous
-            // split, which is taken care of when calling `shrink_buffer_to_size`.
-            let offset_usize = -offset as usize;
-            input_iter.set_size_of_buffer(offset_usize);
-            while let Some((ln, line)) = input_iter.next() {
-                let l = line?;
-                if regex.is_match(&l) {
-                    for line in input_iter.shrink_buffer_to_size() {
-                        self.writeln(&line)?;
-                    }
-                    if !self.options.suppress_matched {
-                        // add 1 to the buffer size to make place for the matched line
-                        input_iter.set_size_of_buffer(offset_usize + 1);
-                        assert!(
-                            input_iter.add_line_to_buffer(ln, l).is_none(),
-                            "should be big enough to hold every lines"
-                        );
-                    }
-                    self.finish_split();
-                    if input_iter.buffer_len() < offset_usize {
-                        return Err(CsplitError::LineOutOfRange(pattern_as_str.to_string()));
-                    }
-                    return Ok(());
-                }
-                if let Some(line) = input_iter.add_line_to_buffer(ln, l) {
-                    self.writeln(&line)?;
-                }
-            }
-            // no match, drain the buffer into the current split
-            for line in input_iter.drain_buffer() {
-                self.writeln(&line)?;
-            }
-        }
-
-        self.finish_split();
-        Err(CsplitError::MatchNotFound(pattern_as_str.to_string()))
-    }
 }
 
-/// An iterator which can output items from a buffer filled externally.
-/// This is used to pass matching lines to the next split and to support patterns with a negative offset.
-struct InputSplitter<I>
-where
-    I: Iterator<Item = (usize, io::Result<String>)>,
-{
-    iter: I,
-    buffer: Vec<<I as Iterator>::Item>,
-    /// the number of elements the buffer may hold
-    size: usize,
-    /// flag to indicate content off the buffer should be returned instead of off the wrapped
-    /// iterator
-    rewind: bool,
+/// The lines of the input that were read but not split yet.
+///
+/// The patterns look for lines ahead of the ones written to the splits, e.g. a regular
+/// expression with a negative offset is matched on lines that go to the next split, so the
+/// lines are read as needed while looking for them, and are only removed from the buffer
+/// once they are split.
+struct InputBuffer<T> {
+    input: T,
+    /// the lines read and not split yet, with their newline character
+    lines: VecDeque<Vec<u8>>,
+    /// the number, starting from 1, of the first line in the buffer
+    first_line: usize,
+    /// the number of the last line a regular expression was tried on or that was split, the
+    /// next regular expression is tried from the line after it
+    current_line: usize,
+    /// flag to indicate that the whole input has been read
+    eof: bool,
 }
 
-impl<I> InputSplitter<I>
+impl<T> InputBuffer<T>
 where
-    I: Iterator<Item = (usize, io::Result<String>)>,
+    T: BufRead,
 {
-    fn new(iter: I) -> Self {
+    fn new(input: T) -> Self {
         Self {
-            iter,
-            buffer: Vec::new(),
-            rewind: false,
-            size: 1,
+            input,
+            lines: VecDeque::new(),
+            first_line: 1,
+            current_line: 0,
+            eof: false,
         }
     }
 
-    /// Rewind the iteration by outputting the buffer's content.
-    fn rewind_buffer(&mut self) {
-        self.rewind = true;
+    /// Returns the number of the next line to split.
+    fn first_line(&self) -> usize {
+        self.first_line
     }
 
-    /// Shrink the buffer so that its length is equal to the set size, returning an iterator for
-    /// the elements that were too much.
-    fn shrink_buffer_to_size(&mut self) -> impl Iterator<Item = String> + '_ {
-        let shrink_offset = if self.buffer.len() > self.size {
-            self.buffer.len() - self.size
-        } else {
-            0
-        };
-        self.buffer
-            .drain(..shrink_offset)
-            .map(|(_, line)| line.unwrap())
-    }
-
-    /// Drain the content of the buffer.
-    fn drain_buffer(&mut self) -> impl Iterator<Item = String> + '_ {
-        self.buffer.drain(..).map(|(_, line)| line.unwrap())
-    }
-
-    /// Set the maximum number of lines to keep.
-    fn set_size_of_buffer(&mut self, size: usize) {
-        self.size = size;
+    /// Reads one more line into the buffer, returning false at the end of the input.
+    fn read_line(&mut self) -> io::Result<bool> {
+        if self.eof {
+            return Ok(false);
+        }
+        let mut line = Vec::new();
+        if self.input.read_until(b'\n', &mut line)? == 0 {
+            self.eof = true;
+            return Ok(false);
+        }
+        self.lines.push_back(line);
+        Ok(true)
     }
 
-    /// Add a line to the buffer. If the buffer has [`self.size`] elements, then its head is removed and
-    /// the new line is pushed to the buffer. The removed head is then available in the returned
-    /// option.
-    fn add_line_to_buffer(&mut self, ln: usize, line: String) -> Option<String> {
-        if self.rewind {
-            self.buffer.insert(0, (ln, Ok(line)));
-            None
-        } else if self.buffer.len() >= self.size {
-            let (_, head_line) = self.buffer.remove(0);
-            self.buffer.push((ln, Ok(line)));
-            Some(head_line.unwrap())
-        } else {
-            self.buffer.push((ln, Ok(line)));
-            None
+    /// Returns the line number `n`, reading the input up to it if needed, or `None` if it is past
+    /// the end of the input or was already split.
+    fn find_line(&mut self, n: usize) -> io::Result<Option<&[u8]>> {
+        if n < self.first_line {
+            return Ok(None);
+        }
+        let index = n - self.first_line;
+        while self.lines.len() <= index {
+            if !self.read_line()? {
+                return Ok(None);
+            }
         }
+        Ok(Some(&self.lines[index]))
     }
 
-    /// Returns the number of lines stored in the buffer
-    fn buffer_len(&self) -> usize {
-        self.buffer.len()
+    /// Removes the next line to split from the buffer and returns it, or `None` at the end of the
+    /// input.
+    fn remove_line(&mut self) -> io::Result<Option<Vec<u8>>> {
+        if self.lines.is_empty() && !self.read_line()? {
+            return Ok(None);
+        }
+        self.current_line = self.current_line.max(self.first_line);
+        self.first_line += 1;
+        Ok(self.lines.pop_front())
     }
-}
-
-impl<I> Iterator for InputSplitter<I>
-where
-    I: Iterator<Item = (usize, io::Result<String>)>,
-{
-    type Item = <I as Iterator>::Item;
 
-    fn next(&mut self) -> Option<Self::Item> {
-        if self.rewind {
-            if !self.buffer.is_empty() {
-                return Some(self.buffer.remove(0));
-            }
-            self.rewind = false;
-        }
-        self.iter.next()
+    /// Returns true if there are no lines after the current one.
+    fn no_more_lines(&mut self) -> io::Result<bool> {
+        Ok(self.find_line(self.current_line + 1)?.is_none())
     }
 }
 
@@ -556,161 +423,37 @@ mod tests {
     use super::*;
 
     #[test]
-    fn input_splitter() {
-        let input = vec![
-            Ok(String::from("aaa")),
-            Ok(String::from("bbb")),
-            Ok(String::from("ccc")),
-            Ok(String::from("ddd")),
-        ];
-        let mut input_splitter = InputSplitter::new(input.into_iter().enumerate());
-
-        input_splitter.set_size_of_buffer(2);
-        assert_eq!(input_splitter.buffer_len(), 0);
-
-        match input_splitter.next() {
-            Some((0, Ok(line))) => {
-                assert_eq!(line, String::from("aaa"));
-                assert_eq!(input_splitter.add_line_to_buffer(0, line), None);
-                assert_eq!(input_splitter.buffer_len(), 1);
-            }
-            item => panic!("wrong item: {item:?}"),
-        };
-
-        match input_splitter.next() {
-            Some((1, Ok(line))) => {
-                assert_eq!(line, String::from("bbb"));
-                assert_eq!(input_splitter.add_line_to_buffer(1, line), None);
-                assert_eq!(input_splitter.buffer_len(), 2);
-            }
-            item => panic!("wrong item: {item:?}"),
-        };
-
-        match input_splitter.next() {
-            Some((2, Ok(line))) => {
-                assert_eq!(line, String::from("ccc"));
-                assert_eq!(
-                    input_splitter.add_line_to_buffer(2, line),
-                    Some(String::from("aaa"))
-                );
-                assert_eq!(input_splitter.buffer_len(), 2);
-            }
-            item => panic!("wrong item: {item:?}"),
-        };
-
-        input_splitter.rewind_buffer();
-
-        match input_splitter.next() {
-            Some((1, Ok(line))) => {
-                assert_eq!(line, String::from("bbb"));
-                assert_eq!(input_splitter.buffer_len(), 1);
-            }
-            item => panic!("wrong item: {item:?}"),
-        };
-
-        match input_splitter.next() {
-            Some((2, Ok(line))) => {
-                assert_eq!(line, String::from("ccc"));
-                assert_eq!(input_splitter.buffer_len(), 0);
-            }
-            item => panic!("wrong item: {item:?}"),
-        };
-
-        match input_splitter.next() {
-            Some((3, Ok(line))) => {
-                assert_eq!(line, String::from("ddd"));
-                assert_eq!(input_splitter.buffer_len(), 0);
-            }
-            item => panic!("wrong item: {item:?}"),
-        };
-
-        assert!(input_splitter.next().is_none());
+    fn input_buffer() {
+        let mut input = InputBuffer::new(&b"aaa\nbbb\nccc\nddd"[..]);
+
+        assert_eq!(input.first_line(), 1);
+        assert_eq!(input.find_line(3).unwrap(), Some(&b"ccc\n"[..]));
+        assert_eq!(input.lines.len(), 3);
+        assert_eq!(input.find_line(1).unwrap(), Some(&b"aaa\n"[..]));
+
+        assert_eq!(input.remove_line().unwrap(), Some(b"aaa\n".to_vec()));
+        assert_eq!(input.first_line(), 2);
+        assert_eq!(input.find_line(1).unwrap(), None);
+        assert_eq!(input.find_line(2).unwrap(), Some(&b"bbb\n"[..]));
+
+        assert_eq!(input.find_line(4).unwrap(), Some(&b"ddd"[..]));
+        assert_eq!(input.find_line(5).unwrap(), None);
+        assert!(!input.no_more_lines().unwrap());
+        input.current_line = 4;
+        assert!(input.no_more_lines().unwrap());
     }
 
     #[test]
-    fn input_splitter_interrupt_rewind() {
-        let input = vec![
-            Ok(String::from("aaa")),
-            Ok(String::from("bbb")),
-            Ok(String::from("ccc")),
-            Ok(String::from("ddd")),
-        ];
-        let mut input_splitter = InputSplitter::new(input.into_iter().enumerate());
-
-        input_splitter.set_size_of_buffer(3);
-        assert_eq!(input_splitter.buffer_len(), 0);
-
-        match input_splitter.next() {
-            Some((0, Ok(line))) => {
-                assert_eq!(line, String::from("aaa"));
-                assert_eq!(input_splitter.add_line_to_buffer(0, line), None);
-                assert_eq!(input_splitter.buffer_len(), 1);
-            }
-            item => panic!("wrong item: {item:?}"),
-        };
-
-        match input_splitter.next() {
-            Some((1, Ok(line))) => {
-                assert_eq!(line, String::from("bbb"));
-                assert_eq!(input_splitter.add_line_to_buffer(1, line), None);
-                assert_eq!(input_splitter.buffer_len(), 2);
-            }
-            item => panic!("wrong item: {item:?}"),
-        };
-
-        match input_splitter.next() {
-            Some((2, Ok(line))) => {
-                assert_eq!(line, String::from("ccc"));
-                assert_eq!(input_splitter.add_line_to_buffer(2, line), None);
-                assert_eq!(input_splitter.buffer_len(), 3);
-            }
-            item => panic!("wrong item: {item:?}"),
-        };
-
-        input_splitter.rewind_buffer();
-
-        match input_splitter.next() {
-            Some((0, Ok(line))) => {
-                assert_eq!(line, String::from("aaa"));
-                assert_eq!(input_splitter.add_line_to_buffer(0, line), None);
-                assert_eq!(input_splitter.buffer_len(), 3);
-            }
-            item => panic!("wrong item: {item:?}"),
-        };
-
-        match input_splitter.next() {
-            Some((0, Ok(line))) => {
-                assert_eq!(line, String::from("aaa"));
-                assert_eq!(input_splitter.buffer_len(), 2);
-            }
-            item => panic!("wrong item: {item:?}"),
-        };
-
-        match input_splitter.next() {
-            Some((1, Ok(line))) => {
-                assert_eq!(line, String::from("bbb"));
-                assert_eq!(input_splitter.buffer_len(), 1);
-            }
-            item => panic!("wrong item: {item:?}"),
-        };
-
-        match input_splitter.next() {
-            Some((2, Ok(line))) => {
-                assert_eq!(line, String::from("ccc"));
-                assert_eq!(input_splitter.buffer_len(), 0);
-            }
-            item => panic!("wrong item: {item:?}"),
-        };
-
-        match input_splitter.next() {
-            Some((3, Ok(line))) => {
-                assert_eq!(line, String::from("ddd"));
-                assert_eq!(input_splitter.buffer_len(), 0);
-            }
-            item => panic!("wrong item: {item:?}"),
-        };
-
-        assert!(input_splitter.next().is_none());
+    fn input_buffer_remove_lines_not_read() {
+        let mut input = InputBuffer::new(&b"aaa\nbbb\n"[..]);
+
+        assert_eq!(input.remove_line().unwrap(), Some(b"aaa\n".to_vec()));
+        assert_eq!(input.remove_line().unwrap(), Some(b"bbb\n".to_vec()));
+        assert_eq!(input.first_line(), 3);
+        assert_eq!(input.current_line, 2);
+        assert!(input.no_more_lines().unwrap());
+        assert_eq!(input.remove_line().unwrap(), None);
+        assert_eq!(input.first_line(), 3);
     }
 }
 
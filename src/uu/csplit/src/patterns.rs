@@ -1,7 +1,7 @@
 // spell-checker:ignore (regex) SKIPTO UPTO ; (vars) ntimes
 
 use crate::csplit_error::CsplitError;
-use regex::Regex;
+use regex::{bytes, Regex};
 use uucore::show_warning;
 
 /// The definition of a pattern to match on a line.
@@ -14,11 +14,11 @@ pub enum Pattern {
     /// integer is an offset relative to the matched line of what to include (if positive) or
     /// to exclude (if negative). The number of times the pattern is executed is detailed in
     /// [`ExecutePattern`].
-    UpToMatch(Regex, i32, ExecutePattern),
+    UpToMatch(bytes::Regex, i32, ExecutePattern),
     /// Skip the file's content up to, not including, the line matching the regex. The integer
     /// is an offset relative to the matched line of what to include (if positive) or to exclude
     /// (if negative). The number of times the pattern is executed is detailed in [`ExecutePattern`].
-    SkipToMatch(Regex, i32, ExecutePattern),
+    SkipToMatch(bytes::Regex, i32, ExecutePattern),
 }
 
 impl ToString for Pattern {
@@ -134,11 +134,11 @@ fn extract_patterns(args: &[String]) -> Result<Vec<Pattern>, CsplitError> {
                 Some(m) => m.as_str().parse().unwrap(),
             };
             if let Some(up_to_match) = captures.name("UPTO") {
-                let pattern = Regex::new(up_to_match.as_str())
+                let pattern = bytes::Regex::new(up_to_match.as_str())
                     .map_err(|_| CsplitError::InvalidPattern(arg.to_string()))?;
                 patterns.push(Pattern::UpToMatch(pattern, offset, execute_ntimes));
             } else if let Some(skip_to_match) = captures.name("SKIPTO") {
-                let pattern = Regex::new(skip_to_match.as_str())
+                let pattern = bytes::Regex::new(skip_to_match.as_str())
                     .map_err(|_| CsplitError::InvalidPattern(arg.to_string()))?;
                 patterns.push(Pattern::SkipToMatch(pattern, offset, execute_ntimes));
             }
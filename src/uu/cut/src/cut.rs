@@ -5,26 +5,27 @@
 // For the full copyright and license information, please view the LICENSE
 // file that was distributed with this source code.
 
-// spell-checker:ignore (ToDO) delim sourcefiles
+// spell-checker:ignore (ToDO) delim sourcefiles memmap Mmap mmap SIGBUS
 
-use bstr::io::BufReadExt;
-use clap::{crate_version, Arg, ArgAction, Command};
+use clap::{crate_version, Arg, ArgAction, ArgMatches, Command};
+use memmap2::Mmap;
+use regex::bytes::Regex;
 use std::fs::File;
 use std::io::{stdin, stdout, BufReader, BufWriter, Read, Write};
 use std::path::Path;
 use uucore::display::Quotable;
 use uucore::error::{FromIo, UResult, USimpleError};
 
-use self::searcher::Searcher;
-use matcher::{ExactMatcher, Matcher, WhitespaceMatcher};
-use uucore::ranges::Range;
+use engine::{Cutter, CutterBuilder, Delimiter, OutputFormat, Range};
 use uucore::{format_usage, show, show_error, show_if_err};
 
+pub mod engine;
 mod matcher;
 mod searcher;
 
 static USAGE: &str =
-    "{} [-d|-w] [-s] [-z] [--output-delimiter] ((-f|-b|-c) {{sequence}}) {{sourcefile}}+";
+    "{} [-d|-w|--regex-delimiter] [--csv] [-s] [-z] [--output-delimiter] ((-f|-b|-c) {{sequence}}) {{sourcefile}}+
+    {} [OPTION]... ((-f|-b|-c) {{sequence}}) --files0-from=F";
 static ABOUT: &str =
     "Prints specified byte or field columns from each line of stdin or the input files";
 static LONG_HELP: &str = "
@@ -35,6 +36,11 @@ static LONG_HELP: &str = "
 
     Use --bytes (-b) or --characters (-c) to specify byte mode
 
+    When -n is used together with --bytes (-b), a selected range is adjusted
+    so that it never splits a multibyte (UTF-8) character: a range starting
+    inside a character is extended to include the whole character, and a
+    range ending inside a character stops before it
+
     Use --fields (-f) to specify field mode, where each line is broken into
     fields identified by a delimiter character. For example for a typical CSV
     you could use this in combination with setting comma as the delimiter
@@ -59,6 +65,19 @@ static LONG_HELP: &str = "
     cut -f 1,3-5 some_file.txt
     will display the 1st, 3rd, 4th, and 5th field for each source line
 
+    A range may be followed by a step, to only take every Nth column of the
+    range. The range must then have an end
+
+    cut -f 2-20:3 some_file.txt
+    will display every third field from the 2nd to the 20th for each source line
+
+    Fields are always printed in ascending order, however they are listed in
+    the sequence. With the --ordered option, fields are instead printed in
+    the order of the sequence, and a field listed twice is printed twice
+
+    cut --ordered -f 3,1 some_file.txt
+    will display the 3rd field followed by the 1st field for each source line
+
     The --complement option, when used, inverts the effect of the sequence
 
     cut --complement -f 4-6 some_file.txt
@@ -77,6 +96,10 @@ static LONG_HELP: &str = "
     To print columns from both STDIN and a file argument, use - (dash) as a
     sourcefile argument to represent stdin.
 
+    With --files0-from=F, the names of the sourcefiles are read from F as a
+    list of NUL-terminated names instead of being given as arguments. If F
+    is - (dash), the names are read from STDIN.
+
  Field Mode options
 
     The fields in each line are identified by a delimiter (separator)
@@ -86,11 +109,43 @@ static LONG_HELP: &str = "
         --delimiter (-d) option. Setting the delimiter is optional.
         If not set, a default delimiter of Tab will be used.
 
+        The delimiter may be longer than one character (e.g. -d '::') and
+        may contain the escapes \\0, \\a, \\b, \\f, \\n, \\r, \\t, \\v and \\\\.
+
         If the -w option is provided, fields will be separated by any number
         of whitespace characters (Space and Tab). The output delimiter will
         be a Tab unless explicitly specified. Only one of -d or -w option can be specified.
         This is an extension adopted from FreeBSD.
 
+        If the --regex-delimiter option is provided, fields will be separated
+        by any non-empty match of the given regular expression, similar to
+        awk -F. Only one of -d, -w or --regex-delimiter can be specified.
+
+    Parse CSV input
+        If the --csv option is provided, fields are parsed according to
+        RFC 4180: a delimiter or line ending inside a double-quoted field
+        does not split it. The delimiter defaults to a comma. Selected fields
+        are printed as they appear in the input, unless --output-delimiter is
+        also given, in which case each field is re-quoted as needed for the
+        new delimiter.
+
+    Transform fields
+        If the --trim option is provided, leading and trailing whitespace is
+        removed from each printed field. If the --empty-as option is provided,
+        its argument is printed instead of each empty field, after trimming
+
+    Validate input
+        If the --strict option is provided, each printed line that lacks one
+        of the requested fields is reported with its line number, and cut
+        exits with an error once the source was read. Ranges without an end
+        only require their first field. Lines skipped because of
+        --only-delimited (-s) are not checked
+
+    Structured output
+        If the --output-format=json option is provided, each printed line is
+        a JSON array of the selected fields, as strings. Fields parsed with
+        --csv are unquoted first. --output-delimiter has no effect then.
+
     Optionally Filter based on delimiter
         If the --only-delimited (-s) flag is provided, only lines which
         contain the delimiter will be printed
@@ -112,29 +167,6 @@ static LONG_HELP: &str = "
     will result in 'a\\0c\\0'
 ";
 
-struct Options {
-    out_delim: Option<String>,
-    zero_terminated: bool,
-}
-
-enum Delimiter {
-    Whitespace,
-    String(String), // FIXME: use char?
-}
-
-struct FieldOptions {
-    delimiter: Delimiter,
-    out_delimiter: Option<String>,
-    only_delimited: bool,
-    zero_terminated: bool,
-}
-
-enum Mode {
-    Bytes(Vec<Range>, Options),
-    Characters(Vec<Range>, Options),
-    Fields(Vec<Range>, FieldOptions),
-}
-
 fn stdout_writer() -> Box<dyn Write> {
     if atty::is(atty::Stream::Stdout) {
         Box::new(stdout())
@@ -143,261 +175,123 @@ fn stdout_writer() -> Box<dyn Write> {
     }
 }
 
-fn list_to_ranges(list: &str, complement: bool) -> Result<Vec<Range>, String> {
-    if complement {
-        Range::from_list(list).map(|r| uucore::ranges::complement(&r))
-    } else {
-        Range::from_list(list)
-    }
-}
-
-fn cut_bytes<R: Read>(reader: R, ranges: &[Range], opts: &Options) -> UResult<()> {
-    let newline_char = if opts.zero_terminated { b'\0' } else { b'\n' };
-    let mut buf_in = BufReader::new(reader);
-    let mut out = stdout_writer();
-    let delim = opts
-        .out_delim
-        .as_ref()
-        .map_or("", String::as_str)
-        .as_bytes();
-
-    let result = buf_in.for_byte_record(newline_char, |line| {
-        let mut print_delim = false;
-        for &Range { low, high } in ranges {
-            if low > line.len() {
-                break;
-            }
-            if print_delim {
-                out.write_all(delim)?;
-            } else if opts.out_delim.is_some() {
-                print_delim = true;
+/// Expand the backslash escapes supported in a `--delimiter` argument.
+///
+/// Unknown escapes and a trailing backslash are kept as-is, so that a lone
+/// `\\` still works as a delimiter.
+fn unescape_delimiter(delim: &str) -> String {
+    let mut result = String::with_capacity(delim.len());
+    let mut chars = delim.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            result.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('0') => result.push('\0'),
+            Some('a') => result.push('\x07'),
+            Some('b') => result.push('\x08'),
+            Some('f') => result.push('\x0c'),
+            Some('n') => result.push('\n'),
+            Some('r') => result.push('\r'),
+            Some('t') => result.push('\t'),
+            Some('v') => result.push('\x0b'),
+            Some('\\') => result.push('\\'),
+            Some(other) => {
+                result.push('\\');
+                result.push(other);
             }
-            // change `low` from 1-indexed value to 0-index value
-            let low = low - 1;
-            let high = high.min(line.len());
-            out.write_all(&line[low..high])?;
+            None => result.push('\\'),
         }
-        out.write_all(&[newline_char])?;
-        Ok(true)
-    });
-
-    if let Err(e) = result {
-        return Err(USimpleError::new(1, e.to_string()));
     }
-
-    Ok(())
+    result
 }
 
-// Output delimiter is explicitly specified
-fn cut_fields_explicit_out_delim<R: Read, M: Matcher>(
-    reader: R,
-    matcher: &M,
-    ranges: &[Range],
-    only_delimited: bool,
-    newline_char: u8,
-    out_delim: &str,
-) -> UResult<()> {
-    let mut buf_in = BufReader::new(reader);
-    let mut out = stdout_writer();
-
-    let result = buf_in.for_byte_record_with_terminator(newline_char, |line| {
-        let mut fields_pos = 1;
-        let mut low_idx = 0;
-        let mut delim_search = Searcher::new(matcher, line).peekable();
-        let mut print_delim = false;
-
-        if delim_search.peek().is_none() {
-            if !only_delimited {
-                out.write_all(line)?;
-                if line[line.len() - 1] != newline_char {
-                    out.write_all(&[newline_char])?;
-                }
-            }
-
-            return Ok(true);
-        }
-
-        for &Range { low, high } in ranges {
-            if low - fields_pos > 0 {
-                // current field is not in the range, so jump to the field corresponding to the
-                // beginning of the range if any
-                low_idx = match delim_search.nth(low - fields_pos - 1) {
-                    Some((_, last)) => last,
-                    None => break,
-                };
-            }
-
-            // at this point, current field is the first in the range
-            for _ in 0..=high - low {
-                // skip printing delimiter if this is the first matching field for this line
-                if print_delim {
-                    out.write_all(out_delim.as_bytes())?;
-                } else {
-                    print_delim = true;
-                }
-
-                match delim_search.next() {
-                    // print the current field up to the next field delim
-                    Some((first, last)) => {
-                        let segment = &line[low_idx..first];
-
-                        out.write_all(segment)?;
-
-                        low_idx = last;
-                        fields_pos = high + 1;
-                    }
-                    None => {
-                        // this is the last field in the line, so print the rest
-                        let segment = &line[low_idx..];
-
-                        out.write_all(segment)?;
-
-                        if line[line.len() - 1] == newline_char {
-                            return Ok(true);
-                        }
-                        break;
-                    }
-                }
-            }
-        }
-
-        out.write_all(&[newline_char])?;
-        Ok(true)
-    });
-
-    if let Err(e) = result {
-        return Err(USimpleError::new(1, e.to_string()));
+// Map regular files into memory, so that records are split with memchr directly
+// over the mapping instead of being copied through a `BufReader`. A mapped slice
+// is a `BufRead` whose buffer is the whole file.
+fn try_mmap_file(file: &File) -> Option<Mmap> {
+    let metadata = file.metadata().ok()?;
+    if !metadata.is_file() || metadata.len() == 0 {
+        return None;
     }
 
-    Ok(())
+    // SAFETY: If the file is truncated while we map it, SIGBUS will be raised
+    // and our process will be terminated, thus preventing access of invalid memory.
+    unsafe { Mmap::map(file).ok() }
+}
+
+/// Where the names of the input files come from
+enum Inputs {
+    /// File operands given on the command line
+    Operands(Vec<String>),
+    /// NUL-terminated file names read from the given file, or stdin for `-`
+    Files0From(String),
 }
 
-// Output delimiter is the same as input delimiter
-fn cut_fields_implicit_out_delim<R: Read, M: Matcher>(
-    reader: R,
-    matcher: &M,
-    ranges: &[Range],
-    only_delimited: bool,
-    newline_char: u8,
-) -> UResult<()> {
-    let mut buf_in = BufReader::new(reader);
-    let mut out = stdout_writer();
-
-    let result = buf_in.for_byte_record_with_terminator(newline_char, |line| {
-        let mut fields_pos = 1;
-        let mut low_idx = 0;
-        let mut delim_search = Searcher::new(matcher, line).peekable();
-        let mut print_delim = false;
-
-        if delim_search.peek().is_none() {
-            if !only_delimited {
-                out.write_all(line)?;
-                if line[line.len() - 1] != newline_char {
-                    out.write_all(&[newline_char])?;
+impl Inputs {
+    fn into_paths(self) -> UResult<Vec<String>> {
+        match self {
+            Self::Operands(mut filenames) => {
+                if filenames.is_empty() {
+                    filenames.push("-".to_owned());
                 }
+                Ok(filenames)
             }
-
-            return Ok(true);
-        }
-
-        for &Range { low, high } in ranges {
-            if low - fields_pos > 0 {
-                if let Some((first, last)) = delim_search.nth(low - fields_pos - 1) {
-                    low_idx = if print_delim { first } else { last }
+            Self::Files0From(source) => {
+                let read_from_stdin = source == "-";
+                let mut names = Vec::new();
+                if read_from_stdin {
+                    stdin().lock().read_to_end(&mut names)
                 } else {
-                    break;
+                    File::open(&source).and_then(|mut file| file.read_to_end(&mut names))
                 }
-            }
-
-            match delim_search.nth(high - low) {
-                Some((first, _)) => {
-                    let segment = &line[low_idx..first];
+                .map_err_context(|| format!("cannot open {} for reading", source.quote()))?;
 
-                    out.write_all(segment)?;
-
-                    print_delim = true;
-                    low_idx = first;
-                    fields_pos = high + 1;
+                let names = names.strip_suffix(b"\0").unwrap_or(&names);
+                if names.is_empty() {
+                    return Ok(Vec::new());
                 }
-                None => {
-                    let segment = &line[low_idx..line.len()];
-
-                    out.write_all(segment)?;
 
-                    if line[line.len() - 1] == newline_char {
-                        return Ok(true);
+                let mut filenames = Vec::new();
+                for name in names.split(|&b| b == b'\0') {
+                    let name = String::from_utf8(name.to_vec()).map_err(|_| {
+                        USimpleError::new(
+                            1,
+                            format!("{}: invalid UTF-8 in file name", source.maybe_quote()),
+                        )
+                    })?;
+                    if read_from_stdin && name == "-" {
+                        return Err(USimpleError::new(
+                            1,
+                            "when reading file names from stdin, no file name of '-' allowed",
+                        ));
                     }
-                    break;
+                    filenames.push(name);
                 }
+                Ok(filenames)
             }
         }
-        out.write_all(&[newline_char])?;
-        Ok(true)
-    });
-
-    if let Err(e) = result {
-        return Err(USimpleError::new(1, e.to_string()));
     }
-
-    Ok(())
 }
 
-fn cut_fields<R: Read>(reader: R, ranges: &[Range], opts: &FieldOptions) -> UResult<()> {
-    let newline_char = if opts.zero_terminated { b'\0' } else { b'\n' };
-    match opts.delimiter {
-        Delimiter::String(ref delim) => {
-            let matcher = ExactMatcher::new(delim.as_bytes());
-            match opts.out_delimiter {
-                Some(ref out_delim) => cut_fields_explicit_out_delim(
-                    reader,
-                    &matcher,
-                    ranges,
-                    opts.only_delimited,
-                    newline_char,
-                    out_delim,
-                ),
-                None => cut_fields_implicit_out_delim(
-                    reader,
-                    &matcher,
-                    ranges,
-                    opts.only_delimited,
-                    newline_char,
-                ),
-            }
-        }
-        Delimiter::Whitespace => {
-            let matcher = WhitespaceMatcher {};
-            let out_delim = opts.out_delimiter.as_deref().unwrap_or("\t");
-            cut_fields_explicit_out_delim(
-                reader,
-                &matcher,
-                ranges,
-                opts.only_delimited,
-                newline_char,
-                out_delim,
-            )
-        }
-    }
-}
-
-fn cut_files(mut filenames: Vec<String>, mode: &Mode) {
+fn cut_files(inputs: Inputs, cutter: &Cutter) -> UResult<()> {
     let mut stdin_read = false;
-
-    if filenames.is_empty() {
-        filenames.push("-".to_owned());
-    }
+    let from_files0 = matches!(inputs, Inputs::Files0From(_));
+    let filenames = inputs.into_paths()?;
 
     for filename in &filenames {
+        if from_files0 && filename.is_empty() {
+            show!(USimpleError::new(1, "invalid zero-length file name"));
+            continue;
+        }
+
         if filename == "-" {
             if stdin_read {
                 continue;
             }
 
-            show_if_err!(match mode {
-                Mode::Bytes(ref ranges, ref opts) => cut_bytes(stdin(), ranges, opts),
-                Mode::Characters(ref ranges, ref opts) => cut_bytes(stdin(), ranges, opts),
-                Mode::Fields(ref ranges, ref opts) => cut_fields(stdin(), ranges, opts),
-            });
+            show_if_err!(cutter.cut_input(stdin().lock(), filename, stdout_writer()));
 
             stdin_read = true;
         } else {
@@ -410,16 +304,14 @@ fn cut_files(mut filenames: Vec<String>, mode: &Mode) {
 
             show_if_err!(File::open(path)
                 .map_err_context(|| filename.maybe_quote().to_string())
-                .and_then(|file| {
-                    match &mode {
-                        Mode::Bytes(ranges, opts) | Mode::Characters(ranges, opts) => {
-                            cut_bytes(file, ranges, opts)
-                        }
-                        Mode::Fields(ranges, opts) => cut_fields(file, ranges, opts),
-                    }
+                .and_then(|file| match try_mmap_file(&file) {
+                    Some(mmap) => cutter.cut_input(&mmap[..], filename, stdout_writer()),
+                    None => cutter.cut_input(BufReader::new(file), filename, stdout_writer()),
                 }));
         }
     }
+
+    Ok(())
 }
 
 mod options {
@@ -431,10 +323,53 @@ mod options {
     pub const ONLY_DELIMITED: &str = "only-delimited";
     pub const OUTPUT_DELIMITER: &str = "output-delimiter";
     pub const WHITESPACE_DELIMITED: &str = "whitespace-delimited";
+    pub const REGEX_DELIMITER: &str = "regex-delimiter";
+    pub const CSV: &str = "csv";
+    pub const NO_SPLIT_MULTIBYTE: &str = "no-split-multibyte";
+    pub const ORDERED: &str = "ordered";
+    pub const FILES0_FROM: &str = "files0-from";
+    pub const OUTPUT_FORMAT: &str = "output-format";
+    pub const TRIM: &str = "trim";
+    pub const EMPTY_AS: &str = "empty-as";
+    pub const STRICT: &str = "strict";
     pub const COMPLEMENT: &str = "complement";
     pub const FILE: &str = "file";
 }
 
+// Apply the options that are shared by all modes; the engine ignores the
+// field options when cutting bytes or characters
+fn with_common_options(mut builder: CutterBuilder, matches: &ArgMatches) -> CutterBuilder {
+    if let Some(out_delim) = matches.get_one::<String>(options::OUTPUT_DELIMITER) {
+        builder = if out_delim.is_empty() && matches.contains_id(options::FIELDS) {
+            builder.output_delimiter("\0")
+        } else {
+            builder.output_delimiter(out_delim)
+        };
+    }
+    if let Some(placeholder) = matches.get_one::<String>(options::EMPTY_AS) {
+        builder = builder.empty_as(placeholder);
+    }
+    let output_format = match matches
+        .get_one::<String>(options::OUTPUT_FORMAT)
+        .map(|s| s.as_str())
+    {
+        Some("json") => OutputFormat::Json,
+        _ => OutputFormat::Text,
+    };
+    let terminator = if matches.get_flag(options::ZERO_TERMINATED) {
+        b'\0'
+    } else {
+        b'\n'
+    };
+    builder
+        .terminator(terminator)
+        .only_delimited(matches.get_flag(options::ONLY_DELIMITED))
+        .output_format(output_format)
+        .trim(matches.get_flag(options::TRIM))
+        .strict(matches.get_flag(options::STRICT))
+        .complement(matches.get_flag(options::COMPLEMENT))
+}
+
 #[uucore::main]
 pub fn uumain(args: impl uucore::Args) -> UResult<()> {
     let args = args.collect_ignore();
@@ -449,57 +384,41 @@ pub fn uumain(args: impl uucore::Args) -> UResult<()> {
         matches.get_one::<String>(options::CHARACTERS),
         matches.get_one::<String>(options::FIELDS),
     ) {
-        (Some(byte_ranges), None, None) => list_to_ranges(byte_ranges, complement).map(|ranges| {
-            Mode::Bytes(
-                ranges,
-                Options {
-                    out_delim: Some(
-                        matches
-                            .get_one::<String>(options::OUTPUT_DELIMITER)
-                            .map(|s| s.as_str())
-                            .unwrap_or_default()
-                            .to_owned(),
-                    ),
-                    zero_terminated: matches.get_flag(options::ZERO_TERMINATED),
-                },
-            )
-        }),
-        (None, Some(char_ranges), None) => list_to_ranges(char_ranges, complement).map(|ranges| {
-            Mode::Characters(
-                ranges,
-                Options {
-                    out_delim: Some(
-                        matches
-                            .get_one::<String>(options::OUTPUT_DELIMITER)
-                            .map(|s| s.as_str())
-                            .unwrap_or_default()
-                            .to_owned(),
-                    ),
-                    zero_terminated: matches.get_flag(options::ZERO_TERMINATED),
-                },
-            )
+        (Some(byte_ranges), None, None) => Range::from_list(byte_ranges).map(|ranges| {
+            Cutter::bytes(ranges)
+                .no_split_multibyte(matches.get_flag(options::NO_SPLIT_MULTIBYTE))
         }),
+        (None, Some(char_ranges), None) => Range::from_list(char_ranges).map(Cutter::characters),
         (None, None, Some(field_ranges)) => {
-            list_to_ranges(field_ranges, complement).and_then(|ranges| {
-                let out_delim = match matches.get_one::<String>(options::OUTPUT_DELIMITER) {
-                    Some(s) => {
-                        if s.is_empty() {
-                            Some("\0".to_owned())
-                        } else {
-                            Some(s.to_owned())
-                        }
-                    }
-                    None => None,
+            let ordered = matches.get_flag(options::ORDERED) && !complement;
+            let ranges = if ordered {
+                Range::from_list_unordered(field_ranges)
+            } else {
+                Range::from_list(field_ranges)
+            };
+            ranges.and_then(|ranges| {
+                // keep the streaming cutters when the list is already in ascending order
+                let (ranges, ordered) = if ordered && Range::is_ascending(&ranges) {
+                    (Range::from_list(field_ranges)?, false)
+                } else {
+                    (ranges, ordered)
                 };
 
-                let only_delimited = matches.get_flag(options::ONLY_DELIMITED);
                 let whitespace_delimited = matches.get_flag(options::WHITESPACE_DELIMITED);
-                let zero_terminated = matches.get_flag(options::ZERO_TERMINATED);
+
+                let regex_delimiter = matches.get_one::<String>(options::REGEX_DELIMITER);
+                let csv = matches.get_flag(options::CSV);
 
                 match matches.get_one::<String>(options::DELIMITER).map(|s| s.as_str()) {
                     Some(_) if whitespace_delimited => {
                             Err("invalid input: Only one of --delimiter (-d) or -w option can be specified".into())
                         }
+                    Some(_) if regex_delimiter.is_some() => {
+                            Err("invalid input: Only one of --delimiter (-d) or --regex-delimiter option can be specified".into())
+                        }
+                    _ if csv && (whitespace_delimited || regex_delimiter.is_some()) => {
+                            Err("invalid input: The '--csv' option cannot be combined with -w or --regex-delimiter".into())
+                        }
                     Some(mut delim) => {
                         // GNU's `cut` supports `-d=` to set the delimiter to `=`.
                         // Clap parsing is limited in this situation, see:
@@ -510,38 +429,41 @@ pub fn uumain(args: impl uucore::Args) -> UResult<()> {
                             // treat `''` as empty delimiter
                             delim = "";
                         }
-                        if delim.chars().count() > 1 {
-                            Err("invalid input: The '--delimiter' ('-d') option expects empty or 1 character long, but was provided a value 2 characters or longer".into())
+                        let delim = if delim.is_empty() {
+                            "\0".to_owned()
                         } else {
-                            let delim = if delim.is_empty() {
-                                "\0".to_owned()
-                            } else {
-                                delim.to_owned()
-                            };
-
-                            Ok(Mode::Fields(
-                                ranges,
-                                FieldOptions {
-                                    delimiter: Delimiter::String(delim),
-                                    out_delimiter: out_delim,
-                                    only_delimited,
-                                    zero_terminated,
-                                },
-                            ))
-                        }
+                            unescape_delimiter(delim)
+                        };
+                        let delimiter = if csv {
+                            if delim.contains('"') {
+                                return Err("invalid input: The '--csv' option does not allow '\"' as part of the delimiter".into());
+                            }
+                            Delimiter::Csv(delim)
+                        } else {
+                            Delimiter::String(delim)
+                        };
+
+                        Ok(Cutter::fields(ranges)
+                            .delimiter(delimiter)
+                            .ordered(ordered))
+                    }
+                    None => {
+                        let delimiter = match regex_delimiter {
+                            Some(_) if whitespace_delimited => {
+                                return Err("invalid input: Only one of -w or --regex-delimiter option can be specified".into());
+                            }
+                            Some(pattern) => Delimiter::Regex(Regex::new(pattern).map_err(|e| {
+                                format!("invalid regular expression {}: {}", pattern.quote(), e)
+                            })?),
+                            None if csv => Delimiter::Csv(",".to_owned()),
+                            None if whitespace_delimited => Delimiter::Whitespace,
+                            None => Delimiter::String("\t".to_owned()),
+                        };
+
+                        Ok(Cutter::fields(ranges)
+                            .delimiter(delimiter)
+                            .ordered(ordered))
                     }
-                    None => Ok(Mode::Fields(
-                        ranges,
-                        FieldOptions {
-                            delimiter: match whitespace_delimited {
-                                true => Delimiter::Whitespace,
-                                false => Delimiter::String("\t".to_owned()),
-                            },
-                            out_delimiter: out_delim,
-                            only_delimited,
-                            zero_terminated,
-                        },
-                    )),
                 }
             })
         }
@@ -551,26 +473,36 @@ pub fn uumain(args: impl uucore::Args) -> UResult<()> {
         _ => Err("invalid usage: expects one of --fields (-f), --chars (-c) or --bytes (-b)".into()),
     };
 
+    let fields_mode = matches.contains_id(options::FIELDS);
     let mode_parse = match mode_parse {
-        Err(_) => mode_parse,
-        Ok(mode) => match mode {
-            Mode::Bytes(_, _) | Mode::Characters(_, _)
-                if matches.contains_id(options::DELIMITER) =>
-            {
-                Err("invalid input: The '--delimiter' ('-d') option only usable if printing a sequence of fields".into())
-            }
-            Mode::Bytes(_, _) | Mode::Characters(_, _)
-                if matches.get_flag(options::WHITESPACE_DELIMITED) =>
-            {
-                Err("invalid input: The '-w' option only usable if printing a sequence of fields".into())
-            }
-            Mode::Bytes(_, _) | Mode::Characters(_, _)
-                if matches.get_flag(options::ONLY_DELIMITED) =>
-            {
-                Err("invalid input: The '--only-delimited' ('-s') option only usable if printing a sequence of fields".into())
-            }
-            _ => Ok(mode),
-        },
+        Ok(_) if !fields_mode && matches.contains_id(options::DELIMITER) => {
+            Err("invalid input: The '--delimiter' ('-d') option only usable if printing a sequence of fields".into())
+        }
+        Ok(_) if !fields_mode && matches.get_flag(options::WHITESPACE_DELIMITED) => {
+            Err("invalid input: The '-w' option only usable if printing a sequence of fields".into())
+        }
+        Ok(_) if !fields_mode && matches.contains_id(options::REGEX_DELIMITER) => {
+            Err("invalid input: The '--regex-delimiter' option only usable if printing a sequence of fields".into())
+        }
+        Ok(_) if !fields_mode && matches.contains_id(options::OUTPUT_FORMAT) => {
+            Err("invalid input: The '--output-format' option only usable if printing a sequence of fields".into())
+        }
+        Ok(_) if !fields_mode && (matches.get_flag(options::TRIM) || matches.contains_id(options::EMPTY_AS)) => {
+            Err("invalid input: The '--trim' and '--empty-as' options only usable if printing a sequence of fields".into())
+        }
+        Ok(_) if !fields_mode && matches.get_flag(options::STRICT) => {
+            Err("invalid input: The '--strict' option only usable if printing a sequence of fields".into())
+        }
+        Ok(_) if !fields_mode && matches.get_flag(options::ORDERED) => {
+            Err("invalid input: The '--ordered' option only usable if printing a sequence of fields".into())
+        }
+        Ok(_) if !fields_mode && matches.get_flag(options::CSV) => {
+            Err("invalid input: The '--csv' option only usable if printing a sequence of fields".into())
+        }
+        Ok(_) if !fields_mode && matches.get_flag(options::ONLY_DELIMITED) => {
+            Err("invalid input: The '--only-delimited' ('-s') option only usable if printing a sequence of fields".into())
+        }
+        mode_parse => mode_parse,
     };
 
     let files: Vec<String> = matches
@@ -579,11 +511,22 @@ pub fn uumain(args: impl uucore::Args) -> UResult<()> {
         .map(|s| s.to_owned())
         .collect();
 
-    match mode_parse {
-        Ok(mode) => {
-            cut_files(files, &mode);
-            Ok(())
+    let inputs = match matches.get_one::<String>(options::FILES0_FROM) {
+        Some(_) if !files.is_empty() => {
+            return Err(USimpleError::new(
+                1,
+                format!(
+                    "extra operand {}\nfile operands cannot be combined with --files0-from",
+                    files[0].quote()
+                ),
+            ));
         }
+        Some(source) => Inputs::Files0From(source.to_owned()),
+        None => Inputs::Operands(files),
+    };
+
+    match mode_parse.and_then(|builder| with_common_options(builder, &matches).build()) {
+        Ok(cutter) => cut_files(inputs, &cutter),
         Err(e) => Err(USimpleError::new(1, e)),
     }
 }
@@ -611,11 +554,17 @@ pub fn uu_app() -> Command {
                 .allow_hyphen_values(true)
                 .value_name("LIST"),
         )
+        .arg(
+            Arg::new(options::NO_SPLIT_MULTIBYTE)
+                .short('n')
+                .help("with -b, don't split multibyte characters")
+                .action(ArgAction::SetTrue),
+        )
         .arg(
             Arg::new(options::DELIMITER)
                 .short('d')
                 .long(options::DELIMITER)
-                .help("specify the delimiter character that separates fields in the input source. Defaults to Tab.")
+                .help("specify the delimiter string that separates fields in the input source. Defaults to Tab.")
                 .value_name("DELIM"),
         )
         .arg(
@@ -625,6 +574,18 @@ pub fn uu_app() -> Command {
                 .value_name("WHITESPACE")
                 .action(ArgAction::SetTrue),
         )
+        .arg(
+            Arg::new(options::REGEX_DELIMITER)
+                .long(options::REGEX_DELIMITER)
+                .help("use any non-empty match of the regular expression REGEX to separate fields in the input source")
+                .value_name("REGEX"),
+        )
+        .arg(
+            Arg::new(options::CSV)
+                .long(options::CSV)
+                .help("parse fields as RFC 4180 CSV, honoring double-quoted fields. The delimiter defaults to a comma.")
+                .action(ArgAction::SetTrue),
+        )
         .arg(
             Arg::new(options::FIELDS)
                 .short('f')
@@ -639,6 +600,12 @@ pub fn uu_app() -> Command {
                 .help("invert the filter - instead of displaying only the filtered columns, display all but those columns")
                 .action(ArgAction::SetTrue),
         )
+        .arg(
+            Arg::new(options::ORDERED)
+                .long(options::ORDERED)
+                .help("in field mode, print fields in the order given in LIST instead of ascending order")
+                .action(ArgAction::SetTrue),
+        )
         .arg(
             Arg::new(options::ONLY_DELIMITED)
                 .short('s')
@@ -659,6 +626,38 @@ pub fn uu_app() -> Command {
                 .help("in field mode, replace the delimiter in output lines with this option's argument")
                 .value_name("NEW_DELIM"),
         )
+        .arg(
+            Arg::new(options::OUTPUT_FORMAT)
+                .long(options::OUTPUT_FORMAT)
+                .help("in field mode, print each line as FORMAT: 'text' (the default) or 'json', an array of the selected fields")
+                .value_name("FORMAT")
+                .value_parser(["text", "json"]),
+        )
+        .arg(
+            Arg::new(options::TRIM)
+                .long(options::TRIM)
+                .help("in field mode, remove leading and trailing whitespace from each printed field")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new(options::EMPTY_AS)
+                .long(options::EMPTY_AS)
+                .help("in field mode, print STRING instead of each empty field")
+                .value_name("STRING"),
+        )
+        .arg(
+            Arg::new(options::STRICT)
+                .long(options::STRICT)
+                .help("in field mode, report lines that lack a requested field and exit with an error")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new(options::FILES0_FROM)
+                .long(options::FILES0_FROM)
+                .help("read input from the files specified by NUL-terminated names in file F; if F is - then read names from standard input")
+                .value_name("F")
+                .value_hint(clap::ValueHint::FilePath),
+        )
         .arg(
             Arg::new(options::FILE)
             .hide(true)
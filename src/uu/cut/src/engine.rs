@@ -0,0 +1,992 @@
+// This file is part of the uutils coreutils package.
+//
+// For the full copyright and license information, please view the LICENSE
+// file that was distributed with this source code.
+
+// spell-checker:ignore (ToDO) delim
+
+//! The cutting engine behind `cut`, usable on its own.
+//!
+//! A [`Cutter`] is configured through a [`CutterBuilder`] and then selects
+//! bytes, characters or fields from every record of a reader, writing the
+//! result to a writer.
+//!
+//! ```
+//! use uu_cut::engine::{Cutter, Delimiter, Range};
+//!
+//! let cutter = Cutter::fields(Range::from_list("1,3").unwrap())
+//!     .delimiter(Delimiter::String(",".to_owned()))
+//!     .build()
+//!     .unwrap();
+//!
+//! let mut output = Vec::new();
+//! cutter.cut(&b"a,b,c\nd,e,f\n"[..], &mut output).unwrap();
+//! assert_eq!(output, b"a,c\nd,f\n");
+//! ```
+
+use bstr::io::BufReadExt;
+use bstr::ByteSlice;
+use regex::bytes::Regex;
+use std::borrow::Cow;
+use std::io::{BufRead, BufReader, Read, Write};
+use uucore::error::{UResult, USimpleError};
+use uucore::show_error;
+
+use crate::matcher::{CsvMatcher, ExactMatcher, Matcher, RegexMatcher, WhitespaceMatcher};
+use crate::searcher::Searcher;
+pub use uucore::ranges::Range;
+use uucore::ranges::Runs;
+
+/// How the fields of a record are separated
+pub enum Delimiter {
+    /// Runs of spaces and tabs, as with `-w`
+    Whitespace,
+    /// An exact string
+    String(String), // FIXME: use char?
+    /// Every match of a regular expression
+    Regex(Regex),
+    /// The given string outside of double-quoted CSV fields
+    Csv(String),
+}
+
+/// How the selected fields are written
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// The fields joined by the output delimiter
+    Text,
+    /// A JSON array of strings per record
+    Json,
+}
+
+/// Selects parts of every record of its input, like `cut` does
+pub struct Cutter {
+    mode: Mode,
+}
+
+impl Cutter {
+    /// Start building a cutter that selects byte positions
+    pub fn bytes(ranges: Vec<Range>) -> CutterBuilder {
+        CutterBuilder::new(Unit::Bytes, ranges)
+    }
+
+    /// Start building a cutter that selects character positions
+    pub fn characters(ranges: Vec<Range>) -> CutterBuilder {
+        CutterBuilder::new(Unit::Characters, ranges)
+    }
+
+    /// Start building a cutter that selects fields
+    pub fn fields(ranges: Vec<Range>) -> CutterBuilder {
+        CutterBuilder::new(Unit::Fields, ranges)
+    }
+
+    /// Cut every record read from `reader` and write the result to `writer`
+    pub fn cut<R: Read, W: Write>(&self, reader: R, writer: W) -> UResult<()> {
+        self.cut_input(BufReader::new(reader), "-", writer)
+    }
+
+    /// Like [`Cutter::cut`] for a reader that is already buffered. `input`
+    /// names the input in diagnostics.
+    pub fn cut_input<R: BufRead, W: Write>(
+        &self,
+        reader: R,
+        input: &str,
+        mut writer: W,
+    ) -> UResult<()> {
+        match self.mode {
+            Mode::Bytes(ref selection, ref opts) => cut_bytes(reader, &mut writer, selection, opts),
+            Mode::Characters(ref selection, ref opts) => {
+                cut_bytes(reader, &mut writer, selection, opts)
+            }
+            Mode::Fields(ref selection, ref opts) => {
+                cut_fields(reader, &mut writer, input, selection, opts)
+            }
+        }
+    }
+}
+
+enum Unit {
+    Bytes,
+    Characters,
+    Fields,
+}
+
+/// Configures a [`Cutter`].
+///
+/// The options that only apply to fields are ignored when cutting bytes or
+/// characters.
+pub struct CutterBuilder {
+    unit: Unit,
+    ranges: Vec<Range>,
+    delimiter: Delimiter,
+    output_delimiter: Option<String>,
+    terminator: u8,
+    only_delimited: bool,
+    no_split_multibyte: bool,
+    ordered: bool,
+    output_format: OutputFormat,
+    trim: bool,
+    empty_as: Option<String>,
+    strict: bool,
+    complement: bool,
+}
+
+impl CutterBuilder {
+    fn new(unit: Unit, ranges: Vec<Range>) -> Self {
+        Self {
+            unit,
+            ranges,
+            delimiter: Delimiter::String("\t".to_owned()),
+            output_delimiter: None,
+            terminator: b'\n',
+            only_delimited: false,
+            no_split_multibyte: false,
+            ordered: false,
+            output_format: OutputFormat::Text,
+            trim: false,
+            empty_as: None,
+            strict: false,
+            complement: false,
+        }
+    }
+
+    /// Separate fields by `delimiter` instead of a tab
+    pub fn delimiter(mut self, delimiter: Delimiter) -> Self {
+        self.delimiter = delimiter;
+        self
+    }
+
+    /// Join the selected parts with `delimiter` in the output
+    pub fn output_delimiter(mut self, delimiter: impl Into<String>) -> Self {
+        self.output_delimiter = Some(delimiter.into());
+        self
+    }
+
+    /// End records with `terminator` instead of a newline
+    pub fn terminator(mut self, terminator: u8) -> Self {
+        self.terminator = terminator;
+        self
+    }
+
+    /// Skip records that contain no delimiter
+    pub fn only_delimited(mut self, only_delimited: bool) -> Self {
+        self.only_delimited = only_delimited;
+        self
+    }
+
+    /// Do not split multibyte characters when cutting bytes
+    pub fn no_split_multibyte(mut self, no_split_multibyte: bool) -> Self {
+        self.no_split_multibyte = no_split_multibyte;
+        self
+    }
+
+    /// Print fields in the order of the ranges rather than the input order
+    pub fn ordered(mut self, ordered: bool) -> Self {
+        self.ordered = ordered;
+        self
+    }
+
+    /// Write the selected fields in `format`
+    pub fn output_format(mut self, format: OutputFormat) -> Self {
+        self.output_format = format;
+        self
+    }
+
+    /// Remove leading and trailing whitespace from every selected field
+    pub fn trim(mut self, trim: bool) -> Self {
+        self.trim = trim;
+        self
+    }
+
+    /// Print `placeholder` in place of empty fields
+    pub fn empty_as(mut self, placeholder: impl Into<String>) -> Self {
+        self.empty_as = Some(placeholder.into());
+        self
+    }
+
+    /// Report records that lack any of the selected fields as an error
+    pub fn strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    /// Select the parts that are not in the ranges instead
+    pub fn complement(mut self, complement: bool) -> Self {
+        self.complement = complement;
+        self
+    }
+
+    /// Check the configuration and create the [`Cutter`]
+    pub fn build(self) -> Result<Cutter, String> {
+        let selection = Selection {
+            ranges: self.ranges,
+            complement: self.complement,
+        };
+        let mode = match self.unit {
+            Unit::Bytes => Mode::Bytes(
+                selection,
+                Options {
+                    out_delim: self.output_delimiter,
+                    terminator: self.terminator,
+                    no_split_multibyte: self.no_split_multibyte,
+                },
+            ),
+            Unit::Characters => Mode::Characters(
+                selection,
+                Options {
+                    out_delim: self.output_delimiter,
+                    terminator: self.terminator,
+                    no_split_multibyte: false,
+                },
+            ),
+            Unit::Fields => {
+                match self.delimiter {
+                    Delimiter::String(ref delim) | Delimiter::Csv(ref delim)
+                        if delim.is_empty() =>
+                    {
+                        return Err("the delimiter must not be empty".into());
+                    }
+                    Delimiter::Csv(ref delim) if delim.contains('"') => {
+                        return Err("the CSV delimiter must not contain '\"'".into());
+                    }
+                    _ => {}
+                }
+                Mode::Fields(
+                    selection,
+                    FieldOptions {
+                        delimiter: self.delimiter,
+                        out_delimiter: self.output_delimiter,
+                        only_delimited: self.only_delimited,
+                        terminator: self.terminator,
+                        ordered: self.ordered,
+                        output_format: self.output_format,
+                        trim: self.trim,
+                        empty_as: self.empty_as,
+                        strict: self.strict,
+                    },
+                )
+            }
+        };
+        Ok(Cutter { mode })
+    }
+}
+
+struct Options {
+    out_delim: Option<String>,
+    terminator: u8,
+    no_split_multibyte: bool,
+}
+
+struct FieldOptions {
+    delimiter: Delimiter,
+    out_delimiter: Option<String>,
+    only_delimited: bool,
+    terminator: u8,
+    ordered: bool,
+    output_format: OutputFormat,
+    trim: bool,
+    empty_as: Option<String>,
+    strict: bool,
+}
+
+impl FieldOptions {
+    // Per-field transformations applied after a field was extracted
+    fn transform_field<'a>(&'a self, field: &'a [u8]) -> &'a [u8] {
+        let field = if self.trim { field.trim() } else { field };
+        match self.empty_as {
+            Some(ref placeholder) if field.is_empty() => placeholder.as_bytes(),
+            _ => field,
+        }
+    }
+}
+
+// The ranges to cut, or the parts between them with `complement`
+struct Selection {
+    ranges: Vec<Range>,
+    complement: bool,
+}
+
+impl Selection {
+    // The selected parts, as sorted and disjoint ranges
+    fn runs(&self) -> Runs<'_> {
+        Runs::new(&self.ranges, self.complement)
+    }
+}
+
+enum Mode {
+    Bytes(Selection, Options),
+    Characters(Selection, Options),
+    Fields(Selection, FieldOptions),
+}
+
+fn is_utf8_continuation(byte: u8) -> bool {
+    byte & 0b1100_0000 == 0b1000_0000
+}
+
+fn cut_bytes<R: BufRead, W: Write>(
+    mut reader: R,
+    out: &mut W,
+    selection: &Selection,
+    opts: &Options,
+) -> UResult<()> {
+    let newline_char = opts.terminator;
+    let delim = opts
+        .out_delim
+        .as_ref()
+        .map_or("", String::as_str)
+        .as_bytes();
+
+    let result = reader.for_byte_record(newline_char, |line| {
+        let mut print_delim = false;
+        for Range { low, high, .. } in selection.runs() {
+            if low > line.len() {
+                break;
+            }
+            // change `low` from 1-indexed value to 0-index value
+            let mut low = low - 1;
+            let mut high = high.min(line.len());
+            if opts.no_split_multibyte {
+                // widen the start and shrink the end to the nearest character boundaries
+                while low > 0 && is_utf8_continuation(line[low]) {
+                    low -= 1;
+                }
+                while high > 0 && high < line.len() && is_utf8_continuation(line[high]) {
+                    high -= 1;
+                }
+                if high <= low {
+                    continue;
+                }
+            }
+            if print_delim {
+                out.write_all(delim)?;
+            } else if opts.out_delim.is_some() {
+                print_delim = true;
+            }
+            out.write_all(&line[low..high])?;
+        }
+        out.write_all(&[newline_char])?;
+        Ok(true)
+    });
+
+    if let Err(e) = result {
+        return Err(USimpleError::new(1, e.to_string()));
+    }
+
+    Ok(())
+}
+
+// Output delimiter is explicitly specified
+fn cut_fields_explicit_out_delim<R: BufRead, W: Write, M: Matcher>(
+    mut reader: R,
+    out: &mut W,
+    matcher: &M,
+    selection: &Selection,
+    only_delimited: bool,
+    newline_char: u8,
+    out_delim: &str,
+) -> UResult<()> {
+    let result = reader.for_byte_record_with_terminator(newline_char, |line| {
+        let mut fields_pos = 1;
+        let mut low_idx = 0;
+        let mut delim_search = Searcher::new(matcher, line).peekable();
+        let mut print_delim = false;
+
+        if delim_search.peek().is_none() {
+            if !only_delimited {
+                out.write_all(line)?;
+                if line[line.len() - 1] != newline_char {
+                    out.write_all(&[newline_char])?;
+                }
+            }
+
+            return Ok(true);
+        }
+
+        for Range { low, high, .. } in selection.runs() {
+            if low - fields_pos > 0 {
+                // current field is not in the range, so jump to the field corresponding to the
+                // beginning of the range if any
+                low_idx = match delim_search.nth(low - fields_pos - 1) {
+                    Some((_, last)) => last,
+                    None => break,
+                };
+            }
+
+            // at this point, current field is the first in the range
+            for _ in 0..=high - low {
+                // skip printing delimiter if this is the first matching field for this line
+                if print_delim {
+                    out.write_all(out_delim.as_bytes())?;
+                } else {
+                    print_delim = true;
+                }
+
+                match delim_search.next() {
+                    // print the current field up to the next field delim
+                    Some((first, last)) => {
+                        let segment = &line[low_idx..first];
+
+                        out.write_all(segment)?;
+
+                        low_idx = last;
+                        fields_pos = high + 1;
+                    }
+                    None => {
+                        // this is the last field in the line, so print the rest
+                        let segment = &line[low_idx..];
+
+                        out.write_all(segment)?;
+
+                        if line[line.len() - 1] == newline_char {
+                            return Ok(true);
+                        }
+                        break;
+                    }
+                }
+            }
+        }
+
+        out.write_all(&[newline_char])?;
+        Ok(true)
+    });
+
+    if let Err(e) = result {
+        return Err(USimpleError::new(1, e.to_string()));
+    }
+
+    Ok(())
+}
+
+// Output delimiter is the same as input delimiter
+fn cut_fields_implicit_out_delim<R: BufRead, W: Write, M: Matcher>(
+    mut reader: R,
+    out: &mut W,
+    matcher: &M,
+    selection: &Selection,
+    only_delimited: bool,
+    newline_char: u8,
+) -> UResult<()> {
+    let result = reader.for_byte_record_with_terminator(newline_char, |line| {
+        let mut fields_pos = 1;
+        let mut low_idx = 0;
+        let mut delim_search = Searcher::new(matcher, line).peekable();
+        let mut print_delim = false;
+
+        if delim_search.peek().is_none() {
+            if !only_delimited {
+                out.write_all(line)?;
+                if line[line.len() - 1] != newline_char {
+                    out.write_all(&[newline_char])?;
+                }
+            }
+
+            return Ok(true);
+        }
+
+        for Range { low, high, .. } in selection.runs() {
+            if low - fields_pos > 0 {
+                if let Some((first, last)) = delim_search.nth(low - fields_pos - 1) {
+                    low_idx = if print_delim { first } else { last }
+                } else {
+                    break;
+                }
+            }
+
+            match delim_search.nth(high - low) {
+                Some((first, _)) => {
+                    let segment = &line[low_idx..first];
+
+                    out.write_all(segment)?;
+
+                    print_delim = true;
+                    low_idx = first;
+                    fields_pos = high + 1;
+                }
+                None => {
+                    let segment = &line[low_idx..line.len()];
+
+                    out.write_all(segment)?;
+
+                    if line[line.len() - 1] == newline_char {
+                        return Ok(true);
+                    }
+                    break;
+                }
+            }
+        }
+        out.write_all(&[newline_char])?;
+        Ok(true)
+    });
+
+    if let Err(e) = result {
+        return Err(USimpleError::new(1, e.to_string()));
+    }
+
+    Ok(())
+}
+
+// Remove the quotes around a CSV field and unescape the quotes inside it
+fn unquote_csv_field(field: &[u8]) -> Cow<'_, [u8]> {
+    match field
+        .strip_prefix(b"\"")
+        .and_then(|f| f.strip_suffix(b"\""))
+    {
+        Some(inner) => Cow::Owned(inner.replace("\"\"", "\"")),
+        None => Cow::Borrowed(field),
+    }
+}
+
+// Write a CSV field for the given output delimiter, quoting it only if needed
+fn write_csv_field<W: Write>(out: &mut W, field: &[u8], out_delim: &[u8]) -> std::io::Result<()> {
+    let field = unquote_csv_field(field);
+
+    let needs_quotes = memchr::memchr3(b'"', b'\n', b'\r', &field).is_some()
+        || (!out_delim.is_empty() && memchr::memmem::find(&field, out_delim).is_some());
+
+    if needs_quotes {
+        out.write_all(b"\"")?;
+        out.write_all(&field.replace("\"", "\"\""))?;
+        out.write_all(b"\"")
+    } else {
+        out.write_all(&field)
+    }
+}
+
+// Like `BufReadExt::for_byte_record`, but keeps reading past terminators that
+// appear inside a double-quoted CSV field. The terminator is not included.
+fn for_csv_record<R: BufRead, F>(
+    mut reader: R,
+    terminator: u8,
+    mut for_each: F,
+) -> std::io::Result<()>
+where
+    F: FnMut(&[u8]) -> std::io::Result<bool>,
+{
+    let mut record = Vec::new();
+    loop {
+        record.clear();
+        let mut in_quotes = false;
+        loop {
+            let start = record.len();
+            if reader.read_until(terminator, &mut record)? == 0 {
+                break;
+            }
+            in_quotes ^= memchr::memchr_iter(b'"', &record[start..]).count() % 2 == 1;
+            if !in_quotes {
+                break;
+            }
+        }
+        if record.is_empty() {
+            return Ok(());
+        }
+        let line = record.strip_suffix(&[terminator]).unwrap_or(&record);
+        if !for_each(line)? {
+            return Ok(());
+        }
+    }
+}
+
+// Write a field as a JSON string. Invalid UTF-8 is replaced with U+FFFD.
+fn write_json_string<W: Write>(out: &mut W, field: &[u8]) -> std::io::Result<()> {
+    out.write_all(b"\"")?;
+    for c in String::from_utf8_lossy(field).chars() {
+        match c {
+            '"' => out.write_all(b"\\\"")?,
+            '\\' => out.write_all(b"\\\\")?,
+            '\n' => out.write_all(b"\\n")?,
+            '\r' => out.write_all(b"\\r")?,
+            '\t' => out.write_all(b"\\t")?,
+            c if c.is_control() => write!(out, "\\u{:04x}", c as u32)?,
+            c => write!(out, "{}", c)?,
+        }
+    }
+    out.write_all(b"\"")
+}
+
+// Split the record into fields up front and print the selected ones in the order
+// given by the ranges with `ordered`, or else in the input order, in the requested
+// output format
+fn write_fields_in_order<W: Write, M: Matcher>(
+    out: &mut W,
+    matcher: &M,
+    line: &[u8],
+    selection: &Selection,
+    opts: &FieldOptions,
+) -> std::io::Result<Option<usize>> {
+    let newline_char = opts.terminator;
+    let is_csv = matches!(opts.delimiter, Delimiter::Csv(_));
+    let delims: Vec<(usize, usize)> = Searcher::new(matcher, line).collect();
+
+    if delims.is_empty() {
+        if !opts.only_delimited {
+            match opts.output_format {
+                OutputFormat::Text => out.write_all(line)?,
+                OutputFormat::Json => {
+                    out.write_all(b"[")?;
+                    write_json_string(out, line)?;
+                    out.write_all(b"]")?;
+                }
+            }
+            out.write_all(&[newline_char])?;
+            return Ok(Some(1));
+        }
+        return Ok(None);
+    }
+
+    // Without an output delimiter, fields are joined with the first delimiter
+    // found in the record
+    let out_delim = match (&opts.out_delimiter, &opts.delimiter) {
+        (Some(out_delim), _) => out_delim.as_bytes(),
+        (None, Delimiter::Whitespace) => b"\t",
+        (None, Delimiter::Csv(delim)) => delim.as_bytes(),
+        (None, _) => &line[delims[0].0..delims[0].1],
+    };
+    let (separator, prefix, suffix): (&[u8], &[u8], &[u8]) = match opts.output_format {
+        OutputFormat::Text => (out_delim, b"", b""),
+        OutputFormat::Json => (b",", b"[", b"]"),
+    };
+
+    let field_count = delims.len() + 1;
+    let fields: Box<dyn Iterator<Item = usize>> = if opts.ordered {
+        Box::new(
+            selection
+                .ranges
+                .iter()
+                .flat_map(|r| (r.low..=r.high.min(field_count)).step_by(r.step)),
+        )
+    } else {
+        Box::new(
+            selection
+                .runs()
+                .take_while(|r| r.low <= field_count)
+                .flat_map(|r| r.low..=r.high.min(field_count)),
+        )
+    };
+    let mut print_delim = false;
+    out.write_all(prefix)?;
+    for field in fields {
+        let first = if field == 1 { 0 } else { delims[field - 2].1 };
+        let last = if field == field_count {
+            line.len()
+        } else {
+            delims[field - 1].0
+        };
+
+        if print_delim {
+            out.write_all(separator)?;
+        } else {
+            print_delim = true;
+        }
+
+        let field = opts.transform_field(&line[first..last]);
+        match opts.output_format {
+            OutputFormat::Json if is_csv => write_json_string(out, &unquote_csv_field(field))?,
+            OutputFormat::Json => write_json_string(out, field)?,
+            OutputFormat::Text if is_csv && opts.out_delimiter.is_some() => {
+                write_csv_field(out, field, out_delim)?;
+            }
+            OutputFormat::Text => out.write_all(field)?,
+        }
+    }
+    out.write_all(suffix)?;
+    out.write_all(&[newline_char])?;
+    Ok(Some(field_count))
+}
+
+// Reports records that lack one of the requested fields, for --strict
+struct StrictCheck<'a> {
+    input: &'a str,
+    line: usize,
+    failures: usize,
+}
+
+impl<'a> StrictCheck<'a> {
+    fn new(input: &'a str) -> Self {
+        Self {
+            input,
+            line: 0,
+            failures: 0,
+        }
+    }
+
+    // The record starts on the next line and spans `line_count` lines.
+    // `field_count` is `None` for records that were not printed.
+    fn check(&mut self, selection: &Selection, field_count: Option<usize>, line_count: usize) {
+        let line = self.line + 1;
+        self.line += line_count;
+
+        let field_count = match field_count {
+            Some(n) => n,
+            None => return,
+        };
+        // The first selected field after the last one, unless the range it is
+        // in has no upper bound and started within the record
+        let first_missing = |&Range { low, high, step }: &Range| {
+            if low > field_count {
+                Some(low)
+            } else if high != usize::MAX - 1 && high > field_count {
+                Some(low + ((field_count - low) / step + 1) * step)
+            } else {
+                None
+            }
+        };
+        let missing = if selection.complement {
+            selection
+                .runs()
+                .find(|r| r.high > field_count)
+                .and_then(|r| first_missing(&r))
+        } else {
+            selection.ranges.iter().filter_map(first_missing).min()
+        };
+
+        if let Some(field) = missing {
+            self.failures += 1;
+            show_error!(
+                "{}:{}: missing field {} (found {})",
+                self.input,
+                line,
+                field,
+                field_count
+            );
+        }
+    }
+
+    fn finish(self) -> UResult<()> {
+        match self.failures {
+            0 => Ok(()),
+            1 => Err(USimpleError::new(
+                1,
+                format!("{}: 1 line with missing fields", self.input),
+            )),
+            n => Err(USimpleError::new(
+                1,
+                format!("{}: {} lines with missing fields", self.input, n),
+            )),
+        }
+    }
+}
+
+// Fields are buffered per record, so that they can be printed in the order they
+// are given in the list, which may repeat or go back to earlier fields, or in a
+// structured output format
+fn cut_fields_buffered<R: BufRead, W: Write, M: Matcher>(
+    mut reader: R,
+    out: &mut W,
+    input: &str,
+    matcher: &M,
+    selection: &Selection,
+    opts: &FieldOptions,
+) -> UResult<()> {
+    let newline_char = opts.terminator;
+    let mut strict = StrictCheck::new(input);
+
+    let result = reader.for_byte_record(newline_char, |line| {
+        let field_count = write_fields_in_order(out, matcher, line, selection, opts)?;
+        if opts.strict {
+            strict.check(selection, field_count, 1);
+        }
+        Ok(true)
+    });
+
+    if let Err(e) = result {
+        return Err(USimpleError::new(1, e.to_string()));
+    }
+
+    strict.finish()
+}
+
+// Fields are quote-aware, and records may span several lines if a quoted field
+// contains the line terminator
+fn cut_fields_csv<R: BufRead, W: Write>(
+    reader: R,
+    out: &mut W,
+    input: &str,
+    delim: &str,
+    selection: &Selection,
+    opts: &FieldOptions,
+) -> UResult<()> {
+    let newline_char = opts.terminator;
+    let matcher = CsvMatcher::new(delim.as_bytes());
+    let mut strict = StrictCheck::new(input);
+
+    let result = for_csv_record(reader, newline_char, |line| {
+        let field_count = write_fields_in_order(out, &matcher, line, selection, opts)?;
+        if opts.strict {
+            let line_count = 1 + memchr::memchr_iter(newline_char, line).count();
+            strict.check(selection, field_count, line_count);
+        }
+        Ok(true)
+    });
+
+    if let Err(e) = result {
+        return Err(USimpleError::new(1, e.to_string()));
+    }
+
+    strict.finish()
+}
+
+fn cut_fields<R: BufRead, W: Write>(
+    reader: R,
+    out: &mut W,
+    input: &str,
+    selection: &Selection,
+    opts: &FieldOptions,
+) -> UResult<()> {
+    let newline_char = opts.terminator;
+    let buffered = opts.ordered
+        || opts.output_format != OutputFormat::Text
+        || opts.trim
+        || opts.empty_as.is_some()
+        || opts.strict;
+    match opts.delimiter {
+        Delimiter::String(ref delim) if buffered => cut_fields_buffered(
+            reader,
+            out,
+            input,
+            &ExactMatcher::new(delim.as_bytes()),
+            selection,
+            opts,
+        ),
+        Delimiter::Regex(ref regex) if buffered => cut_fields_buffered(
+            reader,
+            out,
+            input,
+            &RegexMatcher::new(regex),
+            selection,
+            opts,
+        ),
+        Delimiter::Whitespace if buffered => {
+            cut_fields_buffered(reader, out, input, &WhitespaceMatcher {}, selection, opts)
+        }
+        Delimiter::String(ref delim) => {
+            let matcher = ExactMatcher::new(delim.as_bytes());
+            match opts.out_delimiter {
+                Some(ref out_delim) => cut_fields_explicit_out_delim(
+                    reader,
+                    out,
+                    &matcher,
+                    selection,
+                    opts.only_delimited,
+                    newline_char,
+                    out_delim,
+                ),
+                None => cut_fields_implicit_out_delim(
+                    reader,
+                    out,
+                    &matcher,
+                    selection,
+                    opts.only_delimited,
+                    newline_char,
+                ),
+            }
+        }
+        Delimiter::Regex(ref regex) => {
+            let matcher = RegexMatcher::new(regex);
+            match opts.out_delimiter {
+                Some(ref out_delim) => cut_fields_explicit_out_delim(
+                    reader,
+                    out,
+                    &matcher,
+                    selection,
+                    opts.only_delimited,
+                    newline_char,
+                    out_delim,
+                ),
+                None => cut_fields_implicit_out_delim(
+                    reader,
+                    out,
+                    &matcher,
+                    selection,
+                    opts.only_delimited,
+                    newline_char,
+                ),
+            }
+        }
+        Delimiter::Csv(ref delim) => cut_fields_csv(reader, out, input, delim, selection, opts),
+        Delimiter::Whitespace => {
+            let matcher = WhitespaceMatcher {};
+            let out_delim = opts.out_delimiter.as_deref().unwrap_or("\t");
+            cut_fields_explicit_out_delim(
+                reader,
+                out,
+                &matcher,
+                selection,
+                opts.only_delimited,
+                newline_char,
+                out_delim,
+            )
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cut(cutter: &Cutter, input: &[u8]) -> Vec<u8> {
+        let mut output = Vec::new();
+        cutter.cut(input, &mut output).unwrap();
+        output
+    }
+
+    #[test]
+    fn test_bytes() {
+        let cutter = Cutter::bytes(Range::from_list("2-3").unwrap())
+            .build()
+            .unwrap();
+        assert_eq!(
+            cut(
+                &cutter,
+                b"abcd
+efgh
+"
+            ),
+            b"bc
+fg
+"
+        );
+    }
+
+    #[test]
+    fn test_fields_with_output_delimiter_and_terminator() {
+        let cutter = Cutter::fields(Range::from_list("1,3").unwrap())
+            .delimiter(Delimiter::String(":".to_owned()))
+            .output_delimiter("-")
+            .terminator(b'\0')
+            .build()
+            .unwrap();
+        assert_eq!(cut(&cutter, b"a:b:c\0d:e:f"), b"a-c\0d-f\0");
+    }
+
+    #[test]
+    fn test_fields_ordered_json() {
+        let cutter = Cutter::fields(Range::from_list_unordered("2,1").unwrap())
+            .delimiter(Delimiter::Whitespace)
+            .ordered(true)
+            .output_format(OutputFormat::Json)
+            .build()
+            .unwrap();
+        assert_eq!(cut(&cutter, b"a  b\n"), b"[\"b\",\"a\"]\n");
+    }
+
+    #[test]
+    fn test_fields_csv() {
+        let cutter = Cutter::fields(Range::from_list("2").unwrap())
+            .delimiter(Delimiter::Csv(",".to_owned()))
+            .build()
+            .unwrap();
+        assert_eq!(cut(&cutter, b"a,\"b,c\",d\n"), b"\"b,c\"\n");
+    }
+
+    #[test]
+    fn test_build_rejects_invalid_delimiters() {
+        let ranges = || Range::from_list("1").unwrap();
+        assert!(Cutter::fields(ranges())
+            .delimiter(Delimiter::String(String::new()))
+            .build()
+            .is_err());
+        assert!(Cutter::fields(ranges())
+            .delimiter(Delimiter::Csv("\"".to_owned()))
+            .build()
+            .is_err());
+    }
+}
@@ -4,11 +4,19 @@
 // file that was distributed with this source code.
 
 use memchr::{memchr, memchr2};
+use regex::bytes::Regex;
 
 // Find the next matching byte sequence positions
 // Return (first, last) where haystack[first..last] corresponds to the matched pattern
 pub trait Matcher {
     fn next_match(&self, haystack: &[u8]) -> Option<(usize, usize)>;
+
+    // Like `next_match` on `haystack[start..]`, with positions in `haystack`, for matchers
+    // that depend on what comes before `start`
+    fn next_match_from(&self, haystack: &[u8], start: usize) -> Option<(usize, usize)> {
+        self.next_match(&haystack[start..])
+            .map(|(first, last)| (first + start, last + start))
+    }
 }
 
 // Matches for the exact byte sequence pattern
@@ -67,6 +75,69 @@ impl Matcher for WhitespaceMatcher {
     }
 }
 
+// Matches for a regular expression, ignoring empty matches
+pub struct RegexMatcher<'a> {
+    regex: &'a Regex,
+}
+
+impl<'a> RegexMatcher<'a> {
+    pub fn new(regex: &'a Regex) -> Self {
+        Self { regex }
+    }
+}
+
+impl<'a> Matcher for RegexMatcher<'a> {
+    fn next_match(&self, haystack: &[u8]) -> Option<(usize, usize)> {
+        self.next_match_from(haystack, 0)
+    }
+
+    // Search the whole record, so that anchors like `^` and `\b` only match where they do
+    // in the record, not at the start of every field
+    fn next_match_from(&self, haystack: &[u8], start: usize) -> Option<(usize, usize)> {
+        let mut pos = start;
+        while pos <= haystack.len() {
+            let m = self.regex.find_at(haystack, pos)?;
+            if !m.range().is_empty() {
+                return Some((m.start(), m.end()));
+            }
+            pos = m.end() + 1;
+        }
+        None
+    }
+}
+
+// Matches for the exact byte sequence pattern, ignoring occurrences inside
+// double-quoted CSV fields. The haystack is expected to start at a field
+// boundary, which is what `Searcher` provides.
+pub struct CsvMatcher<'a> {
+    needle: &'a [u8],
+}
+
+impl<'a> CsvMatcher<'a> {
+    pub fn new(needle: &'a [u8]) -> Self {
+        assert!(!needle.is_empty());
+        assert!(!needle.contains(&b'"'));
+        Self { needle }
+    }
+}
+
+impl<'a> Matcher for CsvMatcher<'a> {
+    fn next_match(&self, haystack: &[u8]) -> Option<(usize, usize)> {
+        let mut in_quotes = false;
+        let mut pos = 0usize;
+        while let Some(match_idx) = memchr2(b'"', self.needle[0], &haystack[pos..]) {
+            let match_idx = match_idx + pos; // account for starting from pos
+            if haystack[match_idx] == b'"' {
+                in_quotes = !in_quotes;
+            } else if !in_quotes && haystack[match_idx..].starts_with(self.needle) {
+                return Some((match_idx, match_idx + self.needle.len()));
+            }
+            pos = match_idx + 1;
+        }
+        None
+    }
+}
+
 #[cfg(test)]
 mod matcher_tests {
 
@@ -123,4 +194,42 @@ mod matcher_tests {
         assert_eq!(matcher.next_match("abcxyz".as_bytes()), None);
         // spell-checker:enable
     }
+
+    #[test]
+    fn test_regex_matcher() {
+        let regex = Regex::new("[:;]+").unwrap();
+        let matcher = RegexMatcher::new(&regex);
+        // spell-checker:disable
+        assert_eq!(matcher.next_match("".as_bytes()), None);
+        assert_eq!(matcher.next_match(":".as_bytes()), Some((0, 1)));
+        assert_eq!(matcher.next_match("abc;:xyz".as_bytes()), Some((3, 5)));
+        assert_eq!(matcher.next_match("abcxyz::".as_bytes()), Some((6, 8)));
+        assert_eq!(matcher.next_match("abcxyz".as_bytes()), None);
+        // spell-checker:enable
+    }
+
+    #[test]
+    fn test_regex_matcher_skips_empty_matches() {
+        let regex = Regex::new(",*").unwrap();
+        let matcher = RegexMatcher::new(&regex);
+        assert_eq!(matcher.next_match("".as_bytes()), None);
+        assert_eq!(matcher.next_match("ab,,c".as_bytes()), Some((2, 4)));
+        assert_eq!(matcher.next_match("abc".as_bytes()), None);
+    }
+
+    #[test]
+    fn test_csv_matcher() {
+        let matcher = CsvMatcher::new(",".as_bytes());
+        // spell-checker:disable
+        assert_eq!(matcher.next_match("".as_bytes()), None);
+        assert_eq!(matcher.next_match("abc,xyz".as_bytes()), Some((3, 4)));
+        assert_eq!(matcher.next_match("\"a,b\",xyz".as_bytes()), Some((5, 6)));
+        assert_eq!(
+            matcher.next_match("\"a\"\",b\",xyz".as_bytes()),
+            Some((7, 8))
+        );
+        assert_eq!(matcher.next_match("\"a,b\"".as_bytes()), None);
+        assert_eq!(matcher.next_match("\"a\nb,c".as_bytes()), None);
+        // spell-checker:enable
+    }
 }
@@ -33,11 +33,10 @@ impl<'a, 'b, M: Matcher> Iterator for Searcher<'a, 'b, M> {
     type Item = (usize, usize);
 
     fn next(&mut self) -> Option<Self::Item> {
-        match self.matcher.next_match(&self.haystack[self.position..]) {
+        match self.matcher.next_match_from(self.haystack, self.position) {
             Some((first, last)) => {
-                let result = (first + self.position, last + self.position);
-                self.position += last;
-                Some(result)
+                self.position = last;
+                Some((first, last))
             }
             None => None,
         }
@@ -183,3 +182,26 @@ mod whitespace_searcher_tests {
         assert_eq!(searcher.next(), None);
     }
 }
+
+#[cfg(test)]
+mod csv_searcher_tests {
+
+    use super::super::matcher::CsvMatcher;
+    use super::*;
+
+    #[test]
+    fn test_quoted_delimiters_are_skipped() {
+        let matcher = CsvMatcher::new(",".as_bytes());
+        let iter = Searcher::new(&matcher, "a,\"b,c\",d,\"\"\"e,\"".as_bytes());
+        let items: Vec<(usize, usize)> = iter.collect();
+        assert_eq!(vec![(1, 2), (7, 8), (9, 10)], items);
+    }
+
+    #[test]
+    fn test_empty() {
+        let matcher = CsvMatcher::new(",".as_bytes());
+        let iter = Searcher::new(&matcher, "".as_bytes());
+        let items: Vec<(usize, usize)> = iter.collect();
+        assert_eq!(vec![] as Vec<(usize, usize)>, items);
+    }
+}
@@ -9,20 +9,19 @@
 // spell-checker:ignore (chrono) Datelike Timelike ; (format) DATEFILE MMDDhhmm ; (vars) datetime datetimes
 
 use chrono::format::{Item, StrftimeItems};
-use chrono::{DateTime, FixedOffset, Local, Offset, Utc};
+use chrono::{DateTime, FixedOffset, Local, Offset, TimeZone, Utc};
 #[cfg(windows)]
 use chrono::{Datelike, Timelike};
-use clap::{crate_version, Arg, ArgAction, Command};
+use clap::{crate_version, parser::ValueSource, Arg, ArgAction, Command};
 #[cfg(all(unix, not(target_os = "macos"), not(target_os = "redox")))]
 use libc::{clock_settime, timespec, CLOCK_REALTIME};
 use std::fs::File;
 use std::io::{BufRead, BufReader};
 use std::path::PathBuf;
 use uucore::display::Quotable;
-#[cfg(not(any(target_os = "macos", target_os = "redox")))]
-use uucore::error::FromIo;
-use uucore::error::{UResult, USimpleError};
-use uucore::{format_usage, show_error};
+use uucore::error::{FromIo, UResult, USimpleError, UUsageError};
+use uucore::parse_datetime::{parse_datetime, ParseDateTimeError};
+use uucore::{format_usage, show};
 #[cfg(windows)]
 use windows_sys::Win32::{Foundation::SYSTEMTIME, System::SystemInformation::SetSystemTime};
 
@@ -31,9 +30,6 @@ const DATE: &str = "date";
 const HOURS: &str = "hours";
 const MINUTES: &str = "minutes";
 const SECONDS: &str = "seconds";
-const HOUR: &str = "hour";
-const MINUTE: &str = "minute";
-const SECOND: &str = "second";
 const NS: &str = "ns";
 
 const ABOUT: &str = "print or set the system date and time";
@@ -100,6 +96,7 @@ enum DateSource {
     File(PathBuf),
 }
 
+#[derive(Clone, Copy)]
 enum Iso8601Format {
     Date,
     Hours,
@@ -108,42 +105,69 @@ enum Iso8601Format {
     Ns,
 }
 
-impl<'a> From<&'a str> for Iso8601Format {
-    fn from(s: &str) -> Self {
-        match s {
-            HOURS | HOUR => Self::Hours,
-            MINUTES | MINUTE => Self::Minutes,
-            SECONDS | SECOND => Self::Seconds,
-            NS => Self::Ns,
-            DATE => Self::Date,
-            // Should be caught by clap
-            _ => panic!("Invalid format: {s}"),
-        }
-    }
-}
+const ISO_8601_WORDS: [(&str, Iso8601Format); 5] = [
+    (HOURS, Iso8601Format::Hours),
+    (MINUTES, Iso8601Format::Minutes),
+    (DATE, Iso8601Format::Date),
+    (SECONDS, Iso8601Format::Seconds),
+    (NS, Iso8601Format::Ns),
+];
 
+#[derive(Clone, Copy)]
 enum Rfc3339Format {
     Date,
     Seconds,
     Ns,
 }
 
-impl<'a> From<&'a str> for Rfc3339Format {
-    fn from(s: &str) -> Self {
-        match s {
-            DATE => Self::Date,
-            SECONDS | SECOND => Self::Seconds,
-            NS => Self::Ns,
-            // Should be caught by clap
-            _ => panic!("Invalid format: {s}"),
-        }
-    }
+const RFC_3339_WORDS: [(&str, Rfc3339Format); 3] = [
+    (DATE, Rfc3339Format::Date),
+    (SECONDS, Rfc3339Format::Seconds),
+    (NS, Rfc3339Format::Ns),
+];
+
+/// Get the precision `s` names for the `--iso-8601` or `--rfc-3339` option,
+/// which may abbreviate it.
+fn parse_precision<T: Copy>(s: &str, option: &str, words: &[(&str, T)]) -> UResult<T> {
+    // The words all start with different letters, so only the empty string
+    // is ambiguous.
+    let kind = match words.iter().find(|(word, _)| word.starts_with(s)) {
+        Some((_, precision)) if !s.is_empty() => return Ok(*precision),
+        Some(_) => "ambiguous",
+        None => "invalid",
+    };
+    let valid: String = words
+        .iter()
+        .map(|(word, _)| format!("\n  - '{word}'"))
+        .collect();
+    Err(USimpleError::new(
+        1,
+        format!(
+            "{kind} argument {} for '--{option}'\nValid arguments are:{valid}\nTry '{} --help' for more information.",
+            s.quote(),
+            uucore::execution_phrase()
+        ),
+    ))
 }
 
 #[uucore::main]
 pub fn uumain(args: impl uucore::Args) -> UResult<()> {
     let matches = uu_app().try_get_matches_from(args)?;
 
+    let utc = matches.get_flag(OPT_UNIVERSAL);
+    if utc {
+        // Like GNU, treat the dates we're given as UTC times as well.
+        std::env::set_var("TZ", "UTC0");
+    }
+
+    let formats_given = [OPT_FORMAT, OPT_ISO_8601, OPT_RFC_EMAIL, OPT_RFC_3339]
+        .iter()
+        .filter(|opt| matches.value_source(opt) == Some(ValueSource::CommandLine))
+        .count();
+    if formats_given > 1 {
+        return Err(USimpleError::new(1, "multiple output formats specified"));
+    }
+
     let format = if let Some(form) = matches.get_one::<String>(OPT_FORMAT) {
         if !form.starts_with('+') {
             return Err(USimpleError::new(
@@ -153,22 +177,22 @@ pub fn uumain(args: impl uucore::Args) -> UResult<()> {
         }
         let form = form[1..].to_string();
         Format::Custom(form)
-    } else if let Some(fmt) = matches
-        .get_many::<String>(OPT_ISO_8601)
-        .map(|mut iter| iter.next().unwrap_or(&DATE.to_string()).as_str().into())
-    {
-        Format::Iso8601(fmt)
+    } else if let Some(fmt) = matches.get_one::<String>(OPT_ISO_8601) {
+        Format::Iso8601(parse_precision(fmt, OPT_ISO_8601, &ISO_8601_WORDS)?)
     } else if matches.get_flag(OPT_RFC_EMAIL) {
         Format::Rfc5322
-    } else if let Some(fmt) = matches
-        .get_one::<String>(OPT_RFC_3339)
-        .map(|s| s.as_str().into())
-    {
-        Format::Rfc3339(fmt)
+    } else if let Some(fmt) = matches.get_one::<String>(OPT_RFC_3339) {
+        Format::Rfc3339(parse_precision(fmt, OPT_RFC_3339, &RFC_3339_WORDS)?)
     } else {
         Format::Default
     };
 
+    if matches.contains_id(OPT_DATE) && matches.contains_id(OPT_FILE) {
+        return Err(UUsageError::new(
+            1,
+            "the options to specify dates for printing are mutually exclusive",
+        ));
+    }
     let date_source = if let Some(date) = matches.get_one::<String>(OPT_DATE) {
         DateSource::Custom(date.into())
     } else if let Some(file) = matches.get_one::<String>(OPT_FILE) {
@@ -189,7 +213,7 @@ pub fn uumain(args: impl uucore::Args) -> UResult<()> {
     };
 
     let settings = Settings {
-        utc: matches.get_flag(OPT_UNIVERSAL),
+        utc,
         format,
         date_source,
         set_to,
@@ -205,9 +229,6 @@ pub fn uumain(args: impl uucore::Args) -> UResult<()> {
 
         return set_system_datetime(date);
     } else {
-        // Declare a file here because it needs to outlive the `dates` iterator.
-        let file: File;
-
         // Get the current time, either in the local time zone or UTC.
         let now: DateTime<FixedOffset> = if settings.utc {
             let now = Utc::now();
@@ -225,9 +246,14 @@ pub fn uumain(args: impl uucore::Args) -> UResult<()> {
                 Box::new(iter)
             }
             DateSource::File(ref path) => {
-                file = File::open(path).unwrap();
-                let lines = BufReader::new(file).lines();
-                let iter = lines.filter_map(Result::ok).map(parse_date);
+                let reader: Box<dyn BufRead> = if path.as_os_str() == "-" {
+                    Box::new(BufReader::new(std::io::stdin()))
+                } else {
+                    let file =
+                        File::open(path).map_err_context(|| path.maybe_quote().to_string())?;
+                    Box::new(BufReader::new(file))
+                };
+                let iter = reader.lines().map_while(Result::ok).map(parse_date);
                 Box::new(iter)
             }
             DateSource::Now => {
@@ -259,7 +285,10 @@ pub fn uumain(args: impl uucore::Args) -> UResult<()> {
                         .replace("%f", "%N");
                     println!("{formatted}");
                 }
-                Err((input, _err)) => show_error!("invalid date {}", input.quote()),
+                Err((input, _err)) => show!(USimpleError::new(
+                    1,
+                    format!("invalid date {}", input.quote())
+                )),
             }
         }
     }
@@ -277,6 +306,7 @@ pub fn uu_app() -> Command {
             Arg::new(OPT_DATE)
                 .short('d')
                 .long(OPT_DATE)
+                .allow_hyphen_values(true)
                 .value_name("STRING")
                 .help("display time described by STRING, not 'now'"),
         )
@@ -293,6 +323,8 @@ pub fn uu_app() -> Command {
                 .short('I')
                 .long(OPT_ISO_8601)
                 .value_name("FMT")
+                .num_args(0..=1)
+                .default_missing_value(DATE)
                 .help(ISO_8601_HELP_STRING),
         )
         .arg(
@@ -361,13 +393,20 @@ fn make_format_string(settings: &Settings) -> &str {
     }
 }
 
-/// Parse a `String` into a `DateTime`.
-/// If it fails, return a tuple of the `String` along with its `ParseError`.
+/// Parse a `String` into a `DateTime`, the way GNU `date --date` does.
+/// If it fails, return a tuple of the `String` along with its `ParseDateTimeError`.
 fn parse_date<S: AsRef<str> + Clone>(
     s: S,
-) -> Result<DateTime<FixedOffset>, (String, chrono::format::ParseError)> {
-    // TODO: The GNU date command can parse a wide variety of inputs.
-    s.as_ref().parse().map_err(|e| (s.as_ref().into(), e))
+) -> Result<DateTime<FixedOffset>, (String, ParseDateTimeError)> {
+    let err = || (s.as_ref().into(), ParseDateTimeError);
+    let date = parse_datetime(s.as_ref()).map_err(|_| err())?;
+    FixedOffset::east_opt(date.offset().whole_seconds())
+        .and_then(|offset| {
+            offset
+                .timestamp_opt(date.unix_timestamp(), date.nanosecond())
+                .single()
+        })
+        .ok_or_else(err)
 }
 
 #[cfg(not(any(unix, windows)))]
@@ -3,144 +3,216 @@
 //  * For the full copyright and license information, please view the LICENSE
 //  * file that was distributed with this source code.
 
-// spell-checker:ignore datastructures rstat rposition cflags ctable
+// spell-checker:ignore datastructures iconv swab
 
-use crate::conversion_tables::ConversionTable;
-use crate::datastructures::ConversionMode;
-use crate::progress::ReadStat;
+use crate::datastructures::{Block, IConvFlags};
 
-const NEWLINE: u8 = b'\n';
-const SPACE: u8 = b' ';
-
-/// Split a slice into chunks, padding or truncating as necessary.
-///
-/// The slice `buf` is split on newlines, then each block is resized
-/// to `cbs` bytes, padding with spaces if necessary. This function
-/// expects the input bytes to be ASCII-encoded.
+/// Apply the conversions requested with `conv=...` to the input.
 ///
-/// If `sync` is true and there has been at least one partial record
-/// read from the input (as indicated in `rstat`), then leave an
-/// all-spaces block at the end. Otherwise, remove the last block if
-/// it is all spaces.
-fn block(buf: &[u8], cbs: usize, sync: bool, rstat: &mut ReadStat) -> Vec<Vec<u8>> {
-    let mut blocks = buf
-        .split(|&e| e == NEWLINE)
-        .map(|split| split.to_vec())
-        .fold(Vec::new(), |mut blocks, mut split| {
-            if split.len() > cbs {
-                rstat.records_truncated += 1;
-            }
-            split.resize(cbs, SPACE);
-            blocks.push(split);
-
-            blocks
-        });
-
-    // If `sync` is true and there has been at least one partial
-    // record read from the input, then leave the all-spaces block at
-    // the end. Otherwise, remove it.
-    if let Some(last) = blocks.last() {
-        if (!sync || rstat.reads_partial == 0) && last.iter().all(|&e| e == SPACE) {
-            blocks.pop();
+/// The translation table is applied first, then bytes are swapped in
+/// pairs, then lines are blocked into records or records are unblocked
+/// into lines. Swapping, blocking, and unblocking work on the input as
+/// a stream: a pair of bytes or a record may be split across several
+/// reads, so the state they need is kept here between calls to
+/// [`Converter::convert`].
+pub(crate) struct Converter<'a> {
+    iconv: &'a IConvFlags,
+
+    /// The odd byte at the end of the data converted so far, with
+    /// `conv=swab`, waiting to be swapped with the next byte.
+    saved_byte: Option<u8>,
+
+    /// The position in the current line (`conv=block`) or record
+    /// (`conv=unblock`).
+    col: usize,
+
+    /// The number of spaces in the current record that have not been
+    /// written yet, with `conv=unblock`. They are dropped if they turn
+    /// out to be trailing spaces.
+    pending_spaces: usize,
+}
+
+impl<'a> Converter<'a> {
+    pub(crate) fn new(iconv: &'a IConvFlags) -> Self {
+        Self {
+            iconv,
+            saved_byte: None,
+            col: 0,
+            pending_spaces: 0,
         }
     }
 
-    blocks
-}
-
-/// Trims padding from each cbs-length partition of buf
-/// as specified by conv=unblock and cbs=N
-/// Expects ascii encoded data
-fn unblock(buf: &[u8], cbs: usize) -> Vec<u8> {
-    buf.chunks(cbs).fold(Vec::new(), |mut acc, block| {
-        if let Some(last_char_idx) = block.iter().rposition(|&e| e != SPACE) {
-            // Include text up to last space.
-            acc.extend(&block[..=last_char_idx]);
+    /// Convert the bytes in `buf`, which follow those of the previous calls.
+    ///
+    /// Returns the number of records that were truncated.
+    pub(crate) fn convert(&mut self, buf: &mut Vec<u8>) -> u32 {
+        if let Some(ctable) = &self.iconv.ctable {
+            for b in buf.iter_mut() {
+                *b = ctable[*b as usize];
+            }
+        }
+        if self.iconv.swab {
+            self.swab(buf);
+        }
+        match &self.iconv.block {
+            Some(block) => {
+                let input = std::mem::take(buf);
+                self.block_or_unblock(block, &input, buf)
+            }
+            None => 0,
         }
+    }
 
-        acc.push(NEWLINE);
-        acc
-    })
-}
+    /// Append the bytes held back at the end of the input to `buf`.
+    ///
+    /// Returns the number of records that were truncated.
+    pub(crate) fn finish(&mut self, buf: &mut Vec<u8>) -> u32 {
+        // The last odd byte is not translated a second time.
+        let saved_byte: Vec<u8> = self.saved_byte.take().into_iter().collect();
+        let block = match &self.iconv.block {
+            Some(block) => block,
+            None => {
+                buf.extend(saved_byte);
+                return 0;
+            }
+        };
+        let truncated = self.block_or_unblock(block, &saved_byte, buf);
+
+        // Complete the last line or record, even if the input does not.
+        if self.col > 0 {
+            if block.unblock {
+                buf.push(block.newline);
+            } else if self.col < block.cbs {
+                buf.resize(buf.len() + block.cbs - self.col, block.space);
+            }
+            self.col = 0;
+        }
+        truncated
+    }
 
-/// Apply the specified conversion, blocking, and/or unblocking in the right order.
-///
-/// The `mode` specifies the combination of conversion, blocking, and
-/// unblocking to apply and the order in which to apply it. This
-/// function is responsible only for applying the operations.
-///
-/// `buf` is the buffer of input bytes to transform. This function
-/// mutates this input and also returns a new buffer of bytes
-/// representing the result of the transformation.
-///
-/// `rstat` maintains a running total of the number of partial and
-/// complete blocks read before calling this function. In certain
-/// settings of `mode`, this function will update the number of
-/// records truncated; that's why `rstat` is borrowed mutably.
-pub(crate) fn conv_block_unblock_helper(
-    buf: Vec<u8>,
-    mode: &ConversionMode,
-    rstat: &mut ReadStat,
-) -> Vec<u8> {
-    fn apply_conversion(buf: Vec<u8>, ct: &ConversionTable) -> impl Iterator<Item = u8> + '_ {
-        buf.into_iter().map(|b| ct[b as usize])
-    }
-
-    match mode {
-        ConversionMode::ConvertOnly(ct) => apply_conversion(buf, ct).collect(),
-        ConversionMode::BlockThenConvert(ct, cbs, sync) => {
-            let blocks = block(&buf, *cbs, *sync, rstat);
-            blocks
-                .into_iter()
-                .flat_map(|block| apply_conversion(block, ct))
-                .collect()
+    /// Swap each pair of bytes, holding back a last odd byte.
+    fn swab(&mut self, buf: &mut Vec<u8>) {
+        if let Some(b) = self.saved_byte.take() {
+            buf.insert(0, b);
         }
-        ConversionMode::ConvertThenBlock(ct, cbs, sync) => {
-            let buf: Vec<_> = apply_conversion(buf, ct).collect();
-            block(&buf, *cbs, *sync, rstat)
-                .into_iter()
-                .flatten()
-                .collect()
+        if buf.len() % 2 == 1 {
+            self.saved_byte = buf.pop();
         }
-        ConversionMode::BlockOnly(cbs, sync) => block(&buf, *cbs, *sync, rstat)
-            .into_iter()
-            .flatten()
-            .collect(),
-        ConversionMode::UnblockThenConvert(ct, cbs) => {
-            let buf = unblock(&buf, *cbs);
-            apply_conversion(buf, ct).collect()
+        for pair in buf.chunks_exact_mut(2) {
+            pair.swap(0, 1);
+        }
+    }
+
+    fn block_or_unblock(&mut self, block: &Block, input: &[u8], output: &mut Vec<u8>) -> u32 {
+        if block.unblock {
+            self.unblock(block, input, output);
+            0
+        } else {
+            self.block(block, input, output)
+        }
+    }
+
+    /// Pad each line to `cbs` bytes with spaces, removing the newline.
+    ///
+    /// Lines longer than `cbs` bytes are truncated, and the number of
+    /// truncated lines is returned.
+    fn block(&mut self, block: &Block, input: &[u8], output: &mut Vec<u8>) -> u32 {
+        let mut truncated = 0;
+        for &b in input {
+            if b == block.newline {
+                if self.col < block.cbs {
+                    output.resize(output.len() + block.cbs - self.col, block.space);
+                }
+                self.col = 0;
+            } else {
+                if self.col == block.cbs {
+                    truncated += 1;
+                } else if self.col < block.cbs {
+                    output.push(b);
+                }
+                self.col += 1;
+            }
         }
-        ConversionMode::ConvertThenUnblock(ct, cbs) => {
-            let buf: Vec<_> = apply_conversion(buf, ct).collect();
-            unblock(&buf, *cbs)
+        truncated
+    }
+
+    /// Strip the trailing spaces of each `cbs`-byte record and terminate it with a newline.
+    fn unblock(&mut self, block: &Block, input: &[u8], output: &mut Vec<u8>) {
+        for &b in input {
+            if self.col == block.cbs {
+                output.push(block.newline);
+                self.col = 0;
+                self.pending_spaces = 0;
+            }
+            self.col += 1;
+            if b == block.space {
+                self.pending_spaces += 1;
+            } else {
+                output.resize(output.len() + self.pending_spaces, block.space);
+                self.pending_spaces = 0;
+                output.push(b);
+            }
         }
-        ConversionMode::UnblockOnly(cbs) => unblock(&buf, *cbs),
     }
 }
 
 #[cfg(test)]
 mod tests {
 
-    use crate::blocks::{block, unblock};
-    use crate::progress::ReadStat;
+    use crate::blocks::Converter;
+    use crate::datastructures::{Block, IConvFlags};
 
     const NEWLINE: u8 = b'\n';
     const SPACE: u8 = b' ';
 
+    /// Convert `bufs` as consecutive reads of the input.
+    fn convert(iconv: &IConvFlags, bufs: &[&[u8]]) -> (Vec<u8>, u32) {
+        let mut converter = Converter::new(iconv);
+        let mut output = Vec::new();
+        let mut truncated = 0;
+        for buf in bufs {
+            let mut buf = buf.to_vec();
+            truncated += converter.convert(&mut buf);
+            output.extend(buf);
+        }
+        truncated += converter.finish(&mut output);
+        (output, truncated)
+    }
+
+    fn blocking(unblock: bool, cbs: usize) -> IConvFlags {
+        IConvFlags {
+            block: Some(Block {
+                unblock,
+                cbs,
+                newline: NEWLINE,
+                space: SPACE,
+            }),
+            ..IConvFlags::default()
+        }
+    }
+
+    /// Block `buf`, returning the records and the number of truncated records.
+    fn block(buf: &[u8], cbs: usize) -> (Vec<Vec<u8>>, u32) {
+        let (output, truncated) = convert(&blocking(false, cbs), &[buf]);
+        (output.chunks(cbs).map(<[u8]>::to_vec).collect(), truncated)
+    }
+
+    fn unblock(buf: &[u8], cbs: usize) -> Vec<u8> {
+        convert(&blocking(true, cbs), &[buf]).0
+    }
+
     #[test]
     fn block_test_no_nl() {
-        let mut rs = ReadStat::default();
         let buf = [0u8, 1u8, 2u8, 3u8];
-        let res = block(&buf, 4, false, &mut rs);
+        let (res, _) = block(&buf, 4);
 
         assert_eq!(res, vec![vec![0u8, 1u8, 2u8, 3u8],]);
     }
 
     #[test]
     fn block_test_no_nl_short_record() {
-        let mut rs = ReadStat::default();
         let buf = [0u8, 1u8, 2u8, 3u8];
-        let res = block(&buf, 8, false, &mut rs);
+        let (res, _) = block(&buf, 8);
 
         assert_eq!(
             res,
@@ -150,22 +222,20 @@ mod tests {
 
     #[test]
     fn block_test_no_nl_trunc() {
-        let mut rs = ReadStat::default();
         let buf = [0u8, 1u8, 2u8, 3u8, 4u8];
-        let res = block(&buf, 4, false, &mut rs);
+        let (res, truncated) = block(&buf, 4);
 
         // Commented section(s) should be truncated and appear for reference only.
         assert_eq!(res, vec![vec![0u8, 1u8, 2u8, 3u8 /*, 4u8*/],]);
-        assert_eq!(rs.records_truncated, 1);
+        assert_eq!(truncated, 1);
     }
 
     #[test]
     fn block_test_nl_gt_cbs_trunc() {
-        let mut rs = ReadStat::default();
         let buf = [
             0u8, 1u8, 2u8, 3u8, 4u8, NEWLINE, 0u8, 1u8, 2u8, 3u8, 4u8, NEWLINE, 5u8, 6u8, 7u8, 8u8,
         ];
-        let res = block(&buf, 4, false, &mut rs);
+        let (res, truncated) = block(&buf, 4);
 
         assert_eq!(
             res,
@@ -178,14 +248,13 @@ mod tests {
                 vec![5u8, 6u8, 7u8, 8u8],
             ]
         );
-        assert_eq!(rs.records_truncated, 2);
+        assert_eq!(truncated, 2);
     }
 
     #[test]
     fn block_test_surrounded_nl() {
-        let mut rs = ReadStat::default();
         let buf = [0u8, 1u8, 2u8, 3u8, NEWLINE, 4u8, 5u8, 6u8, 7u8, 8u8];
-        let res = block(&buf, 8, false, &mut rs);
+        let (res, _) = block(&buf, 8);
 
         assert_eq!(
             res,
@@ -198,11 +267,10 @@ mod tests {
 
     #[test]
     fn block_test_multiple_nl_same_cbs_block() {
-        let mut rs = ReadStat::default();
         let buf = [
             0u8, 1u8, 2u8, 3u8, NEWLINE, 4u8, NEWLINE, 5u8, 6u8, 7u8, 8u8, 9u8,
         ];
-        let res = block(&buf, 8, false, &mut rs);
+        let (res, _) = block(&buf, 8);
 
         assert_eq!(
             res,
@@ -216,11 +284,10 @@ mod tests {
 
     #[test]
     fn block_test_multiple_nl_diff_cbs_block() {
-        let mut rs = ReadStat::default();
         let buf = [
             0u8, 1u8, 2u8, 3u8, NEWLINE, 4u8, 5u8, 6u8, 7u8, NEWLINE, 8u8, 9u8,
         ];
-        let res = block(&buf, 8, false, &mut rs);
+        let (res, _) = block(&buf, 8);
 
         assert_eq!(
             res,
@@ -234,27 +301,24 @@ mod tests {
 
     #[test]
     fn block_test_end_nl_diff_cbs_block() {
-        let mut rs = ReadStat::default();
         let buf = [0u8, 1u8, 2u8, 3u8, NEWLINE];
-        let res = block(&buf, 4, false, &mut rs);
+        let (res, _) = block(&buf, 4);
 
         assert_eq!(res, vec![vec![0u8, 1u8, 2u8, 3u8],]);
     }
 
     #[test]
     fn block_test_end_nl_same_cbs_block() {
-        let mut rs = ReadStat::default();
         let buf = [0u8, 1u8, 2u8, NEWLINE];
-        let res = block(&buf, 4, false, &mut rs);
+        let (res, _) = block(&buf, 4);
 
         assert_eq!(res, vec![vec![0u8, 1u8, 2u8, SPACE]]);
     }
 
     #[test]
     fn block_test_double_end_nl() {
-        let mut rs = ReadStat::default();
         let buf = [0u8, 1u8, 2u8, NEWLINE, NEWLINE];
-        let res = block(&buf, 4, false, &mut rs);
+        let (res, _) = block(&buf, 4);
 
         assert_eq!(
             res,
@@ -264,9 +328,8 @@ mod tests {
 
     #[test]
     fn block_test_start_nl() {
-        let mut rs = ReadStat::default();
         let buf = [NEWLINE, 0u8, 1u8, 2u8, 3u8];
-        let res = block(&buf, 4, false, &mut rs);
+        let (res, _) = block(&buf, 4);
 
         assert_eq!(
             res,
@@ -276,9 +339,8 @@ mod tests {
 
     #[test]
     fn block_test_double_surrounded_nl_no_trunc() {
-        let mut rs = ReadStat::default();
         let buf = [0u8, 1u8, 2u8, 3u8, NEWLINE, NEWLINE, 4u8, 5u8, 6u8, 7u8];
-        let res = block(&buf, 8, false, &mut rs);
+        let (res, _) = block(&buf, 8);
 
         assert_eq!(
             res,
@@ -292,11 +354,10 @@ mod tests {
 
     #[test]
     fn block_test_double_surrounded_nl_double_trunc() {
-        let mut rs = ReadStat::default();
         let buf = [
             0u8, 1u8, 2u8, 3u8, NEWLINE, NEWLINE, 4u8, 5u8, 6u8, 7u8, 8u8,
         ];
-        let res = block(&buf, 4, false, &mut rs);
+        let (res, truncated) = block(&buf, 4);
 
         assert_eq!(
             res,
@@ -307,7 +368,7 @@ mod tests {
                 vec![4u8, 5u8, 6u8, 7u8 /*, 8u8*/],
             ]
         );
-        assert_eq!(rs.records_truncated, 1);
+        assert_eq!(truncated, 1);
     }
 
     #[test]
@@ -371,4 +432,30 @@ mod tests {
 
         assert_eq!(res, exp);
     }
+
+    #[test]
+    fn block_test_across_reads() {
+        let (res, truncated) = convert(&blocking(false, 4), &[b"ab", b"c\nde", b"fgh\n"]);
+
+        assert_eq!(res, b"abc defg");
+        assert_eq!(truncated, 1);
+    }
+
+    #[test]
+    fn unblock_test_across_reads() {
+        let (res, _) = convert(&blocking(true, 4), &[b"ab  ", b"c", b"d  e"]);
+
+        assert_eq!(res, b"ab\ncd\ne\n");
+    }
+
+    #[test]
+    fn swab_test_across_reads() {
+        let iconv = IConvFlags {
+            swab: true,
+            ..IConvFlags::default()
+        };
+        let (res, _) = convert(&iconv, &[b"abc", b"def", b"g"]);
+
+        assert_eq!(res, b"badcfeg");
+    }
 }
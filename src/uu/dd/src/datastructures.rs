@@ -6,32 +6,34 @@
 // file that was distributed with this source code.
 // spell-checker:ignore ctable, outfile, iseek, oseek
 
-use crate::conversion_tables::*;
+use crate::conversion_tables::ConversionTable;
 
-type Cbs = usize;
-
-/// How to apply conversion, blocking, and/or unblocking.
+/// How to convert between newline-terminated lines and fixed-length records.
 ///
-/// Certain settings of the `conv` parameter to `dd` require a
-/// combination of conversion, blocking, or unblocking, applied in a
-/// certain order. The variants of this enumeration give the different
-/// ways of combining those three operations.
-#[derive(Debug, PartialEq)]
-pub(crate) enum ConversionMode {
-    ConvertOnly(&'static ConversionTable),
-    BlockOnly(Cbs, bool),
-    UnblockOnly(Cbs),
-    BlockThenConvert(&'static ConversionTable, Cbs, bool),
-    ConvertThenBlock(&'static ConversionTable, Cbs, bool),
-    UnblockThenConvert(&'static ConversionTable, Cbs),
-    ConvertThenUnblock(&'static ConversionTable, Cbs),
+/// Both directions work on the translated input, so [`Block::newline`]
+/// and [`Block::space`] are given in the character set that the input is
+/// translated to (for example, EBCDIC for `conv=ebcdic`).
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) struct Block {
+    /// Whether to split records into lines (`conv=unblock`) instead of lines into records (`conv=block`).
+    pub unblock: bool,
+    /// The length of a record given by `cbs=N`.
+    pub cbs: usize,
+    /// The character that terminates a line.
+    pub newline: u8,
+    /// The character that pads a record.
+    pub space: u8,
 }
 
 /// Stores all Conv Flags that apply to the input
-#[derive(Debug, Default, PartialEq)]
+///
+/// The conversions are applied in this order: the translation table,
+/// then `swab`, then blocking or unblocking.
+#[derive(Debug, Default, PartialEq, Eq)]
 pub(crate) struct IConvFlags {
-    pub mode: Option<ConversionMode>,
+    pub ctable: Option<ConversionTable>,
     pub swab: bool,
+    pub block: Option<Block>,
     pub sync: Option<u8>,
     pub noerror: bool,
 }
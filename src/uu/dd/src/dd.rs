@@ -5,7 +5,7 @@
 // For the full copyright and license information, please view the LICENSE
 // file that was distributed with this source code.
 
-// spell-checker:ignore fname, tname, fpath, specfile, testfile, unspec, ifile, ofile, outfile, fullblock, urand, fileio, atoe, atoibm, behaviour, bmax, bremain, cflags, creat, ctable, ctty, datastructures, doesnt, etoa, fileout, fname, gnudd, iconvflags, iseek, nocache, noctty, noerror, nofollow, nolinks, nonblock, oconvflags, oseek, outfile, parseargs, rlen, rmax, rremain, rsofar, rstat, sigusr, wlen, wstat seekable oconv canonicalized
+// spell-checker:ignore fname, tname, fpath, specfile, testfile, unspec, ifile, ofile, outfile, fullblock, urand, fileio, atoe, atoibm, behaviour, bmax, bremain, cflags, creat, ctable, ctty, datastructures, doesnt, etoa, fileout, fname, gnudd, iconvflags, iseek, nocache, noctty, noerror, nofollow, nolinks, nonblock, oconvflags, oseek, outfile, parseargs, rlen, rmax, rremain, rsofar, rstat, sigusr, wlen, wstat seekable oconv canonicalized obuf fdatasync
 
 mod datastructures;
 use datastructures::*;
@@ -16,10 +16,12 @@ use parseargs::Parser;
 mod conversion_tables;
 
 mod progress;
-use progress::{gen_prog_updater, ProgUpdate, ReadStat, StatusLevel, WriteStat};
+use progress::{
+    gen_prog_updater, InfoSignal, ProgUpdate, ProgUpdateType, ReadStat, StatusLevel, WriteStat,
+};
 
 mod blocks;
-use blocks::conv_block_unblock_helper;
+use blocks::Converter;
 
 mod numbers;
 
@@ -38,9 +40,9 @@ use std::time;
 use clap::{crate_version, Arg, Command};
 use gcd::Gcd;
 use uucore::display::Quotable;
-use uucore::error::{FromIo, UResult};
+use uucore::error::{FromIo, UError, UResult};
 use uucore::help_section;
-use uucore::show_error;
+use uucore::{show, show_error};
 
 const ABOUT: &str = help_section!("about", "dd.md");
 const AFTER_HELP: &str = help_section!("after help", "dd.md");
@@ -53,6 +55,10 @@ struct Settings {
     outfile: Option<String>,
     ibs: usize,
     obs: usize,
+    /// Whether to gather the output into blocks of `obs` bytes.
+    ///
+    /// Otherwise each block read from the input is written as is.
+    buffered: bool,
     skip: u64,
     seek: u64,
     count: Option<Num>,
@@ -63,6 +69,24 @@ struct Settings {
     status: Option<StatusLevel>,
 }
 
+impl Settings {
+    /// The name of the input, quoted for messages.
+    fn input_name(&self) -> String {
+        match &self.infile {
+            Some(name) => name.quote().to_string(),
+            None => "'standard input'".to_string(),
+        }
+    }
+
+    /// The name of the output, quoted for messages.
+    fn output_name(&self) -> String {
+        match &self.outfile {
+            Some(name) => name.quote().to_string(),
+            None => "'standard output'".to_string(),
+        }
+    }
+}
+
 /// A number in blocks or bytes
 ///
 /// Some values (seek, skip, iseek, oseek) can have values either in blocks or in bytes.
@@ -93,6 +117,8 @@ impl Num {
 struct Input<'a, R: Read> {
     src: R,
     settings: &'a Settings,
+    /// The buffer to read through with `iflag=direct`.
+    direct_buf: AlignedBuffer,
 }
 
 impl<'a> Input<'a, io::Stdin> {
@@ -100,6 +126,7 @@ impl<'a> Input<'a, io::Stdin> {
         let mut input = Self {
             src: io::stdin(),
             settings,
+            direct_buf: AlignedBuffer::default(),
         };
 
         if settings.skip > 0 {
@@ -173,7 +200,11 @@ impl<'a> Input<'a, File> {
                 .map_err_context(|| "failed to seek in input file".to_string())?;
         }
 
-        Ok(Self { src, settings })
+        Ok(Self {
+            src,
+            settings,
+            direct_buf: AlignedBuffer::default(),
+        })
     }
 }
 
@@ -182,7 +213,12 @@ impl<'a, R: Read> Read for Input<'a, R> {
         let mut base_idx = 0;
         let target_len = buf.len();
         loop {
-            match self.src.read(&mut buf[base_idx..]) {
+            let result = if self.settings.iflags.direct {
+                self.direct_buf.read(&mut self.src, &mut buf[base_idx..])
+            } else {
+                self.src.read(&mut buf[base_idx..])
+            };
+            match result {
                 Ok(0) => return Ok(base_idx),
                 Ok(rlen) if self.settings.iflags.fullblock => {
                     base_idx += rlen;
@@ -229,6 +265,7 @@ impl<'a, R: Read> Input<'a, R> {
             reads_partial,
             // Records are not truncated when filling.
             records_truncated: 0,
+            bytes_total: bytes_total as u128,
         })
     }
 
@@ -239,6 +276,7 @@ impl<'a, R: Read> Input<'a, R> {
         let mut reads_complete = 0;
         let mut reads_partial = 0;
         let mut base_idx = 0;
+        let mut bytes_total = 0;
 
         while base_idx < buf.len() {
             let next_blk = cmp::min(base_idx + self.settings.ibs, buf.len());
@@ -247,11 +285,13 @@ impl<'a, R: Read> Input<'a, R> {
             match self.read(&mut buf[base_idx..next_blk])? {
                 0 => break,
                 rlen if rlen < target_len => {
+                    bytes_total += rlen;
                     reads_partial += 1;
                     let padding = vec![pad; target_len - rlen];
                     buf.splice(base_idx + rlen..next_blk, padding.into_iter());
                 }
-                _ => {
+                rlen => {
+                    bytes_total += rlen;
                     reads_complete += 1;
                 }
             }
@@ -264,6 +304,7 @@ impl<'a, R: Read> Input<'a, R> {
             reads_complete,
             reads_partial,
             records_truncated: 0,
+            bytes_total: bytes_total as u128,
         })
     }
 
@@ -302,7 +343,10 @@ enum Dest {
 impl Dest {
     fn fsync(&mut self) -> io::Result<()> {
         match self {
-            Self::Stdout(stdout) => stdout.flush(),
+            Self::Stdout(stdout) => {
+                stdout.flush()?;
+                with_stdout_file(File::sync_all)
+            }
             Self::File(f, _) => {
                 f.flush()?;
                 f.sync_all()
@@ -312,7 +356,10 @@ impl Dest {
 
     fn fdatasync(&mut self) -> io::Result<()> {
         match self {
-            Self::Stdout(stdout) => stdout.flush(),
+            Self::Stdout(stdout) => {
+                stdout.flush()?;
+                with_stdout_file(File::sync_data)
+            }
             Self::File(f, _) => {
                 f.flush()?;
                 f.sync_data()
@@ -337,6 +384,119 @@ impl Dest {
             }
         }
     }
+
+    /// Turn off `O_DIRECT` for the underlying file.
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    fn disable_direct(&mut self) -> io::Result<()> {
+        use nix::fcntl::{fcntl, FcntlArg, OFlag};
+        use std::os::unix::io::AsRawFd;
+
+        let fd = match self {
+            Self::Stdout(stdout) => stdout.as_raw_fd(),
+            Self::File(f, _) => f.as_raw_fd(),
+        };
+        let flags = OFlag::from_bits_truncate(fcntl(fd, FcntlArg::F_GETFL)?);
+        fcntl(fd, FcntlArg::F_SETFL(flags - OFlag::O_DIRECT))?;
+        Ok(())
+    }
+
+    /// Turn off `O_DIRECT` for the underlying file.
+    ///
+    /// Direct I/O is only supported on Linux and Android.
+    #[cfg(not(any(target_os = "linux", target_os = "android")))]
+    fn disable_direct(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+
+    /// Extend the underlying file to the current stream position, if it is shorter.
+    fn extend(&mut self) -> io::Result<()> {
+        match self {
+            Self::Stdout(_) => Ok(()),
+            Self::File(f, _) => {
+                let pos = f.stream_position()?;
+                if f.metadata()?.len() < pos {
+                    f.set_len(pos)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Call `f` with stdout viewed as a [`File`], if stdout is a file descriptor.
+///
+/// This lets stdout be synced like a file, which fails if it is not a
+/// file (for example, if it is a pipe), as in GNU `dd`.
+fn with_stdout_file(f: impl FnOnce(&File) -> io::Result<()>) -> io::Result<()> {
+    #[cfg(unix)]
+    {
+        use std::mem::ManuallyDrop;
+        use std::os::unix::io::{AsRawFd, FromRawFd};
+
+        // SAFETY: the file descriptor of stdout stays open for the
+        // lifetime of the process, and it is not closed here since the
+        // `File` is never dropped.
+        let file = ManuallyDrop::new(unsafe { File::from_raw_fd(io::stdout().as_raw_fd()) });
+        f(&file)
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = f;
+        Ok(())
+    }
+}
+
+/// The alignment of the memory used for direct I/O.
+///
+/// With `O_DIRECT`, reads and writes must use memory aligned to the
+/// logical block size of the file system, which is at most the size of
+/// a page on common systems.
+const DIRECT_IO_ALIGNMENT: usize = 4096;
+
+/// A buffer for direct I/O, at an address aligned to [`DIRECT_IO_ALIGNMENT`].
+///
+/// Data that is not suitably aligned already is copied through this
+/// buffer on its way to or from the file.
+#[derive(Default)]
+struct AlignedBuffer {
+    storage: Vec<u8>,
+    offset: usize,
+}
+
+impl AlignedBuffer {
+    fn is_aligned(buf: &[u8]) -> bool {
+        buf.as_ptr() as usize % DIRECT_IO_ALIGNMENT == 0
+    }
+
+    /// Get an aligned buffer of `len` bytes.
+    fn get(&mut self, len: usize) -> &mut [u8] {
+        if self.storage.len() < self.offset + len {
+            self.storage = vec![0; len + DIRECT_IO_ALIGNMENT];
+            self.offset = self.storage.as_ptr().align_offset(DIRECT_IO_ALIGNMENT);
+        }
+        &mut self.storage[self.offset..self.offset + len]
+    }
+
+    /// Read from `src` into `buf`, through this buffer if `buf` is not aligned.
+    fn read(&mut self, src: &mut impl Read, buf: &mut [u8]) -> io::Result<usize> {
+        if Self::is_aligned(buf) {
+            return src.read(buf);
+        }
+        let aligned = self.get(buf.len());
+        let n = src.read(aligned)?;
+        buf[..n].copy_from_slice(&aligned[..n]);
+        Ok(n)
+    }
+
+    /// Write `buf` to `dst`, through this buffer if `buf` is not aligned.
+    fn write(&mut self, dst: &mut impl Write, buf: &[u8]) -> io::Result<usize> {
+        if Self::is_aligned(buf) {
+            return dst.write(buf);
+        }
+        let aligned = self.get(buf.len());
+        aligned.copy_from_slice(buf);
+        dst.write(aligned)
+    }
 }
 
 /// Decide whether the given buffer is all zeros.
@@ -380,6 +540,15 @@ struct Output<'a> {
 
     /// Configuration settings for how to read and write the data.
     settings: &'a Settings,
+
+    /// The buffer to write through with `oflag=direct`.
+    direct_buf: AlignedBuffer,
+
+    /// Whether `O_DIRECT` was turned off to write a final partial block.
+    ///
+    /// The output is then synced at the end as if `conv=fsync` were
+    /// given, so that it reaches the disk like the direct writes.
+    direct_off: bool,
 }
 
 impl<'a> Output<'a> {
@@ -388,7 +557,7 @@ impl<'a> Output<'a> {
         let mut dst = Dest::Stdout(io::stdout());
         dst.seek(settings.seek)
             .map_err_context(|| "write error".to_string())?;
-        Ok(Self { dst, settings })
+        Ok(Self::new(dst, settings))
     }
 
     /// Instantiate this struct with the named file as a destination.
@@ -430,7 +599,16 @@ impl<'a> Output<'a> {
         let mut dst = Dest::File(dst, density);
         dst.seek(settings.seek)
             .map_err_context(|| "failed to seek in output file".to_string())?;
-        Ok(Self { dst, settings })
+        Ok(Self::new(dst, settings))
+    }
+
+    fn new(dst: Dest, settings: &'a Settings) -> Self {
+        Self {
+            dst,
+            settings,
+            direct_buf: AlignedBuffer::default(),
+            direct_off: false,
+        }
     }
 
     /// Write the given bytes one block at a time.
@@ -446,7 +624,17 @@ impl<'a> Output<'a> {
         let mut bytes_total = 0;
 
         for chunk in buf.chunks(self.settings.obs) {
-            let wlen = self.dst.write(chunk)?;
+            let wlen = if self.settings.oflags.direct {
+                // Like GNU, write a final partial block without `O_DIRECT`,
+                // since its size is most likely not suitably aligned.
+                if chunk.len() < self.settings.obs && !self.direct_off {
+                    self.dst.disable_direct()?;
+                    self.direct_off = true;
+                }
+                self.direct_buf.write(&mut self.dst, chunk)?
+            } else {
+                self.dst.write(chunk)?
+            };
             if wlen < self.settings.obs {
                 writes_partial += 1;
             } else {
@@ -462,12 +650,50 @@ impl<'a> Output<'a> {
         })
     }
 
+    /// Write the given bytes in whole blocks, if the output is buffered.
+    ///
+    /// The bytes that do not fill a whole block are left in `obuf`,
+    /// after any left there by the previous calls. Otherwise, if the
+    /// output is not buffered, `buf` is written as is.
+    fn write_buffered(&mut self, obuf: &mut Vec<u8>, buf: &[u8]) -> io::Result<WriteStat> {
+        if !self.settings.buffered {
+            return self.write_blocks(buf);
+        }
+        let obs = self.settings.obs;
+        if obuf.is_empty() {
+            // Avoid copying the whole blocks when nothing precedes them.
+            let len = buf.len() - buf.len() % obs;
+            let wstat = self.write_blocks(&buf[..len])?;
+            obuf.extend_from_slice(&buf[len..]);
+            return Ok(wstat);
+        }
+        obuf.extend_from_slice(buf);
+        let len = obuf.len() - obuf.len() % obs;
+        let wstat = self.write_blocks(&obuf[..len])?;
+        obuf.drain(..len);
+        Ok(wstat)
+    }
+
     /// Flush the output to disk, if configured to do so.
-    fn sync(&mut self) -> std::io::Result<()> {
-        if self.settings.oconv.fsync {
-            self.dst.fsync()
+    fn sync(&mut self) -> UResult<()> {
+        let name = self.settings.output_name();
+        if self.settings.oconv.fsync || self.direct_off {
+            self.dst
+                .fsync()
+                .map_err_context(|| format!("fsync failed for {name}"))
         } else if self.settings.oconv.fdatasync {
-            self.dst.fdatasync()
+            match self.dst.fdatasync() {
+                // Like GNU, fall back to fsync where fdatasync is not supported.
+                Err(e)
+                    if e.kind() == io::ErrorKind::InvalidInput
+                        || e.raw_os_error() == Some(libc::ENOSYS) =>
+                {
+                    self.dst
+                        .fsync()
+                        .map_err_context(|| format!("fsync failed for {name}"))
+                }
+                result => result.map_err_context(|| format!("fdatasync failed for {name}")),
+            }
         } else {
             // Intentionally do nothing in this case.
             Ok(())
@@ -511,17 +737,19 @@ impl<'a> Output<'a> {
 
         // Start a thread that reports transfer progress.
         //
-        // The `dd` program reports its progress after every block is written,
-        // at most every 1 second, and only if `status=progress` is given on
-        // the command-line or a SIGUSR1 signal is received. We
-        // perform this reporting in a new thread so as not to take
-        // any CPU time away from the actual reading and writing of
+        // The `dd` program reports its progress after a block is written,
+        // every second if `status=progress` is given on the command-line,
+        // and whenever the info signal (SIGUSR1, or SIGINFO on BSDs) is
+        // received. We perform this reporting in a new thread so as not to
+        // take any CPU time away from the actual reading and writing of
         // data. We send a `ProgUpdate` from the transmitter `prog_tx`
         // to the receives `rx`, and the receiver prints the transfer
         // information.
+        let info_signal = InfoSignal::register(i.settings.status);
         let (prog_tx, rx) = mpsc::channel();
         let output_thread = thread::spawn(gen_prog_updater(rx, i.settings.status));
-        let mut progress_as_secs = 0;
+        let print_progress = i.settings.status == Some(StatusLevel::Progress);
+        let mut progress_as_secs = 1;
 
         // Optimization: if no blocks are to be written, then don't
         // bother allocating any buffers.
@@ -539,37 +767,96 @@ impl<'a> Output<'a> {
         // blocks to this output. Read/write statistics are updated on
         // each iteration and cumulative statistics are reported to
         // the progress reporting thread.
-        while below_count_limit(&i.settings.count, &rstat, &wstat) {
+        // The conversions applied to the input, and the converted data
+        // that does not fill a whole output block yet.
+        let mut converter = Converter::new(&i.settings.iconv);
+        let mut obuf = Vec::new();
+
+        while below_count_limit(&i.settings.count, &rstat) {
             // Read a block from the input then write the block to the output.
             //
             // As an optimization, make an educated guess about the
             // best buffer size for reading based on the number of
             // blocks already read and the number of blocks remaining.
-            let loop_bsize =
-                calc_loop_bsize(&i.settings.count, &rstat, &wstat, i.settings.ibs, bsize);
-            let rstat_update = read_helper(&mut i, &mut buf, loop_bsize)?;
+            let loop_bsize = calc_loop_bsize(&i.settings.count, &rstat, i.settings.ibs, bsize);
+            //
+            // Like GNU, a read error stops the copy, but what was read
+            // before is still written, and a write error stops the copy
+            // at once. Either way, the statistics are printed.
+            let rstat_update = match read_helper(&mut i, &mut converter, &mut buf, loop_bsize) {
+                Ok(rstat_update) => rstat_update,
+                Err(e) => {
+                    show!(
+                        e.map_err_context(|| format!("error reading {}", i.settings.input_name()))
+                    );
+                    break;
+                }
+            };
             if rstat_update.is_empty() {
                 break;
             }
-            let wstat_update = self.write_blocks(&buf)?;
+            rstat += rstat_update;
+            match self.write_buffered(&mut obuf, &buf) {
+                Ok(wstat_update) => wstat += wstat_update,
+                Err(e) => {
+                    return self.write_failed(e, rstat, wstat, start, &prog_tx, output_thread)
+                }
+            }
 
-            // Update the read/write stats and inform the progress thread once per second.
+            // Inform the progress thread once per second, or when the
+            // info signal was received.
             //
             // If the receiver is disconnected, `send()` returns an
             // error. Since it is just reporting progress and is not
             // crucial to the operation of `dd`, let's just ignore the
             // error.
-            rstat += rstat_update;
-            wstat += wstat_update;
-            let prog_update = ProgUpdate::new(rstat, wstat, start.elapsed(), false);
-            if prog_update.duration.as_secs() >= progress_as_secs {
-                progress_as_secs = prog_update.duration.as_secs() + 1;
+            let duration = start.elapsed();
+            if info_signal.received() {
+                let prog_update = ProgUpdate::new(rstat, wstat, duration, ProgUpdateType::Signal);
+                prog_tx.send(prog_update).unwrap_or(());
+            } else if print_progress && duration.as_secs() >= progress_as_secs {
+                progress_as_secs = duration.as_secs() + 1;
+                let prog_update = ProgUpdate::new(rstat, wstat, duration, ProgUpdateType::Periodic);
                 prog_tx.send(prog_update).unwrap_or(());
             }
         }
+
+        // Write what the conversions held back, then the last partial block.
+        buf.clear();
+        rstat.records_truncated += converter.finish(&mut buf);
+        let result = self
+            .write_buffered(&mut obuf, &buf)
+            .and_then(|mut wstat_update| {
+                wstat_update += self.write_blocks(&obuf)?;
+                Ok(wstat_update)
+            });
+        match result {
+            Ok(wstat_update) => wstat += wstat_update,
+            Err(e) => return self.write_failed(e, rstat, wstat, start, &prog_tx, output_thread),
+        }
+
         self.finalize(rstat, wstat, start, &prog_tx, output_thread)
     }
 
+    /// Report a write error, then print the final stats.
+    fn write_failed<T>(
+        &mut self,
+        e: io::Error,
+        rstat: ReadStat,
+        wstat: WriteStat,
+        start: time::Instant,
+        prog_tx: &mpsc::Sender<ProgUpdate>,
+        output_thread: thread::JoinHandle<T>,
+    ) -> std::io::Result<()> {
+        show!(e.map_err_context(|| format!("error writing {}", self.settings.output_name())));
+        let prog_update = ProgUpdate::new(rstat, wstat, start.elapsed(), ProgUpdateType::Final);
+        prog_tx.send(prog_update).unwrap_or(());
+        output_thread
+            .join()
+            .expect("Failed to join with the output thread.");
+        Ok(())
+    }
+
     /// Flush output, print final stats, and join with the progress thread.
     fn finalize<T>(
         &mut self,
@@ -580,7 +867,11 @@ impl<'a> Output<'a> {
         output_thread: thread::JoinHandle<T>,
     ) -> std::io::Result<()> {
         // Flush the output, if configured to do so.
-        self.sync()?;
+        //
+        // Like GNU, report a failure but still print the statistics.
+        if let Err(e) = self.sync() {
+            show!(e);
+        }
 
         // Truncate the file to the final cursor location.
         //
@@ -590,12 +881,17 @@ impl<'a> Output<'a> {
         // suppress the error by calling `Result::ok()`. This matches
         // the behavior of GNU `dd` when given the command-line
         // argument `of=/dev/null`.
+        //
+        // Even with `conv=notrunc`, a file that ends with a hole left by
+        // `conv=sparse` is extended to include the hole.
         if !self.settings.oconv.notrunc {
             self.dst.truncate().ok();
+        } else if self.settings.oconv.sparse {
+            self.dst.extend().ok();
         }
 
         // Print the final read/write statistics.
-        let prog_update = ProgUpdate::new(rstat, wstat, start.elapsed(), true);
+        let prog_update = ProgUpdate::new(rstat, wstat, start.elapsed(), ProgUpdateType::Final);
         prog_tx.send(prog_update).unwrap_or(());
         // Wait for the output thread to finish
         output_thread
@@ -645,19 +941,13 @@ fn make_linux_oflags(oflags: &OFlags) -> Option<libc::c_int> {
     }
 }
 
-/// Read helper performs read operations common to all dd reads, and dispatches the buffer to relevant helper functions as dictated by the operations requested by the user.
+/// Read helper performs read operations common to all dd reads, and passes the buffer through the conversions requested by the user.
 fn read_helper<R: Read>(
     i: &mut Input<R>,
+    converter: &mut Converter,
     buf: &mut Vec<u8>,
     bsize: usize,
 ) -> std::io::Result<ReadStat> {
-    // Local Helper Fns -------------------------------------------------
-    fn perform_swab(buf: &mut [u8]) {
-        for base in (1..buf.len()).step_by(2) {
-            buf.swap(base, base - 1);
-        }
-    }
-    // ------------------------------------------------------------------
     // Read
     // Resize the buffer to the bsize. Any garbage data in the buffer is overwritten or truncated, so there is no need to fill with BUF_INIT_BYTE first.
     buf.resize(bsize, BUF_INIT_BYTE);
@@ -672,17 +962,8 @@ fn read_helper<R: Read>(
     }
 
     // Perform any conv=x[,x...] options
-    if i.settings.iconv.swab {
-        perform_swab(buf);
-    }
-
-    match i.settings.iconv.mode {
-        Some(ref mode) => {
-            *buf = conv_block_unblock_helper(buf.clone(), mode, &mut rstat);
-            Ok(rstat)
-        }
-        None => Ok(rstat),
-    }
+    rstat.records_truncated += converter.convert(buf);
+    Ok(rstat)
 }
 
 // Calculate a 'good' internal buffer size.
@@ -699,13 +980,7 @@ fn calc_bsize(ibs: usize, obs: usize) -> usize {
 
 // Calculate the buffer size appropriate for this loop iteration, respecting
 // a count=N if present.
-fn calc_loop_bsize(
-    count: &Option<Num>,
-    rstat: &ReadStat,
-    wstat: &WriteStat,
-    ibs: usize,
-    ideal_bsize: usize,
-) -> usize {
+fn calc_loop_bsize(count: &Option<Num>, rstat: &ReadStat, ibs: usize, ideal_bsize: usize) -> usize {
     match count {
         Some(Num::Blocks(rmax)) => {
             let rsofar = rstat.reads_complete + rstat.reads_partial;
@@ -714,7 +989,7 @@ fn calc_loop_bsize(
         }
         Some(Num::Bytes(bmax)) => {
             let bmax: u128 = (*bmax).try_into().unwrap();
-            let bremain: u128 = bmax - wstat.bytes_total;
+            let bremain: u128 = bmax - rstat.bytes_total;
             cmp::min(ideal_bsize as u128, bremain) as usize
         }
         None => ideal_bsize,
@@ -723,7 +998,7 @@ fn calc_loop_bsize(
 
 // Decide if the current progress is below a count=N limit or return
 // true if no such limit is set.
-fn below_count_limit(count: &Option<Num>, rstat: &ReadStat, wstat: &WriteStat) -> bool {
+fn below_count_limit(count: &Option<Num>, rstat: &ReadStat) -> bool {
     match count {
         Some(Num::Blocks(n)) => {
             let n = *n;
@@ -731,7 +1006,7 @@ fn below_count_limit(count: &Option<Num>, rstat: &ReadStat, wstat: &WriteStat) -
         }
         Some(Num::Bytes(n)) => {
             let n = (*n).try_into().unwrap();
-            wstat.bytes_total <= n
+            rstat.bytes_total <= n
         }
         None => true,
     }
@@ -9,7 +9,7 @@
 #[cfg(test)]
 mod unit_tests;
 
-use super::{ConversionMode, IConvFlags, IFlags, Num, OConvFlags, OFlags, Settings, StatusLevel};
+use super::{Block, IConvFlags, IFlags, Num, OConvFlags, OFlags, Settings, StatusLevel};
 use crate::conversion_tables::ConversionTable;
 use std::error::Error;
 use uucore::display::Quotable;
@@ -26,10 +26,10 @@ pub enum ParseError {
     MultipleBlockUnblock,
     MultipleExclNoCreate,
     FlagNoMatch(String),
+    OFlagNoMatch(String),
     ConvFlagNoMatch(String),
     MultiplierStringParseFailure(String),
     MultiplierStringOverflow(String),
-    BlockUnblockWithoutCBS,
     StatusLevelNotRecognized(String),
     Unimplemented(String),
     BsOutOfRange(String),
@@ -41,6 +41,7 @@ pub enum ParseError {
 pub struct Parser {
     infile: Option<String>,
     outfile: Option<String>,
+    bs: Option<usize>,
     ibs: usize,
     obs: usize,
     cbs: Option<usize>,
@@ -56,6 +57,7 @@ pub struct Parser {
 impl Default for Parser {
     fn default() -> Self {
         Self {
+            bs: None,
             ibs: 512,
             obs: 512,
             cbs: None,
@@ -105,12 +107,6 @@ enum Case {
     Upper,
 }
 
-#[derive(Clone, Copy)]
-enum Block {
-    Block(usize),
-    Unblock(usize),
-}
-
 /// Return an Unimplemented error when the target is not Linux or Android
 macro_rules! linux_only {
     ($s: expr, $val: expr) => {
@@ -149,6 +145,21 @@ impl Parser {
             _ => return Err(ParseError::MultipleFmtTable),
         };
 
+        // The GNU docs state that
+        // - ascii implies unblock
+        // - ebcdic and ibm imply block
+        // Blocking and unblocking are silently dropped without a cbs=N,
+        // so conflicts between them only matter when one is given.
+        let (block, unblock) = match (self.cbs, conversion) {
+            (None, _) => (false, false),
+            (Some(_), Some(Conversion::Ascii)) => (conv.block, true),
+            (Some(_), Some(_)) => (true, conv.unblock),
+            (Some(_), None) => (conv.block, conv.unblock),
+        };
+        if block && unblock {
+            return Err(ParseError::MultipleBlockUnblock);
+        }
+
         let case = match (conv.ucase, conv.lcase) {
             (false, false) => None,
             (true, false) => Some(Case::Upper),
@@ -156,42 +167,30 @@ impl Parser {
             (true, true) => return Err(ParseError::MultipleUCaseLCase),
         };
 
-        let non_ascii = matches!(conversion, Some(Conversion::Ascii));
-        let conversion_table = get_ctable(conversion, case);
-
         if conv.nocreat && conv.excl {
             return Err(ParseError::MultipleExclNoCreate);
         }
 
-        // The GNU docs state that
-        // - ascii implies unblock
-        // - ebcdic and ibm imply block
-        // This has a side effect in how it's implemented in GNU, because this errors:
-        //     conv=block,unblock
-        // but these don't:
-        //     conv=ascii,block,unblock
-        //     conv=block,ascii,unblock
-        //     conv=block,unblock,ascii
-        //     conv=block conv=unblock conv=ascii
-        let block = if let Some(cbs) = self.cbs {
-            match conversion {
-                Some(Conversion::Ascii) => Some(Block::Unblock(cbs)),
-                Some(_) => Some(Block::Block(cbs)),
-                None => match (conv.block, conv.unblock) {
-                    (false, false) => None,
-                    (true, false) => Some(Block::Block(cbs)),
-                    (false, true) => Some(Block::Unblock(cbs)),
-                    (true, true) => return Err(ParseError::MultipleBlockUnblock),
-                },
+        let ctable = get_ctable(conversion, case);
+
+        // Records are delimited after translation, so if the input is
+        // translated to EBCDIC, they are delimited by EBCDIC characters.
+        let (newline, space) = match conversion {
+            Some(Conversion::Ebcdic | Conversion::Ibm) => {
+                let table = ctable.as_ref().unwrap();
+                (table[b'\n' as usize], table[b' ' as usize])
             }
-        } else if conv.block || conv.unblock {
-            return Err(ParseError::BlockUnblockWithoutCBS);
-        } else {
-            None
+            _ => (b'\n', b' '),
         };
+        let block = self.cbs.filter(|_| block || unblock).map(|cbs| Block {
+            unblock,
+            cbs,
+            newline,
+            space,
+        });
 
         let iconv = IConvFlags {
-            mode: conversion_mode(conversion_table, block, non_ascii, conv.sync),
+            ctable,
             swab: conv.swab,
             sync: if conv.sync {
                 if block.is_some() {
@@ -202,6 +201,7 @@ impl Parser {
             } else {
                 None
             },
+            block,
             noerror: conv.noerror,
         };
 
@@ -214,15 +214,34 @@ impl Parser {
             fsync: conv.fsync,
         };
 
+        // bs=N takes precedence over ibs=N and obs=N, wherever it is given.
+        let (ibs, obs) = match self.bs {
+            Some(bs) => (bs, bs),
+            None => (self.ibs, self.obs),
+        };
+
+        // Like GNU, gather the output into obs-sized blocks unless
+        // the input blocks are written as they are read, that is,
+        // unless bs=N is given and no conversion changes the data.
+        let buffered = self.bs.is_none()
+            || conv.ascii
+            || conv.ebcdic
+            || conv.ibm
+            || conv.lcase
+            || conv.ucase
+            || conv.block
+            || conv.unblock
+            || conv.swab;
+
         let skip = self
             .skip
             .force_bytes_if(self.iflag.skip_bytes)
-            .to_bytes(self.ibs as u64);
+            .to_bytes(ibs as u64);
 
         let seek = self
             .seek
             .force_bytes_if(self.oflag.seek_bytes)
-            .to_bytes(self.obs as u64);
+            .to_bytes(obs as u64);
 
         let count = self.count.map(|c| c.force_bytes_if(self.iflag.count_bytes));
 
@@ -232,8 +251,9 @@ impl Parser {
             count,
             iconv,
             oconv,
-            ibs: self.ibs,
-            obs: self.obs,
+            ibs,
+            obs,
+            buffered,
             infile: self.infile,
             outfile: self.outfile,
             iflags: self.iflag,
@@ -246,11 +266,7 @@ impl Parser {
         match operand.split_once('=') {
             None => return Err(ParseError::UnrecognizedOperand(operand.to_string())),
             Some((k, v)) => match k {
-                "bs" => {
-                    let bs = self.parse_bytes(k, v)?;
-                    self.ibs = bs;
-                    self.obs = bs;
-                }
+                "bs" => self.bs = Some(self.parse_bytes(k, v)?),
                 "cbs" => self.cbs = Some(self.parse_bytes(k, v)?),
                 "conv" => self.parse_conv_flags(v)?,
                 "count" => self.count = Some(self.parse_n(v)?),
@@ -278,10 +294,14 @@ impl Parser {
         })
     }
 
+    /// Parse a block size, which must be positive.
     fn parse_bytes(&self, arg: &str, val: &str) -> Result<usize, ParseError> {
-        parse_bytes_with_opt_multiplier(val)?
-            .try_into()
-            .map_err(|_| ParseError::BsOutOfRange(arg.to_string()))
+        match parse_bytes_with_opt_multiplier(val)? {
+            0 => Err(ParseError::InvalidNumber(val.to_string())),
+            n => n
+                .try_into()
+                .map_err(|_| ParseError::BsOutOfRange(arg.to_string())),
+        }
     }
 
     fn parse_status_level(&self, val: &str) -> Result<StatusLevel, ParseError> {
@@ -329,7 +349,7 @@ impl Parser {
         for f in val.split(',') {
             match f {
                 // Common flags
-                "cio" => return Err(ParseError::Unimplemented(val.to_string())),
+                "cio" => return Err(ParseError::Unimplemented(f.to_string())),
                 "direct" => linux_only!(f, o.direct = true),
                 "directory" => linux_only!(f, o.directory = true),
                 "dsync" => linux_only!(f, o.dsync = true),
@@ -348,7 +368,7 @@ impl Parser {
                 "seek_bytes" => o.seek_bytes = true,
                 // GNU silently ignores iflags given as oflag.
                 "fullblock" | "count_bytes" | "skip_bytes" => {}
-                _ => return Err(ParseError::FlagNoMatch(f.to_string())),
+                _ => return Err(ParseError::OFlagNoMatch(f.to_string())),
             }
         }
         Ok(())
@@ -397,19 +417,16 @@ impl std::fmt::Display for ParseError {
                 write!(f, "Unrecognized operand '{arg}'")
             }
             Self::MultipleFmtTable => {
-                write!(
-                    f,
-                    "Only one of conv=ascii conv=ebcdic or conv=ibm may be specified"
-                )
+                write!(f, "cannot combine any two of {{ascii,ebcdic,ibm}}")
             }
             Self::MultipleUCaseLCase => {
-                write!(f, "Only one of conv=lcase or conv=ucase may be specified")
+                write!(f, "cannot combine lcase and ucase")
             }
             Self::MultipleBlockUnblock => {
-                write!(f, "Only one of conv=block or conv=unblock may be specified")
+                write!(f, "cannot combine block and unblock")
             }
             Self::MultipleExclNoCreate => {
-                write!(f, "Only one ov conv=excl or conv=nocreat may be specified")
+                write!(f, "cannot combine excl and nocreat")
             }
             Self::FlagNoMatch(arg) => {
                 // Additional message about 'dd --help' is displayed only in this situation.
@@ -420,8 +437,21 @@ impl std::fmt::Display for ParseError {
                     uucore::execution_phrase()
                 )
             }
+            Self::OFlagNoMatch(arg) => {
+                write!(
+                    f,
+                    "invalid output flag: ‘{}’\nTry '{} --help' for more information.",
+                    arg,
+                    uucore::execution_phrase()
+                )
+            }
             Self::ConvFlagNoMatch(arg) => {
-                write!(f, "Unrecognized conv=CONV -> {arg}")
+                write!(
+                    f,
+                    "invalid conversion: {}\nTry '{} --help' for more information.",
+                    arg.quote(),
+                    uucore::execution_phrase()
+                )
             }
             Self::MultiplierStringParseFailure(arg) => {
                 write!(f, "Unrecognized byte multiplier -> {arg}")
@@ -432,9 +462,6 @@ impl std::fmt::Display for ParseError {
                     "Multiplier string would overflow on current system -> {arg}"
                 )
             }
-            Self::BlockUnblockWithoutCBS => {
-                write!(f, "conv=block or conv=unblock specified without cbs=N")
-            }
             Self::StatusLevelNotRecognized(arg) => {
                 write!(f, "status=LEVEL not recognized -> {arg}")
             }
@@ -558,67 +585,34 @@ pub fn parse_bytes_with_opt_multiplier(s: &str) -> Result<u64, ParseError> {
     }
 }
 
-fn get_ctable(
-    conversion: Option<Conversion>,
-    case: Option<Case>,
-) -> Option<&'static ConversionTable> {
-    use crate::conversion_tables::*;
-    Some(match (conversion, case) {
-        (None, None) => return None,
-        (Some(conv), None) => match conv {
-            Conversion::Ascii => &EBCDIC_TO_ASCII,
-            Conversion::Ebcdic => &ASCII_TO_EBCDIC,
-            Conversion::Ibm => &ASCII_TO_IBM,
-        },
-        (None, Some(case)) => match case {
-            Case::Lower => &ASCII_UCASE_TO_LCASE,
-            Case::Upper => &ASCII_LCASE_TO_UCASE,
-        },
-        (Some(conv), Some(case)) => match (conv, case) {
-            (Conversion::Ascii, Case::Upper) => &EBCDIC_TO_ASCII_LCASE_TO_UCASE,
-            (Conversion::Ascii, Case::Lower) => &EBCDIC_TO_ASCII_UCASE_TO_LCASE,
-            (Conversion::Ebcdic, Case::Upper) => &ASCII_TO_EBCDIC_LCASE_TO_UCASE,
-            (Conversion::Ebcdic, Case::Lower) => &ASCII_TO_EBCDIC_UCASE_TO_LCASE,
-            (Conversion::Ibm, Case::Upper) => &ASCII_TO_IBM_UCASE_TO_LCASE,
-            (Conversion::Ibm, Case::Lower) => &ASCII_TO_IBM_LCASE_TO_UCASE,
-        },
-    })
-}
-
-/// Given the various command-line parameters, determine the conversion mode.
+/// Build the translation table for the given character set and case conversions.
 ///
-/// The `conv` command-line option can take many different values,
-/// each of which may combine with others. For example, `conv=ascii`,
-/// `conv=lcase`, `conv=sync`, and so on. The arguments to this
-/// function represent the settings of those various command-line
-/// parameters. This function translates those settings to a
-/// [`ConversionMode`].
-fn conversion_mode(
-    ctable: Option<&'static ConversionTable>,
-    block: Option<Block>,
-    is_ascii: bool,
-    is_sync: bool,
-) -> Option<ConversionMode> {
-    match (ctable, block) {
-        (Some(ct), None) => Some(ConversionMode::ConvertOnly(ct)),
-        (Some(ct), Some(Block::Block(cbs))) => {
-            if is_ascii {
-                Some(ConversionMode::ConvertThenBlock(ct, cbs, is_sync))
-            } else {
-                Some(ConversionMode::BlockThenConvert(ct, cbs, is_sync))
-            }
-        }
-        (Some(ct), Some(Block::Unblock(cbs))) => {
-            if is_ascii {
-                Some(ConversionMode::ConvertThenUnblock(ct, cbs))
-            } else {
-                Some(ConversionMode::UnblockThenConvert(ct, cbs))
-            }
-        }
-        (None, Some(Block::Block(cbs))) => Some(ConversionMode::BlockOnly(cbs, is_sync)),
-        (None, Some(Block::Unblock(cbs))) => Some(ConversionMode::UnblockOnly(cbs)),
-        (None, None) => None,
-    }
+/// Like GNU, translate from EBCDIC first for `conv=ascii`, then
+/// convert the case of the ASCII characters, then translate to EBCDIC
+/// for `conv=ebcdic` and `conv=ibm`.
+fn get_ctable(conversion: Option<Conversion>, case: Option<Case>) -> Option<ConversionTable> {
+    use crate::conversion_tables::*;
+    let from_ebcdic = match conversion {
+        Some(Conversion::Ascii) => Some(&EBCDIC_TO_ASCII),
+        _ => None,
+    };
+    let case = match case {
+        Some(Case::Lower) => Some(&ASCII_UCASE_TO_LCASE),
+        Some(Case::Upper) => Some(&ASCII_LCASE_TO_UCASE),
+        None => None,
+    };
+    let to_ebcdic = match conversion {
+        Some(Conversion::Ebcdic) => Some(&ASCII_TO_EBCDIC),
+        Some(Conversion::Ibm) => Some(&ASCII_TO_IBM),
+        _ => None,
+    };
+    [from_ebcdic, case, to_ebcdic]
+        .into_iter()
+        .flatten()
+        .fold(None, |table, stage| match table {
+            None => Some(*stage),
+            Some(table) => Some(compose(&table, stage)),
+        })
 }
 
 #[cfg(test)]
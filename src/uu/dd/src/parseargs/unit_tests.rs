@@ -3,7 +3,8 @@
 use super::*;
 
 use crate::conversion_tables::{
-    ASCII_TO_EBCDIC_UCASE_TO_LCASE, ASCII_TO_IBM, EBCDIC_TO_ASCII_LCASE_TO_UCASE,
+    compose, ASCII_LCASE_TO_UCASE, ASCII_TO_EBCDIC, ASCII_TO_IBM, ASCII_UCASE_TO_LCASE,
+    EBCDIC_TO_ASCII,
 };
 use crate::parseargs::Parser;
 use crate::StatusLevel;
@@ -140,11 +141,14 @@ fn test_all_top_level_args_no_leading_dashes() {
     assert_eq!(
         settings.iconv,
         IConvFlags {
+            ctable: Some(compose(&EBCDIC_TO_ASCII, &ASCII_LCASE_TO_UCASE)),
             // ascii implies unblock
-            mode: Some(ConversionMode::ConvertThenUnblock(
-                &EBCDIC_TO_ASCII_LCASE_TO_UCASE,
-                1
-            )),
+            block: Some(Block {
+                unblock: true,
+                cbs: 1,
+                newline: b'\n',
+                space: b' ',
+            }),
             ..IConvFlags::default()
         },
     );
@@ -229,10 +233,13 @@ fn test_multiple_flags_options() {
     assert_eq!(
         settings.iconv,
         IConvFlags {
-            mode: Some(ConversionMode::ConvertThenUnblock(
-                &EBCDIC_TO_ASCII_LCASE_TO_UCASE,
-                512
-            )),
+            ctable: Some(compose(&EBCDIC_TO_ASCII, &ASCII_LCASE_TO_UCASE)),
+            block: Some(Block {
+                unblock: true,
+                cbs: 512,
+                newline: b'\n',
+                space: b' ',
+            }),
             ..Default::default()
         }
     );
@@ -294,8 +301,49 @@ fn icf_case_error() {
 
 #[test]
 fn icf_block_error() {
+    let args = &["conv=block,unblock", "cbs=1"];
+    assert_eq!(
+        Parser::new().parse(args).err(),
+        Some(ParseError::MultipleBlockUnblock)
+    );
+
+    // ascii implies unblock
+    let args = &["conv=ascii,block", "cbs=1"];
+    assert_eq!(
+        Parser::new().parse(args).err(),
+        Some(ParseError::MultipleBlockUnblock)
+    );
+}
+
+#[test]
+fn icf_block_without_cbs_is_ignored() {
     let args = &["conv=block,unblock"];
-    assert!(Parser::new().parse(args).is_err());
+    assert_eq!(Parser::new().parse(args).unwrap().iconv.block, None);
+}
+
+#[test]
+fn zero_block_size_error() {
+    for arg in ["bs=0", "ibs=0", "obs=0", "cbs=0"] {
+        assert_eq!(
+            Parser::new().parse(&[arg]).err(),
+            Some(ParseError::InvalidNumber("0".to_string()))
+        );
+    }
+}
+
+#[test]
+fn bs_overrides_ibs_and_obs() {
+    let settings = Parser::new()
+        .parse(&["bs=100", "ibs=10", "obs=20"])
+        .unwrap();
+    assert_eq!(settings.ibs, 100);
+    assert_eq!(settings.obs, 100);
+    assert!(!settings.buffered);
+
+    let settings = Parser::new().parse(&["ibs=10", "obs=20"]).unwrap();
+    assert_eq!(settings.ibs, 10);
+    assert_eq!(settings.obs, 20);
+    assert!(settings.buffered);
 }
 
 #[test]
@@ -312,7 +360,7 @@ fn parse_icf_token_ibm() {
     assert_eq!(
         settings.iconv,
         IConvFlags {
-            mode: Some(ConversionMode::ConvertOnly(&ASCII_TO_IBM)),
+            ctable: Some(ASCII_TO_IBM),
             ..Default::default()
         }
     );
@@ -326,7 +374,7 @@ fn parse_icf_tokens_elu() {
     assert_eq!(
         settings.iconv,
         IConvFlags {
-            mode: Some(ConversionMode::ConvertOnly(&ASCII_TO_EBCDIC_UCASE_TO_LCASE)),
+            ctable: Some(compose(&ASCII_UCASE_TO_LCASE, &ASCII_TO_EBCDIC)),
             ..Default::default()
         }
     );
@@ -10,23 +10,24 @@
 //! [`gen_prog_updater`] function can be used to implement a progress
 //! updater that runs in its own thread.
 use std::io::Write;
-use std::sync::mpsc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc};
 use std::time::Duration;
 
 use crate::numbers::{to_magnitude_and_suffix, SuffixType};
 
-// On Linux, we register a signal handler that prints progress updates.
-#[cfg(target_os = "linux")]
-use signal_hook::consts::signal;
-#[cfg(target_os = "linux")]
-use std::{
-    env,
-    error::Error,
-    sync::{
-        atomic::{AtomicUsize, Ordering},
-        Arc,
-    },
-};
+/// Why a [`ProgUpdate`] is sent to the progress updater.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum ProgUpdateType {
+    /// The progress line is due for an update, with `status=progress`.
+    Periodic,
+
+    /// The info signal was received, see [`InfoSignal`].
+    Signal,
+
+    /// The transfer is complete.
+    Final,
+}
 
 /// Summary statistics for read and write progress of dd for a given duration.
 pub(crate) struct ProgUpdate {
@@ -45,10 +46,8 @@ pub(crate) struct ProgUpdate {
     /// The time period over which the reads and writes were measured.
     pub(crate) duration: Duration,
 
-    /// The status of the write.
-    ///
-    /// True if the write is completed, false if still in-progress.
-    pub(crate) complete: bool,
+    /// What to print for this update.
+    pub(crate) update_type: ProgUpdateType,
 }
 
 impl ProgUpdate {
@@ -57,13 +56,13 @@ impl ProgUpdate {
         read_stat: ReadStat,
         write_stat: WriteStat,
         duration: Duration,
-        complete: bool,
+        update_type: ProgUpdateType,
     ) -> Self {
         Self {
             read_stat,
             write_stat,
             duration,
-            complete,
+            update_type,
         }
     }
 
@@ -111,7 +110,8 @@ impl ProgUpdate {
     /// then a `\r` character is written first and no newline is
     /// written at the end. When writing to `stderr`, this has the
     /// visual effect of overwriting the previous characters on the
-    /// line.
+    /// line. As the line is rewritten every second, the duration is
+    /// then rounded to whole seconds.
     ///
     /// # Examples
     ///
@@ -138,7 +138,11 @@ impl ProgUpdate {
         let btotal_bin = to_magnitude_and_suffix(btotal, SuffixType::Iec);
 
         // Compute the throughput (bytes per second) as a string.
-        let duration = self.duration.as_secs_f64();
+        let duration = if rewrite {
+            format!("{:.0} s", self.duration.as_secs_f64())
+        } else {
+            format!("{:.1} s", self.duration.as_secs_f64())
+        };
         let safe_millis = std::cmp::max(1, self.duration.as_millis());
         let rate = 1000 * (btotal / safe_millis);
         let transfer_rate = to_magnitude_and_suffix(rate, SuffixType::Si);
@@ -154,19 +158,19 @@ impl ProgUpdate {
         match btotal {
             1 => write!(
                 w,
-                "{carriage_return}{btotal} byte copied, {duration:.1} s, {transfer_rate}/s{newline}",
+                "{carriage_return}{btotal} byte copied, {duration}, {transfer_rate}/s{newline}",
             ),
             0..=999 => write!(
                 w,
-                "{carriage_return}{btotal} bytes copied, {duration:.1} s, {transfer_rate}/s{newline}",
+                "{carriage_return}{btotal} bytes copied, {duration}, {transfer_rate}/s{newline}",
             ),
             1000..=1023 => write!(
                 w,
-                "{carriage_return}{btotal} bytes ({btotal_metric}) copied, {duration:.1} s, {transfer_rate}/s{newline}",
+                "{carriage_return}{btotal} bytes ({btotal_metric}) copied, {duration}, {transfer_rate}/s{newline}",
             ),
             _ => write!(
                 w,
-                "{carriage_return}{btotal} bytes ({btotal_metric}, {btotal_bin}) copied, {duration:.1} s, {transfer_rate}/s{newline}",
+                "{carriage_return}{btotal} bytes ({btotal_metric}, {btotal_bin}) copied, {duration}, {transfer_rate}/s{newline}",
             ),
         }
     }
@@ -220,11 +224,20 @@ impl ProgUpdate {
 
     /// Re-print the number of bytes written, duration, and throughput.
     ///
+    /// Spaces are added to erase the rest of the previous line, which
+    /// was `previous_len` characters long. Returns the length of the
+    /// new line.
+    ///
     /// See [`ProgUpdate::write_prog_line`] for more information.
-    pub(crate) fn reprint_prog_line(&self) {
-        let mut stderr = std::io::stderr();
+    pub(crate) fn reprint_prog_line(&self, previous_len: usize) -> usize {
+        let mut line = vec![];
         let rewrite = true;
-        self.write_prog_line(&mut stderr, rewrite).unwrap();
+        self.write_prog_line(&mut line, rewrite).unwrap();
+        // Do not count the carriage return.
+        let len = line.len() - 1;
+        line.resize(line.len() + previous_len.saturating_sub(len), b' ');
+        std::io::stderr().write_all(&line).unwrap();
+        len
     }
 
     /// Write all summary statistics.
@@ -236,6 +249,8 @@ impl ProgUpdate {
     }
 
     /// Write all the final statistics.
+    ///
+    /// This is also what is printed when the info signal is received.
     pub(crate) fn print_final_stats(
         &self,
         print_level: Option<StatusLevel>,
@@ -269,6 +284,9 @@ pub(crate) struct ReadStat {
     ///
     /// A truncated record can only occur in `conv=block` mode.
     pub(crate) records_truncated: u32,
+
+    /// The total number of bytes read, not counting the padding added by `conv=sync`.
+    pub(crate) bytes_total: u128,
 }
 
 impl ReadStat {
@@ -279,6 +297,7 @@ impl ReadStat {
             reads_complete: complete,
             reads_partial: partial,
             records_truncated: truncated,
+            bytes_total: 0,
         }
     }
 
@@ -308,6 +327,7 @@ impl std::ops::AddAssign for ReadStat {
             reads_complete: self.reads_complete + other.reads_complete,
             reads_partial: self.reads_partial + other.reads_partial,
             records_truncated: self.records_truncated + other.records_truncated,
+            bytes_total: self.bytes_total + other.bytes_total,
         }
     }
 }
@@ -387,90 +407,95 @@ pub(crate) enum StatusLevel {
     None,
 }
 
-/// Return a closure that can be used in its own thread to print progress info.
+/// A flag raised when the user asks for the transfer statistics.
 ///
-/// This function returns a closure that receives [`ProgUpdate`]
-/// instances sent through `rx`. When a [`ProgUpdate`] instance is
-/// received, the transfer statistics are re-printed to stderr.
-#[cfg(not(target_os = "linux"))]
-pub(crate) fn gen_prog_updater(
-    rx: mpsc::Receiver<ProgUpdate>,
-    print_level: Option<StatusLevel>,
-) -> impl Fn() {
-    move || {
-        let mut progress_printed = false;
-        while let Ok(update) = rx.recv() {
-            // Print the final read/write statistics.
-            if update.complete {
-                update.print_final_stats(print_level, progress_printed);
-                return;
-            }
-            if Some(StatusLevel::Progress) == print_level {
-                update.reprint_prog_line();
-                progress_printed = true;
+/// Like GNU dd, this is done with `SIGINFO` where it exists, and with
+/// `SIGUSR1` otherwise, unless `POSIXLY_CORRECT` is set.
+#[derive(Clone, Default)]
+pub(crate) struct InfoSignal(Arc<AtomicBool>);
+
+#[cfg(any(
+    target_os = "macos",
+    target_os = "ios",
+    target_os = "freebsd",
+    target_os = "dragonfly",
+    target_os = "netbsd",
+    target_os = "openbsd"
+))]
+const INFO_SIGNAL: libc::c_int = libc::SIGINFO;
+
+#[cfg(all(
+    unix,
+    not(any(
+        target_os = "macos",
+        target_os = "ios",
+        target_os = "freebsd",
+        target_os = "dragonfly",
+        target_os = "netbsd",
+        target_os = "openbsd"
+    ))
+))]
+const INFO_SIGNAL: libc::c_int = libc::SIGUSR1;
+
+impl InfoSignal {
+    /// Register the signal handler raising the flag.
+    ///
+    /// This must be done before starting the transfer, as the default
+    /// action of `SIGUSR1` is to terminate the process.
+    #[cfg(unix)]
+    pub(crate) fn register(print_level: Option<StatusLevel>) -> Self {
+        let info_signal = Self::default();
+        if INFO_SIGNAL == libc::SIGUSR1 && std::env::var_os("POSIXLY_CORRECT").is_some() {
+            return info_signal;
+        }
+        if let Err(e) = signal_hook::flag::register(INFO_SIGNAL, info_signal.0.clone()) {
+            if Some(StatusLevel::None) != print_level {
+                eprintln!("Internal dd Warning: Unable to register signal handler \n\t{e}");
             }
         }
+        info_signal
+    }
+
+    #[cfg(not(unix))]
+    pub(crate) fn register(_print_level: Option<StatusLevel>) -> Self {
+        Self::default()
+    }
+
+    /// Whether the signal was received since the last call.
+    pub(crate) fn received(&self) -> bool {
+        // Only write to the flag when it is raised, as this is called after every block.
+        self.0.load(Ordering::Relaxed) && self.0.swap(false, Ordering::Relaxed)
     }
 }
 
 /// Return a closure that can be used in its own thread to print progress info.
 ///
 /// This function returns a closure that receives [`ProgUpdate`]
-/// instances sent through `rx`. When a [`ProgUpdate`] instance is
-/// received, the transfer statistics are re-printed to stderr.
-///
-/// The closure also registers a signal handler for `SIGUSR1`. When
-/// the `SIGUSR1` signal is sent to this process, the transfer
-/// statistics are printed to stderr.
-#[cfg(target_os = "linux")]
+/// instances sent through `rx`. The progress line is re-printed to
+/// stderr on periodic updates, and all the transfer statistics are
+/// printed on the final update or when an [`InfoSignal`] was received.
 pub(crate) fn gen_prog_updater(
     rx: mpsc::Receiver<ProgUpdate>,
     print_level: Option<StatusLevel>,
 ) -> impl Fn() {
-    // TODO: SIGINFO: Trigger progress line reprint. BSD-style Linux only.
-    const SIGUSR1_USIZE: usize = signal::SIGUSR1 as usize;
-    fn posixly_correct() -> bool {
-        env::var("POSIXLY_CORRECT").is_ok()
-    }
-    fn register_linux_signal_handler(sigval: Arc<AtomicUsize>) -> Result<(), Box<dyn Error>> {
-        if !posixly_correct() {
-            signal_hook::flag::register_usize(signal::SIGUSR1, sigval, SIGUSR1_USIZE)?;
-        }
-
-        Ok(())
-    }
-    // --------------------------------------------------------------
     move || {
-        let sigval = Arc::new(AtomicUsize::new(0));
-
-        register_linux_signal_handler(sigval.clone()).unwrap_or_else(|e| {
-            if Some(StatusLevel::None) != print_level {
-                eprintln!("Internal dd Warning: Unable to register signal handler \n\t{e}");
-            }
-        });
-
-        // Holds the state of whether we have printed the current progress.
-        // This is needed so that we know whether or not to print a newline
+        // The length of the progress line currently displayed, if any.
+        // We need to know whether there is one to print a newline
         // character before outputting non-progress data.
-        let mut progress_printed = false;
+        let mut progress_len = 0;
         while let Ok(update) = rx.recv() {
-            // Print the final read/write statistics.
-            if update.complete {
-                update.print_final_stats(print_level, progress_printed);
-                return;
-            }
-            // (Re)print status line if progress is requested.
-            if Some(StatusLevel::Progress) == print_level && !update.complete {
-                update.reprint_prog_line();
-                progress_printed = true;
-            }
-            // Handle signals and set the signal to un-seen.
-            // This will print a maximum of 1 time per second, even though it
-            // should be printing on every SIGUSR1.
-            if let SIGUSR1_USIZE = sigval.swap(0, Ordering::Relaxed) {
-                update.print_transfer_stats(progress_printed);
-                // Reset the progress printed, since print_transfer_stats always prints a newline.
-                progress_printed = false;
+            match update.update_type {
+                ProgUpdateType::Periodic => {
+                    progress_len = update.reprint_prog_line(progress_len);
+                }
+                ProgUpdateType::Signal => {
+                    update.print_final_stats(print_level, progress_len > 0);
+                    progress_len = 0;
+                }
+                ProgUpdateType::Final => {
+                    update.print_final_stats(print_level, progress_len > 0);
+                    return;
+                }
             }
         }
     }
@@ -482,7 +507,7 @@ mod tests {
     use std::io::Cursor;
     use std::time::Duration;
 
-    use super::{ProgUpdate, ReadStat, WriteStat};
+    use super::{ProgUpdate, ProgUpdateType, ReadStat, WriteStat};
 
     fn prog_update_write(n: u128) -> ProgUpdate {
         ProgUpdate {
@@ -492,7 +517,7 @@ mod tests {
                 ..Default::default()
             },
             duration: Duration::new(1, 0), // one second
-            complete: false,
+            update_type: ProgUpdateType::Periodic,
         }
     }
 
@@ -517,12 +542,12 @@ mod tests {
         let read_stat = ReadStat::new(1, 2, 3);
         let write_stat = WriteStat::new(4, 5, 6);
         let duration = Duration::new(789, 0);
-        let complete = false;
+        let update_type = ProgUpdateType::Periodic;
         let prog_update = ProgUpdate {
             read_stat,
             write_stat,
             duration,
-            complete,
+            update_type,
         };
 
         let mut cursor = Cursor::new(vec![]);
@@ -539,7 +564,7 @@ mod tests {
             read_stat: Default::default(),
             write_stat: Default::default(),
             duration: Duration::new(1, 0), // one second
-            complete: false,
+            update_type: ProgUpdateType::Periodic,
         };
 
         let mut cursor = Cursor::new(vec![]);
@@ -595,7 +620,7 @@ mod tests {
             read_stat: Default::default(),
             write_stat: Default::default(),
             duration: Duration::new(1, 0), // one second
-            complete: false,
+            update_type: ProgUpdateType::Periodic,
         };
         let mut cursor = Cursor::new(vec![]);
         prog_update
@@ -616,14 +641,14 @@ mod tests {
             read_stat: Default::default(),
             write_stat: Default::default(),
             duration: Duration::new(1, 0), // one second
-            complete: false,
+            update_type: ProgUpdateType::Periodic,
         };
         let mut cursor = Cursor::new(vec![]);
         let rewrite = true;
         prog_update.write_prog_line(&mut cursor, rewrite).unwrap();
         prog_update.write_transfer_stats(&mut cursor, true).unwrap();
         let mut iter = cursor.get_ref().split(|v| *v == b'\n');
-        assert_eq!(iter.next().unwrap(), b"\r0 bytes copied, 1.0 s, 0.0 B/s");
+        assert_eq!(iter.next().unwrap(), b"\r0 bytes copied, 1 s, 0.0 B/s");
         assert_eq!(iter.next().unwrap(), b"0+0 records in");
         assert_eq!(iter.next().unwrap(), b"0+0 records out");
         assert_eq!(iter.next().unwrap(), b"0 bytes copied, 1.0 s, 0.0 B/s");
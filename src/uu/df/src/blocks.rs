@@ -5,7 +5,7 @@
 //! Types for representing and displaying block sizes.
 use crate::{OPT_BLOCKSIZE, OPT_PORTABILITY};
 use clap::ArgMatches;
-use std::{env, fmt};
+use std::{cmp::Ordering, env, fmt};
 
 use uucore::{
     display::Quotable,
@@ -40,21 +40,19 @@ const SI_BASES: [u128; 10] = [
     1_000_000_000_000_000_000_000_000_000,
 ];
 
-/// A SuffixType determines whether the suffixes are 1000 or 1024 based, and whether they are
-/// intended for HumanReadable mode or not.
+/// A SuffixType determines whether the suffixes are 1000 or 1024 based.
 #[derive(Clone, Copy)]
 pub(crate) enum SuffixType {
     Iec,
     Si,
-    HumanReadable(HumanReadable),
 }
 
 impl SuffixType {
     /// The first ten powers of 1024 and 1000, respectively.
     fn bases(&self) -> [u128; 10] {
         match self {
-            Self::Iec | Self::HumanReadable(HumanReadable::Binary) => IEC_BASES,
-            Self::Si | Self::HumanReadable(HumanReadable::Decimal) => SI_BASES,
+            Self::Iec => IEC_BASES,
+            Self::Si => SI_BASES,
         }
     }
 
@@ -64,12 +62,6 @@ impl SuffixType {
             // we use "kB" instead of "KB", same as GNU df
             Self::Si => ["B", "kB", "MB", "GB", "TB", "PB", "EB", "ZB", "YB"],
             Self::Iec => ["B", "K", "M", "G", "T", "P", "E", "Z", "Y"],
-            Self::HumanReadable(HumanReadable::Binary) => {
-                ["", "K", "M", "G", "T", "P", "E", "Z", "Y"]
-            }
-            Self::HumanReadable(HumanReadable::Decimal) => {
-                ["", "k", "M", "G", "T", "P", "E", "Z", "Y"]
-            }
         }
     }
 }
@@ -105,6 +97,65 @@ pub(crate) fn to_magnitude_and_suffix(n: u128, suffix_type: SuffixType) -> Strin
     }
 }
 
+/// Convert a number into a human-readable string, like "4.0K" or "252G".
+///
+/// Like GNU `df`, the number is rounded up, and values below ten are
+/// shown with one decimal place.
+pub(crate) fn to_human_readable(n: u128, human_readable: HumanReadable) -> String {
+    let (base, suffixes) = match human_readable {
+        HumanReadable::Binary => (1024, ["K", "M", "G", "T", "P", "E", "Z", "Y"]),
+        HumanReadable::Decimal => (1000, ["k", "M", "G", "T", "P", "E", "Z", "Y"]),
+    };
+
+    // `tenths` is the first digit of the fractional part, and
+    // `rounding` describes the rest of it: 0 if it is zero, 1 if it is
+    // less than a half, 2 if it is a half, and 3 if it is more.
+    let mut amount = n;
+    let mut tenths = 0;
+    let mut rounding = 0;
+    let mut exponent = 0;
+    while amount >= base && exponent < suffixes.len() {
+        let r10 = (amount % base) * 10 + tenths;
+        let r2 = (r10 % base) * 2 + (rounding >> 1);
+        amount /= base;
+        tenths = r10 / base;
+        rounding = match r2.cmp(&base) {
+            Ordering::Less => u128::from(r2 != 0),
+            Ordering::Equal => 2,
+            Ordering::Greater => 3,
+        };
+        exponent += 1;
+    }
+
+    let mut fraction = "";
+    if exponent > 0 && amount < 10 {
+        if rounding > 0 {
+            tenths += 1;
+            rounding = 0;
+            if tenths == 10 {
+                amount += 1;
+                tenths = 0;
+            }
+        }
+        if amount < 10 {
+            return format!("{amount}.{tenths}{}", suffixes[exponent - 1]);
+        }
+    }
+    if tenths + rounding > 0 {
+        amount += 1;
+        if amount == base && exponent < suffixes.len() {
+            exponent += 1;
+            amount = 1;
+            fraction = ".0";
+        }
+    }
+
+    match exponent {
+        0 => format!("{amount}"),
+        _ => format!("{amount}{fraction}{}", suffixes[exponent - 1]),
+    }
+}
+
 /// A mode to use in condensing the human readable display of a large number
 /// of bytes.
 ///
@@ -216,7 +267,9 @@ mod tests {
 
     use std::env;
 
-    use crate::blocks::{to_magnitude_and_suffix, BlockSize, SuffixType};
+    use crate::blocks::{
+        to_human_readable, to_magnitude_and_suffix, BlockSize, HumanReadable, SuffixType,
+    };
 
     #[test]
     fn test_to_magnitude_and_suffix_powers_of_1024() {
@@ -276,6 +329,27 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_to_human_readable() {
+        let binary = |n| to_human_readable(n, HumanReadable::Binary);
+        assert_eq!(binary(0), "0");
+        assert_eq!(binary(1023), "1023");
+        assert_eq!(binary(1024), "1.0K");
+        assert_eq!(binary(1025), "1.1K");
+        assert_eq!(binary(10 * 1024 - 1), "10K");
+        assert_eq!(binary(10 * 1024), "10K");
+        assert_eq!(binary(10 * 1024 + 1), "11K");
+        assert_eq!(binary(1024 * 1024 - 1), "1.0M");
+        assert_eq!(binary(3_140_341_760), "3.0G");
+
+        let decimal = |n| to_human_readable(n, HumanReadable::Decimal);
+        assert_eq!(decimal(999), "999");
+        assert_eq!(decimal(1000), "1.0k");
+        assert_eq!(decimal(4000), "4.0k");
+        assert_eq!(decimal(999_001), "1.0M");
+        assert_eq!(decimal(16_777_216), "17M");
+    }
+
     #[test]
     fn test_block_size_display() {
         assert_eq!(format!("{}", BlockSize::Bytes(1024)), "1K");
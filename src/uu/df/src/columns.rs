@@ -3,7 +3,7 @@
 //  * For the full copyright and license information, please view the LICENSE
 //  * file that was distributed with this source code.
 // spell-checker:ignore itotal iused iavail ipcent pcent squashfs
-use crate::{OPT_INODES, OPT_OUTPUT, OPT_PRINT_TYPE};
+use crate::{OPT_INODES, OPT_OUTPUT, OPT_PORTABILITY, OPT_PRINT_TYPE};
 use clap::{parser::ValueSource, ArgMatches};
 
 /// The columns in the output table produced by `df`.
@@ -60,6 +60,12 @@ pub(crate) enum Column {
 pub(crate) enum ColumnError {
     /// If a column appears more than once in the `--output` argument.
     MultipleColumns(String),
+
+    /// If a column name in the `--output` argument is not known.
+    UnknownColumn(String),
+
+    /// If `--output` is combined with one of `-i`, `-P`, or `-T`.
+    MutuallyExclusive(&'static str),
 }
 
 impl Column {
@@ -71,9 +77,13 @@ impl Column {
     ///
     /// # Errors
     ///
-    /// This function returns an error if a column is specified more
-    /// than once in the command-line argument.
+    /// This function returns an error if a column is unknown or
+    /// specified more than once in the command-line argument, or if
+    /// `--output` is combined with an option that selects columns.
     pub(crate) fn from_matches(matches: &ArgMatches) -> Result<Vec<Self>, ColumnError> {
+        if matches.value_source(OPT_OUTPUT) == Some(ValueSource::CommandLine) {
+            Self::check_conflicts(matches)?;
+        }
         match (
             matches.get_flag(OPT_PRINT_TYPE),
             matches.get_flag(OPT_INODES),
@@ -103,12 +113,9 @@ impl Column {
                     if seen.contains(&name) {
                         return Err(ColumnError::MultipleColumns(name.to_string()));
                     }
+                    let column = Self::parse(name)
+                        .map_err(|_| ColumnError::UnknownColumn(name.to_string()))?;
                     seen.push(name);
-                    // Unwrapping here should not panic because the
-                    // command-line argument parsing library should be
-                    // responsible for ensuring each comma-separated
-                    // string is a valid column label.
-                    let column = Self::parse(name).unwrap();
                     columns.push(column);
                 }
                 Ok(columns)
@@ -142,13 +149,39 @@ impl Column {
                 Self::Target,
             ]),
             // The command-line arguments -T and -i are each mutually
-            // exclusive with --output, so the command-line argument
-            // parser should reject those combinations before we get
-            // to this point in the code.
+            // exclusive with --output, so `check_conflicts()` should
+            // reject those combinations before we get to this point in
+            // the code.
             _ => unreachable!(),
         }
     }
 
+    /// Find an option that must not be combined with `--output`.
+    ///
+    /// Like GNU `df`, an option given before `--output` is reported in
+    /// the order `-i`, `-P`, `-T`, otherwise the first option given
+    /// after `--output` is reported.
+    fn check_conflicts(matches: &ArgMatches) -> Result<(), ColumnError> {
+        let output_index = matches.index_of(OPT_OUTPUT).unwrap_or(0);
+        let conflicts: Vec<(&'static str, usize)> = [
+            (OPT_INODES, "-i"),
+            (OPT_PORTABILITY, "-P"),
+            (OPT_PRINT_TYPE, "-T"),
+        ]
+        .iter()
+        .filter(|(id, _)| matches.get_flag(id))
+        .filter_map(|(id, name)| Some((*name, matches.index_of(id)?)))
+        .collect();
+        let conflict = conflicts
+            .iter()
+            .find(|(_, index)| *index < output_index)
+            .or_else(|| conflicts.iter().min_by_key(|(_, index)| *index));
+        match conflict {
+            Some((name, _)) => Err(ColumnError::MutuallyExclusive(name)),
+            None => Ok(()),
+        }
+    }
+
     /// Convert a column name to the corresponding enumeration variant.
     ///
     /// There are twelve valid column names, one for each variant:
@@ -200,7 +233,8 @@ impl Column {
         match column {
             // 14 = length of "Filesystem" plus 4 spaces
             Self::Source => 14,
-            Self::Used => 5,
+            // 5 = length of the widest human-readable value, like "1023K"
+            Self::Size | Self::Used | Self::Avail | Self::Itotal | Self::Iused | Self::Iavail => 5,
             // the shortest headers have a length of 4 chars so we use that as the minimum width
             _ => 4,
         }
@@ -74,6 +74,12 @@ static OUTPUT_FIELD_LIST: [&str; 12] = [
 struct Options {
     show_local_fs: bool,
     show_all_fs: bool,
+
+    /// Whether files were given on the command line.
+    ///
+    /// Dummy and empty filesystems containing these files are listed
+    /// too, like with `--all`.
+    show_listed_fs: bool,
     human_readable: Option<HumanReadable>,
     block_size: BlockSize,
     header_mode: HeaderMode,
@@ -105,6 +111,7 @@ impl Default for Options {
         Self {
             show_local_fs: Default::default(),
             show_all_fs: Default::default(),
+            show_listed_fs: Default::default(),
             block_size: BlockSize::default(),
             human_readable: Option::default(),
             header_mode: HeaderMode::default(),
@@ -155,6 +162,12 @@ impl fmt::Display for OptionsError {
                 "option --output: field {} used more than once",
                 s.quote()
             ),
+            Self::ColumnError(ColumnError::UnknownColumn(s)) => {
+                write!(f, "option --output: field {} unknown", s.quote())
+            }
+            Self::ColumnError(ColumnError::MutuallyExclusive(s)) => {
+                write!(f, "options {s} and --output are mutually exclusive")
+            }
             #[allow(clippy::print_in_format_impl)]
             Self::FilesystemTypeBothSelectedAndExcluded(types) => {
                 for t in types {
@@ -189,6 +202,7 @@ impl Options {
         Ok(Self {
             show_local_fs: matches.get_flag(OPT_LOCAL),
             show_all_fs: matches.get_flag(OPT_ALL),
+            show_listed_fs: matches.contains_id(OPT_PATHS),
             sync: matches.get_flag(OPT_SYNC),
             block_size: read_block_size(matches).map_err(|e| match e {
                 ParseSizeError::InvalidSuffix(s) => OptionsError::InvalidSuffix(s),
@@ -251,8 +265,8 @@ fn is_included(mi: &MountInfo, opt: &Options) -> bool {
         return false;
     }
 
-    // Don't show pseudo filesystems unless `--all` has been given.
-    if mi.dummy && !opt.show_all_fs {
+    // Don't show pseudo filesystems unless `--all` or files have been given.
+    if mi.dummy && !opt.show_all_fs && !opt.show_listed_fs {
         return false;
     }
 
@@ -321,7 +335,7 @@ fn is_best(previous: &[MountInfo], mi: &MountInfo) -> bool {
 /// [`MountInfo`] instances; see [`Options`] for more information.
 ///
 /// Finally, if there are duplicate entries, the one with the shorter
-/// path is kept.
+/// path is kept, unless `--all` has been given.
 
 fn filter_mount_list(vmi: Vec<MountInfo>, opt: &Options) -> Vec<MountInfo> {
     let mut result = vec![];
@@ -331,7 +345,7 @@ fn filter_mount_list(vmi: Vec<MountInfo>, opt: &Options) -> Vec<MountInfo> {
         // this loop quadratic in the length of `vmi`. This could be
         // improved by a more efficient implementation of `is_best()`,
         // but `vmi` is probably not very long in practice.
-        if is_included(&mi, opt) && is_best(&result, &mi) {
+        if is_included(&mi, opt) && (opt.show_all_fs || is_best(&result, &mi)) {
             result.push(mi);
         }
     }
@@ -377,41 +391,44 @@ where
 {
     // The list of all mounted filesystems.
     //
-    // Filesystems marked as `dummy` or of type "lofs" are not
-    // considered. The "lofs" filesystem is a loopback
-    // filesystem present on Solaris and FreeBSD systems. It
-    // is similar to a symbolic link.
-    let mounts: Vec<MountInfo> = filter_mount_list(read_fs_list()?, opt)
+    // Filesystems of type "lofs" are not considered. The "lofs"
+    // filesystem is a loopback filesystem present on Solaris and
+    // FreeBSD systems. It is similar to a symbolic link.
+    //
+    // Like GNU `df`, the filesystem containing each path is looked up
+    // among all mounted filesystems, and only then are the
+    // command-line options used to decide whether to display it.
+    let mounts: Vec<MountInfo> = read_fs_list()?
         .into_iter()
-        .filter(|mi| mi.fs_type != "lofs" && !mi.dummy)
+        .filter(|mi| mi.fs_type != "lofs")
         .collect();
 
     let mut result = vec![];
-
-    // this happens if the file system type doesn't exist
-    if mounts.is_empty() {
-        show!(USimpleError::new(1, "no file systems processed"));
-        return Ok(result);
-    }
+    let mut has_errors = false;
 
     // Convert each path into a `Filesystem`, which contains
     // both the mount information and usage information.
     for path in paths {
         match Filesystem::from_path(&mounts, path) {
-            Some(fs) => result.push(fs),
-            None => {
-                // this happens if specified file system type != file system type of the file
-                if path.as_ref().exists() {
-                    show!(USimpleError::new(1, "no file systems processed"));
-                } else {
-                    show!(USimpleError::new(
-                        1,
-                        format!("{}: No such file or directory", path.as_ref().display())
-                    ));
+            Some(fs) => {
+                if is_included(&fs.mount_info, opt) {
+                    result.push(fs);
                 }
             }
+            None => {
+                has_errors = true;
+                show!(USimpleError::new(
+                    1,
+                    format!("{}: No such file or directory", path.as_ref().display())
+                ));
+            }
         }
     }
+
+    // this happens if the file system type of every path is excluded
+    if result.is_empty() && !has_errors {
+        show!(USimpleError::new(1, "no file systems processed"));
+    }
     Ok(result)
 }
 
@@ -576,10 +593,8 @@ pub fn uu_app() -> Command {
                 .num_args(0..)
                 .require_equals(true)
                 .use_value_delimiter(true)
-                .value_parser(OUTPUT_FIELD_LIST)
                 .default_missing_values(OUTPUT_FIELD_LIST)
                 .default_values(["source", "size", "used", "avail", "pcent", "target"])
-                .conflicts_with_all([OPT_INODES, OPT_PORTABILITY, OPT_PRINT_TYPE])
                 .help(
                     "use the output format defined by FIELD_LIST, \
                      or print all fields if FIELD_LIST is omitted.",
@@ -9,7 +9,7 @@
 //! collection of data rows ([`Row`]), one per filesystem.
 use unicode_width::UnicodeWidthStr;
 
-use crate::blocks::{to_magnitude_and_suffix, SuffixType};
+use crate::blocks::to_human_readable;
 use crate::columns::{Alignment, Column};
 use crate::filesystem::Filesystem;
 use crate::{BlockSize, Options};
@@ -223,7 +223,7 @@ impl<'a> RowFormatter<'a> {
     /// The scaling factor is defined in the `options` field.
     fn scaled_bytes(&self, size: u64) -> String {
         if let Some(h) = self.options.human_readable {
-            to_magnitude_and_suffix(size.into(), SuffixType::HumanReadable(h))
+            to_human_readable(size.into(), h)
         } else {
             let BlockSize::Bytes(d) = self.options.block_size;
             (size as f64 / d as f64).ceil().to_string()
@@ -235,7 +235,7 @@ impl<'a> RowFormatter<'a> {
     /// The scaling factor is defined in the `options` field.
     fn scaled_inodes(&self, size: u64) -> String {
         if let Some(h) = self.options.human_readable {
-            to_magnitude_and_suffix(size.into(), SuffixType::HumanReadable(h))
+            to_human_readable(size.into(), h)
         } else {
             size.to_string()
         }
@@ -387,7 +387,7 @@ impl Table {
             // If the filesystem is not empty, or if the options require
             // showing all filesystems, then print the data as a row in
             // the output table.
-            if options.show_all_fs || filesystem.usage.blocks > 0 {
+            if options.show_all_fs || options.show_listed_fs || filesystem.usage.blocks > 0 {
                 let row = Row::from(filesystem);
                 let fmt = RowFormatter::new(&row, options, false);
                 let values = fmt.get_values();
@@ -748,7 +748,15 @@ mod tests {
         let fmt = RowFormatter::new(&row, &options, false);
         assert_eq!(
             fmt.get_values(),
-            vec!("my_device", "my_type", "4k", "1k", "3k", "25%", "my_mount")
+            vec!(
+                "my_device",
+                "my_type",
+                "4.0k",
+                "1.0k",
+                "3.0k",
+                "25%",
+                "my_mount"
+            )
         );
     }
 
@@ -774,7 +782,15 @@ mod tests {
         let fmt = RowFormatter::new(&row, &options, false);
         assert_eq!(
             fmt.get_values(),
-            vec!("my_device", "my_type", "4K", "1K", "3K", "25%", "my_mount")
+            vec!(
+                "my_device",
+                "my_type",
+                "4.0K",
+                "1.0K",
+                "3.0K",
+                "25%",
+                "my_mount"
+            )
         );
     }
 
@@ -5,6 +5,7 @@
 //  * For the full copyright and license information, please view the LICENSE
 //  * file that was distributed with this source code.
 
+use chrono::format::{Item, StrftimeItems};
 use chrono::prelude::DateTime;
 use chrono::Local;
 use clap::ArgAction;
@@ -14,8 +15,6 @@ use std::collections::HashSet;
 use std::env;
 use std::fs;
 use std::fs::File;
-#[cfg(not(windows))]
-use std::fs::Metadata;
 use std::io::BufRead;
 use std::io::BufReader;
 use std::io::Result;
@@ -26,10 +25,11 @@ use std::os::unix::fs::MetadataExt;
 use std::os::windows::fs::MetadataExt;
 #[cfg(windows)]
 use std::os::windows::io::AsRawHandle;
+#[cfg(windows)]
 use std::path::Path;
 use std::path::PathBuf;
 use std::str::FromStr;
-use std::time::{Duration, UNIX_EPOCH};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use std::{error::Error, fmt::Display};
 use uucore::display::{print_verbatim, Quotable};
 use uucore::error::FromIo;
@@ -107,6 +107,15 @@ struct Options {
     verbose: bool,
 }
 
+/// The time to display with `--time`.
+#[derive(Clone, Copy)]
+enum Time {
+    Accessed,
+    Modified,
+    Changed,
+    Birth,
+}
+
 #[derive(PartialEq, Eq, Hash, Clone, Copy)]
 struct FileInfo {
     file_id: u128,
@@ -120,9 +129,10 @@ struct Stat {
     blocks: u64,
     inodes: u64,
     inode: Option<FileInfo>,
-    created: Option<u64>,
-    accessed: u64,
-    modified: u64,
+    created: Option<SystemTime>,
+    accessed: SystemTime,
+    modified: SystemTime,
+    changed: SystemTime,
 }
 
 impl Stat {
@@ -146,9 +156,10 @@ impl Stat {
             blocks: metadata.blocks(),
             inodes: 1,
             inode: Some(file_info),
-            created: birth_u64(&metadata),
-            accessed: metadata.atime() as u64,
-            modified: metadata.mtime() as u64,
+            created: metadata.created().ok(),
+            accessed: unix_time(metadata.atime(), metadata.atime_nsec()),
+            modified: unix_time(metadata.mtime(), metadata.mtime_nsec()),
+            changed: unix_time(metadata.ctime(), metadata.ctime_nsec()),
         });
 
         #[cfg(windows)]
@@ -156,6 +167,8 @@ impl Stat {
         #[cfg(windows)]
         let file_info = get_file_info(&path);
         #[cfg(windows)]
+        let modified = windows_time_to_unix_time(metadata.last_write_time());
+        #[cfg(windows)]
         Ok(Self {
             path,
             is_dir: metadata.is_dir(),
@@ -165,30 +178,62 @@ impl Stat {
             inodes: 1,
             created: windows_creation_time_to_unix_time(metadata.creation_time()),
             accessed: windows_time_to_unix_time(metadata.last_access_time()),
-            modified: windows_time_to_unix_time(metadata.last_write_time()),
+            modified,
+            // Windows has no status change time.
+            changed: modified,
         })
     }
+
+    /// Add the usage of a file or directory below this one.
+    ///
+    /// Like GNU `du`, the times of a directory are the latest times of
+    /// any file in it, or in any of its subdirectories.
+    fn add(&mut self, other: &Self) {
+        self.size += other.size;
+        self.blocks += other.blocks;
+        self.inodes += other.inodes;
+        self.created = self.created.max(other.created);
+        self.accessed = self.accessed.max(other.accessed);
+        self.modified = self.modified.max(other.modified);
+        self.changed = self.changed.max(other.changed);
+    }
+
+    /// Get the time to display with `--time`.
+    fn time(&self, time: Time) -> Option<SystemTime> {
+        match time {
+            Time::Accessed => Some(self.accessed),
+            Time::Modified => Some(self.modified),
+            Time::Changed => Some(self.changed),
+            Time::Birth => self.created,
+        }
+    }
 }
 
 #[cfg(windows)]
 // https://doc.rust-lang.org/std/os/windows/fs/trait.MetadataExt.html#tymethod.last_access_time
 // "The returned 64-bit value [...] which represents the number of 100-nanosecond intervals since January 1, 1601 (UTC)."
 // "If the underlying filesystem does not support last access time, the returned value is 0."
-fn windows_time_to_unix_time(win_time: u64) -> u64 {
-    (win_time / 10_000_000).saturating_sub(11_644_473_600)
+fn windows_time_to_unix_time(win_time: u64) -> SystemTime {
+    UNIX_EPOCH + Duration::from_secs((win_time / 10_000_000).saturating_sub(11_644_473_600))
 }
 
 #[cfg(windows)]
-fn windows_creation_time_to_unix_time(win_time: u64) -> Option<u64> {
-    (win_time / 10_000_000).checked_sub(11_644_473_600)
+fn windows_creation_time_to_unix_time(win_time: u64) -> Option<SystemTime> {
+    (win_time / 10_000_000)
+        .checked_sub(11_644_473_600)
+        .map(|secs| UNIX_EPOCH + Duration::from_secs(secs))
 }
 
+/// Convert seconds and nanoseconds since the epoch, as found in the
+/// `stat` structure, into a [`SystemTime`].
 #[cfg(not(windows))]
-fn birth_u64(meta: &Metadata) -> Option<u64> {
-    meta.created()
-        .ok()
-        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
-        .map(|e| e.as_secs())
+fn unix_time(secs: i64, nsecs: i64) -> SystemTime {
+    let nsecs = Duration::from_nanos(nsecs as u64);
+    if secs >= 0 {
+        UNIX_EPOCH + Duration::from_secs(secs as u64) + nsecs
+    } else {
+        UNIX_EPOCH - Duration::from_secs(secs.unsigned_abs()) + nsecs
+    }
 }
 
 #[cfg(windows)]
@@ -312,21 +357,13 @@ fn du(
                 Ok(entry) => {
                     match Stat::new(entry.path(), options) {
                         Ok(this_stat) => {
-                            // We have an exclude list
-                            for pattern in exclude {
-                                // Look at all patterns with both short and long paths
-                                // if we have 'du foo' but search to exclude 'foo/bar'
-                                // we need the full path
-                                if pattern.matches(&this_stat.path.to_string_lossy())
-                                    || pattern.matches(&entry.file_name().into_string().unwrap())
-                                {
-                                    // if the directory is ignored, leave early
-                                    if options.verbose {
-                                        println!("{} ignored", &this_stat.path.quote());
-                                    }
-                                    // Go to the next file
-                                    continue 'file_loop;
+                            if is_excluded(exclude, &this_stat.path.to_string_lossy()) {
+                                // if the directory is ignored, leave early
+                                if options.verbose {
+                                    println!("{} ignored", &this_stat.path.quote());
                                 }
+                                // Go to the next file
+                                continue 'file_loop;
                             }
 
                             if let Some(inode) = this_stat.inode {
@@ -347,9 +384,7 @@ fn du(
                                 }
                                 futures.push(du(this_stat, options, depth + 1, inodes, exclude));
                             } else {
-                                my_stat.size += this_stat.size;
-                                my_stat.blocks += this_stat.blocks;
-                                my_stat.inodes += 1;
+                                my_stat.add(&this_stat);
                                 if options.all {
                                     stats.push(this_stat);
                                 }
@@ -367,9 +402,7 @@ fn du(
 
     stats.extend(futures.into_iter().flatten().filter(|stat| {
         if !options.separate_dirs && stat.path.parent().unwrap() == my_stat.path {
-            my_stat.size += stat.size;
-            my_stat.blocks += stat.blocks;
-            my_stat.inodes += stat.inodes;
+            my_stat.add(stat);
         }
         options
             .max_depth
@@ -417,6 +450,8 @@ enum DuError {
     SummarizeDepthConflict(String),
     InvalidTimeStyleArg(String),
     InvalidTimeArg(String),
+    InvalidTimeWord(String),
+    AmbiguousTimeWord(String),
     InvalidGlob(String),
 }
 
@@ -435,9 +470,9 @@ impl Display for DuError {
                 f,
                 "invalid argument {} for 'time style'
 Valid arguments are:
-- 'full-iso'
-- 'long-iso'
-- 'iso'
+  - 'full-iso'
+  - 'long-iso'
+  - 'iso'
 Try '{} --help' for more information.",
                 s.quote(),
                 uucore::execution_phrase()
@@ -448,6 +483,22 @@ Try '{} --help' for more information.",
 'birth' and 'creation' arguments are not supported on this platform.",
                 s.quote()
             ),
+            Self::InvalidTimeWord(s) | Self::AmbiguousTimeWord(s) => write!(
+                f,
+                "{} argument {} for '--time'
+Valid arguments are:
+  - 'atime', 'access', 'use'
+  - 'ctime', 'status'
+  - 'birth', 'creation'
+Try '{} --help' for more information.",
+                if matches!(self, Self::InvalidTimeWord(_)) {
+                    "invalid"
+                } else {
+                    "ambiguous"
+                },
+                s.quote(),
+                uucore::execution_phrase()
+            ),
             Self::InvalidGlob(s) => write!(f, "Invalid exclude syntax: {s}"),
         }
     }
@@ -462,28 +513,36 @@ impl UError for DuError {
             | Self::SummarizeDepthConflict(_)
             | Self::InvalidTimeStyleArg(_)
             | Self::InvalidTimeArg(_)
+            | Self::InvalidTimeWord(_)
+            | Self::AmbiguousTimeWord(_)
             | Self::InvalidGlob(_) => 1,
         }
     }
 }
 
-// Read a file and return each line in a vector of String
-fn file_as_vec(filename: impl AsRef<Path>) -> Vec<String> {
-    let file = File::open(filename).expect("no such file");
-    let buf = BufReader::new(file);
-
-    buf.lines()
-        .map(|l| l.expect("Could not parse line"))
-        .collect()
+// Read a file and return each non-empty line in a vector of String
+fn file_as_vec(filename: &str) -> UResult<Vec<String>> {
+    let file = File::open(filename).map_err_context(|| filename.maybe_quote().to_string())?;
+    let mut lines = vec![];
+    for line in BufReader::new(file).split(b'\n') {
+        let line = line.map_err_context(|| filename.maybe_quote().to_string())?;
+        if !line.is_empty() {
+            lines.push(String::from_utf8_lossy(&line).into_owned());
+        }
+    }
+    Ok(lines)
 }
 
 // Given the --exclude-from and/or --exclude arguments, returns the globset lists
 // to ignore the files
 fn build_exclude_patterns(matches: &ArgMatches) -> UResult<Vec<Pattern>> {
-    let exclude_from_iterator = matches
+    let mut exclude_from = vec![];
+    for filename in matches
         .get_many::<String>(options::EXCLUDE_FROM)
         .unwrap_or_default()
-        .flat_map(file_as_vec);
+    {
+        exclude_from.extend(file_as_vec(filename)?);
+    }
 
     let excludes_iterator = matches
         .get_many::<String>(options::EXCLUDE)
@@ -491,7 +550,7 @@ fn build_exclude_patterns(matches: &ArgMatches) -> UResult<Vec<Pattern>> {
         .map(|v| v.to_owned());
 
     let mut exclude_patterns = Vec::new();
-    for f in excludes_iterator.chain(exclude_from_iterator) {
+    for f in excludes_iterator.chain(exclude_from) {
         if matches.get_flag(options::VERBOSE) {
             println!("adding {:?} to the exclude list ", &f);
         }
@@ -503,6 +562,20 @@ fn build_exclude_patterns(matches: &ArgMatches) -> UResult<Vec<Pattern>> {
     Ok(exclude_patterns)
 }
 
+/// Whether a path matches one of the patterns given with `--exclude`.
+///
+/// Like GNU `du`, a pattern can match the whole path, or any suffix
+/// of it that starts after a `/`, so that `--exclude=b/c` excludes
+/// `a/b/c`.
+fn is_excluded(exclude: &[Pattern], path: &str) -> bool {
+    exclude.iter().any(|pattern| {
+        pattern.matches(path)
+            || path
+                .match_indices('/')
+                .any(|(i, _)| pattern.matches(&path[i + 1..]))
+    })
+}
+
 #[uucore::main]
 #[allow(clippy::cognitive_complexity)]
 pub fn uumain(args: impl uucore::Args) -> UResult<()> {
@@ -582,8 +655,18 @@ pub fn uumain(args: impl uucore::Args) -> UResult<()> {
         }
     };
 
-    let time_format_str =
-        parse_time_style(matches.get_one::<String>("time-style").map(|s| s.as_str()))?;
+    let time = if matches.contains_id(options::TIME) {
+        Some(parse_time(
+            matches.get_one::<String>(options::TIME).map(|s| s.as_str()),
+        )?)
+    } else {
+        None
+    };
+    let time_format_str = parse_time_style(
+        matches
+            .get_one::<String>(options::TIME_STYLE)
+            .map(|s| s.as_str()),
+    )?;
 
     let line_separator = if matches.get_flag(options::NULL) {
         "\0"
@@ -595,17 +678,12 @@ pub fn uumain(args: impl uucore::Args) -> UResult<()> {
 
     let mut grand_total = 0;
     'loop_file: for path_string in files {
-        // Skip if we don't want to ignore anything
-        if !&excludes.is_empty() {
-            for pattern in &excludes {
-                if pattern.matches(path_string) {
-                    // if the directory is ignored, leave early
-                    if options.verbose {
-                        println!("{} ignored", path_string.quote());
-                    }
-                    continue 'loop_file;
-                }
+        if is_excluded(&excludes, path_string) {
+            // if the directory is ignored, leave early
+            if options.verbose {
+                println!("{} ignored", path_string.quote());
             }
+            continue 'loop_file;
         }
 
         let path = PathBuf::from(&path_string);
@@ -624,30 +702,24 @@ pub fn uumain(args: impl uucore::Args) -> UResult<()> {
             for (index, stat) in iter.enumerate() {
                 let size = choose_size(&matches, &stat);
 
+                if options.total && index == (len - 1) {
+                    // The last element will be the total size of the the path under
+                    // path_string.  We add it to the grand total.
+                    grand_total += size;
+                }
+
                 if threshold.map_or(false, |threshold| threshold.should_exclude(size)) {
                     continue;
                 }
 
-                if matches.contains_id(options::TIME) {
-                    let tm = {
-                        let secs = {
-                            match matches.get_one::<String>(options::TIME) {
-                                Some(s) => match s.as_str() {
-                                    "ctime" | "status" => stat.modified,
-                                    "access" | "atime" | "use" => stat.accessed,
-                                    "birth" | "creation" => stat
-                                        .created
-                                        .ok_or_else(|| DuError::InvalidTimeArg(s.into()))?,
-                                    // below should never happen as clap already restricts the values.
-                                    _ => unreachable!("Invalid field for --time"),
-                                },
-                                None => stat.modified,
-                            }
-                        };
-                        DateTime::<Local>::from(UNIX_EPOCH + Duration::from_secs(secs))
-                    };
+                if let Some(time) = time {
+                    let tm = DateTime::<Local>::from(stat.time(time).ok_or_else(|| {
+                        DuError::InvalidTimeArg(
+                            matches.get_one::<String>(options::TIME).unwrap().into(),
+                        )
+                    })?);
                     if !summarize || index == len - 1 {
-                        let time_str = tm.format(time_format_str).to_string();
+                        let time_str = tm.format(&time_format_str).to_string();
                         print!("{}\t{}\t", convert_size(size), time_str);
                         print_verbatim(stat.path).unwrap();
                         print!("{line_separator}");
@@ -657,11 +729,6 @@ pub fn uumain(args: impl uucore::Args) -> UResult<()> {
                     print_verbatim(stat.path).unwrap();
                     print!("{line_separator}");
                 }
-                if options.total && index == (len - 1) {
-                    // The last element will be the total size of the the path under
-                    // path_string.  We add it to the grand total.
-                    grand_total += size;
-                }
             }
         } else {
             show_error!(
@@ -680,15 +747,67 @@ pub fn uumain(args: impl uucore::Args) -> UResult<()> {
     Ok(())
 }
 
-fn parse_time_style(s: Option<&str>) -> UResult<&str> {
-    match s {
-        Some(s) => match s {
-            "full-iso" => Ok("%Y-%m-%d %H:%M:%S.%f %z"),
-            "long-iso" => Ok("%Y-%m-%d %H:%M"),
-            "iso" => Ok("%Y-%m-%d"),
-            _ => Err(DuError::InvalidTimeStyleArg(s.into()).into()),
-        },
-        None => Ok("%Y-%m-%d %H:%M"),
+/// Parse the WORD given with `--time[=WORD]`.
+///
+/// Like GNU `du`, WORD can be abbreviated as long as it is not
+/// ambiguous.
+fn parse_time(s: Option<&str>) -> UResult<Time> {
+    const WORDS: [(&str, Time); 7] = [
+        ("atime", Time::Accessed),
+        ("access", Time::Accessed),
+        ("use", Time::Accessed),
+        ("ctime", Time::Changed),
+        ("status", Time::Changed),
+        ("birth", Time::Birth),
+        ("creation", Time::Birth),
+    ];
+    let s = match s {
+        Some(s) => s,
+        None => return Ok(Time::Modified),
+    };
+    if let Some((_, time)) = WORDS.iter().find(|(word, _)| *word == s) {
+        return Ok(*time);
+    }
+    let mut candidates = WORDS.iter().filter(|(word, _)| word.starts_with(s));
+    match candidates.next() {
+        None => Err(DuError::InvalidTimeWord(s.into()).into()),
+        Some((_, time)) => {
+            if candidates
+                .all(|(_, other)| std::mem::discriminant(other) == std::mem::discriminant(time))
+            {
+                Ok(*time)
+            } else {
+                Err(DuError::AmbiguousTimeWord(s.into()).into())
+            }
+        }
+    }
+}
+
+/// Get the format to use for the times shown with `--time`.
+///
+/// The style comes from `--time-style`, or otherwise from the
+/// `TIME_STYLE` environment variable, and may be `+FORMAT`.
+fn parse_time_style(s: Option<&str>) -> UResult<String> {
+    let from_env = env::var("TIME_STYLE").ok();
+    let style = match (s, &from_env) {
+        (Some(s), _) => s,
+        (None, Some(s)) if s != "locale" => s.trim_start_matches("posix-"),
+        (None, _) => "long-iso",
+    };
+    match style {
+        "full-iso" => Ok("%Y-%m-%d %H:%M:%S.%f %z".to_string()),
+        "long-iso" => Ok("%Y-%m-%d %H:%M".to_string()),
+        "iso" => Ok("%Y-%m-%d".to_string()),
+        _ if style.starts_with('+') => {
+            // GNU `du` uses `%N` for nano seconds, however crate::chrono uses `%f`
+            let format = style[1..].replace("%N", "%f");
+            if StrftimeItems::new(&format).any(|item| item == Item::Error) {
+                Err(DuError::InvalidTimeStyleArg(style.into()).into())
+            } else {
+                Ok(format)
+            }
+        }
+        _ => Err(DuError::InvalidTimeStyleArg(style.into()).into()),
     }
 }
 
@@ -888,7 +1007,6 @@ pub fn uu_app() -> Command {
                 .value_name("WORD")
                 .require_equals(true)
                 .num_args(0..)
-                .value_parser(["atime", "access", "use", "ctime", "status", "birth", "creation"])
                 .help(
                     "show time of the last modification of any file in the \
                     directory, or any of its subdirectories. If WORD is given, show time as WORD instead \
@@ -927,6 +1045,10 @@ impl FromStr for Threshold {
         let size = parse_size(&s[offset..])?;
 
         if s.starts_with('-') {
+            // GNU du rejects "-0" as it would exclude every entry.
+            if size == 0 {
+                return Err(ParseSizeError::ParseFailure(s.to_string()));
+            }
             Ok(Self::Upper(size))
         } else {
             Ok(Self::Lower(size))
@@ -7,10 +7,12 @@
 // file that was distributed with this source code.
 
 use clap::{crate_version, Arg, ArgAction, Command};
+use std::env;
+use std::ffi::OsString;
 use std::io::{self, Write};
-use std::iter::Peekable;
-use std::str::Chars;
+use uucore::display::os_str_as_bytes;
 use uucore::error::{FromIo, UResult};
+use uucore::format::{unescape_all, EscapeStyle};
 use uucore::format_usage;
 
 const ABOUT: &str = "display a line of text";
@@ -32,6 +34,9 @@ const AFTER_HELP: &str = r#"
  \\v      vertical tab
  \\0NNN   byte with octal value NNN (1 to 3 digits)
  \\xHH    byte with hexadecimal value HH (1 to 2 digits)
+
+ If the POSIXLY_CORRECT environment variable is set, the sequences are always
+ recognized, and options are only recognized if the first one is -n.
 "#;
 
 mod options {
@@ -41,88 +46,45 @@ mod options {
     pub const DISABLE_BACKSLASH_ESCAPE: &str = "disable_backslash_escape";
 }
 
-fn parse_code(
-    input: &mut Peekable<Chars>,
-    base: u32,
-    max_digits: u32,
-    bits_per_digit: u32,
-) -> Option<char> {
-    let mut ret = 0x8000_0000;
-    for _ in 0..max_digits {
-        match input.peek().and_then(|c| c.to_digit(base)) {
-            Some(n) => ret = (ret << bits_per_digit) | n,
-            None => break,
-        }
-        input.next();
+#[uucore::main]
+pub fn uumain(args: impl uucore::Args) -> UResult<()> {
+    let args: Vec<OsString> = args.collect();
+
+    // Like GNU echo, options are taken by hand: only arguments made of
+    // option letters are options, and they may be followed by anything.
+    let posixly_correct = env::var_os("POSIXLY_CORRECT").is_some();
+    let allow_options = !posixly_correct || args.get(1).map_or(false, |arg| arg == "-n");
+    if allow_options && args.len() == 2 && (args[1] == "--help" || args[1] == "--version") {
+        uu_app().get_matches_from(&args);
     }
-    std::char::from_u32(ret)
-}
-
-fn print_escaped(input: &str, mut output: impl Write) -> io::Result<bool> {
-    let mut should_stop = false;
 
-    let mut buffer = ['\\'; 2];
-
-    // TODO `cargo +nightly clippy` complains that `.peek()` is never
-    // called on `iter`. However, `peek()` is called inside the
-    // `parse_code()` function that borrows `iter`.
-    let mut iter = input.chars().peekable();
-    while let Some(mut c) = iter.next() {
-        let mut start = 1;
-
-        if c == '\\' {
-            if let Some(next) = iter.next() {
-                c = match next {
-                    '\\' => '\\',
-                    'a' => '\x07',
-                    'b' => '\x08',
-                    'c' => {
-                        should_stop = true;
-                        break;
-                    }
-                    'e' => '\x1b',
-                    'f' => '\x0c',
-                    'n' => '\n',
-                    'r' => '\r',
-                    't' => '\t',
-                    'v' => '\x0b',
-                    'x' => parse_code(&mut iter, 16, 2, 4).unwrap_or_else(|| {
-                        start = 0;
-                        next
-                    }),
-                    '0' => parse_code(&mut iter, 8, 3, 3).unwrap_or('\0'),
-                    _ => {
-                        start = 0;
-                        next
-                    }
-                };
-            }
+    let mut no_newline = false;
+    let mut escaped = false;
+    let mut strings = args.get(1..).unwrap_or_default();
+    while let Some(flags) = strings
+        .first()
+        .and_then(|arg| arg.to_str())
+        .and_then(|arg| arg.strip_prefix('-'))
+    {
+        if !allow_options || flags.is_empty() || !flags.chars().all(|c| "neE".contains(c)) {
+            break;
         }
-
-        buffer[1] = c;
-
-        // because printing char slices is apparently not available in the standard library
-        for ch in &buffer[start..] {
-            write!(output, "{ch}")?;
+        for flag in flags.chars() {
+            match flag {
+                'n' => no_newline = true,
+                'e' => escaped = true,
+                _ => escaped = false,
+            }
         }
+        strings = &strings[1..];
     }
 
-    Ok(should_stop)
-}
-
-#[uucore::main]
-pub fn uumain(args: impl uucore::Args) -> UResult<()> {
-    let args = args.collect_lossy();
-    let matches = uu_app().get_matches_from(args);
-
-    let no_newline = matches.get_flag(options::NO_NEWLINE);
-    let escaped = matches.get_flag(options::ENABLE_BACKSLASH_ESCAPE);
-    let values: Vec<String> = match matches.get_many::<String>(options::STRING) {
-        Some(s) => s.map(|s| s.to_string()).collect(),
-        None => vec![String::new()],
-    };
-
-    execute(no_newline, escaped, &values)
+    // The escapes are always replaced with POSIXLY_CORRECT, as XSI requires.
+    let output = echo_output(strings, no_newline, escaped || posixly_correct)?;
+    let mut stdout = io::stdout().lock();
+    stdout
+        .write_all(&output)
+        .and_then(|()| stdout.flush())
         .map_err_context(|| "could not write to stdout".to_string())
 }
 
@@ -159,27 +121,25 @@ pub fn uu_app() -> Command {
         .arg(Arg::new(options::STRING).action(ArgAction::Append))
 }
 
-fn execute(no_newline: bool, escaped: bool, free: &[String]) -> io::Result<()> {
-    let stdout = io::stdout();
-    let mut output = stdout.lock();
-
-    for (i, input) in free.iter().enumerate() {
+/// The output of `strings`, separated by spaces.
+fn echo_output(strings: &[OsString], no_newline: bool, escaped: bool) -> UResult<Vec<u8>> {
+    let mut output = Vec::new();
+    for (i, input) in strings.iter().enumerate() {
+        let input = os_str_as_bytes(input);
         if i > 0 {
-            write!(output, " ")?;
+            output.push(b' ');
         }
         if escaped {
-            let should_stop = print_escaped(input, &mut output)?;
-            if should_stop {
-                break;
+            if unescape_all(&input, EscapeStyle::Echo, &mut output)?.is_break() {
+                // Nothing is printed after `\c`, not even the newline.
+                return Ok(output);
             }
         } else {
-            write!(output, "{input}")?;
+            output.extend_from_slice(&input);
         }
     }
-
     if !no_newline {
-        writeln!(output)?;
+        output.push(b'\n');
     }
-
-    Ok(())
+    Ok(output)
 }
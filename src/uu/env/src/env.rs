@@ -10,20 +10,30 @@
 
 // spell-checker:ignore (ToDO) chdir execvp progname subcommand subcommands unsets setenv putenv spawnp SIGSEGV SIGBUS sigaction
 
+#[cfg(unix)]
+mod signal_mask;
+mod split_string;
+
 use clap::{crate_name, crate_version, Arg, ArgAction, Command};
 use ini::Ini;
 #[cfg(unix)]
 use nix::sys::signal::{raise, sigaction, SaFlags, SigAction, SigHandler, SigSet, Signal};
 use std::borrow::Cow;
+use std::collections::VecDeque;
 use std::env;
+use std::ffi::OsString;
 use std::io::{self, Write};
 use std::iter::Iterator;
 #[cfg(unix)]
-use std::os::unix::process::ExitStatusExt;
+use std::os::unix::process::{CommandExt, ExitStatusExt};
 use std::process;
 use uucore::display::Quotable;
-use uucore::error::{UClapError, UResult, USimpleError, UUsageError};
-use uucore::{format_usage, show_warning};
+use uucore::error::{strip_errno, UClapError, UResult, USimpleError, UUsageError};
+use uucore::{format_usage, show_error, show_warning};
+
+#[cfg(unix)]
+use crate::signal_mask::SignalSetup;
+use crate::split_string::split_string;
 
 const ABOUT: &str = "set each NAME to VALUE in the environment and run COMMAND";
 const USAGE: &str = "{} [OPTION]... [-] [NAME=VALUE]... [COMMAND [ARG]...]";
@@ -39,6 +49,8 @@ struct Options<'a> {
     unsets: Vec<&'a str>,
     sets: Vec<(&'a str, &'a str)>,
     program: Vec<&'a str>,
+    #[cfg(unix)]
+    signals: SignalSetup,
 }
 
 // print name=value env pairs on screen
@@ -120,8 +132,134 @@ fn build_command<'a, 'b>(args: &'a mut Vec<&'b str>) -> (Cow<'b, str>, &'a [&'b
     (progname, &args[..])
 }
 
+/// Replace each `-S`/`--split-string` option by the arguments its value splits
+/// into, so that they are parsed as if they were given separately.
+///
+/// Only options are looked at, as everything from the first operand on is
+/// left alone.
+fn expand_split_strings(args: impl uucore::Args) -> UResult<Vec<OsString>> {
+    let mut pending: VecDeque<OsString> = args.collect();
+    let mut expanded: Vec<OsString> = pending.pop_front().into_iter().collect();
+
+    while let Some(arg) = pending.pop_front() {
+        let s = match arg.to_str() {
+            Some(s) => s.to_owned(),
+            None => {
+                expanded.push(arg);
+                continue;
+            }
+        };
+        if s == "-" || s == "--" || !s.starts_with('-') {
+            expanded.push(arg);
+            break;
+        }
+
+        let split_value = if let Some(long) = s.strip_prefix("--") {
+            let (name, value) = match long.split_once('=') {
+                Some((name, value)) => (name, Some(value.to_owned())),
+                None => (long, None),
+            };
+            if "split-string".starts_with(name) {
+                Some((None, value))
+            } else {
+                expanded.push(arg);
+                if value.is_none()
+                    && ["chdir", "file", "unset"]
+                        .iter()
+                        .any(|o| o.starts_with(name))
+                {
+                    expanded.extend(pending.pop_front());
+                }
+                None
+            }
+        } else {
+            // A cluster of short options, which may end with the value of
+            // the last one.
+            match s.find(['S', 'C', 'f', 'u']) {
+                Some(i) if s[i..].starts_with('S') => {
+                    let cluster = Some(s[..i].to_owned()).filter(|c| c != "-");
+                    Some((
+                        cluster,
+                        Some(s[i + 1..].to_owned()).filter(|v| !v.is_empty()),
+                    ))
+                }
+                Some(i) => {
+                    expanded.push(arg);
+                    if s.len() == i + 1 {
+                        expanded.extend(pending.pop_front());
+                    }
+                    None
+                }
+                None => {
+                    expanded.push(arg);
+                    None
+                }
+            }
+        };
+
+        if let Some((cluster, value)) = split_value {
+            expanded.extend(cluster.map(OsString::from));
+            match value.map(OsString::from).or_else(|| pending.pop_front()) {
+                Some(value) => {
+                    let value = value.to_str().ok_or_else(|| {
+                        USimpleError::new(125, format!("invalid -S string {}", value.quote()))
+                    })?;
+                    for word in split_string(value)?.into_iter().rev() {
+                        pending.push_front(word.into());
+                    }
+                }
+                // Let clap report the missing value.
+                None => expanded.push("-S".into()),
+            }
+        }
+    }
+
+    expanded.extend(pending);
+    Ok(expanded)
+}
+
+#[cfg(unix)]
+fn signal_setup(matches: &clap::ArgMatches) -> UResult<SignalSetup> {
+    let mut setup = SignalSetup::default();
+
+    // The dispositions are set in the order of the options, so that the last
+    // one given for a signal wins.
+    let mut actions = vec![];
+    for (id, ignore) in [("default-signal", false), ("ignore-signal", true)] {
+        if let (Some(indices), Some(lists)) =
+            (matches.indices_of(id), matches.get_many::<String>(id))
+        {
+            actions.extend(indices.zip(lists).map(|(i, list)| (i, list, ignore)));
+        }
+    }
+    actions.sort_by_key(|&(i, _, _)| i);
+    for (_, list, ignore) in actions {
+        setup.set_action(list, ignore)?;
+    }
+
+    if let Some(lists) = matches.get_many::<String>("block-signal") {
+        for list in lists {
+            setup.block(list)?;
+        }
+    }
+    Ok(setup)
+}
+
+/// Take a comma-separated list of signals, or all signals if none is given.
+#[cfg(unix)]
+fn signal_arg(name: &'static str, help: &'static str) -> Arg {
+    Arg::new(name)
+        .long(name)
+        .value_name("SIG")
+        .num_args(0..=1)
+        .require_equals(true)
+        .default_missing_value("")
+        .action(ArgAction::Append)
+        .help(help)
+}
+
 pub fn uu_app() -> Command {
-    Command::new(crate_name!())
+    let app = Command::new(crate_name!())
         .version(crate_version!())
         .about(ABOUT)
         .override_usage(format_usage(USAGE))
@@ -174,11 +312,39 @@ pub fn uu_app() -> Command {
                 .action(ArgAction::Append)
                 .help("remove variable from the environment"),
         )
-        .arg(Arg::new("vars").action(ArgAction::Append))
+        .arg(
+            Arg::new("split-string")
+                .short('S')
+                .long("split-string")
+                .value_name("S")
+                .help(
+                    "process and split S into separate arguments; used to pass \
+                multiple arguments on shebang lines",
+                ),
+        )
+        .arg(Arg::new("vars").action(ArgAction::Append));
+
+    #[cfg(unix)]
+    let app = app
+        .arg(signal_arg(
+            "block-signal",
+            "block delivery of SIG signal(s) to COMMAND",
+        ))
+        .arg(signal_arg(
+            "default-signal",
+            "reset handling of SIG signal(s) to the default",
+        ))
+        .arg(signal_arg(
+            "ignore-signal",
+            "set handling of SIG signal(s) to do nothing",
+        ));
+
+    app
 }
 
 fn run_env(args: impl uucore::Args) -> UResult<()> {
     let app = uu_app();
+    let args = expand_split_strings(args)?;
     let matches = app.try_get_matches_from(args).with_exit_code(125)?;
 
     let ignore_env = matches.get_flag("ignore-environment");
@@ -201,6 +367,8 @@ fn run_env(args: impl uucore::Args) -> UResult<()> {
         unsets,
         sets: vec![],
         program: vec![],
+        #[cfg(unix)]
+        signals: signal_setup(&matches)?,
     };
 
     // change directory
@@ -210,7 +378,11 @@ fn run_env(args: impl uucore::Args) -> UResult<()> {
             Err(error) => {
                 return Err(USimpleError::new(
                     125,
-                    format!("cannot change directory to \"{d}\": {error}"),
+                    format!(
+                        "cannot change directory to {}: {}",
+                        d.quote(),
+                        strip_errno(&error)
+                    ),
                 ));
             }
         };
@@ -311,7 +483,17 @@ fn run_env(args: impl uucore::Args) -> UResult<()> {
          * standard library contains many checks and fail-safes to ensure the process ends up being
          * created. This is much simpler than dealing with the hassles of calling execvp directly.
          */
-        match process::Command::new(&*prog).args(args).status() {
+        let mut command = process::Command::new(&*prog);
+        command.args(args);
+        #[cfg(unix)]
+        if !opts.signals.is_empty() {
+            let signals = opts.signals.clone();
+            // SAFETY: the setup only makes async-signal-safe calls.
+            unsafe {
+                command.pre_exec(move || signals.apply());
+            }
+        }
+        match command.status() {
             Ok(exit) if !exit.success() => {
                 #[cfg(unix)]
                 if let Some(exit_code) = exit.code() {
@@ -342,8 +524,14 @@ fn run_env(args: impl uucore::Args) -> UResult<()> {
                 #[cfg(not(unix))]
                 return Err(exit.code().unwrap().into());
             }
-            Err(ref err) if err.kind() == io::ErrorKind::NotFound => return Err(127.into()),
-            Err(_) => return Err(126.into()),
+            Err(err) => {
+                let not_found = err.kind() == io::ErrorKind::NotFound;
+                show_error!("{}: {}", prog.quote(), strip_errno(&err));
+                if not_found && prog.contains(char::is_whitespace) {
+                    show_error!("use -[v]S to pass options in shebang lines");
+                }
+                return Err(if not_found { 127 } else { 126 }.into());
+            }
             Ok(_) => (),
         }
     } else {
@@ -0,0 +1,105 @@
+// This file is part of the uutils coreutils package.
+//
+// For the full copyright and license information, please view the LICENSE
+// file that was distributed with this source code.
+
+//! Signal handling of the command, as set with `--default-signal`,
+//! `--ignore-signal` and `--block-signal`
+//!
+//! The standard library resets the signal mask and the disposition of
+//! `SIGPIPE` in the child it spawns, so the setup is applied in the child
+//! right before the command is executed.
+
+// spell-checker:ignore sigprocmask sigaction sigaddset sigemptyset sighandler
+
+use libc::c_int;
+use std::io;
+use std::mem::MaybeUninit;
+use std::ptr;
+use uucore::display::Quotable;
+use uucore::error::{UResult, USimpleError, UUsageError};
+use uucore::signals::{max_signal, signal_by_name_or_value};
+
+#[derive(Clone, Default)]
+pub(crate) struct SignalSetup {
+    /// Dispositions to set, in order, so that later ones win
+    actions: Vec<(c_int, libc::sighandler_t)>,
+    blocked: Vec<c_int>,
+}
+
+/// Parse a comma-separated list of signal names or numbers, where an empty
+/// list means all signals but `SIGKILL` and `SIGSTOP`.
+fn parse_signals(list: &str) -> UResult<Vec<c_int>> {
+    if list.is_empty() {
+        return Ok((1..=max_signal() as c_int)
+            .filter(|&n| n != libc::SIGKILL && n != libc::SIGSTOP)
+            .collect());
+    }
+    list.split(',')
+        .filter(|name| !name.is_empty())
+        .map(|name| {
+            signal_by_name_or_value(name)
+                .filter(|&n| n != 0)
+                .map(|n| n as c_int)
+                .ok_or_else(|| UUsageError::new(125, format!("{}: invalid signal", name.quote())))
+        })
+        .collect()
+}
+
+impl SignalSetup {
+    pub(crate) fn is_empty(&self) -> bool {
+        self.actions.is_empty() && self.blocked.is_empty()
+    }
+
+    /// Reset the signals in `list` to their default action, or ignore them.
+    pub(crate) fn set_action(&mut self, list: &str, ignore: bool) -> UResult<()> {
+        let handler = if ignore { libc::SIG_IGN } else { libc::SIG_DFL };
+        for signal in parse_signals(list)? {
+            if signal == libc::SIGKILL || signal == libc::SIGSTOP {
+                return Err(USimpleError::new(
+                    125,
+                    format!(
+                        "failed to set signal action for signal {}: Invalid argument",
+                        signal
+                    ),
+                ));
+            }
+            self.actions.push((signal, handler));
+        }
+        Ok(())
+    }
+
+    /// Block the delivery of the signals in `list`.
+    pub(crate) fn block(&mut self, list: &str) -> UResult<()> {
+        self.blocked.extend(parse_signals(list)?);
+        Ok(())
+    }
+
+    /// Apply the setup to the current process.
+    ///
+    /// This only makes async-signal-safe calls, as it runs in the child
+    /// between fork and exec. Signals reserved by the C library can't be
+    /// changed, and are left alone like GNU env does.
+    pub(crate) fn apply(&self) -> io::Result<()> {
+        // SAFETY: only SIG_DFL or SIG_IGN are installed, and the signal set
+        // is initialized by sigemptyset before use.
+        unsafe {
+            for &(signal, handler) in &self.actions {
+                let mut action: libc::sigaction = MaybeUninit::zeroed().assume_init();
+                action.sa_sigaction = handler;
+                libc::sigaction(signal, &action, ptr::null_mut());
+            }
+            if !self.blocked.is_empty() {
+                let mut set = MaybeUninit::<libc::sigset_t>::uninit();
+                libc::sigemptyset(set.as_mut_ptr());
+                for &signal in &self.blocked {
+                    libc::sigaddset(set.as_mut_ptr(), signal);
+                }
+                if libc::sigprocmask(libc::SIG_BLOCK, set.as_ptr(), ptr::null_mut()) != 0 {
+                    return Err(io::Error::last_os_error());
+                }
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,226 @@
+// This file is part of the uutils coreutils package.
+//
+// For the full copyright and license information, please view the LICENSE
+// file that was distributed with this source code.
+
+//! Split the argument of `-S`/`--split-string` into separate arguments
+//!
+//! This is what makes shebang lines like `#!/usr/bin/env -S perl -w` work, as
+//! the kernel passes everything after the interpreter as a single argument.
+//! The rules follow GNU env: words are separated by whitespace, quotes and
+//! backslash escapes work much like in the shell, `#` at the start of a word
+//! begins a comment and `${NAME}` is replaced by the value of `NAME`.
+
+// spell-checker:ignore VARNAME
+
+use std::env;
+use std::iter::Peekable;
+use std::str::CharIndices;
+use uucore::error::{UResult, USimpleError};
+
+struct Splitter<'a> {
+    input: &'a str,
+    chars: Peekable<CharIndices<'a>>,
+    args: Vec<String>,
+    /// The argument being built, if one was started
+    current: Option<String>,
+}
+
+/// Whether parsing continues after an escape sequence.
+enum Flow {
+    Continue,
+    Stop,
+}
+
+fn error(msg: String) -> Box<dyn uucore::error::UError> {
+    USimpleError::new(125, msg)
+}
+
+impl<'a> Splitter<'a> {
+    fn push(&mut self, c: char) {
+        self.current.get_or_insert_with(String::new).push(c);
+    }
+
+    fn push_str(&mut self, s: &str) {
+        self.current.get_or_insert_with(String::new).push_str(s);
+    }
+
+    fn end_arg(&mut self) {
+        if let Some(arg) = self.current.take() {
+            self.args.push(arg);
+        }
+    }
+
+    fn split(mut self) -> UResult<Vec<String>> {
+        while let Some((pos, c)) = self.chars.next() {
+            match c {
+                ' ' | '\t' | '\n' | '\x0b' | '\x0c' | '\r' => self.end_arg(),
+                '#' if self.current.is_none() => break,
+                '\'' => self.single_quoted()?,
+                '"' => self.double_quoted()?,
+                '\\' => {
+                    if let Flow::Stop = self.escape(false)? {
+                        break;
+                    }
+                }
+                '$' => self.expand_variable(pos)?,
+                c => self.push(c),
+            }
+        }
+        self.end_arg();
+        Ok(self.args)
+    }
+
+    /// Read the rest of a single-quoted string, where only `\\` and `\'`
+    /// are special.
+    fn single_quoted(&mut self) -> UResult<()> {
+        self.push_str("");
+        loop {
+            match self.chars.next() {
+                None => return Err(error("no terminating quote in -S string".to_string())),
+                Some((_, '\'')) => return Ok(()),
+                Some((_, '\\')) => match self.chars.peek() {
+                    Some(&(_, c @ ('\\' | '\''))) => {
+                        self.chars.next();
+                        self.push(c);
+                    }
+                    _ => self.push('\\'),
+                },
+                Some((_, c)) => self.push(c),
+            }
+        }
+    }
+
+    /// Read the rest of a double-quoted string, where escapes and variables
+    /// work as outside quotes.
+    fn double_quoted(&mut self) -> UResult<()> {
+        self.push_str("");
+        loop {
+            match self.chars.next() {
+                None => return Err(error("no terminating quote in -S string".to_string())),
+                Some((_, '"')) => return Ok(()),
+                Some((_, '\\')) => {
+                    self.escape(true)?;
+                }
+                Some((pos, '$')) => self.expand_variable(pos)?,
+                Some((_, c)) => self.push(c),
+            }
+        }
+    }
+
+    /// Handle the escape sequence after a backslash.
+    fn escape(&mut self, in_double_quotes: bool) -> UResult<Flow> {
+        let c = match self.chars.next() {
+            Some((_, c)) => c,
+            None => {
+                return Err(error(
+                    "invalid backslash at end of string in -S".to_string(),
+                ))
+            }
+        };
+        match c {
+            '"' | '\'' | '#' | '$' | '\\' => self.push(c),
+            'f' => self.push('\x0c'),
+            'n' => self.push('\n'),
+            'r' => self.push('\r'),
+            't' => self.push('\t'),
+            'v' => self.push('\x0b'),
+            '_' if in_double_quotes => self.push(' '),
+            '_' => self.end_arg(),
+            'c' if in_double_quotes => {
+                return Err(error(
+                    "'\\c' must not appear in double-quoted -S string".to_string(),
+                ))
+            }
+            'c' => return Ok(Flow::Stop),
+            c => return Err(error(format!("invalid sequence '\\{}' in -S", c))),
+        }
+        Ok(Flow::Continue)
+    }
+
+    /// Replace `${NAME}`, whose `$` is at `start`, by the value of `NAME`, or
+    /// by nothing if it is not set.
+    fn expand_variable(&mut self, start: usize) -> UResult<()> {
+        let rest = &self.input[start + 1..];
+        let name_len = rest
+            .strip_prefix('{')
+            .filter(|name| name.starts_with(|c: char| c == '_' || c.is_ascii_alphabetic()))
+            .and_then(|name| name.find(|c: char| c != '_' && !c.is_ascii_alphanumeric()))
+            .filter(|&end| rest[1 + end..].starts_with('}'));
+        let name_len = match name_len {
+            Some(len) => len,
+            None => {
+                return Err(error(format!(
+                    "only ${{VARNAME}} expansion is supported, error at: {}",
+                    &self.input[start..]
+                )))
+            }
+        };
+        let name = &rest[1..1 + name_len];
+        let value = env::var_os(name).unwrap_or_default();
+        self.push_str(&value.to_string_lossy());
+        // Skip the name and both braces.
+        for _ in 0..name_len + 2 {
+            self.chars.next();
+        }
+        Ok(())
+    }
+}
+
+/// Split `input` into arguments, with the rules of GNU env's `-S` option.
+pub(crate) fn split_string(input: &str) -> UResult<Vec<String>> {
+    Splitter {
+        input,
+        chars: input.char_indices().peekable(),
+        args: Vec::new(),
+        current: None,
+    }
+    .split()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::split_string;
+
+    fn split(input: &str) -> Vec<String> {
+        split_string(input).unwrap()
+    }
+
+    #[test]
+    fn test_split_words() {
+        assert_eq!(split("  perl -w\t-T "), ["perl", "-w", "-T"]);
+        assert_eq!(split("a\\_b\\_\\_c"), ["a", "b", "c"]);
+        assert_eq!(split("a # comment"), ["a"]);
+        assert_eq!(split("a#b"), ["a#b"]);
+        assert_eq!(split("a b\\c c"), ["a", "b"]);
+        assert!(split("").is_empty());
+    }
+
+    #[test]
+    fn test_split_quotes() {
+        assert_eq!(split("'a b' \"c d\""), ["a b", "c d"]);
+        assert_eq!(split("a'b'\"c\"d"), ["abcd"]);
+        assert_eq!(split("'' \"\""), ["", ""]);
+        assert_eq!(split(r"'a\nb\'\\'"), [r"a\nb'\"]);
+        assert_eq!(split(r#""a\nb\_c\"""#), ["a\nb c\""]);
+        assert_eq!(split(r"\#\$\'"), ["#$'"]);
+    }
+
+    #[test]
+    fn test_split_variables() {
+        std::env::set_var("SPLIT_STRING_TEST", "x y");
+        assert_eq!(split("a${SPLIT_STRING_TEST}b"), ["ax yb"]);
+        assert_eq!(split("\"${SPLIT_STRING_TEST}\""), ["x y"]);
+        assert_eq!(split("'${SPLIT_STRING_TEST}'"), ["${SPLIT_STRING_TEST}"]);
+        assert_eq!(split("${SPLIT_STRING_UNSET}"), [""]);
+    }
+
+    #[test]
+    fn test_split_errors() {
+        for input in [
+            "'a", "\"a", "a\\", "a\\q", "\"\\c\"", "$A", "${A", "${1A}", "${}",
+        ] {
+            assert!(split_string(input).is_err(), "{}", input);
+        }
+    }
+}
@@ -0,0 +1,598 @@
+// * This file is part of the uutils coreutils package.
+// *
+// * For the full copyright and license information, please view the LICENSE file
+// * that was distributed with this source code.
+
+// spell-checker:ignore (names) Baillie Brent Lenstra Pomerance Selfridge Suyama Wagstaff
+
+//! Factorization of integers which do not fit in a `u64`.
+//!
+//! Small factors are removed by trial division, then Pollard's rho (with
+//! Brent's cycle detection) gets a bounded number of iterations before we fall
+//! back to Lenstra's elliptic curve method, which is much better at finding the
+//! medium-sized factors of large numbers.  Cofactors which fit in a `u64` are
+//! handed over to [`crate::factor`].
+
+use num_bigint::BigUint;
+use num_integer::Integer;
+use num_traits::{One, ToPrimitive, Zero};
+use std::fmt;
+
+use crate::table::{NEXT_PRIME, PRIME_INVERSIONS_U64};
+
+type Exponent = u32;
+
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct BigFactors(Vec<(BigUint, Exponent)>);
+
+impl BigFactors {
+    fn add(&mut self, prime: BigUint, exp: Exponent) {
+        debug_assert!(exp > 0);
+
+        if let Some((_, e)) = self.0.iter_mut().find(|(p, _)| *p == prime) {
+            *e += exp;
+        } else {
+            self.0.push((prime, exp));
+        }
+    }
+
+    #[cfg(test)]
+    fn product(&self) -> BigUint {
+        self.0
+            .iter()
+            .fold(BigUint::one(), |acc, (p, exp)| acc * p.pow(*exp))
+    }
+}
+
+impl fmt::Display for BigFactors {
+    /// The alternate form (`{:#}`) prints repeated factors as `p^e`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut v: Vec<_> = self.0.iter().collect();
+        v.sort_unstable();
+
+        for (p, exp) in v {
+            if f.alternate() && *exp > 1 {
+                write!(f, " {p}^{exp}")?;
+            } else {
+                for _ in 0..*exp {
+                    write!(f, " {p}")?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+pub fn factor_big(n: &BigUint) -> BigFactors {
+    let mut factors = BigFactors::default();
+    let mut n = n.clone();
+
+    if n < BigUint::from(2u32) {
+        return factors;
+    }
+
+    let n_zeros = n.trailing_zeros().unwrap_or(0);
+    if n_zeros > 0 {
+        factors.add(BigUint::from(2u32), n_zeros as Exponent);
+        n >>= n_zeros;
+    }
+
+    for &(prime, _, _) in PRIME_INVERSIONS_U64 {
+        let mut k = 0;
+        while (&n % prime).is_zero() {
+            n /= prime;
+            k += 1;
+        }
+        if k > 0 {
+            factors.add(BigUint::from(prime), k);
+        }
+    }
+
+    // Numbers left to factor, with their multiplicity in the original number.
+    let mut stack = vec![(n, 1)];
+    while let Some((n, exp)) = stack.pop() {
+        if n.is_one() {
+            continue;
+        }
+
+        if let Some(small) = n.to_u64() {
+            for (p, e) in crate::factor(small).to_vec() {
+                factors.add(BigUint::from(p), Exponent::from(e) * exp);
+            }
+        } else if let Some((root, k)) = perfect_power(&n) {
+            stack.push((root, exp * k));
+        } else if is_probable_prime(&n) {
+            factors.add(n, exp);
+        } else {
+            let d = find_divisor(&n);
+            stack.push((&n / &d, exp));
+            stack.push((d, exp));
+        }
+    }
+
+    factors
+}
+
+/// Find `(r, k)` with `k > 1` and `rᵏ = n`, for `n` without small factors.
+fn perfect_power(n: &BigUint) -> Option<(BigUint, Exponent)> {
+    for k in 2.. {
+        let root = n.nth_root(k);
+        if root < BigUint::from(NEXT_PRIME) {
+            return None;
+        }
+        if root.pow(k) == *n {
+            return Some((root, k));
+        }
+    }
+    unreachable!()
+}
+
+/// The Baillie-PSW test: a strong probable prime test to base 2, followed by a
+/// strong Lucas probable prime test.  No composite passing both is known.
+///
+/// `n` must be odd, not a perfect square and without factors below 7.
+fn is_probable_prime(n: &BigUint) -> bool {
+    is_strong_probable_prime(n, &BigUint::from(2u32)) && is_strong_lucas_probable_prime(n)
+}
+
+fn is_strong_probable_prime(n: &BigUint, base: &BigUint) -> bool {
+    let n_minus_one = n - 1u32;
+    let s = n_minus_one.trailing_zeros().unwrap_or(0);
+    let mut x = base.modpow(&(&n_minus_one >> s), n);
+
+    if x.is_one() || x == n_minus_one {
+        return true;
+    }
+    for _ in 1..s {
+        x = &x * &x % n;
+        if x == n_minus_one {
+            return true;
+        }
+        if x.is_one() {
+            return false;
+        }
+    }
+    false
+}
+
+fn is_strong_lucas_probable_prime(n: &BigUint) -> bool {
+    // Selfridge's method A: D is the first of 5, -7, 9, -11, ... with (D/n) = -1;
+    //  this terminates quickly as n is not a perfect square.
+    let mut d: i64 = 5;
+    loop {
+        match jacobi(&to_residue(d, n), n) {
+            -1 => break,
+            // As |D| < n, n has a non-trivial common factor with D
+            0 => return false,
+            _ => d = if d > 0 { -(d + 2) } else { 2 - d },
+        }
+    }
+    let d_mod = to_residue(d, n);
+    let q = to_residue((1 - d) / 4, n);
+
+    // Halve x modulo the odd number n
+    let half = |x: BigUint| if x.is_odd() { (x + n) >> 1 } else { x >> 1 };
+
+    // n+1 = k 2ˢ; compute U_k, V_k and Qᵏ with P = 1, going through the bits of k
+    let n_plus_one = n + 1u32;
+    let s = n_plus_one.trailing_zeros().unwrap_or(0);
+    let k = &n_plus_one >> s;
+    let (mut u, mut v, mut q_k) = (BigUint::one(), BigUint::one(), q.clone());
+    for i in (0..k.bits() - 1).rev() {
+        u = &u * &v % n;
+        v = sub_mod(&(&v * &v), &(&q_k << 1), n);
+        q_k = &q_k * &q_k % n;
+        if k.bit(i) {
+            let u_next = half(&u + &v);
+            v = half(&d_mod * &u % n + &v);
+            u = u_next % n;
+            v %= n;
+            q_k = &q_k * &q % n;
+        }
+    }
+
+    if u.is_zero() {
+        return true;
+    }
+    for _ in 0..s {
+        if v.is_zero() {
+            return true;
+        }
+        v = sub_mod(&(&v * &v), &(&q_k << 1), n);
+        q_k = &q_k * &q_k % n;
+    }
+    false
+}
+
+/// The Jacobi symbol (a/n), for odd n.
+fn jacobi(a: &BigUint, n: &BigUint) -> i32 {
+    let mut a = a % n;
+    let mut n = n.clone();
+    let mut result = 1;
+
+    while !a.is_zero() {
+        let zeros = a.trailing_zeros().unwrap_or(0);
+        a >>= zeros;
+        if zeros % 2 == 1 && matches!(low_word(&n) % 8, 3 | 5) {
+            result = -result;
+        }
+        std::mem::swap(&mut a, &mut n);
+        if low_word(&a) % 4 == 3 && low_word(&n) % 4 == 3 {
+            result = -result;
+        }
+        a %= &n;
+    }
+
+    if n.is_one() {
+        result
+    } else {
+        0
+    }
+}
+
+fn low_word(x: &BigUint) -> u64 {
+    x.iter_u64_digits().next().unwrap_or(0)
+}
+
+fn to_residue(x: i64, n: &BigUint) -> BigUint {
+    let r = BigUint::from(x.unsigned_abs()) % n;
+    if x < 0 && !r.is_zero() {
+        n - r
+    } else {
+        r
+    }
+}
+
+/// (a - b) mod n
+fn sub_mod(a: &BigUint, b: &BigUint, n: &BigUint) -> BigUint {
+    (a % n + n - b % n) % n
+}
+
+fn abs_diff(a: &BigUint, b: &BigUint) -> BigUint {
+    if a > b {
+        a - b
+    } else {
+        b - a
+    }
+}
+
+/// Number of iterations of Pollard's rho before switching to ECM;
+///  this is plenty to find factors up to about 10¹⁰.
+const RHO_ITERATIONS: u64 = 1 << 17;
+
+/// Find a non-trivial divisor of the composite number n.
+fn find_divisor(n: &BigUint) -> BigUint {
+    for c in 1..=3 {
+        match rho(n, c, RHO_ITERATIONS) {
+            Some(d) if d != *n => return d,
+            // Failure, retry with a different polynomial
+            Some(_) => continue,
+            None => break,
+        }
+    }
+    ecm(n)
+}
+
+/// Pollard's rho with Brent's cycle detection, iterating x ↦ x² + c.
+///
+/// Returns `None` once it ran for `max_iterations`, and `Some(n)` if it found
+/// a cycle without finding a factor.
+fn rho(n: &BigUint, c: u64, max_iterations: u64) -> Option<BigUint> {
+    // Number of differences multiplied together between two gcds
+    const BATCH: u64 = 128;
+
+    let f = |y: &BigUint| (y * y + c) % n;
+    let mut y = BigUint::from(2u32);
+    let mut q = BigUint::one();
+    let mut g = BigUint::one();
+    let mut r = 1;
+
+    let (x, mut ys) = loop {
+        if r > max_iterations {
+            return None;
+        }
+        let x = y.clone();
+        for _ in 0..r {
+            y = f(&y);
+        }
+        let mut ys = y.clone();
+        let mut k = 0;
+        while k < r && g.is_one() {
+            ys = y.clone();
+            for _ in 0..BATCH.min(r - k) {
+                y = f(&y);
+                q = q * abs_diff(&x, &y) % n;
+            }
+            g = q.gcd(n);
+            k += BATCH;
+        }
+        r *= 2;
+        if !g.is_one() {
+            break (x, ys);
+        }
+    };
+
+    if g == *n {
+        // The batch overshot, go through it one step at a time
+        loop {
+            ys = f(&ys);
+            g = abs_diff(&x, &ys).gcd(n);
+            if !g.is_one() {
+                break;
+            }
+        }
+    }
+    Some(g)
+}
+
+/// Bounds B₁ of the first stage of ECM, with the number of curves to try for
+///  each; those are the optimal values for factors of 15, 20, 25, 30 and 35
+///  digits.  The last bound is used for as many curves as it takes.
+const ECM_LEVELS: &[(u64, u32)] = &[
+    (2_000, 25),
+    (11_000, 90),
+    (50_000, 300),
+    (250_000, 700),
+    (1_000_000, 1800),
+];
+
+/// Ratio between the bounds of the second and first stages.
+const ECM_B2_RATIO: u64 = 50;
+
+/// Find a non-trivial divisor of the composite number n with Lenstra's
+///  elliptic curve method.
+fn ecm(n: &BigUint) -> BigUint {
+    let last = *ECM_LEVELS.last().unwrap();
+    let mut sigma = 6;
+
+    for (b1, curves) in ECM_LEVELS.iter().copied().chain(std::iter::repeat(last)) {
+        let primes = primes_up_to(b1);
+        for _ in 0..curves {
+            sigma += 1;
+            if let Some(d) = Curve::new(n, sigma).and_then(|curve| curve.find_divisor(b1, &primes))
+            {
+                return d;
+            }
+        }
+    }
+    unreachable!()
+}
+
+fn primes_up_to(limit: u64) -> Vec<u64> {
+    let mut is_composite = vec![false; limit as usize + 1];
+    let mut primes = Vec::new();
+    for i in 2..=limit as usize {
+        if !is_composite[i] {
+            primes.push(i as u64);
+            for j in (i * i..=limit as usize).step_by(i) {
+                is_composite[j] = true;
+            }
+        }
+    }
+    primes
+}
+
+/// A point of a Montgomery curve, in projective coordinates (X : Z), without Y.
+#[derive(Clone)]
+struct Point {
+    x: BigUint,
+    z: BigUint,
+}
+
+/// The Montgomery curve By² = x³ + Ax² + x modulo n; (A + 2) / 4 is kept as a
+///  fraction, so that we never need to compute modular inverses.
+struct Curve<'a> {
+    n: &'a BigUint,
+    a24_num: BigUint,
+    a24_den: BigUint,
+    start: Point,
+}
+
+impl<'a> Curve<'a> {
+    /// Suyama's parametrization, with a group order divisible by 12.
+    ///
+    /// Returns `None` if the curve is degenerate modulo n.
+    fn new(n: &'a BigUint, sigma: u64) -> Option<Self> {
+        let sigma = BigUint::from(sigma);
+        let u = (&sigma * &sigma - 5u32) % n;
+        let v = (sigma << 2) % n;
+        let u3 = u.modpow(&BigUint::from(3u32), n);
+        let v_minus_u = sub_mod(&v, &u, n);
+
+        let curve = Self {
+            n,
+            a24_num: v_minus_u.modpow(&BigUint::from(3u32), n) * (&u * 3u32 + &v) % n,
+            a24_den: (&u3 << 4) * &v % n,
+            start: Point {
+                x: u3,
+                z: v.modpow(&BigUint::from(3u32), n),
+            },
+        };
+        if curve.a24_den.is_zero() {
+            None
+        } else {
+            Some(curve)
+        }
+    }
+
+    fn mul(&self, a: &BigUint, b: &BigUint) -> BigUint {
+        a * b % self.n
+    }
+
+    fn sub(&self, a: &BigUint, b: &BigUint) -> BigUint {
+        sub_mod(a, b, self.n)
+    }
+
+    fn double(&self, p: &Point) -> Point {
+        let sum = &p.x + &p.z;
+        let t1 = self.mul(&sum, &sum);
+        let diff = self.sub(&p.x, &p.z);
+        let t2 = self.mul(&diff, &diff);
+        let t = self.sub(&t1, &t2);
+        Point {
+            x: self.mul(&self.mul(&self.a24_den, &t1), &t2),
+            z: self.mul(
+                &t,
+                &(self.mul(&self.a24_den, &t2) + self.mul(&self.a24_num, &t)),
+            ),
+        }
+    }
+
+    /// p + q, knowing p - q.
+    fn add(&self, p: &Point, q: &Point, diff: &Point) -> Point {
+        let u = self.mul(&self.sub(&p.x, &p.z), &(&q.x + &q.z));
+        let v = self.mul(&(&p.x + &p.z), &self.sub(&q.x, &q.z));
+        let sum = &u + &v;
+        let difference = self.sub(&u, &v);
+        Point {
+            x: self.mul(&diff.z, &self.mul(&sum, &sum)),
+            z: self.mul(&diff.x, &self.mul(&difference, &difference)),
+        }
+    }
+
+    /// k p, for k > 0, with Montgomery's ladder.
+    fn scale(&self, p: &Point, k: u64) -> Point {
+        let mut r0 = p.clone();
+        let mut r1 = self.double(p);
+        for i in (0..63 - k.leading_zeros()).rev() {
+            if (k >> i) & 1 == 1 {
+                r0 = self.add(&r1, &r0, p);
+                r1 = self.double(&r1);
+            } else {
+                r1 = self.add(&r1, &r0, p);
+                r0 = self.double(&r0);
+            }
+        }
+        r0
+    }
+
+    /// The gcd of x with n, if it is a non-trivial divisor.
+    fn divisor(&self, x: &BigUint) -> Option<BigUint> {
+        let g = x.gcd(self.n);
+        if g.is_one() || g == *self.n {
+            None
+        } else {
+            Some(g)
+        }
+    }
+
+    /// Look for a prime factor p of n such that the order of the curve modulo p
+    ///  has no prime factor above B₂, and at most one above B₁.
+    fn find_divisor(&self, b1: u64, primes: &[u64]) -> Option<BigUint> {
+        // Stage 1: multiply the starting point by all prime powers up to B₁
+        let mut p = self.start.clone();
+        for &prime in primes {
+            let mut q = prime;
+            while q * prime <= b1 {
+                q *= prime;
+            }
+            p = self.scale(&p, q);
+        }
+        let g = p.z.gcd(self.n);
+        if !g.is_one() {
+            return self.divisor(&g);
+        }
+
+        // Stage 2: look for (kD ± j) p = 0, for every kD ± j up to B₂ coprime with D;
+        //  this is the case modulo some prime exactly when kDp and jp have the
+        //  same x coordinate modulo that prime.
+        const D: u64 = 210;
+        let two_p = self.double(&p);
+        let mut baby_steps = vec![p.clone()];
+        let (mut previous, mut current) = (p.clone(), self.add(&two_p, &p, &p));
+        for j in (3..D / 2).step_by(2) {
+            if j.gcd(&D) == 1 {
+                baby_steps.push(current.clone());
+            }
+            let next = self.add(&current, &two_p, &previous);
+            previous = std::mem::replace(&mut current, next);
+        }
+
+        let d_p = self.scale(&p, D);
+        let mut k = b1 / D;
+        let mut previous = self.scale(&p, (k - 1) * D);
+        let mut giant_step = self.scale(&p, k * D);
+        let mut product = BigUint::one();
+        while k * D <= b1 * ECM_B2_RATIO + D / 2 {
+            for s in &baby_steps {
+                let x_diff = self.sub(
+                    &self.mul(&giant_step.x, &s.z),
+                    &self.mul(&s.x, &giant_step.z),
+                );
+                product = self.mul(&product, &x_diff);
+            }
+            let next = self.add(&giant_step, &d_p, &previous);
+            previous = std::mem::replace(&mut giant_step, next);
+            k += 1;
+        }
+        self.divisor(&product)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn big(s: &str) -> BigUint {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn factor_recombines() {
+        for n in [
+            "18446744073709551616",
+            "18446744073709551617",
+            "340282366920938463463374607431768211455",
+            "99999999999999999999999999999999999999999999",
+            "1000000000000000000000000000009",
+        ] {
+            assert_eq!(factor_big(&big(n)).product(), big(n));
+        }
+    }
+
+    #[test]
+    fn primes() {
+        for p in [
+            "18446744073709551629",
+            "170141183460469231731687303715884105727",
+            "618970019642690137449562111",
+        ] {
+            assert!(is_probable_prime(&big(p)));
+        }
+    }
+
+    #[test]
+    fn composites() {
+        // Strong pseudoprimes to several bases
+        for n in ["3825123056546413051", "318665857834031151167461"] {
+            assert!(!is_probable_prime(&big(n)));
+        }
+    }
+
+    #[test]
+    fn perfect_powers() {
+        let p = big("18446744073709551629");
+        assert_eq!(perfect_power(&p.pow(3)), Some((p, 3)));
+        assert_eq!(perfect_power(&big("18446744073709551617")), None);
+    }
+
+    #[test]
+    fn ecm_finds_factors() {
+        let p = big("18446744073709551629");
+        let q = big("1000000000039");
+        let n = &p * &q;
+        let d = ecm(&n);
+        assert!(d == p || d == q);
+    }
+
+    #[test]
+    fn display_exponents() {
+        let f = factor_big(&(big("18446744073709551629").pow(2) * 12u32));
+        assert_eq!(
+            f.to_string(),
+            " 2 2 3 18446744073709551629 18446744073709551629"
+        );
+        assert_eq!(format!("{f:#}"), " 2^2 3 18446744073709551629^2");
+    }
+}
@@ -7,13 +7,17 @@
 // * that was distributed with this source code.
 
 use std::error::Error;
-use std::fmt::Write as FmtWrite;
+use std::fmt::{self, Display, Write as FmtWrite};
 use std::io::BufRead;
 use std::io::{self, stdin, stdout, Write};
+use std::num::IntErrorKind;
 
+mod big;
 mod factor;
+pub use big::*;
 use clap::{crate_version, Arg, ArgAction, Command};
 pub use factor::*;
+use num_bigint::BigUint;
 use uucore::display::Quotable;
 use uucore::error::UResult;
 use uucore::{show_error, show_warning};
@@ -27,24 +31,42 @@ static ABOUT: &str = r#"Print the prime factors of the given NUMBER(s).
 If none are specified, read from standard input."#;
 
 mod options {
+    pub static EXPONENTS: &str = "exponents";
+    pub static HELP: &str = "help";
     pub static NUMBER: &str = "NUMBER";
 }
 
+fn write_factors(
+    factors_buffer: &mut String,
+    n: impl Display,
+    factors: impl Display,
+    print_exponents: bool,
+) -> fmt::Result {
+    if print_exponents {
+        writeln!(factors_buffer, "{n}:{factors:#}")
+    } else {
+        writeln!(factors_buffer, "{n}:{factors}")
+    }
+}
+
 fn print_factors_str(
     num_str: &str,
     w: &mut io::BufWriter<impl io::Write>,
     factors_buffer: &mut String,
+    print_exponents: bool,
 ) -> Result<(), Box<dyn Error>> {
-    num_str
-        .trim()
-        .parse::<u64>()
-        .map_err(|e| e.into())
-        .and_then(|x| {
-            factors_buffer.clear();
-            writeln!(factors_buffer, "{}:{}", x, factor(x))?;
-            w.write_all(factors_buffer.as_bytes())?;
-            Ok(())
-        })
+    let num_str = num_str.trim();
+    factors_buffer.clear();
+    match num_str.parse::<u64>() {
+        Ok(x) => write_factors(factors_buffer, x, factor(x), print_exponents)?,
+        Err(e) if *e.kind() == IntErrorKind::PosOverflow => {
+            let x = num_str.parse::<BigUint>()?;
+            write_factors(factors_buffer, &x, factor_big(&x), print_exponents)?;
+        }
+        Err(e) => return Err(e.into()),
+    }
+    w.write_all(factors_buffer.as_bytes())?;
+    Ok(())
 }
 
 #[uucore::main]
@@ -54,10 +76,12 @@ pub fn uumain(args: impl uucore::Args) -> UResult<()> {
     // We use a smaller buffer here to pass a gnu test. 4KiB appears to be the default pipe size for bash.
     let mut w = io::BufWriter::with_capacity(4 * 1024, stdout.lock());
     let mut factors_buffer = String::new();
+    let print_exponents = matches.get_flag(options::EXPONENTS);
 
     if let Some(values) = matches.get_many::<String>(options::NUMBER) {
         for number in values {
-            if let Err(e) = print_factors_str(number, &mut w, &mut factors_buffer) {
+            if let Err(e) = print_factors_str(number, &mut w, &mut factors_buffer, print_exponents)
+            {
                 show_warning!("{}: {}", number.maybe_quote(), e);
             }
         }
@@ -66,7 +90,9 @@ pub fn uumain(args: impl uucore::Args) -> UResult<()> {
         let lines = stdin.lock().lines();
         for line in lines {
             for number in line.unwrap().split_whitespace() {
-                if let Err(e) = print_factors_str(number, &mut w, &mut factors_buffer) {
+                if let Err(e) =
+                    print_factors_str(number, &mut w, &mut factors_buffer, print_exponents)
+                {
                     show_warning!("{}: {}", number.maybe_quote(), e);
                 }
             }
@@ -85,5 +111,19 @@ pub fn uu_app() -> Command {
         .version(crate_version!())
         .about(ABOUT)
         .infer_long_args(true)
+        .disable_help_flag(true)
         .arg(Arg::new(options::NUMBER).action(ArgAction::Append))
+        .arg(
+            Arg::new(options::EXPONENTS)
+                .short('h')
+                .long(options::EXPONENTS)
+                .help("Print repeated factors in form p^e unless e is 1")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new(options::HELP)
+                .long(options::HELP)
+                .help("Print help information.")
+                .action(ArgAction::Help),
+        )
 }
@@ -86,6 +86,11 @@ impl Factors {
         self.add(prime, 1);
     }
 
+    /// The prime factors with their exponents, in no particular order.
+    pub(crate) fn to_vec(&self) -> Vec<(u64, Exponent)> {
+        self.0.borrow().0.to_vec()
+    }
+
     #[cfg(test)]
     fn product(&self) -> u64 {
         self.0.borrow().product()
@@ -93,13 +98,18 @@ impl Factors {
 }
 
 impl fmt::Display for Factors {
+    /// The alternate form (`{:#}`) prints repeated factors as `p^e`.
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let v = &mut (self.0).borrow_mut().0;
         v.sort_unstable();
 
         for (p, exp) in v.iter() {
-            for _ in 0..*exp {
-                write!(f, " {p}")?;
+            if f.alternate() && *exp > 1 {
+                write!(f, " {p}^{exp}")?;
+            } else {
+                for _ in 0..*exp {
+                    write!(f, " {p}")?;
+                }
             }
         }
 
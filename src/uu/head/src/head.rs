@@ -360,43 +360,39 @@ fn find_nth_line_from_end<R>(input: &mut R, n: u64, zeroed: bool) -> std::io::Re
 where
     R: Read + Seek,
 {
+    let separator = if zeroed { b'\0' } else { b'\n' };
     let size = input.seek(SeekFrom::End(0))?;
+    if n == 0 {
+        input.rewind()?;
+        return Ok(size);
+    }
 
     let mut buffer = [0u8; BUF_SIZE];
-    let buf_size: usize = (BUF_SIZE as u64).min(size).try_into().unwrap();
-    let buffer = &mut buffer[..buf_size];
-
-    let mut i = 0u64;
     let mut lines = 0u64;
-
-    loop {
-        // the casts here are ok, `buffer.len()` should never be above a few k
-        input.seek(SeekFrom::Current(
-            -((buffer.len() as i64).min((size - i) as i64)),
-        ))?;
-        input.read_exact(buffer)?;
-        for byte in buffer.iter().rev() {
-            match byte {
-                b'\n' if !zeroed => {
-                    lines += 1;
-                }
-                0u8 if zeroed => {
-                    lines += 1;
-                }
-                _ => {}
-            }
-            // if it were just `n`,
-            if lines == n + 1 {
+    // The bytes before `end` have not been searched yet.
+    let mut end = size;
+    while end > 0 {
+        // the cast here is ok, the length is at most `BUF_SIZE`
+        let len = (BUF_SIZE as u64).min(end) as usize;
+        let start = end - len as u64;
+        input.seek(SeekFrom::Start(start))?;
+        input.read_exact(&mut buffer[..len])?;
+        let mut chunk = &buffer[..len];
+        // The last line counts as a line whether or not it is terminated.
+        if end == size && chunk.last() == Some(&separator) {
+            chunk = &chunk[..len - 1];
+        }
+        for i in memchr::memrchr_iter(separator, chunk) {
+            lines += 1;
+            if lines == n {
                 input.rewind()?;
-                return Ok(size - i);
+                return Ok(start + i as u64 + 1);
             }
-            i += 1;
-        }
-        if size - i == 0 {
-            input.rewind()?;
-            return Ok(0);
         }
+        end = start;
     }
+    input.rewind()?;
+    Ok(0)
 }
 
 fn head_backwards_file(input: &mut std::fs::File, options: &HeadOptions) -> std::io::Result<()> {
@@ -434,7 +430,20 @@ fn head_file(input: &mut std::fs::File, options: &HeadOptions) -> std::io::Resul
             n,
             options.zeroed,
         ),
-        Mode::AllButLastBytes(_) | Mode::AllButLastLines(_) => head_backwards_file(input, options),
+        Mode::AllButLastBytes(n) | Mode::AllButLastLines(n) => {
+            let metadata = input.metadata()?;
+            if metadata.is_file() && metadata.len() > 0 {
+                return head_backwards_file(input, options);
+            }
+            // The size of some files, e.g. in /proc, is not known, so they are read like a pipe.
+            let n = usize::try_from(n).map_err(|_| io::Error::from(ErrorKind::OutOfMemory))?;
+            let mut input = std::io::BufReader::with_capacity(BUF_SIZE, input);
+            if let Mode::AllButLastBytes(_) = options.mode {
+                read_but_last_n_bytes(&mut input, n)
+            } else {
+                read_but_last_n_lines(input, n, options.zeroed)
+            }
+        }
     }
 }
 
@@ -651,4 +660,27 @@ mod tests {
         assert_eq!(find_nth_line_from_end(&mut input, 4, false).unwrap(), 0);
         assert_eq!(find_nth_line_from_end(&mut input, 1000, false).unwrap(), 0);
     }
+
+    #[test]
+    fn test_find_nth_line_from_end_unterminated() {
+        let mut input = Cursor::new("x\ny\nz");
+        assert_eq!(find_nth_line_from_end(&mut input, 1, false).unwrap(), 4);
+        assert_eq!(find_nth_line_from_end(&mut input, 3, false).unwrap(), 0);
+        let mut input = Cursor::new("x\0y\0");
+        assert_eq!(find_nth_line_from_end(&mut input, 1, true).unwrap(), 2);
+    }
+
+    #[test]
+    fn test_find_nth_line_from_end_long_input() {
+        let data = "x\n".repeat(BUF_SIZE);
+        let mut input = Cursor::new(&data);
+        assert_eq!(
+            find_nth_line_from_end(&mut input, 1000, false).unwrap(),
+            (data.len() - 2000) as u64
+        );
+        assert_eq!(
+            find_nth_line_from_end(&mut input, BUF_SIZE as u64 + 1, false).unwrap(),
+            0
+        );
+    }
 }
@@ -10,22 +10,22 @@
 mod mode;
 
 use clap::{crate_version, Arg, ArgAction, ArgMatches, Command};
-use file_diff::diff;
 use filetime::{set_file_times, FileTime};
 use uucore::backup_control::{self, BackupMode};
 use uucore::display::Quotable;
 use uucore::entries::{grp2gid, usr2uid};
 use uucore::error::{FromIo, UError, UIoError, UResult, UUsageError};
 use uucore::fs::dir_strip_dot_for_creation;
-use uucore::mode::get_umask;
 use uucore::perms::{wrap_chown, Verbosity, VerbosityLevel};
-use uucore::{format_usage, show, show_error, show_if_err, uio_error};
+use uucore::security_context::{selinux_enabled, set_security_context};
+use uucore::{format_usage, show, show_error, show_if_err, show_warning, uio_error};
 
-use libc::{getegid, geteuid};
+use libc::{getgid, getuid};
 use std::error::Error;
 use std::fmt::{Debug, Display};
 use std::fs;
 use std::fs::File;
+use std::io::Read;
 use std::os::unix::fs::MetadataExt;
 #[cfg(unix)]
 use std::os::unix::prelude::OsStrExt;
@@ -48,6 +48,10 @@ pub struct Behavior {
     compare: bool,
     strip: bool,
     strip_program: String,
+    /// Whether to give installed files the default SELinux security context
+    default_context: bool,
+    /// The SELinux security context to give installed files, if specified
+    context: Option<String>,
     create_leading: bool,
     target_dir: Option<String>,
 }
@@ -273,7 +277,10 @@ pub fn uu_app() -> Command {
         .arg(
             Arg::new(OPT_STRIP_PROGRAM)
                 .long(OPT_STRIP_PROGRAM)
-                .help("program used to strip binaries (no action Windows)")
+                .help(
+                    "program used to strip binaries, with any arguments to give it \
+                    (no action Windows)",
+                )
                 .value_name("PROGRAM")
                 .value_hint(clap::ValueHint::CommandName),
         )
@@ -311,13 +318,16 @@ pub fn uu_app() -> Command {
                 .action(ArgAction::SetTrue),
         )
         .arg(
-            // TODO implement flag
             Arg::new(OPT_CONTEXT)
                 .short('Z')
                 .long(OPT_CONTEXT)
-                .help("(unimplemented) set security context of files and directories")
+                .help(
+                    "set the SELinux security context of destination files and created \
+                    directories to CONTEXT, or to the default type without it",
+                )
                 .value_name("CONTEXT")
-                .action(ArgAction::SetTrue),
+                .num_args(0..=1)
+                .require_equals(true),
         )
         .arg(
             Arg::new(ARG_FILES)
@@ -341,8 +351,6 @@ fn check_unimplemented(matches: &ArgMatches) -> UResult<()> {
         Err(InstallError::Unimplemented(String::from("--no-target-directory, -T")).into())
     } else if matches.get_flag(OPT_PRESERVE_CONTEXT) {
         Err(InstallError::Unimplemented(String::from("--preserve-context, -P")).into())
-    } else if matches.get_flag(OPT_CONTEXT) {
-        Err(InstallError::Unimplemented(String::from("--context, -Z")).into())
     } else {
         Ok(())
     }
@@ -367,8 +375,8 @@ fn behavior(matches: &ArgMatches) -> UResult<Behavior> {
 
     let specified_mode: Option<u32> = if matches.contains_id(OPT_MODE) {
         let x = matches.get_one::<String>(OPT_MODE).ok_or(1)?;
-        Some(mode::parse(x, considering_dir, get_umask()).map_err(|err| {
-            show_error!("Invalid mode string: {}", err);
+        Some(mode::parse(x, considering_dir).map_err(|err| {
+            show_error!("{}", err);
             1
         })?)
     } else {
@@ -391,6 +399,18 @@ fn behavior(matches: &ArgMatches) -> UResult<Behavior> {
         show_error!("Options --compare and --strip are mutually exclusive");
         return Err(1.into());
     }
+
+    // Like GNU, there is nothing to do for -Z without SELinux, but a context
+    // that was asked for is worth a warning.
+    let mut default_context = matches.contains_id(OPT_CONTEXT);
+    let mut context = matches.get_one::<String>(OPT_CONTEXT).cloned();
+    if default_context && !selinux_enabled() {
+        if context.is_some() {
+            show_warning!("ignoring --context; it requires an SELinux-enabled kernel");
+        }
+        default_context = false;
+        context = None;
+    }
     Ok(Behavior {
         main_function,
         specified_mode,
@@ -416,6 +436,8 @@ fn behavior(matches: &ArgMatches) -> UResult<Behavior> {
                 .map(|s| s.as_str())
                 .unwrap_or(DEFAULT_STRIP_PROGRAM),
         ),
+        default_context,
+        context,
         create_leading: matches.get_flag(OPT_CREATE_LEADING),
         target_dir,
     })
@@ -466,6 +488,8 @@ fn directory(paths: &[String], b: &Behavior) -> UResult<()> {
                 uucore::error::set_exit_code(1);
                 continue;
             }
+
+            show_if_err!(set_context(path, b));
         }
         // If the exit code was set, or show! has been called at least once
         // (which sets the exit code as well), function execution will end after
@@ -564,6 +588,8 @@ fn standard(mut paths: Vec<String>, b: &Behavior) -> UResult<()> {
                 if mode::chmod(to_create, b.mode()).is_err() {
                     return Err(InstallError::ChmodFailed(to_create.to_path_buf()).into());
                 }
+
+                set_context(to_create, b)?;
             }
         }
     }
@@ -669,6 +695,15 @@ fn copy(from: &Path, to: &Path, b: &Behavior) -> UResult<()> {
         }
     }
 
+    // Like GNU, replace a symlink rather than copying to the file it points to.
+    if backup_path.is_none() && to.is_symlink() {
+        if let Err(err) = fs::remove_file(to) {
+            return Err(
+                InstallError::InstallFailed(from.to_path_buf(), to.to_path_buf(), err).into(),
+            );
+        }
+    }
+
     if from.as_os_str() == "/dev/null" {
         /* workaround a limitation of fs::copy
          * https://github.com/rust-lang/rust/issues/79390
@@ -683,22 +718,10 @@ fn copy(from: &Path, to: &Path, b: &Behavior) -> UResult<()> {
     }
 
     if b.strip && cfg!(not(windows)) {
-        match process::Command::new(&b.strip_program).arg(to).output() {
-            Ok(o) => {
-                if !o.status.success() {
-                    // Follow GNU's behavior: if strip fails, removes the target
-                    let _ = fs::remove_file(to);
-                    return Err(InstallError::StripProgramFailed(
-                        String::from_utf8(o.stderr).unwrap_or_default(),
-                    )
-                    .into());
-                }
-            }
-            Err(e) => {
-                // Follow GNU's behavior: if strip fails, removes the target
-                let _ = fs::remove_file(to);
-                return Err(InstallError::StripProgramFailed(e.to_string()).into());
-            }
+        if let Err(e) = strip_file(to, b) {
+            // Follow GNU's behavior: if strip fails, removes the target
+            let _ = fs::remove_file(to);
+            return Err(e);
         }
     }
 
@@ -784,6 +807,8 @@ fn copy(from: &Path, to: &Path, b: &Behavior) -> UResult<()> {
         }
     }
 
+    set_context(to, b)?;
+
     if b.verbose {
         print!("{} -> {}", from.quote(), to.quote());
         match backup_path {
@@ -795,13 +820,41 @@ fn copy(from: &Path, to: &Path, b: &Behavior) -> UResult<()> {
     Ok(())
 }
 
+/// Strip the symbol tables of an installed file with the strip program,
+/// which is a command name that may be followed by arguments to give it
+/// before the file name.
+fn strip_file(to: &Path, b: &Behavior) -> UResult<()> {
+    let mut words = b.strip_program.split_whitespace();
+    let program = words.next().unwrap_or(DEFAULT_STRIP_PROGRAM);
+    match process::Command::new(program).args(words).arg(to).output() {
+        Ok(o) if o.status.success() => Ok(()),
+        Ok(o) => Err(InstallError::StripProgramFailed(
+            String::from_utf8(o.stderr).unwrap_or_default(),
+        )
+        .into()),
+        Err(e) => Err(InstallError::StripProgramFailed(e.to_string()).into()),
+    }
+}
+
+/// Give an installed file or created directory the security context asked
+/// for with -Z, or the default one for its path.
+fn set_context(path: &Path, b: &Behavior) -> UResult<()> {
+    if b.default_context {
+        set_security_context(path, b.context.as_deref()).map_err_context(|| {
+            format!("failed to set the security context of {}", path.quote())
+        })?;
+    }
+    Ok(())
+}
+
 /// Return true if a file is necessary to copy. This is the case when:
 ///
 /// - _from_ or _to_ is nonexistent;
 /// - either file has a sticky bit or set\[ug\]id bit, or the user specified one;
 /// - either file isn't a regular file;
 /// - the sizes of _from_ and _to_ differ;
-/// - _to_'s owner differs from intended; or
+/// - _to_'s mode differs from intended;
+/// - _to_'s owner or group differs from intended; or
 /// - the contents of _from_ and _to_ differ.
 ///
 /// # Parameters
@@ -810,71 +863,96 @@ fn copy(from: &Path, to: &Path, b: &Behavior) -> UResult<()> {
 ///
 /// # Errors
 ///
-/// Crashes the program if a nonexistent owner or group is specified in _b_.
+/// Returns an error if a nonexistent owner or group is specified in _b_.
 ///
 fn need_copy(from: &Path, to: &Path, b: &Behavior) -> UResult<bool> {
-    let from_meta = match fs::metadata(from) {
+    // setuid || setgid || sticky
+    let extra_mode: u32 = 0o7000;
+    // setuid || setgid || sticky || permissions
+    let all_modes: u32 = 0o7777;
+
+    if b.specified_mode.unwrap_or(0) & extra_mode != 0 {
+        return Ok(true);
+    }
+
+    // Like GNU, symlinks aren't followed: a link is never up to date.
+    let from_meta = match fs::symlink_metadata(from) {
         Ok(meta) => meta,
         Err(_) => return Ok(true),
     };
-    let to_meta = match fs::metadata(to) {
+    let to_meta = match fs::symlink_metadata(to) {
         Ok(meta) => meta,
         Err(_) => return Ok(true),
     };
 
-    // setuid || setgid || sticky
-    let extra_mode: u32 = 0o7000;
-    // setuid || setgid || sticky || permissions
-    let all_modes: u32 = 0o7777;
-
-    if b.specified_mode.unwrap_or(0) & extra_mode != 0
+    if !from_meta.is_file()
+        || !to_meta.is_file()
         || from_meta.mode() & extra_mode != 0
         || to_meta.mode() & extra_mode != 0
     {
         return Ok(true);
     }
-    if b.mode() != to_meta.mode() & all_modes {
-        return Ok(true);
-    }
-
-    if !from_meta.is_file() || !to_meta.is_file() {
-        return Ok(true);
-    }
 
-    if from_meta.len() != to_meta.len() {
+    if from_meta.len() != to_meta.len() || b.mode() != to_meta.mode() & all_modes {
         return Ok(true);
     }
 
     // TODO: if -P (#1809) and from/to contexts mismatch, return true.
 
-    if !b.owner.is_empty() {
-        let owner_id = match usr2uid(&b.owner) {
+    // Files are installed with the owner and group asked for, or with the
+    // user's own (real) ones.
+    let owner_id = if b.owner.is_empty() {
+        unsafe { getuid() }
+    } else {
+        match usr2uid(&b.owner) {
             Ok(id) => id,
             _ => return Err(InstallError::NoSuchUser(b.owner.clone()).into()),
-        };
-        if owner_id != to_meta.uid() {
-            return Ok(true);
         }
-    } else if !b.group.is_empty() {
-        let group_id = match grp2gid(&b.group) {
+    };
+    let group_id = if b.group.is_empty() {
+        unsafe { getgid() }
+    } else {
+        match grp2gid(&b.group) {
             Ok(id) => id,
             _ => return Err(InstallError::NoSuchGroup(b.group.clone()).into()),
-        };
-        if group_id != to_meta.gid() {
-            return Ok(true);
-        }
-    } else {
-        #[cfg(not(target_os = "windows"))]
-        unsafe {
-            if to_meta.uid() != geteuid() || to_meta.gid() != getegid() {
-                return Ok(true);
-            }
         }
+    };
+    if owner_id != to_meta.uid() || group_id != to_meta.gid() {
+        return Ok(true);
     }
 
-    if !diff(from.to_str().unwrap(), to.to_str().unwrap()) {
-        return Ok(true);
+    // A file that can't be read has to be copied, which reports the error.
+    Ok(!have_same_content(from, to).unwrap_or(false))
+}
+
+/// Compare the contents of two files, a block at a time.
+fn have_same_content(a: &Path, b: &Path) -> std::io::Result<bool> {
+    let mut a = File::open(a)?;
+    let mut b = File::open(b)?;
+    let mut a_buf = [0; 8192];
+    let mut b_buf = [0; 8192];
+    loop {
+        let n = read_full(&mut a, &mut a_buf)?;
+        if n != read_full(&mut b, &mut b_buf)? || a_buf[..n] != b_buf[..n] {
+            return Ok(false);
+        }
+        if n == 0 {
+            return Ok(true);
+        }
     }
+}
 
-    Ok(false)
+/// Read until `buf` is full or the end of the file, and return how much
+/// was read.
+fn read_full(file: &mut File, buf: &mut [u8]) -> std::io::Result<usize> {
+    let mut total = 0;
+    while total < buf.len() {
+        match file.read(&mut buf[total..]) {
+            Ok(0) => break,
+            Ok(n) => total += n,
+            Err(e) if e.kind() == std::io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(total)
 }
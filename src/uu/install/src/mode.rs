@@ -4,15 +4,9 @@ use std::path::Path;
 use uucore::mode;
 
 /// Takes a user-supplied string and tries to parse to u16 mode bitmask.
-pub fn parse(mode_string: &str, considering_dir: bool, umask: u32) -> Result<u32, String> {
-    let numbers: &[char] = &['0', '1', '2', '3', '4', '5', '6', '7', '8', '9'];
-
-    // Passing 000 as the existing permissions seems to mirror GNU behavior.
-    if mode_string.contains(numbers) {
-        mode::parse_numeric(0, mode_string, considering_dir)
-    } else {
-        mode::parse_symbolic(0, mode_string, umask, considering_dir)
-    }
+pub fn parse(mode_string: &str, considering_dir: bool) -> Result<u32, String> {
+    // Like GNU, the mode applies to 000 and ignores the umask.
+    Ok(mode::Mode::parse(mode_string)?.apply(0, considering_dir, 0))
 }
 
 /// chmod a file or directory on UNIX.
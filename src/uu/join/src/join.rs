@@ -421,9 +421,9 @@ impl<'a> State<'a> {
         other: &State,
         repr: &Repr,
     ) -> Result<(), std::io::Error> {
-        let key = self.get_current_key();
-
         for line1 in &self.seq {
+            // The keys only compare equal, and differ in case with -i.
+            let key = line1.get_field(self.key);
             for line2 in &other.seq {
                 if repr.uses_format() {
                     repr.print_format(writer, |spec| match *spec {
@@ -535,6 +535,7 @@ impl<'a> State<'a> {
                 return Ok(Some(line));
             }
 
+            // The lines in seq have the key of the previous line of this file.
             let diff = input.compare(self.get_current_key(), line.get_field(self.key));
 
             if diff == Ordering::Greater
@@ -543,7 +544,7 @@ impl<'a> State<'a> {
             {
                 let err_msg = format!(
                     "{}:{}: is not sorted: {}",
-                    self.file_name.maybe_quote(),
+                    self.file_name,
                     self.line_num,
                     String::from_utf8_lossy(&line.string)
                 );
@@ -778,12 +779,14 @@ FILENUM is 1 or 2, corresponding to FILE1 or FILE2",
                     "check that the input is correctly sorted, \
              even if all input lines are pairable",
                 )
+                .overrides_with("nocheck-order")
                 .action(ArgAction::SetTrue),
         )
         .arg(
             Arg::new("nocheck-order")
                 .long("nocheck-order")
                 .help("do not check that the input is correctly sorted")
+                .overrides_with("check-order")
                 .action(ArgAction::SetTrue),
         )
         .arg(
@@ -8,16 +8,19 @@
 // spell-checker:ignore (ToDO) signalname pids killpg
 
 use clap::{crate_version, Arg, ArgAction, Command};
-use nix::sys::signal::{self, Signal};
-use nix::unistd::Pid;
 use std::io::Error;
 use uucore::display::Quotable;
 use uucore::error::{FromIo, UError, UResult, USimpleError};
-use uucore::signals::{signal_by_name_or_value, ALL_SIGNALS};
+use uucore::signals::{
+    is_signal, max_signal, signal_by_name_or_value, signal_description, signal_name_by_value,
+};
 use uucore::{format_usage, show};
 
 static ABOUT: &str = "Send signal to processes or list information about signals.";
-const USAGE: &str = "{} [OPTIONS]... PID...";
+const USAGE: &str = "\
+    {} [-s SIGNAL | -SIGNAL] PID...
+    {} -l [SIGNAL]...
+    {} -t [SIGNAL]...";
 
 pub mod options {
     pub static PIDS_OR_SIGNALS: &str = "pids_or_signals";
@@ -62,18 +65,18 @@ pub fn uumain(args: impl uucore::Args) -> UResult<()> {
             } else {
                 15_usize //SIGTERM
             };
-            let sig: Signal = (sig as i32)
-                .try_into()
-                .map_err(|e| std::io::Error::from_raw_os_error(e as i32))?;
             let pids = parse_pids(&pids_or_signals)?;
             kill(sig, &pids);
             Ok(())
         }
         Mode::Table => {
-            table();
+            table(&pids_or_signals);
+            Ok(())
+        }
+        Mode::List => {
+            list(&pids_or_signals);
             Ok(())
         }
-        Mode::List => list(pids_or_signals.get(0)),
     }
 }
 
@@ -88,8 +91,8 @@ pub fn uu_app() -> Command {
             Arg::new(options::LIST)
                 .short('l')
                 .long(options::LIST)
-                .help("Lists signals")
-                .conflicts_with(options::TABLE)
+                .help("list signal names, or convert signal names to/from numbers")
+                .conflicts_with_all([options::TABLE, options::SIGNAL])
                 .action(ArgAction::SetTrue),
         )
         .arg(
@@ -97,15 +100,17 @@ pub fn uu_app() -> Command {
                 .short('t')
                 .short_alias('L')
                 .long(options::TABLE)
-                .help("Lists table of signals")
+                .help("print a table of signal information")
+                .conflicts_with(options::SIGNAL)
                 .action(ArgAction::SetTrue),
         )
         .arg(
             Arg::new(options::SIGNAL)
                 .short('s')
+                .short_alias('n')
                 .long(options::SIGNAL)
                 .value_name("signal")
-                .help("Sends given signal instead of SIGTERM"),
+                .help("specify the name or number of the signal to be sent"),
         )
         .arg(
             Arg::new(options::PIDS_OR_SIGNALS)
@@ -117,11 +122,16 @@ pub fn uu_app() -> Command {
 fn handle_obsolete(args: &mut Vec<String>) -> Option<usize> {
     // Sanity check
     if args.len() > 2 {
-        // Old signal can only be in the first argument position
+        // Old signal can only be in the first argument position, and starts
+        // with a digit or an uppercase letter, so it can't be taken for an
+        // option.
         let slice = args[1].as_str();
-        if let Some(signal) = slice.strip_prefix('-') {
+        if let Some(signal) = slice
+            .strip_prefix('-')
+            .filter(|s| s.starts_with(|c: char| c.is_ascii_digit() || c.is_ascii_uppercase()))
+        {
             // Check if it is a valid signal
-            let opt_signal = signal_by_name_or_value(signal);
+            let opt_signal = parse_signal_value(signal).ok();
             if opt_signal.is_some() {
                 // remove the signal before return
                 args.remove(1);
@@ -132,61 +142,73 @@ fn handle_obsolete(args: &mut Vec<String>) -> Option<usize> {
     None
 }
 
-fn table() {
-    let name_width = ALL_SIGNALS.iter().map(|n| n.len()).max().unwrap();
+/// Print a row of the signal table for each of `signals`, or for all signals.
+fn table(signals: &[String]) {
+    let number_width = max_signal().to_string().len();
+    let name_width = (1..=max_signal())
+        .filter_map(signal_name_by_value)
+        .map(|name| name.len())
+        .max()
+        .unwrap_or_default();
+    let print_row = |signal: usize| {
+        println!(
+            "{:>number_width$} {:<name_width$} {}",
+            signal,
+            signal_name_by_value(signal).unwrap_or_default(),
+            signal_description(signal).unwrap_or_else(|| "?".to_string()),
+        );
+    };
 
-    for (idx, signal) in ALL_SIGNALS.iter().enumerate() {
-        print!("{0: >#2} {1: <#2$}", idx, signal, name_width + 2);
-        if (idx + 1) % 7 == 0 {
-            println!();
-        }
+    if signals.is_empty() {
+        (1..=max_signal())
+            .filter(|&s| is_signal(s))
+            .for_each(print_row);
     }
-    println!();
-}
-
-fn print_signal(signal_name_or_value: &str) -> UResult<()> {
-    for (value, &signal) in ALL_SIGNALS.iter().enumerate() {
-        if signal == signal_name_or_value || (format!("SIG{signal}")) == signal_name_or_value {
-            println!("{value}");
-            return Ok(());
-        } else if signal_name_or_value == value.to_string() {
-            println!("{signal}");
-            return Ok(());
+    for signal in signals {
+        match parse_signal_value(signal) {
+            Ok(value) => print_row(value),
+            Err(e) => show!(e),
         }
     }
-    Err(USimpleError::new(
-        1,
-        format!("unknown signal name {}", signal_name_or_value.quote()),
-    ))
 }
 
-fn print_signals() {
-    for (idx, signal) in ALL_SIGNALS.iter().enumerate() {
-        if idx > 0 {
-            print!(" ");
+/// Print the names of all signals, or convert each of `signals` from a
+/// number to a name or from a name to a number.
+fn list(signals: &[String]) {
+    if signals.is_empty() {
+        for name in (1..=max_signal()).filter_map(signal_name_by_value) {
+            println!("{name}");
         }
-        print!("{signal}");
     }
-    println!();
-}
-
-fn list(arg: Option<&String>) -> UResult<()> {
-    match arg {
-        Some(x) => print_signal(x),
-        None => {
-            print_signals();
-            Ok(())
+    for signal in signals {
+        match parse_signal_value(signal) {
+            Ok(value) if signal.starts_with(|c: char| c.is_ascii_digit()) => {
+                println!("{}", signal_name_by_value(value).unwrap_or_default());
+            }
+            Ok(value) => println!("{value}"),
+            Err(e) => show!(e),
         }
     }
 }
 
+/// Parse a signal name or number. Numbers above 128 are taken as the exit
+/// status of a command killed by the signal, like the shell reports it.
 fn parse_signal_value(signal_name: &str) -> UResult<usize> {
-    let optional_signal_value = signal_by_name_or_value(signal_name);
+    let optional_signal_value = if signal_name.starts_with(|c: char| c.is_ascii_digit()) {
+        signal_name
+            .parse::<usize>()
+            .ok()
+            // Some shells add 256 instead of 128 to the signal number.
+            .map(|value| value & if value >= 0xff { 0xff } else { 0x7f })
+            .filter(|&value| is_signal(value))
+    } else {
+        signal_by_name_or_value(signal_name)
+    };
     match optional_signal_value {
         Some(x) => Ok(x),
         None => Err(USimpleError::new(
             1,
-            format!("unknown signal name {}", signal_name.quote()),
+            format!("{}: invalid signal", signal_name.quote()),
         )),
     }
 }
@@ -201,10 +223,11 @@ fn parse_pids(pids: &[String]) -> UResult<Vec<i32>> {
         .collect()
 }
 
-fn kill(sig: Signal, pids: &[i32]) {
+fn kill(sig: usize, pids: &[i32]) {
     for &pid in pids {
-        if let Err(e) = signal::kill(Pid::from_raw(pid), sig) {
-            show!(Error::from_raw_os_error(e as i32)
+        // SAFETY: kill only sends a signal, and the signal number is valid.
+        if unsafe { libc::kill(pid, sig as libc::c_int) } != 0 {
+            show!(Error::last_os_error()
                 .map_err_context(|| format!("sending signal to {pid} failed")));
         }
     }
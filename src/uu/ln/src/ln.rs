@@ -54,6 +54,8 @@ enum LnError {
     SameFile(PathBuf, PathBuf),
     MissingDestination(PathBuf),
     ExtraOperand(OsString),
+    RelativeWithoutSymbolic,
+    HardLinkToDirectory(PathBuf),
 }
 
 impl Display for LnError {
@@ -73,6 +75,16 @@ impl Display for LnError {
                 s.quote(),
                 uucore::execution_phrase()
             ),
+            Self::RelativeWithoutSymbolic => {
+                write!(f, "cannot do --relative without --symbolic")
+            }
+            Self::HardLinkToDirectory(s) => {
+                write!(
+                    f,
+                    "{}: hard link not allowed for directory",
+                    s.maybe_quote()
+                )
+            }
         }
     }
 }
@@ -154,16 +166,22 @@ pub fn uumain(args: impl uucore::Args) -> UResult<()> {
     let backup_mode = backup_control::determine_backup_mode(&matches)?;
     let backup_suffix = backup_control::determine_backup_suffix(&matches);
 
-    // When we have "-L" or "-L -P", false otherwise
+    // -L and -P override each other; like GNU, hard links are made to
+    // symlinks themselves unless -L is last.
     let logical = matches.get_flag(options::LOGICAL);
 
+    let relative = matches.get_flag(options::RELATIVE);
+    if relative && !symbolic {
+        return Err(LnError::RelativeWithoutSymbolic.into());
+    }
+
     let settings = Settings {
         overwrite: overwrite_mode,
         backup: backup_mode,
         suffix: backup_suffix,
         symbolic,
         logical,
-        relative: matches.get_flag(options::RELATIVE),
+        relative,
         target_dir: matches
             .get_one::<String>(options::TARGET_DIRECTORY)
             .map(String::from),
@@ -222,7 +240,6 @@ pub fn uu_app() -> Command {
                 .action(ArgAction::SetTrue),
         )
         .arg(
-            // Not implemented yet
             Arg::new(options::PHYSICAL)
                 .short('P')
                 .long(options::PHYSICAL)
@@ -260,7 +277,6 @@ pub fn uu_app() -> Command {
                 .short('r')
                 .long(options::RELATIVE)
                 .help("create symbolic links relative to link location")
-                .requires(options::SYMBOLIC)
                 .action(ArgAction::SetTrue),
         )
         .arg(
@@ -369,13 +385,19 @@ fn link_files_in_dir(files: &[PathBuf], target_dir: &Path, settings: &Settings)
     }
 }
 
+/// The path to `src` from the directory that `dst` goes in, which is what a
+/// symlink at `dst` needs to hold to point to `src`.
+///
+/// Like GNU, both paths are canonicalized first, except for the last
+/// component of `dst`, which may be an existing symlink to be replaced.
 fn relative_path<'a>(src: &'a Path, dst: &Path) -> Cow<'a, Path> {
+    let dst_dir = match dst.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
     if let Ok(src_abs) = canonicalize(src, MissingHandling::Missing, ResolveMode::Physical) {
-        if let Ok(dst_abs) = canonicalize(
-            dst.parent().unwrap(),
-            MissingHandling::Missing,
-            ResolveMode::Physical,
-        ) {
+        if let Ok(dst_abs) = canonicalize(dst_dir, MissingHandling::Missing, ResolveMode::Physical)
+        {
             return make_path_relative_to(src_abs, dst_abs).into();
         }
     }
@@ -427,7 +449,8 @@ fn link(src: &Path, dst: &Path, settings: &Settings) -> UResult<()> {
     }
 
     if settings.symbolic {
-        symlink(&source, dst)?;
+        symlink(&source, dst)
+            .map_err_context(|| format!("failed to create symbolic link {}", dst.quote()))?;
     } else {
         let p = if settings.logical && source.is_symlink() {
             // if we want to have an hard link,
@@ -438,6 +461,10 @@ fn link(src: &Path, dst: &Path, settings: &Settings) -> UResult<()> {
         } else {
             source.to_path_buf()
         };
+        // With -P, a symlink to a directory is linked to like any other.
+        if fs::symlink_metadata(&p).map_or(false, |m| m.is_dir()) {
+            return Err(LnError::HardLinkToDirectory(source.to_path_buf()).into());
+        }
         fs::hard_link(p, dst).map_err_context(|| {
             format!(
                 "failed to create hard link {} => {}",
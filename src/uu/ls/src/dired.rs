@@ -0,0 +1,117 @@
+// This file is part of the uutils coreutils package.
+//
+// For the full copyright and license information, please view the LICENSE file
+// that was distributed with this source code.
+
+// spell-checker:ignore dired subdired
+
+//! The `--dired` output of `ls`, which Emacs' dired mode parses.
+//!
+//! The long listing is indented by two spaces and followed by the byte offsets (in the whole
+//! output) of the start and end of every file name and of the directory names in the headings.
+
+use std::io::{self, BufWriter, Stdout, Write};
+
+use uucore::quoting_style::{Quotes, QuotingStyle};
+
+/// Keeps track of the positions of the names written so far.
+#[derive(Default)]
+pub(crate) struct DiredOutput {
+    /// The number of bytes written to the standard output.
+    position: usize,
+    /// The start and end offsets of the listed file names.
+    file_names: Vec<(usize, usize)>,
+    /// The start and end offsets of the directory names in the headings.
+    dir_names: Vec<(usize, usize)>,
+}
+
+impl DiredOutput {
+    /// Records that `len` more bytes have been written.
+    pub fn advance(&mut self, len: usize) {
+        self.position += len;
+    }
+
+    /// Records a file name of `len` bytes, starting `offset` bytes after the current position.
+    pub fn add_file_name(&mut self, offset: usize, len: usize) {
+        let start = self.position + offset;
+        self.file_names.push((start, start + len));
+    }
+
+    /// Records a directory name of `len` bytes, starting `offset` bytes after the current
+    /// position.
+    pub fn add_dir_name(&mut self, offset: usize, len: usize) {
+        let start = self.position + offset;
+        self.dir_names.push((start, start + len));
+    }
+
+    /// Writes the trailer with all recorded positions.
+    pub fn write_positions(
+        &self,
+        quoting_style: &QuotingStyle,
+        out: &mut BufWriter<Stdout>,
+    ) -> io::Result<()> {
+        write_positions("//DIRED//", &self.file_names, out)?;
+        write_positions("//SUBDIRED//", &self.dir_names, out)?;
+        writeln!(
+            out,
+            "//DIRED-OPTIONS// --quoting-style={}",
+            quoting_style_name(quoting_style)
+        )
+    }
+}
+
+fn write_positions(
+    label: &str,
+    positions: &[(usize, usize)],
+    out: &mut BufWriter<Stdout>,
+) -> io::Result<()> {
+    if positions.is_empty() {
+        return Ok(());
+    }
+    write!(out, "{label}")?;
+    for (start, end) in positions {
+        write!(out, " {start} {end}")?;
+    }
+    writeln!(out)
+}
+
+/// Returns the `--quoting-style` argument selecting `style`.
+fn quoting_style_name(style: &QuotingStyle) -> &'static str {
+    match style {
+        QuotingStyle::Literal { .. } => "literal",
+        QuotingStyle::Shell {
+            escape: false,
+            always_quote: false,
+            ..
+        } => "shell",
+        QuotingStyle::Shell {
+            escape: false,
+            always_quote: true,
+            ..
+        } => "shell-always",
+        QuotingStyle::Shell {
+            escape: true,
+            always_quote: false,
+            ..
+        } => "shell-escape",
+        QuotingStyle::Shell {
+            escape: true,
+            always_quote: true,
+            ..
+        } => "shell-escape-always",
+        QuotingStyle::C {
+            quotes: Quotes::None,
+        } => "escape",
+        QuotingStyle::C { .. } => "c",
+    }
+}
+
+/// Returns the offset of the file name in `name`, as returned by `display_file_name`, which
+/// starts with the escape sequence coloring it if colors are enabled.
+pub(crate) fn file_name_offset(name: &str) -> usize {
+    if name.starts_with('\x1b') {
+        name.find('m').map_or(0, |i| i + 1)
+    } else {
+        0
+    }
+}
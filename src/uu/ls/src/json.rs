@@ -0,0 +1,177 @@
+// This file is part of the uutils coreutils package.
+//
+// For the full copyright and license information, please view the LICENSE file
+// that was distributed with this source code.
+
+//! The `--format=json` output of `ls`.
+//!
+//! Every listed file is written as an object of a single JSON array, one object per line, so
+//! the entries of all listed directories (including those of `-R`) end up in the same array.
+
+use std::fmt::Write as FmtWrite;
+use std::fs::{FileType, Metadata};
+use std::io::{BufWriter, Stdout, Write};
+#[cfg(unix)]
+use std::os::unix::fs::{FileTypeExt, MetadataExt};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::SystemTime;
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use uucore::error::UResult;
+use uucore::fs::display_permissions;
+
+#[cfg(unix)]
+use super::display_symlink_count;
+use super::{display_group, display_uname, Config, PathData};
+
+/// Whether an entry has been written yet, i.e. whether the next one needs a separating comma.
+static ENTRY_WRITTEN: AtomicBool = AtomicBool::new(false);
+
+/// Opens the array holding the entries.
+pub(crate) fn write_start(out: &mut BufWriter<Stdout>) -> UResult<()> {
+    write!(out, "[")?;
+    Ok(())
+}
+
+/// Closes the array opened by [`write_start`].
+pub(crate) fn write_end(out: &mut BufWriter<Stdout>) -> UResult<()> {
+    if ENTRY_WRITTEN.load(Ordering::Relaxed) {
+        writeln!(out, "\n]")?;
+    } else {
+        writeln!(out, "]")?;
+    }
+    Ok(())
+}
+
+/// Writes one object for each of `items`.
+pub(crate) fn display_items(
+    items: &[PathData],
+    config: &Config,
+    out: &mut BufWriter<Stdout>,
+) -> UResult<()> {
+    for item in items {
+        let entry = entry(item, config, out);
+        let separator = if ENTRY_WRITTEN.swap(true, Ordering::Relaxed) {
+            ","
+        } else {
+            ""
+        };
+        write!(out, "{separator}\n  {{{entry}}}")?;
+    }
+    Ok(())
+}
+
+/// Returns the members of the object describing `item`, without the surrounding braces.
+fn entry(item: &PathData, config: &Config, out: &mut BufWriter<Stdout>) -> String {
+    let mut entry = String::new();
+    let mut member = |key: &str, value: String| {
+        if !entry.is_empty() {
+            entry.push_str(", ");
+        }
+        write!(entry, "{}: {}", quote(key), value).unwrap();
+    };
+
+    member("name", quote(&item.display_name.to_string_lossy()));
+    member("path", quote(&item.p_buf.to_string_lossy()));
+
+    // The error has already been shown by `PathData::md`.
+    if let Some(md) = item.md(out) {
+        member("type", quote(file_type_name(&md.file_type())));
+        member("size", md.len().to_string());
+        member("mode", quote(&display_permissions(md, true)));
+        #[cfg(unix)]
+        member("links", display_symlink_count(md));
+        member("owner", quote(&display_uname(md, config)));
+        member("group", quote(&display_group(md, config)));
+        member("modified", timestamp(md.modified().ok()));
+        member("accessed", timestamp(md.accessed().ok()));
+        #[cfg(unix)]
+        member("changed", changed(md));
+        if let Ok(birth) = md.created() {
+            member("birth", timestamp(Some(birth)));
+        }
+        if md.file_type().is_symlink() {
+            if let Ok(target) = item.p_buf.read_link() {
+                member("target", quote(&target.to_string_lossy()));
+            }
+        }
+    }
+
+    if config.context {
+        member("context", quote(&item.security_context));
+    }
+
+    entry
+}
+
+fn file_type_name(file_type: &FileType) -> &'static str {
+    if file_type.is_symlink() {
+        return "symlink";
+    } else if file_type.is_dir() {
+        return "directory";
+    } else if file_type.is_file() {
+        return "file";
+    }
+    #[cfg(unix)]
+    {
+        if file_type.is_fifo() {
+            return "fifo";
+        } else if file_type.is_socket() {
+            return "socket";
+        } else if file_type.is_block_device() {
+            return "block_device";
+        } else if file_type.is_char_device() {
+            return "char_device";
+        }
+    }
+    "unknown"
+}
+
+#[cfg(unix)]
+fn changed(md: &Metadata) -> String {
+    timestamp(Some(
+        SystemTime::UNIX_EPOCH
+            + std::time::Duration::new(md.ctime() as u64, md.ctime_nsec() as u32),
+    ))
+}
+
+/// Formats `time` as an RFC 3339 string in UTC, or as `null` if it is not available.
+fn timestamp(time: Option<SystemTime>) -> String {
+    match time {
+        Some(time) => {
+            quote(&DateTime::<Utc>::from(time).to_rfc3339_opts(SecondsFormat::Nanos, true))
+        }
+        None => "null".to_string(),
+    }
+}
+
+/// Returns `s` as a JSON string literal.
+fn quote(s: &str) -> String {
+    let mut quoted = String::with_capacity(s.len() + 2);
+    quoted.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c if c.is_control() => write!(quoted, "\\u{:04x}", c as u32).unwrap(),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::quote;
+
+    #[test]
+    fn test_quote() {
+        assert_eq!(quote("a b"), "\"a b\"");
+        assert_eq!(quote("a\"b\\c"), "\"a\\\"b\\\\c\"");
+        assert_eq!(quote("a\nb\u{1}"), "\"a\\nb\\u0001\"");
+    }
+}
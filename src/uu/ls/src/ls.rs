@@ -7,6 +7,10 @@
 
 // spell-checker:ignore (ToDO) cpio svgz webm somegroup nlink rmvb xspf tabsize dired
 
+mod dired;
+mod json;
+
+use crate::dired::DiredOutput;
 use clap::{
     builder::{NonEmptyStringValueParser, ValueParser},
     crate_version, Arg, ArgAction, Command,
@@ -31,7 +35,10 @@ use std::{
 #[cfg(unix)]
 use std::{
     collections::HashMap,
-    os::unix::fs::{FileTypeExt, MetadataExt},
+    os::unix::{
+        ffi::OsStrExt,
+        fs::{FileTypeExt, MetadataExt},
+    },
     time::Duration,
 };
 use term_grid::{Cell, Direction, Filling, Grid, GridOptions};
@@ -50,7 +57,7 @@ use uucore::{
     display::Quotable,
     error::{set_exit_code, UError, UResult},
     format_usage,
-    fs::display_permissions,
+    fs::{canonicalize, display_permissions, MissingHandling, ResolveMode},
     parse_size::parse_size,
     version_cmp::version_cmp,
 };
@@ -66,6 +73,9 @@ Ignore files and directories starting with a '.' by default"#;
 
 const USAGE: &str = "{} [OPTION]... [FILE]...";
 
+/// The indentation of the lines of the `--dired` output.
+const DIRED_INDENT: &str = "  ";
+
 pub mod options {
     pub mod format {
         pub static ONE_LINE: &str = "1";
@@ -149,6 +159,7 @@ pub mod options {
     pub static GROUP_DIRECTORIES_FIRST: &str = "group-directories-first";
     pub static ZERO: &str = "zero";
     pub static DIRED: &str = "dired";
+    pub static HYPERLINK: &str = "hyperlink";
 }
 
 const DEFAULT_TERM_WIDTH: u16 = 80;
@@ -278,6 +289,7 @@ pub enum Format {
     OneLine,
     Across,
     Commas,
+    Json,
 }
 
 #[derive(PartialEq, Eq)]
@@ -388,6 +400,9 @@ pub struct Config {
     #[cfg(unix)]
     inode: bool,
     color: Option<LsColors>,
+    // The host name used in the file:// URLs of --hyperlink
+    hyperlink: Option<String>,
+    dired: bool,
     long: LongFormat,
     alloc_size: bool,
     block_size: Option<u64>,
@@ -438,6 +453,7 @@ impl Config {
                     "columns" | "vertical" => Format::Columns,
                     "across" | "horizontal" => Format::Across,
                     "commas" => Format::Commas,
+                    "json" => Format::Json,
                     // below should never happen as clap already restricts the values.
                     _ => unreachable!("Invalid field for --format"),
                 },
@@ -504,6 +520,12 @@ impl Config {
             }
         }
 
+        // The offsets printed by --dired are those of the long format, which it implies.
+        let dired = options.get_flag(options::DIRED);
+        if dired {
+            format = Format::Long;
+        }
+
         let files = if options.get_flag(options::files::ALL) {
             Files::All
         } else if options.get_flag(options::files::ALMOST_ALL) {
@@ -562,6 +584,24 @@ impl Config {
             },
         };
 
+        let hyperlink = match options.get_one::<String>(options::HYPERLINK) {
+            None => false,
+            Some(val) => match val.as_str() {
+                "always" | "yes" | "force" => true,
+                "auto" | "tty" | "if-tty" => atty::is(atty::Stream::Stdout),
+                /* "never" | "no" | "none" | */ _ => false,
+            },
+        };
+        let hyperlink = if hyperlink && !dired {
+            Some(
+                hostname::get()
+                    .map(|hostname| hostname.to_string_lossy().into_owned())
+                    .unwrap_or_default(),
+            )
+        } else {
+            None
+        };
+
         let cmd_line_bs = options.get_one::<String>(options::size::BLOCK_SIZE);
         let opt_si = cmd_line_bs.is_some()
             && options
@@ -842,7 +882,7 @@ impl Config {
                 .max()
                 .unwrap_or(0)
         {
-            format = if format == Format::Long {
+            format = if format == Format::Long || format == Format::Json {
                 format
             } else {
                 Format::OneLine
@@ -891,6 +931,7 @@ impl Config {
         } else if options.get_flag(options::DIRECTORY)
             || indicator_style == IndicatorStyle::Classify
             || format == Format::Long
+            || format == Format::Json
         {
             Dereference::None
         } else {
@@ -909,6 +950,8 @@ impl Config {
             directory: options.get_flag(options::DIRECTORY),
             time,
             color,
+            hyperlink,
+            dired,
             #[cfg(unix)]
             inode: options.get_flag(options::INODE),
             long,
@@ -982,6 +1025,7 @@ pub fn uu_app() -> Command {
                     "across",
                     "horizontal",
                     "commas",
+                    "json",
                 ])
                 .hide_possible_values(true)
                 .require_equals(true)
@@ -1067,7 +1111,7 @@ pub fn uu_app() -> Command {
             Arg::new(options::DIRED)
                 .long(options::DIRED)
                 .short('D')
-                .hide(true)
+                .help("generate output designed for Emacs' dired mode")
                 .action(ArgAction::SetTrue),
         )
         // The next four arguments do not override with the other format
@@ -1505,6 +1549,18 @@ pub fn uu_app() -> Command {
                 .require_equals(true)
                 .num_args(0..=1),
         )
+        .arg(
+            Arg::new(options::HYPERLINK)
+                .long(options::HYPERLINK)
+                .help("hyperlink file names WHEN")
+                .value_parser([
+                    "always", "yes", "force", "auto", "tty", "if-tty", "never", "no", "none",
+                ])
+                .value_name("WHEN")
+                .require_equals(true)
+                .num_args(0..=1)
+                .default_missing_value("always"),
+        )
         .arg(
             Arg::new(options::INDICATOR_STYLE)
                 .long(options::INDICATOR_STYLE)
@@ -1778,6 +1834,7 @@ pub fn list(locs: Vec<&Path>, config: &Config) -> UResult<()> {
     let mut files = Vec::<PathData>::new();
     let mut dirs = Vec::<PathData>::new();
     let mut out = BufWriter::new(stdout());
+    let mut dired = DiredOutput::default();
     let initial_locs_len = locs.len();
 
     for loc in locs {
@@ -1808,10 +1865,15 @@ pub fn list(locs: Vec<&Path>, config: &Config) -> UResult<()> {
         }
     }
 
+    prefetch_metadata(&files, config);
     sort_entries(&mut files, config, &mut out);
     sort_entries(&mut dirs, config, &mut out);
 
-    display_items(&files, config, &mut out)?;
+    if config.format == Format::Json {
+        json::write_start(&mut out)?;
+    }
+
+    display_items(&files, config, &mut out, &mut dired)?;
 
     for (pos, path_data) in dirs.iter().enumerate() {
         // Do read_dir call here to match GNU semantics by printing
@@ -1831,19 +1893,36 @@ pub fn list(locs: Vec<&Path>, config: &Config) -> UResult<()> {
         };
 
         // Print dir heading - name... 'total' comes after error display
-        if initial_locs_len > 1 || config.recursive {
-            if pos.eq(&0usize) && files.is_empty() {
-                writeln!(out, "{}:", path_data.p_buf.display())?;
-            } else {
-                writeln!(out, "\n{}:", path_data.p_buf.display())?;
-            }
+        if (initial_locs_len > 1 || config.recursive) && config.format != Format::Json {
+            let leading_newline = !(pos.eq(&0usize) && files.is_empty());
+            display_dir_heading(
+                &path_data.p_buf,
+                leading_newline,
+                config,
+                &mut out,
+                &mut dired,
+            )?;
         }
         let mut listed_ancestors = HashSet::new();
         listed_ancestors.insert(FileInformation::from_path(
             &path_data.p_buf,
             path_data.must_dereference,
         )?);
-        enter_directory(path_data, read_dir, config, &mut out, &mut listed_ancestors)?;
+        enter_directory(
+            path_data,
+            read_dir,
+            config,
+            &mut out,
+            &mut listed_ancestors,
+            &mut dired,
+        )?;
+    }
+
+    if config.format == Format::Json {
+        json::write_end(&mut out)?;
+    }
+    if config.dired {
+        dired.write_positions(&config.quoting_style, &mut out)?;
     }
 
     Ok(())
@@ -1851,11 +1930,15 @@ pub fn list(locs: Vec<&Path>, config: &Config) -> UResult<()> {
 
 fn sort_entries(entries: &mut [PathData], config: &Config, out: &mut BufWriter<Stdout>) {
     match config.sort {
-        Sort::Time => entries.sort_by_key(|k| {
-            Reverse(
-                k.md(out)
-                    .and_then(|md| get_system_time(md, config))
-                    .unwrap_or(UNIX_EPOCH),
+        // Like GNU ls, sort files with the same time by name
+        Sort::Time => entries.sort_by_cached_key(|k| {
+            (
+                Reverse(
+                    k.md(out)
+                        .and_then(|md| get_system_time(md, config))
+                        .unwrap_or(UNIX_EPOCH),
+                ),
+                k.display_name.clone(),
             )
         }),
         Sort::Size => entries.sort_by_key(|k| Reverse(k.md(out).map(|md| md.len()).unwrap_or(0))),
@@ -1942,6 +2025,7 @@ fn enter_directory(
     config: &Config,
     out: &mut BufWriter<Stdout>,
     listed_ancestors: &mut HashSet<FileInformation>,
+    dired: &mut DiredOutput,
 ) -> UResult<()> {
     // Create vec of entries with initial dot files
     let mut entries: Vec<PathData> = if config.files == Files::All {
@@ -1983,14 +2067,15 @@ fn enter_directory(
         };
     }
 
+    prefetch_metadata(&entries, config);
     sort_entries(&mut entries, config, out);
 
     // Print total after any error display
-    if config.format == Format::Long || config.alloc_size {
-        display_total(&entries, config, out)?;
+    if config.format == Format::Long || (config.alloc_size && config.format != Format::Json) {
+        display_total(&entries, config, out, dired)?;
     }
 
-    display_items(&entries, config, out)?;
+    display_items(&entries, config, out, dired)?;
 
     if config.recursive {
         for e in entries
@@ -2017,8 +2102,10 @@ fn enter_directory(
                         out.flush()?;
                         show!(LsError::AlreadyListedError(e.p_buf.clone()));
                     } else {
-                        writeln!(out, "\n{}:", e.p_buf.display())?;
-                        enter_directory(e, rd, config, out, listed_ancestors)?;
+                        if config.format != Format::Json {
+                            display_dir_heading(&e.p_buf, true, config, out, dired)?;
+                        }
+                        enter_directory(e, rd, config, out, listed_ancestors, dired)?;
                         listed_ancestors
                             .remove(&FileInformation::from_path(&e.p_buf, e.must_dereference)?);
                     }
@@ -2030,6 +2117,56 @@ fn enter_directory(
     Ok(())
 }
 
+/// Directories with fewer entries than this have their metadata fetched one by one.
+const PREFETCH_THRESHOLD: usize = 1024;
+
+/// Fetches the metadata of `entries` on several threads if it is needed to sort or display them.
+///
+/// For huge directories, `ls -l` spends most of its time waiting for `stat` calls, so doing them
+/// in parallel pays off even with a single CPU. Only the metadata that could be fetched is
+/// stored, so errors are still reported in order once the metadata is requested.
+fn prefetch_metadata(entries: &[PathData], config: &Config) {
+    let needs_metadata = matches!(config.format, Format::Long | Format::Json)
+        || matches!(config.sort, Sort::Size | Sort::Time);
+    if !needs_metadata || entries.len() < PREFETCH_THRESHOLD {
+        return;
+    }
+
+    let pending: Vec<(usize, PathBuf, bool)> = entries
+        .iter()
+        .enumerate()
+        .filter(|(_, entry)| entry.md.get().is_none())
+        .map(|(i, entry)| (i, entry.p_buf.clone(), entry.must_dereference))
+        .collect();
+    let threads = std::thread::available_parallelism()
+        .map_or(1, |n| n.get())
+        .max(4);
+    let chunk_size = (pending.len() + threads - 1) / threads;
+    let handles: Vec<_> = pending
+        .chunks(chunk_size.max(1))
+        .map(|chunk| {
+            let chunk = chunk.to_vec();
+            std::thread::spawn(move || {
+                chunk
+                    .into_iter()
+                    .filter_map(|(i, p_buf, dereference)| {
+                        get_metadata(&p_buf, dereference).ok().map(|md| (i, md))
+                    })
+                    .collect::<Vec<_>>()
+            })
+        })
+        .collect();
+
+    for handle in handles {
+        // If a thread failed, its entries just get their metadata later on.
+        if let Ok(fetched) = handle.join() {
+            for (i, md) in fetched {
+                let _ = entries[i].md.set(Some(md));
+            }
+        }
+    }
+}
+
 fn get_metadata(p_buf: &Path, dereference: bool) -> std::io::Result<Metadata> {
     if dereference {
         p_buf.metadata()
@@ -2074,7 +2211,30 @@ fn pad_right(string: &str, count: usize) -> String {
     format!("{string:<count$}")
 }
 
-fn display_total(items: &[PathData], config: &Config, out: &mut BufWriter<Stdout>) -> UResult<()> {
+/// Prints the heading of the listing of the directory at `path`.
+fn display_dir_heading(
+    path: &Path,
+    leading_newline: bool,
+    config: &Config,
+    out: &mut BufWriter<Stdout>,
+    dired: &mut DiredOutput,
+) -> UResult<()> {
+    let newline = if leading_newline { "\n" } else { "" };
+    let indent = if config.dired { DIRED_INDENT } else { "" };
+    let name = path.display().to_string();
+    dired.add_dir_name(newline.len() + indent.len(), name.len());
+    let heading = format!("{newline}{indent}{name}:\n");
+    write!(out, "{heading}")?;
+    dired.advance(heading.len());
+    Ok(())
+}
+
+fn display_total(
+    items: &[PathData],
+    config: &Config,
+    out: &mut BufWriter<Stdout>,
+    dired: &mut DiredOutput,
+) -> UResult<()> {
     let mut total_size = 0;
     for item in items {
         total_size += item
@@ -2082,12 +2242,14 @@ fn display_total(items: &[PathData], config: &Config, out: &mut BufWriter<Stdout
             .as_ref()
             .map_or(0, |md| get_block_size(md, config));
     }
-    write!(
-        out,
-        "total {}{}",
+    let total = format!(
+        "{}total {}{}",
+        if config.dired { DIRED_INDENT } else { "" },
         display_size(total_size, config),
         config.eol
-    )?;
+    );
+    write!(out, "{total}")?;
+    dired.advance(total.len());
     Ok(())
 }
 
@@ -2126,28 +2288,41 @@ fn display_additional_leading_info(
     Ok(result)
 }
 
-fn display_items(items: &[PathData], config: &Config, out: &mut BufWriter<Stdout>) -> UResult<()> {
+fn display_items(
+    items: &[PathData],
+    config: &Config,
+    out: &mut BufWriter<Stdout>,
+    dired: &mut DiredOutput,
+) -> UResult<()> {
     // `-Z`, `--context`:
     // Display the SELinux security context or '?' if none is found. When used with the `-l`
     // option, print the security context to the left of the size column.
 
-    if config.format == Format::Long {
+    if config.format == Format::Json {
+        json::display_items(items, config, out)?;
+    } else if config.format == Format::Long {
         let padding_collection = calculate_padding_collection(items, config, out);
 
         for item in items {
+            if config.dired {
+                write!(out, "{DIRED_INDENT}")?;
+                dired.advance(DIRED_INDENT.len());
+            }
             #[cfg(unix)]
             if config.inode || config.alloc_size {
                 let more_info =
                     display_additional_leading_info(item, &padding_collection, config, out)?;
                 write!(out, "{more_info}")?;
+                dired.advance(more_info.len());
             }
             #[cfg(not(unix))]
             if config.alloc_size {
                 let more_info =
                     display_additional_leading_info(item, &padding_collection, config, out)?;
                 write!(out, "{more_info}")?;
+                dired.advance(more_info.len());
             }
-            display_item_long(item, &padding_collection, config, out)?;
+            display_item_long(item, &padding_collection, config, out, dired)?;
         }
     } else {
         let mut longest_context_len = 1;
@@ -2320,10 +2495,13 @@ fn display_item_long(
     padding: &PaddingCollection,
     config: &Config,
     out: &mut BufWriter<Stdout>,
+    dired: &mut DiredOutput,
 ) -> UResult<()> {
-    if let Some(md) = item.md(out) {
+    // The line is assembled first, so that the offset of the file name in it is known.
+    let mut line = Vec::new();
+    let (dfn, eol) = if let Some(md) = item.md(out) {
         write!(
-            out,
+            line,
             "{}{} {}",
             display_permissions(md, true),
             if item.security_context.len() > 1 {
@@ -2338,7 +2516,7 @@ fn display_item_long(
 
         if config.long.owner {
             write!(
-                out,
+                line,
                 " {}",
                 pad_right(&display_uname(md, config), padding.uname)
             )?;
@@ -2346,7 +2524,7 @@ fn display_item_long(
 
         if config.long.group {
             write!(
-                out,
+                line,
                 " {}",
                 pad_right(&display_group(md, config), padding.group)
             )?;
@@ -2354,7 +2532,7 @@ fn display_item_long(
 
         if config.context {
             write!(
-                out,
+                line,
                 " {}",
                 pad_right(&item.security_context, padding.context)
             )?;
@@ -2364,7 +2542,7 @@ fn display_item_long(
         // the owner, since GNU/Hurd is not currently supported by Rust.
         if config.long.author {
             write!(
-                out,
+                line,
                 " {}",
                 pad_right(&display_uname(md, config), padding.uname)
             )?;
@@ -2372,11 +2550,11 @@ fn display_item_long(
 
         match display_len_or_rdev(md, config) {
             SizeOrDeviceId::Size(size) => {
-                write!(out, " {}", pad_left(&size, padding.size))?;
+                write!(line, " {}", pad_left(&size, padding.size))?;
             }
             SizeOrDeviceId::Device(major, minor) => {
                 write!(
-                    out,
+                    line,
                     " {}, {}",
                     pad_left(
                         &major,
@@ -2400,9 +2578,11 @@ fn display_item_long(
             }
         };
 
-        let dfn = display_file_name(item, config, None, String::new(), out).contents;
-
-        write!(out, " {} {}{}", display_date(md, config), dfn, config.eol)?;
+        write!(line, " {} ", display_date(md, config))?;
+        (
+            display_file_name(item, config, None, String::new(), out).contents,
+            config.eol,
+        )
     } else {
         #[cfg(unix)]
         let leading_char = {
@@ -2438,7 +2618,7 @@ fn display_item_long(
         };
 
         write!(
-            out,
+            line,
             "{}{} {}",
             format_args!("{leading_char}?????????"),
             if item.security_context.len() > 1 {
@@ -2452,16 +2632,16 @@ fn display_item_long(
         )?;
 
         if config.long.owner {
-            write!(out, " {}", pad_right("?", padding.uname))?;
+            write!(line, " {}", pad_right("?", padding.uname))?;
         }
 
         if config.long.group {
-            write!(out, " {}", pad_right("?", padding.group))?;
+            write!(line, " {}", pad_right("?", padding.group))?;
         }
 
         if config.context {
             write!(
-                out,
+                line,
                 " {}",
                 pad_right(&item.security_context, padding.context)
             )?;
@@ -2470,20 +2650,30 @@ fn display_item_long(
         // Author is only different from owner on GNU/Hurd, so we reuse
         // the owner, since GNU/Hurd is not currently supported by Rust.
         if config.long.author {
-            write!(out, " {}", pad_right("?", padding.uname))?;
+            write!(line, " {}", pad_right("?", padding.uname))?;
         }
 
-        let dfn = display_file_name(item, config, None, String::new(), out).contents;
         let date_len = 12;
 
-        writeln!(
-            out,
-            " {} {} {}",
+        write!(
+            line,
+            " {} {} ",
             pad_left("?", padding.size),
             pad_left("?", date_len),
-            dfn,
         )?;
+        (
+            display_file_name(item, config, None, String::new(), out).contents,
+            '\n',
+        )
+    };
+
+    if config.dired {
+        let name = escape_name(&item.display_name, &config.quoting_style);
+        dired.add_file_name(line.len() + dired::file_name_offset(&dfn), name.len());
     }
+    write!(line, "{dfn}{eol}")?;
+    out.write_all(&line)?;
+    dired.advance(line.len());
 
     Ok(())
 }
@@ -2731,6 +2921,50 @@ fn classify_file(path: &PathData, out: &mut BufWriter<Stdout>) -> Option<char> {
     }
 }
 
+/// Wraps `name` in an OSC 8 escape sequence, so terminals link it to the file:// URL of `path`.
+fn create_hyperlink(name: &str, path: &PathData, hostname: &str) -> String {
+    // Like GNU ls, link to the path with all symlinks resolved.
+    let absolute_path = canonicalize(&path.p_buf, MissingHandling::Missing, ResolveMode::Physical)
+        .unwrap_or_else(|_| path.p_buf.clone());
+    #[cfg(unix)]
+    let absolute_path = absolute_path.as_os_str().as_bytes().to_vec();
+    #[cfg(not(unix))]
+    let absolute_path = absolute_path
+        .to_string_lossy()
+        .replace('\\', "/")
+        .into_bytes();
+
+    let mut url = String::from("file://");
+    url_escape(&mut url, hostname.as_bytes(), false);
+    if !absolute_path.starts_with(b"/") {
+        url.push('/');
+    }
+    url_escape(&mut url, &absolute_path, true);
+
+    // Leave the quotes of quoted names outside of the link.
+    let quoted = name.len() >= 2
+        && (name.starts_with('\'') && name.ends_with('\'')
+            || name.starts_with('"') && name.ends_with('"'));
+    if quoted {
+        let (quote, inner) = (&name[..1], &name[1..name.len() - 1]);
+        format!("{quote}\x1b]8;;{url}\x07{inner}\x1b]8;;\x07{quote}")
+    } else {
+        format!("\x1b]8;;{url}\x07{name}\x1b]8;;\x07")
+    }
+}
+
+/// Appends `bytes` to `url`, percent-encoding all but the unreserved characters of RFC 3986, and
+/// slashes if `is_path` is set.
+fn url_escape(url: &mut String, bytes: &[u8], is_path: bool) {
+    for &byte in bytes {
+        if byte.is_ascii_alphanumeric() || b"-._~".contains(&byte) || (is_path && byte == b'/') {
+            url.push(byte as char);
+        } else {
+            write!(url, "%{byte:02x}").unwrap();
+        }
+    }
+}
+
 /// Takes a [`PathData`] struct and returns a cell with a name ready for displaying.
 ///
 /// This function relies on the following parameters in the provided `&Config`:
@@ -2759,6 +2993,10 @@ fn display_file_name(
     // infer it because the color codes mess up term_grid's width calculation.
     let mut width = name.width();
 
+    if let Some(hostname) = &config.hyperlink {
+        name = create_hyperlink(&name, path, hostname);
+    }
+
     if let Some(ls_colors) = &config.color {
         let md = path.md(out);
         name = if md.is_some() {
@@ -12,14 +12,14 @@ use clap::parser::ValuesRef;
 use clap::{crate_version, Arg, ArgAction, ArgMatches, Command};
 use std::ffi::OsString;
 use std::path::{Path, PathBuf};
-#[cfg(not(windows))]
-use uucore::error::FromIo;
-use uucore::error::{UResult, USimpleError};
+use uucore::error::{FromIo, UResult, USimpleError};
 #[cfg(not(windows))]
 use uucore::mode;
+use uucore::security_context::{self, set_security_context};
 use uucore::{display::Quotable, fs::dir_strip_dot_for_creation};
-use uucore::{format_usage, show, show_if_err};
+use uucore::{format_usage, show, show_if_err, show_warning};
 
+#[cfg(windows)]
 static DEFAULT_PERM: u32 = 0o755;
 
 static ABOUT: &str = "Create the given DIRECTORY(ies) if they do not exist";
@@ -29,6 +29,7 @@ mod options {
     pub const MODE: &str = "mode";
     pub const PARENTS: &str = "parents";
     pub const VERBOSE: &str = "verbose";
+    pub const CONTEXT: &str = "context";
     pub const DIRS: &str = "dirs";
 }
 
@@ -43,26 +44,17 @@ fn get_mode(_matches: &ArgMatches, _mode_had_minus_prefix: bool) -> Result<u32,
 
 #[cfg(not(windows))]
 fn get_mode(matches: &ArgMatches, mode_had_minus_prefix: bool) -> Result<u32, String> {
-    let digits: &[char] = &['0', '1', '2', '3', '4', '5', '6', '7', '8', '9'];
-    // Translate a ~str in octal form to u16, default to 755
-    // Not tested on Windows
-    let mut new_mode = DEFAULT_PERM;
+    // Like GNU, MODE applies to the permissions a directory would get
+    // without it, i.e. a=rwx minus the umask.
     match matches.get_one::<String>(options::MODE) {
         Some(m) => {
-            for mode in m.split(',') {
-                if mode.contains(digits) {
-                    new_mode = mode::parse_numeric(new_mode, m, true)?;
-                } else {
-                    let cmode = if mode_had_minus_prefix {
-                        // clap parsing is finished, now put prefix back
-                        format!("-{mode}")
-                    } else {
-                        mode.to_string()
-                    };
-                    new_mode = mode::parse_symbolic(new_mode, &cmode, mode::get_umask(), true)?;
-                }
-            }
-            Ok(new_mode)
+            let m = if mode_had_minus_prefix {
+                // clap parsing is finished, now put prefix back
+                format!("-{m}")
+            } else {
+                m.to_string()
+            };
+            Ok(mode::Mode::parse(&m)?.apply(0o777, true, mode::get_umask()))
         }
         None => {
             // If no mode argument is specified return the mode derived from umask
@@ -89,9 +81,6 @@ pub fn uumain(args: impl uucore::Args) -> UResult<()> {
     // a possible MODE prefix '-' needs to be removed (e.g. "chmod -x FILE").
     let mode_had_minus_prefix = strip_minus_from_mode(&mut args);
 
-    // Linux-specific options, not implemented
-    // opts.optflag("Z", "context", "set SELinux security context" +
-    // " of each created directory to CTX"),
     let matches = uu_app()
         .after_help(get_long_usage())
         .try_get_matches_from(args)?;
@@ -102,8 +91,22 @@ pub fn uumain(args: impl uucore::Args) -> UResult<()> {
     let verbose = matches.get_flag(options::VERBOSE);
     let recursive = matches.get_flag(options::PARENTS);
 
+    // Like GNU, -Z is silently ignored without SELinux or SMACK, but a
+    // context that was asked for is worth a warning.
+    let mut context = None;
+    if matches.contains_id(options::CONTEXT) {
+        let ctx = matches
+            .get_one::<String>(options::CONTEXT)
+            .map(String::as_str);
+        if security_context::is_supported() {
+            context = Some(ctx);
+        } else if ctx.is_some() {
+            show_warning!("ignoring --context; it requires an SELinux/SMACK-enabled kernel");
+        }
+    }
+
     match get_mode(&matches, mode_had_minus_prefix) {
-        Ok(mode) => exec(dirs, recursive, mode, verbose),
+        Ok(mode) => exec(dirs, recursive, mode, verbose, context),
         Err(f) => Err(USimpleError::new(1, f)),
     }
 }
@@ -134,6 +137,18 @@ pub fn uu_app() -> Command {
                 .help("print a message for each printed directory")
                 .action(ArgAction::SetTrue),
         )
+        .arg(
+            Arg::new(options::CONTEXT)
+                .short('Z')
+                .long(options::CONTEXT)
+                .value_name("CTX")
+                .num_args(0..=1)
+                .require_equals(true)
+                .help(
+                    "set the SELinux security context of each created directory to the \
+                     default type, or to CTX if specified (the SMACK label, with SMACK)",
+                ),
+        )
         .arg(
             Arg::new(options::DIRS)
                 .action(ArgAction::Append)
@@ -145,8 +160,17 @@ pub fn uu_app() -> Command {
 
 /**
  * Create the list of new directories
+ *
+ * `context` is `None` without -Z, and otherwise holds the security context to
+ * give each created directory, if not the default one.
  */
-fn exec(dirs: ValuesRef<OsString>, recursive: bool, mode: u32, verbose: bool) -> UResult<()> {
+fn exec(
+    dirs: ValuesRef<OsString>,
+    recursive: bool,
+    mode: u32,
+    verbose: bool,
+    context: Option<Option<&str>>,
+) -> UResult<()> {
     for dir in dirs {
         // Special case to match GNU's behavior:
         // mkdir -p foo/. should work and just create foo/
@@ -157,13 +181,19 @@ fn exec(dirs: ValuesRef<OsString>, recursive: bool, mode: u32, verbose: bool) ->
             // Normal case
             PathBuf::from(dir)
         };
-        show_if_err!(mkdir(path.as_path(), recursive, mode, verbose));
+        show_if_err!(mkdir(path.as_path(), recursive, mode, verbose, context));
     }
     Ok(())
 }
 
-fn mkdir(path: &Path, recursive: bool, mode: u32, verbose: bool) -> UResult<()> {
-    create_dir(path, recursive, verbose)?;
+fn mkdir(
+    path: &Path,
+    recursive: bool,
+    mode: u32,
+    verbose: bool,
+    context: Option<Option<&str>>,
+) -> UResult<()> {
+    create_dir(path, recursive, verbose, context)?;
     chmod(path, mode)
 }
 
@@ -184,7 +214,12 @@ fn chmod(_path: &Path, _mode: u32) -> UResult<()> {
     Ok(())
 }
 
-fn create_dir(path: &Path, recursive: bool, verbose: bool) -> UResult<()> {
+fn create_dir(
+    path: &Path,
+    recursive: bool,
+    verbose: bool,
+    context: Option<Option<&str>>,
+) -> UResult<()> {
     if path.exists() && !recursive {
         return Err(USimpleError::new(
             1,
@@ -197,7 +232,7 @@ fn create_dir(path: &Path, recursive: bool, verbose: bool) -> UResult<()> {
 
     if recursive {
         match path.parent() {
-            Some(p) => create_dir(p, recursive, verbose)?,
+            Some(p) => create_dir(p, recursive, verbose, context)?,
             None => {
                 USimpleError::new(1, "failed to create whole tree");
             }
@@ -205,6 +240,11 @@ fn create_dir(path: &Path, recursive: bool, verbose: bool) -> UResult<()> {
     }
     match std::fs::create_dir(path) {
         Ok(()) => {
+            if let Some(context) = context {
+                set_security_context(path, context).map_err_context(|| {
+                    format!("failed to set the security context of {}", path.quote())
+                })?;
+            }
             if verbose {
                 println!(
                     "{}: created directory {}",
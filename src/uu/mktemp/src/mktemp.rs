@@ -11,7 +11,8 @@
 use clap::{crate_version, Arg, ArgAction, ArgMatches, Command};
 use uucore::display::{println_verbatim, Quotable};
 use uucore::error::{FromIo, UError, UResult, UUsageError};
-use uucore::format_usage;
+use uucore::security_context::{self, set_security_context};
+use uucore::{format_usage, show_warning};
 
 use std::env;
 use std::error::Error;
@@ -20,13 +21,11 @@ use std::io::ErrorKind;
 use std::iter;
 use std::path::{Path, PathBuf, MAIN_SEPARATOR};
 
-#[cfg(unix)]
 use std::fs;
 #[cfg(unix)]
-use std::os::unix::prelude::PermissionsExt;
+use std::os::unix::fs::{DirBuilderExt, OpenOptionsExt};
 
 use rand::Rng;
-use tempfile::Builder;
 
 static ABOUT: &str = "create a temporary file or directory.";
 const USAGE: &str = "{} [OPTION]... [TEMPLATE]";
@@ -39,12 +38,12 @@ static OPT_QUIET: &str = "quiet";
 static OPT_SUFFIX: &str = "suffix";
 static OPT_TMPDIR: &str = "tmpdir";
 static OPT_T: &str = "t";
+static OPT_CONTEXT: &str = "context";
 
 static ARG_TEMPLATE: &str = "template";
 
 #[derive(Debug)]
 enum MkTempError {
-    PersistError(PathBuf),
     MustEndInX(String),
     TooFewXs(String),
 
@@ -55,9 +54,6 @@ enum MkTempError {
     SuffixContainsDirSeparator(String),
     InvalidTemplate(String),
     TooManyTemplates,
-
-    /// When a specified temporary directory could not be found.
-    NotFound(String, String),
 }
 
 impl UError for MkTempError {
@@ -72,7 +68,6 @@ impl Display for MkTempError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         use MkTempError::*;
         match self {
-            PersistError(p) => write!(f, "could not persist file {}", p.quote()),
             MustEndInX(s) => write!(f, "with --suffix, template {} must end in X", s.quote()),
             TooFewXs(s) => write!(f, "too few X's in template {}", s.quote()),
             PrefixContainsDirSeparator(s) => {
@@ -97,12 +92,6 @@ impl Display for MkTempError {
             TooManyTemplates => {
                 write!(f, "too many templates")
             }
-            NotFound(template_type, s) => write!(
-                f,
-                "failed to create {} via template {}: No such file or directory",
-                template_type,
-                s.quote()
-            ),
         }
     }
 }
@@ -135,6 +124,11 @@ struct Options {
 
     /// The template to use for the name of the temporary file.
     template: String,
+
+    /// The security context to give the temporary file, if -Z was given.
+    ///
+    /// The inner `None` stands for the default context of its path.
+    context: Option<Option<String>>,
 }
 
 /// Decide whether the argument to `--tmpdir` should actually be the template.
@@ -167,6 +161,29 @@ fn is_tmpdir_argument_actually_the_template(matches: &ArgMatches) -> bool {
     false
 }
 
+/// The temporary directory given by the environment, if any.
+///
+/// This is `$TMPDIR` if it is set and not empty, or on Windows `%TMP%` or
+/// `%TEMP%`.
+fn env_tmpdir() -> Option<String> {
+    #[cfg(not(windows))]
+    let vars = ["TMPDIR"];
+    #[cfg(windows)]
+    let vars = ["TMP", "TEMP"];
+    vars.iter()
+        .filter_map(|var| env::var(var).ok())
+        .find(|dir| !dir.is_empty())
+}
+
+/// The temporary directory to fall back to when none is given at all.
+fn fallback_tmpdir() -> String {
+    if cfg!(windows) {
+        env::temp_dir().display().to_string()
+    } else {
+        String::from("/tmp")
+    }
+}
+
 impl Options {
     fn from(matches: &ArgMatches) -> Self {
         // Special case to work around a limitation of `clap`; see
@@ -175,35 +192,46 @@ impl Options {
         //
         // Fixed in clap 3
         // See https://github.com/clap-rs/clap/pull/1587
-        let (tmpdir, template) = if is_tmpdir_argument_actually_the_template(matches) {
-            let tmpdir = Some(env::temp_dir().display().to_string());
-            let template = matches.get_one::<String>(OPT_TMPDIR).unwrap().to_string();
-            (tmpdir, template)
+        let (tmpdir_arg, template) = if is_tmpdir_argument_actually_the_template(matches) {
+            let template = matches.get_one::<String>(OPT_TMPDIR).unwrap();
+            (None, Some(template.to_string()))
         } else {
-            // If no template argument is given, `--tmpdir` is implied.
-            match matches.get_one::<String>(ARG_TEMPLATE) {
-                None => {
-                    let tmpdir = match matches.get_one::<String>(OPT_TMPDIR) {
-                        None => Some(env::temp_dir().display().to_string()),
-                        Some(tmpdir) => Some(tmpdir.to_string()),
-                    };
-                    let template = DEFAULT_TEMPLATE;
-                    (tmpdir, template.to_string())
-                }
-                Some(template) => {
-                    let tmpdir = matches.get_one::<String>(OPT_TMPDIR).map(String::from);
-                    (tmpdir, template.to_string())
-                }
-            }
+            (
+                matches.get_one::<String>(OPT_TMPDIR).cloned(),
+                matches.get_one::<String>(ARG_TEMPLATE).cloned(),
+            )
+        };
+        let treat_as_template = matches.get_flag(OPT_T);
+
+        // Like GNU, the directory is only prepended with -p/--tmpdir or -t,
+        // or if no template argument is given. The deprecated -t prefers
+        // $TMPDIR to the directory given with -p, while -p and --tmpdir
+        // prefer it the other way around.
+        let use_tmpdir = template.is_none() || matches.contains_id(OPT_TMPDIR) || treat_as_template;
+        let tmpdir = if !use_tmpdir {
+            None
+        } else if treat_as_template {
+            Some(env_tmpdir().or(tmpdir_arg).unwrap_or_else(fallback_tmpdir))
+        } else {
+            let tmpdir_arg = tmpdir_arg.filter(|dir| !dir.is_empty());
+            Some(
+                tmpdir_arg
+                    .or_else(env_tmpdir)
+                    .unwrap_or_else(fallback_tmpdir),
+            )
         };
+        let template = template.unwrap_or_else(|| DEFAULT_TEMPLATE.to_string());
         Self {
             directory: matches.get_flag(OPT_DIRECTORY),
             dry_run: matches.get_flag(OPT_DRY_RUN),
             quiet: matches.get_flag(OPT_QUIET),
             tmpdir,
             suffix: matches.get_one::<String>(OPT_SUFFIX).map(String::from),
-            treat_as_template: matches.get_flag(OPT_T),
+            treat_as_template,
             template,
+            context: matches
+                .contains_id(OPT_CONTEXT)
+                .then(|| matches.get_one::<String>(OPT_CONTEXT).cloned()),
         }
     }
 }
@@ -234,18 +262,22 @@ struct Params {
 
 /// Find the start and end indices of the last contiguous block of Xs.
 ///
-/// If no contiguous block of at least three Xs could be found, this
-/// function returns `None`.
+/// Like GNU, everything after the last X is the suffix, so the block has
+/// to end there. If that block is shorter than three Xs, this function
+/// returns `None`.
 ///
 /// # Examples
 ///
 /// ```rust,ignore
 /// assert_eq!(find_last_contiguous_block_of_xs("XXX_XXX"), Some((4, 7)));
-/// assert_eq!(find_last_contiguous_block_of_xs("aXbXcX"), None);
+/// assert_eq!(find_last_contiguous_block_of_xs("aXXXbX"), None);
 /// ```
 fn find_last_contiguous_block_of_xs(s: &str) -> Option<(usize, usize)> {
-    let j = s.rfind("XXX")? + 3;
+    let j = s.rfind('X')? + 1;
     let i = s[..j].rfind(|c| c != 'X').map_or(0, |i| i + 1);
+    if j - i < 3 {
+        return None;
+    }
     Some((i, j))
 }
 
@@ -281,7 +313,7 @@ impl Params {
             .join(prefix_from_template)
             .display()
             .to_string();
-        if options.treat_as_template && prefix.contains(MAIN_SEPARATOR) {
+        if options.treat_as_template && prefix_from_template.contains(MAIN_SEPARATOR) {
             return Err(MkTempError::PrefixContainsDirSeparator(options.template));
         }
         if tmpdir.is_some() && Path::new(prefix_from_template).is_absolute() {
@@ -370,6 +402,18 @@ pub fn uumain(args: impl uucore::Args) -> UResult<()> {
     let suppress_file_err = options.quiet;
     let make_dir = options.directory;
 
+    // Like mkdir, -Z is silently ignored without SELinux or SMACK, but a
+    // context that was asked for is worth a warning.
+    let context = match options.context.clone() {
+        Some(ctx) if !security_context::is_supported() => {
+            if ctx.is_some() {
+                show_warning!("ignoring --context; it requires an SELinux/SMACK-enabled kernel");
+            }
+            None
+        }
+        context => context,
+    };
+
     // Parse file path parameters from the command-line options.
     let Params {
         directory: tmpdir,
@@ -382,7 +426,7 @@ pub fn uumain(args: impl uucore::Args) -> UResult<()> {
     let res = if dry_run {
         dry_exec(&tmpdir, &prefix, rand, &suffix)
     } else {
-        exec(&tmpdir, &prefix, rand, &suffix, make_dir)
+        exec(&tmpdir, &prefix, rand, &suffix, make_dir, context)
     };
 
     if suppress_file_err {
@@ -454,10 +498,29 @@ pub fn uu_app() -> Command {
                 )
                 .action(ArgAction::SetTrue),
         )
+        .arg(
+            Arg::new(OPT_CONTEXT)
+                .short('Z')
+                .long(OPT_CONTEXT)
+                .value_name("CTX")
+                .num_args(0..=1)
+                .require_equals(true)
+                .help(
+                    "set the SELinux security context of the created file or directory \
+                     to the default type, or to CTX if specified (the SMACK label, with SMACK)",
+                ),
+        )
         .arg(Arg::new(ARG_TEMPLATE).num_args(..=1))
 }
 
-pub fn dry_exec(tmpdir: &str, prefix: &str, rand: usize, suffix: &str) -> UResult<()> {
+/// How many names to try before giving up, like glibc's `TMP_MAX`.
+///
+/// This is the number of names that three Xs can stand for.
+const MAX_ATTEMPTS: u32 = 62 * 62 * 62;
+
+/// Make a random name `{prefix}{XXX}{suffix}` in `dir`, where `{XXX}` is
+/// `rand` random letters and digits.
+fn random_path(dir: &str, prefix: &str, rand: usize, suffix: &str) -> PathBuf {
     let len = prefix.len() + suffix.len() + rand;
     let mut buf = Vec::with_capacity(len);
     buf.extend(prefix.as_bytes());
@@ -477,8 +540,64 @@ pub fn dry_exec(tmpdir: &str, prefix: &str, rand: usize, suffix: &str) -> UResul
     }
     // We guarantee utf8.
     let buf = String::from_utf8(buf).unwrap();
-    let tmpdir = Path::new(tmpdir).join(buf);
-    println_verbatim(tmpdir).map_err_context(|| "failed to print directory name".to_owned())
+    Path::new(dir).join(buf)
+}
+
+/// Try random names until `create` makes one that did not exist yet.
+///
+/// `create` must fail with [`ErrorKind::AlreadyExists`] if the name is taken,
+/// and only then is another name tried, up to [`MAX_ATTEMPTS`] times.
+///
+/// # Errors
+///
+/// If `create` fails for another reason, or all names that were tried are
+/// taken; `kind` is what is being created, for the error message.
+fn create_temp<F>(
+    dir: &str,
+    prefix: &str,
+    rand: usize,
+    suffix: &str,
+    kind: &str,
+    create: F,
+) -> UResult<PathBuf>
+where
+    F: Fn(&Path) -> std::io::Result<()>,
+{
+    let mut res = Err(ErrorKind::AlreadyExists.into());
+    for _ in 0..MAX_ATTEMPTS {
+        let path = random_path(dir, prefix, rand, suffix);
+        res = create(&path).map(|()| path);
+        if !matches!(&res, Err(e) if e.kind() == ErrorKind::AlreadyExists) {
+            break;
+        }
+    }
+    // Say "File exists" like GNU, rather than uucore's "Already exists".
+    let res = res.map_err(|e| match e.kind() {
+        ErrorKind::AlreadyExists => std::io::Error::new(e.kind(), "File exists"),
+        _ => e,
+    });
+    res.map_err_context(|| {
+        let filename = format!("{}{}{}", prefix, "X".repeat(rand), suffix);
+        let path = Path::new(dir).join(filename);
+        format!("failed to create {} via template {}", kind, path.quote())
+    })
+}
+
+pub fn dry_exec(tmpdir: &str, prefix: &str, rand: usize, suffix: &str) -> UResult<()> {
+    // Like GNU, make sure that at least the name is not taken yet.
+    let path = create_temp(
+        tmpdir,
+        prefix,
+        rand,
+        suffix,
+        "file",
+        |path| match fs::symlink_metadata(path) {
+            Ok(_) => Err(ErrorKind::AlreadyExists.into()),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(e),
+        },
+    )?;
+    println_verbatim(path).map_err_context(|| "failed to print directory name".to_owned())
 }
 
 /// Create a temporary directory with the given parameters.
@@ -493,24 +612,12 @@ pub fn dry_exec(tmpdir: &str, prefix: &str, rand: usize, suffix: &str) -> UResul
 /// If the temporary directory could not be written to disk or if the
 /// given directory `dir` does not exist.
 fn make_temp_dir(dir: &str, prefix: &str, rand: usize, suffix: &str) -> UResult<PathBuf> {
-    let mut builder = Builder::new();
-    builder.prefix(prefix).rand_bytes(rand).suffix(suffix);
-    match builder.tempdir_in(dir) {
-        Ok(d) => {
-            // `into_path` consumes the TempDir without removing it
-            let path = d.into_path();
-            #[cfg(not(windows))]
-            fs::set_permissions(&path, fs::Permissions::from_mode(0o700))?;
-            Ok(path)
-        }
-        Err(e) if e.kind() == ErrorKind::NotFound => {
-            let filename = format!("{}{}{}", prefix, "X".repeat(rand), suffix);
-            let path = Path::new(dir).join(filename);
-            let s = path.display().to_string();
-            Err(MkTempError::NotFound("directory".to_string(), s).into())
-        }
-        Err(e) => Err(e.into()),
-    }
+    create_temp(dir, prefix, rand, suffix, "directory", |path| {
+        let mut builder = fs::DirBuilder::new();
+        #[cfg(unix)]
+        builder.mode(0o700);
+        builder.create(path)
+    })
 }
 
 /// Create a temporary file with the given parameters.
@@ -525,42 +632,45 @@ fn make_temp_dir(dir: &str, prefix: &str, rand: usize, suffix: &str) -> UResult<
 /// If the file could not be written to disk or if the directory does
 /// not exist.
 fn make_temp_file(dir: &str, prefix: &str, rand: usize, suffix: &str) -> UResult<PathBuf> {
-    let mut builder = Builder::new();
-    builder.prefix(prefix).rand_bytes(rand).suffix(suffix);
-    match builder.tempfile_in(dir) {
-        // `keep` ensures that the file is not deleted
-        Ok(named_tempfile) => match named_tempfile.keep() {
-            Ok((_, pathbuf)) => Ok(pathbuf),
-            Err(e) => Err(MkTempError::PersistError(e.file.path().to_path_buf()).into()),
-        },
-        Err(e) if e.kind() == ErrorKind::NotFound => {
-            let filename = format!("{}{}{}", prefix, "X".repeat(rand), suffix);
-            let path = Path::new(dir).join(filename);
-            let s = path.display().to_string();
-            Err(MkTempError::NotFound("file".to_string(), s).into())
-        }
-        Err(e) => Err(e.into()),
-    }
+    create_temp(dir, prefix, rand, suffix, "file", |path| {
+        let mut options = fs::OpenOptions::new();
+        // `create_new` fails if the file exists, atomically (`O_EXCL`).
+        options.write(true).create_new(true);
+        #[cfg(unix)]
+        options.mode(0o600);
+        options.open(path).map(|_| ())
+    })
 }
 
-fn exec(dir: &str, prefix: &str, rand: usize, suffix: &str, make_dir: bool) -> UResult<()> {
+fn exec(
+    dir: &str,
+    prefix: &str,
+    rand: usize,
+    suffix: &str,
+    make_dir: bool,
+    context: Option<Option<String>>,
+) -> UResult<()> {
     let path = if make_dir {
         make_temp_dir(dir, prefix, rand, suffix)?
     } else {
         make_temp_file(dir, prefix, rand, suffix)?
     };
 
-    // Get just the last component of the path to the created
-    // temporary file or directory.
-    let filename = path.file_name();
-    let filename = filename.unwrap().to_str().unwrap();
-
-    // Join the directory to the path to get the path to print. We
-    // cannot use the path returned by the `Builder` because it gives
-    // the absolute path and we need to return a filename that matches
-    // the template given on the command-line which might be a
-    // relative path.
-    let path = Path::new(dir).join(filename);
+    if let Some(context) = context {
+        let res = set_security_context(&path, context.as_deref());
+        if res.is_err() {
+            // Don't leave behind a temporary file that did not get the
+            // asked-for context.
+            if make_dir {
+                fs::remove_dir(&path).ok();
+            } else {
+                fs::remove_file(&path).ok();
+            }
+            return res.map_err_context(|| {
+                format!("failed to set the security context of {}", path.quote())
+            });
+        }
+    }
 
     println_verbatim(path).map_err_context(|| "failed to print directory name".to_owned())
 }
@@ -580,5 +690,8 @@ mod tests {
         assert_eq!(findxs("XX"), None);
         assert_eq!(findxs("aXbXcX"), None);
         assert_eq!(findxs("aXXbXXcXX"), None);
+        assert_eq!(findxs("aXXXbX"), None);
+        assert_eq!(findxs("aXXXbXc"), None);
+        assert_eq!(findxs("a/XXXb"), Some((2, 5)));
     }
 }
@@ -15,16 +15,19 @@ use clap::{crate_version, error::ErrorKind, Arg, ArgAction, ArgMatches, Command}
 use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
 use std::env;
 use std::ffi::OsString;
-use std::fs;
-use std::io;
+use std::fs::{self, File, Metadata, OpenOptions};
+use std::io::{self, Read, Seek, SeekFrom, Write};
 #[cfg(unix)]
 use std::os::unix;
 #[cfg(windows)]
 use std::os::windows;
 use std::path::{Path, PathBuf};
+#[cfg(not(unix))]
+use std::time::UNIX_EPOCH;
 use uucore::backup_control::{self, BackupMode};
 use uucore::display::Quotable;
 use uucore::error::{FromIo, UError, UResult, USimpleError, UUsageError};
+use uucore::progress;
 use uucore::{format_usage, prompt_yes, show};
 
 use fs_extra::dir::{
@@ -44,6 +47,7 @@ pub struct Behavior {
     verbose: bool,
     strip_slashes: bool,
     progress_bar: bool,
+    exchange: bool,
 }
 
 #[derive(Clone, Eq, PartialEq)]
@@ -69,6 +73,7 @@ static OPT_NO_TARGET_DIRECTORY: &str = "no-target-directory";
 static OPT_UPDATE: &str = "update";
 static OPT_VERBOSE: &str = "verbose";
 static OPT_PROGRESS: &str = "progress";
+static OPT_EXCHANGE: &str = "exchange";
 static ARG_FILES: &str = "files";
 
 #[uucore::main]
@@ -127,6 +132,7 @@ pub fn uumain(args: impl uucore::Args) -> UResult<()> {
         verbose: matches.get_flag(OPT_VERBOSE),
         strip_slashes: matches.get_flag(OPT_STRIP_TRAILING_SLASHES),
         progress_bar: matches.get_flag(OPT_PROGRESS),
+        exchange: matches.get_flag(OPT_EXCHANGE),
     };
 
     exec(&files[..], &behavior)
@@ -212,6 +218,22 @@ pub fn uu_app() -> Command {
                 )
                 .action(ArgAction::SetTrue),
         )
+        .arg(
+            Arg::new(OPT_EXCHANGE)
+                .long(OPT_EXCHANGE)
+                .help(
+                    "atomically exchange SOURCE and DEST, which must both exist \n\
+                Note: this feature is not supported by GNU coreutils.",
+                )
+                .conflicts_with_all([
+                    OPT_TARGET_DIRECTORY,
+                    OPT_NO_CLOBBER,
+                    OPT_INTERACTIVE,
+                    OPT_UPDATE,
+                    OPT_PROGRESS,
+                ])
+                .action(ArgAction::SetTrue),
+        )
         .arg(
             Arg::new(ARG_FILES)
                 .action(ArgAction::Append)
@@ -254,6 +276,9 @@ fn exec(files: &[OsString], b: &Behavior) -> UResult<()> {
     if let Some(ref name) = b.target_dir {
         return move_files_into_dir(&paths, &PathBuf::from(name), b);
     }
+    if b.exchange {
+        return exchange_files(&paths, b);
+    }
     match paths.len() {
         /* case 0/1 are not possible thanks to clap */
         2 => {
@@ -410,6 +435,73 @@ fn move_files_into_dir(files: &[PathBuf], target_dir: &Path, b: &Behavior) -> UR
     Ok(())
 }
 
+/// Swaps the two files of `paths`, whose backups are made by copying.
+fn exchange_files(paths: &[PathBuf], b: &Behavior) -> UResult<()> {
+    if paths.len() > 2 {
+        return Err(UUsageError::new(
+            1,
+            format!("extra operand {}", paths[2].quote()),
+        ));
+    }
+    let (first, second) = (&paths[0], &paths[1]);
+    if b.backup != BackupMode::NoBackup {
+        for path in [first, second] {
+            if let Some(backup_path) = backup_control::get_backup_path(b.backup, path, &b.suffix) {
+                fs::copy(path, &backup_path).map_err_context(|| {
+                    format!("cannot backup {} to {}", path.quote(), backup_path.quote())
+                })?;
+            }
+        }
+    }
+    exchange(first, second)
+        .map_err_context(|| format!("cannot exchange {} and {}", first.quote(), second.quote()))?;
+    if b.verbose {
+        println!("{} <-> {}", first.quote(), second.quote());
+    }
+    Ok(())
+}
+
+/// Atomically exchanges `a` and `b` with `renameat2(RENAME_EXCHANGE)`.
+#[cfg(any(target_os = "linux", target_os = "android"))]
+fn exchange(a: &Path, b: &Path) -> io::Result<()> {
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+
+    let a = CString::new(a.as_os_str().as_bytes())?;
+    let b = CString::new(b.as_os_str().as_bytes())?;
+    // The libc crate only declares `renameat2` for glibc, so the system call is made directly.
+    // SAFETY: both paths are NUL-terminated strings that outlive the call.
+    let result = unsafe {
+        libc::syscall(
+            libc::SYS_renameat2,
+            libc::AT_FDCWD,
+            a.as_ptr(),
+            libc::AT_FDCWD,
+            b.as_ptr(),
+            libc::RENAME_EXCHANGE,
+        )
+    };
+    if result == 0 {
+        return Ok(());
+    }
+    let err = io::Error::last_os_error();
+    match err.raw_os_error() {
+        Some(libc::EINVAL) | Some(libc::ENOSYS) => Err(io::Error::new(
+            io::ErrorKind::Other,
+            "exchanging files is not supported by this file system",
+        )),
+        _ => Err(err),
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "android")))]
+fn exchange(_a: &Path, _b: &Path) -> io::Result<()> {
+    Err(io::Error::new(
+        io::ErrorKind::Other,
+        "exchanging files is not supported on this platform",
+    ))
+}
+
 fn rename(
     from: &Path,
     to: &Path,
@@ -518,14 +610,7 @@ fn rename_with_fallback(
 
             let progress_bar =
                 if let (Some(multi_progress), Some(total_size)) = (multi_progress, total_size) {
-                    let bar = ProgressBar::new(total_size).with_style(
-                        ProgressStyle::with_template(
-                            "{msg}: [{elapsed_precise}] {wide_bar} {bytes:>7}/{total_bytes:7}",
-                        )
-                        .unwrap(),
-                    );
-
-                    Some(multi_progress.add(bar))
+                    Some(multi_progress.add(progress::bytes_progress_bar(total_size)))
                 } else {
                     None
                 };
@@ -550,12 +635,143 @@ fn rename_with_fallback(
                 };
             }
         } else {
-            fs::copy(from, to).and_then(|_| fs::remove_file(from))?;
+            move_file_across_devices(from, to, multi_progress)?;
         }
     }
     Ok(())
 }
 
+/// Returns a name for the source file of a move with the given `metadata`, which changes if
+/// the file is replaced or modified.
+#[cfg(unix)]
+fn source_identity(metadata: &Metadata) -> String {
+    use std::os::unix::fs::MetadataExt;
+    format!(
+        "{}-{}-{}-{}.{:09}",
+        metadata.dev(),
+        metadata.ino(),
+        metadata.size(),
+        metadata.mtime(),
+        metadata.mtime_nsec()
+    )
+}
+
+/// Returns a name for the source file of a move with the given `metadata`, which changes if
+/// the file is modified.
+#[cfg(not(unix))]
+fn source_identity(metadata: &Metadata) -> String {
+    let modified = metadata
+        .modified()
+        .ok()
+        .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+        .unwrap_or_default();
+    format!(
+        "{}-{}.{:09}",
+        metadata.len(),
+        modified.as_secs(),
+        modified.subsec_nanos()
+    )
+}
+
+/// Returns the temporary name of the partial copy to `to` of the source file with the given
+/// `metadata`.
+fn partial_path(to: &Path, metadata: &Metadata) -> PathBuf {
+    let mut name = OsString::from(".");
+    name.push(to.file_name().unwrap_or_default());
+    name.push(format!(".{}.mv-partial", source_identity(metadata)));
+    to.with_file_name(name)
+}
+
+/// Moves the regular file `from` to `to` on another file system by copying and removing it.
+///
+/// The data is copied to a temporary file next to `to` first, which only replaces `to` once it
+/// is complete, and is removed if the copy fails. If mv is interrupted instead, the temporary
+/// file is kept. As its name identifies the source, only the next move of the same, unchanged
+/// file resumes copying after its data.
+fn move_file_across_devices(
+    from: &Path,
+    to: &Path,
+    multi_progress: Option<&MultiProgress>,
+) -> io::Result<()> {
+    let mut src = File::open(from)?;
+    let src_metadata = src.metadata()?;
+    let partial = partial_path(to, &src_metadata);
+    let (mut dst, offset) = open_partial(&partial, src_metadata.len())?;
+    let result = copy_to_partial(
+        &mut src,
+        &src_metadata,
+        &mut dst,
+        offset,
+        from,
+        multi_progress,
+    )
+    .and_then(|_| fs::rename(&partial, to));
+    if result.is_err() {
+        let _ = fs::remove_file(&partial);
+    }
+    result.and_then(|_| fs::remove_file(from))
+}
+
+/// Opens the partial copy `partial` of a source file of `src_len` bytes, and returns it with the
+/// length of the data to keep.
+///
+/// The file is created, unless an interrupted earlier move of the same source left it behind.
+fn open_partial(partial: &Path, src_len: u64) -> io::Result<(File, u64)> {
+    match OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(partial)
+    {
+        Ok(file) => Ok((file, 0)),
+        Err(err) if err.kind() == io::ErrorKind::AlreadyExists => {
+            // Never write through a symlink or to anything else mv can't have created.
+            if !partial.symlink_metadata()?.is_file() {
+                return Err(err);
+            }
+            let file = OpenOptions::new().write(true).open(partial)?;
+            let len = file.metadata()?.len();
+            Ok((file, if len <= src_len { len } else { 0 }))
+        }
+        Err(err) => Err(err),
+    }
+}
+
+/// Copies `src` to `dst`, keeping the first `offset` bytes already in `dst`.
+fn copy_to_partial(
+    src: &mut File,
+    src_metadata: &Metadata,
+    dst: &mut File,
+    offset: u64,
+    from: &Path,
+    multi_progress: Option<&MultiProgress>,
+) -> io::Result<()> {
+    dst.set_len(offset)?;
+    src.seek(SeekFrom::Start(offset))?;
+    dst.seek(SeekFrom::Start(offset))?;
+
+    let progress_bar = multi_progress.map(|multi_progress| {
+        let bar = multi_progress.add(progress::bytes_progress_bar(src_metadata.len()));
+        progress::set_current_file(&bar, from, src_metadata.len());
+        bar.set_position(offset);
+        bar
+    });
+    let mut buf = vec![0; 64 * 1024];
+    loop {
+        let read = match src.read(&mut buf) {
+            Ok(0) => break,
+            Ok(read) => read,
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+            Err(err) => return Err(err),
+        };
+        dst.write_all(&buf[..read])?;
+        if let Some(bar) = &progress_bar {
+            bar.inc(read as u64);
+        }
+    }
+
+    dst.set_permissions(src_metadata.permissions())
+}
+
 /// Move the given symlink to the given destination. On Windows, dangling
 /// symlinks return an error.
 #[inline]
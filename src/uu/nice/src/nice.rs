@@ -5,21 +5,25 @@
 //  * For the full copyright and license information, please view the LICENSE
 //  * file that was distributed with this source code.
 
-// spell-checker:ignore (ToDO) getpriority execvp setpriority nstr PRIO cstrs ENOENT
+// spell-checker:ignore (ToDO) getpriority execvp setpriority nstr PRIO cstrs ENOENT pgrp renice
 
-use libc::{c_char, c_int, execvp, PRIO_PROCESS};
+use libc::{c_char, c_int, execvp};
 use std::ffi::{CString, OsString};
 use std::io::{Error, Write};
 use std::ptr;
 
-use clap::{crate_version, Arg, ArgAction, Command};
-use uucore::{
-    error::{set_exit_code, UClapError, UResult, USimpleError, UUsageError},
-    format_usage, show_error,
-};
+use clap::{crate_version, Arg, ArgAction, ArgMatches, Command};
+use uucore::display::Quotable;
+use uucore::entries::usr2uid;
+use uucore::error::{set_exit_code, strip_errno, UClapError, UResult, USimpleError, UUsageError};
+use uucore::process::{getpriority, setpriority, PriorityTarget};
+use uucore::{format_usage, show, show_error};
 
 pub mod options {
     pub static ADJUSTMENT: &str = "adjustment";
+    pub static PID: &str = "pid";
+    pub static PGRP: &str = "pgrp";
+    pub static USER: &str = "user";
     pub static COMMAND: &str = "COMMAND";
 }
 
@@ -28,7 +32,9 @@ const ABOUT: &str = "\
     With no COMMAND, print the current niceness.  Niceness values range from at \
     least -20 (most favorable to the process) to 19 (least favorable to the \
     process).";
-const USAGE: &str = "{} [OPTIONS] [COMMAND [ARGS]]";
+const USAGE: &str = "\
+    {} [OPTIONS] [COMMAND [ARGS]]
+    {} [-n N] {-p PID|-g PGRP|-u USER}...";
 
 fn is_prefix_of(maybe_prefix: &str, target: &str, min_match: usize) -> bool {
     if maybe_prefix.len() < min_match || maybe_prefix.len() > target.len() {
@@ -59,10 +65,12 @@ fn is_prefix_of(maybe_prefix: &str, target: &str, min_match: usize) -> bool {
 /// with clap in this case, it's much simpler to just normalize the
 /// arguments to nice before clap starts work. Here, we insert a
 /// prefix of "-n" onto all arguments of the form "-{i}", "--{i}" and
-/// "-+{i}" which are not already preceded by "-n".
+/// "-+{i}" which are not already preceded by "-n". The values of the
+/// options selecting running processes are left alone.
 fn standardize_nice_args(mut args: impl uucore::Args) -> impl uucore::Args {
     let mut v = Vec::<OsString>::new();
     let mut saw_n = false;
+    let mut saw_target = false;
     let mut saw_command = false;
     if let Some(cmd) = args.next() {
         v.push(cmd);
@@ -70,6 +78,9 @@ fn standardize_nice_args(mut args: impl uucore::Args) -> impl uucore::Args {
     for s in args {
         if saw_command {
             v.push(s);
+        } else if saw_target {
+            v.push(s);
+            saw_target = false;
         } else if saw_n {
             let mut new_arg: OsString = "-n".into();
             new_arg.push(s);
@@ -81,6 +92,14 @@ fn standardize_nice_args(mut args: impl uucore::Args) -> impl uucore::Args {
                 .unwrap_or_default()
         {
             saw_n = true;
+        } else if s.to_str().map_or(false, |s| {
+            ["-p", "-g", "-u"].contains(&s)
+                || ["--pid", "--pgrp", "--user"]
+                    .iter()
+                    .any(|target| is_prefix_of(s, target, "--p".len()))
+        }) {
+            saw_target = true;
+            v.push(s);
         } else if let Ok(s) = s.clone().into_string() {
             if let Some(stripped) = s.strip_prefix('-') {
                 match stripped.parse::<i64>() {
@@ -115,15 +134,20 @@ pub fn uumain(args: impl uucore::Args) -> UResult<()> {
 
     let matches = uu_app().try_get_matches_from(args).with_exit_code(125)?;
 
-    nix::errno::Errno::clear();
-    let mut niceness = unsafe { libc::getpriority(PRIO_PROCESS, 0) };
-    if Error::last_os_error().raw_os_error().unwrap() != 0 {
-        return Err(USimpleError::new(
-            125,
-            format!("getpriority: {}", Error::last_os_error()),
-        ));
+    let targets = [options::PID, options::PGRP, options::USER];
+    if targets.iter().any(|&target| matches.contains_id(target)) {
+        if matches.contains_id(options::COMMAND) {
+            return Err(UUsageError::new(
+                125,
+                "A command can't be given with --pid, --pgrp or --user.",
+            ));
+        }
+        return renice(&matches);
     }
 
+    let mut niceness = getpriority(PriorityTarget::Process(0))
+        .map_err(|e| USimpleError::new(125, format!("getpriority: {e}")))?;
+
     let adjustment = match matches.get_one::<String>(options::ADJUSTMENT) {
         Some(nstr) => {
             if !matches.contains_id(options::COMMAND) {
@@ -132,15 +156,7 @@ pub fn uumain(args: impl uucore::Args) -> UResult<()> {
                     "A command must be given with an adjustment.",
                 ));
             }
-            match nstr.parse() {
-                Ok(num) => num,
-                Err(e) => {
-                    return Err(USimpleError::new(
-                        125,
-                        format!("\"{nstr}\" is not a valid number: {e}"),
-                    ))
-                }
-            }
+            parse_adjustment(nstr)?
         }
         None => {
             if !matches.contains_id(options::COMMAND) {
@@ -156,17 +172,18 @@ pub fn uumain(args: impl uucore::Args) -> UResult<()> {
     // isn't writable. The GNU test suite checks specifically that the
     // exit code when failing to write the advisory is 125, but Rust
     // will produce an exit code of 101 when it panics.
-    if unsafe { libc::setpriority(PRIO_PROCESS, 0, niceness) } == -1
-        && write!(
+    if let Err(e) = setpriority(PriorityTarget::Process(0), niceness) {
+        if write!(
             std::io::stderr(),
             "{}: warning: setpriority: {}",
             uucore::util_name(),
-            Error::last_os_error()
+            e
         )
         .is_err()
-    {
-        set_exit_code(125);
-        return Ok(());
+        {
+            set_exit_code(125);
+            return Ok(());
+        }
     }
 
     let cstrs: Vec<CString> = matches
@@ -191,6 +208,92 @@ pub fn uumain(args: impl uucore::Args) -> UResult<()> {
     Ok(())
 }
 
+fn parse_adjustment(nstr: &str) -> UResult<i32> {
+    nstr.parse()
+        .map_err(|e| USimpleError::new(125, format!("\"{nstr}\" is not a valid number: {e}")))
+}
+
+fn parse_target(id: &str, kind: &str) -> UResult<PriorityTarget> {
+    let target = if kind == options::USER {
+        id.parse()
+            .ok()
+            .or_else(|| usr2uid(id).ok())
+            .map(PriorityTarget::User)
+    } else {
+        id.parse().ok().map(|id| {
+            if kind == options::PID {
+                PriorityTarget::Process(id)
+            } else {
+                PriorityTarget::ProcessGroup(id)
+            }
+        })
+    };
+    target.ok_or_else(|| {
+        let what = if kind == options::USER { "user" } else { "ID" };
+        USimpleError::new(1, format!("invalid {what}: {}", id.quote()))
+    })
+}
+
+/// Add the adjustment to the niceness of running processes, like renice.
+fn renice(matches: &ArgMatches) -> UResult<()> {
+    let adjustment = match matches.get_one::<String>(options::ADJUSTMENT) {
+        Some(nstr) => parse_adjustment(nstr)?,
+        None => 10,
+    };
+
+    // Handle the targets in the order they were given.
+    let mut ids = vec![];
+    for kind in [options::PID, options::PGRP, options::USER] {
+        if let (Some(indices), Some(values)) =
+            (matches.indices_of(kind), matches.get_many::<String>(kind))
+        {
+            ids.extend(indices.zip(values).map(|(i, id)| (i, id, kind)));
+        }
+    }
+    ids.sort_by_key(|&(i, _, _)| i);
+
+    for (_, id, kind) in ids {
+        let target = match parse_target(id, kind) {
+            Ok(target) => target,
+            Err(e) => {
+                show!(e);
+                continue;
+            }
+        };
+        let description = match target {
+            PriorityTarget::Process(_) => "process ID",
+            PriorityTarget::ProcessGroup(_) => "process group ID",
+            PriorityTarget::User(_) => "user ID",
+        };
+        let old = match getpriority(target) {
+            Ok(niceness) => niceness,
+            Err(e) => {
+                show!(USimpleError::new(
+                    1,
+                    format!(
+                        "failed to get priority for {id} ({description}): {}",
+                        strip_errno(&e)
+                    )
+                ));
+                continue;
+            }
+        };
+        let new =
+            setpriority(target, old.saturating_add(adjustment)).and_then(|_| getpriority(target));
+        match new {
+            Ok(new) => println!("{id} ({description}) old priority {old}, new priority {new}"),
+            Err(e) => show!(USimpleError::new(
+                1,
+                format!(
+                    "failed to set priority for {id} ({description}): {}",
+                    strip_errno(&e)
+                )
+            )),
+        }
+    }
+    Ok(())
+}
+
 pub fn uu_app() -> Command {
     Command::new(uucore::util_name())
         .about(ABOUT)
@@ -207,6 +310,32 @@ pub fn uu_app() -> Command {
                 .overrides_with(options::ADJUSTMENT)
                 .allow_hyphen_values(true),
         )
+        .arg(
+            Arg::new(options::PID)
+                .short('p')
+                .long(options::PID)
+                .value_name("PID")
+                .help("adjust the niceness of the running process PID instead (uutils extension)")
+                .action(ArgAction::Append),
+        )
+        .arg(
+            Arg::new(options::PGRP)
+                .short('g')
+                .long(options::PGRP)
+                .value_name("PGRP")
+                .help(
+                    "adjust the niceness of the processes in group PGRP instead (uutils extension)",
+                )
+                .action(ArgAction::Append),
+        )
+        .arg(
+            Arg::new(options::USER)
+                .short('u')
+                .long(options::USER)
+                .value_name("USER")
+                .help("adjust the niceness of the processes of USER instead (uutils extension)")
+                .action(ArgAction::Append),
+        )
         .arg(
             Arg::new(options::COMMAND)
                 .action(ArgAction::Append)
@@ -5,7 +5,7 @@
 //  * For the full copyright and license information, please view the LICENSE
 //  * file that was distributed with this source code.
 
-// spell-checker:ignore (ToDO) execvp SIGHUP cproc vprocmgr cstrs homeout
+// spell-checker:ignore (ToDO) execvp SIGHUP cproc vprocmgr cstrs homeout setsid WEXITSTATUS WIFEXITED WTERMSIG
 
 use clap::{crate_version, Arg, ArgAction, Command};
 use libc::{c_char, dup2, execvp, signal};
@@ -18,18 +18,19 @@ use std::io::Error;
 use std::os::unix::prelude::*;
 use std::path::{Path, PathBuf};
 use uucore::display::Quotable;
-use uucore::error::{set_exit_code, UClapError, UError, UResult};
+use uucore::error::{strip_errno, UClapError, UError, UResult};
 use uucore::{format_usage, show_error};
 
 static ABOUT: &str = "Run COMMAND ignoring hangup signals.";
 static LONG_HELP: &str = "
-If standard input is terminal, it'll be replaced with /dev/null.
-If standard output is terminal, it'll be appended to nohup.out instead,
-or $HOME/nohup.out, if nohup.out open failed.
+If standard input is a terminal, it'll be replaced with an unreadable file.
+If standard output is a terminal, it'll be appended to nohup.out instead,
+or $HOME/nohup.out, if nohup.out open failed. The file is only readable
+and writable by its owner.
 If standard error is terminal, it'll be redirected to stdout.
 ";
 const USAGE: &str = "\
-    {} COMMAND [ARG]...
+    {} [--setsid] COMMAND [ARG]...
     {} FLAG";
 static NOHUP_OUT: &str = "nohup.out";
 // exit codes that match the GNU implementation
@@ -40,14 +41,17 @@ static POSIX_NOHUP_FAILURE: i32 = 127;
 
 mod options {
     pub const CMD: &str = "cmd";
+    pub const SETSID: &str = "setsid";
 }
 
 #[derive(Debug)]
 enum NohupError {
     CannotDetach,
-    CannotReplace(&'static str, std::io::Error),
+    CannotReplace(i32, &'static str, std::io::Error),
     OpenFailed(i32, std::io::Error),
     OpenFailed2(i32, std::io::Error, String, std::io::Error),
+    CannotStartSession(i32, std::io::Error),
+    CannotRun(String, std::io::Error),
 }
 
 impl std::error::Error for NohupError {}
@@ -55,8 +59,13 @@ impl std::error::Error for NohupError {}
 impl UError for NohupError {
     fn code(&self) -> i32 {
         match self {
-            Self::OpenFailed(code, _) | Self::OpenFailed2(code, _, _, _) => *code,
-            _ => 2,
+            Self::CannotReplace(code, _, _)
+            | Self::OpenFailed(code, _)
+            | Self::OpenFailed2(code, _, _, _)
+            | Self::CannotStartSession(code, _) => *code,
+            Self::CannotRun(_, e) if e.kind() == std::io::ErrorKind::NotFound => EXIT_ENOENT,
+            Self::CannotRun(_, _) => EXIT_CANNOT_INVOKE,
+            Self::CannotDetach => 2,
         }
     }
 }
@@ -65,18 +74,34 @@ impl Display for NohupError {
     fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
         match self {
             Self::CannotDetach => write!(f, "Cannot detach from console"),
-            Self::CannotReplace(s, e) => write!(f, "Cannot replace {s}: {e}"),
+            Self::CannotReplace(_, s, e) => write!(f, "{s}: {}", strip_errno(e)),
             Self::OpenFailed(_, e) => {
-                write!(f, "failed to open {}: {}", NOHUP_OUT.quote(), e)
+                write!(
+                    f,
+                    "failed to open {}: {}",
+                    NOHUP_OUT.quote(),
+                    strip_errno(e)
+                )
             }
             Self::OpenFailed2(_, e1, s, e2) => write!(
                 f,
                 "failed to open {}: {}\nfailed to open {}: {}",
                 NOHUP_OUT.quote(),
-                e1,
+                strip_errno(e1),
                 s.quote(),
-                e2
+                strip_errno(e2)
             ),
+            Self::CannotStartSession(_, e) => {
+                write!(f, "failed to start a new session: {}", strip_errno(e))
+            }
+            Self::CannotRun(cmd, e) => {
+                write!(
+                    f,
+                    "failed to run command {}: {}",
+                    cmd.quote(),
+                    strip_errno(e)
+                )
+            }
         }
     }
 }
@@ -87,7 +112,8 @@ pub fn uumain(args: impl uucore::Args) -> UResult<()> {
 
     let matches = uu_app().try_get_matches_from(args).with_exit_code(125)?;
 
-    replace_fds()?;
+    let failure_code = internal_failure_code();
+    let saved_stderr = replace_fds(failure_code)?;
 
     unsafe { signal(SIGHUP, SIG_IGN) };
 
@@ -95,20 +121,18 @@ pub fn uumain(args: impl uucore::Args) -> UResult<()> {
         return Err(NohupError::CannotDetach.into());
     };
 
-    let cstrs: Vec<CString> = matches
-        .get_many::<String>(options::CMD)
-        .unwrap()
-        .map(|x| CString::new(x.as_bytes()).unwrap())
-        .collect();
-    let mut args: Vec<*const c_char> = cstrs.iter().map(|s| s.as_ptr()).collect();
-    args.push(std::ptr::null());
+    if matches.get_flag(options::SETSID) {
+        start_session(failure_code)?;
+    }
+
+    let command: Vec<&String> = matches.get_many::<String>(options::CMD).unwrap().collect();
+    let error = exec(&command);
 
-    let ret = unsafe { execvp(args[0], args.as_mut_ptr()) };
-    match ret {
-        libc::ENOENT => set_exit_code(EXIT_ENOENT),
-        _ => set_exit_code(EXIT_CANNOT_INVOKE),
+    // Report the failure where errors went before stderr was redirected.
+    if let Some(fd) = saved_stderr {
+        unsafe { dup2(fd, 2) };
     }
-    Ok(())
+    Err(NohupError::CannotRun(command[0].to_string(), error).into())
 }
 
 pub fn uu_app() -> Command {
@@ -117,6 +141,15 @@ pub fn uu_app() -> Command {
         .about(ABOUT)
         .after_help(LONG_HELP)
         .override_usage(format_usage(USAGE))
+        .arg(
+            Arg::new(options::SETSID)
+                .long(options::SETSID)
+                .help(
+                    "also run COMMAND in a new session, without a controlling \
+                    terminal (uutils extension)",
+                )
+                .action(ArgAction::SetTrue),
+        )
         .arg(
             Arg::new(options::CMD)
                 .hide(true)
@@ -128,80 +161,167 @@ pub fn uu_app() -> Command {
         .infer_long_args(true)
 }
 
-fn replace_fds() -> UResult<()> {
-    if atty::is(atty::Stream::Stdin) {
-        let new_stdin = File::open(Path::new("/dev/null"))
-            .map_err(|e| NohupError::CannotReplace("STDIN", e))?;
-        if unsafe { dup2(new_stdin.as_raw_fd(), 0) } != 0 {
-            return Err(NohupError::CannotReplace("STDIN", Error::last_os_error()).into());
-        }
+/// The exit code for failures of nohup itself, before COMMAND is run
+fn internal_failure_code() -> i32 {
+    match std::env::var("POSIXLY_CORRECT") {
+        Ok(_) => POSIX_NOHUP_FAILURE,
+        Err(_) => EXIT_CANCELED,
     }
+}
 
-    if atty::is(atty::Stream::Stdout) {
-        let new_stdout = find_stdout()?;
-        let fd = new_stdout.as_raw_fd();
+/// Redirect the standard streams that are terminals.
+///
+/// Returns a copy of the original stderr if it was redirected, closed on exec,
+/// so that a failure to run the command can still be reported there.
+fn replace_fds(failure_code: i32) -> UResult<Option<RawFd>> {
+    let ignoring_input = atty::is(atty::Stream::Stdin);
+    let redirecting_stdout = atty::is(atty::Stream::Stdout);
+    let redirecting_stderr = atty::is(atty::Stream::Stderr);
 
-        if unsafe { dup2(fd, 1) } != 1 {
-            return Err(NohupError::CannotReplace("STDOUT", Error::last_os_error()).into());
+    if ignoring_input {
+        // Like GNU, make reading fail rather than look like an empty input.
+        OpenOptions::new()
+            .write(true)
+            .open(Path::new("/dev/null"))
+            .and_then(|new_stdin| reopen(new_stdin.as_raw_fd(), 0))
+            .map_err(|e| {
+                NohupError::CannotReplace(
+                    failure_code,
+                    "failed to render standard input unusable",
+                    e,
+                )
+            })?;
+        if !redirecting_stdout && !redirecting_stderr {
+            show_error!("ignoring input");
         }
     }
 
-    if atty::is(atty::Stream::Stderr) && unsafe { dup2(1, 2) } != 2 {
-        return Err(NohupError::CannotReplace("STDERR", Error::last_os_error()).into());
+    if redirecting_stdout {
+        let (new_stdout, path) = find_stdout(failure_code)?;
+        reopen(new_stdout.as_raw_fd(), 1).map_err(|e| {
+            NohupError::CannotReplace(failure_code, "failed to redirect standard output", e)
+        })?;
+        show_error!(
+            "{}appending output to {}",
+            if ignoring_input {
+                "ignoring input and "
+            } else {
+                ""
+            },
+            path.quote()
+        );
     }
-    Ok(())
+
+    if !redirecting_stderr {
+        return Ok(None);
+    }
+    if !redirecting_stdout {
+        show_error!(
+            "{}redirecting stderr to stdout",
+            if ignoring_input {
+                "ignoring input and "
+            } else {
+                ""
+            }
+        );
+    }
+    // It's no big deal if the copy fails, as only the report of a failure
+    // to run the command would be lost.
+    let saved = unsafe { libc::fcntl(2, libc::F_DUPFD_CLOEXEC, 3) };
+    reopen(1, 2).map_err(|e| {
+        NohupError::CannotReplace(failure_code, "failed to redirect standard error", e)
+    })?;
+    Ok(Some(saved).filter(|&fd| fd != -1))
 }
 
-fn find_stdout() -> UResult<File> {
-    let internal_failure_code = match std::env::var("POSIXLY_CORRECT") {
-        Ok(_) => POSIX_NOHUP_FAILURE,
-        Err(_) => EXIT_CANCELED,
+/// Make `target` a copy of `fd`.
+fn reopen(fd: RawFd, target: RawFd) -> std::io::Result<()> {
+    if fd == target || unsafe { dup2(fd, target) } == target {
+        Ok(())
+    } else {
+        Err(Error::last_os_error())
+    }
+}
+
+/// Open nohup.out for appending, or $HOME/nohup.out if that fails.
+fn find_stdout(failure_code: i32) -> UResult<(File, String)> {
+    let e1 = match open_nohup_out(Path::new(NOHUP_OUT)) {
+        Ok(t) => return Ok((t, NOHUP_OUT.to_string())),
+        Err(e1) => e1,
     };
+    let home = match env::var("HOME") {
+        Err(_) => return Err(NohupError::OpenFailed(failure_code, e1).into()),
+        Ok(h) => h,
+    };
+    let mut homeout = PathBuf::from(home);
+    homeout.push(NOHUP_OUT);
+    let homeout_str = homeout.to_string_lossy().into_owned();
+    match open_nohup_out(&homeout) {
+        Ok(t) => Ok((t, homeout_str)),
+        Err(e2) => Err(NohupError::OpenFailed2(failure_code, e1, homeout_str, e2).into()),
+    }
+}
 
-    match OpenOptions::new()
-        .write(true)
+/// Open `path` for appending, creating it readable and writable by the user
+/// only, whatever the umask.
+fn open_nohup_out(path: &Path) -> std::io::Result<File> {
+    let old_umask = unsafe { libc::umask(!0o600) };
+    let file = OpenOptions::new()
         .create(true)
         .append(true)
-        .open(Path::new(NOHUP_OUT))
-    {
-        Ok(t) => {
-            show_error!(
-                "ignoring input and appending output to {}",
-                NOHUP_OUT.quote()
+        .mode(0o600)
+        .open(path);
+    unsafe { libc::umask(old_umask) };
+    file
+}
+
+/// Make nohup part of a new session, without a controlling terminal.
+///
+/// A process group leader can't start a session, so in that case the command
+/// is run in a child process, whose exit status nohup waits for and returns.
+fn start_session(failure_code: i32) -> UResult<()> {
+    if unsafe { libc::setsid() } != -1 {
+        return Ok(());
+    }
+    let pid = unsafe { libc::fork() };
+    if pid == -1 {
+        return Err(NohupError::CannotStartSession(failure_code, Error::last_os_error()).into());
+    }
+    if pid == 0 {
+        if unsafe { libc::setsid() } == -1 {
+            return Err(
+                NohupError::CannotStartSession(failure_code, Error::last_os_error()).into(),
             );
-            Ok(t)
         }
-        Err(e1) => {
-            let home = match env::var("HOME") {
-                Err(_) => return Err(NohupError::OpenFailed(internal_failure_code, e1).into()),
-                Ok(h) => h,
-            };
-            let mut homeout = PathBuf::from(home);
-            homeout.push(NOHUP_OUT);
-            let homeout_str = homeout.to_str().unwrap();
-            match OpenOptions::new()
-                .write(true)
-                .create(true)
-                .append(true)
-                .open(&homeout)
-            {
-                Ok(t) => {
-                    show_error!(
-                        "ignoring input and appending output to {}",
-                        homeout_str.quote()
-                    );
-                    Ok(t)
-                }
-                Err(e2) => Err(NohupError::OpenFailed2(
-                    internal_failure_code,
-                    e1,
-                    homeout_str.to_string(),
-                    e2,
-                )
-                .into()),
-            }
+        return Ok(());
+    }
+
+    let mut status = 0;
+    while unsafe { libc::waitpid(pid, &mut status, 0) } == -1 {
+        let e = Error::last_os_error();
+        if e.kind() != std::io::ErrorKind::Interrupted {
+            return Err(NohupError::CannotStartSession(failure_code, e).into());
         }
     }
+    let code = if libc::WIFEXITED(status) {
+        libc::WEXITSTATUS(status)
+    } else {
+        128 + libc::WTERMSIG(status)
+    };
+    std::process::exit(code);
+}
+
+/// Replace nohup with `command`, which only returns if that fails.
+fn exec(command: &[&String]) -> Error {
+    let cstrs: Vec<CString> = match command.iter().map(|x| CString::new(x.as_bytes())).collect() {
+        Ok(cstrs) => cstrs,
+        Err(e) => return e.into(),
+    };
+    let mut args: Vec<*const c_char> = cstrs.iter().map(|s| s.as_ptr()).collect();
+    args.push(std::ptr::null());
+
+    unsafe { execvp(args[0], args.as_mut_ptr()) };
+    Error::last_os_error()
 }
 
 #[cfg(target_vendor = "apple")]
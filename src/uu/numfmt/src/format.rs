@@ -1,7 +1,9 @@
 // spell-checker:ignore powf
 use uucore::display::Quotable;
+use uucore::error::set_exit_code;
+use uucore::show_error;
 
-use crate::options::{NumfmtOptions, RoundMethod, TransformOptions};
+use crate::options::{InvalidModes, NumfmtOptions, RoundMethod, TransformOptions};
 use crate::units::{DisplayableSuffix, RawSuffix, Result, Suffix, Unit, IEC_BASES, SI_BASES};
 
 /// Iterate over a line's fields, where each field is a contiguous sequence of
@@ -330,7 +332,20 @@ fn format_string(
     let padded_number = match padding {
         0 => number_with_suffix,
         p if p > 0 && options.format.zero_padding => {
-            let zero_padded = format!("{:0>padding$}", number_with_suffix, padding = p as usize);
+            // only the number is padded with zeros, after its sign and before its suffixes
+            let (number, suffix) = number_with_suffix.split_at(
+                number_with_suffix
+                    .find(|c: char| !(c.is_ascii_digit() || c == '.' || c == '-'))
+                    .unwrap_or(number_with_suffix.len()),
+            );
+            let (sign, digits) = match number.strip_prefix('-') {
+                Some(digits) => ("-", digits),
+                None => ("", number),
+            };
+            let zero_padded = format!(
+                "{sign}{digits:0>padding$}{suffix}",
+                padding = (p as usize).saturating_sub(sign.len())
+            );
 
             match implicit_padding.unwrap_or(options.padding) {
                 0 => zero_padded,
@@ -352,6 +367,21 @@ fn format_string(
     ))
 }
 
+/// Reports a field that isn't a valid number as selected by `--invalid`, and returns the error
+/// if it is fatal.
+fn handle_invalid(err: String, options: &NumfmtOptions) -> Result<()> {
+    match options.invalid {
+        InvalidModes::Abort => return Err(err),
+        InvalidModes::Fail => {
+            show_error!("{}", err);
+            set_exit_code(2);
+        }
+        InvalidModes::Warn => show_error!("{}", err),
+        InvalidModes::Ignore => {}
+    }
+    Ok(())
+}
+
 fn format_and_print_delimited(s: &str, options: &NumfmtOptions) -> Result<()> {
     let delimiter = options.delimiter.as_ref().unwrap();
 
@@ -364,7 +394,13 @@ fn format_and_print_delimited(s: &str, options: &NumfmtOptions) -> Result<()> {
         }
 
         if field_selected {
-            print!("{}", format_string(field.trim_start(), options, None)?);
+            match format_string(field.trim_start(), options, None) {
+                Ok(formatted) => print!("{formatted}"),
+                Err(e) => {
+                    handle_invalid(e, options)?;
+                    print!("{field}");
+                }
+            }
         } else {
             // print unselected field without conversion
             print!("{field}");
@@ -397,7 +433,13 @@ fn format_and_print_whitespace(s: &str, options: &NumfmtOptions) -> Result<()> {
                 None
             };
 
-            print!("{}", format_string(field, options, implicit_padding)?);
+            match format_string(field, options, implicit_padding) {
+                Ok(formatted) => print!("{formatted}"),
+                Err(e) => {
+                    handle_invalid(e, options)?;
+                    print!("{prefix}{field}");
+                }
+            }
         } else {
             // print unselected field without conversion
             print!("{prefix}{field}");
@@ -161,10 +161,7 @@ fn parse_options(args: &ArgMatches) -> Result<NumfmtOptions> {
     let fields = args.get_one::<String>(options::FIELD).unwrap().as_str();
     // a lone "-" means "all fields", even as part of a list of fields
     let fields = if fields.split(&[',', ' ']).any(|x| x == "-") {
-        vec![Range {
-            low: 1,
-            high: std::usize::MAX,
-        }]
+        vec![Range::new(1, usize::MAX)]
     } else {
         Range::from_list(fields)?
     };
@@ -202,6 +199,15 @@ fn parse_options(args: &ArgMatches) -> Result<NumfmtOptions> {
         .get_one::<String>(options::SUFFIX)
         .map(|s| s.to_owned());
 
+    // unwrap is fine because the argument has a default value
+    let invalid = match args.get_one::<String>(options::INVALID).unwrap().as_str() {
+        "abort" => InvalidModes::Abort,
+        "fail" => InvalidModes::Fail,
+        "warn" => InvalidModes::Warn,
+        "ignore" => InvalidModes::Ignore,
+        _ => unreachable!("Should be restricted by clap"),
+    };
+
     Ok(NumfmtOptions {
         transform,
         padding,
@@ -211,6 +217,7 @@ fn parse_options(args: &ArgMatches) -> Result<NumfmtOptions> {
         round,
         suffix,
         format,
+        invalid,
     })
 }
 
@@ -358,6 +365,17 @@ pub fn uu_app() -> Command {
                 )
                 .value_name("SUFFIX"),
         )
+        .arg(
+            Arg::new(options::INVALID)
+                .long(options::INVALID)
+                .help(
+                    "set the failure mode for invalid input; MODE can be: \
+                    abort, fail, warn, ignore",
+                )
+                .value_name("MODE")
+                .default_value("abort")
+                .value_parser(["abort", "fail", "warn", "ignore"]),
+        )
         .arg(
             Arg::new(options::NUMBER)
                 .hide(true)
@@ -368,8 +386,8 @@ pub fn uu_app() -> Command {
 #[cfg(test)]
 mod tests {
     use super::{
-        handle_buffer, parse_unit_size, parse_unit_size_suffix, FormatOptions, NumfmtOptions,
-        Range, RoundMethod, TransformOptions, Unit,
+        handle_buffer, parse_unit_size, parse_unit_size_suffix, FormatOptions, InvalidModes,
+        NumfmtOptions, Range, RoundMethod, TransformOptions, Unit,
     };
     use std::io::{BufReader, Error, ErrorKind, Read};
     struct MockBuffer {}
@@ -390,11 +408,12 @@ mod tests {
             },
             padding: 10,
             header: 1,
-            fields: vec![Range { low: 0, high: 1 }],
+            fields: vec![Range::new(0, 1)],
             delimiter: None,
             round: RoundMethod::Nearest,
             suffix: None,
             format: FormatOptions::default(),
+            invalid: InvalidModes::Abort,
         }
     }
 
@@ -13,6 +13,7 @@ pub const FROM_UNIT: &str = "from-unit";
 pub const FROM_UNIT_DEFAULT: &str = "1";
 pub const HEADER: &str = "header";
 pub const HEADER_DEFAULT: &str = "1";
+pub const INVALID: &str = "invalid";
 pub const NUMBER: &str = "NUMBER";
 pub const PADDING: &str = "padding";
 pub const ROUND: &str = "round";
@@ -38,6 +39,20 @@ pub struct NumfmtOptions {
     pub round: RoundMethod,
     pub suffix: Option<String>,
     pub format: FormatOptions,
+    pub invalid: InvalidModes,
+}
+
+/// What to do with input that isn't a valid number, selected by `--invalid`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum InvalidModes {
+    /// Exit with an error (the default).
+    Abort,
+    /// Print the input unchanged with a diagnostic, and exit with an error at the end.
+    Fail,
+    /// Print the input unchanged with a diagnostic.
+    Warn,
+    /// Print the input unchanged.
+    Ignore,
 }
 
 #[derive(Clone, Copy)]
@@ -0,0 +1,84 @@
+// spell-checker:ignore (ToDO) hexyl
+
+//! ANSI colors used by `--color`, in the spirit of hexyl: offsets, NUL bytes,
+//! printable ASCII, other ASCII and non-ASCII bytes are each colored
+//! differently.
+
+pub const RESET: &str = "\x1b[0m";
+pub const OFFSET: &str = "\x1b[34m";
+
+/// The color of a formatted item depends on the kind of bytes it was read from
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum ByteClass {
+    Null,
+    AsciiPrintable,
+    AsciiOther,
+    NonAscii,
+}
+
+impl ByteClass {
+    /// Classifies the bytes of a single item. An item is only considered
+    /// printable if all of its bytes are, and non-ASCII if any of them are.
+    pub fn of_item(bytes: &[u8]) -> Self {
+        if bytes.iter().all(|&b| b == 0) {
+            Self::Null
+        } else if bytes.iter().any(|&b| !b.is_ascii()) {
+            Self::NonAscii
+        } else if bytes.iter().all(|&b| b.is_ascii_graphic() || b == b' ') {
+            Self::AsciiPrintable
+        } else {
+            Self::AsciiOther
+        }
+    }
+
+    pub fn color(self) -> &'static str {
+        match self {
+            Self::Null => "\x1b[90m",
+            Self::AsciiPrintable => "\x1b[36m",
+            Self::AsciiOther => "\x1b[32m",
+            Self::NonAscii => "\x1b[33m",
+        }
+    }
+}
+
+/// Writes `text` to `out` in `color`, leaving the leading padding uncolored
+pub fn push_colored(out: &mut String, text: &str, color: &str) {
+    let trimmed = text.trim_start_matches(' ');
+    out.push_str(&text[..text.len() - trimmed.len()]);
+    if !trimmed.is_empty() {
+        out.push_str(color);
+        out.push_str(trimmed);
+        out.push_str(RESET);
+    }
+}
+
+/// The number of characters of `text` that take up space on the terminal
+pub fn visible_width(text: &str) -> usize {
+    let mut width = 0;
+    let mut in_escape = false;
+    for c in text.chars() {
+        match c {
+            '\x1b' => in_escape = true,
+            'm' if in_escape => in_escape = false,
+            _ if in_escape => {}
+            _ => width += 1,
+        }
+    }
+    width
+}
+
+#[test]
+fn test_byte_class() {
+    assert_eq!(ByteClass::Null, ByteClass::of_item(&[0, 0]));
+    assert_eq!(ByteClass::AsciiPrintable, ByteClass::of_item(b"a "));
+    assert_eq!(ByteClass::AsciiOther, ByteClass::of_item(b"a\n"));
+    assert_eq!(ByteClass::NonAscii, ByteClass::of_item(&[0, 0xc3]));
+}
+
+#[test]
+fn test_push_colored() {
+    let mut s = String::new();
+    push_colored(&mut s, "  ff", OFFSET);
+    assert_eq!("  \x1b[34mff\x1b[0m", s);
+    assert_eq!(4, visible_width(&s));
+}
@@ -5,7 +5,7 @@ use std::fmt;
 #[allow(clippy::enum_variant_names)]
 #[derive(Copy)]
 pub enum FormatWriter {
-    IntWriter(fn(u64) -> String),
+    IntWriter(fn(u128) -> String),
     FloatWriter(fn(f64) -> String),
     MultibyteWriter(fn(&[u8]) -> String),
 }
@@ -99,7 +99,47 @@ pub struct MemoryDecoder<'a> {
     byte_order: ByteOrder,
 }
 
+/// The buffer taken from a `MemoryDecoder`, so that it can be decoded on
+/// another thread
+pub struct MemoryBlock {
+    data: Vec<u8>,
+    used_normal_length: usize,
+    used_peek_length: usize,
+    byte_order: ByteOrder,
+}
+
+impl MemoryBlock {
+    /// Returns a `MemoryDecoder` providing access to the data.
+    pub fn decoder(&mut self) -> MemoryDecoder<'_> {
+        MemoryDecoder {
+            data: &mut self.data,
+            used_normal_length: self.used_normal_length,
+            used_peek_length: self.used_peek_length,
+            byte_order: self.byte_order,
+        }
+    }
+
+    /// Returns the buffer, to be handed to `MemoryDecoder::take_block` again.
+    pub fn into_buffer(self) -> Vec<u8> {
+        self.data
+    }
+}
+
 impl<'a> MemoryDecoder<'a> {
+    /// Takes the whole internal buffer, including the peek data and any bytes
+    /// zeroed out beyond the valid data, and replaces it with `buffer`, which
+    /// the next read fills.
+    pub fn take_block(&mut self, mut buffer: Vec<u8>) -> MemoryBlock {
+        buffer.resize(self.data.len(), 0);
+        std::mem::swap(self.data, &mut buffer);
+        MemoryBlock {
+            data: buffer,
+            used_normal_length: self.used_normal_length,
+            used_peek_length: self.used_peek_length,
+            byte_order: self.byte_order,
+        }
+    }
+
     /// Set a part of the internal buffer to zero.
     /// access to the whole buffer is possible, not just to the valid data.
     pub fn zero_out_buffer(&mut self, start: usize, end: usize) {
@@ -129,13 +169,14 @@ impl<'a> MemoryDecoder<'a> {
         &self.data[start..self.used_normal_length + self.used_peek_length]
     }
 
-    /// Returns a u8/u16/u32/u64 from the internal buffer at position `start`.
-    pub fn read_uint(&self, start: usize, byte_size: usize) -> u64 {
+    /// Returns a u8/u16/u32/u64/u128 from the internal buffer at position `start`.
+    pub fn read_uint(&self, start: usize, byte_size: usize) -> u128 {
         match byte_size {
-            1 => u64::from(self.data[start]),
-            2 => u64::from(self.byte_order.read_u16(&self.data[start..start + 2])),
-            4 => u64::from(self.byte_order.read_u32(&self.data[start..start + 4])),
-            8 => self.byte_order.read_u64(&self.data[start..start + 8]),
+            1 => u128::from(self.data[start]),
+            2 => u128::from(self.byte_order.read_u16(&self.data[start..start + 2])),
+            4 => u128::from(self.byte_order.read_u32(&self.data[start..start + 4])),
+            8 => u128::from(self.byte_order.read_u64(&self.data[start..start + 8])),
+            16 => self.byte_order.read_u128(&self.data[start..start + 16]),
             _ => panic!("Invalid byte_size: {byte_size}"),
         }
     }
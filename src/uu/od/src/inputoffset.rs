@@ -1,3 +1,5 @@
+use crate::color::{OFFSET, RESET};
+
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
 pub enum Radix {
     Decimal,
@@ -56,9 +58,13 @@ impl InputOffset {
 
     /// Prints the byte offset followed by a newline, or nothing at all if
     /// both `Radix::NoPrefix` was set and no label (--traditional) is used.
-    pub fn print_final_offset(&self) {
+    pub fn print_final_offset(&self, color: bool) {
         if self.radix != Radix::NoPrefix || self.label.is_some() {
-            println!("{}", self.format_byte_offset());
+            if color {
+                println!("{}{}{}", OFFSET, self.format_byte_offset(), RESET);
+            } else {
+                println!("{}", self.format_byte_offset());
+            }
         }
     }
 }
@@ -2,7 +2,7 @@
 
 use std::fs::File;
 use std::io;
-use std::io::BufReader;
+use std::io::{BufReader, Seek, SeekFrom};
 use std::vec::Vec;
 
 use uucore::display::Quotable;
@@ -19,6 +19,9 @@ pub enum InputSource<'a> {
 pub struct MultifileReader<'a> {
     ni: Vec<InputSource<'a>>,
     curr_file: Option<Box<dyn io::Read>>,
+    /// The current input instead of `curr_file` while it is a regular file that
+    /// has not been read from yet, so that `seek_forward` can seek in it
+    curr_regular_file: Option<BufReader<File>>,
     any_err: bool,
 }
 
@@ -31,6 +34,7 @@ impl<'b> MultifileReader<'b> {
         let mut mf = MultifileReader {
             ni: fnames,
             curr_file: None, // normally this means done; call next_file()
+            curr_regular_file: None,
             any_err: false,
         };
         mf.next_file();
@@ -38,10 +42,11 @@ impl<'b> MultifileReader<'b> {
     }
 
     fn next_file(&mut self) {
+        self.curr_file = None;
+        self.curr_regular_file = None;
         // loop retries with subsequent files if err - normally 'loops' once
         loop {
             if self.ni.is_empty() {
-                self.curr_file = None;
                 break;
             }
             match self.ni.remove(0) {
@@ -52,7 +57,11 @@ impl<'b> MultifileReader<'b> {
                 InputSource::FileName(fname) => {
                     match File::open(fname) {
                         Ok(f) => {
-                            self.curr_file = Some(Box::new(BufReader::new(f)));
+                            if f.metadata().map_or(false, |m| m.is_file() && m.len() > 0) {
+                                self.curr_regular_file = Some(BufReader::new(f));
+                            } else {
+                                self.curr_file = Some(Box::new(BufReader::new(f)));
+                            }
                             break;
                         }
                         Err(e) => {
@@ -72,6 +81,34 @@ impl<'b> MultifileReader<'b> {
             }
         }
     }
+
+    /// Skips up to `n` bytes without reading them, by seeking in or past regular
+    /// files at the start of the input. Must be called before any data is read.
+    ///
+    /// Returns the number of bytes that are left to skip, as the remaining input
+    /// can only be skipped by reading it.
+    pub fn seek_forward(&mut self, mut n: u64) -> u64 {
+        while n > 0 {
+            let file = match self.curr_regular_file {
+                Some(ref mut file) => file,
+                None => break,
+            };
+            let len = match file.get_ref().metadata() {
+                Ok(metadata) => metadata.len(),
+                Err(_) => break,
+            };
+            if n >= len {
+                n -= len;
+                self.next_file();
+            } else {
+                match file.seek(SeekFrom::Start(n)) {
+                    Ok(_) => n = 0,
+                    Err(_) => break,
+                }
+            }
+        }
+        n
+    }
 }
 
 impl<'b> io::Read for MultifileReader<'b> {
@@ -84,6 +121,9 @@ impl<'b> io::Read for MultifileReader<'b> {
         let mut xfrd = 0;
         // while buffer we are filling is not full.. May go through several files.
         'fillloop: while xfrd < buf.len() {
+            if let Some(file) = self.curr_regular_file.take() {
+                self.curr_file = Some(Box::new(file));
+            }
             match self.curr_file {
                 None => break,
                 Some(ref mut curr_file) => {
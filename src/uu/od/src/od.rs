@@ -9,6 +9,7 @@
 // spell-checker:ignore (ToDO) formatteriteminfo inputdecoder inputoffset mockstream nrofbytes partialreader odfunc multifile exitcode
 
 mod byteorder_io;
+mod color;
 mod formatteriteminfo;
 mod inputdecoder;
 mod inputoffset;
@@ -27,10 +28,13 @@ mod prn_int;
 
 use std::cmp;
 use std::fmt::Write;
+use std::fs;
+use std::io::Read;
 
 use crate::byteorder_io::*;
+use crate::color::{push_colored, visible_width, ByteClass, OFFSET, RESET};
 use crate::formatteriteminfo::*;
-use crate::inputdecoder::{InputDecoder, MemoryDecoder};
+use crate::inputdecoder::{InputDecoder, MemoryBlock, MemoryDecoder};
 use crate::inputoffset::{InputOffset, Radix};
 use crate::multifilereader::*;
 use crate::output_info::OutputInfo;
@@ -42,6 +46,7 @@ use crate::peekreader::*;
 use crate::prn_char::format_ascii_dump;
 use clap::ArgAction;
 use clap::{crate_version, parser::ValueSource, Arg, ArgMatches, Command};
+use rayon::prelude::*;
 use uucore::display::Quotable;
 use uucore::error::{UResult, USimpleError};
 use uucore::format_usage;
@@ -74,7 +79,10 @@ RADIX is one of o,d,x,n for octal, decimal, hexadecimal or none.
 
 BYTES is decimal by default, octal if prefixed with a "0", or hexadecimal if
 prefixed with "0x". The suffixes b, KB, K, MB, M, GB, G, will multiply the
-number with 512, 1000, 1024, 1000^2, 1024^2, 1000^3, 1024^3, 1000^2, 1024^2.
+number with 512, 1000, 1024, 1000^2, 1024^2, 1000^3, 1024^3, 1000^2, 1024^2,
+and so on for T, P and E. KiB, MiB, ... are the same as K, M, ... The
+suffixes can follow numbers in any radix, except that "b" is a hexadecimal
+digit, as in "0x1kB".
 
 OFFSET and LABEL are octal by default, hexadecimal if prefixed with "0x" or
 decimal if a "." suffix is added. The "b" suffix will multiply with 512.
@@ -82,6 +90,7 @@ decimal if a "." suffix is added. The "b" suffix will multiply with 512.
 TYPE contains one or more format specifications consisting of:
     a       for printable 7-bits ASCII
     c       for utf-8 characters or octal for undefined characters
+    U       like c, but with the code points of non-ASCII characters as U+XXXX
     d[SIZE] for signed decimal
     f[SIZE] for floating point
     o[SIZE] for octal
@@ -107,6 +116,8 @@ pub(crate) mod options {
     pub const OUTPUT_DUPLICATES: &str = "output-duplicates";
     pub const TRADITIONAL: &str = "traditional";
     pub const WIDTH: &str = "width";
+    pub const COLOR: &str = "color";
+    pub const DIFFABLE: &str = "diffable";
     pub const FILENAME: &str = "FILENAME";
 }
 
@@ -120,6 +131,8 @@ struct OdOptions {
     line_bytes: usize,
     output_duplicates: bool,
     radix: Radix,
+    string_min_length: Option<usize>,
+    color: bool,
 }
 
 impl OdOptions {
@@ -167,8 +180,14 @@ impl OdOptions {
 
         let formats = parse_format_flags(args).map_err(|e| USimpleError::new(1, e))?;
 
+        let auto_width = matches.value_source(options::WIDTH) == Some(ValueSource::CommandLine)
+            && matches
+                .get_one::<String>(options::WIDTH)
+                .map(|s| s.as_str())
+                == Some("auto");
         let mut line_bytes = match matches.get_one::<String>(options::WIDTH) {
             None => 16,
+            Some(_) if auto_width => 0, // depends on the offset radix, see below
             Some(s) => {
                 if matches.value_source(options::WIDTH) == Some(ValueSource::CommandLine) {
                     match parse_number_of_bytes(s) {
@@ -190,12 +209,17 @@ impl OdOptions {
         let min_bytes = formats.iter().fold(1, |max, next| {
             cmp::max(max, next.formatter_item_info.byte_size)
         });
-        if line_bytes == 0 || line_bytes % min_bytes != 0 {
+        if !auto_width && (line_bytes == 0 || line_bytes % min_bytes != 0) {
             show_warning!("invalid width {}; using {} instead", line_bytes, min_bytes);
             line_bytes = min_bytes;
         }
 
-        let output_duplicates = matches.get_flag(options::OUTPUT_DUPLICATES);
+        let diffable = matches.get_one::<String>(options::DIFFABLE);
+        if diffable.map(|s| s.as_str()) == Some("items") {
+            line_bytes = min_bytes;
+        }
+
+        let output_duplicates = matches.get_flag(options::OUTPUT_DUPLICATES) || diffable.is_some();
 
         let read_bytes = match matches.get_one::<String>(options::READ_BYTES) {
             None => None,
@@ -211,6 +235,7 @@ impl OdOptions {
         };
 
         let radix = match matches.get_one::<String>(options::ADDRESS_RADIX) {
+            _ if diffable.is_some() => Radix::NoPrefix,
             None => Radix::Octal,
             Some(s) => {
                 let st = s.as_bytes();
@@ -240,6 +265,46 @@ impl OdOptions {
             }
         };
 
+        if diffable.is_some() {
+            label = None;
+        }
+
+        if auto_width && diffable.is_none() {
+            let prefix_width = InputOffset::new(radix, 0, label)
+                .format_byte_offset()
+                .chars()
+                .count();
+            line_bytes = OutputInfo::fitting_line_bytes(&formats, terminal_width(), prefix_width);
+        }
+
+        let string_min_length = match matches.get_one::<String>(options::STRINGS) {
+            None => None,
+            Some(s) => match parse_number_of_bytes(s) {
+                Ok(n) => Some(
+                    usize::try_from(n)
+                        .map_err(|_| USimpleError::new(1, format!("‘{s}‘ is too large")))?,
+                ),
+                Err(e) => {
+                    return Err(USimpleError::new(
+                        1,
+                        format_error_message(&e, s, options::STRINGS),
+                    ))
+                }
+            },
+        };
+
+        let color = match matches.get_one::<String>(options::COLOR) {
+            None => matches.contains_id(options::COLOR),
+            Some(val) => match val.as_str() {
+                "" | "always" | "yes" | "force" => true,
+                "auto" | "tty" | "if-tty" => {
+                    atty::is(atty::Stream::Stdout)
+                        && std::env::var_os("NO_COLOR").map_or(true, |v| v.is_empty())
+                }
+                /* "never" | "no" | "none" | */ _ => false,
+            },
+        };
+
         Ok(Self {
             byte_order,
             skip_bytes,
@@ -250,10 +315,25 @@ impl OdOptions {
             line_bytes,
             output_duplicates,
             radix,
+            string_min_length,
+            color,
         })
     }
 }
 
+/// Returns the width of the terminal, or of `COLUMNS` if stdout is not a terminal
+fn terminal_width() -> usize {
+    const DEFAULT_TERM_WIDTH: usize = 80;
+
+    match terminal_size::terminal_size() {
+        Some((width, _)) => width.0.into(),
+        None => std::env::var("COLUMNS")
+            .ok()
+            .and_then(|columns| columns.parse().ok())
+            .unwrap_or(DEFAULT_TERM_WIDTH),
+    }
+}
+
 /// parses and validates command line parameters, prepares data structures,
 /// opens the input and calls `odfunc` to process the input.
 #[uucore::main]
@@ -274,6 +354,17 @@ pub fn uumain(args: impl uucore::Args) -> UResult<()> {
         od_options.skip_bytes,
         od_options.read_bytes,
     );
+
+    if let Some(min_length) = od_options.string_min_length {
+        return dump_strings(
+            &mut input_offset,
+            &mut input,
+            min_length,
+            od_options.read_bytes,
+            od_options.color,
+        );
+    }
+
     let mut input_decoder = InputDecoder::new(
         &mut input,
         od_options.line_bytes,
@@ -285,9 +376,22 @@ pub fn uumain(args: impl uucore::Args) -> UResult<()> {
         od_options.line_bytes,
         &od_options.formats[..],
         od_options.output_duplicates,
+        od_options.color,
     );
 
-    odfunc(&mut input_offset, &mut input_decoder, &output_info)
+    let batch_lines = if inputs_are_regular_files(&od_options.input_strings) {
+        BATCH_LINES
+    } else {
+        // Print each line as soon as it is read, as the next read may block.
+        1
+    };
+
+    odfunc(
+        &mut input_offset,
+        &mut input_decoder,
+        &output_info,
+        batch_lines,
+    )
 }
 
 pub fn uu_app() -> Command {
@@ -305,7 +409,7 @@ pub fn uu_app() -> Command {
             Arg::new(options::HELP)
                 .long(options::HELP)
                 .help("Print help information.")
-                .action(ArgAction::Help)
+                .action(ArgAction::Help),
         )
         .arg(
             Arg::new(options::ADDRESS_RADIX)
@@ -340,11 +444,12 @@ pub fn uu_app() -> Command {
                 .short('S')
                 .long(options::STRINGS)
                 .help(
-                    "NotImplemented: output strings of at least BYTES graphic chars. 3 is assumed when \
+                    "output strings of at least BYTES graphic chars. 3 is assumed when \
                      BYTES is not specified.",
                 )
                 .default_missing_value("3")
-                .value_name("BYTES"),
+                .value_name("BYTES")
+                .num_args(..=1),
         )
         .arg(
             Arg::new("a")
@@ -422,7 +527,7 @@ pub fn uu_app() -> Command {
             Arg::new("O")
                 .short('O')
                 .help("octal 4-byte units")
-                .action(ArgAction::SetTrue)
+                .action(ArgAction::SetTrue),
         )
         .arg(
             Arg::new("s")
@@ -482,12 +587,39 @@ pub fn uu_app() -> Command {
                 .long(options::WIDTH)
                 .help(
                     "output BYTES bytes per output line. 32 is implied when BYTES is not \
-                     specified.",
+                     specified. With 'auto', as many bytes as fit the terminal width.",
                 )
                 .default_missing_value("32")
                 .value_name("BYTES")
                 .num_args(..=1),
         )
+        .arg(
+            Arg::new(options::COLOR)
+                .long(options::COLOR)
+                .help(
+                    "color offsets, NUL bytes, printable ASCII and other bytes differently; \
+                     WHEN is 'always' (the default if omitted), 'auto' or 'never'",
+                )
+                .value_parser([
+                    "always", "yes", "force", "auto", "tty", "if-tty", "never", "no", "none",
+                ])
+                .value_name("WHEN")
+                .require_equals(true)
+                .num_args(0..=1),
+        )
+        .arg(
+            Arg::new(options::DIFFABLE)
+                .long(options::DIFFABLE)
+                .help(
+                    "output for comparing with diff: implies -An and -v; with \
+                     MODE 'items', print a single item per line",
+                )
+                .value_parser(["lines", "items"])
+                .default_missing_value("lines")
+                .value_name("MODE")
+                .require_equals(true)
+                .num_args(0..=1),
+        )
         .arg(
             Arg::new(options::TRADITIONAL)
                 .long(options::TRADITIONAL)
@@ -502,11 +634,83 @@ pub fn uu_app() -> Command {
         )
 }
 
-/// Loops through the input line by line, calling print_bytes to take care of the output.
+/// The number of lines read before they are formatted in parallel, if the
+/// input consists of regular files.
+const BATCH_LINES: usize = 4096;
+
+/// Whether all inputs are regular files, which can be read without waiting for
+/// more data.
+fn inputs_are_regular_files(input_strings: &[String]) -> bool {
+    input_strings.iter().all(|name| {
+        let path = match name.as_str() {
+            "-" if cfg!(unix) => "/dev/stdin",
+            "-" => return false,
+            name => name,
+        };
+        fs::metadata(path).map_or(false, |metadata| metadata.is_file())
+    })
+}
+
+/// A line of output, which is formatted after it was read
+enum OutputLine {
+    /// Marks one or more lines suppressed because they equal the previous line.
+    Duplicate,
+    Data {
+        prefix: String,
+        block: MemoryBlock,
+    },
+}
+
+/// Formats `line` and appends it to `output`.
+fn format_line(output: &mut String, line: &mut OutputLine, output_info: &OutputInfo) {
+    match line {
+        OutputLine::Duplicate => output.push_str("*\n"),
+        OutputLine::Data { prefix, block } => {
+            format_bytes(output, prefix, &block.decoder(), output_info);
+        }
+    }
+}
+
+/// Formats the lines of `batch` and prints them in order, on a thread pool if
+/// there are several. The buffers of the lines are moved to `spare_buffers`.
+fn print_batch(
+    batch: &mut Vec<OutputLine>,
+    spare_buffers: &mut Vec<Vec<u8>>,
+    output_info: &OutputInfo,
+) {
+    if let [line] = &mut batch[..] {
+        let mut output = String::new();
+        format_line(&mut output, line, output_info);
+        print!("{output}");
+    } else {
+        let chunks: Vec<String> = batch
+            .par_iter_mut()
+            .fold(String::new, |mut output, line| {
+                format_line(&mut output, line, output_info);
+                output
+            })
+            .collect();
+        for chunk in chunks {
+            print!("{chunk}");
+        }
+    }
+    spare_buffers.extend(batch.drain(..).filter_map(|line| match line {
+        OutputLine::Duplicate => None,
+        OutputLine::Data { block, .. } => Some(block.into_buffer()),
+    }));
+}
+
+/// Loops through the input line by line, and formats the lines to print in
+/// batches of `batch_lines`.
+///
+/// Whether a line is suppressed as a duplicate is decided while reading, as
+/// it only depends on the previous line, so batches can be formatted
+/// independently of each other.
 fn odfunc<I>(
     input_offset: &mut InputOffset,
     input_decoder: &mut InputDecoder<I>,
     output_info: &OutputInfo,
+    batch_lines: usize,
 ) -> UResult<()>
 where
     I: PeekRead + HasError,
@@ -514,6 +718,8 @@ where
     let mut duplicate_line = false;
     let mut previous_bytes: Vec<u8> = Vec::new();
     let line_bytes = output_info.byte_size_line;
+    let mut batch: Vec<OutputLine> = Vec::new();
+    let mut spare_buffers: Vec<Vec<u8>> = Vec::new();
 
     loop {
         // print each line data (or multi-format raster of several lines describing the same data).
@@ -523,7 +729,8 @@ where
                 let length = memory_decoder.length();
 
                 if length == 0 {
-                    input_offset.print_final_offset();
+                    print_batch(&mut batch, &mut spare_buffers, output_info);
+                    input_offset.print_final_offset(output_info.color);
                     break;
                 }
 
@@ -544,7 +751,7 @@ where
                 {
                     if !duplicate_line {
                         duplicate_line = true;
-                        println!("*");
+                        batch.push(OutputLine::Duplicate);
                     }
                 } else {
                     duplicate_line = false;
@@ -553,18 +760,21 @@ where
                         memory_decoder.clone_buffer(&mut previous_bytes);
                     }
 
-                    print_bytes(
-                        &input_offset.format_byte_offset(),
-                        &memory_decoder,
-                        output_info,
-                    );
+                    batch.push(OutputLine::Data {
+                        prefix: input_offset.format_byte_offset(),
+                        block: memory_decoder.take_block(spare_buffers.pop().unwrap_or_default()),
+                    });
                 }
 
                 input_offset.increase_position(length as u64);
+                if batch.len() == batch_lines {
+                    print_batch(&mut batch, &mut spare_buffers, output_info);
+                }
             }
             Err(e) => {
+                print_batch(&mut batch, &mut spare_buffers, output_info);
                 show_error!("{}", e);
-                input_offset.print_final_offset();
+                input_offset.print_final_offset(output_info.color);
                 return Err(1.into());
             }
         };
@@ -577,8 +787,81 @@ where
     }
 }
 
-/// Outputs a single line of input, into one or more lines human readable output.
-fn print_bytes(prefix: &str, input_decoder: &MemoryDecoder, output_info: &OutputInfo) {
+/// Prints every run of at least `min_length` printable ASCII characters that is
+/// terminated by a NUL byte, preceded by the offset of its first character.
+fn dump_strings<I>(
+    input_offset: &mut InputOffset,
+    input: &mut I,
+    min_length: usize,
+    read_bytes: Option<u64>,
+    color: bool,
+) -> UResult<()>
+where
+    I: Read + HasError,
+{
+    let mut buf = [0; 4096];
+    let mut string: Vec<u8> = Vec::new();
+    // position of the next byte, and of the byte `input_offset` points to
+    let mut pos: u64 = 0;
+    let mut offset_pos: u64 = 0;
+
+    // print `string`, which ends before the byte at `end`
+    let mut print_string = |string: &[u8], end: u64| {
+        let start = end - string.len() as u64;
+        input_offset.increase_position(start - offset_pos);
+        offset_pos = start;
+        let prefix = input_offset.format_byte_offset();
+        if !prefix.is_empty() && color {
+            print!("{OFFSET}{prefix}{RESET} ");
+        } else if !prefix.is_empty() {
+            print!("{prefix} ");
+        }
+        println!("{}", String::from_utf8_lossy(string));
+    };
+
+    loop {
+        let length = match input.read(&mut buf) {
+            Ok(0) => break,
+            Ok(n) => n,
+            Err(e) => {
+                show_error!("{}", e);
+                return Err(1.into());
+            }
+        };
+
+        for &byte in &buf[..length] {
+            pos += 1;
+            if byte.is_ascii_graphic() || byte == b' ' {
+                string.push(byte);
+            } else {
+                if byte == b'\0' && string.len() >= min_length {
+                    print_string(&string, pos - 1);
+                }
+                string.clear();
+            }
+        }
+    }
+
+    // a string cut off by the -N limit is printed, unlike one at the end of
+    // the input, as long as GNU would: if it is longer than the minimum
+    if read_bytes == Some(pos) && string.len() > min_length {
+        print_string(&string, pos);
+    }
+
+    if input.has_error() {
+        Err(1.into())
+    } else {
+        Ok(())
+    }
+}
+
+/// Formats a single line of input, into one or more lines human readable output.
+fn format_bytes(
+    output: &mut String,
+    prefix: &str,
+    input_decoder: &MemoryDecoder,
+    output_info: &OutputInfo,
+) {
     let mut first = true; // First line of a multi-format raster.
     for f in output_info.spaced_formatters_iter() {
         let mut output_text = String::new();
@@ -593,18 +876,22 @@ fn print_bytes(prefix: &str, input_decoder: &MemoryDecoder, output_info: &Output
             )
             .unwrap();
 
-            match f.formatter_item_info.formatter {
+            let item = match f.formatter_item_info.formatter {
                 FormatWriter::IntWriter(func) => {
-                    let p = input_decoder.read_uint(b, f.formatter_item_info.byte_size);
-                    output_text.push_str(&func(p));
+                    func(input_decoder.read_uint(b, f.formatter_item_info.byte_size))
                 }
                 FormatWriter::FloatWriter(func) => {
-                    let p = input_decoder.read_float(b, f.formatter_item_info.byte_size);
-                    output_text.push_str(&func(p));
-                }
-                FormatWriter::MultibyteWriter(func) => {
-                    output_text.push_str(&func(input_decoder.get_full_buffer(b)));
+                    func(input_decoder.read_float(b, f.formatter_item_info.byte_size))
                 }
+                FormatWriter::MultibyteWriter(func) => func(input_decoder.get_full_buffer(b)),
+            };
+
+            if output_info.color {
+                let bytes = input_decoder.get_buffer(b);
+                let bytes = &bytes[..bytes.len().min(f.formatter_item_info.byte_size)];
+                push_colored(&mut output_text, &item, ByteClass::of_item(bytes).color());
+            } else {
+                output_text.push_str(&item);
             }
 
             b += f.formatter_item_info.byte_size;
@@ -613,7 +900,7 @@ fn print_bytes(prefix: &str, input_decoder: &MemoryDecoder, output_info: &Output
         if f.add_ascii_dump {
             let missing_spacing = output_info
                 .print_width_line
-                .saturating_sub(output_text.chars().count());
+                .saturating_sub(visible_width(&output_text));
             write!(
                 output_text,
                 "{:>width$}  {}",
@@ -624,16 +911,20 @@ fn print_bytes(prefix: &str, input_decoder: &MemoryDecoder, output_info: &Output
             .unwrap();
         }
 
-        if first {
-            print!("{prefix}"); // print offset
-                                // if printing in multiple formats offset is printed only once
+        if first && output_info.color && !prefix.is_empty() {
+            write!(output, "{OFFSET}{prefix}{RESET}").unwrap();
+            first = false;
+        } else if first {
+            output.push_str(prefix); // print offset
+                                     // if printing in multiple formats offset is printed only once
             first = false;
         } else {
             // this takes the space of the file offset on subsequent
             // lines of multi-format rasters.
-            print!("{:>width$}", "", width = prefix.chars().count());
+            write!(output, "{:>width$}", "", width = prefix.chars().count()).unwrap();
         }
-        println!("{output_text}");
+        output.push_str(&output_text);
+        output.push('\n');
     }
 }
 
@@ -655,7 +946,8 @@ fn open_input_peek_reader(
         })
         .collect::<Vec<_>>();
 
-    let mf = MultifileReader::new(inputs);
+    let mut mf = MultifileReader::new(inputs);
+    let skip_bytes = mf.seek_forward(skip_bytes);
     let pr = PartialReader::new(mf, skip_bytes, read_bytes);
     PeekReader::new(pr)
 }
@@ -7,7 +7,7 @@ use crate::formatteriteminfo::FormatterItemInfo;
 use crate::parse_formats::ParsedFormatterItemInfo;
 
 /// Size in bytes of the max datatype. ie set to 16 for 128-bit numbers.
-const MAX_BYTES_PER_UNIT: usize = 8;
+const MAX_BYTES_PER_UNIT: usize = 16;
 
 /// Contains information to output single output line in human readable form
 pub struct SpacedFormatterItemInfo {
@@ -41,6 +41,8 @@ pub struct OutputInfo {
     /// determines if duplicate output lines should be printed, or
     /// skipped with a "*" showing one or more skipped lines.
     pub output_duplicates: bool,
+    /// if set offsets and items are colored by the kind of bytes they show
+    pub color: bool,
 }
 
 impl OutputInfo {
@@ -49,12 +51,9 @@ impl OutputInfo {
         self.spaced_formatters.iter()
     }
 
-    /// Creates a new `OutputInfo` based on the parameters
-    pub fn new(
-        line_bytes: usize,
-        formats: &[ParsedFormatterItemInfo],
-        output_duplicates: bool,
-    ) -> Self {
+    /// Returns the size of a block, the largest datatype in `formats`, and
+    /// the width of a block, the widest format for that number of bytes.
+    fn block_size_and_width(formats: &[ParsedFormatterItemInfo]) -> (usize, usize) {
         let byte_size_block = formats.iter().fold(1, |max, next| {
             cmp::max(max, next.formatter_item_info.byte_size)
         });
@@ -65,6 +64,43 @@ impl OutputInfo {
                     * (byte_size_block / next.formatter_item_info.byte_size),
             )
         });
+        (byte_size_block, print_width_block)
+    }
+
+    /// Returns the largest number of bytes per line, such that lines with an
+    /// offset of `prefix_width` characters fit in `terminal_width` columns.
+    /// At least one block is always printed.
+    pub fn fitting_line_bytes(
+        formats: &[ParsedFormatterItemInfo],
+        terminal_width: usize,
+        prefix_width: usize,
+    ) -> usize {
+        let (byte_size_block, print_width_block) = Self::block_size_and_width(formats);
+        let ascii_dump = formats.iter().any(|f| f.add_ascii_dump);
+
+        let mut blocks = 1;
+        loop {
+            let line_bytes = (blocks + 1) * byte_size_block;
+            let mut width = prefix_width + (blocks + 1) * print_width_block;
+            if ascii_dump {
+                // two spaces and the bytes between '>' and '<'
+                width += 4 + line_bytes;
+            }
+            if width > terminal_width {
+                return blocks * byte_size_block;
+            }
+            blocks += 1;
+        }
+    }
+
+    /// Creates a new `OutputInfo` based on the parameters
+    pub fn new(
+        line_bytes: usize,
+        formats: &[ParsedFormatterItemInfo],
+        output_duplicates: bool,
+        color: bool,
+    ) -> Self {
+        let (byte_size_block, print_width_block) = Self::block_size_and_width(formats);
         let print_width_line = print_width_block * (line_bytes / byte_size_block);
 
         let spaced_formatters =
@@ -77,6 +113,7 @@ impl OutputInfo {
             print_width_block,
             spaced_formatters,
             output_duplicates,
+            color,
         }
     }
 
@@ -187,6 +224,21 @@ impl TypeSizeInfo for ParsedFormatterItemInfo {
     }
 }
 
+#[test]
+fn test_fitting_line_bytes() {
+    use crate::prn_int::{FORMAT_ITEM_HEX8, FORMAT_ITEM_OCT16};
+
+    let oct16 = [ParsedFormatterItemInfo::new(FORMAT_ITEM_OCT16, false)];
+    // 7 characters of offset and 7 for every 2 bytes
+    assert_eq!(20, OutputInfo::fitting_line_bytes(&oct16, 80, 7));
+    assert_eq!(2, OutputInfo::fitting_line_bytes(&oct16, 10, 7));
+
+    let hex8_dump = [ParsedFormatterItemInfo::new(FORMAT_ITEM_HEX8, true)];
+    // 3 characters for every byte, and 1 in the ascii dump
+    assert_eq!(16, OutputInfo::fitting_line_bytes(&hex8_dump, 75, 7));
+    assert_eq!(15, OutputInfo::fitting_line_bytes(&hex8_dump, 74, 7));
+}
+
 #[cfg(test)]
 struct TypeInfo {
     byte_size: usize,
@@ -220,7 +272,7 @@ fn test_calculate_alignment() {
             },
             8,
             23
-        )
+        )[..8]
     );
     // the second line a single space at the start of the block:
     assert_eq!(
@@ -232,7 +284,7 @@ fn test_calculate_alignment() {
             },
             8,
             23
-        )
+        )[..8]
     );
     // the third line two spaces at pos 0, and 1 space at pos 4:
     assert_eq!(
@@ -244,7 +296,7 @@ fn test_calculate_alignment() {
             },
             8,
             23
-        )
+        )[..8]
     );
 
     // For this example `byte_size_block` is 8 and 'print_width_block' is 28:
@@ -262,7 +314,7 @@ fn test_calculate_alignment() {
             },
             8,
             28
-        )
+        )[..8]
     );
     assert_eq!(
         [5, 0, 0, 0, 5, 0, 0, 0],
@@ -273,7 +325,7 @@ fn test_calculate_alignment() {
             },
             8,
             28
-        )
+        )[..8]
     );
     assert_eq!(
         [0, 0, 0, 0, 0, 0, 0, 0],
@@ -284,7 +336,7 @@ fn test_calculate_alignment() {
             },
             8,
             28
-        )
+        )[..8]
     );
     assert_eq!(
         [1, 0, 1, 0, 1, 0, 1, 0],
@@ -295,7 +347,7 @@ fn test_calculate_alignment() {
             },
             8,
             28
-        )
+        )[..8]
     );
 
     // 9 tests where 8 .. 16 spaces are spread across 8 positions
@@ -308,7 +360,7 @@ fn test_calculate_alignment() {
             },
             8,
             16 + 8
-        )
+        )[..8]
     );
     assert_eq!(
         [2, 1, 1, 1, 1, 1, 1, 1],
@@ -319,7 +371,7 @@ fn test_calculate_alignment() {
             },
             8,
             16 + 9
-        )
+        )[..8]
     );
     assert_eq!(
         [2, 1, 1, 1, 2, 1, 1, 1],
@@ -330,7 +382,7 @@ fn test_calculate_alignment() {
             },
             8,
             16 + 10
-        )
+        )[..8]
     );
     assert_eq!(
         [3, 1, 1, 1, 2, 1, 1, 1],
@@ -341,7 +393,7 @@ fn test_calculate_alignment() {
             },
             8,
             16 + 11
-        )
+        )[..8]
     );
     assert_eq!(
         [2, 1, 2, 1, 2, 1, 2, 1],
@@ -352,7 +404,7 @@ fn test_calculate_alignment() {
             },
             8,
             16 + 12
-        )
+        )[..8]
     );
     assert_eq!(
         [3, 1, 2, 1, 2, 1, 2, 1],
@@ -363,7 +415,7 @@ fn test_calculate_alignment() {
             },
             8,
             16 + 13
-        )
+        )[..8]
     );
     assert_eq!(
         [3, 1, 2, 1, 3, 1, 2, 1],
@@ -374,7 +426,7 @@ fn test_calculate_alignment() {
             },
             8,
             16 + 14
-        )
+        )[..8]
     );
     assert_eq!(
         [4, 1, 2, 1, 3, 1, 2, 1],
@@ -385,7 +437,7 @@ fn test_calculate_alignment() {
             },
             8,
             16 + 15
-        )
+        )[..8]
     );
     assert_eq!(
         [2, 2, 2, 2, 2, 2, 2, 2],
@@ -396,7 +448,7 @@ fn test_calculate_alignment() {
             },
             8,
             16 + 16
-        )
+        )[..8]
     );
 
     // 4 tests where 15 spaces are spread across 8, 4, 2 or 1 position(s)
@@ -409,7 +461,7 @@ fn test_calculate_alignment() {
             },
             8,
             16 + 15
-        )
+        )[..8]
     );
     assert_eq!(
         [5, 0, 3, 0, 4, 0, 3, 0],
@@ -420,7 +472,7 @@ fn test_calculate_alignment() {
             },
             8,
             16 + 15
-        )
+        )[..8]
     );
     assert_eq!(
         [8, 0, 0, 0, 7, 0, 0, 0],
@@ -431,7 +483,7 @@ fn test_calculate_alignment() {
             },
             8,
             16 + 15
-        )
+        )[..8]
     );
     assert_eq!(
         [15, 0, 0, 0, 0, 0, 0, 0],
@@ -442,6 +494,34 @@ fn test_calculate_alignment() {
             },
             8,
             16 + 15
+        )[..8]
+    );
+}
+
+#[test]
+fn test_calculate_alignment_128_bits() {
+    // ffffffffffffffffffffffffffffffff is one space narrower than a block of
+    // two ffffffffffffffff
+    assert_eq!(
+        [1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0],
+        OutputInfo::calculate_alignment(
+            &TypeInfo {
+                byte_size: 16,
+                print_width: 33,
+            },
+            16,
+            34
+        )
+    );
+    assert_eq!(
+        [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0],
+        OutputInfo::calculate_alignment(
+            &TypeInfo {
+                byte_size: 8,
+                print_width: 17,
+            },
+            16,
+            34
         )
     );
 }
@@ -52,26 +52,31 @@ fn od_format_type(type_char: FormatType, byte_size: u8) -> Option<FormatterItemI
     match (type_char, byte_size) {
         (FormatType::Ascii, _) => Some(FORMAT_ITEM_A),
         (FormatType::Char, _) => Some(FORMAT_ITEM_C),
+        (FormatType::Unicode, _) => Some(FORMAT_ITEM_UNICODE),
 
         (FormatType::DecimalInt, 1) => Some(FORMAT_ITEM_DEC8S),
         (FormatType::DecimalInt, 2) => Some(FORMAT_ITEM_DEC16S),
         (FormatType::DecimalInt, 0 | 4) => Some(FORMAT_ITEM_DEC32S),
         (FormatType::DecimalInt, 8) => Some(FORMAT_ITEM_DEC64S),
+        (FormatType::DecimalInt, 16) => Some(FORMAT_ITEM_DEC128S),
 
         (FormatType::OctalInt, 1) => Some(FORMAT_ITEM_OCT8),
         (FormatType::OctalInt, 2) => Some(FORMAT_ITEM_OCT16),
         (FormatType::OctalInt, 0 | 4) => Some(FORMAT_ITEM_OCT32),
         (FormatType::OctalInt, 8) => Some(FORMAT_ITEM_OCT64),
+        (FormatType::OctalInt, 16) => Some(FORMAT_ITEM_OCT128),
 
         (FormatType::UnsignedInt, 1) => Some(FORMAT_ITEM_DEC8U),
         (FormatType::UnsignedInt, 2) => Some(FORMAT_ITEM_DEC16U),
         (FormatType::UnsignedInt, 0 | 4) => Some(FORMAT_ITEM_DEC32U),
         (FormatType::UnsignedInt, 8) => Some(FORMAT_ITEM_DEC64U),
+        (FormatType::UnsignedInt, 16) => Some(FORMAT_ITEM_DEC128U),
 
         (FormatType::HexadecimalInt, 1) => Some(FORMAT_ITEM_HEX8),
         (FormatType::HexadecimalInt, 2) => Some(FORMAT_ITEM_HEX16),
         (FormatType::HexadecimalInt, 0 | 4) => Some(FORMAT_ITEM_HEX32),
         (FormatType::HexadecimalInt, 8) => Some(FORMAT_ITEM_HEX64),
+        (FormatType::HexadecimalInt, 16) => Some(FORMAT_ITEM_HEX128),
 
         (FormatType::Float, 2) => Some(FORMAT_ITEM_F16),
         (FormatType::Float, 0 | 4) => Some(FORMAT_ITEM_F32),
@@ -159,6 +164,7 @@ pub fn parse_format_flags(args: &[String]) -> Result<Vec<ParsedFormatterItemInfo
 enum FormatType {
     Ascii,
     Char,
+    Unicode,
     DecimalInt,
     OctalInt,
     UnsignedInt,
@@ -177,6 +183,7 @@ fn format_type(ch: char) -> Option<FormatType> {
     match ch {
         'a' => Some(FormatType::Ascii),
         'c' => Some(FormatType::Char),
+        'U' => Some(FormatType::Unicode),
         'd' => Some(FormatType::DecimalInt),
         'o' => Some(FormatType::OctalInt),
         'u' => Some(FormatType::UnsignedInt),
@@ -188,7 +195,7 @@ fn format_type(ch: char) -> Option<FormatType> {
 
 fn format_type_category(t: FormatType) -> FormatTypeCategory {
     match t {
-        FormatType::Ascii | FormatType::Char => FormatTypeCategory::Char,
+        FormatType::Ascii | FormatType::Char | FormatType::Unicode => FormatTypeCategory::Char,
         FormatType::DecimalInt
         | FormatType::OctalInt
         | FormatType::UnsignedInt
@@ -428,6 +435,19 @@ fn test_long_format_d() {
     );
 }
 
+#[test]
+fn test_long_format_128_bits() {
+    assert_eq!(
+        parse_format_flags_str(&["od", "--format=d16", "-to16", "-tu16x16"]).unwrap(),
+        vec![
+            FORMAT_ITEM_DEC128S,
+            FORMAT_ITEM_OCT128,
+            FORMAT_ITEM_DEC128U,
+            FORMAT_ITEM_HEX128
+        ]
+    );
+}
+
 #[test]
 fn test_long_format_d_default() {
     assert_eq!(
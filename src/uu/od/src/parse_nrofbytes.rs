@@ -1,77 +1,52 @@
+use std::num::IntErrorKind;
+
+use uucore::display::Quotable;
 use uucore::parse_size::{parse_size, ParseSizeError};
 
+/// Parses a number of bytes, which is decimal by default, hexadecimal if prefixed
+/// with "0x" or octal if prefixed with "0", followed by an optional size suffix.
+///
+/// The suffixes are the same for all radixes, see [`parse_size`]. As "b" and
+/// "B" are hexadecimal digits, they can only be part of the suffix of a
+/// hexadecimal number after another letter, like in "0x1kB".
 pub fn parse_number_of_bytes(s: &str) -> Result<u64, ParseSizeError> {
-    let mut start = 0;
-    let mut len = s.len();
-    let mut radix = 16;
-    let mut multiply = 1;
-
-    if s.starts_with("0x") || s.starts_with("0X") {
-        start = 2;
+    let (digits, radix) = if let Some(hex) = s.strip_prefix("0x").or_else(|| s.strip_prefix("0X")) {
+        (hex, 16)
     } else if s.starts_with('0') {
-        radix = 8;
+        (s, 8)
     } else {
-        return parse_size(&s[start..]);
-    }
+        return parse_size(s);
+    };
 
-    let mut ends_with = s.chars().rev();
-    match ends_with.next() {
-        Some('b') if radix != 16 => {
-            multiply = 512;
-            len -= 1;
-        }
-        Some('k' | 'K') => {
-            multiply = 1024;
-            len -= 1;
-        }
-        Some('m' | 'M') => {
-            multiply = 1024 * 1024;
-            len -= 1;
-        }
-        Some('G') => {
-            multiply = 1024 * 1024 * 1024;
-            len -= 1;
-        }
-        #[cfg(target_pointer_width = "64")]
-        Some('T') => {
-            multiply = 1024 * 1024 * 1024 * 1024;
-            len -= 1;
-        }
-        #[cfg(target_pointer_width = "64")]
-        Some('P') => {
-            multiply = 1024 * 1024 * 1024 * 1024 * 1024;
-            len -= 1;
-        }
-        #[cfg(target_pointer_width = "64")]
-        Some('E') => {
-            multiply = 1024 * 1024 * 1024 * 1024 * 1024 * 1024;
-            len -= 1;
-        }
-        Some('B') if radix != 16 => {
-            len -= 2;
-            multiply = match ends_with.next() {
-                Some('k' | 'K') => 1000,
-                Some('m' | 'M') => 1000 * 1000,
-                Some('G') => 1000 * 1000 * 1000,
-                #[cfg(target_pointer_width = "64")]
-                Some('T') => 1000 * 1000 * 1000 * 1000,
-                #[cfg(target_pointer_width = "64")]
-                Some('P') => 1000 * 1000 * 1000 * 1000 * 1000,
-                #[cfg(target_pointer_width = "64")]
-                Some('E') => 1000 * 1000 * 1000 * 1000 * 1000 * 1000,
-                _ => return Err(ParseSizeError::ParseFailure(s.to_string())),
-            }
-        }
-        _ => {}
+    let (digits, suffix) = digits.split_at(
+        digits
+            .find(|c: char| !c.is_digit(radix))
+            .unwrap_or(digits.len()),
+    );
+    if digits.is_empty() {
+        return Err(ParseSizeError::ParseFailure(s.quote().to_string()));
     }
 
-    let factor = match u64::from_str_radix(&s[start..len], radix) {
-        Ok(f) => f,
-        Err(e) => return Err(ParseSizeError::ParseFailure(e.to_string())),
+    let number = u64::from_str_radix(digits, radix).map_err(|e| match e.kind() {
+        IntErrorKind::PosOverflow => ParseSizeError::SizeTooBig(s.quote().to_string()),
+        _ => ParseSizeError::ParseFailure(s.quote().to_string()),
+    })?;
+    let factor = if suffix.is_empty() {
+        1
+    } else if !suffix.chars().all(|c| c.is_ascii_alphabetic()) {
+        return Err(ParseSizeError::InvalidSuffix(s.quote().to_string()));
+    } else {
+        // the suffix is validated the same way as for decimal numbers
+        parse_size(&format!("1{suffix}")).map_err(|e| match e {
+            ParseSizeError::InvalidSuffix(_) | ParseSizeError::ParseFailure(_) => {
+                ParseSizeError::InvalidSuffix(s.quote().to_string())
+            }
+            ParseSizeError::SizeTooBig(_) => ParseSizeError::SizeTooBig(s.quote().to_string()),
+        })?
     };
-    factor
-        .checked_mul(multiply)
-        .ok_or_else(|| ParseSizeError::SizeTooBig(s.to_string()))
+    number
+        .checked_mul(factor)
+        .ok_or_else(|| ParseSizeError::SizeTooBig(s.quote().to_string()))
 }
 
 #[test]
@@ -88,4 +63,47 @@ fn test_parse_number_of_bytes() {
     assert_eq!(27, parse_number_of_bytes("0x1b").unwrap());
     assert_eq!(16 * 1024, parse_number_of_bytes("0x10k").unwrap());
     assert_eq!(16 * 1_048_576, parse_number_of_bytes("0x10m").unwrap());
+
+    assert_eq!(0x10b, parse_number_of_bytes("0x10b").unwrap());
+
+    // suffixes after hex and octal numbers
+    assert_eq!(1000, parse_number_of_bytes("0x1kB").unwrap());
+    assert_eq!(1000, parse_number_of_bytes("0X1KB").unwrap());
+    assert_eq!(16 * 1024, parse_number_of_bytes("0x10KiB").unwrap());
+    assert_eq!(2 * 1_000_000, parse_number_of_bytes("0x2MB").unwrap());
+    assert_eq!(2 * 1_048_576, parse_number_of_bytes("0x2MiB").unwrap());
+    assert_eq!(8 * 1000, parse_number_of_bytes("010kB").unwrap());
+    assert_eq!(8 * 1024, parse_number_of_bytes("010KiB").unwrap());
+    assert_eq!(
+        8 * 1024 * 1024 * 1024,
+        parse_number_of_bytes("010G").unwrap()
+    );
+}
+
+#[test]
+fn test_parse_number_of_bytes_errors() {
+    assert!(matches!(
+        parse_number_of_bytes("0x"),
+        Err(ParseSizeError::ParseFailure(_))
+    ));
+    assert!(matches!(
+        parse_number_of_bytes("0x1kb"),
+        Err(ParseSizeError::InvalidSuffix(_))
+    ));
+    assert!(matches!(
+        parse_number_of_bytes("09"),
+        Err(ParseSizeError::InvalidSuffix(_))
+    ));
+    assert!(matches!(
+        parse_number_of_bytes("0x1Y"),
+        Err(ParseSizeError::SizeTooBig(_))
+    ));
+    assert!(matches!(
+        parse_number_of_bytes("0x10000000000000000"),
+        Err(ParseSizeError::SizeTooBig(_))
+    ));
+    assert!(matches!(
+        parse_number_of_bytes("0xffffffffffffffffk"),
+        Err(ParseSizeError::SizeTooBig(_))
+    ));
 }
@@ -14,6 +14,12 @@ pub static FORMAT_ITEM_C: FormatterItemInfo = FormatterItemInfo {
     formatter: FormatWriter::MultibyteWriter(format_item_c),
 };
 
+pub static FORMAT_ITEM_UNICODE: FormatterItemInfo = FormatterItemInfo {
+    byte_size: 1,
+    print_width: 9,
+    formatter: FormatWriter::MultibyteWriter(format_item_unicode),
+};
+
 static A_CHARS: [&str; 128] = [
     "nul", "soh", "stx", "etx", "eot", "enq", "ack", "bel", "bs", "ht", "nl", "vt", "ff", "cr",
     "so", "si", "dle", "dc1", "dc2", "dc3", "dc4", "nak", "syn", "etb", "can", "em", "sub", "esc",
@@ -25,7 +31,7 @@ static A_CHARS: [&str; 128] = [
     "z", "{", "|", "}", "~", "del",
 ];
 
-fn format_item_a(p: u64) -> String {
+fn format_item_a(p: u128) -> String {
     // item-bytes == 1
     let b = (p & 0x7f) as u8;
     format!("{:>4}", A_CHARS.get(b as usize).unwrap_or(&"??"))
@@ -78,6 +84,45 @@ fn format_item_c(bytes: &[u8]) -> String {
     }
 }
 
+/// Returns the utf-8 character starting at the first byte of `bytes`, if it
+/// is the lead byte of a complete multi-octet sequence
+fn utf8_sequence(bytes: &[u8]) -> Option<&str> {
+    let b = bytes[0];
+    let len = if (b & 0xe0) == 0xc0 {
+        2
+    } else if (b & 0xf0) == 0xe0 {
+        3
+    } else if (b & 0xf8) == 0xf0 {
+        4
+    } else {
+        return None;
+    };
+    bytes.get(0..len).and_then(|s| from_utf8(s).ok())
+}
+
+/// Like `format_item_c`, but shows the code point of non-ASCII characters
+/// instead of the character itself
+fn format_item_unicode(bytes: &[u8]) -> String {
+    // item-bytes == 1
+    let b = bytes[0];
+
+    if b & 0x80 == 0x00 {
+        match C_CHARS.get(b as usize) {
+            Some(s) => format!("{s:>9}"),
+            None => format!("{b:>9}"),
+        }
+    } else if (b & 0xc0) == 0x80 {
+        // second or subsequent octet of an utf-8 sequence
+        String::from("       **")
+    } else {
+        match utf8_sequence(bytes).and_then(|s| s.chars().next()) {
+            Some(c) => format!("{:>9}", format!("U+{:04X}", c as u32)),
+            // invalid utf-8
+            None => format!("      {b:03o}"),
+        }
+    }
+}
+
 pub fn format_ascii_dump(bytes: &[u8]) -> String {
     let mut result = String::new();
 
@@ -145,6 +190,24 @@ fn test_format_item_c() {
     assert_eq!(" 377", format_item_c(&[0xff])); // invalid utf-8
 }
 
+#[test]
+fn test_format_item_unicode() {
+    assert_eq!("       \\0", format_item_unicode(&[0x00]));
+    assert_eq!("       \\n", format_item_unicode(&[0x0a]));
+    assert_eq!("        A", format_item_unicode(&[0x41, 0x21]));
+    assert_eq!("      177", format_item_unicode(&[0x7f]));
+
+    assert_eq!("       **", format_item_unicode(&[0x9f]));
+    assert_eq!("   U+00DF", format_item_unicode(&[0xc3, 0x9f]));
+    assert_eq!("   U+1000", format_item_unicode(&[0xe1, 0x80, 0x80, 0x21]));
+    assert_eq!("  U+1F496", format_item_unicode(&[0xf0, 0x9f, 0x92, 0x96]));
+    assert_eq!(" U+10FFFD", format_item_unicode(&[0xf4, 0x8f, 0xbf, 0xbd]));
+
+    assert_eq!("      300", format_item_unicode(&[0xc0, 0x80])); // invalid utf-8 (UTF-8 null)
+    assert_eq!("      360", format_item_unicode(&[0xf0, 0x9f, 0x92])); // invalid utf-8 (missing octet)
+    assert_eq!("      377", format_item_unicode(&[0xff])); // invalid utf-8
+}
+
 #[test]
 fn test_format_ascii_dump() {
     assert_eq!(">.<", format_ascii_dump(&[0x00]));
@@ -26,7 +26,7 @@ macro_rules! DEC {
 /// human-readable format. `$format_str` is one of OCT, HEX or DEC
 macro_rules! int_writer_unsigned {
     ($NAME:ident, $byte_size:expr, $print_width:expr, $function:ident, $format_str:expr) => {
-        fn $function(p: u64) -> String {
+        fn $function(p: u128) -> String {
             format!($format_str, p, width = $print_width - 1)
         }
 
@@ -45,7 +45,7 @@ macro_rules! int_writer_unsigned {
 /// human-readable format. `$format_str` should be DEC
 macro_rules! int_writer_signed {
     ($NAME:ident, $byte_size:expr, $print_width:expr, $function:ident, $format_str:expr) => {
-        fn $function(p: u64) -> String {
+        fn $function(p: u128) -> String {
             let s = sign_extend(p, $byte_size);
             format!($format_str, s, width = $print_width - 1)
         }
@@ -58,64 +58,68 @@ macro_rules! int_writer_signed {
     };
 }
 
-/// Extends a signed number in `item` of `item_bytes` bytes into a (signed) i64
-fn sign_extend(item: u64, item_bytes: usize) -> i64 {
-    let shift = 64 - item_bytes * 8;
-    (item << shift) as i64 >> shift
+/// Extends a signed number in `item` of `item_bytes` bytes into a (signed) i128
+fn sign_extend(item: u128, item_bytes: usize) -> i128 {
+    let shift = 128 - item_bytes * 8;
+    (item << shift) as i128 >> shift
 }
 
 int_writer_unsigned!(FORMAT_ITEM_OCT8, 1, 4, format_item_oct8, OCT!()); // max: 377
 int_writer_unsigned!(FORMAT_ITEM_OCT16, 2, 7, format_item_oct16, OCT!()); // max: 177777
 int_writer_unsigned!(FORMAT_ITEM_OCT32, 4, 12, format_item_oct32, OCT!()); // max: 37777777777
 int_writer_unsigned!(FORMAT_ITEM_OCT64, 8, 23, format_item_oct64, OCT!()); // max: 1777777777777777777777
+int_writer_unsigned!(FORMAT_ITEM_OCT128, 16, 44, format_item_oct128, OCT!()); // max: 3777777777777777777777777777777777777777777
 
 int_writer_unsigned!(FORMAT_ITEM_HEX8, 1, 3, format_item_hex8, HEX!()); // max: ff
 int_writer_unsigned!(FORMAT_ITEM_HEX16, 2, 5, format_item_hex16, HEX!()); // max: ffff
 int_writer_unsigned!(FORMAT_ITEM_HEX32, 4, 9, format_item_hex32, HEX!()); // max: ffffffff
 int_writer_unsigned!(FORMAT_ITEM_HEX64, 8, 17, format_item_hex64, HEX!()); // max: ffffffffffffffff
+int_writer_unsigned!(FORMAT_ITEM_HEX128, 16, 33, format_item_hex128, HEX!()); // max: ffffffffffffffffffffffffffffffff
 
 int_writer_unsigned!(FORMAT_ITEM_DEC8U, 1, 4, format_item_dec_u8, DEC!()); // max: 255
 int_writer_unsigned!(FORMAT_ITEM_DEC16U, 2, 6, format_item_dec_u16, DEC!()); // max: 65535
 int_writer_unsigned!(FORMAT_ITEM_DEC32U, 4, 11, format_item_dec_u32, DEC!()); // max: 4294967295
 int_writer_unsigned!(FORMAT_ITEM_DEC64U, 8, 21, format_item_dec_u64, DEC!()); // max: 18446744073709551615
+int_writer_unsigned!(FORMAT_ITEM_DEC128U, 16, 40, format_item_dec_u128, DEC!()); // max: 340282366920938463463374607431768211455
 
 int_writer_signed!(FORMAT_ITEM_DEC8S, 1, 5, format_item_dec_s8, DEC!()); // max: -128
 int_writer_signed!(FORMAT_ITEM_DEC16S, 2, 7, format_item_dec_s16, DEC!()); // max: -32768
 int_writer_signed!(FORMAT_ITEM_DEC32S, 4, 12, format_item_dec_s32, DEC!()); // max: -2147483648
 int_writer_signed!(FORMAT_ITEM_DEC64S, 8, 21, format_item_dec_s64, DEC!()); // max: -9223372036854775808
+int_writer_signed!(FORMAT_ITEM_DEC128S, 16, 41, format_item_dec_s128, DEC!()); // max: -170141183460469231731687303715884105728
 
 #[test]
 fn test_sign_extend() {
     assert_eq!(
-        0xffff_ffff_ffff_ff80u64 as i64,
+        i128::from(0xffff_ffff_ffff_ff80u64 as i64),
         sign_extend(0x0000_0000_0000_0080, 1)
     );
     assert_eq!(
-        0xffff_ffff_ffff_8000u64 as i64,
+        i128::from(0xffff_ffff_ffff_8000u64 as i64),
         sign_extend(0x0000_0000_0000_8000, 2)
     );
     assert_eq!(
-        0xffff_ffff_ff80_0000u64 as i64,
+        i128::from(0xffff_ffff_ff80_0000u64 as i64),
         sign_extend(0x0000_0000_0080_0000, 3)
     );
     assert_eq!(
-        0xffff_ffff_8000_0000u64 as i64,
+        i128::from(0xffff_ffff_8000_0000u64 as i64),
         sign_extend(0x0000_0000_8000_0000, 4)
     );
     assert_eq!(
-        0xffff_ff80_0000_0000u64 as i64,
+        i128::from(0xffff_ff80_0000_0000u64 as i64),
         sign_extend(0x0000_0080_0000_0000, 5)
     );
     assert_eq!(
-        0xffff_8000_0000_0000u64 as i64,
+        i128::from(0xffff_8000_0000_0000u64 as i64),
         sign_extend(0x0000_8000_0000_0000, 6)
     );
     assert_eq!(
-        0xff80_0000_0000_0000u64 as i64,
+        i128::from(0xff80_0000_0000_0000u64 as i64),
         sign_extend(0x0080_0000_0000_0000, 7)
     );
     assert_eq!(
-        0x8000_0000_0000_0000u64 as i64,
+        i128::from(0x8000_0000_0000_0000u64 as i64),
         sign_extend(0x8000_0000_0000_0000, 8)
     );
 
@@ -127,6 +131,8 @@ fn test_sign_extend() {
     assert_eq!(0x0000_7fff_ffff_ffff, sign_extend(0x0000_7fff_ffff_ffff, 6));
     assert_eq!(0x007f_ffff_ffff_ffff, sign_extend(0x007f_ffff_ffff_ffff, 7));
     assert_eq!(0x7fff_ffff_ffff_ffff, sign_extend(0x7fff_ffff_ffff_ffff, 8));
+    assert_eq!(i128::MIN, sign_extend(1 << 127, 16));
+    assert_eq!(i128::MAX, sign_extend(u128::MAX >> 1, 16));
 }
 
 #[test]
@@ -142,6 +148,10 @@ fn test_format_item_oct() {
         " 1777777777777777777777",
         format_item_oct64(0xffff_ffff_ffff_ffff)
     );
+    assert_eq!(
+        " 3777777777777777777777777777777777777777777",
+        format_item_oct128(u128::MAX)
+    );
 }
 
 #[test]
@@ -157,6 +167,10 @@ fn test_format_item_hex() {
         " ffffffffffffffff",
         format_item_hex64(0xffff_ffff_ffff_ffff)
     );
+    assert_eq!(
+        " ffffffffffffffffffffffffffffffff",
+        format_item_hex128(u128::MAX)
+    );
 }
 
 #[test]
@@ -172,6 +186,10 @@ fn test_format_item_dec_u() {
         " 18446744073709551615",
         format_item_dec_u64(0xffff_ffff_ffff_ffff)
     );
+    assert_eq!(
+        " 340282366920938463463374607431768211455",
+        format_item_dec_u128(u128::MAX)
+    );
 }
 
 #[test]
@@ -194,4 +212,12 @@ fn test_format_item_dec_s() {
         " -9223372036854775808",
         format_item_dec_s64(0x8000_0000_0000_0000)
     );
+    assert_eq!(
+        " -170141183460469231731687303715884105728",
+        format_item_dec_s128(1 << 127)
+    );
+    assert_eq!(
+        "                                       -1",
+        format_item_dec_s128(u128::MAX)
+    );
 }
@@ -7,12 +7,14 @@
 
 // spell-checker:ignore (ToDO) delim
 
+use clap::builder::ValueParser;
 use clap::{crate_version, Arg, ArgAction, Command};
-use std::fmt::Display;
+use std::ffi::OsString;
 use std::fs::File;
-use std::io::{stdin, stdout, BufRead, BufReader, Read, Write};
-use std::path::Path;
-use uucore::error::{FromIo, UResult};
+use std::io::{stdin, stdout, BufRead, BufReader, BufWriter, Read, Write};
+use uucore::display::Quotable;
+use uucore::error::{FromIo, UResult, USimpleError};
+use uucore::show;
 
 static ABOUT: &str = "Write lines consisting of the sequentially corresponding lines from each
 FILE, separated by TABs, to standard output.";
@@ -31,15 +33,6 @@ enum LineEnding {
     Nul = 0,
 }
 
-impl Display for LineEnding {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            Self::Newline => writeln!(f),
-            Self::Nul => write!(f, "\0"),
-        }
-    }
-}
-
 // Wraps BufReader and stdin
 fn read_until<R: Read>(
     reader: Option<&mut BufReader<R>>,
@@ -57,7 +50,7 @@ pub fn uumain(args: impl uucore::Args) -> UResult<()> {
     let matches = uu_app().try_get_matches_from(args)?;
 
     let serial = matches.get_flag(options::SERIAL);
-    let delimiters = matches.get_one::<String>(options::DELIMITER).unwrap();
+    let delimiters = parse_delimiters(matches.get_one::<OsString>(options::DELIMITER).unwrap())?;
     let files = matches
         .get_many::<String>(options::FILE)
         .unwrap()
@@ -69,7 +62,7 @@ pub fn uumain(args: impl uucore::Args) -> UResult<()> {
         LineEnding::Newline
     };
 
-    paste(files, serial, delimiters, line_ending)
+    paste(files, serial, &delimiters, line_ending)
 }
 
 pub fn uu_app() -> Command {
@@ -90,6 +83,7 @@ pub fn uu_app() -> Command {
                 .short('d')
                 .help("reuse characters from LIST instead of TABs")
                 .value_name("LIST")
+                .value_parser(ValueParser::os_string())
                 .default_value("\t")
                 .hide_default_value(true),
         )
@@ -112,118 +106,181 @@ pub fn uu_app() -> Command {
 fn paste(
     filenames: Vec<String>,
     serial: bool,
-    delimiters: &str,
+    delimiters: &[Vec<u8>],
     line_ending: LineEnding,
 ) -> UResult<()> {
-    let mut files = Vec::with_capacity(filenames.len());
-    for name in filenames {
-        let file = if name == "-" {
-            None
-        } else {
-            let path = Path::new(&name);
-            let r = File::open(path).map_err_context(String::new)?;
-            Some(BufReader::new(r))
-        };
-        files.push(file);
-    }
-
-    let delimiters: Vec<char> = unescape(delimiters).chars().collect();
-    let mut delim_count = 0;
-    let mut delim_length = 1;
     let stdout = stdout();
-    let mut stdout = stdout.lock();
+    let mut stdout = BufWriter::new(stdout.lock());
 
-    let mut output = Vec::new();
     if serial {
-        for file in &mut files {
-            output.clear();
-            loop {
-                match read_until(file.as_mut(), line_ending as u8, &mut output) {
-                    Ok(0) => break,
-                    Ok(_) => {
-                        if output.ends_with(&[line_ending as u8]) {
-                            output.pop();
-                        }
-                        // a buffer of length four is large enough to encode any char
-                        let mut buffer = [0; 4];
-                        let ch =
-                            delimiters[delim_count % delimiters.len()].encode_utf8(&mut buffer);
-                        delim_length = ch.len();
-
-                        for byte in buffer.iter().take(delim_length) {
-                            output.push(*byte);
-                        }
-                    }
-                    Err(e) => return Err(e.map_err_context(String::new)),
+        for name in &filenames {
+            match open(name) {
+                Ok(mut file) => {
+                    paste_serial(&mut file, name, delimiters, line_ending, &mut stdout)?
                 }
-                delim_count += 1;
+                Err(e) => show!(e),
             }
-            // remove final delimiter
-            output.truncate(output.len() - delim_length);
-
-            write!(
-                stdout,
-                "{}{}",
-                String::from_utf8_lossy(&output),
-                line_ending
-            )?;
         }
     } else {
-        let mut eof = vec![false; files.len()];
-        loop {
-            output.clear();
-            let mut eof_count = 0;
-            for (i, file) in files.iter_mut().enumerate() {
-                if eof[i] {
-                    eof_count += 1;
-                } else {
-                    match read_until(file.as_mut(), line_ending as u8, &mut output) {
-                        Ok(0) => {
-                            eof[i] = true;
-                            eof_count += 1;
-                        }
-                        Ok(_) => {
-                            if output.ends_with(&[line_ending as u8]) {
-                                output.pop();
-                            }
-                        }
-                        Err(e) => return Err(e.map_err_context(String::new)),
-                    }
-                }
-                // a buffer of length four is large enough to encode any char
-                let mut buffer = [0; 4];
-                let ch = delimiters[delim_count % delimiters.len()].encode_utf8(&mut buffer);
-                delim_length = ch.len();
+        let mut files = Vec::with_capacity(filenames.len());
+        for name in &filenames {
+            files.push(Some(open(name)?));
+        }
+        paste_parallel(&mut files, &filenames, delimiters, line_ending, &mut stdout)?;
+    }
 
-                for byte in buffer.iter().take(delim_length) {
-                    output.push(*byte);
-                }
+    stdout.flush()?;
+    Ok(())
+}
 
-                delim_count += 1;
-            }
-            if files.len() == eof_count {
-                break;
+/// Opens `name`, or returns `None` for the standard input.
+fn open(name: &str) -> UResult<Option<BufReader<File>>> {
+    if name == "-" {
+        Ok(None)
+    } else {
+        let file = File::open(name).map_err_context(|| name.maybe_quote().to_string())?;
+        Ok(Some(BufReader::new(file)))
+    }
+}
+
+/// Reads the next line of `file` into `buf`, without its line ending, and returns whether there
+/// was one.
+fn read_line(
+    file: Option<&mut BufReader<File>>,
+    name: &str,
+    line_ending: LineEnding,
+    buf: &mut Vec<u8>,
+) -> UResult<bool> {
+    buf.clear();
+    match read_until(file, line_ending as u8, buf) {
+        Ok(0) => Ok(false),
+        Ok(_) => {
+            if buf.ends_with(&[line_ending as u8]) {
+                buf.pop();
             }
-            // Remove final delimiter
-            output.truncate(output.len() - delim_length);
-
-            write!(
-                stdout,
-                "{}{}",
-                String::from_utf8_lossy(&output),
-                line_ending
-            )?;
-            delim_count = 0;
+            Ok(true)
+        }
+        Err(e) => Err(e.map_err_context(|| name.maybe_quote().to_string())),
+    }
+}
+
+/// Writes all lines of a file as one line.
+fn paste_serial(
+    file: &mut Option<BufReader<File>>,
+    name: &str,
+    delimiters: &[Vec<u8>],
+    line_ending: LineEnding,
+    stdout: &mut impl Write,
+) -> UResult<()> {
+    let mut line = Vec::new();
+    let mut delimiters = delimiters.iter().cycle();
+    let mut first = true;
+    while read_line(file.as_mut(), name, line_ending, &mut line)? {
+        if !first {
+            stdout.write_all(delimiters.next().unwrap())?;
         }
+        first = false;
+        stdout.write_all(&line)?;
     }
+    stdout.write_all(&[line_ending as u8])?;
     Ok(())
 }
 
-// Unescape all special characters
-// TODO: this will need work to conform to GNU implementation
-fn unescape(s: &str) -> String {
-    s.replace("\\n", "\n")
-        .replace("\\t", "\t")
-        .replace("\\\\", "\\")
-        .replace('\\', "")
+/// Writes the corresponding lines of the files on one line, until the end of all of them. The
+/// files that are already finished contribute empty lines.
+fn paste_parallel(
+    files: &mut [Option<Option<BufReader<File>>>],
+    names: &[String],
+    delimiters: &[Vec<u8>],
+    line_ending: LineEnding,
+    stdout: &mut impl Write,
+) -> UResult<()> {
+    let mut output = Vec::new();
+    let mut line = Vec::new();
+    loop {
+        output.clear();
+        let mut has_line = false;
+        let mut delimiters = delimiters.iter().cycle();
+        for (i, (file, name)) in files.iter_mut().zip(names).enumerate() {
+            if i > 0 {
+                output.extend_from_slice(delimiters.next().unwrap());
+            }
+            if let Some(reader) = file {
+                if read_line(reader.as_mut(), name, line_ending, &mut line)? {
+                    has_line = true;
+                    output.extend_from_slice(&line);
+                } else {
+                    *file = None;
+                }
+            }
+        }
+        if !has_line {
+            return Ok(());
+        }
+        output.push(line_ending as u8);
+        stdout.write_all(&output)?;
+    }
+}
+
+/// Parses the delimiter list of `-d`, which are used in turn. `\0` is an empty delimiter, and
+/// `\n`, `\t`, `\\`, `\b`, `\f`, `\r` and `\v` are escapes as in C; a backslash before any
+/// other character is dropped. An empty list means `\0`.
+fn parse_delimiters(list: &OsString) -> UResult<Vec<Vec<u8>>> {
+    #[cfg(unix)]
+    let bytes = std::os::unix::ffi::OsStrExt::as_bytes(list.as_os_str());
+    #[cfg(not(unix))]
+    let lossy = list.to_string_lossy();
+    #[cfg(not(unix))]
+    let bytes = lossy.as_bytes();
+
+    let mut delimiters = Vec::new();
+    let mut rest = bytes;
+    while let Some(&byte) = rest.first() {
+        if byte != b'\\' {
+            let len = char_len(rest);
+            delimiters.push(rest[..len].to_vec());
+            rest = &rest[len..];
+            continue;
+        }
+        let escaped = &rest[1..];
+        let delimiter = match escaped.first() {
+            None => {
+                return Err(USimpleError::new(
+                    1,
+                    format!(
+                        "delimiter list ends with an unescaped backslash: {}",
+                        list.to_string_lossy()
+                    ),
+                ))
+            }
+            Some(b'0') => vec![],
+            Some(b'b') => vec![b'\x08'],
+            Some(b'f') => vec![b'\x0c'],
+            Some(b'n') => vec![b'\n'],
+            Some(b'r') => vec![b'\r'],
+            Some(b't') => vec![b'\t'],
+            Some(b'v') => vec![b'\x0b'],
+            Some(_) => escaped[..char_len(escaped)].to_vec(),
+        };
+        delimiters.push(delimiter);
+        rest = &escaped[char_len(escaped)..];
+    }
+    if delimiters.is_empty() {
+        delimiters.push(vec![]);
+    }
+    Ok(delimiters)
+}
+
+/// Returns the length of the character at the start of `bytes`, or 1 if it isn't valid UTF-8.
+fn char_len(bytes: &[u8]) -> usize {
+    let len = match bytes[0] {
+        0xc0..=0xdf => 2,
+        0xe0..=0xef => 3,
+        0xf0..=0xf7 => 4,
+        _ => 1,
+    };
+    match bytes.get(..len).map(std::str::from_utf8) {
+        Some(Ok(_)) => len,
+        _ => 1,
+    }
 }
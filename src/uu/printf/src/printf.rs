@@ -1,11 +1,13 @@
 #![allow(dead_code)]
 // spell-checker:ignore (change!) each's
-// spell-checker:ignore (ToDO) LONGHELP FORMATSTRING templating parameterizing formatstr
+// spell-checker:ignore (ToDO) LONGHELP FORMATSTRING templating parameterizing formatstr strftime
 
+use clap::builder::ValueParser;
 use clap::{crate_version, Arg, ArgAction, Command};
+use std::ffi::OsString;
 use uucore::error::{UResult, UUsageError};
+use uucore::format::printf;
 use uucore::format_usage;
-use uucore::memo::printf;
 
 const VERSION: &str = "version";
 const HELP: &str = "help";
@@ -58,7 +60,7 @@ will print the corresponding character literal:
 
 \\uHHHH Unicode (IEC 10646) character with value expressed in hexadecimal value HHHH (4 digits)
 
-\\uHHHH Unicode character with value expressed in hexadecimal value HHHH (8 digits)
+\\UHHHHHHHH Unicode character with value expressed in hexadecimal value HHHHHHHH (8 digits)
 
 %% a single %
 
@@ -75,6 +77,11 @@ Fields
 %c - char
   no second parameter
 
+%q - string quoted so that the shell can reuse it as input
+
+%(FORMAT)T - date and time of an integer number of seconds since the epoch,
+  formatted with the strftime FORMAT; -1 or no argument is the current time
+
 %i or %d - 64-bit integer
 %u - 64 bit unsigned integer
 %x or %X - 64-bit unsigned integer as hex
@@ -84,6 +91,7 @@ Fields
 
 %f or %F - decimal floating point value
 %e or %E - scientific notation floating point value
+%a or %A - hexadecimal floating point value
 %g or %G - shorter of specially interpreted decimal or SciNote floating point value.
   second parameter is
     -max places after decimal point for floating point output
@@ -252,6 +260,12 @@ SPECIAL FORMS OF INPUT
     additional bytes, they will throw an error (unless the environment variable POSIXLY_CORRECT
     is set)
 
+  Invalid numbers: an argument that is not a number, only partly one or out of range is
+    reported, the part of it that could be converted (or 0, or the nearest value in range) is
+    used instead, and printf exits with status 1 once the format is done.
+
+ An asterisk as the width or precision, as in %*.*f, takes it from the next argument.
+
 WRITTEN BY :
   Nathan E. Ross, et al. for the uutils project
 
@@ -271,14 +285,13 @@ mod options {
 
 #[uucore::main]
 pub fn uumain(args: impl uucore::Args) -> UResult<()> {
-    let args = args.collect_ignore();
     let matches = uu_app().get_matches_from(args);
 
     let format_string = matches
         .get_one::<String>(options::FORMATSTRING)
         .ok_or_else(|| UUsageError::new(1, "missing operand"))?;
-    let values: Vec<String> = match matches.get_many::<String>(options::ARGUMENT) {
-        Some(s) => s.map(|s| s.to_string()).collect(),
+    let values: Vec<OsString> = match matches.get_many::<OsString>(options::ARGUMENT) {
+        Some(s) => s.cloned().collect(),
         None => vec![],
     };
 
@@ -308,5 +321,9 @@ pub fn uu_app() -> Command {
                 .action(ArgAction::Version),
         )
         .arg(Arg::new(options::FORMATSTRING))
-        .arg(Arg::new(options::ARGUMENT).action(ArgAction::Append))
+        .arg(
+            Arg::new(options::ARGUMENT)
+                .action(ArgAction::Append)
+                .value_parser(ValueParser::os_string()),
+        )
 }
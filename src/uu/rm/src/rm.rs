@@ -7,7 +7,12 @@
 
 // spell-checker:ignore (path) eacces
 
+mod trash;
+#[cfg(unix)]
+mod unix;
+
 use clap::{crate_version, parser::ValueSource, Arg, ArgAction, Command};
+#[cfg(not(unix))]
 use remove_dir_all::remove_dir_all;
 use std::collections::VecDeque;
 use std::fs::{self, File, Metadata};
@@ -36,12 +41,15 @@ struct Options {
     recursive: bool,
     dir: bool,
     verbose: bool,
+    jobs: usize,
+    trash: bool,
 }
 
 static ABOUT: &str = "Remove (unlink) the FILE(s)";
 const USAGE: &str = "{} [OPTION]... FILE...";
 static OPT_DIR: &str = "dir";
 static OPT_INTERACTIVE: &str = "interactive";
+static OPT_JOBS: &str = "jobs";
 static OPT_FORCE: &str = "force";
 static OPT_NO_PRESERVE_ROOT: &str = "no-preserve-root";
 static OPT_ONE_FILE_SYSTEM: &str = "one-file-system";
@@ -49,6 +57,7 @@ static OPT_PRESERVE_ROOT: &str = "preserve-root";
 static OPT_PROMPT: &str = "prompt";
 static OPT_PROMPT_MORE: &str = "prompt-more";
 static OPT_RECURSIVE: &str = "recursive";
+static OPT_TRASH: &str = "trash";
 static OPT_VERBOSE: &str = "verbose";
 static PRESUME_INPUT_TTY: &str = "-presume-input-tty";
 
@@ -111,13 +120,23 @@ pub fn uumain(args: impl uucore::Args) -> UResult<()> {
                     InteractiveMode::Once
                 } else if matches.contains_id(OPT_INTERACTIVE) {
                     match matches.get_one::<String>(OPT_INTERACTIVE).unwrap().as_str() {
-                        "never" => InteractiveMode::Never,
+                        "never" | "no" | "none" => InteractiveMode::Never,
                         "once" => InteractiveMode::Once,
-                        "always" => InteractiveMode::Always,
+                        "always" | "yes" => InteractiveMode::Always,
                         val => {
                             return Err(USimpleError::new(
                                 1,
-                                format!("Invalid argument to interactive ({val})"),
+                                format!(
+                                    "invalid argument {} for '--{}'\n\
+                                    Valid arguments are:\n  \
+                                    - 'never', 'no', 'none'\n  \
+                                    - 'once'\n  \
+                                    - 'always', 'yes'\n\
+                                    Try '{} --help' for more information.",
+                                    val.quote(),
+                                    OPT_INTERACTIVE,
+                                    uucore::execution_phrase()
+                                ),
                             ))
                         }
                     }
@@ -130,14 +149,21 @@ pub fn uumain(args: impl uucore::Args) -> UResult<()> {
             recursive: matches.get_flag(OPT_RECURSIVE),
             dir: matches.get_flag(OPT_DIR),
             verbose: matches.get_flag(OPT_VERBOSE),
+            jobs: *matches.get_one::<usize>(OPT_JOBS).unwrap(),
+            trash: matches.get_flag(OPT_TRASH),
         };
         if options.interactive == InteractiveMode::Once && (options.recursive || files.len() > 3) {
-            let msg = if options.recursive {
-                "Remove all arguments recursively?"
+            let arguments = if files.len() == 1 {
+                "argument"
             } else {
-                "Remove all arguments?"
+                "arguments"
             };
-            if !prompt_yes!("{}", msg) {
+            let recursively = if options.recursive {
+                " recursively"
+            } else {
+                ""
+            };
+            if !prompt_yes!("remove {} {}{}?", files.len(), arguments, recursively) {
                 return Ok(());
             }
         }
@@ -186,6 +212,9 @@ pub fn uu_app() -> Command {
                     prompts always",
                 )
                 .value_name("WHEN")
+                .num_args(0..=1)
+                .require_equals(true)
+                .default_missing_value("always")
                 .overrides_with_all([OPT_PROMPT, OPT_PROMPT_MORE]),
         )
         .arg(
@@ -231,6 +260,28 @@ pub fn uu_app() -> Command {
                 .help("explain what is being done")
                 .action(ArgAction::SetTrue),
         )
+        .arg(
+            Arg::new(OPT_JOBS)
+                .short('j')
+                .long(OPT_JOBS)
+                .value_name("N")
+                .value_parser(clap::value_parser!(usize))
+                .default_value("1")
+                .help(
+                    "remove up to N directories at once when removing recursively, or as many \
+                    as there are CPUs if N is 0. \n\
+                    Note: this feature is not supported by GNU coreutils.",
+                ),
+        )
+        .arg(
+            Arg::new(OPT_TRASH)
+                .long(OPT_TRASH)
+                .help(
+                    "move the files to the trash instead of removing them \n\
+                    Note: this feature is not supported by GNU coreutils.",
+                )
+                .action(ArgAction::SetTrue),
+        )
         // From the GNU source code:
         // This is solely for testing.
         // Do not document.
@@ -262,7 +313,9 @@ fn remove(files: &[String], options: &Options) -> bool {
         let file = Path::new(filename);
         had_err = match file.symlink_metadata() {
             Ok(metadata) => {
-                if metadata.is_dir() {
+                if options.trash {
+                    trash_file(file, &metadata, options)
+                } else if metadata.is_dir() {
                     handle_dir(file, options)
                 } else if is_symlink_dir(&metadata) {
                     remove_dir(file, options)
@@ -292,25 +345,55 @@ fn remove(files: &[String], options: &Options) -> bool {
     had_err
 }
 
+/// Moves `path` to the trash instead of removing it, and returns whether an error occurred.
+///
+/// Directories need the same options as for removing them, but their contents are moved along
+/// with them without prompting for each of them.
+fn trash_file(path: &Path, metadata: &Metadata, options: &Options) -> bool {
+    let is_dir = metadata.is_dir() || is_symlink_dir(metadata);
+    if is_dir {
+        let is_root = path.has_root() && path.parent().is_none();
+        let is_empty = fs::read_dir(path).map_or(false, |mut dir| dir.next().is_none());
+        if is_root && options.preserve_root {
+            show_error!("could not remove directory {}", path.quote());
+            return true;
+        } else if !(options.recursive || options.dir && is_empty) {
+            show_error!("cannot remove {}: Is a directory", path.quote());
+            return true;
+        }
+    }
+    if !prompt_file(path, options, is_dir) {
+        return false;
+    }
+    match trash::trash(path) {
+        Ok(trashed_path) => {
+            if options.verbose {
+                println!(
+                    "moved {} to {}",
+                    normalize(path).quote(),
+                    trashed_path.quote()
+                );
+            }
+            false
+        }
+        Err(e) => {
+            show_error!(
+                "cannot move {} to the trash: {}",
+                path.quote(),
+                uucore::error::strip_errno(&e)
+            );
+            true
+        }
+    }
+}
+
 fn handle_dir(path: &Path, options: &Options) -> bool {
     let mut had_err = false;
 
     let is_root = path.has_root() && path.parent().is_none();
     if options.recursive && (!is_root || !options.preserve_root) {
         if options.interactive != InteractiveMode::Always && !options.verbose {
-            // we need the extra crate because apparently fs::remove_dir_all() does not function
-            // correctly on Windows
-            if let Err(e) = remove_dir_all(path) {
-                had_err = true;
-                if e.kind() == std::io::ErrorKind::PermissionDenied {
-                    // GNU compatibility (rm/fail-eacces.sh)
-                    // here, GNU doesn't use some kind of remove_dir_all
-                    // It will show directory+file
-                    show_error!("cannot remove {}: {}", path.quote(), "Permission denied");
-                } else {
-                    show_error!("cannot remove {}: {}", path.quote(), e);
-                }
-            }
+            had_err = remove_dir_recursive(path, options);
         } else {
             let mut dirs: VecDeque<DirEntry> = VecDeque::new();
             // The Paths to not descend into. We need to this because WalkDir doesn't have a way, afaik, to not descend into a directory
@@ -374,6 +457,31 @@ fn handle_dir(path: &Path, options: &Options) -> bool {
     had_err
 }
 
+/// Removes the directory `path` with all its contents without prompting, and returns whether an
+/// error occurred.
+#[cfg(unix)]
+fn remove_dir_recursive(path: &Path, options: &Options) -> bool {
+    unix::remove_dir_all(path, options.jobs)
+}
+
+#[cfg(not(unix))]
+fn remove_dir_recursive(path: &Path, _options: &Options) -> bool {
+    // we need the extra crate because apparently fs::remove_dir_all() does not function
+    // correctly on Windows
+    if let Err(e) = remove_dir_all(path) {
+        if e.kind() == std::io::ErrorKind::PermissionDenied {
+            // GNU compatibility (rm/fail-eacces.sh)
+            // here, GNU doesn't use some kind of remove_dir_all
+            // It will show directory+file
+            show_error!("cannot remove {}: {}", path.quote(), "Permission denied");
+        } else {
+            show_error!("cannot remove {}: {}", path.quote(), e);
+        }
+        return true;
+    }
+    false
+}
+
 fn remove_dir(path: &Path, options: &Options) -> bool {
     if prompt_file(path, options, true) {
         if let Ok(mut read_dir) = fs::read_dir(path) {
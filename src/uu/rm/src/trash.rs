@@ -0,0 +1,192 @@
+//  * This file is part of the uutils coreutils package.
+//  *
+//  * For the full copyright and license information, please view the LICENSE
+//  * file that was distributed with this source code.
+
+// spell-checker:ignore (path) topdir trashinfo
+
+//! Moving files to the trash instead of removing them.
+//!
+//! On most Unix systems this follows the FreeDesktop.org trash specification
+//! (<https://specifications.freedesktop.org/trash-spec/trashspec-latest.html>): a file is moved
+//! to the `files` directory of a trash directory on its file system, and a `.trashinfo` file
+//! with the same name in the `info` directory records where it came from and when, so that file
+//! managers can restore it.
+
+#[cfg(unix)]
+use std::ffi::OsString;
+#[cfg(unix)]
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Moves `path` to the trash, and returns the path it now has.
+#[cfg(all(unix, not(target_os = "macos")))]
+pub(crate) fn trash(path: &Path) -> io::Result<PathBuf> {
+    use std::fmt::Write as _;
+    use std::io::Write;
+    use std::os::unix::ffi::OsStrExt;
+
+    let path = absolute_path(path)?;
+    let file_name = file_name(&path)?;
+    let trash_dir = trash_dir(&path)?;
+    let files_dir = trash_dir.join("files");
+    let info_dir = trash_dir.join("info");
+    create_private_dir(&files_dir)?;
+    create_private_dir(&info_dir)?;
+
+    let mut info = String::from("[Trash Info]\nPath=");
+    for &byte in path.as_os_str().as_bytes() {
+        if byte.is_ascii_alphanumeric() || b"-._~/".contains(&byte) {
+            info.push(byte as char);
+        } else {
+            write!(info, "%{byte:02X}").unwrap();
+        }
+    }
+    writeln!(
+        info,
+        "\nDeletionDate={}",
+        chrono::Local::now().format("%Y-%m-%dT%H:%M:%S")
+    )
+    .unwrap();
+
+    // The name is reserved by creating its `.trashinfo` file, which only one process can do.
+    for n in 0.. {
+        let name = numbered_name(&file_name, n);
+        let mut info_name = name.clone();
+        info_name.push(".trashinfo");
+        let info_path = info_dir.join(info_name);
+        let mut info_file = match fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&info_path)
+        {
+            Ok(info_file) => info_file,
+            Err(e) if e.kind() == io::ErrorKind::AlreadyExists => continue,
+            Err(e) => return Err(e),
+        };
+        let trashed_path = files_dir.join(&name);
+        let result = if trashed_path.symlink_metadata().is_ok() {
+            // A file without information, which is left alone.
+            Err(io::ErrorKind::AlreadyExists.into())
+        } else {
+            info_file
+                .write_all(info.as_bytes())
+                .and_then(|_| fs::rename(&path, &trashed_path))
+        };
+        match result {
+            Ok(()) => return Ok(trashed_path),
+            Err(e) => {
+                let _ = fs::remove_file(&info_path);
+                if e.kind() != io::ErrorKind::AlreadyExists {
+                    return Err(e);
+                }
+            }
+        }
+    }
+    unreachable!()
+}
+
+/// Moves `path` to the trash, and returns the path it now has.
+#[cfg(target_os = "macos")]
+pub(crate) fn trash(path: &Path) -> io::Result<PathBuf> {
+    let path = absolute_path(path)?;
+    let file_name = file_name(&path)?;
+    let trash_dir = match std::env::var_os("HOME") {
+        Some(home) => Path::new(&home).join(".Trash"),
+        None => return Err(io::Error::new(io::ErrorKind::NotFound, "HOME is not set")),
+    };
+    fs::create_dir_all(&trash_dir)?;
+    for n in 0.. {
+        let trashed_path = trash_dir.join(numbered_name(&file_name, n));
+        if trashed_path.symlink_metadata().is_err() {
+            fs::rename(&path, &trashed_path)?;
+            return Ok(trashed_path);
+        }
+    }
+    unreachable!()
+}
+
+/// Moves `path` to the trash, and returns the path it now has.
+#[cfg(not(unix))]
+pub(crate) fn trash(_path: &Path) -> io::Result<PathBuf> {
+    Err(io::Error::new(
+        io::ErrorKind::Other,
+        "the trash is not supported on this platform",
+    ))
+}
+
+/// Returns the absolute path of `path`, without resolving it if it is a symbolic link.
+#[cfg(unix)]
+fn absolute_path(path: &Path) -> io::Result<PathBuf> {
+    let file_name = file_name(path)?;
+    let parent = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    Ok(parent.canonicalize()?.join(file_name))
+}
+
+#[cfg(unix)]
+fn file_name(path: &Path) -> io::Result<OsString> {
+    match path.file_name() {
+        Some(name) => Ok(name.to_os_string()),
+        None => Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "Invalid argument",
+        )),
+    }
+}
+
+/// Returns `name` with the suffix `.n`, unless `n` is 0.
+#[cfg(unix)]
+fn numbered_name(name: &OsString, n: usize) -> OsString {
+    let mut name = name.clone();
+    if n > 0 {
+        name.push(format!(".{n}"));
+    }
+    name
+}
+
+/// Returns the trash directory for files on the file system of `path`: the home trash if it is
+/// on the same file system, or else the `.Trash-UID` directory at the root of that file system.
+#[cfg(all(unix, not(target_os = "macos")))]
+fn trash_dir(path: &Path) -> io::Result<PathBuf> {
+    use std::os::unix::fs::MetadataExt;
+
+    let data_home = match std::env::var_os("XDG_DATA_HOME") {
+        Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+        _ => match std::env::var_os("HOME") {
+            Some(home) => Path::new(&home).join(".local/share"),
+            None => return Err(io::Error::new(io::ErrorKind::NotFound, "HOME is not set")),
+        },
+    };
+    let home_trash = data_home.join("Trash");
+    create_private_dir(&home_trash)?;
+
+    let dev = path.symlink_metadata()?.dev();
+    if home_trash.metadata()?.dev() == dev {
+        return Ok(home_trash);
+    }
+    let mut top_dir = path.parent().unwrap_or(path);
+    while let Some(parent) = top_dir.parent() {
+        if parent.metadata()?.dev() != dev {
+            break;
+        }
+        top_dir = parent;
+    }
+    // SAFETY: getuid can't fail.
+    let uid = unsafe { libc::getuid() };
+    Ok(top_dir.join(format!(".Trash-{uid}")))
+}
+
+/// Creates `dir` and its parents if they don't exist, only accessible by the user.
+#[cfg(all(unix, not(target_os = "macos")))]
+fn create_private_dir(dir: &Path) -> io::Result<()> {
+    use std::os::unix::fs::DirBuilderExt;
+
+    fs::DirBuilder::new()
+        .recursive(true)
+        .mode(0o700)
+        .create(dir)
+}
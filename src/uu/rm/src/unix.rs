@@ -0,0 +1,186 @@
+//  * This file is part of the uutils coreutils package.
+//  *
+//  * For the full copyright and license information, please view the LICENSE
+//  * file that was distributed with this source code.
+
+// spell-checker:ignore (libc) fstatat openat unlinkat
+
+//! Recursive removal relative to the file descriptors of the removed directories.
+//!
+//! Every directory is opened relative to its parent without following symbolic links, and its
+//! entries are removed with `unlinkat`, so the kernel doesn't resolve the whole path of every
+//! entry again, and a directory replaced by a symbolic link while it is removed can't redirect
+//! the removal to another part of the file system.
+
+use std::ffi::{CStr, CString, OsStr};
+use std::fs;
+use std::io;
+use std::ops::BitOr;
+use std::os::unix::ffi::OsStrExt;
+use std::os::unix::io::{AsRawFd, RawFd};
+use std::path::{Path, PathBuf};
+
+use nix::dir::{Dir, Type};
+use nix::errno::Errno;
+use nix::fcntl::{AtFlags, OFlag};
+use nix::sys::stat::{fstatat, Mode, SFlag};
+use nix::unistd::{unlinkat, UnlinkatFlags};
+use rayon::prelude::*;
+use uucore::display::Quotable;
+use uucore::error::strip_errno;
+use uucore::show_error;
+
+/// The number of nested directories kept open while removing a tree.
+///
+/// Every directory stays open until its subdirectories are removed, so the directories below
+/// this depth are removed by path instead, which doesn't run out of file descriptors in very
+/// deep trees, even when several of them are removed at once.
+const MAX_OPEN_DEPTH: usize = 64;
+
+/// Removes the directory `path` and everything in it, removing the contents of up to `jobs`
+/// directories at once, or of as many as there are CPUs if `jobs` is 0.
+///
+/// Returns whether an error occurred, after showing it.
+pub(crate) fn remove_dir_all(path: &Path, jobs: usize) -> bool {
+    let mut dir = match Dir::open(path, dir_flags(), Mode::empty()) {
+        Ok(dir) => dir,
+        Err(e) => return show_remove_error(path, e),
+    };
+    let had_err = if jobs == 1 {
+        remove_dir_contents(&mut dir, path, 0, false)
+    } else {
+        match rayon::ThreadPoolBuilder::new().num_threads(jobs).build() {
+            Ok(pool) => pool.install(|| remove_dir_contents(&mut dir, path, 0, true)),
+            Err(_) => remove_dir_contents(&mut dir, path, 0, false),
+        }
+    };
+    drop(dir);
+    if had_err {
+        // The directory can't be empty, and its entries have already been reported.
+        return true;
+    }
+    match fs::remove_dir(path) {
+        Ok(()) => false,
+        Err(e) => show_io_error(path, &e),
+    }
+}
+
+fn dir_flags() -> OFlag {
+    OFlag::O_RDONLY | OFlag::O_DIRECTORY | OFlag::O_NOFOLLOW | OFlag::O_CLOEXEC
+}
+
+/// Removes the entries of `dir`, found at `path` `depth` levels below the removed directory, and
+/// returns whether an error occurred.
+///
+/// The subdirectories are removed concurrently on the current rayon thread pool if `parallel`.
+fn remove_dir_contents(dir: &mut Dir, path: &Path, depth: usize, parallel: bool) -> bool {
+    let fd = dir.as_raw_fd();
+    let mut had_err = false;
+    let mut subdirs = Vec::new();
+    for entry in dir.iter() {
+        let entry = match entry {
+            Ok(entry) => entry,
+            Err(e) => return show_remove_error(path, e),
+        };
+        let name = entry.file_name();
+        if name.to_bytes() == b"." || name.to_bytes() == b".." {
+            continue;
+        }
+        let is_dir = match entry.file_type() {
+            Some(file_type) => file_type == Type::Directory,
+            // Not every file system reports the type of the entries.
+            None => fstatat(fd, name, AtFlags::AT_SYMLINK_NOFOLLOW)
+                .map(|stat| {
+                    SFlag::from_bits_truncate(stat.st_mode) & SFlag::S_IFMT == SFlag::S_IFDIR
+                })
+                .unwrap_or(false),
+        };
+        if is_dir {
+            subdirs.push(name.to_owned());
+        } else if let Err(e) = unlinkat(Some(fd), name, UnlinkatFlags::NoRemoveDir) {
+            had_err = show_remove_error(&entry_path(path, name), e);
+        }
+    }
+
+    let remove_subdir = |name: &CString| remove_subdir(fd, name, path, depth, parallel);
+    let subdirs_had_err = if parallel {
+        subdirs
+            .par_iter()
+            .map(remove_subdir)
+            .reduce(|| false, BitOr::bitor)
+    } else {
+        subdirs.iter().map(remove_subdir).fold(false, BitOr::bitor)
+    };
+    had_err || subdirs_had_err
+}
+
+/// Removes the subdirectory `name` of the directory `parent_fd`, found at `parent_path`.
+fn remove_subdir(
+    parent_fd: RawFd,
+    name: &CString,
+    parent_path: &Path,
+    depth: usize,
+    parallel: bool,
+) -> bool {
+    let path = entry_path(parent_path, name);
+    if depth >= MAX_OPEN_DEPTH {
+        return remove_dir_all_by_path(&path);
+    }
+    let had_err = match Dir::openat(parent_fd, name.as_c_str(), dir_flags(), Mode::empty()) {
+        Ok(mut dir) => remove_dir_contents(&mut dir, &path, depth + 1, parallel),
+        Err(e) => return show_remove_error(&path, e),
+    };
+    if had_err {
+        return true;
+    }
+    match unlinkat(Some(parent_fd), name.as_c_str(), UnlinkatFlags::RemoveDir) {
+        Ok(()) => false,
+        Err(e) => show_remove_error(&path, e),
+    }
+}
+
+/// Removes the directory `path` and everything in it by path, keeping at most one directory open.
+fn remove_dir_all_by_path(path: &Path) -> bool {
+    // The entries keep their directory open, so only their paths and types are kept.
+    let entries = fs::read_dir(path).and_then(|entries| {
+        entries
+            .map(|entry| entry.map(|entry| (entry.path(), entry.file_type().map(|t| t.is_dir()))))
+            .collect::<io::Result<Vec<_>>>()
+    });
+    let entries = match entries {
+        Ok(entries) => entries,
+        Err(e) => return show_io_error(path, &e),
+    };
+    let mut had_err = false;
+    for (entry_path, is_dir) in entries {
+        had_err |= match is_dir {
+            Ok(true) => remove_dir_all_by_path(&entry_path),
+            _ => match fs::remove_file(&entry_path) {
+                Ok(()) => false,
+                Err(e) => show_io_error(&entry_path, &e),
+            },
+        };
+    }
+    if had_err {
+        return true;
+    }
+    match fs::remove_dir(path) {
+        Ok(()) => false,
+        Err(e) => show_io_error(path, &e),
+    }
+}
+
+fn entry_path(dir: &Path, name: &CStr) -> PathBuf {
+    dir.join(OsStr::from_bytes(name.to_bytes()))
+}
+
+/// Shows that `path` could not be removed and returns true.
+fn show_remove_error(path: &Path, err: Errno) -> bool {
+    show_error!("cannot remove {}: {}", path.quote(), err.desc());
+    true
+}
+
+fn show_io_error(path: &Path, err: &io::Error) -> bool {
+    show_error!("cannot remove {}: {}", path.quote(), strip_errno(err));
+    true
+}
@@ -25,6 +25,19 @@ pub enum SeqError {
     /// The parameter is the increment argument as a [`String`] as read
     /// from the command line.
     ZeroIncrement(String),
+
+    /// The format given with `-f` has no floating point directive.
+    NoDirective(String),
+
+    /// The format given with `-f` ends in the middle of its directive.
+    EndsInPercent(String),
+
+    /// The directive of the format given with `-f` converts to something
+    /// else than a floating point number, given as the character.
+    UnknownDirective(String, char),
+
+    /// The format given with `-f` has more than one directive.
+    TooManyDirectives(String),
 }
 
 impl SeqError {
@@ -33,6 +46,10 @@ impl SeqError {
         match self {
             Self::ParseError(s, _) => s,
             Self::ZeroIncrement(s) => s,
+            Self::NoDirective(s) => s,
+            Self::EndsInPercent(s) => s,
+            Self::UnknownDirective(s, _) => s,
+            Self::TooManyDirectives(s) => s,
         }
     }
 
@@ -45,6 +62,7 @@ impl SeqError {
                 ParseNumberError::Hex => "hexadecimal argument",
             },
             Self::ZeroIncrement(_) => "Zero increment value",
+            _ => "format",
         }
     }
 }
@@ -55,7 +73,7 @@ impl UError for SeqError {
     }
 
     fn usage(&self) -> bool {
-        true
+        matches!(self, Self::ParseError(..) | Self::ZeroIncrement(_))
     }
 }
 
@@ -63,6 +81,15 @@ impl Error for SeqError {}
 
 impl Display for SeqError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "invalid {}: {}", self.argtype(), self.arg().quote())
+        let arg = self.arg().quote();
+        match self {
+            Self::ParseError(..) | Self::ZeroIncrement(_) => {
+                write!(f, "invalid {}: {}", self.argtype(), arg)
+            }
+            Self::NoDirective(_) => write!(f, "format {arg} has no % directive"),
+            Self::EndsInPercent(_) => write!(f, "format {arg} ends in %"),
+            Self::UnknownDirective(_, c) => write!(f, "format {arg} has unknown %{c} directive"),
+            Self::TooManyDirectives(_) => write!(f, "format {arg} has too many % directives"),
+        }
     }
 }
@@ -4,17 +4,16 @@
 //  * file that was distributed with this source code.
 // TODO: Support -f flag
 // spell-checker:ignore (ToDO) istr chiter argptr ilen extendedbigdecimal extendedbigint numberparse
-use std::io::{stdout, ErrorKind, Write};
-use std::process::exit;
+use std::io::{stdout, BufWriter, ErrorKind, Write};
 
 use clap::{crate_version, Arg, ArgAction, Command};
-use num_traits::Zero;
+use num_bigint::BigInt;
+use num_traits::{Signed, ToPrimitive, Zero};
 
 use uucore::error::FromIo;
 use uucore::error::UResult;
+use uucore::format::{parse_format, FormatItem};
 use uucore::format_usage;
-use uucore::memo::printf;
-use uucore::show;
 
 mod error;
 mod extendedbigdecimal;
@@ -47,6 +46,9 @@ struct SeqOptions<'a> {
     format: Option<&'a str>,
 }
 
+/// The items of a format given with `-f`.
+type Format = Vec<FormatItem>;
+
 /// A range of integers.
 ///
 /// The elements are (first, increment, last).
@@ -111,6 +113,8 @@ pub fn uumain(args: impl uucore::Args) -> UResult<()> {
         }
     };
 
+    let format = options.format.map(parse_seq_format).transpose()?;
+
     let padding = first
         .num_integral_digits
         .max(increment.num_integral_digits)
@@ -128,7 +132,7 @@ pub fn uumain(args: impl uucore::Args) -> UResult<()> {
                 &options.terminator,
                 options.widths,
                 padding,
-                options.format,
+                format.as_deref(),
             )
         }
         (first, increment, last) => print_seq(
@@ -142,7 +146,7 @@ pub fn uumain(args: impl uucore::Args) -> UResult<()> {
             &options.terminator,
             options.widths,
             padding,
-            options.format,
+            format.as_deref(),
         ),
     };
     match result {
@@ -192,6 +196,71 @@ pub fn uu_app() -> Command {
         )
 }
 
+/// Check that `format` has exactly one floating point directive, like GNU
+/// seq does, and split it into its items.
+///
+/// Backslash escapes are not replaced, and the grouping flag `'` is allowed
+/// with all the conversions, though it groups no digits in the C locale.
+fn parse_seq_format(format: &str) -> Result<Format, SeqError> {
+    let bytes = format.as_bytes();
+    let span = |i: usize, set: &[u8]| bytes[i..].iter().take_while(|b| set.contains(b)).count();
+
+    // The directive is the first `%` that isn't part of a `%%`.
+    let mut i = 0;
+    while !(bytes.get(i) == Some(&b'%') && bytes.get(i + 1) != Some(&b'%')) {
+        if i >= bytes.len() {
+            return Err(SeqError::NoDirective(format.to_string()));
+        }
+        i += if bytes[i] == b'%' { 2 } else { 1 };
+    }
+    let start = i + 1;
+    let flags_end = start + span(start, b"-+#0 '");
+    i = flags_end + span(flags_end, b"0123456789");
+    if bytes.get(i) == Some(&b'.') {
+        i += 1 + span(i + 1, b"0123456789");
+    }
+    if bytes.get(i) == Some(&b'L') {
+        i += 1;
+    }
+    let conversion = match format[i..].chars().next() {
+        Some(c) => c,
+        None => return Err(SeqError::EndsInPercent(format.to_string())),
+    };
+    if !"efgaEFGA".contains(conversion) {
+        return Err(SeqError::UnknownDirective(format.to_string(), conversion));
+    }
+    i += 1;
+    while let Some(&b) = bytes.get(i) {
+        if b == b'%' && bytes.get(i + 1) != Some(&b'%') {
+            return Err(SeqError::TooManyDirectives(format.to_string()));
+        }
+        i += if b == b'%' { 2 } else { 1 };
+    }
+
+    let flags = format[start..flags_end].replace('\'', "");
+    let format = format!("{}{flags}{}", &format[..start], &format[flags_end..]);
+    // The checks above only let through what a float conversion parses.
+    Ok(parse_format(&format, false)
+        .map(|item| item.expect("valid float directive"))
+        .collect())
+}
+
+/// Write a value rendered into the template given with `-f FORMAT`.
+fn write_formatted(
+    writer: &mut impl Write,
+    format: &[FormatItem],
+    value: f64,
+) -> std::io::Result<()> {
+    for item in format {
+        match item {
+            FormatItem::Text(text) => writer.write_all(text)?,
+            FormatItem::Spec(spec) => spec.write_float(writer, value)?,
+            FormatItem::Stop => break,
+        }
+    }
+    Ok(())
+}
+
 fn done_printing<T: Zero + PartialOrd>(next: &T, increment: &T, last: &T) -> bool {
     if increment >= &T::zero() {
         next > last
@@ -250,10 +319,10 @@ fn print_seq(
     terminator: &str,
     pad: bool,
     padding: usize,
-    format: Option<&str>,
+    format: Option<&[FormatItem]>,
 ) -> std::io::Result<()> {
     let stdout = stdout();
-    let mut stdout = stdout.lock();
+    let mut stdout = BufWriter::new(stdout.lock());
     let (first, increment, last) = range;
     let mut value = first;
     let padding = if pad { padding + 1 + largest_dec } else { 0 };
@@ -265,23 +334,11 @@ fn print_seq(
         // If there was an argument `-f FORMAT`, then use that format
         // template instead of the default formatting strategy.
         //
-        // TODO The `printf()` method takes a string as its second
-        // parameter but we have an `ExtendedBigDecimal`. In order to
-        // satisfy the signature of the function, we convert the
-        // `ExtendedBigDecimal` into a string. The `printf()`
-        // logic will subsequently parse that string into something
-        // similar to an `ExtendedBigDecimal` again before rendering
-        // it as a string and ultimately writing to `stdout`. We
-        // shouldn't have to do so much converting back and forth via
-        // strings.
+        // The directive converts a floating point number, which the
+        // displayed value always parses as, including infinity and
+        // negative zero.
         match format {
-            Some(f) => {
-                let s = format!("{value}");
-                if let Err(x) = printf(f, &[s]) {
-                    show!(x);
-                    exit(1);
-                }
-            }
+            Some(f) => write_formatted(&mut stdout, f, to_f64(&value))?,
             None => write_value_float(
                 &mut stdout,
                 &value,
@@ -321,11 +378,38 @@ fn print_seq_integers(
     terminator: &str,
     pad: bool,
     padding: usize,
-    format: Option<&str>,
+    format: Option<&[FormatItem]>,
 ) -> std::io::Result<()> {
     let stdout = stdout();
-    let mut stdout = stdout.lock();
+    let mut stdout = BufWriter::new(stdout.lock());
     let (first, increment, last) = range;
+    if format.is_none() && !pad && increment == ExtendedBigInt::one() {
+        match (&first, &last) {
+            (ExtendedBigInt::BigInt(first), ExtendedBigInt::BigInt(last))
+                if !first.is_negative() && first <= last =>
+            {
+                return print_seq_fast(&mut stdout, first, Some(last), separator, terminator);
+            }
+            (ExtendedBigInt::BigInt(first), ExtendedBigInt::Infinity) if !first.is_negative() => {
+                return print_seq_fast(&mut stdout, first, None, separator, terminator);
+            }
+            _ => {}
+        }
+    }
+    if format.is_none() {
+        if let (Some(first), Some(increment), Some(last)) =
+            (to_i64(&first), to_i64(&increment), to_i64(&last))
+        {
+            return print_seq_i64(
+                &mut stdout,
+                (first, increment, last),
+                separator,
+                terminator,
+                pad,
+                padding,
+            );
+        }
+    }
     let mut value = first;
     let mut is_first_iteration = true;
     while !done_printing(&value, &increment, &last) {
@@ -334,19 +418,12 @@ fn print_seq_integers(
         }
         // If there was an argument `-f FORMAT`, then use that format
         // template instead of the default formatting strategy.
-        //
-        // The `printf()` function takes in the template and
-        // the current value and writes the result to `stdout`.
-        //
-        // TODO See similar comment about formatting in `print_seq()`.
         match format {
-            Some(f) => {
-                let s = format!("{value}");
-                if let Err(x) = printf(f, &[s]) {
-                    show!(x);
-                    exit(1);
-                }
+            // Negative zero is displayed without its sign.
+            Some(f) if value == ExtendedBigInt::MinusZero => {
+                write_formatted(&mut stdout, f, -0.0)?;
             }
+            Some(f) => write_formatted(&mut stdout, f, to_f64(&value))?,
             None => write_value_int(&mut stdout, &value, padding, pad, is_first_iteration)?,
         }
         // TODO Implement augmenting addition.
@@ -357,5 +434,97 @@ fn print_seq_integers(
     if !is_first_iteration {
         write!(stdout, "{terminator}")?;
     }
-    Ok(())
+    stdout.flush()
+}
+
+/// The floating point number nearest to `value`.
+fn to_f64(value: &impl std::fmt::Display) -> f64 {
+    value.to_string().parse().unwrap_or(f64::NAN)
+}
+
+fn to_i64(value: &ExtendedBigInt) -> Option<i64> {
+    match value {
+        ExtendedBigInt::BigInt(n) => n.to_i64(),
+        _ => None,
+    }
+}
+
+/// Print an integer sequence whose values fit in an `i64`, without
+/// the arbitrary precision arithmetic of [`print_seq_integers`].
+fn print_seq_i64(
+    writer: &mut impl Write,
+    (first, increment, last): (i64, i64, i64),
+    separator: &str,
+    terminator: &str,
+    pad: bool,
+    padding: usize,
+) -> std::io::Result<()> {
+    let mut value = first;
+    let mut is_first_iteration = true;
+    while !done_printing(&value, &increment, &last) {
+        if !is_first_iteration {
+            writer.write_all(separator.as_bytes())?;
+        }
+        if pad {
+            write!(writer, "{value:0padding$}")?;
+        } else {
+            write!(writer, "{value}")?;
+        }
+        is_first_iteration = false;
+        // Beyond `i64`, the value is past `last`.
+        value = match value.checked_add(increment) {
+            Some(value) => value,
+            None => break,
+        };
+    }
+    if !is_first_iteration {
+        writer.write_all(terminator.as_bytes())?;
+    }
+    writer.flush()
+}
+
+/// Print the integers from `first` to `last`, or without end if `last` is
+/// `None`, by incrementing their decimal digits in place and writing the
+/// output in large blocks.
+///
+/// Both must be nonnegative, and `first` must not be greater than `last`.
+fn print_seq_fast(
+    writer: &mut impl Write,
+    first: &BigInt,
+    last: Option<&BigInt>,
+    separator: &str,
+    terminator: &str,
+) -> std::io::Result<()> {
+    const BLOCK_SIZE: usize = 64 * 1024;
+    let mut value = first.to_string().into_bytes();
+    let last = last.map(|last| last.to_string().into_bytes());
+    let mut block = Vec::with_capacity(2 * BLOCK_SIZE);
+    loop {
+        block.extend_from_slice(&value);
+        if last.as_ref() == Some(&value) {
+            break;
+        }
+        block.extend_from_slice(separator.as_bytes());
+        if block.len() >= BLOCK_SIZE {
+            writer.write_all(&block)?;
+            block.clear();
+        }
+        increment_digits(&mut value);
+    }
+    block.extend_from_slice(terminator.as_bytes());
+    writer.write_all(&block)?;
+    writer.flush()
+}
+
+/// Add one to the decimal number in `digits`.
+fn increment_digits(digits: &mut Vec<u8>) {
+    for digit in digits.iter_mut().rev() {
+        if *digit == b'9' {
+            *digit = b'0';
+        } else {
+            *digit += 1;
+            return;
+        }
+    }
+    digits.insert(0, b'1');
 }
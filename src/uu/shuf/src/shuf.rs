@@ -8,17 +8,15 @@
 // spell-checker:ignore (ToDO) cmdline evec seps rvec fdata
 
 use clap::{crate_version, Arg, ArgAction, Command};
-use memchr::memchr_iter;
-use rand::prelude::SliceRandom;
+use rand::rngs::ThreadRng;
 use rand::RngCore;
-use std::fs::File;
-use std::io::{stdin, stdout, BufReader, BufWriter, Read, Write};
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::{stdin, stdout, BufRead, BufReader, BufWriter, ErrorKind, Read, Write};
 use uucore::display::Quotable;
 use uucore::error::{FromIo, UResult, USimpleError};
 use uucore::format_usage;
 
-mod rand_read_adapter;
-
 enum Mode {
     Default(String),
     Echo(Vec<String>),
@@ -104,20 +102,29 @@ pub fn uumain(args: impl uucore::Args) -> UResult<()> {
 
     match mode {
         Mode::Echo(args) => {
-            let mut evec = args.iter().map(String::as_bytes).collect::<Vec<_>>();
-            find_seps(&mut evec, options.sep);
-            shuf_bytes(&mut evec, options)?;
+            let lines = args.iter().map(String::as_bytes).collect::<Vec<_>>();
+            let mut random = RandomInt::new(options.random_source.as_deref())?;
+            shuf_lines(&lines, &options, &mut random)?;
         }
         Mode::InputRange((b, e)) => {
-            let rvec = (b..e).map(|x| format!("{x}")).collect::<Vec<String>>();
-            let mut rvec = rvec.iter().map(String::as_bytes).collect::<Vec<&[u8]>>();
-            shuf_bytes(&mut rvec, options)?;
+            let mut random = RandomInt::new(options.random_source.as_deref())?;
+            shuf_range(b, e, &options, &mut random)?;
         }
         Mode::Default(filename) => {
-            let fdata = read_input_file(&filename)?;
-            let mut fdata = vec![&fdata[..]];
-            find_seps(&mut fdata, options.sep);
-            shuf_bytes(&mut fdata, options)?;
+            if options.repeat
+                || options.head_count == usize::MAX
+                || input_size(&filename).map_or(false, |size| size <= RESERVOIR_MIN_INPUT)
+            {
+                let fdata = read_input_file(&filename)?;
+                let lines = split_lines(&fdata, options.sep);
+                let mut random = RandomInt::new(options.random_source.as_deref())?;
+                shuf_lines(&lines, &options, &mut random)?;
+            } else {
+                let mut random = RandomInt::new(options.random_source.as_deref())?;
+                let reservoir = read_reservoir(&filename, &options, &mut random)?;
+                let lines = reservoir.iter().map(Vec::as_slice).collect::<Vec<_>>();
+                shuf_lines(&lines, &options, &mut random)?;
+            }
         }
     }
 
@@ -188,14 +195,43 @@ pub fn uu_app() -> Command {
         .arg(Arg::new(options::FILE).value_hint(clap::ValueHint::FilePath))
 }
 
-fn read_input_file(filename: &str) -> UResult<Vec<u8>> {
-    let mut file = BufReader::new(if filename == "-" {
+/// Inputs larger than this, or of unknown size, are read with reservoir
+/// sampling when only some of their lines are output, as in GNU shuf.
+const RESERVOIR_MIN_INPUT: u64 = 8192 * 1024;
+
+/// The size of the regular file `filename`, if it is one.
+fn input_size(filename: &str) -> Option<u64> {
+    let metadata = if filename == "-" {
+        #[cfg(unix)]
+        {
+            fs::metadata("/dev/stdin").ok()?
+        }
+        #[cfg(not(unix))]
+        {
+            return None;
+        }
+    } else {
+        fs::metadata(filename).ok()?
+    };
+    if metadata.is_file() {
+        Some(metadata.len())
+    } else {
+        None
+    }
+}
+
+fn open_input_file(filename: &str) -> UResult<BufReader<Box<dyn Read>>> {
+    Ok(BufReader::new(if filename == "-" {
         Box::new(stdin()) as Box<dyn Read>
     } else {
         let file = File::open(filename)
             .map_err_context(|| format!("failed to open {}", filename.quote()))?;
         Box::new(file) as Box<dyn Read>
-    });
+    }))
+}
+
+fn read_input_file(filename: &str) -> UResult<Vec<u8>> {
+    let mut file = open_input_file(filename)?;
 
     let mut data = Vec::new();
     file.read_to_end(&mut data)
@@ -204,76 +240,123 @@ fn read_input_file(filename: &str) -> UResult<Vec<u8>> {
     Ok(data)
 }
 
-fn find_seps(data: &mut Vec<&[u8]>, sep: u8) {
-    // need to use for loop so we don't borrow the vector as we modify it in place
-    // basic idea:
-    // * We don't care about the order of the result. This lets us slice the slices
-    //   without making a new vector.
-    // * Starting from the end of the vector, we examine each element.
-    // * If that element contains the separator, we remove it from the vector,
-    //   and then sub-slice it into slices that do not contain the separator.
-    // * We maintain the invariant throughout that each element in the vector past
-    //   the ith element does not have any separators remaining.
-    for i in (0..data.len()).rev() {
-        if data[i].contains(&sep) {
-            let this = data.swap_remove(i);
-            let mut p = 0;
-            for i in memchr_iter(sep, this) {
-                data.push(&this[p..i]);
-                p = i + 1;
-            }
-            if p < this.len() {
-                data.push(&this[p..]);
-            }
+/// Split `data` into lines, without their separator; the last line needs none.
+fn split_lines(data: &[u8], sep: u8) -> Vec<&[u8]> {
+    let data = data.strip_suffix(&[sep]).unwrap_or(data);
+    if data.is_empty() {
+        return Vec::new();
+    }
+    data.split(|&b| b == sep).collect()
+}
+
+/// Keep a uniformly random sample of `opts.head_count` lines of the input,
+/// without reading all of it in memory.
+fn read_reservoir(filename: &str, opts: &Options, random: &mut RandomInt) -> UResult<Vec<Vec<u8>>> {
+    let mut file = open_input_file(filename)?;
+    let mut read_line = |line: &mut Vec<u8>| -> UResult<bool> {
+        line.clear();
+        file.read_until(opts.sep, line)
+            .map_err_context(|| format!("failed reading {}", filename.quote()))?;
+        if line.last() == Some(&opts.sep) {
+            line.pop();
+        } else if line.is_empty() {
+            return Ok(false);
         }
+        Ok(true)
+    };
+
+    let k = opts.head_count;
+    let mut reservoir = Vec::new();
+    let mut line = Vec::new();
+    while reservoir.len() < k {
+        if !read_line(&mut line)? {
+            return Ok(reservoir);
+        }
+        reservoir.push(std::mem::take(&mut line));
+    }
+    if k == 0 {
+        return Ok(reservoir);
     }
+
+    // The n-th line replaces a random line of the reservoir with probability k/n;
+    //  like GNU, pick the slot before finding out whether there is such a line.
+    let mut n_lines = k;
+    loop {
+        let j = random.choose(n_lines + 1)?;
+        if !read_line(&mut line)? {
+            break;
+        }
+        if j < k {
+            std::mem::swap(&mut reservoir[j], &mut line);
+        }
+        n_lines += 1;
+    }
+    Ok(reservoir)
 }
 
-fn shuf_bytes(input: &mut Vec<&[u8]>, opts: Options) -> UResult<()> {
-    let mut output = BufWriter::new(match opts.output {
+fn open_output(opts: &Options) -> UResult<BufWriter<Box<dyn Write>>> {
+    Ok(BufWriter::new(match &opts.output {
         None => Box::new(stdout()) as Box<dyn Write>,
         Some(s) => {
             let file = File::create(&s[..])
                 .map_err_context(|| format!("failed to open {} for writing", s.quote()))?;
             Box::new(file) as Box<dyn Write>
         }
-    });
+    }))
+}
 
-    let mut rng = match opts.random_source {
-        Some(r) => {
-            let file = File::open(&r[..])
-                .map_err_context(|| format!("failed to open random source {}", r.quote()))?;
-            WrappedRng::RngFile(rand_read_adapter::ReadRng::new(file))
-        }
-        None => WrappedRng::RngDefault(rand::thread_rng()),
-    };
+fn write_line(output: &mut impl Write, line: &[u8], sep: u8) -> UResult<()> {
+    output
+        .write_all(line)
+        .and_then(|_| output.write_all(&[sep]))
+        .map_err_context(|| "write failed".to_string())
+}
 
-    if input.is_empty() {
-        return Ok(());
+fn shuf_lines(input: &[&[u8]], opts: &Options, random: &mut RandomInt) -> UResult<()> {
+    if opts.repeat {
+        if opts.head_count == 0 {
+            return Ok(());
+        }
+        if input.is_empty() {
+            return Err(USimpleError::new(1, "no lines to repeat"));
+        }
+        let mut output = open_output(opts)?;
+        for _ in 0..opts.head_count {
+            let line = input[random.choose(input.len())?];
+            write_line(&mut output, line, opts.sep)?;
+        }
+    } else {
+        let count = opts.head_count.min(input.len());
+        let permutation = random.permutation(count, input.len())?;
+        let mut output = open_output(opts)?;
+        for i in permutation {
+            write_line(&mut output, input[i], opts.sep)?;
+        }
     }
 
+    Ok(())
+}
+
+/// Shuffle the numbers in `begin..end`, without making a line for each.
+fn shuf_range(begin: usize, end: usize, opts: &Options, random: &mut RandomInt) -> UResult<()> {
+    let n = end.saturating_sub(begin);
     if opts.repeat {
+        if opts.head_count == 0 {
+            return Ok(());
+        }
+        if n == 0 {
+            return Err(USimpleError::new(1, "no lines to repeat"));
+        }
+        let mut output = open_output(opts)?;
         for _ in 0..opts.head_count {
-            // Returns None is the slice is empty. We checked this before, so
-            // this is safe.
-            let r = input.choose(&mut rng).unwrap();
-
-            output
-                .write_all(r)
-                .map_err_context(|| "write failed".to_string())?;
-            output
-                .write_all(&[opts.sep])
-                .map_err_context(|| "write failed".to_string())?;
+            let number = begin + random.choose(n)?;
+            write_line(&mut output, number.to_string().as_bytes(), opts.sep)?;
         }
     } else {
-        let (shuffled, _) = input.partial_shuffle(&mut rng, opts.head_count);
-        for r in shuffled {
-            output
-                .write_all(r)
-                .map_err_context(|| "write failed".to_string())?;
-            output
-                .write_all(&[opts.sep])
-                .map_err_context(|| "write failed".to_string())?;
+        let permutation = random.permutation(opts.head_count.min(n), n)?;
+        let mut output = open_output(opts)?;
+        for i in permutation {
+            write_line(&mut output, (begin + i).to_string().as_bytes(), opts.sep)?;
         }
     }
 
@@ -305,37 +388,125 @@ fn parse_head_count(headcounts: Vec<String>) -> Result<usize, String> {
     Ok(result)
 }
 
-enum WrappedRng {
-    RngFile(rand_read_adapter::ReadRng<File>),
-    RngDefault(rand::rngs::ThreadRng),
+enum RandomSource {
+    File(BufReader<File>, String),
+    Default(ThreadRng),
 }
 
-impl RngCore for WrappedRng {
-    fn next_u32(&mut self) -> u32 {
-        match self {
-            Self::RngFile(r) => r.next_u32(),
-            Self::RngDefault(r) => r.next_u32(),
-        }
+/// Uniformly distributed random integers, which use the random bytes just as
+/// GNU shuf does, so that a given `--random-source` produces the same output.
+struct RandomInt {
+    source: RandomSource,
+    // A random number in 0..=randmax which is left over from previous draws.
+    randnum: u64,
+    randmax: u64,
+}
+
+impl RandomInt {
+    fn new(random_source: Option<&str>) -> UResult<Self> {
+        let source = match random_source {
+            Some(r) => {
+                let file = File::open(r).map_err_context(|| r.maybe_quote().to_string())?;
+                RandomSource::File(BufReader::new(file), r.to_string())
+            }
+            None => RandomSource::Default(rand::thread_rng()),
+        };
+        Ok(Self {
+            source,
+            randnum: 0,
+            randmax: 0,
+        })
     }
 
-    fn next_u64(&mut self) -> u64 {
-        match self {
-            Self::RngFile(r) => r.next_u64(),
-            Self::RngDefault(r) => r.next_u64(),
+    fn fill_bytes(&mut self, buf: &mut [u8]) -> UResult<()> {
+        match &mut self.source {
+            RandomSource::File(file, name) => file.read_exact(buf).map_err(|e| {
+                if e.kind() == ErrorKind::UnexpectedEof {
+                    USimpleError::new(1, format!("{}: end of file", name.quote()))
+                } else {
+                    e.map_err_context(|| name.maybe_quote().to_string())
+                }
+            }),
+            RandomSource::Default(rng) => {
+                rng.fill_bytes(buf);
+                Ok(())
+            }
         }
     }
 
-    fn fill_bytes(&mut self, dest: &mut [u8]) {
-        match self {
-            Self::RngFile(r) => r.fill_bytes(dest),
-            Self::RngDefault(r) => r.fill_bytes(dest),
+    /// A random number in `0..=genmax`, as computed by gnulib's randint.c.
+    fn gen_max(&mut self, genmax: u64) -> UResult<u64> {
+        let choices = genmax.wrapping_add(1);
+
+        loop {
+            if self.randmax < genmax {
+                // Append as few random bytes as needed to reach genmax.
+                let mut rmax = self.randmax;
+                let mut len = 0;
+                while rmax < genmax {
+                    rmax = (rmax << 8) + u64::from(u8::MAX);
+                    len += 1;
+                }
+                let mut buf = [0; 8];
+                self.fill_bytes(&mut buf[..len])?;
+                for b in &buf[..len] {
+                    self.randnum = (self.randnum << 8) + u64::from(*b);
+                    self.randmax = (self.randmax << 8) + u64::from(u8::MAX);
+                }
+            }
+
+            if self.randmax == genmax {
+                let randnum = self.randnum;
+                self.randnum = 0;
+                self.randmax = 0;
+                return Ok(randnum);
+            }
+
+            // Use randnum modulo choices if that is fair, which is the case unless
+            //  it falls in the last, incomplete, range of choices; keep the
+            //  randomness which is not used either way.
+            let excess_choices = self.randmax - genmax;
+            let unusable_choices = excess_choices % choices;
+            let last_usable_choice = self.randmax - unusable_choices;
+            let reduced_randnum = self.randnum % choices;
+
+            if self.randnum <= last_usable_choice {
+                self.randnum /= choices;
+                self.randmax = excess_choices / choices;
+                return Ok(reduced_randnum);
+            }
+            self.randnum = reduced_randnum;
+            self.randmax = unusable_choices - 1;
         }
     }
 
-    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
-        match self {
-            Self::RngFile(r) => r.try_fill_bytes(dest),
-            Self::RngDefault(r) => r.try_fill_bytes(dest),
+    /// A random number in `0..choices`.
+    fn choose(&mut self, choices: usize) -> UResult<usize> {
+        debug_assert!(choices > 0);
+        Ok(self.gen_max(choices as u64 - 1)? as usize)
+    }
+
+    /// The first `count` elements of a random permutation of `0..n`, as
+    /// computed by gnulib's randperm.c.
+    fn permutation(&mut self, count: usize, n: usize) -> UResult<Vec<usize>> {
+        if count <= n / 3 {
+            // Only keep track of the elements which moved.
+            let mut moved = HashMap::new();
+            let mut permutation = Vec::with_capacity(count);
+            for i in 0..count {
+                let j = i + self.choose(n - i)?;
+                let v_i = *moved.get(&i).unwrap_or(&i);
+                permutation.push(moved.insert(j, v_i).unwrap_or(j));
+            }
+            Ok(permutation)
+        } else {
+            let mut permutation = (0..n).collect::<Vec<_>>();
+            for i in 0..count {
+                let j = i + self.choose(n - i)?;
+                permutation.swap(i, j);
+            }
+            permutation.truncate(count);
+            Ok(permutation)
         }
     }
 }
@@ -5,8 +5,12 @@
 //  * For the full copyright and license information, please view the LICENSE
 //  * file that was distributed with this source code.
 
+use std::io;
+#[cfg(not(any(target_os = "linux", target_os = "android")))]
 use std::thread;
 use std::time::Duration;
+#[cfg(not(any(target_os = "linux", target_os = "android")))]
+use std::time::SystemTime;
 
 use uucore::{
     error::{UResult, USimpleError, UUsageError},
@@ -22,11 +26,25 @@ const USAGE: &str = "\
 static LONG_HELP: &str = "Pause for NUMBER seconds.  SUFFIX may be 's' for seconds (the default),
 'm' for minutes, 'h' for hours or 'd' for days.  Unlike most implementations
 that require NUMBER be an integer, here NUMBER may be an arbitrary floating
-point number.  Given two or more arguments, pause for the amount of time
-specified by the sum of their values.";
+point number, with an exponent like in '1.5e3'.  It is converted exactly,
+rounded up to the next nanosecond.  Given two or more arguments, pause for the
+amount of time specified by the sum of their values.
+
+The pause is measured against the monotonic clock by default, so that setting
+the system clock doesn't change it.  With --clock=realtime, sleep pauses until
+the system clock reaches the time it would have reached after the pause
+instead.";
 
 mod options {
     pub const NUMBER: &str = "NUMBER";
+    pub const CLOCK: &str = "clock";
+}
+
+/// The clock that the pause is measured against.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Clock {
+    Monotonic,
+    Realtime,
 }
 
 #[uucore::main]
@@ -46,8 +64,15 @@ pub fn uumain(args: impl uucore::Args) -> UResult<()> {
         })?
         .map(|s| s.as_str())
         .collect::<Vec<_>>();
+    let clock = match matches
+        .get_one::<String>(options::CLOCK)
+        .map(|s| s.as_str())
+    {
+        Some("realtime") => Clock::Realtime,
+        _ => Clock::Monotonic,
+    };
 
-    sleep(&numbers)
+    sleep(&numbers, clock)
 }
 
 pub fn uu_app() -> Command {
@@ -63,22 +88,89 @@ pub fn uu_app() -> Command {
                 .value_name(options::NUMBER)
                 .action(ArgAction::Append),
         )
+        .arg(
+            Arg::new(options::CLOCK)
+                .long(options::CLOCK)
+                .help("measure the pause against CLOCK: 'monotonic' (the default) or 'realtime'")
+                .value_name("CLOCK")
+                .value_parser(["monotonic", "realtime"]),
+        )
 }
 
-fn sleep(args: &[&str]) -> UResult<()> {
+fn sleep(args: &[&str], clock: Clock) -> UResult<()> {
     let mut arg_error = false;
     let intervals = args.iter().map(|s| match uucore::parse_time::from_str(s) {
         Ok(result) => result,
         Err(err) => {
             arg_error = true;
             show!(USimpleError::new(1, err));
-            Duration::new(0, 0)
+            Duration::ZERO
         }
     });
-    let sleep_dur = intervals.fold(Duration::new(0, 0), |acc, n| acc + n);
+    let sleep_dur = intervals.fold(Duration::ZERO, Duration::saturating_add);
     if arg_error {
         return Err(UUsageError::new(1, ""));
     };
-    thread::sleep(sleep_dur);
+    sleep_for(sleep_dur, clock)?;
+    Ok(())
+}
+
+/// Pause until `clock` reaches its current time plus `duration`.
+#[cfg(any(target_os = "linux", target_os = "android"))]
+fn sleep_for(duration: Duration, clock: Clock) -> io::Result<()> {
+    let clock_id = match clock {
+        Clock::Monotonic => libc::CLOCK_MONOTONIC,
+        Clock::Realtime => libc::CLOCK_REALTIME,
+    };
+    let mut now = libc::timespec {
+        tv_sec: 0,
+        tv_nsec: 0,
+    };
+    // SAFETY: `now` is a valid timespec to write to.
+    if unsafe { libc::clock_gettime(clock_id, &mut now) } != 0 {
+        return Err(io::Error::last_os_error());
+    }
+    let deadline = Duration::new(now.tv_sec as u64, now.tv_nsec as u32).saturating_add(duration);
+    // Deadlines that can't be represented are forever anyway.
+    let deadline = libc::timespec {
+        tv_sec: libc::time_t::try_from(deadline.as_secs()).unwrap_or(libc::time_t::MAX),
+        tv_nsec: deadline.subsec_nanos() as _,
+    };
+    loop {
+        // SAFETY: `deadline` is a valid timespec, and the remaining time is
+        // not needed with an absolute deadline.
+        match unsafe {
+            libc::clock_nanosleep(
+                clock_id,
+                libc::TIMER_ABSTIME,
+                &deadline,
+                std::ptr::null_mut(),
+            )
+        } {
+            0 => return Ok(()),
+            libc::EINTR => continue,
+            errno => return Err(io::Error::from_raw_os_error(errno)),
+        }
+    }
+}
+
+/// Pause until `clock` reaches its current time plus `duration`.
+#[cfg(not(any(target_os = "linux", target_os = "android")))]
+fn sleep_for(duration: Duration, clock: Clock) -> io::Result<()> {
+    match clock {
+        Clock::Monotonic => thread::sleep(duration),
+        Clock::Realtime => match SystemTime::now().checked_add(duration) {
+            // Wake up regularly to notice when the clock is set.
+            Some(deadline) => {
+                while let Ok(remaining) = deadline.duration_since(SystemTime::now()) {
+                    if remaining.is_zero() {
+                        break;
+                    }
+                    thread::sleep(remaining.min(Duration::from_secs(1)));
+                }
+            }
+            None => thread::sleep(duration),
+        },
+    }
     Ok(())
 }
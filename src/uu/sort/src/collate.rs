@@ -0,0 +1,124 @@
+//  * This file is part of the uutils coreutils package.
+//  *
+//  * For the full copyright and license information, please view the LICENSE
+//  * file that was distributed with this source code.
+
+//! Locale-aware string comparisons.
+//!
+//! Strings are compared with the C library's `strcoll`, which orders them by the `LC_COLLATE`
+//! category of the current locale. This is only built with the `feat_collate` feature.
+
+use std::borrow::Cow;
+use std::cmp::Ordering;
+use std::ffi::{CStr, CString};
+
+use crate::custom_str_cmp::filter_char;
+
+/// Sets the locale used for collation to `locale`, or to the locale of the environment
+/// (`LC_ALL`, `LC_COLLATE` or `LANG`) if `locale` is empty.
+/// Returns false if the locale is not available.
+pub fn set_locale(locale: &str) -> bool {
+    match CString::new(locale) {
+        // SAFETY: the locale is set before any threads are started.
+        Ok(locale) => unsafe { !libc::setlocale(libc::LC_COLLATE, locale.as_ptr()).is_null() },
+        Err(_) => false,
+    }
+}
+
+/// Returns the name of the locale used for collation, or `None` if it just compares bytes.
+pub fn locale_name() -> Option<String> {
+    // SAFETY: a null locale queries the current locale without changing it.
+    let name = unsafe { libc::setlocale(libc::LC_COLLATE, std::ptr::null()) };
+    if name.is_null() {
+        return None;
+    }
+    // SAFETY: setlocale returns a NUL-terminated string.
+    let name = unsafe { CStr::from_ptr(name) }.to_string_lossy();
+    if name == "C" || name == "POSIX" {
+        None
+    } else {
+        Some(name.into_owned())
+    }
+}
+
+/// Compares `a` and `b` like `custom_str_cmp`, but by the collation order of the locale.
+pub fn collate_str_cmp(
+    a: &str,
+    b: &str,
+    ignore_non_printing: bool,
+    ignore_non_dictionary: bool,
+    ignore_case: bool,
+) -> Ordering {
+    let transform = |s| -> Cow<str> {
+        if !(ignore_case || ignore_non_dictionary || ignore_non_printing) {
+            return Cow::Borrowed(s);
+        }
+        s.chars()
+            .filter(|&c| filter_char(c, ignore_non_printing, ignore_non_dictionary))
+            .map(|c| {
+                if ignore_case {
+                    c.to_ascii_uppercase()
+                } else {
+                    c
+                }
+            })
+            .collect()
+    };
+    let (a, b) = (transform(a), transform(b));
+
+    // strcoll stops at NUL characters, so the parts between them are compared separately.
+    let mut a_parts = a.split('\0');
+    let mut b_parts = b.split('\0');
+    loop {
+        match (a_parts.next(), b_parts.next()) {
+            (None, None) => return Ordering::Equal,
+            (Some(_), None) => return Ordering::Greater,
+            (None, Some(_)) => return Ordering::Less,
+            (Some(a_part), Some(b_part)) => {
+                let ordering = strcoll(a_part, b_part);
+                if ordering != Ordering::Equal {
+                    return ordering;
+                }
+            }
+        }
+    }
+}
+
+fn strcoll(a: &str, b: &str) -> Ordering {
+    // The parts of a line between NUL characters can't contain NUL characters.
+    let a = CString::new(a).unwrap();
+    let b = CString::new(b).unwrap();
+    // SAFETY: both strings are NUL-terminated.
+    unsafe { libc::strcoll(a.as_ptr(), b.as_ptr()) }.cmp(&0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_c_locale() {
+        assert!(set_locale("C"));
+        assert_eq!(locale_name(), None);
+        assert_eq!(
+            collate_str_cmp("B", "a", false, false, false),
+            Ordering::Less
+        );
+        assert_eq!(
+            collate_str_cmp("B", "a", false, false, true),
+            Ordering::Greater
+        );
+        assert_eq!(
+            collate_str_cmp("a\0b", "a\0c", false, false, false),
+            Ordering::Less
+        );
+        assert_eq!(
+            collate_str_cmp("a", "a\0", false, false, false),
+            Ordering::Less
+        );
+        assert_eq!(
+            collate_str_cmp("a-b", "ab", false, true, false),
+            Ordering::Equal
+        );
+    }
+}
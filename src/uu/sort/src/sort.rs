@@ -15,6 +15,8 @@
 
 mod check;
 mod chunks;
+#[cfg(feature = "feat_collate")]
+mod collate;
 mod custom_str_cmp;
 mod ext_sort;
 mod merge;
@@ -45,9 +47,9 @@ use std::str::Utf8Error;
 use unicode_width::UnicodeWidthStr;
 use uucore::display::Quotable;
 use uucore::error::{set_exit_code, strip_errno, UError, UResult, USimpleError, UUsageError};
-use uucore::format_usage;
 use uucore::parse_size::{ParseSizeError, Parser};
 use uucore::version_cmp::version_cmp;
+use uucore::{format_usage, show_error};
 
 use crate::tmp_dir::TmpDirWrapper;
 
@@ -116,6 +118,10 @@ mod options {
     pub const TMP_DIR: &str = "temporary-directory";
     pub const COMPRESS_PROG: &str = "compress-program";
     pub const BATCH_SIZE: &str = "batch-size";
+    pub const RANDOM_SOURCE: &str = "random-source";
+    pub const SEED: &str = "seed";
+    #[cfg(feature = "feat_collate")]
+    pub const COLLATE: &str = "collate";
 
     pub const FILES: &str = "files";
 }
@@ -316,7 +322,9 @@ pub struct GlobalSettings {
     salt: Option<[u8; 16]>,
     selectors: Vec<FieldSelector>,
     separator: Option<char>,
-    threads: String,
+    /// Compare by the collation order of the locale instead of by bytes.
+    #[cfg(feature = "feat_collate")]
+    collate: bool,
     zero_terminated: bool,
     buffer_size: usize,
     compress_prog: Option<String>,
@@ -393,7 +401,8 @@ impl Default for GlobalSettings {
             salt: None,
             selectors: vec![],
             separator: None,
-            threads: String::new(),
+            #[cfg(feature = "feat_collate")]
+            collate: false,
             zero_terminated: false,
             buffer_size: DEFAULT_BUF_SIZE,
             compress_prog: None,
@@ -454,6 +463,29 @@ impl KeySettings {
         Ok(())
     }
 
+    /// Returns the short options that correspond to these settings, in the order GNU sort
+    /// lists them.
+    fn short_options(&self, ignore_blanks: bool) -> Vec<char> {
+        let mut options = vec![];
+        let mut push_if = |set: bool, option: char| {
+            if set {
+                options.push(option);
+            }
+        };
+        push_if(ignore_blanks, 'b');
+        push_if(self.dictionary_order, 'd');
+        push_if(self.ignore_case, 'f');
+        push_if(self.mode == SortMode::GeneralNumeric, 'g');
+        push_if(self.mode == SortMode::HumanNumeric, 'h');
+        push_if(self.ignore_non_printing, 'i');
+        push_if(self.mode == SortMode::Month, 'M');
+        push_if(self.mode == SortMode::Numeric, 'n');
+        push_if(self.mode == SortMode::Random, 'R');
+        push_if(self.reverse, 'r');
+        push_if(self.mode == SortMode::Version, 'V');
+        options
+    }
+
     fn set_dictionary_order(&mut self) -> Result<(), String> {
         Self::check_compatibility(self.mode, self.ignore_non_printing, true)?;
         self.dictionary_order = true;
@@ -906,6 +938,47 @@ impl FieldSelector {
         }
     }
 
+    /// Prints the warnings of --debug about this key, which is the `number`th key.
+    fn print_debug_warnings(&self, number: usize, settings: &GlobalSettings) {
+        let zero_width = matches!(&self.to, Some(to) if to.field < self.from.field);
+        if zero_width {
+            show_error!("key {} has zero width and will be ignored", number);
+        }
+
+        // Numbers and months skip leading blanks anyway.
+        let implicit_skip = matches!(
+            self.settings.mode,
+            SortMode::Numeric | SortMode::HumanNumeric | SortMode::GeneralNumeric | SortMode::Month
+        );
+        // A key like -k1.2,1.5 selects characters of the line rather than of fields.
+        let line_offset = matches!(&self.to, Some(to) if to.field == 1 && to.char != 0);
+        let blanks_significant_at_end =
+            matches!(&self.to, Some(to) if !to.ignore_blanks && to.char != 0);
+        if !zero_width
+            && settings.separator.is_none()
+            && !line_offset
+            && ((!self.from.ignore_blanks && (!implicit_skip || self.from.char != 1))
+                || blanks_significant_at_end)
+        {
+            show_error!(
+                "leading blanks are significant in key {}; consider also specifying 'b'",
+                number
+            );
+        }
+
+        // Field separators could be interpreted as part of a number.
+        if matches!(
+            self.settings.mode,
+            SortMode::Numeric | SortMode::HumanNumeric | SortMode::GeneralNumeric
+        ) && self
+            .to
+            .as_ref()
+            .map_or(true, |to| self.from.field < to.field)
+        {
+            show_error!("key {} is numeric and spans multiple fields", number);
+        }
+    }
+
     /// Get the selection that corresponds to this selector for the line.
     /// If needs_fields returned false, tokens may be empty.
     fn get_selection<'a>(&self, line: &'a str, tokens: &[Field]) -> Selection<'a> {
@@ -1133,7 +1206,7 @@ pub fn uumain(args: impl uucore::Args) -> UResult<()> {
             .map(|s| s.as_str())
             == Some("random")
     {
-        settings.salt = Some(get_rand_string());
+        settings.salt = Some(get_salt(&matches)?);
         SortMode::Random
     } else {
         SortMode::Default
@@ -1141,13 +1214,21 @@ pub fn uumain(args: impl uucore::Args) -> UResult<()> {
 
     settings.dictionary_order = matches.get_flag(options::DICTIONARY_ORDER);
     settings.ignore_non_printing = matches.get_flag(options::IGNORE_NONPRINTING);
-    if matches.contains_id(options::PARALLEL) {
-        // "0" is default - threads = num of cores
-        settings.threads = matches
-            .get_one::<String>(options::PARALLEL)
-            .map(String::from)
-            .unwrap_or_else(|| "0".to_string());
-        env::set_var("RAYON_NUM_THREADS", &settings.threads);
+    if let Some(threads) = matches.get_one::<String>(options::PARALLEL) {
+        let threads: usize = threads.parse().map_err(|_| {
+            UUsageError::new(
+                2,
+                format!("invalid --parallel argument {}", threads.quote()),
+            )
+        })?;
+        if threads == 0 {
+            return Err(UUsageError::new(2, "number in parallel must be nonzero"));
+        }
+        // Without --parallel, rayon uses one thread per available CPU.
+        rayon::ThreadPoolBuilder::new()
+            .num_threads(threads)
+            .build_global()
+            .map_err(|e| USimpleError::new(2, format!("failed to set up threads: {e}")))?;
     }
 
     settings.buffer_size =
@@ -1179,6 +1260,21 @@ pub fn uumain(args: impl uucore::Args) -> UResult<()> {
         })?;
     }
 
+    #[cfg(feature = "feat_collate")]
+    if let Some(locale) = matches.get_one::<String>(options::COLLATE) {
+        if !collate::set_locale(locale) {
+            if locale.is_empty() {
+                show_error!("failed to set locale");
+            } else {
+                return Err(USimpleError::new(
+                    2,
+                    format!("invalid locale {}", locale.quote()),
+                ));
+            }
+        }
+        settings.collate = true;
+    }
+
     settings.zero_terminated = matches.get_flag(options::ZERO_TERMINATED);
     settings.merge = matches.get_flag(options::MERGE);
 
@@ -1242,7 +1338,7 @@ pub fn uumain(args: impl uucore::Args) -> UResult<()> {
         for value in values {
             let selector = FieldSelector::parse(value, &settings)?;
             if selector.settings.mode == SortMode::Random && settings.salt.is_none() {
-                settings.salt = Some(get_rand_string());
+                settings.salt = Some(get_salt(&matches)?);
             }
             settings.selectors.push(selector);
         }
@@ -1279,6 +1375,10 @@ pub fn uumain(args: impl uucore::Args) -> UResult<()> {
             .map(|s| s.as_str()),
     )?;
 
+    if settings.debug {
+        print_debug_warnings(&settings, matches.contains_id(options::KEY));
+    }
+
     settings.init_precomputed();
 
     let result = exec(&mut files, &settings, output, &mut tmp_dir);
@@ -1288,8 +1388,56 @@ pub fn uumain(args: impl uucore::Args) -> UResult<()> {
     result
 }
 
+/// Prints warnings about keys and options that may not do what the user expects, like GNU sort
+/// does with --debug. Without explicit keys, the global options apply to the whole line.
+fn print_debug_warnings(settings: &GlobalSettings, keys_given: bool) {
+    #[cfg(feature = "feat_collate")]
+    let locale = collate::locale_name().filter(|_| settings.collate);
+    #[cfg(not(feature = "feat_collate"))]
+    let locale: Option<String> = None;
+    match locale {
+        Some(locale) => show_error!(
+            "text ordering performed using {} sorting rules",
+            locale.quote()
+        ),
+        None => show_error!("text ordering performed using simple byte comparison"),
+    }
+    if !keys_given {
+        return;
+    }
+
+    let global_settings = KeySettings::from(settings);
+    let mut unused_options = global_settings.short_options(settings.ignore_leading_blanks);
+    for (i, selector) in settings.selectors.iter().enumerate() {
+        selector.print_debug_warnings(i + 1, settings);
+
+        let ignore_blanks = selector.from.ignore_blanks
+            || selector.to.as_ref().map_or(false, |to| to.ignore_blanks);
+        let used_options = selector.settings.short_options(ignore_blanks);
+        unused_options.retain(|option| !used_options.contains(option));
+    }
+
+    // -r also reverses the last-resort comparison, unless that is disabled.
+    let last_resort = !settings.stable && !settings.unique;
+    let reverse_unused = unused_options.contains(&'r');
+    if last_resort {
+        unused_options.retain(|&option| option != 'r');
+    }
+    match unused_options.len() {
+        0 => {}
+        1 => show_error!("option '-{}' is ignored", unused_options[0]),
+        _ => show_error!(
+            "options '-{}' are ignored",
+            unused_options.iter().collect::<String>()
+        ),
+    }
+    if reverse_unused && last_resort {
+        show_error!("option '-r' only applies to last-resort comparison");
+    }
+}
+
 pub fn uu_app() -> Command {
-    Command::new(uucore::util_name())
+    let cmd = Command::new(uucore::util_name())
         .version(crate_version!())
         .about(ABOUT)
         .after_help(LONG_HELP_KEYS)
@@ -1352,6 +1500,21 @@ pub fn uu_app() -> Command {
             'R',
             "shuffle in random order",
         ))
+        .arg(
+            Arg::new(options::RANDOM_SOURCE)
+                .long(options::RANDOM_SOURCE)
+                .help("get random bytes from FILE")
+                .value_name("FILE")
+                .value_parser(ValueParser::os_string())
+                .value_hint(clap::ValueHint::FilePath),
+        )
+        .arg(
+            Arg::new(options::SEED)
+                .long(options::SEED)
+                .help("shuffle reproducibly, in the order determined by STRING")
+                .value_name("STRING")
+                .conflicts_with(options::RANDOM_SOURCE),
+        )
         .arg(
             Arg::new(options::DICTIONARY_ORDER)
                 .short('d')
@@ -1529,7 +1692,21 @@ pub fn uu_app() -> Command {
                 .action(ArgAction::Append)
                 .value_parser(ValueParser::os_string())
                 .value_hint(clap::ValueHint::FilePath),
-        )
+        );
+    #[cfg(feature = "feat_collate")]
+    let cmd = cmd.arg(
+        Arg::new(options::COLLATE)
+            .long(options::COLLATE)
+            .help(
+                "compare by the collation order of LOCALE instead of by bytes; \
+                 LOCALE defaults to the one set by LC_ALL, LC_COLLATE or LANG",
+            )
+            .value_name("LOCALE")
+            .require_equals(true)
+            .num_args(0..=1)
+            .default_missing_value(""),
+    );
+    cmd
 }
 
 fn exec(
@@ -1629,6 +1806,14 @@ fn compare_by<'a>(
                 parsed_float_index += 1;
                 general_numeric_compare(a_float, b_float)
             }
+            #[cfg(feature = "feat_collate")]
+            SortMode::Default if global_settings.collate => collate::collate_str_cmp(
+                a_str,
+                b_str,
+                settings.ignore_non_printing,
+                settings.dictionary_order,
+                settings.ignore_case,
+            ),
             SortMode::Month => month_compare(a_str, b_str),
             SortMode::Version => version_cmp(a_str, b_str),
             SortMode::Default => custom_str_cmp(
@@ -1757,6 +1942,33 @@ fn get_rand_string() -> [u8; 16] {
     thread_rng().sample(rand::distributions::Standard)
 }
 
+/// Returns the salt for random sorting, which is derived from --seed, read from
+/// --random-source, or random.
+fn get_salt(matches: &clap::ArgMatches) -> UResult<[u8; 16]> {
+    let mut salt = [0; 16];
+    if let Some(seed) = matches.get_one::<String>(options::SEED) {
+        salt[..8].copy_from_slice(&get_hash(&(seed, 0u8)).to_le_bytes());
+        salt[8..].copy_from_slice(&get_hash(&(seed, 1u8)).to_le_bytes());
+    } else if let Some(path) = matches.get_one::<OsString>(options::RANDOM_SOURCE) {
+        let mut file = File::open(path).map_err(|error| SortError::OpenFailed {
+            path: path.to_string_lossy().into_owned(),
+            error,
+        })?;
+        file.read_exact(&mut salt)
+            .map_err(|error| SortError::ReadFailed {
+                path: PathBuf::from(path),
+                error: if error.kind() == std::io::ErrorKind::UnexpectedEof {
+                    std::io::Error::new(error.kind(), "end of file")
+                } else {
+                    error
+                },
+            })?;
+    } else {
+        salt = get_rand_string();
+    }
+    Ok(salt)
+}
+
 fn get_hash<T: Hash>(t: &T) -> u64 {
     let mut s = FnvHasher::default();
     t.hash(&mut s);
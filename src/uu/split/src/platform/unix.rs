@@ -1,11 +1,14 @@
 use std::env;
 use std::io::Write;
 use std::io::{BufWriter, Error, ErrorKind, Result};
+use std::os::unix::process::ExitStatusExt;
 use std::path::Path;
-use std::process::{Child, Command, Stdio};
+use std::process::{Child, ChildStdin, Command, Stdio};
 use uucore::crash;
+use uucore::display::Quotable;
 use uucore::fs;
 use uucore::fs::FileInformation;
+use uucore::signals::signal_name_by_value;
 
 /// A writer that writes to a shell_process' stdin
 ///
@@ -14,54 +17,51 @@ use uucore::fs::FileInformation;
 struct FilterWriter {
     /// Running shell process
     shell_process: Child,
+    /// The shell command run by `shell_process`
+    command: String,
+    /// Path of the output file, forwarded to the command as $FILE
+    filepath: String,
+    /// Whether the command has closed its input, so the rest of the chunk is discarded
+    closed: bool,
 }
 
-impl Write for FilterWriter {
-    fn write(&mut self, buf: &[u8]) -> Result<usize> {
-        self.shell_process
-            .stdin
-            .as_mut()
-            .expect("failed to get shell stdin")
-            .write(buf)
-    }
-    fn flush(&mut self) -> Result<()> {
-        self.shell_process
+impl FilterWriter {
+    /// Calls `write` on the stdin of the shell process, unless the command stopped reading.
+    ///
+    /// Like GNU split, a command that doesn't read all of its input (e.g. `head`) is not an
+    /// error: the rest of the chunk is discarded when writing to it fails with EPIPE.
+    fn write_stdin<T>(
+        &mut self,
+        default: T,
+        write: impl FnOnce(&mut ChildStdin) -> Result<T>,
+    ) -> Result<T> {
+        if self.closed {
+            return Ok(default);
+        }
+        let stdin = self
+            .shell_process
             .stdin
             .as_mut()
-            .expect("failed to get shell stdin")
-            .flush()
-    }
-}
-
-/// Have an environment variable set at a value during this lifetime
-struct WithEnvVarSet {
-    /// Env var key
-    _previous_var_key: String,
-    /// Previous value set to this key
-    _previous_var_value: std::result::Result<String, env::VarError>,
-}
-impl WithEnvVarSet {
-    /// Save previous value assigned to key, set key=value
-    fn new(key: &str, value: &str) -> Self {
-        let previous_env_value = env::var(key);
-        env::set_var(key, value);
-        Self {
-            _previous_var_key: String::from(key),
-            _previous_var_value: previous_env_value,
+            .expect("failed to get shell stdin");
+        match write(stdin) {
+            Err(e) if e.kind() == ErrorKind::BrokenPipe => {
+                self.closed = true;
+                Ok(default)
+            }
+            result => result,
         }
     }
 }
 
-impl Drop for WithEnvVarSet {
-    /// Restore previous value now that this is being dropped by context
-    fn drop(&mut self) {
-        if let Ok(ref prev_value) = self._previous_var_value {
-            env::set_var(&self._previous_var_key, prev_value);
-        } else {
-            env::remove_var(&self._previous_var_key);
-        }
+impl Write for FilterWriter {
+    fn write(&mut self, buf: &[u8]) -> Result<usize> {
+        self.write_stdin(buf.len(), |stdin| stdin.write(buf))
+    }
+    fn flush(&mut self) -> Result<()> {
+        self.write_stdin((), |stdin| stdin.flush())
     }
 }
+
 impl FilterWriter {
     /// Create a new filter running a command with $FILE pointing at the output name
     ///
@@ -70,37 +70,55 @@ impl FilterWriter {
     /// * `command` - The shell command to execute
     /// * `filepath` - Path of the output file (forwarded to command as $FILE)
     fn new(command: &str, filepath: &str) -> Result<Self> {
-        // set $FILE, save previous value (if there was one)
-        let _with_env_var_set = WithEnvVarSet::new("FILE", filepath);
-
         let shell_process =
             Command::new(env::var("SHELL").unwrap_or_else(|_| "/bin/sh".to_owned()))
                 .arg("-c")
                 .arg(command)
+                .env("FILE", filepath)
                 .stdin(Stdio::piped())
                 .spawn()?;
 
-        Ok(Self { shell_process })
+        Ok(Self {
+            shell_process,
+            command: command.to_owned(),
+            filepath: filepath.to_owned(),
+            closed: false,
+        })
     }
 }
 
 impl Drop for FilterWriter {
-    /// flush stdin, close it and wait on `shell_process` before dropping self
+    /// Close stdin and wait on `shell_process` before dropping self, exiting like GNU split if
+    /// the command failed
     fn drop(&mut self) {
-        {
-            // close stdin by dropping it
-            let _stdin = self.shell_process.stdin.as_mut();
-        }
+        // close stdin by dropping it
+        drop(self.shell_process.stdin.take());
         let exit_status = self
             .shell_process
             .wait()
             .expect("Couldn't wait for child process");
         if let Some(return_code) = exit_status.code() {
             if return_code != 0 {
-                crash!(1, "Shell process returned {}", return_code);
+                crash!(
+                    return_code,
+                    "with FILE={}, exit {} from command: {}",
+                    self.filepath.maybe_quote(),
+                    return_code,
+                    self.command
+                );
+            }
+        } else if let Some(signal) = exit_status.signal() {
+            let name = signal_name_by_value(signal as usize).unwrap_or_else(|| signal.to_string());
+            // A command killed by SIGPIPE only failed to write its own output.
+            if name != "PIPE" {
+                crash!(
+                    128 + signal,
+                    "with FILE={}, signal {} from command: {}",
+                    self.filepath.maybe_quote(),
+                    name,
+                    self.command
+                );
             }
-        } else {
-            crash!(1, "Shell process terminated by signal")
         }
     }
 }
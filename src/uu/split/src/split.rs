@@ -17,9 +17,9 @@ use clap::ArgAction;
 use clap::{crate_version, parser::ValueSource, Arg, ArgMatches, Command};
 use std::env;
 use std::fmt;
-use std::fs::{metadata, File};
+use std::fs::File;
 use std::io;
-use std::io::{stdin, BufRead, BufReader, BufWriter, ErrorKind, Read, Write};
+use std::io::{stdin, BufRead, BufReader, BufWriter, ErrorKind, Read, Seek, SeekFrom, Write};
 use std::path::Path;
 use uucore::display::Quotable;
 use uucore::error::{FromIo, UIoError, UResult, USimpleError, UUsageError};
@@ -51,7 +51,15 @@ const USAGE: &str = "{} [OPTION]... [INPUT [PREFIX]]";
 const AFTER_HELP: &str = "\
     Output fixed-size pieces of INPUT to PREFIXaa, PREFIXab, ...; default \
     size is 1000, and default PREFIX is 'x'. With no INPUT, or when INPUT is \
-    -, read standard input.";
+    -, read standard input.\n\
+    \n\
+    CHUNKS may be:\n  \
+    N       split into N files based on size of input\n  \
+    K/N     output Kth of N to stdout\n  \
+    l/N     split into N files without splitting lines\n  \
+    l/K/N   output Kth of N to stdout without splitting lines\n  \
+    r/N     like 'l' but use round robin distribution\n  \
+    r/K/N   likewise but only output Kth of N to stdout";
 
 #[uucore::main]
 pub fn uumain(args: impl uucore::Args) -> UResult<()> {
@@ -129,9 +137,11 @@ pub fn uu_app() -> Command {
             Arg::new(OPT_NUMERIC_SUFFIXES)
                 .short('d')
                 .long(OPT_NUMERIC_SUFFIXES)
-                .default_missing_value("0")
+                .value_name("FROM")
                 .num_args(0..=1)
-                .help("use numeric suffixes instead of alphabetic"),
+                .require_equals(true)
+                .overrides_with(OPT_HEX_SUFFIXES)
+                .help("use numeric suffixes starting at 0 or FROM, instead of alphabetic"),
         )
         .arg(
             Arg::new(OPT_SUFFIX_LENGTH)
@@ -145,9 +155,11 @@ pub fn uu_app() -> Command {
             Arg::new(OPT_HEX_SUFFIXES)
                 .short('x')
                 .long(OPT_HEX_SUFFIXES)
-                .default_missing_value("0")
+                .value_name("FROM")
                 .num_args(0..=1)
-                .help("use hex suffixes instead of alphabetic"),
+                .require_equals(true)
+                .overrides_with(OPT_NUMERIC_SUFFIXES)
+                .help("use hex suffixes starting at 0 or FROM, instead of alphabetic"),
         )
         .arg(
             Arg::new(OPT_VERBOSE)
@@ -184,6 +196,10 @@ enum NumberType {
     /// Split into a specific number of chunks by byte.
     Bytes(u64),
 
+    /// Split into a specific number of chunks by byte, but output only
+    /// the *k*th chunk.
+    KthBytes(u64, u64),
+
     /// Split into a specific number of chunks by line (approximately).
     Lines(u64),
 
@@ -204,6 +220,7 @@ impl NumberType {
     fn num_chunks(&self) -> u64 {
         match self {
             Self::Bytes(n) => *n,
+            Self::KthBytes(_, n) => *n,
             Self::Lines(n) => *n,
             Self::KthLines(_, n) => *n,
             Self::RoundRobin(n) => *n,
@@ -222,6 +239,7 @@ enum NumberTypeError {
     ///
     /// ```ignore
     /// -n N
+    /// -n K/N
     /// -n l/N
     /// -n l/K/N
     /// -n r/N
@@ -235,9 +253,12 @@ enum NumberTypeError {
     /// command-line options is not a positive integer:
     ///
     /// ```ignore
+    /// -n K/N
     /// -n l/K/N
     /// -n r/K/N
     /// ```
+    ///
+    /// `K` must also not be greater than `N`.
     ChunkNumber(String),
 }
 
@@ -248,6 +269,7 @@ impl NumberType {
     ///
     /// ```ignore
     /// "N"
+    /// "K/N"
     /// "l/N"
     /// "l/K/N"
     /// "r/N"
@@ -260,45 +282,35 @@ impl NumberType {
     /// # Errors
     ///
     /// If the string is not one of the valid number types, if `K` is
-    /// not a nonnegative integer, or if `N` is not a positive
-    /// integer, then this function returns [`NumberTypeError`].
+    /// not a positive integer not greater than `N`, or if `N` is not a
+    /// positive integer, then this function returns [`NumberTypeError`].
     fn from(s: &str) -> Result<Self, NumberTypeError> {
+        let parse_num_chunks = |n_str: &str| match n_str.parse() {
+            Ok(0) | Err(_) => Err(NumberTypeError::NumberOfChunks(n_str.to_string())),
+            Ok(num_chunks) => Ok(num_chunks),
+        };
+        let parse_chunk_number = |k_str: &str, num_chunks: u64| match k_str.parse() {
+            Ok(chunk_number) if chunk_number > 0 && chunk_number <= num_chunks => Ok(chunk_number),
+            _ => Err(NumberTypeError::ChunkNumber(k_str.to_string())),
+        };
         let parts: Vec<&str> = s.split('/').collect();
         match &parts[..] {
-            [n_str] => {
-                let num_chunks = n_str
-                    .parse()
-                    .map_err(|_| NumberTypeError::NumberOfChunks(n_str.to_string()))?;
-                Ok(Self::Bytes(num_chunks))
-            }
-            ["l", n_str] => {
-                let num_chunks = n_str
-                    .parse()
-                    .map_err(|_| NumberTypeError::NumberOfChunks(n_str.to_string()))?;
-                Ok(Self::Lines(num_chunks))
+            [n_str] => Ok(Self::Bytes(parse_num_chunks(n_str)?)),
+            [k_str, n_str] if *k_str != "l" && *k_str != "r" => {
+                let num_chunks = parse_num_chunks(n_str)?;
+                let chunk_number = parse_chunk_number(k_str, num_chunks)?;
+                Ok(Self::KthBytes(chunk_number, num_chunks))
             }
+            ["l", n_str] => Ok(Self::Lines(parse_num_chunks(n_str)?)),
             ["l", k_str, n_str] => {
-                let num_chunks = n_str
-                    .parse()
-                    .map_err(|_| NumberTypeError::NumberOfChunks(n_str.to_string()))?;
-                let chunk_number = k_str
-                    .parse()
-                    .map_err(|_| NumberTypeError::ChunkNumber(k_str.to_string()))?;
+                let num_chunks = parse_num_chunks(n_str)?;
+                let chunk_number = parse_chunk_number(k_str, num_chunks)?;
                 Ok(Self::KthLines(chunk_number, num_chunks))
             }
-            ["r", n_str] => {
-                let num_chunks = n_str
-                    .parse()
-                    .map_err(|_| NumberTypeError::NumberOfChunks(n_str.to_string()))?;
-                Ok(Self::RoundRobin(num_chunks))
-            }
+            ["r", n_str] => Ok(Self::RoundRobin(parse_num_chunks(n_str)?)),
             ["r", k_str, n_str] => {
-                let num_chunks = n_str
-                    .parse()
-                    .map_err(|_| NumberTypeError::NumberOfChunks(n_str.to_string()))?;
-                let chunk_number = k_str
-                    .parse()
-                    .map_err(|_| NumberTypeError::ChunkNumber(k_str.to_string()))?;
+                let num_chunks = parse_num_chunks(n_str)?;
+                let chunk_number = parse_chunk_number(k_str, num_chunks)?;
                 Ok(Self::KthRoundRobin(chunk_number, num_chunks))
             }
             _ => Err(NumberTypeError::NumberOfChunks(s.to_string())),
@@ -396,25 +408,51 @@ impl Strategy {
     }
 }
 
-/// Parse the suffix type from the command-line arguments.
-fn suffix_type_from(matches: &ArgMatches) -> Result<(SuffixType, usize), SettingsError> {
-    if matches.value_source(OPT_NUMERIC_SUFFIXES) == Some(ValueSource::CommandLine) {
-        let suffix_start = matches.get_one::<String>(OPT_NUMERIC_SUFFIXES);
-        let suffix_start = suffix_start.ok_or(SettingsError::SuffixNotParsable(String::new()))?;
-        let suffix_start = suffix_start
-            .parse()
-            .map_err(|_| SettingsError::SuffixNotParsable(suffix_start.to_string()))?;
-        Ok((SuffixType::Decimal, suffix_start))
-    } else if matches.value_source(OPT_HEX_SUFFIXES) == Some(ValueSource::CommandLine) {
-        let suffix_start = matches.get_one::<String>(OPT_HEX_SUFFIXES);
-        let suffix_start = suffix_start.ok_or(SettingsError::SuffixNotParsable(String::new()))?;
-        let suffix_start = usize::from_str_radix(suffix_start, 16)
-            .map_err(|_| SettingsError::SuffixNotParsable(suffix_start.to_string()))?;
-        Ok((SuffixType::Hexadecimal, suffix_start))
-    } else {
-        // no numeric/hex suffix
-        Ok((SuffixType::Alphabetic, 0))
+/// Parse the suffix type and the start value of the suffixes from the
+/// command-line arguments.
+///
+/// The start value is `None` unless one was given to
+/// `--numeric-suffixes` or `--hex-suffixes`.
+fn suffix_type_from(matches: &ArgMatches) -> Result<(SuffixType, Option<usize>), SettingsError> {
+    let (suffix_type, option) =
+        if matches.value_source(OPT_NUMERIC_SUFFIXES) == Some(ValueSource::CommandLine) {
+            (SuffixType::Decimal, OPT_NUMERIC_SUFFIXES)
+        } else if matches.value_source(OPT_HEX_SUFFIXES) == Some(ValueSource::CommandLine) {
+            (SuffixType::Hexadecimal, OPT_HEX_SUFFIXES)
+        } else {
+            // no numeric/hex suffix
+            return Ok((SuffixType::Alphabetic, None));
+        };
+    let suffix_start = match matches.get_one::<String>(option) {
+        Some(suffix_start) => suffix_start,
+        None => return Ok((suffix_type, None)),
+    };
+    // Only the digits of the suffixes are allowed, without a sign.
+    let radix = suffix_type.radix() as u32;
+    if suffix_start.is_empty()
+        || !suffix_start
+            .chars()
+            .all(|c| c.is_digit(radix) && !c.is_ascii_uppercase())
+    {
+        return Err(SettingsError::SuffixStartNotParsable(
+            suffix_start.to_string(),
+            suffix_type,
+        ));
+    }
+    let suffix_start = usize::from_str_radix(suffix_start, radix).map_err(|_| {
+        SettingsError::SuffixStartNotParsable(suffix_start.to_string(), suffix_type)
+    })?;
+    Ok((suffix_type, Some(suffix_start)))
+}
+
+/// The number of digits of `n` in the given radix.
+fn num_digits(mut n: u64, radix: u8) -> usize {
+    let mut digits = 1;
+    while n >= radix as u64 {
+        n /= radix as u64;
+        digits += 1;
     }
+    digits
 }
 
 /// Parameters that control how a file gets split.
@@ -458,6 +496,12 @@ enum SettingsError {
     /// Suffix is not large enough to split into specified chunks
     SuffixTooSmall(usize),
 
+    /// Invalid start value of numeric or hexadecimal suffixes.
+    SuffixStartNotParsable(String, SuffixType),
+
+    /// The start value of the suffixes has more digits than the suffix length.
+    SuffixStartTooLarge,
+
     /// The `--filter` option is not supported on Windows.
     #[cfg(windows)]
     NotSupported,
@@ -468,7 +512,10 @@ impl SettingsError {
     fn requires_usage(&self) -> bool {
         matches!(
             self,
-            Self::Strategy(StrategyError::MultipleWays) | Self::SuffixContainsSeparator(_)
+            Self::Strategy(StrategyError::MultipleWays)
+                | Self::SuffixContainsSeparator(_)
+                | Self::SuffixStartNotParsable(..)
+                | Self::SuffixStartTooLarge
         )
     }
 }
@@ -479,6 +526,21 @@ impl fmt::Display for SettingsError {
             Self::Strategy(e) => e.fmt(f),
             Self::SuffixNotParsable(s) => write!(f, "invalid suffix length: {}", s.quote()),
             Self::SuffixTooSmall(i) => write!(f, "the suffix length needs to be at least {i}"),
+            Self::SuffixStartNotParsable(s, suffix_type) => {
+                let suffix_type = match suffix_type {
+                    SuffixType::Hexadecimal => "hexadecimal",
+                    _ => "numerical",
+                };
+                write!(
+                    f,
+                    "{}: invalid start value for {suffix_type} suffix",
+                    s.quote()
+                )
+            }
+            Self::SuffixStartTooLarge => write!(
+                f,
+                "numerical suffix start value is too large for the suffix length"
+            ),
             Self::SuffixContainsSeparator(s) => write!(
                 f,
                 "invalid suffix {}, contains directory separator",
@@ -509,22 +571,42 @@ impl Settings {
         let suffix_length: usize = suffix_length_str
             .parse()
             .map_err(|_| SettingsError::SuffixNotParsable(suffix_length_str.to_string()))?;
-        if let Strategy::Number(ref number_type) = strategy {
+
+        // Like GNU split, the suffixes only grow as more files are
+        // written if neither the suffix length, nor the number of files,
+        // nor the start value are given, so the names of the files sort
+        // in the order of the chunks. The start value only counts for
+        // the required length if it is smaller than the number of files.
+        let radix = suffix_type.radix();
+        let suffix_length = if let Strategy::Number(ref number_type) = strategy {
             let chunks = number_type.num_chunks();
-            if suffix_length != 0 {
-                let required_suffix_length =
-                    (chunks as f64).log(suffix_type.radix() as f64).ceil() as usize;
-                if suffix_length < required_suffix_length {
-                    return Err(SettingsError::SuffixTooSmall(required_suffix_length));
-                }
+            let last_chunk = match suffix_start {
+                Some(start) if (start as u64) < chunks => (chunks - 1).saturating_add(start as u64),
+                _ => chunks - 1,
+            };
+            let required_suffix_length = num_digits(last_chunk, radix);
+            if suffix_length == 0 {
+                required_suffix_length.max(2)
+            } else if suffix_length < required_suffix_length {
+                return Err(SettingsError::SuffixTooSmall(required_suffix_length));
+            } else {
+                suffix_length
+            }
+        } else if suffix_length == 0 && suffix_start.is_some() {
+            2
+        } else {
+            suffix_length
+        };
+        if let Some(start) = suffix_start {
+            if num_digits(start as u64, radix) > suffix_length {
+                return Err(SettingsError::SuffixStartTooLarge);
             }
         }
+
         let result = Self {
-            suffix_length: suffix_length_str
-                .parse()
-                .map_err(|_| SettingsError::SuffixNotParsable(suffix_length_str.to_string()))?,
+            suffix_length,
             suffix_type,
-            suffix_start,
+            suffix_start: suffix_start.unwrap_or(0),
             additional_suffix,
             verbose: matches.value_source("verbose") == Some(ValueSource::CommandLine),
             strategy,
@@ -744,6 +826,23 @@ impl<'a> LineChunkWriter<'a> {
             filename_iterator,
         })
     }
+
+    /// Start a new chunk and its corresponding writer, if the lines to
+    /// write in the current chunk have been written.
+    fn start_chunk_if_full(&mut self) -> std::io::Result<()> {
+        if self.num_lines_remaining_in_current_chunk == 0 {
+            self.num_chunks_written += 1;
+            let filename = self.filename_iterator.next().ok_or_else(|| {
+                std::io::Error::new(ErrorKind::Other, "output file suffixes exhausted")
+            })?;
+            if self.settings.verbose {
+                println!("creating file {}", filename.quote());
+            }
+            self.inner = self.settings.instantiate_current_writer(&filename)?;
+            self.num_lines_remaining_in_current_chunk = self.chunk_size;
+        }
+        Ok(())
+    }
 }
 
 impl<'a> Write for LineChunkWriter<'a> {
@@ -756,32 +855,24 @@ impl<'a> Write for LineChunkWriter<'a> {
         let mut prev = 0;
         let mut total_bytes_written = 0;
         for i in memchr::memchr_iter(b'\n', buf) {
-            // If we have exceeded the number of lines to write in the
-            // current chunk, then start a new chunk and its
-            // corresponding writer.
-            if self.num_lines_remaining_in_current_chunk == 0 {
-                self.num_chunks_written += 1;
-                let filename = self.filename_iterator.next().ok_or_else(|| {
-                    std::io::Error::new(ErrorKind::Other, "output file suffixes exhausted")
-                })?;
-                if self.settings.verbose {
-                    println!("creating file {}", filename.quote());
-                }
-                self.inner = self.settings.instantiate_current_writer(&filename)?;
-                self.num_lines_remaining_in_current_chunk = self.chunk_size;
-            }
+            self.start_chunk_if_full()?;
 
             // Write the line, starting from *after* the previous
             // newline character and ending *after* the current
             // newline character.
-            let n = self.inner.write(&buf[prev..i + 1])?;
-            total_bytes_written += n;
+            self.inner.write_all(&buf[prev..i + 1])?;
+            total_bytes_written += i + 1 - prev;
             prev = i + 1;
             self.num_lines_remaining_in_current_chunk -= 1;
         }
 
-        let n = self.inner.write(&buf[prev..buf.len()])?;
-        total_bytes_written += n;
+        // The start of a line continued in the next buffer belongs to
+        // the next chunk if the current one is full.
+        if prev < buf.len() {
+            self.start_chunk_if_full()?;
+            self.inner.write_all(&buf[prev..])?;
+            total_bytes_written += buf.len() - prev;
+        }
         Ok(total_bytes_written)
     }
 
@@ -985,48 +1076,104 @@ impl<'a> Write for LineBytesChunkWriter<'a> {
     }
 }
 
+/// An input that can be read from any position.
+trait SeekableRead: Read + Seek {}
+
+impl<T: Read + Seek> SeekableRead for T {}
+
+/// Open the input file for reading.
+fn open_input_file(settings: &Settings) -> UResult<File> {
+    File::open(Path::new(&settings.input)).map_err_context(|| {
+        format!(
+            "cannot open {} for reading: No such file or directory",
+            settings.input.quote()
+        )
+    })
+}
+
+/// Open the input, which is read from start to end.
+fn open_input(settings: &Settings) -> UResult<BufReader<Box<dyn Read>>> {
+    Ok(BufReader::new(if settings.input == "-" {
+        Box::new(stdin()) as Box<dyn Read>
+    } else {
+        Box::new(open_input_file(settings)?) as Box<dyn Read>
+    }))
+}
+
+/// Open the input of a split into chunks of similar size, and get its size in bytes.
+///
+/// A regular file is read directly, so that a single chunk can be read
+/// without reading the chunks before it. Any other input (like a pipe)
+/// is read into memory first, because its size is only known once it
+/// has been read entirely.
+fn open_sized_input(settings: &Settings) -> UResult<(Box<dyn SeekableRead>, u64)> {
+    let mut input = if settings.input == "-" {
+        Box::new(stdin()) as Box<dyn Read>
+    } else {
+        let file = open_input_file(settings)?;
+        match file.metadata() {
+            // Files like those in /proc have a size of 0, but they
+            // are not empty.
+            Ok(metadata) if metadata.is_file() && metadata.len() > 0 => {
+                return Ok((Box::new(file), metadata.len()));
+            }
+            _ => Box::new(file) as Box<dyn Read>,
+        }
+    };
+    let mut buf = vec![];
+    input
+        .read_to_end(&mut buf)
+        .map_err_context(|| format!("{}: read error", settings.input.maybe_quote()))?;
+    let num_bytes = buf.len() as u64;
+    Ok((Box::new(io::Cursor::new(buf)), num_bytes))
+}
+
+/// Compute the number of chunks to write and the number of bytes of
+/// each chunk (except the last) when splitting `num_bytes` bytes into
+/// `num_chunks` chunks by byte.
+///
+/// The last chunk gets all remaining bytes so that if the number of
+/// bytes was not evenly divisible by `num_chunks`, we don't leave any
+/// bytes behind.
+fn byte_chunk_sizes(settings: &Settings, num_bytes: u64, num_chunks: u64) -> (u64, u64) {
+    // If the requested number of chunks exceeds the number of bytes
+    // in the file *and* the `elide_empty_files` parameter is enabled,
+    // then behave as if the number of chunks was set to the number of
+    // bytes in the file. This ensures that we don't write empty
+    // files. Otherwise, just write the `num_chunks - num_bytes` empty
+    // files.
+    if settings.elide_empty_files && num_chunks > num_bytes {
+        (num_bytes, 1)
+    } else {
+        (num_chunks, (num_bytes / num_chunks).max(1))
+    }
+}
+
 /// Split a file into a specific number of chunks by byte.
 ///
-/// This function always creates one output file for each chunk, even
-/// if there is an error reading or writing one of the chunks or if
-/// the input file is truncated. However, if the `filter` option is
-/// being used, then no files are created.
+/// This function creates one output file for each chunk, even if the
+/// input file is truncated. However, if the `filter` option is being
+/// used, then no files are created.
 ///
 /// # Errors
 ///
 /// This function returns an error if there is a problem reading from
 /// `reader` or writing to one of the output files.
+///
+/// # See also
+///
+/// * [`kth_chunk_by_byte`], which splits its input in the same way,
+///   but writes only one specified chunk to stdout.
 fn split_into_n_chunks_by_byte<R>(
     settings: &Settings,
     reader: &mut R,
+    num_bytes: u64,
     num_chunks: u64,
 ) -> UResult<()>
 where
     R: Read,
 {
-    // Get the size of the input file in bytes and compute the number
-    // of bytes per chunk.
-    //
-    // If the requested number of chunks exceeds the number of bytes
-    // in the file *and* the `elide_empty_files` parameter is enabled,
-    // then behave as if the number of chunks was set to the number of
-    // bytes in the file. This ensures that we don't write empty
-    // files. Otherwise, just write the `num_chunks - num_bytes` empty
-    // files.
-    let metadata = metadata(&settings.input).map_err(|_| {
-        USimpleError::new(1, format!("{}: cannot determine file size", settings.input))
-    })?;
-
-    let num_bytes = metadata.len();
-    let will_have_empty_files = settings.elide_empty_files && num_chunks > num_bytes;
-    let (num_chunks, chunk_size) = if will_have_empty_files {
-        let num_chunks = num_bytes;
-        let chunk_size = 1;
-        (num_chunks, chunk_size)
-    } else {
-        let chunk_size = (num_bytes / (num_chunks)).max(1);
-        (num_chunks, chunk_size)
-    };
+    let (num_chunks, chunk_size) = byte_chunk_sizes(settings, num_bytes, num_chunks);
 
     // If we would have written zero chunks of output, then terminate
     // immediately. This happens on `split -e -n 3 /dev/null`, for
@@ -1035,63 +1182,224 @@ where
         return Ok(());
     }
 
-    let num_chunks: usize = num_chunks
-        .try_into()
-        .map_err(|_| USimpleError::new(1, "Number of chunks too big"))?;
-
-    // This object is responsible for creating the filename for each chunk.
-    let mut filename_iterator = FilenameIterator::new(
-        &settings.prefix,
-        &settings.additional_suffix,
-        settings.suffix_length,
-        settings.suffix_type,
-        settings.suffix_start,
-    )?;
+    if usize::try_from(num_chunks).is_err() {
+        return Err(USimpleError::new(1, "Number of chunks too big"));
+    }
 
-    // Create one writer for each chunk. This will create each
+    // Create the writer of each chunk in turn. This will create each
     // of the underlying files (if not in `--filter` mode).
-    let mut writers = vec![];
-    for _ in 0..num_chunks {
-        let filename = filename_iterator
-            .next()
-            .ok_or_else(|| USimpleError::new(1, "output file suffixes exhausted"))?;
-        let writer = settings.instantiate_current_writer(filename.as_str())?;
-        writers.push(writer);
+    let mut outputs = ChunkOutputs::new(settings)?;
+    for i in 0..num_chunks {
+        // Write `chunk_size` bytes from the reader into each writer
+        // except the last, which gets all the remaining bytes.
+        let size = if i == num_chunks - 1 {
+            num_bytes.saturating_sub(chunk_size * (num_chunks - 1))
+        } else {
+            chunk_size
+        };
+        let writer = outputs.writer(i)?;
+        match io::copy(&mut reader.by_ref().take(size), writer) {
+            Ok(_) => {}
+            Err(e) if e.kind() == ErrorKind::BrokenPipe => return Ok(()),
+            Err(e) => return Err(uio_error!(e, "input/output error")),
+        }
     }
+    outputs.finish(num_chunks)
+}
 
-    // Capture the result of the `std::io::copy()` calls to check for
-    // `BrokenPipe`.
-    let result: std::io::Result<()> = {
-        // Write `chunk_size` bytes from the reader into each writer
-        // except the last.
-        //
-        // The last writer gets all remaining bytes so that if the number
-        // of bytes in the input file was not evenly divisible by
-        // `num_chunks`, we don't leave any bytes behind.
-        for writer in writers.iter_mut().take(num_chunks - 1) {
-            io::copy(&mut reader.by_ref().take(chunk_size), writer)?;
+/// Print the k-th chunk of a file, splitting by byte.
+///
+/// This function is like [`split_into_n_chunks_by_byte`], but instead
+/// of writing each chunk to its own file, it only writes to stdout
+/// the contents of the chunk identified by `chunk_number`.
+///
+/// # Errors
+///
+/// This function returns an error if there is a problem reading from
+/// `reader` or writing to stdout.
+fn kth_chunk_by_byte<R>(
+    settings: &Settings,
+    reader: &mut R,
+    num_bytes: u64,
+    chunk_number: u64,
+    num_chunks: u64,
+) -> UResult<()>
+where
+    R: Read + Seek,
+{
+    let (num_chunks, chunk_size) = byte_chunk_sizes(settings, num_bytes, num_chunks);
+    if chunk_number >= num_chunks {
+        return Ok(());
+    }
+    let start = (chunk_number * chunk_size).min(num_bytes);
+    let end = if chunk_number == num_chunks - 1 {
+        num_bytes
+    } else {
+        (start + chunk_size).min(num_bytes)
+    };
+
+    reader.seek(SeekFrom::Start(start))?;
+    let stdout = std::io::stdout();
+    let mut writer = stdout.lock();
+    io::copy(&mut reader.take(end - start), &mut writer)?;
+    Ok(())
+}
+
+/// Read the chunks of a split into a specific number of chunks by
+/// line, and call `write_chunk` with the bytes of each chunk.
+///
+/// The chunks are split like the same number of chunks by byte, except
+/// that each line goes entirely into the chunk in which it starts. So
+/// a chunk ends with the first line ending at or after its last byte,
+/// and it is empty if a line spans it entirely. The bytes of a chunk
+/// may be passed to `write_chunk` in several parts, and not at all if
+/// the chunk is empty. Reading stops once `write_chunk` returns false.
+///
+/// `reader` is read from offset `position` of the input, at which the
+/// chunk `chunk_number` is being read.
+fn read_line_chunks<R, F>(
+    reader: &mut R,
+    mut position: u64,
+    mut chunk_number: u64,
+    num_bytes: u64,
+    num_chunks: u64,
+    mut write_chunk: F,
+) -> UResult<()>
+where
+    R: BufRead,
+    F: FnMut(u64, &[u8]) -> UResult<bool>,
+{
+    let chunk_size = num_bytes / num_chunks;
+    // The offset of the last byte of a chunk, if it were not for lines.
+    let last_byte = |chunk_number: u64| ((chunk_number + 1) * chunk_size).saturating_sub(1);
+
+    loop {
+        let buf = match reader.fill_buf() {
+            Ok([]) => return Ok(()),
+            Ok(buf) => buf,
+            Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e.into()),
+        };
+        let buf_len = buf.len();
+        let mut rest = buf;
+        while !rest.is_empty() {
+            // The last chunk gets everything that is left.
+            let end_of_chunk = if chunk_number == num_chunks - 1 {
+                None
+            } else {
+                let skip = last_byte(chunk_number).saturating_sub(position);
+                let skip = skip.min(rest.len() as u64) as usize;
+                memchr::memchr(b'\n', &rest[skip..]).map(|i| skip + i + 1)
+            };
+            let len = end_of_chunk.unwrap_or(rest.len());
+            if !write_chunk(chunk_number, &rest[..len])? {
+                return Ok(());
+            }
+            position += len as u64;
+            rest = &rest[len..];
+
+            if end_of_chunk.is_some() {
+                // A long line could have spanned entire chunks, which
+                // are empty.
+                chunk_number += 1;
+                while chunk_number < num_chunks - 1 && last_byte(chunk_number) < position {
+                    chunk_number += 1;
+                }
+            }
         }
+        reader.consume(buf_len);
+    }
+}
 
-        // Write all the remaining bytes to the last chunk.
-        let i = num_chunks - 1;
-        let last_chunk_size = num_bytes - (chunk_size * (num_chunks as u64 - 1));
-        io::copy(&mut reader.by_ref().take(last_chunk_size), &mut writers[i])?;
+/// The outputs of the chunks of a split into a specific number of
+/// chunks, which are created one after the other.
+struct ChunkOutputs<'a> {
+    settings: &'a Settings,
+
+    /// Iterator that yields filenames for each chunk.
+    filename_iterator: FilenameIterator<'a>,
 
+    /// The number of the chunk being written, and its writer.
+    current: Option<(u64, BufWriter<Box<dyn Write>>)>,
+}
+
+impl<'a> ChunkOutputs<'a> {
+    fn new(settings: &'a Settings) -> UResult<Self> {
+        let filename_iterator = FilenameIterator::new(
+            &settings.prefix,
+            &settings.additional_suffix,
+            settings.suffix_length,
+            settings.suffix_type,
+            settings.suffix_start,
+        )?;
+        Ok(Self {
+            settings,
+            filename_iterator,
+            current: None,
+        })
+    }
+
+    /// Get the writer of the output of chunk `chunk_number`.
+    ///
+    /// The outputs of the chunks before it are created first if they
+    /// have not been yet, unless they are empty and `elide_empty_files`
+    /// is set.
+    fn writer(&mut self, chunk_number: u64) -> UResult<&mut BufWriter<Box<dyn Write>>> {
+        self.create_outputs(chunk_number)?;
+        match &mut self.current {
+            Some((_, writer)) => Ok(writer),
+            None => unreachable!("the output of the chunk has just been created"),
+        }
+    }
+
+    /// Create the outputs of the chunks up to `chunk_number`.
+    fn create_outputs(&mut self, chunk_number: u64) -> UResult<()> {
+        let mut next = match &mut self.current {
+            Some((i, _)) if *i == chunk_number => return Ok(()),
+            Some((i, writer)) => {
+                writer.flush()?;
+                *i + 1
+            }
+            None => 0,
+        };
+        while next <= chunk_number {
+            if next == chunk_number || !self.settings.elide_empty_files {
+                let filename = self
+                    .filename_iterator
+                    .next()
+                    .ok_or_else(|| USimpleError::new(1, "output file suffixes exhausted"))?;
+                if self.settings.verbose {
+                    println!("creating file {}", filename.quote());
+                }
+                let writer = self
+                    .settings
+                    .instantiate_current_writer(filename.as_str())?;
+                self.current = Some((next, writer));
+            }
+            next += 1;
+        }
+        Ok(())
+    }
+
+    /// Finish writing `num_chunks` chunks, creating the outputs of the
+    /// remaining empty chunks if empty files are not elided.
+    fn finish(mut self, num_chunks: u64) -> UResult<()> {
+        if !self.settings.elide_empty_files && num_chunks > 0 {
+            self.create_outputs(num_chunks - 1)?;
+        }
+        if let Some((_, writer)) = &mut self.current {
+            writer.flush()?;
+        }
         Ok(())
-    };
-    match result {
-        Ok(_) => Ok(()),
-        Err(e) if e.kind() == ErrorKind::BrokenPipe => Ok(()),
-        Err(e) => Err(uio_error!(e, "input/output error")),
     }
 }
 
 /// Split a file into a specific number of chunks by line.
 ///
-/// This function always creates one output file for each chunk, even
-/// if there is an error reading or writing one of the chunks or if
-/// the input file is truncated. However, if the `filter` option is
-/// being used, then no files are created.
+/// This function creates one output file for each chunk, even if the
+/// input file is truncated, unless `elide_empty_files` is set.
+/// However, if the `filter` option is being used, then no files are
+/// created.
 ///
 /// # Errors
 ///
@@ -1105,58 +1413,18 @@ where
 fn split_into_n_chunks_by_line<R>(
     settings: &Settings,
     reader: &mut R,
+    num_bytes: u64,
     num_chunks: u64,
 ) -> UResult<()>
 where
     R: BufRead,
 {
-    // Get the size of the input file in bytes and compute the number
-    // of bytes per chunk.
-    let metadata = metadata(&settings.input).unwrap();
-    let num_bytes = metadata.len();
-    let chunk_size = (num_bytes / num_chunks) as usize;
-
-    // This object is responsible for creating the filename for each chunk.
-    let mut filename_iterator = FilenameIterator::new(
-        &settings.prefix,
-        &settings.additional_suffix,
-        settings.suffix_length,
-        settings.suffix_type,
-        settings.suffix_start,
-    )?;
-
-    // Create one writer for each chunk. This will create each
-    // of the underlying files (if not in `--filter` mode).
-    let mut writers = vec![];
-    for _ in 0..num_chunks {
-        let filename = filename_iterator
-            .next()
-            .ok_or_else(|| USimpleError::new(1, "output file suffixes exhausted"))?;
-        let writer = settings.instantiate_current_writer(filename.as_str())?;
-        writers.push(writer);
-    }
-
-    let mut num_bytes_remaining_in_current_chunk = chunk_size;
-    let mut i = 0;
-    for line_result in reader.lines() {
-        let line = line_result.unwrap();
-        let maybe_writer = writers.get_mut(i);
-        let writer = maybe_writer.unwrap();
-        let bytes = line.as_bytes();
-        writer.write_all(bytes)?;
-        writer.write_all(b"\n")?;
-
-        // Add one byte for the newline character.
-        let num_bytes = bytes.len() + 1;
-        if num_bytes > num_bytes_remaining_in_current_chunk {
-            num_bytes_remaining_in_current_chunk = chunk_size;
-            i += 1;
-        } else {
-            num_bytes_remaining_in_current_chunk -= num_bytes;
-        }
-    }
-
-    Ok(())
+    let mut outputs = ChunkOutputs::new(settings)?;
+    read_line_chunks(reader, 0, 0, num_bytes, num_chunks, |i, buf| {
+        outputs.writer(i)?.write_all(buf)?;
+        Ok(true)
+    })?;
+    outputs.finish(num_chunks)
 }
 
 /// Print the k-th chunk of a file, splitting by line.
@@ -1168,58 +1436,66 @@ where
 /// # Errors
 ///
 /// This function returns an error if there is a problem reading from
-/// `reader` or writing to one of the output files.
+/// `reader` or writing to stdout.
 ///
 /// # See also
 ///
 /// * [`split_into_n_chunks_by_line`], which splits its input in the
 ///   same way, but writes each chunk to its own file.
 fn kth_chunk_by_line<R>(
-    settings: &Settings,
     reader: &mut R,
+    num_bytes: u64,
     chunk_number: u64,
     num_chunks: u64,
 ) -> UResult<()>
 where
-    R: BufRead,
+    R: Read + Seek,
 {
-    // Get the size of the input file in bytes and compute the number
-    // of bytes per chunk.
-    let metadata = metadata(&settings.input).unwrap();
-    let num_bytes = metadata.len();
-    let chunk_size = (num_bytes / num_chunks) as usize;
+    // Start reading at the last byte of the previous chunk, where the
+    // line ending the previous chunk is looked for.
+    let chunk_size = num_bytes / num_chunks;
+    let (position, previous_chunk) = if chunk_number > 0 && chunk_size > 0 {
+        (chunk_number * chunk_size - 1, chunk_number - 1)
+    } else {
+        (0, 0)
+    };
+    reader.seek(SeekFrom::Start(position))?;
 
     // Write to stdout instead of to a file.
     let stdout = std::io::stdout();
     let mut writer = stdout.lock();
 
-    let mut num_bytes_remaining_in_current_chunk = chunk_size;
-    let mut i = 0;
-    for line_result in reader.lines() {
-        let line = line_result?;
-        let bytes = line.as_bytes();
-        if i == chunk_number {
-            writer.write_all(bytes)?;
-            writer.write_all(b"\n")?;
-        }
-
-        // Add one byte for the newline character.
-        let num_bytes = bytes.len() + 1;
-        if num_bytes >= num_bytes_remaining_in_current_chunk {
-            num_bytes_remaining_in_current_chunk = chunk_size;
-            i += 1;
-        } else {
-            num_bytes_remaining_in_current_chunk -= num_bytes;
-        }
-
-        if i > chunk_number {
-            break;
-        }
-    }
-
-    Ok(())
+    read_line_chunks(
+        &mut BufReader::new(reader),
+        position,
+        previous_chunk,
+        num_bytes,
+        num_chunks,
+        |i, buf| {
+            if i == chunk_number {
+                writer.write_all(buf)?;
+            }
+            Ok(i <= chunk_number)
+        },
+    )
 }
 
+/// Split a file into a specific number of chunks by line, assigning
+/// the lines via round-robin to the chunks.
+///
+/// All the outputs are written at the same time, and they are created
+/// before reading the input, unless `elide_empty_files` is set, in
+/// which case they are only created once they are written to.
+///
+/// # Errors
+///
+/// This function returns an error if there is a problem reading from
+/// `reader` or writing to one of the output files.
+///
+/// # See also
+///
+/// * [`kth_chunk_by_line_round_robin`], which splits its input in the
+///   same way, but writes only one specified chunk to stdout.
 fn split_into_n_chunks_by_line_round_robin<R>(
     settings: &Settings,
     reader: &mut R,
@@ -1237,61 +1513,128 @@ where
         settings.suffix_start,
     )?;
 
-    // Create one writer for each chunk. This will create each
-    // of the underlying files (if not in `--filter` mode).
-    let mut writers = vec![];
+    let num_chunks: usize = num_chunks
+        .try_into()
+        .map_err(|_| USimpleError::new(1, "Number of chunks too big"))?;
+
+    // Name each chunk, then create one writer for each chunk (if
+    // not in `--filter` mode, this will create the underlying files).
+    let mut filenames = vec![];
     for _ in 0..num_chunks {
         let filename = filename_iterator
             .next()
             .ok_or_else(|| USimpleError::new(1, "output file suffixes exhausted"))?;
-        let writer = settings.instantiate_current_writer(filename.as_str())?;
-        writers.push(writer);
+        filenames.push(filename);
+    }
+    let create_writer = |filename: &str| {
+        if settings.verbose {
+            println!("creating file {}", filename.quote());
+        }
+        settings.instantiate_current_writer(filename)
+    };
+    let mut writers = vec![];
+    for filename in &filenames {
+        writers.push(if settings.elide_empty_files {
+            None
+        } else {
+            Some(create_writer(filename)?)
+        });
     }
 
-    let num_chunks: usize = num_chunks.try_into().unwrap();
-    for (i, line_result) in reader.lines().enumerate() {
-        let line = line_result.unwrap();
-        let maybe_writer = writers.get_mut(i % num_chunks);
-        let writer = maybe_writer.unwrap();
-        let bytes = line.as_bytes();
-        writer.write_all(bytes)?;
-        writer.write_all(b"\n")?;
+    let mut line = vec![];
+    for i in (0..num_chunks).cycle() {
+        line.clear();
+        if reader.read_until(b'\n', &mut line)? == 0 {
+            break;
+        }
+        let writer = match &mut writers[i] {
+            Some(writer) => writer,
+            writer => writer.insert(create_writer(&filenames[i])?),
+        };
+        writer.write_all(&line)?;
+    }
+    for writer in writers.iter_mut().flatten() {
+        writer.flush()?;
     }
 
     Ok(())
 }
 
-fn split(settings: &Settings) -> UResult<()> {
-    let mut reader = BufReader::new(if settings.input == "-" {
-        Box::new(stdin()) as Box<dyn Read>
-    } else {
-        let r = File::open(Path::new(&settings.input)).map_err_context(|| {
-            format!(
-                "cannot open {} for reading: No such file or directory",
-                settings.input.quote()
-            )
-        })?;
-        Box::new(r) as Box<dyn Read>
-    });
+/// Print the k-th chunk of a file, assigning the lines via round-robin
+/// to the chunks.
+///
+/// # Errors
+///
+/// This function returns an error if there is a problem reading from
+/// `reader` or writing to stdout.
+///
+/// # See also
+///
+/// * [`split_into_n_chunks_by_line_round_robin`], which splits its
+///   input in the same way, but writes each chunk to its own file.
+fn kth_chunk_by_line_round_robin<R>(
+    reader: &mut R,
+    chunk_number: u64,
+    num_chunks: u64,
+) -> UResult<()>
+where
+    R: BufRead,
+{
+    // Write to stdout instead of to a file.
+    let stdout = std::io::stdout();
+    let mut writer = stdout.lock();
+
+    let mut line = vec![];
+    for i in (0..num_chunks).cycle() {
+        line.clear();
+        if reader.read_until(b'\n', &mut line)? == 0 {
+            break;
+        }
+        if i == chunk_number {
+            writer.write_all(&line)?;
+        }
+    }
 
+    Ok(())
+}
+
+fn split(settings: &Settings) -> UResult<()> {
+    // The chunk numbers are given as 1-indexed numbers, but it is a
+    // little easier to deal with 0-indexed numbers.
     match settings.strategy {
         Strategy::Number(NumberType::Bytes(num_chunks)) => {
-            split_into_n_chunks_by_byte(settings, &mut reader, num_chunks)
+            let (mut reader, num_bytes) = open_sized_input(settings)?;
+            split_into_n_chunks_by_byte(settings, &mut reader, num_bytes, num_chunks)
+        }
+        Strategy::Number(NumberType::KthBytes(chunk_number, num_chunks)) => {
+            let (mut reader, num_bytes) = open_sized_input(settings)?;
+            kth_chunk_by_byte(
+                settings,
+                &mut reader,
+                num_bytes,
+                chunk_number - 1,
+                num_chunks,
+            )
         }
         Strategy::Number(NumberType::Lines(num_chunks)) => {
-            split_into_n_chunks_by_line(settings, &mut reader, num_chunks)
+            let (reader, num_bytes) = open_sized_input(settings)?;
+            let mut reader = BufReader::new(reader);
+            split_into_n_chunks_by_line(settings, &mut reader, num_bytes, num_chunks)
         }
         Strategy::Number(NumberType::KthLines(chunk_number, num_chunks)) => {
-            // The chunk number is given as a 1-indexed number, but it
-            // is a little easier to deal with a 0-indexed number.
-            let chunk_number = chunk_number - 1;
-            kth_chunk_by_line(settings, &mut reader, chunk_number, num_chunks)
+            let (mut reader, num_bytes) = open_sized_input(settings)?;
+            kth_chunk_by_line(&mut reader, num_bytes, chunk_number - 1, num_chunks)
         }
         Strategy::Number(NumberType::RoundRobin(num_chunks)) => {
+            let mut reader = open_input(settings)?;
             split_into_n_chunks_by_line_round_robin(settings, &mut reader, num_chunks)
         }
-        Strategy::Number(_) => Err(USimpleError::new(1, "-n mode not yet fully implemented")),
+        Strategy::Number(NumberType::KthRoundRobin(chunk_number, num_chunks)) => {
+            let mut reader = open_input(settings)?;
+            kth_chunk_by_line_round_robin(&mut reader, chunk_number - 1, num_chunks)
+        }
         Strategy::Lines(chunk_size) => {
+            let mut reader = open_input(settings)?;
             let mut writer = LineChunkWriter::new(chunk_size, settings)?;
             match std::io::copy(&mut reader, &mut writer) {
                 Ok(_) => Ok(()),
@@ -1311,6 +1654,7 @@ fn split(settings: &Settings) -> UResult<()> {
             }
         }
         Strategy::Bytes(chunk_size) => {
+            let mut reader = open_input(settings)?;
             let mut writer = ByteChunkWriter::new(chunk_size, settings)?;
             match std::io::copy(&mut reader, &mut writer) {
                 Ok(_) => Ok(()),
@@ -1330,6 +1674,7 @@ fn split(settings: &Settings) -> UResult<()> {
             }
         }
         Strategy::LineBytes(chunk_size) => {
+            let mut reader = open_input(settings)?;
             let mut writer = LineBytesChunkWriter::new(chunk_size, settings)?;
             match std::io::copy(&mut reader, &mut writer) {
                 Ok(_) => Ok(()),
@@ -1360,6 +1705,10 @@ mod tests {
     #[test]
     fn test_number_type_from() {
         assert_eq!(NumberType::from("123").unwrap(), NumberType::Bytes(123));
+        assert_eq!(
+            NumberType::from("123/456").unwrap(),
+            NumberType::KthBytes(123, 456)
+        );
         assert_eq!(NumberType::from("l/123").unwrap(), NumberType::Lines(123));
         assert_eq!(
             NumberType::from("l/123/456").unwrap(),
@@ -1423,11 +1772,48 @@ mod tests {
             NumberType::from("r/abc/xyz").unwrap_err(),
             NumberTypeError::NumberOfChunks("xyz".to_string())
         );
+        assert_eq!(
+            NumberType::from("abc/456").unwrap_err(),
+            NumberTypeError::ChunkNumber("abc".to_string())
+        );
+        assert_eq!(
+            NumberType::from("x/123").unwrap_err(),
+            NumberTypeError::ChunkNumber("x".to_string())
+        );
+    }
+
+    #[test]
+    fn test_number_type_from_out_of_range() {
+        assert_eq!(
+            NumberType::from("0").unwrap_err(),
+            NumberTypeError::NumberOfChunks("0".to_string())
+        );
+        assert_eq!(
+            NumberType::from("l/0").unwrap_err(),
+            NumberTypeError::NumberOfChunks("0".to_string())
+        );
+        assert_eq!(
+            NumberType::from("r/0/0").unwrap_err(),
+            NumberTypeError::NumberOfChunks("0".to_string())
+        );
+        assert_eq!(
+            NumberType::from("0/3").unwrap_err(),
+            NumberTypeError::ChunkNumber("0".to_string())
+        );
+        assert_eq!(
+            NumberType::from("l/4/3").unwrap_err(),
+            NumberTypeError::ChunkNumber("4".to_string())
+        );
+        assert_eq!(
+            NumberType::from("r/4/3").unwrap_err(),
+            NumberTypeError::ChunkNumber("4".to_string())
+        );
     }
 
     #[test]
     fn test_number_type_num_chunks() {
         assert_eq!(NumberType::from("123").unwrap().num_chunks(), 123);
+        assert_eq!(NumberType::from("123/456").unwrap().num_chunks(), 456);
         assert_eq!(NumberType::from("l/123").unwrap().num_chunks(), 123);
         assert_eq!(NumberType::from("l/123/456").unwrap().num_chunks(), 456);
         assert_eq!(NumberType::from("r/123").unwrap().num_chunks(), 123);
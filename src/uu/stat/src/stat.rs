@@ -10,10 +10,10 @@ use uucore::display::Quotable;
 use uucore::error::{FromIo, UResult, USimpleError};
 use uucore::fs::display_permissions;
 use uucore::fsext::{
-    pretty_filetype, pretty_fstype, pretty_time, read_fs_list, statfs, BirthTime, FsMeta,
+    pretty_filetype, pretty_fstype, pretty_time, read_fs_list, statfs, BirthTime, FsMeta, StatFs,
 };
 use uucore::libc::mode_t;
-use uucore::{entries, format_usage, show_error, show_warning};
+use uucore::{entries, format_usage, show, show_warning};
 
 use clap::{crate_version, Arg, ArgAction, ArgMatches, Command};
 use std::borrow::Cow;
@@ -64,31 +64,35 @@ enum Padding {
     Space,
 }
 
-/// pads the string with zeroes or spaces and prints it
+/// pads the string with zeroes or spaces
 ///
 /// # Example
 /// ```ignore
-/// uu_stat::pad_and_print("1", false, 5, Padding::Zero) == "00001";
+/// uu_stat::pad("1", false, 5, Padding::Zero) == "00001";
 /// ```
 /// currently only supports '0' & ' ' as the padding character
-/// because the format specification of print! does not support general
+/// because the format specification of format! does not support general
 /// fill characters.
-fn pad_and_print(result: &str, left: bool, width: usize, padding: Padding) {
+fn pad(result: &str, left: bool, width: usize, padding: Padding) -> String {
     match (left, padding) {
-        (false, Padding::Zero) => print!("{result:0>width$}"),
-        (false, Padding::Space) => print!("{result:>width$}"),
-        (true, Padding::Zero) => print!("{result:0<width$}"),
-        (true, Padding::Space) => print!("{result:<width$}"),
-    };
+        (false, Padding::Zero) => format!("{result:0>width$}"),
+        (false, Padding::Space) => format!("{result:>width$}"),
+        (true, Padding::Zero) => format!("{result:0<width$}"),
+        (true, Padding::Space) => format!("{result:<width$}"),
+    }
 }
 
 #[derive(Debug)]
 pub enum OutputType {
     Str(String),
+    /// a symbolic link and its target, padded separately like GNU's %N
+    Symlink(String, String),
     Integer(i64),
     Unsigned(u64),
     UnsignedHex(u64),
     UnsignedOct(u32),
+    /// seconds and nanoseconds since the Epoch
+    Timestamp(i64, i64),
     Unknown,
 }
 
@@ -99,6 +103,8 @@ enum Token {
         flag: Flags,
         width: usize,
         precision: Option<usize>,
+        /// 'H' or 'L' before 'd' or 'r': the major or minor device number
+        modifier: Option<char>,
         format: char,
     },
 }
@@ -234,39 +240,15 @@ fn print_it(output: &OutputType, flags: Flags, width: usize, precision: Option<u
     // By default, a sign  is  used only for negative numbers.
     // A + overrides a space if both are used.
 
-    let padding_char = if flags.zero && !flags.left && precision.is_none() {
-        Padding::Zero
-    } else {
-        Padding::Space
-    };
-
-    match output {
-        OutputType::Str(s) => {
-            let s = match precision {
-                Some(p) if p < s.len() => &s[..p],
-                _ => s,
-            };
-            pad_and_print(s, flags.left, width, Padding::Space);
-        }
+    let s = match output {
+        OutputType::Str(s) => format_str(s, flags, width, precision),
+        OutputType::Symlink(name, target) => format!(
+            "{} -> {}",
+            format_str(name, flags, width, precision),
+            format_str(target, flags, width, precision)
+        ),
         OutputType::Integer(num) => {
-            let num = num.to_string();
-            let arg = if flags.group {
-                group_num(&num)
-            } else {
-                Cow::Borrowed(num.as_str())
-            };
-            let prefix = if flags.sign {
-                "+"
-            } else if flags.space {
-                " "
-            } else {
-                ""
-            };
-            let extended = format!(
-                "{prefix}{arg:0>precision$}",
-                precision = precision.unwrap_or(0)
-            );
-            pad_and_print(&extended, flags.left, width, padding_char);
+            format_integer(*num < 0, num.unsigned_abs(), flags, width, precision)
         }
         OutputType::Unsigned(num) => {
             let num = num.to_string();
@@ -275,29 +257,161 @@ fn print_it(output: &OutputType, flags: Flags, width: usize, precision: Option<u
             } else {
                 Cow::Borrowed(num.as_str())
             };
-            let s = format!("{s:0>precision$}", precision = precision.unwrap_or(0));
-            pad_and_print(&s, flags.left, width, padding_char);
+            pad_number("", &s, flags, width, precision)
         }
         OutputType::UnsignedOct(num) => {
-            let prefix = if flags.alter { "0" } else { "" };
-            let s = format!(
-                "{prefix}{num:0>precision$o}",
-                precision = precision.unwrap_or(0)
-            );
-            pad_and_print(&s, flags.left, width, padding_char);
+            let s = format!("{num:0>precision$o}", precision = precision.unwrap_or(0));
+            // the alternate form only makes sure that the first digit is a zero
+            let prefix = if flags.alter && !s.starts_with('0') {
+                "0"
+            } else {
+                ""
+            };
+            pad_number(prefix, &s, flags, width, precision)
         }
         OutputType::UnsignedHex(num) => {
-            let prefix = if flags.alter { "0x" } else { "" };
-            let s = format!(
-                "{prefix}{num:0>precision$x}",
-                precision = precision.unwrap_or(0)
-            );
-            pad_and_print(&s, flags.left, width, padding_char);
+            let prefix = if flags.alter && *num != 0 { "0x" } else { "" };
+            pad_number(prefix, &format!("{num:x}"), flags, width, precision)
+        }
+        OutputType::Timestamp(secs, nsecs) => {
+            format_timestamp(*secs, *nsecs, flags, width, precision)
         }
-        OutputType::Unknown => print!("?"),
+        OutputType::Unknown => "?".to_owned(),
+    };
+    print!("{s}");
+}
+
+/// truncates the string to the precision, if any, and pads it with spaces
+fn format_str(s: &str, flags: Flags, width: usize, precision: Option<usize>) -> String {
+    let s = match precision {
+        Some(mut p) if p < s.len() => {
+            while !s.is_char_boundary(p) {
+                p -= 1;
+            }
+            &s[..p]
+        }
+        _ => s,
+    };
+    pad(s, flags.left, width, Padding::Space)
+}
+
+/// pads the digits of a number to the precision and width;
+/// zero padding goes between the sign or radix `prefix` and the digits
+fn pad_number(
+    prefix: &str,
+    digits: &str,
+    flags: Flags,
+    width: usize,
+    precision: Option<usize>,
+) -> String {
+    let digits = format!("{digits:0>precision$}", precision = precision.unwrap_or(0));
+    if flags.zero && !flags.left && precision.is_none() {
+        let width = width.saturating_sub(prefix.len());
+        format!("{prefix}{}", pad(&digits, false, width, Padding::Zero))
+    } else {
+        pad(
+            &format!("{prefix}{digits}"),
+            flags.left,
+            width,
+            Padding::Space,
+        )
     }
 }
 
+/// formats a signed number given as its sign and absolute value,
+/// so that the "-0" of a negative timestamp can be printed too
+fn format_integer(
+    negative: bool,
+    abs: u64,
+    flags: Flags,
+    width: usize,
+    precision: Option<usize>,
+) -> String {
+    let num = abs.to_string();
+    let digits = if flags.group {
+        group_num(&num)
+    } else {
+        Cow::Borrowed(num.as_str())
+    };
+    let sign = if negative {
+        "-"
+    } else if flags.sign {
+        "+"
+    } else if flags.space {
+        " "
+    } else {
+        ""
+    };
+    pad_number(sign, &digits, flags, width, precision)
+}
+
+/// formats seconds since the Epoch, followed by as many digits of the
+/// fraction as the precision asks for
+///
+/// Like GNU stat, the width applies to the whole value: the seconds are
+/// padded to whatever the decimal point and the fraction leave over, and
+/// a left-adjusted value is padded after the fraction.
+fn format_timestamp(
+    secs: i64,
+    nsecs: i64,
+    flags: Flags,
+    width: usize,
+    precision: Option<usize>,
+) -> String {
+    let precision = precision.unwrap_or(0);
+    let prec = precision.min(9);
+    let divisor = 10_i64.pow(9 - prec as u32);
+    let mut frac = nsecs / divisor;
+    let mut secs = secs;
+    let mut minus_zero = false;
+    if secs < 0 && nsecs != 0 {
+        // the fraction counts down from the next second towards zero
+        frac = 1_000_000_000 / divisor - frac - i64::from(nsecs % divisor != 0);
+        secs += 1;
+        minus_zero = secs == 0;
+    }
+    if precision == 0 {
+        return format_integer(
+            minus_zero || secs < 0,
+            secs.unsigned_abs(),
+            flags,
+            width,
+            None,
+        );
+    }
+
+    let sec_width = if width <= 1 {
+        width
+    } else if !flags.left && width > precision + 2 {
+        width - 1 - precision
+    } else {
+        0
+    };
+    let mut s = format_integer(
+        minus_zero || secs < 0,
+        secs.unsigned_abs(),
+        flags,
+        sec_width,
+        None,
+    );
+    let int_len = s.len() as i64;
+    let width = width as i64;
+    let trailing_width = if int_len < width && 1 < width - int_len {
+        width - int_len - 1 - prec as i64
+    } else {
+        0
+    };
+    s.push_str(&format!(".{frac:0prec$}"));
+    // GNU pads a negative trailing width too, as printf would
+    s.push_str(&pad(
+        &"0".repeat(precision - prec),
+        true,
+        trailing_width.unsigned_abs() as usize,
+        Padding::Space,
+    ));
+    s
+}
+
 impl Stater {
     fn generate_tokens(format_str: &str, use_printf: bool) -> UResult<Vec<Token>> {
         let mut tokens = Vec::new();
@@ -330,7 +444,8 @@ impl Stater {
                             ' ' => flag.space = true,
                             '+' => flag.sign = true,
                             '\'' => flag.group = true,
-                            'I' => unimplemented!(),
+                            // glibc's locale digits, which are the same in every locale we support
+                            'I' => {}
                             _ => break,
                         }
                         i += 1;
@@ -339,6 +454,7 @@ impl Stater {
 
                     let mut width = 0;
                     let mut precision = None;
+                    let mut bare_dot = false;
                     let mut j = i;
 
                     if let Some((field_width, offset)) = format_str[j..].scan_num::<usize>() {
@@ -351,6 +467,7 @@ impl Stater {
                         j += 1;
                         check_bound(format_str, bound, old, j)?;
 
+                        bare_dot = !chars[j].is_ascii_digit();
                         match format_str[j..].scan_num::<i32>() {
                             Some((value, offset)) => {
                                 if value >= 0 {
@@ -364,10 +481,30 @@ impl Stater {
                     }
 
                     i = j;
+                    if chars[i] == '%' {
+                        return Err(USimpleError::new(
+                            1,
+                            format!("{}: invalid directive", format_str[old..=i].quote()),
+                        ));
+                    }
+                    let mut modifier = None;
+                    if matches!(chars[i], 'H' | 'L')
+                        && i + 1 < bound
+                        && matches!(chars[i + 1], 'd' | 'r')
+                    {
+                        modifier = Some(chars[i]);
+                        i += 1;
+                    }
+                    // timestamps show all nine digits of the fraction
+                    // when the precision has no digits
+                    if bare_dot && matches!(chars[i], 'W' | 'X' | 'Y' | 'Z') {
+                        precision = Some(9);
+                    }
                     tokens.push(Token::Directive {
                         width,
                         flag,
                         precision,
+                        modifier,
                         format: chars[i],
                     });
                 }
@@ -478,8 +615,18 @@ impl Stater {
         })
     }
 
-    fn find_mount_point<P: AsRef<Path>>(&self, p: P) -> Option<String> {
-        let path = p.as_ref().canonicalize().ok()?;
+    fn find_mount_point<P: AsRef<Path>>(&self, p: P, is_symlink: bool) -> Option<String> {
+        let p = p.as_ref();
+        let path = if is_symlink {
+            // the link itself, not its target, lives below the mount point
+            let dir = match p.parent() {
+                Some(dir) if !dir.as_os_str().is_empty() => dir,
+                _ => Path::new("."),
+            };
+            dir.canonicalize().ok()?.join(p.file_name()?)
+        } else {
+            p.canonicalize().ok()?
+        };
 
         for root in self.mount_list.as_ref()? {
             if path.starts_with(root) {
@@ -504,6 +651,27 @@ impl Stater {
         ret
     }
 
+    /// prints the tokens, asking `directive` for the value of each directive
+    /// given its modifier and format character
+    fn print_tokens<F>(tokens: &[Token], mut directive: F) -> UResult<()>
+    where
+        F: FnMut(Option<char>, char) -> UResult<OutputType>,
+    {
+        for t in tokens {
+            match *t {
+                Token::Char(c) => print!("{c}"),
+                Token::Directive {
+                    flag,
+                    width,
+                    precision,
+                    modifier,
+                    format,
+                } => print_it(&directive(modifier, format)?, flag, width, precision),
+            }
+        }
+        Ok(())
+    }
+
     fn do_stat(&self, file: &OsStr, stdin_is_fifo: bool) -> i32 {
         let display_name = file.to_string_lossy();
         let file = if cfg!(unix) && display_name == "-" {
@@ -516,209 +684,195 @@ impl Stater {
             OsString::from(file)
         };
 
-        if !self.show_fs {
-            let result = if self.follow || stdin_is_fifo && display_name == "-" {
-                fs::metadata(&file)
+        let result = if self.show_fs {
+            self.print_fs_stat(&file, &display_name)
+        } else {
+            self.print_file_stat(&file, &display_name, stdin_is_fifo)
+        };
+        match result {
+            Ok(()) => 0,
+            Err(e) => {
+                show!(e);
+                1
+            }
+        }
+    }
+
+    fn print_file_stat(
+        &self,
+        file: &OsStr,
+        display_name: &str,
+        stdin_is_fifo: bool,
+    ) -> UResult<()> {
+        let result = if self.follow || stdin_is_fifo && display_name == "-" {
+            fs::metadata(file)
+        } else {
+            fs::symlink_metadata(file)
+        };
+        let meta = result.map_err_context(|| format!("cannot stat {}", display_name.quote()))?;
+        let file_type = meta.file_type();
+        let tokens =
+            if self.from_user || !(file_type.is_char_device() || file_type.is_block_device()) {
+                &self.default_tokens
             } else {
-                fs::symlink_metadata(&file)
+                &self.default_dev_tokens
             };
-            match result {
-                Ok(meta) => {
-                    let file_type = meta.file_type();
-                    let tokens = if self.from_user
-                        || !(file_type.is_char_device() || file_type.is_block_device())
-                    {
-                        &self.default_tokens
+
+        Self::print_tokens(tokens, |modifier, format| {
+            self.file_directive(file, display_name, &meta, modifier, format)
+        })
+    }
+
+    /// the value of a directive for files (without --file-system)
+    fn file_directive(
+        &self,
+        file: &OsStr,
+        display_name: &str,
+        meta: &fs::Metadata,
+        modifier: Option<char>,
+        format: char,
+    ) -> UResult<OutputType> {
+        let file_type = meta.file_type();
+        let output = match (modifier, format) {
+            // major and minor device numbers in decimal
+            (Some('H'), 'd') => OutputType::Unsigned(major(meta.dev())),
+            (Some('L'), 'd') => OutputType::Unsigned(minor(meta.dev())),
+            (Some('H'), 'r') => OutputType::Unsigned(major(meta.rdev())),
+            (Some('L'), 'r') => OutputType::Unsigned(minor(meta.rdev())),
+            (Some(_), _) => OutputType::Unknown,
+
+            // access rights in octal
+            (None, 'a') => OutputType::UnsignedOct(0o7777 & meta.mode()),
+            // access rights in human readable form
+            (None, 'A') => OutputType::Str(display_permissions(meta, true)),
+            // number of blocks allocated (see %B)
+            (None, 'b') => OutputType::Unsigned(meta.blocks()),
+
+            // the size in bytes of each block reported by %b
+            // FIXME: blocksize differs on various platform
+            // See coreutils/gnulib/lib/stat-size.h ST_NBLOCKSIZE // spell-checker:disable-line
+            (None, 'B') => OutputType::Unsigned(512),
+
+            // device number in decimal
+            (None, 'd') => OutputType::Unsigned(meta.dev()),
+            // device number in hex
+            (None, 'D') => OutputType::UnsignedHex(meta.dev()),
+            // raw mode in hex
+            (None, 'f') => OutputType::UnsignedHex(meta.mode() as u64),
+            // file type
+            (None, 'F') => {
+                OutputType::Str(pretty_filetype(meta.mode() as mode_t, meta.len()).to_owned())
+            }
+            // group ID of owner
+            (None, 'g') => OutputType::Unsigned(meta.gid() as u64),
+            // group name of owner
+            (None, 'G') => {
+                let group_name =
+                    entries::gid2grp(meta.gid()).unwrap_or_else(|_| "UNKNOWN".to_owned());
+                OutputType::Str(group_name)
+            }
+            // number of hard links
+            (None, 'h') => OutputType::Unsigned(meta.nlink()),
+            // inode number
+            (None, 'i') => OutputType::Unsigned(meta.ino()),
+            // mount point
+            (None, 'm') => match self.find_mount_point(file, file_type.is_symlink()) {
+                Some(mount_point) => OutputType::Str(mount_point),
+                None => OutputType::Unknown,
+            },
+            // file name
+            (None, 'n') => OutputType::Str(display_name.to_owned()),
+            // quoted file name with dereference if symbolic link;
+            // the default formats show it unquoted
+            (None, 'N') => {
+                let name = if self.from_user {
+                    display_name.quote().to_string()
+                } else {
+                    display_name.to_owned()
+                };
+                if file_type.is_symlink() {
+                    let dst = fs::read_link(file).map_err_context(|| {
+                        format!("cannot read symbolic link {}", display_name.quote())
+                    })?;
+                    let dst = if self.from_user {
+                        dst.quote().to_string()
                     } else {
-                        &self.default_dev_tokens
+                        dst.to_string_lossy().into_owned()
                     };
-
-                    for t in tokens.iter() {
-                        match *t {
-                            Token::Char(c) => print!("{c}"),
-                            Token::Directive {
-                                flag,
-                                width,
-                                precision,
-                                format,
-                            } => {
-                                let output = match format {
-                                    // access rights in octal
-                                    'a' => OutputType::UnsignedOct(0o7777 & meta.mode()),
-                                    // access rights in human readable form
-                                    'A' => OutputType::Str(display_permissions(&meta, true)),
-                                    // number of blocks allocated (see %B)
-                                    'b' => OutputType::Unsigned(meta.blocks()),
-
-                                    // the size in bytes of each block reported by %b
-                                    // FIXME: blocksize differs on various platform
-                                    // See coreutils/gnulib/lib/stat-size.h ST_NBLOCKSIZE // spell-checker:disable-line
-                                    'B' => OutputType::Unsigned(512),
-
-                                    // device number in decimal
-                                    'd' => OutputType::Unsigned(meta.dev()),
-                                    // device number in hex
-                                    'D' => OutputType::UnsignedHex(meta.dev()),
-                                    // raw mode in hex
-                                    'f' => OutputType::UnsignedHex(meta.mode() as u64),
-                                    // file type
-                                    'F' => OutputType::Str(
-                                        pretty_filetype(meta.mode() as mode_t, meta.len())
-                                            .to_owned(),
-                                    ),
-                                    // group ID of owner
-                                    'g' => OutputType::Unsigned(meta.gid() as u64),
-                                    // group name of owner
-                                    'G' => {
-                                        let group_name = entries::gid2grp(meta.gid())
-                                            .unwrap_or_else(|_| "UNKNOWN".to_owned());
-                                        OutputType::Str(group_name)
-                                    }
-                                    // number of hard links
-                                    'h' => OutputType::Unsigned(meta.nlink()),
-                                    // inode number
-                                    'i' => OutputType::Unsigned(meta.ino()),
-                                    // mount point
-                                    'm' => OutputType::Str(self.find_mount_point(&file).unwrap()),
-                                    // file name
-                                    'n' => OutputType::Str(display_name.to_string()),
-                                    // quoted file name with dereference if symbolic link
-                                    'N' => {
-                                        let file_name = if file_type.is_symlink() {
-                                            let dst = match fs::read_link(&file) {
-                                                Ok(path) => path,
-                                                Err(e) => {
-                                                    println!("{e}");
-                                                    return 1;
-                                                }
-                                            };
-                                            format!("{} -> {}", display_name.quote(), dst.quote())
-                                        } else {
-                                            display_name.to_string()
-                                        };
-                                        OutputType::Str(file_name)
-                                    }
-                                    // optimal I/O transfer size hint
-                                    'o' => OutputType::Unsigned(meta.blksize()),
-                                    // total size, in bytes
-                                    's' => OutputType::Integer(meta.len() as i64),
-                                    // major device type in hex, for character/block device special
-                                    // files
-                                    't' => OutputType::UnsignedHex(meta.rdev() >> 8),
-                                    // minor device type in hex, for character/block device special
-                                    // files
-                                    'T' => OutputType::UnsignedHex(meta.rdev() & 0xff),
-                                    // user ID of owner
-                                    'u' => OutputType::Unsigned(meta.uid() as u64),
-                                    // user name of owner
-                                    'U' => {
-                                        let user_name = entries::uid2usr(meta.uid())
-                                            .unwrap_or_else(|_| "UNKNOWN".to_owned());
-                                        OutputType::Str(user_name)
-                                    }
-
-                                    // time of file birth, human-readable; - if unknown
-                                    'w' => OutputType::Str(meta.pretty_birth()),
-
-                                    // time of file birth, seconds since Epoch; 0 if unknown
-                                    'W' => OutputType::Unsigned(meta.birth()),
-
-                                    // time of last access, human-readable
-                                    'x' => OutputType::Str(pretty_time(
-                                        meta.atime(),
-                                        meta.atime_nsec(),
-                                    )),
-                                    // time of last access, seconds since Epoch
-                                    'X' => OutputType::Integer(meta.atime()),
-                                    // time of last data modification, human-readable
-                                    'y' => OutputType::Str(pretty_time(
-                                        meta.mtime(),
-                                        meta.mtime_nsec(),
-                                    )),
-                                    // time of last data modification, seconds since Epoch
-                                    'Y' => OutputType::Integer(meta.mtime()),
-                                    // time of last status change, human-readable
-                                    'z' => OutputType::Str(pretty_time(
-                                        meta.ctime(),
-                                        meta.ctime_nsec(),
-                                    )),
-                                    // time of last status change, seconds since Epoch
-                                    'Z' => OutputType::Integer(meta.ctime()),
-
-                                    _ => OutputType::Unknown,
-                                };
-                                print_it(&output, flag, width, precision);
-                            }
-                        }
-                    }
-                }
-                Err(e) => {
-                    show_error!("cannot stat {}: {}", display_name.quote(), e);
-                    return 1;
+                    OutputType::Symlink(name, dst)
+                } else {
+                    OutputType::Str(name)
                 }
             }
-        } else {
-            #[cfg(unix)]
-            let p = file.as_bytes();
-            #[cfg(not(unix))]
-            let p = file.into_string().unwrap();
-            match statfs(p) {
-                Ok(meta) => {
-                    let tokens = &self.default_tokens;
-
-                    for t in tokens.iter() {
-                        match *t {
-                            Token::Char(c) => print!("{c}"),
-                            Token::Directive {
-                                flag,
-                                width,
-                                precision,
-                                format,
-                            } => {
-                                let output = match format {
-                                    // free blocks available to non-superuser
-                                    'a' => OutputType::Unsigned(meta.avail_blocks()),
-                                    // total data blocks in file system
-                                    'b' => OutputType::Unsigned(meta.total_blocks()),
-                                    // total file nodes in file system
-                                    'c' => OutputType::Unsigned(meta.total_file_nodes()),
-                                    // free file nodes in file system
-                                    'd' => OutputType::Unsigned(meta.free_file_nodes()),
-                                    // free blocks in file system
-                                    'f' => OutputType::Unsigned(meta.free_blocks()),
-                                    // file system ID in hex
-                                    'i' => OutputType::UnsignedHex(meta.fsid()),
-                                    // maximum length of filenames
-                                    'l' => OutputType::Unsigned(meta.namelen()),
-                                    // file name
-                                    'n' => OutputType::Str(display_name.to_string()),
-                                    // block size (for faster transfers)
-                                    's' => OutputType::Unsigned(meta.io_size()),
-                                    // fundamental block size (for block counts)
-                                    'S' => OutputType::Integer(meta.block_size()),
-                                    // file system type in hex
-                                    't' => OutputType::UnsignedHex(meta.fs_type() as u64),
-                                    // file system type in human readable form
-                                    'T' => OutputType::Str(pretty_fstype(meta.fs_type()).into()),
-                                    _ => OutputType::Unknown,
-                                };
-
-                                print_it(&output, flag, width, precision);
-                            }
-                        }
-                    }
-                }
-                Err(e) => {
-                    show_error!(
-                        "cannot read file system information for {}: {}",
-                        display_name.quote(),
-                        e
-                    );
-                    return 1;
-                }
+            // optimal I/O transfer size hint
+            (None, 'o') => OutputType::Unsigned(meta.blksize()),
+            // device type in decimal, for character/block device special files
+            (None, 'r') => OutputType::Unsigned(meta.rdev()),
+            // device type in hex, for character/block device special files
+            (None, 'R') => OutputType::UnsignedHex(meta.rdev()),
+            // total size, in bytes
+            (None, 's') => OutputType::Integer(meta.len() as i64),
+            // major device type in hex, for character/block device special
+            // files
+            (None, 't') => OutputType::UnsignedHex(major(meta.rdev())),
+            // minor device type in hex, for character/block device special
+            // files
+            (None, 'T') => OutputType::UnsignedHex(minor(meta.rdev())),
+            // user ID of owner
+            (None, 'u') => OutputType::Unsigned(meta.uid() as u64),
+            // user name of owner
+            (None, 'U') => {
+                let user_name =
+                    entries::uid2usr(meta.uid()).unwrap_or_else(|_| "UNKNOWN".to_owned());
+                OutputType::Str(user_name)
             }
-        }
-        0
+
+            // time of file birth, human-readable; - if unknown
+            (None, 'w') => OutputType::Str(meta.pretty_birth()),
+
+            // time of file birth, seconds since Epoch; 0 if unknown
+            (None, 'W') => match meta.birth() {
+                Some((secs, nsecs)) => OutputType::Timestamp(secs as i64, nsecs as i64),
+                None => OutputType::Unsigned(0),
+            },
+
+            // time of last access, human-readable
+            (None, 'x') => OutputType::Str(pretty_time(meta.atime(), meta.atime_nsec())),
+            // time of last access, seconds since Epoch
+            (None, 'X') => OutputType::Timestamp(meta.atime(), meta.atime_nsec()),
+            // time of last data modification, human-readable
+            (None, 'y') => OutputType::Str(pretty_time(meta.mtime(), meta.mtime_nsec())),
+            // time of last data modification, seconds since Epoch
+            (None, 'Y') => OutputType::Timestamp(meta.mtime(), meta.mtime_nsec()),
+            // time of last status change, human-readable
+            (None, 'z') => OutputType::Str(pretty_time(meta.ctime(), meta.ctime_nsec())),
+            // time of last status change, seconds since Epoch
+            (None, 'Z') => OutputType::Timestamp(meta.ctime(), meta.ctime_nsec()),
+
+            _ => OutputType::Unknown,
+        };
+        Ok(output)
+    }
+
+    fn print_fs_stat(&self, file: &OsStr, display_name: &str) -> UResult<()> {
+        #[cfg(unix)]
+        let p = file.as_bytes();
+        #[cfg(not(unix))]
+        let p = file.to_str().unwrap();
+        let meta = statfs(p).map_err(|e| {
+            USimpleError::new(
+                1,
+                format!(
+                    "cannot read file system information for {}: {}",
+                    display_name.quote(),
+                    e
+                ),
+            )
+        })?;
+
+        Self::print_tokens(&self.default_tokens, |modifier, format| {
+            Ok(fs_directive(&meta, display_name, modifier, format))
+        })
     }
 
     fn default_format(show_fs: bool, terse: bool, show_dev_type: bool) -> String {
@@ -739,9 +893,9 @@ impl Stater {
             [
                 "  File: %N\n  Size: %-10s\tBlocks: %-10b IO Block: %-6o %F\n",
                 if show_dev_type {
-                    "Device: %Dh/%dd\tInode: %-10i  Links: %-5h Device type: %t,%T\n"
+                    "Device: %Hd,%Ld\tInode: %-10i  Links: %-5h Device type: %Hr,%Lr\n"
                 } else {
-                    "Device: %Dh/%dd\tInode: %-10i  Links: %h\n"
+                    "Device: %Hd,%Ld\tInode: %-10i  Links: %h\n"
                 },
                 "Access: (%04a/%10.10A)  Uid: (%5u/%8U)   Gid: (%5g/%8G)\n",
                 "Access: %x\nModify: %y\nChange: %z\n Birth: %w\n",
@@ -751,6 +905,83 @@ impl Stater {
     }
 }
 
+/// the value of a directive for file systems (with --file-system)
+fn fs_directive(
+    meta: &StatFs,
+    display_name: &str,
+    modifier: Option<char>,
+    format: char,
+) -> OutputType {
+    let format = match modifier {
+        // the major and minor modifiers only apply to file directives
+        Some(_) => return OutputType::Str(format!("?{format}")),
+        None => format,
+    };
+    match format {
+        // free blocks available to non-superuser
+        'a' => OutputType::Unsigned(meta.avail_blocks()),
+        // total data blocks in file system
+        'b' => OutputType::Unsigned(meta.total_blocks()),
+        // total file nodes in file system
+        'c' => OutputType::Unsigned(meta.total_file_nodes()),
+        // free file nodes in file system
+        'd' => OutputType::Unsigned(meta.free_file_nodes()),
+        // free blocks in file system
+        'f' => OutputType::Unsigned(meta.free_blocks()),
+        // file system ID in hex
+        'i' => OutputType::UnsignedHex(meta.fsid()),
+        // maximum length of filenames
+        'l' => OutputType::Unsigned(meta.namelen()),
+        // file name
+        'n' => OutputType::Str(display_name.to_owned()),
+        // block size (for faster transfers)
+        's' => OutputType::Unsigned(meta.io_size()),
+        // fundamental block size (for block counts)
+        'S' => OutputType::Integer(meta.block_size()),
+        // file system type in hex
+        't' => OutputType::UnsignedHex(meta.fs_type() as u64),
+        // file system type in human readable form
+        'T' => OutputType::Str(pretty_fstype(meta.fs_type()).into()),
+        _ => OutputType::Unknown,
+    }
+}
+
+/// the major number of a device ID
+#[cfg(any(target_os = "linux", target_os = "android"))]
+fn major(dev: u64) -> u64 {
+    ((dev >> 32) & 0xffff_f000) | ((dev >> 8) & 0x0000_0fff)
+}
+
+/// the minor number of a device ID
+#[cfg(any(target_os = "linux", target_os = "android"))]
+fn minor(dev: u64) -> u64 {
+    ((dev >> 12) & 0xffff_ff00) | (dev & 0x0000_00ff)
+}
+
+/// the major number of a device ID
+#[cfg(target_vendor = "apple")]
+fn major(dev: u64) -> u64 {
+    (dev >> 24) & 0xff
+}
+
+/// the minor number of a device ID
+#[cfg(target_vendor = "apple")]
+fn minor(dev: u64) -> u64 {
+    dev & 0x00ff_ffff
+}
+
+/// the major number of a device ID
+#[cfg(not(any(target_os = "linux", target_os = "android", target_vendor = "apple")))]
+fn major(dev: u64) -> u64 {
+    ((dev >> 32) & 0xffff_ff00) | ((dev >> 8) & 0xff)
+}
+
+/// the minor number of a device ID
+#[cfg(not(any(target_os = "linux", target_os = "android", target_vendor = "apple")))]
+fn minor(dev: u64) -> u64 {
+    ((dev >> 24) & 0x0000_ff00) | (dev & 0xffff_00ff)
+}
+
 fn get_long_usage() -> &'static str {
     "
 The valid format sequences for files (without --file-system):
@@ -760,8 +991,10 @@ The valid format sequences for files (without --file-system):
   %b   number of blocks allocated (see %B)
   %B   the size in bytes of each block reported by %b
   %C   SELinux security context string
-  %d   device number in decimal
-  %D   device number in hex
+  %d   device number in decimal (st_dev)
+  %D   device number in hex (st_dev)
+  %Hd  major device number in decimal
+  %Ld  minor device number in decimal
   %f   raw mode in hex
   %F   file type
   %g   group ID of owner
@@ -773,6 +1006,10 @@ The valid format sequences for files (without --file-system):
   %N   quoted file name with dereference if symbolic link
   %o   optimal I/O transfer size hint
   %s   total size, in bytes
+  %r   device type in decimal (st_rdev)
+  %R   device type in hex (st_rdev)
+  %Hr  major device type in decimal, for character/block device special files
+  %Lr  minor device type in decimal, for character/block device special files
   %t   major device type in hex, for character/block device special files
   %T   minor device type in hex, for character/block device special files
   %u   user ID of owner
@@ -879,7 +1116,7 @@ pub fn uu_app() -> Command {
 
 #[cfg(test)]
 mod tests {
-    use super::{group_num, Flags, ScanUtil, Stater, Token};
+    use super::{format_timestamp, group_num, Flags, ScanUtil, Stater, Token};
 
     #[test]
     fn test_scanners() {
@@ -927,6 +1164,7 @@ mod tests {
                 },
                 width: 10,
                 precision: Some(2),
+                modifier: None,
                 format: 'a',
             },
             Token::Char('c'),
@@ -938,6 +1176,7 @@ mod tests {
                 },
                 width: 5,
                 precision: Some(0),
+                modifier: None,
                 format: 'w',
             },
             Token::Char('\n'),
@@ -958,6 +1197,7 @@ mod tests {
                 },
                 width: 15,
                 precision: None,
+                modifier: None,
                 format: 'a',
             },
             Token::Char('\t'),
@@ -977,6 +1217,7 @@ mod tests {
                 },
                 width: 20,
                 precision: None,
+                modifier: None,
                 format: 'w',
             },
             Token::Char('\x12'),
@@ -987,4 +1228,53 @@ mod tests {
         ];
         assert_eq!(&expected, &Stater::generate_tokens(s, true).unwrap());
     }
+
+    #[test]
+    fn modifier_and_time_precision() {
+        let s = "%Hd%Lr%Hx%.Y%.s";
+        let directive = |precision, modifier, format| Token::Directive {
+            flag: Flags::default(),
+            width: 0,
+            precision,
+            modifier,
+            format,
+        };
+        let expected = vec![
+            directive(None, Some('H'), 'd'),
+            directive(None, Some('L'), 'r'),
+            directive(None, None, 'H'),
+            Token::Char('x'),
+            directive(Some(9), None, 'Y'),
+            directive(Some(0), None, 's'),
+            Token::Char('\n'),
+        ];
+        assert_eq!(&expected, &Stater::generate_tokens(s, false).unwrap());
+    }
+
+    #[test]
+    fn timestamp() {
+        let flags = Flags::default();
+        let left = Flags {
+            left: true,
+            ..Default::default()
+        };
+        assert_eq!("5", format_timestamp(5, 500_000_000, flags, 0, None));
+        assert_eq!("5.50", format_timestamp(5, 500_000_000, flags, 0, Some(2)));
+        assert_eq!(
+            "   5.50",
+            format_timestamp(5, 500_000_000, flags, 7, Some(2))
+        );
+        assert_eq!(
+            "5.50   ",
+            format_timestamp(5, 500_000_000, left, 7, Some(2))
+        );
+        assert_eq!(
+            "-5.50",
+            format_timestamp(-6, 500_000_000, flags, 0, Some(2))
+        );
+        assert_eq!(
+            "-0.25",
+            format_timestamp(-1, 750_000_000, flags, 0, Some(2))
+        );
+    }
 }
@@ -1,68 +1,120 @@
-// spell-checker:ignore (ToDO) IOFBF IOLBF IONBF cstdio setvbuf
+// spell-checker:ignore (ToDO) IOFBF IOLBF IONBF setvbuf stdinp stdoutp stderrp
 
-use cpp::cpp;
-use libc::{c_char, c_int, size_t, FILE, _IOFBF, _IOLBF, _IONBF};
+//! Preloaded into the command run by stdbuf, to set the buffering of its
+//! standard streams from the `_STDBUF_I`, `_STDBUF_O` and `_STDBUF_E`
+//! variables before its `main` runs.
+//!
+//! Only the C library is used, without any C code, so that the library can
+//! be preloaded into any dynamically linked program. The streams are found
+//! through the symbols each C library exports for them.
+
+use libc::{c_char, c_int, size_t, _IOFBF, _IOLBF, _IONBF, FILE};
 use std::env;
 use std::ptr;
-use uucore::crash;
 
-cpp! {{
-    #include <cstdio>
+#[cfg(any(target_os = "linux", target_os = "android"))]
+mod streams {
+    use libc::FILE;
 
     extern "C" {
-        void __stdbuf(void);
+        static mut stdin: *mut FILE;
+        static mut stdout: *mut FILE;
+        static mut stderr: *mut FILE;
+    }
 
-        void __attribute((constructor))
-        __stdbuf_init(void) {
-            __stdbuf();
-        }
+    pub unsafe fn get() -> [*mut FILE; 3] {
+        [stdin, stdout, stderr]
+    }
+}
 
-        FILE *__stdbuf_get_stdin() { return stdin; }
-        FILE *__stdbuf_get_stdout() { return stdout; }
-        FILE *__stdbuf_get_stderr() { return stderr; }
+#[cfg(any(
+    target_vendor = "apple",
+    target_os = "freebsd",
+    target_os = "dragonfly"
+))]
+mod streams {
+    use libc::FILE;
+
+    extern "C" {
+        static mut __stdinp: *mut FILE;
+        static mut __stdoutp: *mut FILE;
+        static mut __stderrp: *mut FILE;
     }
-}}
 
-extern "C" {
-    fn __stdbuf_get_stdin() -> *mut FILE;
-    fn __stdbuf_get_stdout() -> *mut FILE;
-    fn __stdbuf_get_stderr() -> *mut FILE;
+    pub unsafe fn get() -> [*mut FILE; 3] {
+        [__stdinp, __stdoutp, __stderrp]
+    }
 }
 
-fn set_buffer(stream: *mut FILE, value: &str) {
-    let (mode, size): (c_int, size_t) = match value {
-        "0" => (_IONBF, 0_usize),
-        "L" => (_IOLBF, 0_usize),
-        input => {
-            let buff_size: usize = match input.parse() {
-                Ok(num) => num,
-                Err(e) => crash!(1, "incorrect size of buffer!: {}", e),
-            };
-            (_IOFBF, buff_size as size_t)
-        }
-    };
-    let res: c_int;
-    unsafe {
-        let buffer: *mut c_char = ptr::null_mut();
-        assert!(buffer.is_null());
-        res = libc::setvbuf(stream, buffer, mode, size);
+#[cfg(not(any(
+    target_os = "linux",
+    target_os = "android",
+    target_vendor = "apple",
+    target_os = "freebsd",
+    target_os = "dragonfly"
+)))]
+mod streams {
+    use libc::FILE;
+
+    pub unsafe fn get() -> [*mut FILE; 3] {
+        [std::ptr::null_mut(); 3]
     }
-    if res != 0 {
-        crash!(res, "error while calling setvbuf!");
+}
+
+/// Run [`__stdbuf`] when the library is loaded, like a C constructor.
+#[used]
+#[cfg_attr(
+    any(
+        target_os = "linux",
+        target_os = "android",
+        target_os = "freebsd",
+        target_os = "dragonfly"
+    ),
+    link_section = ".init_array"
+)]
+#[cfg_attr(target_vendor = "apple", link_section = "__DATA,__mod_init_func")]
+static INIT: unsafe extern "C" fn() = __stdbuf;
+
+unsafe fn set_buffer(stream: *mut FILE, name: &str, value: &str) {
+    let (mode, buffer, size): (c_int, *mut c_char, size_t) = match value {
+        "0" => (_IONBF, ptr::null_mut(), 0),
+        "L" => (_IOLBF, ptr::null_mut(), 0),
+        input => match input.parse::<size_t>() {
+            // Some C libraries ignore the size unless they are given a buffer.
+            Ok(size) => {
+                let buffer = libc::malloc(size);
+                if buffer.is_null() {
+                    eprintln!("failed to allocate a {} byte stdio buffer", size);
+                    return;
+                }
+                (_IOFBF, buffer.cast(), size)
+            }
+            Err(_) => {
+                eprintln!("invalid buffering mode {} for {}", value, name);
+                return;
+            }
+        },
+    };
+    if libc::setvbuf(stream, buffer, mode, size) != 0 {
+        eprintln!("could not set buffering of {} to mode {}", name, value);
+        libc::free(buffer.cast());
     }
 }
 
+/// Set the buffering of the standard streams.
+///
 /// # Safety
-/// ToDO ... (safety note)
+/// This must run before the streams are used, as setvbuf requires.
 #[no_mangle]
 pub unsafe extern "C" fn __stdbuf() {
-    if let Ok(val) = env::var("_STDBUF_E") {
-        set_buffer(__stdbuf_get_stderr(), &val);
-    }
-    if let Ok(val) = env::var("_STDBUF_I") {
-        set_buffer(__stdbuf_get_stdin(), &val);
-    }
-    if let Ok(val) = env::var("_STDBUF_O") {
-        set_buffer(__stdbuf_get_stdout(), &val);
+    let [stdin, stdout, stderr] = streams::get();
+    for (stream, name, var) in [
+        (stderr, "stderr", "_STDBUF_E"),
+        (stdin, "stdin", "_STDBUF_I"),
+        (stdout, "stdout", "_STDBUF_O"),
+    ] {
+        if let (false, Ok(value)) = (stream.is_null(), env::var(var)) {
+            set_buffer(stream, name, &value);
+        }
     }
 }
@@ -0,0 +1,236 @@
+// * This file is part of the uutils coreutils package.
+// *
+// * For the full copyright and license information, please view the LICENSE
+// * file that was distributed with this source code.
+
+//! Make the dynamic linker load libstdbuf into the command.
+//!
+//! The library is written to a temporary directory, and named in the
+//! variable that the dynamic linker of the platform reads the libraries to
+//! preload from. Commands the linker won't preload into are warned about, as
+//! their buffering can't be changed.
+
+// spell-checker:ignore (ToDO) dyld dylib libstdbuf phdr phoff phentsize phnum
+
+use std::env;
+use std::ffi::{OsStr, OsString};
+use std::fs::{self, File};
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use tempfile::TempDir;
+use uucore::display::Quotable;
+use uucore::error::{UResult, USimpleError};
+use uucore::show_warning;
+
+const STDBUF_INJECT: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/libstdbuf.so"));
+
+/// The variable listing the libraries to preload, and the extension of
+/// libraries.
+#[cfg(any(
+    target_os = "linux",
+    target_os = "android",
+    target_os = "freebsd",
+    target_os = "dragonfly"
+))]
+fn preload_strings() -> UResult<(&'static str, &'static str)> {
+    Ok(("LD_PRELOAD", "so"))
+}
+
+/// The variable listing the libraries to preload, and the extension of
+/// libraries.
+#[cfg(target_vendor = "apple")]
+fn preload_strings() -> UResult<(&'static str, &'static str)> {
+    Ok(("DYLD_INSERT_LIBRARIES", "dylib"))
+}
+
+/// The variable listing the libraries to preload, and the extension of
+/// libraries.
+#[cfg(not(any(
+    target_os = "linux",
+    target_os = "android",
+    target_os = "freebsd",
+    target_os = "dragonfly",
+    target_vendor = "apple"
+)))]
+fn preload_strings() -> UResult<(&'static str, &'static str)> {
+    Err(USimpleError::new(
+        125,
+        "preloading libraries is not supported on this platform",
+    ))
+}
+
+/// Write libstdbuf to `tmp_dir`, and return the variable to set to preload
+/// it, with its value.
+///
+/// Libraries that are already preloaded are kept, before libstdbuf.
+pub(crate) fn get_preload_env(tmp_dir: &TempDir) -> UResult<(&'static str, OsString)> {
+    let (preload, extension) = preload_strings()?;
+    let inject_path = tmp_dir.path().join("libstdbuf").with_extension(extension);
+
+    File::create(&inject_path)
+        .and_then(|mut file| file.write_all(STDBUF_INJECT))
+        .map_err(|e| {
+            USimpleError::new(
+                125,
+                format!(
+                    "failed to write {}: {}",
+                    inject_path.quote(),
+                    uucore::error::strip_errno(&e)
+                ),
+            )
+        })?;
+
+    let mut value = OsString::new();
+    if let Some(libs) = env::var_os(preload).filter(|libs| !libs.is_empty()) {
+        value.push(libs);
+        value.push(":");
+    }
+    value.push(inject_path);
+    Ok((preload, value))
+}
+
+/// Find the file that running `program` executes, like execvp does.
+fn find_program(program: &OsStr) -> Option<PathBuf> {
+    let program = Path::new(program);
+    if program.components().count() > 1 {
+        return Some(program.to_path_buf());
+    }
+    env::split_paths(&env::var_os("PATH")?)
+        .map(|dir| dir.join(program))
+        .find(|path| path.is_file())
+}
+
+/// Whether the ELF executable read from `file` needs the dynamic linker, or
+/// `None` if it is not an ELF executable.
+fn elf_is_dynamic(file: &mut (impl Read + Seek)) -> io::Result<Option<bool>> {
+    const PT_INTERP: u32 = 3;
+
+    let mut header = [0; 64];
+    if file.read(&mut header)? < 52 || &header[..4] != b"\x7fELF" {
+        return Ok(None);
+    }
+    let is_64_bit = header[4] == 2;
+    let big_endian = header[5] == 2;
+    let read_uint = |bytes: &[u8]| {
+        let mut value = 0u64;
+        for i in 0..bytes.len() {
+            let byte = if big_endian {
+                bytes[i]
+            } else {
+                bytes[bytes.len() - 1 - i]
+            };
+            value = value << 8 | u64::from(byte);
+        }
+        value
+    };
+    let (phoff, phentsize, phnum) = if is_64_bit {
+        (
+            read_uint(&header[0x20..0x28]),
+            read_uint(&header[0x36..0x38]),
+            read_uint(&header[0x38..0x3a]),
+        )
+    } else {
+        (
+            read_uint(&header[0x1c..0x20]),
+            read_uint(&header[0x2a..0x2c]),
+            read_uint(&header[0x2c..0x2e]),
+        )
+    };
+
+    let mut p_type = [0; 4];
+    for i in 0..phnum {
+        file.seek(SeekFrom::Start(phoff + i * phentsize))?;
+        file.read_exact(&mut p_type)?;
+        if read_uint(&p_type) == u64::from(PT_INTERP) {
+            return Ok(Some(true));
+        }
+    }
+    Ok(Some(false))
+}
+
+/// Why the dynamic linker won't preload libstdbuf into `path`, if it won't.
+#[cfg(not(target_vendor = "apple"))]
+fn preload_blocker(path: &Path) -> Option<&'static str> {
+    use std::os::unix::fs::MetadataExt;
+    const S_ISUID: u32 = 0o4000;
+    const S_ISGID: u32 = 0o2000;
+
+    let metadata = fs::metadata(path).ok()?;
+    let mode = metadata.mode();
+    // Only libraries from the system directories are preloaded into programs
+    // that run with other IDs than the user's.
+    let (uid, gid) = unsafe { (libc::getuid(), libc::getgid()) };
+    if (mode & S_ISUID != 0 && metadata.uid() != uid)
+        || (mode & S_ISGID != 0 && metadata.gid() != gid)
+    {
+        return Some("it is set-user-ID or set-group-ID");
+    }
+    match elf_is_dynamic(&mut File::open(path).ok()?) {
+        Ok(Some(false)) => Some("it is statically linked"),
+        _ => None,
+    }
+}
+
+/// Why the dynamic linker won't preload libstdbuf into `path`, if it won't.
+#[cfg(target_vendor = "apple")]
+fn preload_blocker(path: &Path) -> Option<&'static str> {
+    // System Integrity Protection removes the DYLD_ variables from the
+    // environment of the programs it protects.
+    const PROTECTED: &[&str] = &[
+        "/bin",
+        "/sbin",
+        "/usr/bin",
+        "/usr/sbin",
+        "/usr/libexec",
+        "/System",
+    ];
+
+    let path = fs::canonicalize(path).ok()?;
+    PROTECTED
+        .iter()
+        .any(|dir| path.starts_with(dir))
+        .then(|| "it is protected by System Integrity Protection")
+}
+
+/// Warn if the buffering of `program` can't be changed.
+pub(crate) fn check_program(program: &OsStr) {
+    if let Some(reason) = find_program(program).as_deref().and_then(preload_blocker) {
+        show_warning!(
+            "{} can't be preloaded with libstdbuf, as {}, so its buffering is unchanged",
+            program.quote(),
+            reason
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::elf_is_dynamic;
+    use std::io::Cursor;
+
+    /// A 64-bit little endian ELF header with program headers of `types`
+    fn elf(types: &[u32]) -> Cursor<Vec<u8>> {
+        let mut data = vec![0; 64];
+        data[..6].copy_from_slice(b"\x7fELF\x02\x01");
+        data[0x20] = 64;
+        data[0x36] = 56;
+        data[0x38] = types.len() as u8;
+        for t in types {
+            let mut phdr = vec![0; 56];
+            phdr[..4].copy_from_slice(&t.to_le_bytes());
+            data.extend(phdr);
+        }
+        Cursor::new(data)
+    }
+
+    #[test]
+    fn test_elf_is_dynamic() {
+        assert_eq!(elf_is_dynamic(&mut elf(&[6, 3, 1])).unwrap(), Some(true));
+        assert_eq!(elf_is_dynamic(&mut elf(&[1, 1])).unwrap(), Some(false));
+        let script = b"#!/bin/sh\necho this is a script, not an ELF executable\n";
+        assert_eq!(
+            elf_is_dynamic(&mut Cursor::new(script.to_vec())).unwrap(),
+            None
+        );
+    }
+}
@@ -5,20 +5,22 @@
 // * For the full copyright and license information, please view the LICENSE
 // * file that was distributed with this source code.
 
-// spell-checker:ignore (ToDO) tempdir dyld dylib dragonflybsd optgrps libstdbuf
+// spell-checker:ignore (ToDO) tempdir optgrps libstdbuf
+
+mod preload;
 
 use clap::{crate_version, Arg, ArgAction, ArgMatches, Command};
-use std::fs::File;
-use std::io::{self, Write};
+use std::io;
 use std::os::unix::process::ExitStatusExt;
-use std::path::PathBuf;
 use std::process;
 use tempfile::tempdir;
-use tempfile::TempDir;
-use uucore::error::{FromIo, UResult, USimpleError, UUsageError};
+use uucore::display::Quotable;
+use uucore::error::{strip_errno, FromIo, UResult, USimpleError, UUsageError};
 use uucore::parse_size::parse_size;
 use uucore::{crash, format_usage};
 
+use crate::preload::{check_program, get_preload_env};
+
 static ABOUT: &str =
     "Run COMMAND, with modified buffering operations for its standard streams.\n\n\
      Mandatory arguments to long options are mandatory for short options too.";
@@ -45,8 +47,6 @@ mod options {
     pub const COMMAND: &str = "command";
 }
 
-const STDBUF_INJECT: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/libstdbuf.so"));
-
 enum BufferType {
     Default,
     Line,
@@ -73,36 +73,6 @@ impl TryFrom<&ArgMatches> for ProgramOptions {
 
 struct ProgramOptionsError(String);
 
-#[cfg(any(
-    target_os = "linux",
-    target_os = "android",
-    target_os = "freebsd",
-    target_os = "netbsd",
-    target_os = "dragonflybsd"
-))]
-fn preload_strings() -> (&'static str, &'static str) {
-    ("LD_PRELOAD", "so")
-}
-
-#[cfg(target_vendor = "apple")]
-fn preload_strings() -> (&'static str, &'static str) {
-    ("DYLD_LIBRARY_PATH", "dylib")
-}
-
-#[cfg(not(any(
-    target_os = "linux",
-    target_os = "android",
-    target_os = "freebsd",
-    target_os = "netbsd",
-    target_os = "dragonflybsd",
-    target_vendor = "apple"
-)))]
-fn preload_strings() -> (&'static str, &'static str) {
-    use uucore::crash;
-
-    crash!(1, "Command not supported for this operating system!")
-}
-
 fn check_option(matches: &ArgMatches, name: &str) -> Result<BufferType, ProgramOptionsError> {
     match matches.get_one::<String>(name) {
         Some(value) => match value.as_str() {
@@ -142,16 +112,6 @@ fn set_command_env(command: &mut process::Command, buffer_name: &str, buffer_typ
     }
 }
 
-fn get_preload_env(tmp_dir: &mut TempDir) -> io::Result<(String, PathBuf)> {
-    let (preload, extension) = preload_strings();
-    let inject_path = tmp_dir.path().join("libstdbuf").with_extension(extension);
-
-    let mut file = File::create(&inject_path)?;
-    file.write_all(STDBUF_INJECT)?;
-
-    Ok((preload.to_owned(), inject_path))
-}
-
 #[uucore::main]
 pub fn uumain(args: impl uucore::Args) -> UResult<()> {
     let args = args.collect_ignore();
@@ -161,20 +121,42 @@ pub fn uumain(args: impl uucore::Args) -> UResult<()> {
     let options = ProgramOptions::try_from(&matches).map_err(|e| UUsageError::new(125, e.0))?;
 
     let mut command_values = matches.get_many::<String>(options::COMMAND).unwrap();
-    let mut command = process::Command::new(command_values.next().unwrap());
+    let program = command_values.next().unwrap();
+    let mut command = process::Command::new(program);
     let command_params: Vec<&str> = command_values.map(|s| s.as_ref()).collect();
 
-    let mut tmp_dir = tempdir().unwrap();
-    let (preload_env, libstdbuf) = get_preload_env(&mut tmp_dir).map_err_context(String::new)?;
+    let tmp_dir = tempdir().map_err(|e| {
+        USimpleError::new(
+            125,
+            format!(
+                "failed to create a temporary directory: {}",
+                strip_errno(&e)
+            ),
+        )
+    })?;
+    let (preload_env, libstdbuf) = get_preload_env(&tmp_dir)?;
+    check_program(program.as_ref());
     command.env(preload_env, libstdbuf);
     set_command_env(&mut command, "_STDBUF_I", &options.stdin);
     set_command_env(&mut command, "_STDBUF_O", &options.stdout);
     set_command_env(&mut command, "_STDBUF_E", &options.stderr);
     command.args(command_params);
 
-    let mut process = command
-        .spawn()
-        .map_err_context(|| "failed to execute process".to_string())?;
+    let mut process = command.spawn().map_err(|e| {
+        let code = if e.kind() == io::ErrorKind::NotFound {
+            127
+        } else {
+            126
+        };
+        USimpleError::new(
+            code,
+            format!(
+                "failed to run command {}: {}",
+                program.quote(),
+                strip_errno(&e)
+            ),
+        )
+    })?;
     let status = process.wait().map_err_context(String::new)?;
     match status.code() {
         Some(i) => {
@@ -117,7 +117,8 @@ pub struct Settings {
     pub follow: Option<FollowMode>,
     pub max_unchanged_stats: u32,
     pub mode: FilterMode,
-    pub pid: platform::Pid,
+    /// The processes whose termination ends following, 0 not being one.
+    pub pids: Vec<platform::Pid>,
     pub retry: bool,
     pub sleep_sec: Duration,
     pub use_polling: bool,
@@ -177,7 +178,7 @@ impl Settings {
             }
         }
 
-        if let Some(pid_str) = matches.get_one::<String>(options::PID) {
+        for pid_str in matches.get_many::<String>(options::PID).unwrap_or_default() {
             match pid_str.parse() {
                 Ok(pid) => {
                     // NOTE: on unix platform::Pid is i32, on windows platform::Pid is u32
@@ -190,7 +191,9 @@ impl Settings {
                         ));
                     }
 
-                    settings.pid = pid;
+                    if pid != 0 {
+                        settings.pids.push(pid);
+                    }
                 }
                 Err(e) => {
                     return Err(USimpleError::new(
@@ -246,10 +249,10 @@ impl Settings {
             }
         }
 
-        if self.pid != 0 {
+        if !self.pids.is_empty() {
             if self.follow.is_none() {
                 show_warning!("PID ignored; --pid=PID is useful only when following");
-            } else if !platform::supports_pid_checks(self.pid) {
+            } else if !self.pids.iter().copied().all(platform::supports_pid_checks) {
                 show_warning!("--pid=PID is not supported on this system");
             }
         }
@@ -259,7 +262,7 @@ impl Settings {
         // as `tty` (but no otherwise blocking stdin), then we print a warning that `--follow`
         // cannot be applied under these circumstances and is therefore ineffective.
         if self.follow.is_some() && self.has_stdin() {
-            let blocking_stdin = self.pid == 0
+            let blocking_stdin = self.pids.is_empty()
                 && self.follow == Some(FollowMode::Descriptor)
                 && self.num_inputs() == 1
                 && Handle::stdin().map_or(false, |handle| {
@@ -401,7 +404,8 @@ pub fn uu_app() -> Command {
             Arg::new(options::PID)
                 .long(options::PID)
                 .value_name("PID")
-                .help("With -f, terminate after process ID, PID dies"),
+                .action(ArgAction::Append)
+                .help("With -f, terminate after process ID, PID dies; can be repeated to watch multiple processes"),
         )
         .arg(
             Arg::new(options::verbosity::QUIET)
@@ -77,21 +77,19 @@ impl<'a> Iterator for ReverseChunks<'a> {
         // (that is, the chunk closest to the beginning of the file),
         // which contains the remainder of the bytes.
         let block_size = if self.block_idx == self.max_blocks_to_read - 1 {
-            self.size % BLOCK_SIZE
+            self.size - self.block_idx as u64 * BLOCK_SIZE
         } else {
             BLOCK_SIZE
         };
 
         // Seek backwards by the next chunk, read the full chunk into
         // `buf`, and then seek back to the start of the chunk again.
-        let mut buf = vec![0; BLOCK_SIZE as usize];
+        let mut buf = vec![0; block_size as usize];
         let pos = self
             .file
             .seek(SeekFrom::Current(-(block_size as i64)))
             .unwrap();
-        self.file
-            .read_exact(&mut buf[0..(block_size as usize)])
-            .unwrap();
+        self.file.read_exact(&mut buf).unwrap();
         let pos2 = self
             .file
             .seek(SeekFrom::Current(-(block_size as i64)))
@@ -100,7 +98,7 @@ impl<'a> Iterator for ReverseChunks<'a> {
 
         self.block_idx += 1;
 
-        Some(buf[0..(block_size as usize)].to_vec())
+        Some(buf)
     }
 }
 
@@ -7,7 +7,7 @@
 
 use crate::args::Settings;
 use crate::chunks::BytesChunkBuffer;
-use crate::paths::{HeaderPrinter, PathExtTail};
+use crate::paths::{HeaderPrinter, MetadataExtTail, PathExtTail};
 use crate::text;
 use std::collections::hash_map::Keys;
 use std::collections::HashMap;
@@ -151,7 +151,16 @@ impl FileHandling {
             chunks.print(writer)?;
 
             self.last.replace(path.to_owned());
-            self.update_metadata(path, None);
+            // If `path` has been replaced, the metadata of the file that was read is kept, so
+            // that the replacement is still noticed.
+            let new_md = path.metadata().ok();
+            let replaced = match (&self.get(path).metadata, &new_md) {
+                (Some(old_md), Some(new_md)) => !old_md.file_id_eq(new_md),
+                _ => false,
+            };
+            if !replaced {
+                self.update_metadata(path, new_md);
+            }
             Ok(true)
         } else {
             Ok(false)
@@ -96,7 +96,8 @@ pub struct Observer {
     pub orphans: Vec<PathBuf>,
     pub files: FileHandling,
 
-    pub pid: platform::Pid,
+    /// The processes whose termination ends following, without those that can't be checked.
+    pub pids: Vec<platform::Pid>,
 }
 
 impl Observer {
@@ -105,13 +106,9 @@ impl Observer {
         follow: Option<FollowMode>,
         use_polling: bool,
         files: FileHandling,
-        pid: platform::Pid,
+        mut pids: Vec<platform::Pid>,
     ) -> Self {
-        let pid = if platform::supports_pid_checks(pid) {
-            pid
-        } else {
-            0
-        };
+        pids.retain(|&pid| platform::supports_pid_checks(pid));
 
         Self {
             retry,
@@ -120,7 +117,7 @@ impl Observer {
             watcher_rx: None,
             orphans: Vec::new(),
             files,
-            pid,
+            pids,
         }
     }
 
@@ -130,7 +127,7 @@ impl Observer {
             settings.follow,
             settings.use_polling,
             FileHandling::from(settings),
-            settings.pid,
+            settings.pids.clone(),
         )
     }
 
@@ -254,6 +251,46 @@ impl Observer {
         Ok(())
     }
 
+    /// Reopens the files followed by name whose name now refers to another file.
+    ///
+    /// This notices rotated files even if polling reported no change, e.g. because the new file
+    /// has the same size and modification time as the old one.
+    fn reopen_replaced_files(&mut self, settings: &Settings) -> UResult<()> {
+        let paths: Vec<PathBuf> = self
+            .files
+            .keys()
+            .filter(|path| !path.is_stdin())
+            .cloned()
+            .collect();
+        for path in paths {
+            let pd = self.files.get(&path);
+            if pd.reader.is_none() {
+                continue;
+            }
+            let new_md = match path.metadata() {
+                Ok(new_md) if new_md.is_tailable() => new_md,
+                _ => continue,
+            };
+            if pd
+                .metadata
+                .as_ref()
+                .map_or(true, |old_md| old_md.file_id_eq(&new_md))
+            {
+                continue;
+            }
+            let display_name = pd.display_name.clone();
+            self.files.tail_file(&path, settings.verbose)?;
+            show_error!(
+                "{} has been replaced;  following new file",
+                display_name.quote()
+            );
+            self.files.update_reader(&path)?;
+            self.files.update_metadata(&path, Some(new_md));
+            self.files.tail_file(&path, settings.verbose)?;
+        }
+        Ok(())
+    }
+
     pub fn follow_descriptor(&self) -> bool {
         self.follow == Some(FollowMode::Descriptor)
     }
@@ -339,6 +376,8 @@ impl Observer {
                                 } else if event.kind == EventKind::Modify(ModifyKind::Name(RenameMode::To))
                                 || (self.use_polling
                                 && !old_md.file_id_eq(&new_md)) {
+                                    // Print what was appended to the old file before it was replaced.
+                                    self.files.tail_file(event_path, settings.verbose)?;
                                     show_error!( "{} has been replaced;  following new file", display_name.quote());
                                     self.files.update_reader(event_path)?;
                                 } else if old_md.got_truncated(&new_md)? {
@@ -386,6 +425,9 @@ impl Observer {
                 // | EventKind::Modify(ModifyKind::Name(RenameMode::Any))
                 | EventKind::Modify(ModifyKind::Name(RenameMode::From)) => {
                     if self.follow_name() {
+                        // The old file may still be open, and have been appended to before it
+                        // was moved or removed, e.g. when a log file is rotated.
+                        self.files.tail_file(event_path, settings.verbose)?;
                         if settings.retry {
                             if let Some(old_md) = self.files.get_mut_metadata(event_path) {
                                 if old_md.is_tailable() && self.files.get(event_path).reader.is_some() {
@@ -476,7 +518,11 @@ pub fn follow(mut observer: Observer, settings: &Settings) -> UResult<()> {
         return Err(USimpleError::new(1, text::NO_FILES_REMAINING.to_string()));
     }
 
-    let mut process = platform::ProcessChecker::new(observer.pid);
+    let mut processes: Vec<_> = observer
+        .pids
+        .iter()
+        .map(|&pid| platform::ProcessChecker::new(pid))
+        .collect();
 
     let mut _event_counter = 0;
     let mut _timeout_counter = 0;
@@ -487,8 +533,11 @@ pub fn follow(mut observer: Observer, settings: &Settings) -> UResult<()> {
 
         // If `--pid=p`, tail checks whether process p
         // is alive at least every `--sleep-interval=N` seconds
-        if settings.follow.is_some() && observer.pid != 0 && process.is_dead() {
-            // p is dead, tail will also terminate
+        if settings.follow.is_some()
+            && !processes.is_empty()
+            && processes.iter_mut().all(platform::ProcessChecker::is_dead)
+        {
+            // every p is dead, tail will also terminate
             break;
         }
 
@@ -586,17 +635,10 @@ pub fn follow(mut observer: Observer, settings: &Settings) -> UResult<()> {
         }
 
         if _timeout_counter == settings.max_unchanged_stats {
-            /*
-            TODO: [2021-10; jhscheer] implement timeout_counter for each file.
-            ‘--max-unchanged-stats=n’
-            When tailing a file by name, if there have been n (default n=5) consecutive iterations
-            for which the file has not changed, then open/fstat the file to determine if that file
-            name is still associated with the same device/inode-number pair as before. When
-            following a log file that is rotated, this is approximately the number of seconds
-            between when tail prints the last pre-rotation lines and when it prints the lines that
-            have accumulated in the new log file. This option is meaningful only when polling
-            (i.e., without inotify) and when following by name.
-            */
+            _timeout_counter = 0;
+            if observer.use_polling && observer.follow_name() {
+                observer.reopen_replaced_files(settings)?;
+            }
         }
     }
     Ok(())
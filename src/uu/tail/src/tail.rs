@@ -151,7 +151,7 @@ fn tail_file(
                     && file.is_seekable(if input.is_stdin() { offset } else { 0 })
                     && metadata.as_ref().unwrap().get_block_size() > 0
                 {
-                    bounded_tail(&mut file, settings);
+                    bounded_tail(&mut file, settings)?;
                     reader = BufReader::new(file);
                 } else {
                     reader = BufReader::new(file);
@@ -297,22 +297,24 @@ where
 {
     let mut reader = BufReader::new(reader);
 
-    let mut buf = vec![];
+    // The lines are skipped buffer by buffer, so a very long line isn't held in memory.
     let mut total = 0;
-    for _ in 0..num_delimiters {
-        match reader.read_until(delimiter, &mut buf) {
-            Ok(0) => {
-                return Ok(total);
-            }
-            Ok(n) => {
-                total += n;
-                buf.clear();
-                continue;
-            }
-            Err(e) => {
-                return Err(e);
+    let mut found = 0;
+    while found < num_delimiters {
+        let buf = reader.fill_buf()?;
+        if buf.is_empty() {
+            break;
+        }
+        let mut consumed = buf.len();
+        for i in memchr::memchr_iter(delimiter, buf) {
+            found += 1;
+            if found == num_delimiters {
+                consumed = i + 1;
+                break;
             }
         }
+        total += consumed;
+        reader.consume(consumed);
     }
     Ok(total)
 }
@@ -363,7 +365,7 @@ fn backwards_thru_file(file: &mut File, num_delimiters: u64, delimiter: u8) {
 /// end of the file, and then read the file "backwards" in blocks of size
 /// `BLOCK_SIZE` until we find the location of the first line/byte. This ends up
 /// being a nice performance win for very large files.
-fn bounded_tail(file: &mut File, settings: &Settings) {
+fn bounded_tail(file: &mut File, settings: &Settings) -> io::Result<()> {
     debug_assert!(!settings.presume_input_pipe);
 
     // Find the position in the file to start printing from.
@@ -372,24 +374,23 @@ fn bounded_tail(file: &mut File, settings: &Settings) {
             backwards_thru_file(file, *count, *delimiter);
         }
         FilterMode::Lines(Signum::Positive(count), delimiter) if count > &1 => {
-            let i = forwards_thru_file(file, *count - 1, *delimiter).unwrap();
-            file.seek(SeekFrom::Start(i as u64)).unwrap();
+            let i = forwards_thru_file(file, *count - 1, *delimiter)?;
+            file.seek(SeekFrom::Start(i as u64))?;
         }
         FilterMode::Lines(Signum::MinusZero, _) => {
-            return;
+            return Ok(());
         }
         FilterMode::Bytes(Signum::Negative(count)) => {
-            let len = file.seek(SeekFrom::End(0)).unwrap();
-            file.seek(SeekFrom::End(-((*count).min(len) as i64)))
-                .unwrap();
+            let len = file.seek(SeekFrom::End(0))?;
+            file.seek(SeekFrom::End(-((*count).min(len) as i64)))?;
         }
         FilterMode::Bytes(Signum::Positive(count)) if count > &1 => {
             // GNU `tail` seems to index bytes and lines starting at 1, not
             // at 0. It seems to treat `+0` and `+1` as the same thing.
-            file.seek(SeekFrom::Start(*count - 1)).unwrap();
+            file.seek(SeekFrom::Start(*count - 1))?;
         }
         FilterMode::Bytes(Signum::MinusZero) => {
-            return;
+            return Ok(());
         }
         _ => {}
     }
@@ -397,7 +398,8 @@ fn bounded_tail(file: &mut File, settings: &Settings) {
     // Print the target section of the file.
     let stdout = stdout();
     let mut stdout = stdout.lock();
-    std::io::copy(file, &mut stdout).unwrap();
+    io::copy(file, &mut stdout)?;
+    Ok(())
 }
 
 fn unbounded_tail<T: Read>(reader: &mut BufReader<T>, settings: &Settings) -> UResult<()> {
@@ -485,6 +487,14 @@ mod tests {
         assert_eq!(i, 4);
     }
 
+    #[test]
+    fn test_forwards_thru_file_long_lines() {
+        let long_line = format!("{}\n", "a".repeat(20_000));
+        let mut reader = Cursor::new(format!("{long_line}{long_line}b\n"));
+        let i = forwards_thru_file(&mut reader, 2, b'\n').unwrap();
+        assert_eq!(i, 2 * long_line.len());
+    }
+
     #[test]
     fn test_forwards_thru_file_past_end() {
         let mut reader = Cursor::new("x\n");
@@ -0,0 +1,176 @@
+//  * This file is part of the uutils coreutils package.
+//  *
+//  * For the full copyright and license information, please view the LICENSE
+//  * file that was distributed with this source code.
+
+//! Copy standard input to the outputs without letting a slow one hold up the others.
+//!
+//! Every output has its own queue of pending input. The queues are drained as
+//! poll(2) reports the outputs writable, and standard input is only read while
+//! each queue has room, so a stalled output delays the others only once its
+//! queue is full.
+
+// spell-checker:ignore POLLIN POLLOUT revents
+
+use retain_mut::RetainMut;
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::{Error, ErrorKind, Read, Result, Write};
+use std::mem::ManuallyDrop;
+use std::os::unix::io::{FromRawFd, RawFd};
+use uucore::error::strip_errno;
+use uucore::libc;
+use uucore::show_error;
+
+use crate::{process_error, MultiWriter, NamedWriter};
+
+/// How much input is read at once
+const BUFFER_SIZE: usize = 8 * 1024;
+/// How much input may wait for a single output before tee stops reading
+const QUEUE_LIMIT: usize = 64 * 1024;
+
+struct Output {
+    writer: NamedWriter,
+    queue: VecDeque<u8>,
+    regular: bool,
+}
+
+impl Output {
+    /// Write as much of the queue as can be written without blocking.
+    ///
+    /// A writable pipe only takes `PIPE_BUF` bytes for sure, while regular
+    /// files never block.
+    fn write_pending(&mut self) -> Result<()> {
+        let (front, _) = self.queue.as_slices();
+        let len = if self.regular {
+            front.len()
+        } else {
+            front.len().min(libc::PIPE_BUF)
+        };
+        match borrow_fd(self.writer.fd).write(&front[..len]) {
+            Ok(n) => {
+                self.queue.drain(..n);
+                Ok(())
+            }
+            Err(e) if matches!(e.kind(), ErrorKind::Interrupted | ErrorKind::WouldBlock) => Ok(()),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+/// Use `fd` as a file, without closing it once done.
+fn borrow_fd(fd: RawFd) -> ManuallyDrop<File> {
+    // SAFETY: the descriptor stays open for as long as its writer or stdin,
+    // and ManuallyDrop prevents it from being closed here.
+    ManuallyDrop::new(unsafe { File::from_raw_fd(fd) })
+}
+
+/// Poll `fd` for `events`, or nothing if `fd` is `None`.
+fn poll_entry(fd: Option<RawFd>, events: libc::c_short) -> libc::pollfd {
+    libc::pollfd {
+        // Negative descriptors are ignored by poll.
+        fd: fd.unwrap_or(-1),
+        events,
+        revents: 0,
+    }
+}
+
+impl MultiWriter {
+    /// Copy standard input to all writers until it ends or no writer is left.
+    ///
+    /// Write errors are handled according to the output error mode like in
+    /// [`Write::write`], and the first one that should make tee exit is
+    /// returned.
+    pub(crate) fn copy_nonblocking(&mut self) -> Result<()> {
+        let mut outputs: Vec<Output> = self
+            .writers
+            .drain(..)
+            .map(|writer| Output {
+                regular: borrow_fd(writer.fd)
+                    .metadata()
+                    .map_or(false, |m| m.is_file()),
+                writer,
+                queue: VecDeque::new(),
+            })
+            .collect();
+        let mut buffer = vec![0; BUFFER_SIZE];
+        let mut eof = false;
+
+        let result = loop {
+            if outputs.is_empty() {
+                break Ok(());
+            }
+            let reading = !eof && outputs.iter().all(|o| o.queue.len() < QUEUE_LIMIT);
+            if !reading && outputs.iter().all(|o| o.queue.is_empty()) {
+                break Ok(());
+            }
+
+            // Entry 0 is stdin, and entry i + 1 is the i-th output.
+            let mut fds = Vec::with_capacity(outputs.len() + 1);
+            fds.push(poll_entry(
+                Some(libc::STDIN_FILENO).filter(|_| reading),
+                libc::POLLIN,
+            ));
+            for output in &outputs {
+                let pending = !output.queue.is_empty();
+                fds.push(poll_entry(
+                    Some(output.writer.fd).filter(|_| pending),
+                    libc::POLLOUT,
+                ));
+            }
+            // SAFETY: `fds` is a valid array of `fds.len()` entries.
+            let ret = unsafe { libc::poll(fds.as_mut_ptr(), fds.len() as libc::nfds_t, -1) };
+            if ret < 0 {
+                let e = Error::last_os_error();
+                if e.kind() == ErrorKind::Interrupted {
+                    continue;
+                }
+                break Err(e);
+            }
+
+            if fds[0].revents != 0 {
+                match borrow_fd(libc::STDIN_FILENO).read(&mut buffer) {
+                    Ok(0) => eof = true,
+                    Ok(n) => {
+                        for output in &mut outputs {
+                            output.queue.extend(&buffer[..n]);
+                        }
+                    }
+                    Err(e) if e.kind() == ErrorKind::Interrupted => (),
+                    Err(e) => {
+                        show_error!("read error: {}", strip_errno(&e));
+                        break Err(e);
+                    }
+                }
+            }
+
+            let mut aborted = None;
+            let mode = self.output_error_mode.clone();
+            let mut errors = 0;
+            let mut i = 0;
+            RetainMut::retain_mut(&mut outputs, |output| {
+                i += 1;
+                if fds[i].revents == 0 {
+                    return true;
+                }
+                match output.write_pending() {
+                    Ok(()) => true,
+                    Err(f) => {
+                        if let Err(e) = process_error(mode.as_ref(), f, &output.writer, &mut errors)
+                        {
+                            aborted.get_or_insert(e);
+                        }
+                        false
+                    }
+                }
+            });
+            self.ignored_errors += errors;
+            if let Some(e) = aborted {
+                break Err(e);
+            }
+        };
+
+        self.writers = outputs.into_iter().map(|o| o.writer).collect();
+        result
+    }
+}
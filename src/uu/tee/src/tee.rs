@@ -8,17 +8,22 @@
 use clap::{builder::PossibleValue, crate_version, Arg, ArgAction, Command};
 use retain_mut::RetainMut;
 use std::fs::OpenOptions;
-use std::io::{copy, sink, stdin, stdout, Error, ErrorKind, Read, Result, Write};
+use std::io::{copy, stdin, stdout, Error, ErrorKind, Read, Result, Write};
 use std::path::PathBuf;
 use uucore::display::Quotable;
-use uucore::error::UResult;
+use uucore::error::{strip_errno, UResult};
 use uucore::{format_usage, show_error};
 
 // spell-checker:ignore nopipe
 
+#[cfg(unix)]
+use std::os::unix::io::{AsRawFd, RawFd};
 #[cfg(unix)]
 use uucore::libc;
 
+#[cfg(unix)]
+mod multiplex;
+
 static ABOUT: &str = "Copy standard input to each FILE, and also to standard output.";
 const USAGE: &str = "{} [OPTION]... [FILE]...";
 
@@ -28,6 +33,7 @@ mod options {
     pub const FILE: &str = "file";
     pub const IGNORE_PIPE_ERRORS: &str = "ignore-pipe-errors";
     pub const OUTPUT_ERROR: &str = "output-error";
+    pub const LOCKSTEP: &str = "lockstep";
 }
 
 #[allow(dead_code)]
@@ -36,6 +42,7 @@ struct Options {
     ignore_interrupts: bool,
     files: Vec<String>,
     output_error: Option<OutputErrorMode>,
+    lockstep: bool,
 }
 
 #[derive(Clone, Debug)]
@@ -76,6 +83,7 @@ pub fn uumain(args: impl uucore::Args) -> UResult<()> {
                 None
             }
         },
+        lockstep: matches.get_flag(options::LOCKSTEP),
     };
 
     match tee(&options) {
@@ -133,6 +141,15 @@ pub fn uu_app() -> Command {
                 .help("set write error behavior")
                 .conflicts_with(options::IGNORE_PIPE_ERRORS),
         )
+        .arg(
+            Arg::new(options::LOCKSTEP)
+                .long(options::LOCKSTEP)
+                .help(
+                    "write each block of input to every output before reading the next one, \
+                    so that a slow output holds up the others (always the case on non-Unix platforms)",
+                )
+                .action(ArgAction::SetTrue),
+        )
 }
 
 #[cfg(unix)]
@@ -173,32 +190,31 @@ fn tee(options: &Options) -> Result<()> {
         enable_pipe_errors()?;
     }
 
-    let mut writers: Vec<NamedWriter> = options
-        .files
-        .clone()
-        .into_iter()
-        .map(|file| {
-            Ok(NamedWriter {
-                name: file.clone(),
-                inner: open(file, options.append, options.output_error.as_ref())?,
-            })
-        })
-        .collect::<Result<Vec<NamedWriter>>>()?;
-
-    writers.insert(
-        0,
-        NamedWriter {
-            name: "'standard output'".to_owned(),
-            inner: Box::new(stdout()),
-        },
-    );
+    let mut writers = vec![NamedWriter {
+        name: "standard output".to_owned(),
+        inner: Box::new(stdout()),
+        #[cfg(unix)]
+        fd: libc::STDOUT_FILENO,
+    }];
+    // A file that cannot be opened fails tee, but only the exit modes stop it
+    let mut open_errors = 0;
+    for file in &options.files {
+        match open(file, options.append) {
+            Ok(writer) => writers.push(writer),
+            Err(f) => {
+                show_error!("{}: {}", file.maybe_quote(), strip_errno(&f));
+                match options.output_error {
+                    Some(OutputErrorMode::Exit | OutputErrorMode::ExitNoPipe) => return Err(f),
+                    _ => open_errors += 1,
+                }
+            }
+        }
+    }
 
     let mut output = MultiWriter::new(writers, options.output_error.clone());
-    let input = &mut NamedReader {
-        inner: Box::new(stdin()) as Box<dyn Read>,
-    };
+    output.ignored_errors = open_errors;
 
-    let res = match copy(input, &mut output) {
+    let res = match copy_input(&mut output, options.lockstep) {
         // ErrorKind::Other is raised by MultiWriter when all writers
         // have exited, so that copy will abort. It's equivalent to
         // success of this part (if there was an error that should
@@ -215,33 +231,51 @@ fn tee(options: &Options) -> Result<()> {
     }
 }
 
-fn open(
-    name: String,
-    append: bool,
-    output_error: Option<&OutputErrorMode>,
-) -> Result<Box<dyn Write>> {
-    let path = PathBuf::from(name.clone());
-    let inner: Box<dyn Write> = {
-        let mut options = OpenOptions::new();
-        let mode = if append {
-            options.append(true)
-        } else {
-            options.truncate(true)
-        };
-        match mode.write(true).create(true).open(path.as_path()) {
-            Ok(file) => Box::new(file),
-            Err(f) => {
-                show_error!("{}: {}", name.maybe_quote(), f);
-                match output_error {
-                    Some(OutputErrorMode::Exit | OutputErrorMode::ExitNoPipe) => return Err(f),
-                    _ => Box::new(sink()),
-                }
-            }
-        }
+/// Copy standard input to `output`, either block by block to all writers at
+/// once, or letting each writer go at its own pace.
+#[cfg(unix)]
+fn copy_input(output: &mut MultiWriter, lockstep: bool) -> Result<u64> {
+    if lockstep {
+        copy_lockstep(output)
+    } else {
+        output.copy_nonblocking().map(|_| 0)
+    }
+}
+
+#[cfg(not(unix))]
+fn copy_input(output: &mut MultiWriter, _lockstep: bool) -> Result<u64> {
+    copy_lockstep(output)
+}
+
+fn copy_lockstep(output: &mut MultiWriter) -> Result<u64> {
+    let input = &mut NamedReader {
+        inner: Box::new(stdin()) as Box<dyn Read>,
+    };
+    copy(input, output)
+}
+
+fn open(name: &str, append: bool) -> Result<NamedWriter> {
+    let path = PathBuf::from(name);
+    let mut options = OpenOptions::new();
+    let mode = if append {
+        options.append(true)
+    } else {
+        options.truncate(true)
     };
-    Ok(Box::new(NamedWriter { inner, name }) as Box<dyn Write>)
+    let file = mode.write(true).create(true).open(path.as_path())?;
+    Ok(NamedWriter {
+        #[cfg(unix)]
+        fd: file.as_raw_fd(),
+        inner: Box::new(file),
+        name: name.to_owned(),
+    })
 }
 
+/// Copies everything written to it to each of its writers.
+///
+/// Each writer is tracked on its own: one that fails is reported according
+/// to the output error mode and dropped, while the others keep receiving
+/// the input.
 struct MultiWriter {
     writers: Vec<NamedWriter>,
     output_error_mode: Option<OutputErrorMode>,
@@ -270,24 +304,24 @@ fn process_error(
 ) -> Result<()> {
     match mode {
         Some(OutputErrorMode::Warn) => {
-            show_error!("{}: {}", writer.name.maybe_quote(), f);
+            show_error!("{}: {}", writer.name.maybe_quote(), strip_errno(&f));
             *ignored_errors += 1;
             Ok(())
         }
         Some(OutputErrorMode::WarnNoPipe) | None => {
             if f.kind() != ErrorKind::BrokenPipe {
-                show_error!("{}: {}", writer.name.maybe_quote(), f);
+                show_error!("{}: {}", writer.name.maybe_quote(), strip_errno(&f));
                 *ignored_errors += 1;
             }
             Ok(())
         }
         Some(OutputErrorMode::Exit) => {
-            show_error!("{}: {}", writer.name.maybe_quote(), f);
+            show_error!("{}: {}", writer.name.maybe_quote(), strip_errno(&f));
             Err(f)
         }
         Some(OutputErrorMode::ExitNoPipe) => {
             if f.kind() != ErrorKind::BrokenPipe {
-                show_error!("{}: {}", writer.name.maybe_quote(), f);
+                show_error!("{}: {}", writer.name.maybe_quote(), strip_errno(&f));
                 Err(f)
             } else {
                 Ok(())
@@ -302,7 +336,9 @@ impl Write for MultiWriter {
         let mode = self.output_error_mode.clone();
         let mut errors = 0;
         RetainMut::retain_mut(&mut self.writers, |writer| {
-            let result = writer.write_all(buf);
+            // Flush right away so that nothing is held back in the buffer
+            // of stdout, e.g. for a reader on the other end of a pipe.
+            let result = writer.write_all(buf).and_then(|_| writer.flush());
             match result {
                 Err(f) => {
                     if let Err(e) = process_error(mode.as_ref(), f, writer, &mut errors) {
@@ -358,6 +394,9 @@ impl Write for MultiWriter {
 struct NamedWriter {
     inner: Box<dyn Write>,
     pub name: String,
+    /// The descriptor of `inner`, for polling
+    #[cfg(unix)]
+    fd: RawFd,
 }
 
 impl Write for NamedWriter {
@@ -378,7 +417,7 @@ impl Read for NamedReader {
     fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
         match self.inner.read(buf) {
             Err(f) => {
-                show_error!("stdin: {}", f);
+                show_error!("read error: {}", strip_errno(&f));
                 Err(f)
             }
             okay => okay,
@@ -5,408 +5,652 @@
 // For the full copyright and license information, please view the LICENSE
 // file that was distributed with this source code.
 
-// spell-checker:ignore (grammar) BOOLOP STRLEN FILETEST FILEOP INTOP STRINGOP ; (vars) LParen StrlenOp
+// spell-checker:ignore (grammar) BOOLOP STRLEN FILETEST FILEOP INTOP STRINGOP ; (vars) nargs
 
-use std::ffi::OsString;
-use std::iter::Peekable;
+use std::cmp::Ordering;
+use std::ffi::{OsStr, OsString};
 
 use uucore::display::Quotable;
-use uucore::show_error;
 
-/// Represents one of the binary comparison operators for strings, integers, or files
-#[derive(Debug, PartialEq, Eq)]
-pub enum Operator {
-    String(OsString),
-    Int(OsString),
-    File(OsString),
+/// A comparison of two strings
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StringOp {
+    Equal,
+    NotEqual,
 }
 
-/// Represents one of the unary test operators for strings or files
-#[derive(Debug, PartialEq, Eq)]
-pub enum UnaryOperator {
-    StrlenOp(OsString),
-    FiletestOp(OsString),
+/// A comparison of two integers
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum IntOp {
+    Equal,
+    NotEqual,
+    Greater,
+    GreaterEqual,
+    Less,
+    LessEqual,
 }
 
-/// Represents a parsed token from a test expression
-#[derive(Debug, PartialEq, Eq)]
-pub enum Symbol {
-    LParen,
-    Bang,
-    BoolOp(OsString),
-    Literal(OsString),
-    Op(Operator),
-    UnaryOp(UnaryOperator),
-    None,
+/// A comparison of two files
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FileOp {
+    /// `-ef`, the same device and inode
+    Same,
+    /// `-nt`, modified later
+    NewerThan,
+    /// `-ot`, modified earlier
+    OlderThan,
+}
+
+/// A unary test of a file
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PathCondition {
+    BlockSpecial,
+    CharacterSpecial,
+    Directory,
+    Exists,
+    ExistsModifiedLastRead,
+    Regular,
+    GroupIdFlag,
+    GroupOwns,
+    SymLink,
+    Sticky,
+    UserOwns,
+    Fifo,
+    Readable,
+    Socket,
+    NonEmpty,
+    UserIdFlag,
+    Writable,
+    Executable,
 }
 
-impl Symbol {
-    /// Create a new Symbol from an OsString.
-    ///
-    /// Returns Symbol::None in place of None
-    fn new(token: Option<OsString>) -> Self {
-        match token {
-            Some(s) => match s.to_str() {
-                Some(t) => match t {
-                    "(" => Self::LParen,
-                    "!" => Self::Bang,
-                    "-a" | "-o" => Self::BoolOp(s),
-                    "=" | "==" | "!=" => Self::Op(Operator::String(s)),
-                    "-eq" | "-ge" | "-gt" | "-le" | "-lt" | "-ne" => Self::Op(Operator::Int(s)),
-                    "-ef" | "-nt" | "-ot" => Self::Op(Operator::File(s)),
-                    "-n" | "-z" => Self::UnaryOp(UnaryOperator::StrlenOp(s)),
-                    "-b" | "-c" | "-d" | "-e" | "-f" | "-g" | "-G" | "-h" | "-k" | "-L" | "-N"
-                    | "-O" | "-p" | "-r" | "-s" | "-S" | "-t" | "-u" | "-w" | "-x" => {
-                        Self::UnaryOp(UnaryOperator::FiletestOp(s))
-                    }
-                    _ => Self::Literal(s),
-                },
-                None => Self::Literal(s),
-            },
-            None => Self::None,
+/// An integer operand of any size, compared by value
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Integer {
+    negative: bool,
+    /// The digits without leading zeros, empty for zero
+    digits: String,
+}
+
+impl Integer {
+    /// Parse an integer like GNU test does: decimal digits with an optional
+    /// sign, and surrounded by any blanks.
+    fn parse(s: &OsStr) -> Result<Self, String> {
+        let invalid = || format!("invalid integer {}", s.quote());
+        let trimmed = s
+            .to_str()
+            .ok_or_else(invalid)?
+            .trim_matches(|c| c == ' ' || c == '\t');
+        let (negative, digits) = match trimmed.strip_prefix('-') {
+            Some(digits) => (true, digits),
+            None => (false, trimmed.strip_prefix('+').unwrap_or(trimmed)),
+        };
+        if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(invalid());
         }
+        let digits = digits.trim_start_matches('0');
+        Ok(Self {
+            negative: negative && !digits.is_empty(),
+            digits: digits.to_string(),
+        })
     }
 
-    /// Convert this Symbol into a Symbol::Literal, useful for cases where
-    /// test treats an operator as a string operand (test has no reserved
-    /// words).
-    ///
-    /// # Panics
-    ///
-    /// Panics if `self` is Symbol::None
-    fn into_literal(self) -> Self {
-        Self::Literal(match self {
-            Self::LParen => OsString::from("("),
-            Self::Bang => OsString::from("!"),
-            Self::BoolOp(s)
-            | Self::Literal(s)
-            | Self::Op(Operator::String(s))
-            | Self::Op(Operator::Int(s))
-            | Self::Op(Operator::File(s))
-            | Self::UnaryOp(UnaryOperator::StrlenOp(s))
-            | Self::UnaryOp(UnaryOperator::FiletestOp(s)) => s,
-            Self::None => panic!(),
-        })
+    /// The length of `s`, for `-l STRING`.
+    fn length_of(s: &OsStr) -> Self {
+        let digits = match s.len() {
+            0 => String::new(),
+            len => len.to_string(),
+        };
+        Self {
+            negative: false,
+            digits,
+        }
     }
+
+    /// The value as a file descriptor, if it is in range.
+    pub fn to_fd(&self) -> Option<i32> {
+        match (self.negative, self.digits.as_str()) {
+            (false, "") => Some(0),
+            (false, digits) => digits.parse().ok(),
+            (true, _) => None,
+        }
+    }
+}
+
+impl Ord for Integer {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let magnitude = self
+            .digits
+            .len()
+            .cmp(&other.digits.len())
+            .then_with(|| self.digits.cmp(&other.digits));
+        match (self.negative, other.negative) {
+            (false, false) => magnitude,
+            (true, true) => magnitude.reverse(),
+            (true, false) => Ordering::Less,
+            (false, true) => Ordering::Greater,
+        }
+    }
+}
+
+impl PartialOrd for Integer {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// A parsed test expression
+#[derive(Debug, PartialEq, Eq)]
+pub enum Expr {
+    /// `STRING` or `-n STRING`, true if the string is not empty
+    String(OsString),
+    Not(Box<Expr>),
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+    Strings(StringOp, OsString, OsString),
+    Integers(IntOp, Integer, Integer),
+    Files(FileOp, OsString, OsString),
+    Path(PathCondition, OsString),
+    /// `-t FD`, with the descriptor if it is in range
+    Terminal(Option<i32>),
+}
+
+/// Whether `s` is one of the binary operators that compare their operands.
+///
+/// `-a` and `-o` are not, even though they are binary too.
+fn is_binary_op(s: Option<&str>) -> bool {
+    matches!(
+        s,
+        Some(
+            "=" | "=="
+                | "!="
+                | "-eq"
+                | "-ne"
+                | "-gt"
+                | "-ge"
+                | "-lt"
+                | "-le"
+                | "-ef"
+                | "-nt"
+                | "-ot"
+        )
+    )
 }
 
-/// Recursive descent parser for test, which converts a list of OsStrings
-/// (typically command line arguments) into a stack of Symbols in postfix
-/// order.
+/// Parser for test, which converts a list of OsStrings (typically command
+/// line arguments) into an [`Expr`].
 ///
-/// Grammar:
+/// Expressions of up to four arguments are disambiguated by their number of
+/// arguments as POSIX specifies, e.g. `test ! =` is the negation of the
+/// string `=` while `test ! = x` compares `!` and `x`. Longer expressions,
+/// and those where POSIX leaves the meaning unspecified, are parsed with the
+/// grammar
 ///
-///   EXPR → TERM | EXPR BOOLOP EXPR
+///   EXPR → AND | AND -o EXPR
+///   AND → TERM | TERM -a AND
+///   TERM → ! TERM
 ///   TERM → ( EXPR )
-///   TERM → ! EXPR
+///   TERM → [-l] str OP [-l] str
 ///   TERM → UOP str
-///   UOP → STRLEN | FILETEST
-///   TERM → str OP str
-///   TERM → str | 𝜖
+///   TERM → str
 ///   OP → STRINGOP | INTOP | FILEOP
 ///   STRINGOP → = | == | !=
 ///   INTOP → -eq | -ge | -gt | -le | -lt | -ne
 ///   FILEOP → -ef | -nt | -ot
+///   UOP → STRLEN | FILETEST
 ///   STRLEN → -n | -z
 ///   FILETEST → -b | -c | -d | -e | -f | -g | -G | -h | -k | -L | -N | -O | -p |
 ///               -r | -s | -S | -t | -u | -w | -x
-///   BOOLOP → -a | -o
 ///
-#[derive(Debug)]
+/// like GNU test does. The same rules apply inside parentheses.
 struct Parser {
-    tokens: Peekable<std::vec::IntoIter<OsString>>,
-    pub stack: Vec<Symbol>,
+    args: Vec<OsString>,
+    pos: usize,
 }
 
 impl Parser {
-    /// Construct a new Parser from a `Vec<OsString>` of tokens.
-    fn new(tokens: Vec<OsString>) -> Self {
-        Self {
-            tokens: tokens.into_iter().peekable(),
-            stack: vec![],
+    /// The argument at `index` if it is valid UTF-8, which all operators
+    /// are.
+    fn get(&self, index: usize) -> Option<&str> {
+        self.args.get(index).and_then(|arg| arg.to_str())
+    }
+
+    fn is(&self, index: usize, s: &str) -> bool {
+        self.get(index) == Some(s)
+    }
+
+    /// Take the next argument.
+    fn next(&mut self) -> OsString {
+        self.pos += 1;
+        self.args[self.pos - 1].clone()
+    }
+
+    /// Skip the next argument, which must be followed by another one.
+    fn advance(&mut self) -> Result<(), String> {
+        self.pos += 1;
+        if self.pos >= self.args.len() {
+            return Err(self.beyond());
         }
+        Ok(())
     }
 
-    /// Fetch the next token from the input stream as a Symbol.
-    fn next_token(&mut self) -> Symbol {
-        Symbol::new(self.tokens.next())
+    /// The error for an expression that needs more arguments.
+    fn beyond(&self) -> String {
+        format!(
+            "missing argument after {}",
+            self.args[self.args.len() - 1].quote()
+        )
     }
 
-    /// Consume the next token & verify that it matches the provided value.
-    ///
-    /// # Panics
-    ///
-    /// Panics if the next token does not match the provided value.
-    ///
-    /// TODO: remove panics and convert Parser to return error messages.
-    fn expect(&mut self, value: &str) {
-        match self.next_token() {
-            Symbol::Literal(s) if s == value => (),
-            _ => panic!("expected ‘{value}’"),
+    /// Parse the `nargs` arguments from the current one like POSIX
+    /// specifies.
+    fn posix(&mut self, nargs: usize) -> Result<Expr, String> {
+        match nargs {
+            1 => Ok(self.one()),
+            2 => self.two(),
+            3 => self.three(),
+            4 if self.is(self.pos, "!") => {
+                self.pos += 1;
+                Ok(Expr::Not(Box::new(self.three()?)))
+            }
+            4 if self.is(self.pos, "(") && self.is(self.pos + 3, ")") => {
+                self.pos += 1;
+                let expr = self.two()?;
+                self.pos += 1;
+                Ok(expr)
+            }
+            _ => self.expr(),
         }
     }
 
-    /// Peek at the next token from the input stream, returning it as a Symbol.
-    /// The stream is unchanged and will return the same Symbol on subsequent
-    /// calls to `next()` or `peek()`.
-    fn peek(&mut self) -> Symbol {
-        Symbol::new(self.tokens.peek().map(|s| s.to_os_string()))
+    /// `STRING`
+    fn one(&mut self) -> Expr {
+        Expr::String(self.next())
     }
 
-    /// Test if the next token in the stream is a BOOLOP (-a or -o), without
-    /// removing the token from the stream.
-    fn peek_is_boolop(&mut self) -> bool {
-        matches!(self.peek(), Symbol::BoolOp(_))
+    /// `! STRING` or `UOP STRING`
+    fn two(&mut self) -> Result<Expr, String> {
+        if self.is(self.pos, "!") {
+            self.pos += 1;
+            Ok(Expr::Not(Box::new(self.one())))
+        } else if self
+            .get(self.pos)
+            .map_or(false, |s| s.len() == 2 && s.starts_with('-'))
+        {
+            self.unary()
+        } else {
+            Err(self.beyond())
+        }
     }
 
-    /// Parse an expression.
-    ///
-    ///   EXPR → TERM | EXPR BOOLOP EXPR
-    fn expr(&mut self) {
-        if !self.peek_is_boolop() {
-            self.term();
+    /// `STRING OP STRING`, `STRING -a STRING`, `STRING -o STRING`,
+    /// `! STRING STRING` or `( STRING )`
+    fn three(&mut self) -> Result<Expr, String> {
+        if is_binary_op(self.get(self.pos + 1)) {
+            self.binary(false)
+        } else if self.is(self.pos + 1, "-a") || self.is(self.pos + 1, "-o") {
+            let left = Box::new(self.one());
+            let op = self.next();
+            let right = Box::new(self.one());
+            Ok(if op == "-a" {
+                Expr::And(left, right)
+            } else {
+                Expr::Or(left, right)
+            })
+        } else if self.is(self.pos, "!") {
+            self.pos += 1;
+            Ok(Expr::Not(Box::new(self.two()?)))
+        } else if self.is(self.pos, "(") && self.is(self.pos + 2, ")") {
+            self.pos += 1;
+            let expr = self.one();
+            self.pos += 1;
+            Ok(expr)
+        } else {
+            Err(format!(
+                "{}: binary operator expected",
+                self.args[self.pos + 1].quote()
+            ))
         }
-        self.maybe_boolop();
     }
 
-    /// Parse a term token and possible subsequent symbols: "(", "!", UOP,
-    /// literal, or None.
-    fn term(&mut self) {
-        let symbol = self.next_token();
-
-        match symbol {
-            Symbol::LParen => self.lparen(),
-            Symbol::Bang => self.bang(),
-            Symbol::UnaryOp(_) => self.uop(symbol),
-            Symbol::None => self.stack.push(symbol),
-            literal => self.literal(literal),
+    /// `EXPR → AND | AND -o EXPR`
+    fn expr(&mut self) -> Result<Expr, String> {
+        if self.pos >= self.args.len() {
+            return Err(self.beyond());
         }
+        let mut expr = self.and()?;
+        while self.is(self.pos, "-o") {
+            self.pos += 1;
+            expr = Expr::Or(Box::new(expr), Box::new(self.and()?));
+        }
+        Ok(expr)
     }
 
-    /// Parse a (possibly) parenthesized expression.
-    ///
-    /// test has no reserved keywords, so "(" will be interpreted as a literal
-    /// in certain cases:
-    ///
-    /// * when found at the end of the token stream
-    /// * when followed by a binary operator that is not _itself_ interpreted
-    ///   as a literal
-    ///
-    fn lparen(&mut self) {
-        // Look ahead up to 3 tokens to determine if the lparen is being used
-        // as a grouping operator or should be treated as a literal string
-        let peek3: Vec<Symbol> = self
-            .tokens
-            .clone()
-            .take(3)
-            .map(|token| Symbol::new(Some(token)))
-            .collect();
-
-        match peek3.as_slice() {
-            // case 1: lparen is a literal when followed by nothing
-            [] => self.literal(Symbol::LParen.into_literal()),
-
-            // case 2: error if end of stream is `( <any_token>`
-            [symbol] => {
-                show_error!("missing argument after ‘{:?}’", symbol);
-                std::process::exit(2);
-            }
+    /// `AND → TERM | TERM -a AND`
+    fn and(&mut self) -> Result<Expr, String> {
+        let mut expr = self.term()?;
+        while self.is(self.pos, "-a") {
+            self.pos += 1;
+            expr = Expr::And(Box::new(expr), Box::new(self.term()?));
+        }
+        Ok(expr)
+    }
 
-            // case 3: `( uop <any_token> )` → parenthesized unary operation;
-            //         this case ensures we don’t get confused by `( -f ) )`
-            //         or `( -f ( )`, for example
-            [Symbol::UnaryOp(_), _, Symbol::Literal(s)] if s == ")" => {
-                let symbol = self.next_token();
-                self.uop(symbol);
-                self.expect(")");
-            }
+    /// A term with any number of leading `!`.
+    fn term(&mut self) -> Result<Expr, String> {
+        let mut negated = false;
+        while self.is(self.pos, "!") {
+            self.advance()?;
+            negated = !negated;
+        }
+        if self.pos >= self.args.len() {
+            return Err(self.beyond());
+        }
 
-            // case 4: binary comparison of literal lparen, e.g. `( != )`
-            [Symbol::Op(_), Symbol::Literal(s)] | [Symbol::Op(_), Symbol::Literal(s), _]
-                if s == ")" =>
+        let remaining = self.args.len() - self.pos;
+        let expr = if self.is(self.pos, "(") {
+            self.advance()?;
+            // The arguments up to a closing parenthesis, if there are few
+            // enough to be disambiguated by their number. Unlike with GNU,
+            // a parenthesis right after a binary operator is its operand, as
+            // in `( ( != ) )`.
+            let mut nargs = 1;
+            while self.pos + nargs < self.args.len()
+                && (!self.is(self.pos + nargs, ")")
+                    || nargs >= 2 && is_binary_op(self.get(self.pos + nargs - 1)))
             {
-                self.literal(Symbol::LParen.into_literal());
-            }
-
-            // case 5: after handling the prior cases, any single token inside
-            //         parentheses is a literal, e.g. `( -f )`
-            [_, Symbol::Literal(s)] | [_, Symbol::Literal(s), _] if s == ")" => {
-                let symbol = self.next_token();
-                self.literal(symbol);
-                self.expect(")");
+                if nargs == 4 {
+                    nargs = self.args.len() - self.pos;
+                    break;
+                }
+                nargs += 1;
             }
-
-            // case 6: two binary ops in a row, treat the first op as a literal
-            [Symbol::Op(_), Symbol::Op(_), _] => {
-                let symbol = self.next_token();
-                self.literal(symbol);
-                self.expect(")");
+            let expr = self.posix(nargs)?;
+            match self.args.get(self.pos) {
+                None => return Err(format!("{} expected", ")".quote())),
+                Some(arg) if arg != ")" => {
+                    return Err(format!("{} expected, found {}", ")".quote(), arg.quote()));
+                }
+                Some(_) => self.pos += 1,
             }
+            expr
+        } else if remaining >= 4 && self.is(self.pos, "-l") && is_binary_op(self.get(self.pos + 2))
+        {
+            self.binary(true)?
+        } else if remaining >= 3 && is_binary_op(self.get(self.pos + 1)) {
+            self.binary(false)?
+        } else if self
+            .get(self.pos)
+            .map_or(false, |s| s.len() == 2 && s.starts_with('-'))
+        {
+            self.unary()?
+        } else {
+            self.one()
+        };
 
-            // case 7: if earlier cases didn’t match, `( op <any_token>…`
-            //         indicates binary comparison of literal lparen with
-            //         anything _except_ ")" (case 4)
-            [Symbol::Op(_), _] | [Symbol::Op(_), _, _] => {
-                self.literal(Symbol::LParen.into_literal());
-            }
+        Ok(if negated {
+            Expr::Not(Box::new(expr))
+        } else {
+            expr
+        })
+    }
 
-            // Otherwise, lparen indicates the start of a parenthesized
-            // expression
-            _ => {
-                self.expr();
-                self.expect(")");
-            }
+    /// `[-l] STRING OP [-l] STRING`, where `OP` is the argument after the
+    /// first string.
+    fn binary(&mut self, left_is_length: bool) -> Result<Expr, String> {
+        if left_is_length {
+            self.pos += 1;
         }
-    }
+        let op = self.get(self.pos + 1).unwrap_or_default().to_string();
+        let right_is_length = !matches!(op.as_str(), "=" | "==" | "!=")
+            && self.pos + 3 < self.args.len()
+            && self.is(self.pos + 2, "-l");
+
+        let left = self.next();
+        self.pos += 1;
+        if right_is_length {
+            self.pos += 1;
+        }
+        let right = self.next();
 
-    /// Parse a (possibly) negated expression.
-    ///
-    /// Example cases:
-    ///
-    /// * `! =`: negate the result of the implicit string length test of `=`
-    /// * `! = foo`: compare the literal strings `!` and `foo`
-    /// * `! = = str`: negate comparison of literal `=` and `str`
-    /// * `!`: bang followed by nothing is literal
-    /// * `! EXPR`: negate the result of the expression
-    ///
-    /// Combined Boolean & negation:
-    ///
-    /// * `! ( EXPR ) [BOOLOP EXPR]`: negate the parenthesized expression only
-    /// * `! UOP str BOOLOP EXPR`: negate the unary subexpression
-    /// * `! str BOOLOP str`: negate the entire Boolean expression
-    /// * `! str BOOLOP EXPR BOOLOP EXPR`: negate the value of the first `str` term
-    ///
-    fn bang(&mut self) {
-        match self.peek() {
-            Symbol::Op(_) | Symbol::BoolOp(_) => {
-                // we need to peek ahead one more token to disambiguate the first
-                // three cases listed above
-                let peek2 = Symbol::new(self.tokens.clone().nth(1));
-
-                match peek2 {
-                    // case 1: `! <OP as literal>`
-                    // case 3: `! = OP str`
-                    Symbol::Op(_) | Symbol::None => {
-                        // op is literal
-                        let op = self.next_token().into_literal();
-                        self.literal(op);
-                        self.stack.push(Symbol::Bang);
-                    }
-                    // case 2: `<! as literal> OP str [BOOLOP EXPR]`.
-                    _ => {
-                        // bang is literal; parsing continues with op
-                        self.literal(Symbol::Bang.into_literal());
-                        self.maybe_boolop();
-                    }
-                }
+        let op = match op.as_str() {
+            "=" | "==" => return Ok(Expr::Strings(StringOp::Equal, left, right)),
+            "!=" => return Ok(Expr::Strings(StringOp::NotEqual, left, right)),
+            "-ef" | "-nt" | "-ot" if left_is_length || right_is_length => {
+                return Err(format!("{op} does not accept -l"));
             }
-
-            // bang followed by nothing is literal
-            Symbol::None => self.stack.push(Symbol::Bang.into_literal()),
-
-            _ => {
-                // peek ahead up to 4 tokens to determine if we need to negate
-                // the entire expression or just the first term
-                let peek4: Vec<Symbol> = self
-                    .tokens
-                    .clone()
-                    .take(4)
-                    .map(|token| Symbol::new(Some(token)))
-                    .collect();
-
-                match peek4.as_slice() {
-                    // we peeked ahead 4 but there were only 3 tokens left
-                    [Symbol::Literal(_), Symbol::BoolOp(_), Symbol::Literal(_)] => {
-                        self.expr();
-                        self.stack.push(Symbol::Bang);
-                    }
-                    _ => {
-                        self.term();
-                        self.stack.push(Symbol::Bang);
-                    }
-                }
+            "-ef" => return Ok(Expr::Files(FileOp::Same, left, right)),
+            "-nt" => return Ok(Expr::Files(FileOp::NewerThan, left, right)),
+            "-ot" => return Ok(Expr::Files(FileOp::OlderThan, left, right)),
+            "-eq" => IntOp::Equal,
+            "-ne" => IntOp::NotEqual,
+            "-gt" => IntOp::Greater,
+            "-ge" => IntOp::GreaterEqual,
+            "-lt" => IntOp::Less,
+            "-le" => IntOp::LessEqual,
+            _ => unreachable!("{op} is not a binary operator"),
+        };
+        let integer = |s: &OsStr, is_length| {
+            if is_length {
+                Ok(Integer::length_of(s))
+            } else {
+                Integer::parse(s)
             }
-        }
+        };
+        let left = integer(&left, left_is_length)?;
+        let right = integer(&right, right_is_length)?;
+        Ok(Expr::Integers(op, left, right))
     }
 
-    /// Peek at the next token and parse it as a BOOLOP or string literal,
-    /// as appropriate.
-    fn maybe_boolop(&mut self) {
-        if self.peek_is_boolop() {
-            let symbol = self.next_token();
-
-            // BoolOp by itself interpreted as Literal
-            if let Symbol::None = self.peek() {
-                self.literal(symbol.into_literal());
-            } else {
-                self.boolop(symbol);
-                self.maybe_boolop();
+    /// `UOP STRING`
+    fn unary(&mut self) -> Result<Expr, String> {
+        let op = self.get(self.pos).unwrap_or_default().to_string();
+        let condition = match op.as_str() {
+            "-b" => PathCondition::BlockSpecial,
+            "-c" => PathCondition::CharacterSpecial,
+            "-d" => PathCondition::Directory,
+            "-e" => PathCondition::Exists,
+            "-f" => PathCondition::Regular,
+            "-g" => PathCondition::GroupIdFlag,
+            "-G" => PathCondition::GroupOwns,
+            "-h" | "-L" => PathCondition::SymLink,
+            "-k" => PathCondition::Sticky,
+            "-N" => PathCondition::ExistsModifiedLastRead,
+            "-O" => PathCondition::UserOwns,
+            "-p" => PathCondition::Fifo,
+            "-r" => PathCondition::Readable,
+            "-s" => PathCondition::NonEmpty,
+            "-S" => PathCondition::Socket,
+            "-u" => PathCondition::UserIdFlag,
+            "-w" => PathCondition::Writable,
+            "-x" => PathCondition::Executable,
+            "-n" | "-z" | "-t" => {
+                self.advance()?;
+                let operand = self.next();
+                return Ok(match op.as_str() {
+                    "-n" => Expr::String(operand),
+                    "-z" => Expr::Not(Box::new(Expr::String(operand))),
+                    _ => Expr::Terminal(Integer::parse(&operand)?.to_fd()),
+                });
             }
-        }
+            _ => return Err(format!("{}: unary operator expected", op.quote())),
+        };
+        self.advance()?;
+        Ok(Expr::Path(condition, self.next()))
     }
+}
 
-    /// Parse a Boolean expression.
-    ///
-    /// Logical and (-a) has higher precedence than or (-o), so in an
-    /// expression like `foo -o '' -a ''`, the and subexpression is evaluated
-    /// first.
-    fn boolop(&mut self, op: Symbol) {
-        if op == Symbol::BoolOp(OsString::from("-a")) {
-            self.term();
-        } else {
-            self.expr();
-        }
-        self.stack.push(op);
+/// Parse the arguments of test into the expression they represent.
+///
+/// No arguments at all are false, like an empty string.
+pub fn parse(args: Vec<OsString>) -> Result<Expr, String> {
+    let nargs = args.len();
+    if nargs == 0 {
+        return Ok(Expr::String(OsString::new()));
     }
-
-    /// Parse a (possible) unary argument test (string length or file
-    /// attribute check).
-    ///
-    /// If a UOP is followed by nothing it is interpreted as a literal string.
-    fn uop(&mut self, op: Symbol) {
-        match self.next_token() {
-            Symbol::None => self.stack.push(op.into_literal()),
-            symbol => {
-                self.stack.push(symbol.into_literal());
-                self.stack.push(op);
-            }
-        }
+    let mut parser = Parser { args, pos: 0 };
+    let expr = parser.posix(nargs)?;
+    match parser.args.get(parser.pos) {
+        Some(arg) => Err(format!("extra argument {}", arg.quote())),
+        None => Ok(expr),
     }
+}
 
-    /// Parse a string literal, optionally followed by a comparison operator
-    /// and a second string literal.
-    fn literal(&mut self, token: Symbol) {
-        self.stack.push(token.into_literal());
+#[cfg(test)]
+mod tests {
+    use super::{parse, Expr, FileOp, IntOp, Integer, PathCondition, StringOp};
+    use std::ffi::{OsStr, OsString};
 
-        // EXPR → str OP str
-        if let Symbol::Op(_) = self.peek() {
-            let op = self.next_token();
+    fn parse_args(args: &[&str]) -> Result<Expr, String> {
+        parse(args.iter().map(OsString::from).collect())
+    }
 
-            match self.next_token() {
-                Symbol::None => panic!("missing argument after {op:?}"),
-                token => self.stack.push(token.into_literal()),
-            }
+    fn string(s: &str) -> Box<Expr> {
+        Box::new(Expr::String(s.into()))
+    }
 
-            self.stack.push(op);
+    fn int(s: &str) -> Integer {
+        Integer::parse(OsStr::new(s)).unwrap()
+    }
+
+    #[test]
+    fn test_integer() {
+        assert_eq!(int(" +042\t"), int("42"));
+        assert_eq!(int("-0"), int("0"));
+        assert_eq!(int("42"), int("42"));
+        assert!(int("18446744073709551616") > int("0"));
+        assert!(int("-5") < int("-4"));
+        assert!(int("-1") < int("0"));
+        assert!(int("99999999999999999999") < int("100000000000000000000"));
+        assert!(int("-100000000000000000000") < int("-99999999999999999999"));
+        assert_eq!(Integer::length_of(OsStr::new("abc")), int("3"));
+        assert_eq!(int("2147483648").to_fd(), None);
+        for invalid in ["", "-", "1.5", "1x", "0x10", "1 2"] {
+            assert_eq!(
+                Integer::parse(OsStr::new(invalid)),
+                Err(format!("invalid integer '{invalid}'"))
+            );
         }
     }
 
-    /// Parser entry point: parse the token stream `self.tokens`, storing the
-    /// resulting `Symbol` stack in `self.stack`.
-    fn parse(&mut self) -> Result<(), String> {
-        self.expr();
+    #[test]
+    fn test_two_arguments() {
+        assert_eq!(parse_args(&["!", "-n"]), Ok(Expr::Not(string("-n"))));
+        assert_eq!(
+            parse_args(&["-k", "-a"]),
+            Ok(Expr::Path(PathCondition::Sticky, "-a".into()))
+        );
+        assert_eq!(
+            parse_args(&["-a", "x"]),
+            Err("'-a': unary operator expected".to_string())
+        );
+        assert_eq!(
+            parse_args(&["x", "="]),
+            Err("missing argument after '='".to_string())
+        );
+    }
 
-        match self.tokens.next() {
-            Some(token) => Err(format!("extra argument {}", token.quote())),
-            None => Ok(()),
-        }
+    #[test]
+    fn test_three_arguments() {
+        // The binary operator wins over everything else.
+        assert_eq!(
+            parse_args(&["!", "=", "x"]),
+            Ok(Expr::Strings(StringOp::Equal, "!".into(), "x".into()))
+        );
+        assert_eq!(
+            parse_args(&["(", "!=", ")"]),
+            Ok(Expr::Strings(StringOp::NotEqual, "(".into(), ")".into()))
+        );
+        assert_eq!(
+            parse_args(&["!", "-a", "!"]),
+            Ok(Expr::And(string("!"), string("!")))
+        );
+        assert_eq!(
+            parse_args(&["!", "-z", "x"]),
+            Ok(Expr::Not(Box::new(Expr::Not(string("x")))))
+        );
+        assert_eq!(parse_args(&["(", "-f", ")"]), Ok(*string("-f")));
+        assert_eq!(
+            parse_args(&["a", "b", "c"]),
+            Err("'b': binary operator expected".to_string())
+        );
     }
-}
 
-/// Parse the token stream `args`, returning a `Symbol` stack representing the
-/// operations to perform in postfix order.
-pub fn parse(args: Vec<OsString>) -> Result<Vec<Symbol>, String> {
-    let mut p = Parser::new(args);
-    p.parse()?;
-    Ok(p.stack)
+    #[test]
+    fn test_four_arguments() {
+        assert_eq!(
+            parse_args(&["!", "a", "-lt", "b"]),
+            Err("invalid integer 'a'".to_string())
+        );
+        assert_eq!(
+            parse_args(&["!", "a", "-o", "b"]),
+            Ok(Expr::Not(Box::new(Expr::Or(string("a"), string("b")))))
+        );
+        assert_eq!(parse_args(&["(", "-n", "x", ")"]), Ok(*string("x")));
+        assert_eq!(
+            parse_args(&["-l", "ab", "-eq", "2"]),
+            Ok(Expr::Integers(IntOp::Equal, int("2"), int("2")))
+        );
+        assert_eq!(
+            parse_args(&["x", "-a", "-z", "-o"]),
+            Ok(Expr::And(string("x"), Box::new(Expr::Not(string("-o")))))
+        );
+    }
+
+    #[test]
+    fn test_long_expressions() {
+        // -a binds tighter than -o.
+        assert_eq!(
+            parse_args(&["a", "-o", "b", "-a", "c"]),
+            Ok(Expr::Or(
+                string("a"),
+                Box::new(Expr::And(string("b"), string("c")))
+            ))
+        );
+        assert_eq!(
+            parse_args(&["!", "a", "-a", "b", "-o", "c"]),
+            Ok(Expr::Or(
+                Box::new(Expr::And(Box::new(Expr::Not(string("a"))), string("b"))),
+                string("c")
+            ))
+        );
+        assert_eq!(
+            parse_args(&["(", "a", "-nt", "b", ")", "-a", "-t", "1"]),
+            Ok(Expr::And(
+                Box::new(Expr::Files(FileOp::NewerThan, "a".into(), "b".into())),
+                Box::new(Expr::Terminal(Some(1)))
+            ))
+        );
+        assert_eq!(
+            parse_args(&["3", "-gt", "-l", "ab", "-a", "x"]),
+            Ok(Expr::And(
+                Box::new(Expr::Integers(IntOp::Greater, int("3"), int("2"))),
+                string("x")
+            ))
+        );
+        assert_eq!(
+            parse_args(&["a", "-ot", "-l", "b", "-a", "x"]),
+            Err("-ot does not accept -l".to_string())
+        );
+        assert_eq!(
+            parse_args(&["(", "(", "!=", ")", ")"]),
+            Ok(Expr::Strings(StringOp::NotEqual, "(".into(), ")".into()))
+        );
+        assert_eq!(
+            parse_args(&["(", "a", "-a", "b", "c", "d"]),
+            Err("')' expected, found 'c'".to_string())
+        );
+        assert_eq!(
+            parse_args(&["(", "a", "-a", "b", "-a", "c"]),
+            Err("')' expected".to_string())
+        );
+        assert_eq!(
+            parse_args(&["a", "=", "b", "c"]),
+            Err("extra argument 'c'".to_string())
+        );
+        assert_eq!(
+            parse_args(&["a", "-a", "b", "-o"]),
+            Err("missing argument after '-o'".to_string())
+        );
+    }
 }
@@ -6,17 +6,16 @@
 // For the full copyright and license information, please view the LICENSE
 // file that was distributed with this source code.
 
-// spell-checker:ignore (vars) egid euid FiletestOp StrlenOp
+// spell-checker:ignore (vars) egid euid
 
 mod parser;
 
 use clap::{crate_version, Command};
-use parser::{parse, Operator, Symbol, UnaryOperator};
+use parser::{parse, Expr, FileOp, IntOp, PathCondition, StringOp};
 use std::ffi::{OsStr, OsString};
 use std::fs;
 #[cfg(unix)]
 use std::os::unix::fs::MetadataExt;
-use uucore::display::Quotable;
 use uucore::error::{UResult, USimpleError};
 use uucore::format_usage;
 
@@ -128,206 +127,77 @@ pub fn uumain(mut args: impl uucore::Args) -> UResult<()> {
         }
     }
 
-    let result = parse(args).and_then(|mut stack| eval(&mut stack));
-
-    match result {
-        Ok(result) => {
-            if result {
-                Ok(())
-            } else {
-                Err(1.into())
-            }
-        }
-        Err(e) => Err(USimpleError::new(2, e)),
+    let expr = parse(args).map_err(|e| USimpleError::new(2, e))?;
+    if eval(&expr) {
+        Ok(())
+    } else {
+        Err(1.into())
     }
 }
 
-/// Evaluate a stack of Symbols, returning the result of the evaluation or
-/// an error message if evaluation failed.
-fn eval(stack: &mut Vec<Symbol>) -> Result<bool, String> {
-    macro_rules! pop_literal {
-        () => {
-            match stack.pop() {
-                Some(Symbol::Literal(s)) => s,
-                _ => panic!(),
-            }
-        };
-    }
-
-    let s = stack.pop();
-
-    match s {
-        Some(Symbol::Bang) => {
-            let result = eval(stack)?;
-
-            Ok(!result)
-        }
-        Some(Symbol::Op(Operator::String(op))) => {
-            let b = stack.pop();
-            let a = stack.pop();
-            Ok(if op == "!=" { a != b } else { a == b })
-        }
-        Some(Symbol::Op(Operator::Int(op))) => {
-            let b = pop_literal!();
-            let a = pop_literal!();
-
-            Ok(integers(&a, &b, &op)?)
-        }
-        Some(Symbol::Op(Operator::File(op))) => {
-            let b = pop_literal!();
-            let a = pop_literal!();
-            Ok(files(&a, &b, &op)?)
-        }
-        Some(Symbol::UnaryOp(UnaryOperator::StrlenOp(op))) => {
-            let s = match stack.pop() {
-                Some(Symbol::Literal(s)) => s,
-                Some(Symbol::None) => OsString::from(""),
-                None => {
-                    return Ok(true);
-                }
-                _ => {
-                    return Err(format!("missing argument after '{op:?}'"));
-                }
-            };
-
-            Ok(if op == "-z" {
-                s.is_empty()
-            } else {
-                !s.is_empty()
-            })
-        }
-        Some(Symbol::UnaryOp(UnaryOperator::FiletestOp(op))) => {
-            let op = op.to_str().unwrap();
-
-            let f = pop_literal!();
-
-            Ok(match op {
-                "-b" => path(&f, &PathCondition::BlockSpecial),
-                "-c" => path(&f, &PathCondition::CharacterSpecial),
-                "-d" => path(&f, &PathCondition::Directory),
-                "-e" => path(&f, &PathCondition::Exists),
-                "-f" => path(&f, &PathCondition::Regular),
-                "-g" => path(&f, &PathCondition::GroupIdFlag),
-                "-G" => path(&f, &PathCondition::GroupOwns),
-                "-h" => path(&f, &PathCondition::SymLink),
-                "-k" => path(&f, &PathCondition::Sticky),
-                "-L" => path(&f, &PathCondition::SymLink),
-                "-N" => path(&f, &PathCondition::ExistsModifiedLastRead),
-                "-O" => path(&f, &PathCondition::UserOwns),
-                "-p" => path(&f, &PathCondition::Fifo),
-                "-r" => path(&f, &PathCondition::Readable),
-                "-S" => path(&f, &PathCondition::Socket),
-                "-s" => path(&f, &PathCondition::NonEmpty),
-                "-t" => isatty(&f)?,
-                "-u" => path(&f, &PathCondition::UserIdFlag),
-                "-w" => path(&f, &PathCondition::Writable),
-                "-x" => path(&f, &PathCondition::Executable),
-                _ => panic!(),
-            })
-        }
-        Some(Symbol::Literal(s)) => Ok(!s.is_empty()),
-        Some(Symbol::None) | None => Ok(false),
-        Some(Symbol::BoolOp(op)) => {
-            let b = eval(stack)?;
-            let a = eval(stack)?;
-
-            Ok(if op == "-a" { a && b } else { a || b })
-        }
-        _ => Err("expected value".to_string()),
+/// Evaluate a parsed expression.
+fn eval(expr: &Expr) -> bool {
+    match expr {
+        Expr::String(s) => !s.is_empty(),
+        Expr::Not(expr) => !eval(expr),
+        Expr::And(a, b) => eval(a) && eval(b),
+        Expr::Or(a, b) => eval(a) || eval(b),
+        Expr::Strings(StringOp::Equal, a, b) => a == b,
+        Expr::Strings(StringOp::NotEqual, a, b) => a != b,
+        Expr::Integers(op, a, b) => match op {
+            IntOp::Equal => a == b,
+            IntOp::NotEqual => a != b,
+            IntOp::Greater => a > b,
+            IntOp::GreaterEqual => a >= b,
+            IntOp::Less => a < b,
+            IntOp::LessEqual => a <= b,
+        },
+        Expr::Files(op, a, b) => files(a, b, *op),
+        Expr::Path(condition, f) => path(f, condition),
+        Expr::Terminal(fd) => fd.map_or(false, isatty),
     }
 }
 
-/// Operations to compare integers
-/// `a` is the left hand side
-/// `b` is the left hand side
-/// `op` the operation (ex: -eq, -lt, etc)
-fn integers(a: &OsStr, b: &OsStr, op: &OsStr) -> Result<bool, String> {
-    let format_err = |value: &OsStr| format!("invalid integer {}", value.quote());
-
-    // Parse the two inputs
-    let a: i128 = a
-        .to_str()
-        .and_then(|s| s.parse().ok())
-        .ok_or_else(|| format_err(a))?;
-
-    let b: i128 = b
-        .to_str()
-        .and_then(|s| s.parse().ok())
-        .ok_or_else(|| format_err(b))?;
-
-    // Do the maths
-    Ok(match op.to_str() {
-        Some("-eq") => a == b,
-        Some("-ne") => a != b,
-        Some("-gt") => a > b,
-        Some("-ge") => a >= b,
-        Some("-lt") => a < b,
-        Some("-le") => a <= b,
-        _ => return Err(format!("unknown operator {}", op.quote())),
-    })
-}
-
 /// Operations to compare files metadata
 /// `a` is the left hand side
-/// `b` is the left hand side
+/// `b` is the right hand side
 /// `op` the operation (ex: -ef, -nt, etc)
-fn files(a: &OsStr, b: &OsStr, op: &OsStr) -> Result<bool, String> {
-    // Don't manage the error. GNU doesn't show error when doing
-    // test foo -nt bar
-    let f_a = match fs::metadata(a) {
-        Ok(f) => f,
-        Err(_) => return Ok(false),
-    };
-    let f_b = match fs::metadata(b) {
-        Ok(f) => f,
-        Err(_) => return Ok(false),
-    };
+fn files(a: &OsStr, b: &OsStr, op: FileOp) -> bool {
+    // Like with GNU, a file that exists is newer than one that doesn't, and
+    // errors are not shown.
+    let modified = |f: &OsStr| fs::metadata(f).and_then(|m| m.modified()).ok();
 
-    Ok(match op.to_str() {
+    match op {
         #[cfg(unix)]
-        Some("-ef") => f_a.ino() == f_b.ino() && f_a.dev() == f_b.dev(),
+        FileOp::Same => match (fs::metadata(a), fs::metadata(b)) {
+            (Ok(f_a), Ok(f_b)) => f_a.ino() == f_b.ino() && f_a.dev() == f_b.dev(),
+            _ => false,
+        },
         #[cfg(not(unix))]
-        Some("-ef") => unimplemented!(),
-        Some("-nt") => f_a.modified().unwrap() > f_b.modified().unwrap(),
-        Some("-ot") => f_a.created().unwrap() > f_b.created().unwrap(),
-        _ => return Err(format!("unknown operator {}", op.quote())),
-    })
-}
-
-fn isatty(fd: &OsStr) -> Result<bool, String> {
-    fd.to_str()
-        .and_then(|s| s.parse().ok())
-        .ok_or_else(|| format!("invalid integer {}", fd.quote()))
-        .map(|i| {
-            #[cfg(not(target_os = "redox"))]
-            unsafe {
-                libc::isatty(i) == 1
-            }
-            #[cfg(target_os = "redox")]
-            syscall::dup(i, b"termios").map(syscall::close).is_ok()
-        })
+        FileOp::Same => match (fs::canonicalize(a), fs::canonicalize(b)) {
+            (Ok(p_a), Ok(p_b)) => p_a == p_b,
+            _ => false,
+        },
+        FileOp::NewerThan => match (modified(a), modified(b)) {
+            (Some(m_a), Some(m_b)) => m_a > m_b,
+            (a, _) => a.is_some(),
+        },
+        FileOp::OlderThan => match (modified(a), modified(b)) {
+            (Some(m_a), Some(m_b)) => m_a < m_b,
+            (_, b) => b.is_some(),
+        },
+    }
 }
 
-#[derive(Eq, PartialEq)]
-enum PathCondition {
-    BlockSpecial,
-    CharacterSpecial,
-    Directory,
-    Exists,
-    ExistsModifiedLastRead,
-    Regular,
-    GroupIdFlag,
-    GroupOwns,
-    SymLink,
-    Sticky,
-    UserOwns,
-    Fifo,
-    Readable,
-    Socket,
-    NonEmpty,
-    UserIdFlag,
-    Writable,
-    Executable,
+fn isatty(fd: i32) -> bool {
+    #[cfg(not(target_os = "redox"))]
+    unsafe {
+        libc::isatty(fd) == 1
+    }
+    #[cfg(target_os = "redox")]
+    syscall::dup(fd as usize, b"termios")
+        .map(syscall::close)
+        .is_ok()
 }
 
 #[cfg(not(windows))]
@@ -426,13 +296,13 @@ fn path(path: &OsStr, condition: &PathCondition) -> bool {
         PathCondition::CharacterSpecial => false,
         PathCondition::Directory => stat.is_dir(),
         PathCondition::Exists => true,
-        PathCondition::ExistsModifiedLastRead => unimplemented!(),
+        PathCondition::ExistsModifiedLastRead => false,
         PathCondition::Regular => stat.is_file(),
         PathCondition::GroupIdFlag => false,
-        PathCondition::GroupOwns => unimplemented!(),
+        PathCondition::GroupOwns => false,
         PathCondition::SymLink => false,
         PathCondition::Sticky => false,
-        PathCondition::UserOwns => unimplemented!(),
+        PathCondition::UserOwns => false,
         PathCondition::Fifo => false,
         PathCondition::Readable => false, // TODO
         PathCondition::Socket => false,
@@ -442,28 +312,3 @@ fn path(path: &OsStr, condition: &PathCondition) -> bool {
         PathCondition::Executable => false, // TODO
     }
 }
-
-#[cfg(test)]
-mod tests {
-    use super::integers;
-    use std::ffi::OsStr;
-
-    #[test]
-    fn test_integer_op() {
-        let a = OsStr::new("18446744073709551616");
-        let b = OsStr::new("0");
-        assert!(!integers(a, b, OsStr::new("-lt")).unwrap());
-        let a = OsStr::new("18446744073709551616");
-        let b = OsStr::new("0");
-        assert!(integers(a, b, OsStr::new("-gt")).unwrap());
-        let a = OsStr::new("-1");
-        let b = OsStr::new("0");
-        assert!(integers(a, b, OsStr::new("-lt")).unwrap());
-        let a = OsStr::new("42");
-        let b = OsStr::new("42");
-        assert!(integers(a, b, OsStr::new("-eq")).unwrap());
-        let a = OsStr::new("42");
-        let b = OsStr::new("42");
-        assert!(!integers(a, b, OsStr::new("-ne")).unwrap());
-    }
-}
@@ -26,11 +26,14 @@ pub(crate) enum ExitStatus {
     /// When `timeout` itself fails.
     TimeoutFailed,
 
-    /// When a signal is sent to the child process or `timeout` itself.
-    SignalSent(usize),
+    /// When the command is found but cannot be invoked.
+    CannotInvoke,
+
+    /// When the command cannot be found.
+    CommandNotFound,
 
-    /// When there is a failure while waiting for the child process to terminate.
-    WaitingFailed,
+    /// When the child process is terminated by a signal.
+    SignalSent(usize),
 }
 
 impl From<ExitStatus> for i32 {
@@ -38,8 +41,9 @@ impl From<ExitStatus> for i32 {
         match exit_status {
             ExitStatus::CommandTimedOut => 124,
             ExitStatus::TimeoutFailed => 125,
+            ExitStatus::CannotInvoke => 126,
+            ExitStatus::CommandNotFound => 127,
             ExitStatus::SignalSent(s) => 128 + s as Self,
-            ExitStatus::WaitingFailed => 124,
         }
     }
 }
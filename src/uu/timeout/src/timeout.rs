@@ -5,17 +5,19 @@
 //  * For the full copyright and license information, please view the LICENSE
 //  * file that was distributed with this source code.
 
-// spell-checker:ignore (ToDO) tstr sigstr cmdname setpgid sigchld getpid
+// spell-checker:ignore (ToDO) tstr sigstr cmdname setpgid sigchld getpid rlimit setrlimit SIGTTIN SIGTTOU
 mod status;
 
 use crate::status::ExitStatus;
 use clap::{crate_version, Arg, ArgAction, Command};
 use std::io::ErrorKind;
-use std::os::unix::process::ExitStatusExt;
+use std::os::unix::process::{CommandExt, ExitStatusExt};
 use std::process::{self, Child, Stdio};
-use std::time::Duration;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
 use uucore::display::Quotable;
-use uucore::error::{UClapError, UResult, USimpleError, UUsageError};
+use uucore::error::{strip_errno, UClapError, UResult, USimpleError, UUsageError};
 use uucore::process::ChildExt;
 use uucore::signals::{signal_by_name_or_value, signal_name_by_value};
 use uucore::{format_usage, show_error};
@@ -196,54 +198,107 @@ fn report_if_verbose(signal: usize, cmd: &str, verbose: bool) {
     }
 }
 
-/// Wait for a child process and send a kill signal if it does not terminate.
-///
-/// This function waits for the child `process` for the time period
-/// given by `duration`. If the child process does not terminate
-/// within that time, we send the `SIGKILL` signal to it. If `verbose`
-/// is `true`, then a message is printed to `stderr` when that
-/// happens.
-///
-/// If the child process terminates within the given time period and
-/// `preserve_status` is `true`, then the status code of the child
-/// process is returned. If the child process terminates within the
-/// given time period and `preserve_status` is `false`, then 124 is
-/// returned. If the child does not terminate within the time period,
-/// then 137 is returned. Finally, if there is an error while waiting
-/// for the child process to terminate, then 124 is returned.
-///
-/// # Errors
-///
-/// If there is a problem sending the `SIGKILL` signal or waiting for
-/// the process after that signal is sent.
-fn wait_or_kill_process(
-    mut process: Child,
-    cmd: &str,
-    duration: Duration,
-    preserve_status: bool,
-    verbose: bool,
-) -> std::io::Result<i32> {
-    match process.wait_or_timeout(duration) {
-        Ok(Some(status)) => {
-            if preserve_status {
-                Ok(status.code().unwrap_or_else(|| status.signal().unwrap()))
-            } else {
-                Ok(ExitStatus::TimeoutFailed.into())
+/// The last signal received by timeout itself, to be passed on to the command
+static RECEIVED_SIGNAL: AtomicUsize = AtomicUsize::new(0);
+
+extern "C" fn record_signal(signal: libc::c_int) {
+    RECEIVED_SIGNAL.store(signal as usize, Ordering::SeqCst);
+}
+
+/// Catch the signals that timeout passes on to the command: the usual
+/// termination signals, and the one to send on timeout.
+fn catch_signals(signal: usize) {
+    use nix::sys::signal::{self, SigHandler, Signal};
+
+    let signals = [
+        libc::SIGINT,
+        libc::SIGQUIT,
+        libc::SIGHUP,
+        libc::SIGTERM,
+        signal as libc::c_int,
+    ];
+    for sig in signals {
+        // SIGKILL, SIGSTOP and the null signal cannot be caught.
+        if let Ok(sig) = Signal::try_from(sig) {
+            unsafe {
+                let _ = signal::signal(sig, SigHandler::Handler(record_signal));
             }
         }
-        Ok(None) => {
-            let signal = signal_by_name_or_value("KILL").unwrap();
-            report_if_verbose(signal, cmd, verbose);
-            process.send_signal(signal)?;
-            process.wait()?;
-            Ok(ExitStatus::SignalSent(signal).into())
+    }
+}
+
+/// Send `signal` to the command, and also to the rest of the process group
+/// unless running in the foreground.
+///
+/// The command and its group are resumed afterwards, so that stopped
+/// processes get the signal too.
+fn send_signal(process: &mut Child, signal: usize, foreground: bool) {
+    let kill = signal_by_name_or_value("KILL").unwrap();
+    let cont = signal_by_name_or_value("CONT").unwrap();
+    let send_to_group = |signal: usize| unsafe {
+        // timeout is part of the group, so it must not be terminated by
+        // the signal, nor catch it again and pass it on in a loop.
+        libc::signal(signal as libc::c_int, libc::SIG_IGN);
+        libc::kill(0, signal as libc::c_int);
+    };
+
+    // Send the signal to the command directly too, in case it left the
+    // process group.
+    let _ = process.send_signal(signal);
+    if !foreground {
+        send_to_group(signal);
+        if signal != kill && signal != cont {
+            let _ = process.send_signal(cont);
+            send_to_group(cont);
+        }
+    }
+}
+
+/// What ended a wait for the command
+enum Event {
+    Exited(process::ExitStatus),
+    TimedOut,
+    Signaled(usize),
+}
+
+/// Wait until the command exits, `deadline` passes (if any) or timeout
+/// receives a signal.
+fn wait_for_event(process: &mut Child, deadline: Option<Instant>) -> std::io::Result<Event> {
+    loop {
+        if let Some(status) = process.try_wait()? {
+            return Ok(Event::Exited(status));
         }
-        Err(_) => Ok(ExitStatus::WaitingFailed.into()),
+        let signal = RECEIVED_SIGNAL.swap(0, Ordering::SeqCst);
+        if signal != 0 {
+            return Ok(Event::Signaled(signal));
+        }
+
+        // XXX: this is kinda gross, but it's cleaner than starting a thread
+        //      just to wait
+        let mut pause = Duration::from_millis(100);
+        if let Some(deadline) = deadline {
+            let now = Instant::now();
+            if now >= deadline {
+                return Ok(Event::TimedOut);
+            }
+            pause = pause.min(deadline - now);
+        }
+        thread::sleep(pause);
+    }
+}
+
+/// The deadline `duration` from now, or none if `duration` is zero or so
+/// long that it cannot be represented.
+fn deadline_after(duration: Duration) -> Option<Instant> {
+    if duration.is_zero() {
+        None
+    } else {
+        Instant::now().checked_add(duration)
     }
 }
 
 #[cfg(unix)]
-fn preserve_signal_info(signal: libc::c_int) -> libc::c_int {
+fn preserve_signal_info(signal: libc::c_int) {
     // This is needed because timeout is expected to preserve the exit
     // status of its child. It is not the case that utilities have a
     // single simple exit code, that's an illusion some shells
@@ -255,17 +310,24 @@ fn preserve_signal_info(signal: libc::c_int) -> libc::c_int {
     //
     // The easiest way to preserve the latter seems to be to kill
     // ourselves with whatever signal our child exited with, which is
-    // what the following is intended to accomplish.
+    // what the following is intended to accomplish. The signal may be
+    // caught or ignored by now, and timeout should not dump core over
+    // its child's crash.
+    let no_core = libc::rlimit {
+        rlim_cur: 0,
+        rlim_max: 0,
+    };
     unsafe {
-        libc::kill(libc::getpid(), signal);
+        if libc::setrlimit(libc::RLIMIT_CORE, &no_core) == 0 {
+            libc::signal(signal, libc::SIG_DFL);
+            libc::kill(libc::getpid(), signal);
+        }
     }
-    signal
 }
 
 #[cfg(not(unix))]
-fn preserve_signal_info(signal: libc::c_int) -> libc::c_int {
+fn preserve_signal_info(_signal: libc::c_int) {
     // Do nothing
-    signal
 }
 
 #[cfg(unix)]
@@ -283,92 +345,118 @@ fn enable_pipe_errors() -> std::io::Result<()> {
     Ok(())
 }
 
-/// TODO: Improve exit codes, and make them consistent with the GNU Coreutils exit codes.
-
+/// Run `cmd`, and send it `signal` if it is still running after `duration`.
+///
+/// If `kill_after` is given, `SIGKILL` follows once that much more time has
+/// passed after the first signal, whether that one was sent on timeout or
+/// passed on from a signal timeout received itself.
+///
+/// The exit status is the one of `cmd`, with signals reported as 128 plus
+/// the signal number, unless it timed out: then it is 124, or still the one
+/// of `cmd` with `preserve_status` or when it had to be killed.
 fn timeout(
     cmd: &[String],
     duration: Duration,
     signal: usize,
     kill_after: Option<Duration>,
     foreground: bool,
-    preserve_status: bool,
+    mut preserve_status: bool,
     verbose: bool,
 ) -> UResult<()> {
+    let mut command = process::Command::new(&cmd[0]);
+    command
+        .args(&cmd[1..])
+        .stdin(Stdio::inherit())
+        .stdout(Stdio::inherit())
+        .stderr(Stdio::inherit());
     if !foreground {
-        unsafe { libc::setpgid(0, 0) };
+        // Run in a group of our own so that the whole group can be signaled,
+        // and keep writing to the terminal from this background group.
+        unsafe {
+            libc::setpgid(0, 0);
+            libc::signal(libc::SIGTTIN, libc::SIG_IGN);
+            libc::signal(libc::SIGTTOU, libc::SIG_IGN);
+            command.pre_exec(|| {
+                libc::signal(libc::SIGTTIN, libc::SIG_DFL);
+                libc::signal(libc::SIGTTOU, libc::SIG_DFL);
+                Ok(())
+            });
+        }
     }
 
     enable_pipe_errors()?;
+    catch_signals(signal);
 
-    let mut process = process::Command::new(&cmd[0])
-        .args(&cmd[1..])
-        .stdin(Stdio::inherit())
-        .stdout(Stdio::inherit())
-        .stderr(Stdio::inherit())
-        .spawn()
-        .map_err(|err| {
-            let status_code = if err.kind() == ErrorKind::NotFound {
-                // FIXME: not sure which to use
-                127
-            } else {
-                // FIXME: this may not be 100% correct...
-                126
-            };
-            USimpleError::new(status_code, format!("failed to execute process: {err}"))
-        })?;
+    let mut process = command.spawn().map_err(|err| {
+        let status_code = if err.kind() == ErrorKind::NotFound {
+            ExitStatus::CommandNotFound
+        } else {
+            ExitStatus::CannotInvoke
+        };
+        USimpleError::new(
+            status_code.into(),
+            format!(
+                "failed to run command {}: {}",
+                cmd[0].quote(),
+                strip_errno(&err)
+            ),
+        )
+    })?;
     unblock_sigchld();
-    // Wait for the child process for the specified time period.
-    //
-    // If the process exits within the specified time period (the
-    // `Ok(Some(_))` arm), then return the appropriate status code.
-    //
-    // If the process does not exit within that time (the `Ok(None)`
-    // arm) and `kill_after` is specified, then try sending `SIGKILL`.
-    //
-    // TODO The structure of this block is extremely similar to the
-    // structure of `wait_or_kill_process()`. They can probably be
-    // refactored into some common function.
-    match process.wait_or_timeout(duration) {
-        Ok(Some(status)) => Err(status
-            .code()
-            .unwrap_or_else(|| preserve_signal_info(status.signal().unwrap()))
-            .into()),
-        Ok(None) => {
-            report_if_verbose(signal, &cmd[0], verbose);
-            process.send_signal(signal)?;
-            match kill_after {
-                None => {
-                    if preserve_status {
-                        Err(ExitStatus::SignalSent(signal).into())
-                    } else {
-                        Err(ExitStatus::CommandTimedOut.into())
-                    }
-                }
-                Some(kill_after) => {
-                    match wait_or_kill_process(
-                        process,
-                        &cmd[0],
-                        kill_after,
-                        preserve_status,
-                        verbose,
-                    ) {
-                        Ok(status) => Err(status.into()),
-                        Err(e) => Err(USimpleError::new(
-                            ExitStatus::TimeoutFailed.into(),
-                            format!("{e}"),
-                        )),
-                    }
-                }
+
+    let mut signal = signal;
+    let mut kill_after = kill_after.filter(|k| !k.is_zero());
+    let mut deadline = deadline_after(duration);
+    let mut timed_out = false;
+    let status = loop {
+        let sig = match wait_for_event(&mut process, deadline) {
+            Ok(Event::Exited(status)) => break status,
+            Ok(Event::TimedOut) => {
+                timed_out = true;
+                deadline = None;
+                signal
+            }
+            Ok(Event::Signaled(sig)) => sig,
+            Err(e) => {
+                send_signal(&mut process, signal, foreground);
+                return Err(USimpleError::new(
+                    ExitStatus::TimeoutFailed.into(),
+                    format!("error waiting for command: {}", strip_errno(&e)),
+                ));
             }
+        };
+        // Only the first signal starts the countdown to SIGKILL.
+        if let Some(kill_after) = kill_after.take() {
+            deadline = deadline_after(kill_after);
+            signal = signal_by_name_or_value("KILL").unwrap();
         }
-        Err(_) => {
-            // We're going to return ERR_EXIT_STATUS regardless of
-            // whether `send_signal()` succeeds or fails, so just
-            // ignore the return value.
-            process
-                .send_signal(signal)
-                .map_err(|e| USimpleError::new(ExitStatus::TimeoutFailed.into(), format!("{e}")))?;
-            Err(ExitStatus::TimeoutFailed.into())
+        report_if_verbose(sig, &cmd[0], verbose);
+        send_signal(&mut process, sig, foreground);
+    };
+
+    let code = match (status.code(), status.signal()) {
+        (Some(code), _) => code,
+        (None, Some(sig)) => {
+            if status.core_dumped() {
+                show_error!("the monitored command dumped core");
+            }
+            if !timed_out {
+                preserve_signal_info(sig);
+            }
+            // Tell whether the command had to be killed, which matters
+            // with --foreground where timeout itself is not killed.
+            if timed_out && sig as usize == signal_by_name_or_value("KILL").unwrap() {
+                preserve_status = true;
+            }
+            ExitStatus::SignalSent(sig as usize).into()
         }
+        (None, None) => unreachable!("the command neither exited nor was signaled"),
+    };
+    if timed_out && !preserve_status {
+        Err(ExitStatus::CommandTimedOut.into())
+    } else if code == 0 {
+        Ok(())
+    } else {
+        Err(code.into())
     }
 }
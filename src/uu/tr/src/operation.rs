@@ -7,10 +7,10 @@
 
 use nom::{
     branch::alt,
-    bytes::complete::tag,
-    character::complete::{anychar, digit1},
-    combinator::{map, peek, value},
-    multi::many0,
+    bytes::complete::{tag, take_until},
+    character::complete::{anychar, one_of},
+    combinator::{all_consuming, map, map_opt, recognize, verify},
+    multi::{many0, many_m_n},
     sequence::{delimited, preceded, separated_pair},
     IResult,
 };
@@ -18,7 +18,7 @@ use std::{
     collections::{HashMap, HashSet},
     error::Error,
     fmt::{Debug, Display},
-    io::{BufRead, Write},
+    io::{self, BufRead, Read, Write},
 };
 use uucore::error::UError;
 
@@ -27,32 +27,73 @@ use crate::unicode_table;
 #[derive(Debug, Clone)]
 pub enum BadSequence {
     MissingCharClassName,
+    InvalidCharClass(String),
     MissingEquivalentClassChar,
+    MultipleCharEquivalentClass(String),
     MultipleCharRepeatInSet2,
     CharRepeatInSet1,
     InvalidRepeatCount(String),
     EmptySet2WhenNotTruncatingSet1,
+    RangeEndpointsReversed(char, char),
+    EquivalentClassInSet2,
+    ClassExceptLowerUpperInSet2,
+    MisalignedLowerUpper,
+    Set2EndsWithClass,
+    ComplementMoreThanOneUniqueInSet2,
 }
 
 impl Display for BadSequence {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            Self::MissingCharClassName => writeln!(f, "missing character class name '[::]'"),
+            Self::MissingCharClassName => write!(f, "missing character class name '[::]'"),
+            Self::InvalidCharClass(name) => write!(f, "invalid character class '{name}'"),
             Self::MissingEquivalentClassChar => {
-                writeln!(f, "missing equivalence class character '[==]'")
+                write!(f, "missing equivalence class character '[==]'")
+            }
+            Self::MultipleCharEquivalentClass(chars) => {
+                write!(
+                    f,
+                    "{chars}: equivalence class operand must be a single character"
+                )
             }
             Self::MultipleCharRepeatInSet2 => {
-                writeln!(f, "only one [c*] repeat construct may appear in string2")
+                write!(f, "only one [c*] repeat construct may appear in string2")
             }
             Self::CharRepeatInSet1 => {
-                writeln!(f, "the [c*] repeat construct may not appear in string1")
+                write!(f, "the [c*] repeat construct may not appear in string1")
             }
             Self::InvalidRepeatCount(count) => {
-                writeln!(f, "invalid repeat count '{count}' in [c*n] construct")
+                write!(f, "invalid repeat count '{count}' in [c*n] construct")
             }
             Self::EmptySet2WhenNotTruncatingSet1 => {
-                writeln!(f, "when not truncating set1, string2 must be non-empty")
+                write!(f, "when not truncating set1, string2 must be non-empty")
             }
+            Self::RangeEndpointsReversed(start, end) => write!(
+                f,
+                "range-endpoints of '{start}-{end}' are in reverse collating sequence order"
+            ),
+            Self::EquivalentClassInSet2 => write!(
+                f,
+                "[=c=] expressions may not appear in string2 when translating"
+            ),
+            Self::ClassExceptLowerUpperInSet2 => write!(
+                f,
+                "when translating, the only character classes that may appear in\n\
+                 string2 are 'upper' and 'lower'"
+            ),
+            Self::MisalignedLowerUpper => {
+                write!(f, "misaligned [:upper:] and/or [:lower:] construct")
+            }
+            Self::Set2EndsWithClass => write!(
+                f,
+                "when translating with string1 longer than string2,\n\
+                 the latter string must not end with a character class"
+            ),
+            Self::ComplementMoreThanOneUniqueInSet2 => write!(
+                f,
+                "when translating with complemented character classes,\n\
+                 string2 must map all characters in the domain to one"
+            ),
         }
     }
 }
@@ -66,6 +107,8 @@ pub enum Sequence {
     CharRange(u32, u32),
     CharStar(char),
     CharRepeat(char, usize),
+    /// An equivalence class `[=c=]`, which in the C locale only holds the character itself.
+    CharEqual(char),
     Alnum,
     Alpha,
     Blank,
@@ -83,7 +126,7 @@ pub enum Sequence {
 impl Sequence {
     pub fn flatten(&self) -> Box<dyn Iterator<Item = char>> {
         match self {
-            Self::Char(c) => Box::new(std::iter::once(*c)),
+            Self::Char(c) | Self::CharEqual(c) => Box::new(std::iter::once(*c)),
             Self::CharRange(l, r) => Box::new((*l..=*r).flat_map(std::char::from_u32)),
             Self::CharStar(c) => Box::new(std::iter::repeat(*c)),
             Self::CharRepeat(c, n) => Box::new(std::iter::repeat(*c).take(*n)),
@@ -96,30 +139,11 @@ impl Sequence {
                     .flat_map(std::char::from_u32),
             ),
             Self::Digit => Box::new('0'..='9'),
-            Self::Graph => Box::new(
-                (48..=57) // digit
-                    .chain(65..=90) // uppercase
-                    .chain(97..=122) // lowercase
-                    // punctuations
-                    .chain(33..=47)
-                    .chain(58..=64)
-                    .chain(91..=96)
-                    .chain(123..=126)
-                    .chain(std::iter::once(32)) // space
-                    .flat_map(std::char::from_u32),
-            ),
+            // the digits, letters and punctuations
+            Self::Graph => Box::new((33..=126).flat_map(std::char::from_u32)),
             Self::Lower => Box::new('a'..='z'),
-            Self::Print => Box::new(
-                (48..=57) // digit
-                    .chain(65..=90) // uppercase
-                    .chain(97..=122) // lowercase
-                    // punctuations
-                    .chain(33..=47)
-                    .chain(58..=64)
-                    .chain(91..=96)
-                    .chain(123..=126)
-                    .flat_map(std::char::from_u32),
-            ),
+            // the same as graph, and the space
+            Self::Print => Box::new((32..=126).flat_map(std::char::from_u32)),
             Self::Punct => Box::new(
                 (33..=47)
                     .chain(58..=64)
@@ -133,16 +157,52 @@ impl Sequence {
         }
     }
 
+    fn is_class(&self) -> bool {
+        !matches!(
+            self,
+            Self::Char(_)
+                | Self::CharRange(_, _)
+                | Self::CharStar(_)
+                | Self::CharRepeat(_, _)
+                | Self::CharEqual(_)
+        )
+    }
+
+    /// Returns the number of characters of the sequence, where `[c*]` fills `star_len`
+    /// characters.
+    fn len(&self, star_len: usize) -> usize {
+        match self {
+            Self::CharStar(_) => star_len,
+            s => s.flatten().count(),
+        }
+    }
+
     // Hide all the nasty sh*t in here
     // TODO: Make the 2 set lazily generate the character mapping as necessary.
+    /// Returns the characters of both sets, SET2 being the one to translate to if `translating`,
+    /// which is then checked against SET1 (or its complement if `complement`).
     pub fn solve_set_characters(
         set1_str: &str,
         set2_str: &str,
         truncate_set1_flag: bool,
+        translating: bool,
+        complement: bool,
     ) -> Result<(Vec<char>, Vec<char>), BadSequence> {
         let set1 = Self::from_str(set1_str)?;
         let set2 = Self::from_str(set2_str)?;
 
+        if translating {
+            if set2.iter().any(|s| matches!(s, Self::CharEqual(_))) {
+                return Err(BadSequence::EquivalentClassInSet2);
+            }
+            if set2
+                .iter()
+                .any(|s| s.is_class() && !matches!(s, Self::Lower | Self::Upper))
+            {
+                return Err(BadSequence::ClassExceptLowerUpperInSet2);
+            }
+        }
+
         let is_char_star = |s: &&Self| -> bool { matches!(s, Self::CharStar(_)) };
         let set1_star_count = set1.iter().filter(is_char_star).count();
         if set1_star_count == 0 {
@@ -201,6 +261,16 @@ impl Sequence {
                             .collect(),
                     },
                 };
+                if translating {
+                    Self::check_translation(
+                        &set1,
+                        &set2,
+                        &set2_solved,
+                        star_compensate_len,
+                        truncate_set1_flag,
+                        complement,
+                    )?;
+                }
                 let mut set1_solved: Vec<char> = set1.iter().flat_map(Self::flatten).collect();
                 if truncate_set1_flag {
                     set1_solved.truncate(set2_solved.len());
@@ -213,14 +283,58 @@ impl Sequence {
             Err(BadSequence::CharRepeatInSet1)
         }
     }
+
+    /// Checks that SET2, whose characters are `set2_solved`, can be used to translate SET1.
+    fn check_translation(
+        set1: &[Self],
+        set2: &[Self],
+        set2_solved: &[char],
+        star_len: usize,
+        truncate_set1_flag: bool,
+        complement: bool,
+    ) -> Result<(), BadSequence> {
+        let set1_len: usize = set1.iter().map(|s| s.len(0)).sum();
+
+        // The case of the letters is converted by [:lower:] and [:upper:] in SET2 at the same
+        // position as [:upper:] or [:lower:] in SET1.
+        let case_class_starts = |set: &[Self], star_len| {
+            let mut start = 0;
+            let mut starts = Vec::new();
+            for s in set {
+                if matches!(s, Self::Lower | Self::Upper) {
+                    starts.push(start);
+                }
+                start += s.len(star_len);
+            }
+            starts
+        };
+        let set1_starts = case_class_starts(set1, 0);
+        for start in case_class_starts(set2, star_len) {
+            if start < set1_len && !complement && !set1_starts.contains(&start) {
+                return Err(BadSequence::MisalignedLowerUpper);
+            }
+        }
+
+        // the complement of SET1 holds nearly every character
+        let set2_extended = complement || set1_len > set2_solved.len();
+        if !truncate_set1_flag && set2_extended && set2.last().map_or(false, Self::is_class) {
+            return Err(BadSequence::Set2EndsWithClass);
+        }
+        if complement
+            && set1.iter().any(Self::is_class)
+            && set2_solved.iter().any(|c| *c != set2_solved[0])
+        {
+            return Err(BadSequence::ComplementMoreThanOneUniqueInSet2);
+        }
+        Ok(())
+    }
 }
 
 impl Sequence {
     pub fn from_str(input: &str) -> Result<Vec<Self>, BadSequence> {
         many0(alt((
             Self::parse_char_range,
-            Self::parse_char_star,
-            Self::parse_char_repeat,
+            Self::parse_char_star_or_repeat,
             Self::parse_class,
             Self::parse_char_equal,
             // NOTE: This must be the last one
@@ -233,19 +347,33 @@ impl Sequence {
     }
 
     fn parse_backslash(input: &str) -> IResult<&str, char> {
-        preceded(tag("\\"), anychar)(input).map(|(l, a)| {
-            let c = match a {
-                'a' => unicode_table::BEL,
-                'b' => unicode_table::BS,
-                'f' => unicode_table::FF,
-                'n' => unicode_table::LF,
-                'r' => unicode_table::CR,
-                't' => unicode_table::HT,
-                'v' => unicode_table::VT,
-                x => x,
-            };
-            (l, c)
-        })
+        preceded(
+            tag("\\"),
+            alt((
+                Self::parse_octal,
+                map(anychar, |a| match a {
+                    'a' => unicode_table::BEL,
+                    'b' => unicode_table::BS,
+                    'f' => unicode_table::FF,
+                    'n' => unicode_table::LF,
+                    'r' => unicode_table::CR,
+                    't' => unicode_table::HT,
+                    'v' => unicode_table::VT,
+                    x => x,
+                }),
+            )),
+        )(input)
+    }
+
+    /// Parses the octal value of a byte after a backslash, so `\\400` is `\\40` followed by `0`.
+    fn parse_octal(input: &str) -> IResult<&str, char> {
+        let octal_digits = |n| {
+            map_opt(
+                recognize(many_m_n(n, n, one_of("01234567"))),
+                |out: &str| u8::from_str_radix(out, 8).ok().map(char::from),
+            )
+        };
+        alt((octal_digits(3), octal_digits(2), octal_digits(1)))(input)
     }
 
     fn parse_backslash_or_char(input: &str) -> IResult<&str, char> {
@@ -261,79 +389,77 @@ impl Sequence {
         .map(|(l, (a, b))| {
             (l, {
                 let (start, end) = (u32::from(a), u32::from(b));
-                Ok(Self::CharRange(start, end))
+                if start > end {
+                    Err(BadSequence::RangeEndpointsReversed(a, b))
+                } else {
+                    Ok(Self::CharRange(start, end))
+                }
             })
         })
     }
 
-    fn parse_char_star(input: &str) -> IResult<&str, Result<Self, BadSequence>> {
-        delimited(tag("["), Self::parse_backslash_or_char, tag("*]"))(input)
-            .map(|(l, a)| (l, Ok(Self::CharStar(a))))
-    }
-
-    fn parse_char_repeat(input: &str) -> IResult<&str, Result<Self, BadSequence>> {
+    /// Parses `[c*]` and `[c*n]`, where everything up to the closing bracket is the repeat count,
+    /// unless it has an escaped character.
+    fn parse_char_star_or_repeat(input: &str) -> IResult<&str, Result<Self, BadSequence>> {
         delimited(
             tag("["),
-            separated_pair(Self::parse_backslash_or_char, tag("*"), digit1),
+            separated_pair(
+                Self::parse_backslash_or_char,
+                tag("*"),
+                verify(take_until("]"), |s: &str| !s.contains('\\')),
+            ),
             tag("]"),
         )(input)
         .map(|(l, (c, cnt_str))| {
-            let result = if cnt_str.starts_with('0') {
-                match usize::from_str_radix(cnt_str, 8) {
-                    Ok(0) => Ok(Self::CharStar(c)),
-                    Ok(count) => Ok(Self::CharRepeat(c, count)),
-                    Err(_) => Err(BadSequence::InvalidRepeatCount(cnt_str.to_string())),
-                }
+            let count = if cnt_str.is_empty() {
+                Ok(0)
+            } else if !cnt_str.bytes().all(|b| b.is_ascii_digit()) {
+                Err(())
+            } else if cnt_str.starts_with('0') {
+                usize::from_str_radix(cnt_str, 8).map_err(|_| ())
             } else {
-                match cnt_str.parse::<usize>() {
-                    Ok(0) => Ok(Self::CharStar(c)),
-                    Ok(count) => Ok(Self::CharRepeat(c, count)),
-                    Err(_) => Err(BadSequence::InvalidRepeatCount(cnt_str.to_string())),
-                }
+                cnt_str.parse::<usize>().map_err(|_| ())
+            };
+            let result = match count {
+                Ok(0) => Ok(Self::CharStar(c)),
+                Ok(count) => Ok(Self::CharRepeat(c, count)),
+                Err(()) => Err(BadSequence::InvalidRepeatCount(cnt_str.to_string())),
             };
             (l, result)
         })
     }
 
     fn parse_class(input: &str) -> IResult<&str, Result<Self, BadSequence>> {
-        delimited(
-            tag("[:"),
-            alt((
-                map(
-                    alt((
-                        value(Self::Alnum, tag("alnum")),
-                        value(Self::Alpha, tag("alpha")),
-                        value(Self::Blank, tag("blank")),
-                        value(Self::Control, tag("cntrl")),
-                        value(Self::Digit, tag("digit")),
-                        value(Self::Graph, tag("graph")),
-                        value(Self::Lower, tag("lower")),
-                        value(Self::Print, tag("print")),
-                        value(Self::Punct, tag("punct")),
-                        value(Self::Space, tag("space")),
-                        value(Self::Upper, tag("upper")),
-                        value(Self::Xdigit, tag("xdigit")),
-                    )),
-                    Ok,
-                ),
-                value(Err(BadSequence::MissingCharClassName), tag("")),
-            )),
-            tag(":]"),
-        )(input)
+        delimited(tag("[:"), take_until(":]"), tag(":]"))(input).map(|(l, name)| {
+            let class = match name {
+                "alnum" => Ok(Self::Alnum),
+                "alpha" => Ok(Self::Alpha),
+                "blank" => Ok(Self::Blank),
+                "cntrl" => Ok(Self::Control),
+                "digit" => Ok(Self::Digit),
+                "graph" => Ok(Self::Graph),
+                "lower" => Ok(Self::Lower),
+                "print" => Ok(Self::Print),
+                "punct" => Ok(Self::Punct),
+                "space" => Ok(Self::Space),
+                "upper" => Ok(Self::Upper),
+                "xdigit" => Ok(Self::Xdigit),
+                "" => Err(BadSequence::MissingCharClassName),
+                _ => Err(BadSequence::InvalidCharClass(name.to_string())),
+            };
+            (l, class)
+        })
     }
 
     fn parse_char_equal(input: &str) -> IResult<&str, Result<Self, BadSequence>> {
-        delimited(
-            tag("[="),
-            alt((
-                value(
-                    Err(BadSequence::MissingEquivalentClassChar),
-                    peek(tag("=]")),
-                ),
-                map(Self::parse_backslash_or_char, |c| Ok(Self::Char(c))),
-            )),
-            tag("=]"),
-        )(input)
+        delimited(tag("[="), take_until("=]"), tag("=]"))(input).map(|(l, chars)| {
+            let class = match all_consuming(Self::parse_backslash_or_char)(chars) {
+                Ok((_, c)) => Ok(Self::CharEqual(c)),
+                Err(_) if chars.is_empty() => Err(BadSequence::MissingEquivalentClassChar),
+                Err(_) => Err(BadSequence::MultipleCharEquivalentClass(chars.to_string())),
+            };
+            (l, class)
+        })
     }
 }
 
@@ -531,23 +657,160 @@ impl SymbolTranslator for SqueezeOperation {
     }
 }
 
-pub fn translate_input<T, R, W>(input: &mut R, output: &mut W, mut translator: T)
+/// Applies a translator, then another one to what is left of each character.
+pub struct ChainedSymbolTranslator<A, B> {
+    stage_a: A,
+    stage_b: B,
+}
+
+impl<A: SymbolTranslator, B: SymbolTranslator> ChainedSymbolTranslator<A, B> {
+    pub fn new(stage_a: A, stage_b: B) -> Self {
+        Self { stage_a, stage_b }
+    }
+}
+
+impl<A: SymbolTranslator, B: SymbolTranslator> SymbolTranslator for ChainedSymbolTranslator<A, B> {
+    fn translate(&mut self, current: char) -> Option<char> {
+        self.stage_a
+            .translate(current)
+            .and_then(|c| self.stage_b.translate(c))
+    }
+}
+
+/// Translates the characters of `input` with `translator`, line by line, where any invalid
+/// UTF-8 is replaced by U+FFFD.
+pub fn translate_input<T, R, W>(input: &mut R, output: &mut W, mut translator: T) -> io::Result<()>
 where
     T: SymbolTranslator,
     R: BufRead,
     W: Write,
 {
-    let mut buf = String::new();
+    let mut buf = Vec::new();
     let mut output_buf = String::new();
-    while let Ok(length) = input.read_line(&mut buf) {
-        if length == 0 {
-            break;
-        } else {
-            let filtered = buf.chars().filter_map(|c| translator.translate(c));
-            output_buf.extend(filtered);
-            output.write_all(output_buf.as_bytes()).unwrap();
-        }
+    while input.read_until(b'\n', &mut buf)? > 0 {
+        output_buf.extend(
+            String::from_utf8_lossy(&buf)
+                .chars()
+                .filter_map(|c| translator.translate(c)),
+        );
+        output.write_all(output_buf.as_bytes())?;
         buf.clear();
         output_buf.clear();
     }
+    output.flush()
+}
+
+/// The size of the blocks of input processed at once by a [`ByteOperation`].
+const BLOCK_SIZE: usize = 64 * 1024;
+
+/// Translates, deletes and squeezes the bytes of the input with tables indexed by their values,
+/// which is how GNU tr works.
+///
+/// The bytes in the sets are those of the characters below 256, e.g. from `\377`.
+#[derive(Clone)]
+pub struct ByteOperation {
+    translation: [u8; 256],
+    deleted: [bool; 256],
+    squeezed: [bool; 256],
+}
+
+impl Default for ByteOperation {
+    fn default() -> Self {
+        let mut translation = [0; 256];
+        for (b, t) in translation.iter_mut().enumerate() {
+            *t = b as u8;
+        }
+        Self {
+            translation,
+            deleted: [false; 256],
+            squeezed: [false; 256],
+        }
+    }
+}
+
+impl ByteOperation {
+    /// Translates the bytes of `set1` (or of its complement, in ascending order) into those of
+    /// `set2`, whose last byte is repeated if it is shorter.
+    pub fn translate(set1: &[char], set2: &[char], complement: bool) -> Result<Self, BadSequence> {
+        let set1: Vec<u8> = if complement {
+            let in_set1 = Self::byte_set(set1, false);
+            (0..=255).filter(|&b| !in_set1[b as usize]).collect()
+        } else {
+            set1.iter().filter_map(|&c| Self::byte(c)).collect()
+        };
+        let set2: Vec<u8> = set2.iter().filter_map(|&c| Self::byte(c)).collect();
+        let mut op = Self::default();
+        if let Some(&fallback) = set2.last() {
+            let set2 = set2.iter().copied().chain(std::iter::repeat(fallback));
+            for (b1, b2) in set1.into_iter().zip(set2) {
+                op.translation[b1 as usize] = b2;
+            }
+        } else if !set1.is_empty() {
+            return Err(BadSequence::EmptySet2WhenNotTruncatingSet1);
+        }
+        Ok(op)
+    }
+
+    /// Also deletes the bytes of `set`, or those not in it if `complement`.
+    pub fn delete(mut self, set: &[char], complement: bool) -> Self {
+        self.deleted = Self::byte_set(set, complement);
+        self
+    }
+
+    /// Also squeezes the repeats of the bytes of `set`, or of those not in it if `complement`,
+    /// after translating and deleting.
+    pub fn squeeze(mut self, set: &[char], complement: bool) -> Self {
+        self.squeezed = Self::byte_set(set, complement);
+        self
+    }
+
+    fn byte(c: char) -> Option<u8> {
+        u8::try_from(u32::from(c)).ok()
+    }
+
+    fn byte_set(set: &[char], complement: bool) -> [bool; 256] {
+        let mut byte_set = [complement; 256];
+        for b in set.iter().filter_map(|&c| Self::byte(c)) {
+            byte_set[b as usize] = !complement;
+        }
+        byte_set
+    }
+
+    /// Writes `input` to `output` after applying the operation.
+    pub fn run<R: Read, W: Write>(&self, input: &mut R, output: &mut W) -> io::Result<()> {
+        let translate_only = !self.deleted.contains(&true) && !self.squeezed.contains(&true);
+        let mut buf = vec![0; BLOCK_SIZE];
+        let mut output_buf = Vec::with_capacity(BLOCK_SIZE);
+        // the last byte written, for squeezing repeats across blocks
+        let mut last = None;
+        loop {
+            let n = match input.read(&mut buf) {
+                Ok(0) => break,
+                Ok(n) => n,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e),
+            };
+            if translate_only {
+                for b in &mut buf[..n] {
+                    *b = self.translation[*b as usize];
+                }
+                output.write_all(&buf[..n])?;
+                continue;
+            }
+            output_buf.clear();
+            for &b in &buf[..n] {
+                if self.deleted[b as usize] {
+                    continue;
+                }
+                let b = self.translation[b as usize];
+                if self.squeezed[b as usize] && last == Some(b) {
+                    continue;
+                }
+                output_buf.push(b);
+                last = Some(b);
+            }
+            output.write_all(&output_buf)?;
+        }
+        output.flush()
+    }
 }
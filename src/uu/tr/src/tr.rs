@@ -5,14 +5,16 @@
 
 // spell-checker:ignore (ToDO) allocs bset dflag cflag sflag tflag
 
-mod convert;
 mod operation;
 mod unicode_table;
 
-use clap::{crate_version, Arg, ArgAction, Command};
-use nom::AsBytes;
-use operation::{translate_input, Sequence, SqueezeOperation, TranslateOperation};
-use std::io::{stdin, stdout, BufReader, BufWriter};
+use clap::{builder::ValueParser, crate_version, Arg, ArgAction, Command};
+use operation::{
+    translate_input, ByteOperation, ChainedSymbolTranslator, Sequence, SqueezeOperation,
+    TranslateOperation,
+};
+use std::ffi::{OsStr, OsString};
+use std::io::{stdin, stdout, BufWriter};
 use uucore::{format_usage, show};
 
 use crate::operation::DeleteOperation;
@@ -27,6 +29,7 @@ mod options {
     pub const DELETE: &str = "delete";
     pub const SQUEEZE: &str = "squeeze-repeats";
     pub const TRUNCATE_SET1: &str = "truncate-set1";
+    pub const UTF8: &str = "utf8";
     pub const SETS: &str = "sets";
 }
 
@@ -38,8 +41,6 @@ fn get_long_usage() -> String {
 
 #[uucore::main]
 pub fn uumain(args: impl uucore::Args) -> UResult<()> {
-    let args = args.collect_lossy();
-
     let matches = uu_app()
         .after_help(get_long_usage())
         .try_get_matches_from(args)?;
@@ -48,12 +49,12 @@ pub fn uumain(args: impl uucore::Args) -> UResult<()> {
     let complement_flag = matches.get_flag(options::COMPLEMENT);
     let squeeze_flag = matches.get_flag(options::SQUEEZE);
     let truncate_set1_flag = matches.get_flag(options::TRUNCATE_SET1);
+    let utf8_flag = matches.get_flag(options::UTF8);
 
     let sets = matches
-        .get_many::<String>(options::SETS)
+        .get_many::<OsString>(options::SETS)
         .map(|v| {
-            v.map(ToString::to_string)
-                .map(|input| convert::reduce_octal_to_char(&input))
+            v.map(|input| set_chars(input, utf8_flag))
                 .collect::<Vec<_>>()
         })
         .unwrap_or_default();
@@ -66,12 +67,36 @@ pub fn uumain(args: impl uucore::Args) -> UResult<()> {
     if !(delete_flag || squeeze_flag) && sets_len < 2 {
         return Err(UUsageError::new(
             1,
-            format!("missing operand after {}", sets[0].quote()),
+            format!(
+                "missing operand after {}\nTwo strings must be given when translating.",
+                sets[0].quote()
+            ),
+        ));
+    }
+
+    if delete_flag && squeeze_flag && sets_len < 2 {
+        return Err(UUsageError::new(
+            1,
+            format!(
+                "missing operand after {}\nTwo strings must be given when both deleting and squeezing repeats.",
+                sets[0].quote()
+            ),
+        ));
+    }
+
+    if delete_flag && !squeeze_flag && sets_len > 1 {
+        return Err(UUsageError::new(
+            1,
+            format!(
+                "extra operand {}\nOnly one string may be given when deleting without squeezing repeats.",
+                sets[1].quote()
+            ),
         ));
     }
 
     if let Some(first) = sets.get(0) {
-        if first.ends_with('\\') {
+        // an even number of backslashes are escaped ones
+        if first.chars().rev().take_while(|c| *c == '\\').count() % 2 == 1 {
             show!(USimpleError::new(
                 0,
                 "warning: an unescaped backslash at end of string is not portable"
@@ -79,60 +104,84 @@ pub fn uumain(args: impl uucore::Args) -> UResult<()> {
         }
     }
 
-    let stdin = stdin();
-    let mut locked_stdin = stdin.lock();
-    let stdout = stdout();
-    let locked_stdout = stdout.lock();
-    let mut buffered_stdout = BufWriter::new(locked_stdout);
-
     let mut sets_iter = sets.iter().map(|c| c.as_str());
     let (set1, set2) = Sequence::solve_set_characters(
         sets_iter.next().unwrap_or_default(),
         sets_iter.next().unwrap_or_default(),
         truncate_set1_flag,
+        !delete_flag && sets_len > 1,
+        complement_flag,
     )?;
 
-    if delete_flag {
-        if squeeze_flag {
-            let mut delete_buffer = vec![];
-            {
-                let mut delete_writer = BufWriter::new(&mut delete_buffer);
-                let delete_op = DeleteOperation::new(set1, complement_flag);
-                translate_input(&mut locked_stdin, &mut delete_writer, delete_op);
+    let stdin = stdin();
+    let mut locked_stdin = stdin.lock();
+    let stdout = stdout();
+    let mut locked_stdout = stdout.lock();
+
+    if !utf8_flag {
+        let op = if delete_flag {
+            let op = ByteOperation::default().delete(&set1, complement_flag);
+            if squeeze_flag {
+                op.squeeze(&set2, false)
+            } else {
+                op
             }
-            {
-                let mut squeeze_reader = BufReader::new(delete_buffer.as_bytes());
-                let op = SqueezeOperation::new(set2, complement_flag);
-                translate_input(&mut squeeze_reader, &mut buffered_stdout, op);
+        } else if squeeze_flag && sets_len < 2 {
+            ByteOperation::default().squeeze(&set1, complement_flag)
+        } else {
+            let op = ByteOperation::translate(&set1, &set2, complement_flag)?;
+            if squeeze_flag {
+                op.squeeze(&set2, false)
+            } else {
+                op
             }
+        };
+        op.run(&mut locked_stdin, &mut locked_stdout)?;
+        return Ok(());
+    }
+
+    let mut buffered_stdout = BufWriter::new(locked_stdout);
+    if delete_flag {
+        let delete_op = DeleteOperation::new(set1, complement_flag);
+        if squeeze_flag {
+            let squeeze_op = SqueezeOperation::new(set2, false);
+            let op = ChainedSymbolTranslator::new(delete_op, squeeze_op);
+            translate_input(&mut locked_stdin, &mut buffered_stdout, op)?;
         } else {
-            let op = DeleteOperation::new(set1, complement_flag);
-            translate_input(&mut locked_stdin, &mut buffered_stdout, op);
+            translate_input(&mut locked_stdin, &mut buffered_stdout, delete_op)?;
         }
     } else if squeeze_flag {
         if sets_len < 2 {
             let op = SqueezeOperation::new(set1, complement_flag);
-            translate_input(&mut locked_stdin, &mut buffered_stdout, op);
+            translate_input(&mut locked_stdin, &mut buffered_stdout, op)?;
         } else {
-            let mut translate_buffer = vec![];
-            {
-                let mut writer = BufWriter::new(&mut translate_buffer);
-                let op = TranslateOperation::new(set1, set2.clone(), complement_flag)?;
-                translate_input(&mut locked_stdin, &mut writer, op);
-            }
-            {
-                let mut reader = BufReader::new(translate_buffer.as_bytes());
-                let squeeze_op = SqueezeOperation::new(set2, false);
-                translate_input(&mut reader, &mut buffered_stdout, squeeze_op);
-            }
+            let translate_op = TranslateOperation::new(set1, set2.clone(), complement_flag)?;
+            let squeeze_op = SqueezeOperation::new(set2, false);
+            let op = ChainedSymbolTranslator::new(translate_op, squeeze_op);
+            translate_input(&mut locked_stdin, &mut buffered_stdout, op)?;
         }
     } else {
         let op = TranslateOperation::new(set1, set2, complement_flag)?;
-        translate_input(&mut locked_stdin, &mut buffered_stdout, op);
+        translate_input(&mut locked_stdin, &mut buffered_stdout, op)?;
     }
     Ok(())
 }
 
+/// Returns the characters of a set given on the command line: those of its text with `utf8`,
+/// or else one for each of its bytes, like those of the octal escapes.
+fn set_chars(set: &OsStr, utf8: bool) -> String {
+    if utf8 {
+        return set.to_string_lossy().into_owned();
+    }
+    #[cfg(unix)]
+    let bytes = std::os::unix::ffi::OsStrExt::as_bytes(set);
+    #[cfg(not(unix))]
+    let lossy = set.to_string_lossy();
+    #[cfg(not(unix))]
+    let bytes = lossy.as_bytes();
+    bytes.iter().map(|&b| char::from(b)).collect()
+}
+
 pub fn uu_app() -> Command {
     Command::new(uucore::util_name())
         .version(crate_version!())
@@ -172,5 +221,18 @@ pub fn uu_app() -> Command {
                 .help("first truncate SET1 to length of SET2")
                 .action(ArgAction::SetTrue),
         )
-        .arg(Arg::new(options::SETS).num_args(1..=2))
+        .arg(
+            Arg::new(options::UTF8)
+                .long(options::UTF8)
+                .help(
+                    "translate the characters of UTF-8 text instead of bytes, \
+                     where the input that is not valid UTF-8 is replaced (an extension)",
+                )
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new(options::SETS)
+                .num_args(1..=2)
+                .value_parser(ValueParser::os_string()),
+        )
 }
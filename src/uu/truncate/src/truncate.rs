@@ -14,8 +14,11 @@ use std::os::unix::fs::FileTypeExt;
 use std::path::Path;
 use uucore::display::Quotable;
 use uucore::error::{FromIo, UResult, USimpleError, UUsageError};
-use uucore::format_usage;
 use uucore::parse_size::{parse_size, ParseSizeError};
+use uucore::{format_usage, show};
+
+/// The largest size a file can have, i.e. the maximum of `off_t`.
+const MAX_SIZE: u64 = i64::MAX as u64;
 
 #[derive(Debug, Eq, PartialEq)]
 enum TruncateMode {
@@ -37,6 +40,9 @@ impl TruncateMode {
     /// reduce by is greater than `fsize`, then this function returns
     /// 0 (since it cannot return a negative number).
     ///
+    /// Returns `None` if extending or rounding up would make the file
+    /// larger than [`MAX_SIZE`].
+    ///
     /// # Examples
     ///
     /// Extending a file of 10 bytes by 5 bytes:
@@ -44,7 +50,7 @@ impl TruncateMode {
     /// ```rust,ignore
     /// let mode = TruncateMode::Extend(5);
     /// let fsize = 10;
-    /// assert_eq!(mode.to_size(fsize), 15);
+    /// assert_eq!(mode.to_size(fsize), Some(15));
     /// ```
     ///
     /// Reducing a file by more than its size results in 0:
@@ -52,23 +58,21 @@ impl TruncateMode {
     /// ```rust,ignore
     /// let mode = TruncateMode::Reduce(5);
     /// let fsize = 3;
-    /// assert_eq!(mode.to_size(fsize), 0);
+    /// assert_eq!(mode.to_size(fsize), Some(0));
     /// ```
-    fn to_size(&self, fsize: u64) -> u64 {
+    fn to_size(&self, fsize: u64) -> Option<u64> {
+        let extend_by = |size: u64| fsize.checked_add(size).filter(|s| *s <= MAX_SIZE);
         match self {
-            Self::Absolute(size) => *size,
-            Self::Extend(size) => fsize + size,
-            Self::Reduce(size) => {
-                if *size > fsize {
-                    0
-                } else {
-                    fsize - size
-                }
-            }
-            Self::AtMost(size) => fsize.min(*size),
-            Self::AtLeast(size) => fsize.max(*size),
-            Self::RoundDown(size) => fsize - fsize % size,
-            Self::RoundUp(size) => fsize + fsize % size,
+            Self::Absolute(size) => Some(*size),
+            Self::Extend(size) => extend_by(*size),
+            Self::Reduce(size) => Some(fsize.saturating_sub(*size)),
+            Self::AtMost(size) => Some(fsize.min(*size)),
+            Self::AtLeast(size) => Some(fsize.max(*size)),
+            Self::RoundDown(size) => Some(fsize - fsize % size),
+            Self::RoundUp(size) => match fsize % size {
+                0 => Some(fsize),
+                r => extend_by(size - r),
+            },
         }
     }
 }
@@ -174,6 +178,7 @@ pub fn uu_app() -> Command {
                 .short('s')
                 .long(options::SIZE)
                 .required_unless_present(options::REFERENCE)
+                .allow_hyphen_values(true)
                 .help(
                     "set or adjust the size of each file according to SIZE, which is in \
             bytes unless --io-blocks is specified",
@@ -189,75 +194,33 @@ pub fn uu_app() -> Command {
         )
 }
 
-/// Truncate the named file to the specified size.
+/// Truncate the named file according to the reference size and the mode.
 ///
 /// If `create` is true, then the file will be created if it does not
-/// already exist. If `size` is larger than the number of bytes in the
-/// file, then the file will be padded with zeros. If `size` is smaller
-/// than the number of bytes in the file, then the file will be
-/// truncated and any bytes beyond `size` will be lost.
-///
-/// # Errors
-///
-/// If the file could not be opened, or there was a problem setting the
-/// size of the file.
-fn file_truncate(filename: &str, create: bool, size: u64) -> std::io::Result<()> {
-    let path = Path::new(filename);
-    let f = OpenOptions::new().write(true).create(create).open(path)?;
-    f.set_len(size)
-}
-
-/// Truncate files to a size relative to a given file.
-///
-/// `rfilename` is the name of the reference file.
-///
-/// `size_string` gives the size relative to the reference file to which
-/// to set the target files. For example, "+3K" means "set each file to
-/// be three kilobytes larger than the size of the reference file".
+/// already exist. A missing file is silently skipped otherwise.
 ///
-/// If `create` is true, then each file will be created if it does not
-/// already exist.
+/// The target size is `reference_size` adjusted by `mode`; a relative
+/// `mode` applies to the size of the file itself when there is no
+/// reference. If the target size is larger than the number of bytes in
+/// the file, then the file will be padded with zeros, and if it is
+/// smaller, then any bytes beyond it will be lost.
 ///
 /// # Errors
 ///
-/// If any file could not be opened, or there was a problem setting
-/// the size of at least one file.
-///
-/// If at least one file is a named pipe (also known as a fifo).
-fn truncate_reference_and_size(
-    rfilename: &str,
-    size_string: &str,
-    filenames: &[String],
+/// If the file could not be opened, if it is a named pipe (also known
+/// as a fifo), if the target size overflows, or if there was a problem
+/// setting the size of the file.
+fn truncate_file(
+    filename: &str,
     create: bool,
+    reference_size: Option<u64>,
+    mode: Option<&TruncateMode>,
 ) -> UResult<()> {
-    let mode = match parse_mode_and_size(size_string) {
-        Err(e) => return Err(USimpleError::new(1, format!("Invalid number: {e}"))),
-        Ok(TruncateMode::Absolute(_)) => {
-            return Err(USimpleError::new(
-                1,
-                String::from("you must specify a relative '--size' with '--reference'"),
-            ))
-        }
-        Ok(m) => m,
-    };
-    if let TruncateMode::RoundDown(0) | TruncateMode::RoundUp(0) = mode {
-        return Err(USimpleError::new(1, "division by zero"));
-    }
-    let metadata = metadata(rfilename).map_err(|e| match e.kind() {
-        ErrorKind::NotFound => USimpleError::new(
-            1,
-            format!(
-                "cannot stat {}: No such file or directory",
-                rfilename.quote()
-            ),
-        ),
-        _ => e.map_err_context(String::new),
-    })?;
-    let fsize = metadata.len();
-    let tsize = mode.to_size(fsize);
-    for filename in filenames {
-        #[cfg(unix)]
-        if std::fs::metadata(filename)?.file_type().is_fifo() {
+    let path = Path::new(filename);
+    // Opening a fifo for writing would block until it has a reader.
+    #[cfg(unix)]
+    if let Ok(m) = metadata(path) {
+        if m.file_type().is_fifo() {
             return Err(USimpleError::new(
                 1,
                 format!(
@@ -266,113 +229,58 @@ fn truncate_reference_and_size(
                 ),
             ));
         }
-
-        file_truncate(filename, create, tsize)
-            .map_err_context(|| format!("cannot open {} for writing", filename.quote()))?;
     }
-    Ok(())
-}
+    let f = match OpenOptions::new().write(true).create(create).open(path) {
+        Ok(f) => f,
+        Err(e) if e.kind() == ErrorKind::NotFound && !create => return Ok(()),
+        Err(e) => {
+            return Err(
+                e.map_err_context(|| format!("cannot open {} for writing", filename.quote()))
+            )
+        }
+    };
 
-/// Truncate files to match the size of a given reference file.
-///
-/// `rfilename` is the name of the reference file.
-///
-/// If `create` is true, then each file will be created if it does not
-/// already exist.
-///
-/// # Errors
-///
-/// If any file could not be opened, or there was a problem setting
-/// the size of at least one file.
-///
-/// If at least one file is a named pipe (also known as a fifo).
-fn truncate_reference_file_only(
-    rfilename: &str,
-    filenames: &[String],
-    create: bool,
-) -> UResult<()> {
-    let metadata = metadata(rfilename).map_err(|e| match e.kind() {
-        ErrorKind::NotFound => USimpleError::new(
-            1,
-            format!(
-                "cannot stat {}: No such file or directory",
-                rfilename.quote()
-            ),
-        ),
-        _ => e.map_err_context(String::new),
-    })?;
-    let tsize = metadata.len();
-    for filename in filenames {
-        #[cfg(unix)]
-        if std::fs::metadata(filename)?.file_type().is_fifo() {
-            return Err(USimpleError::new(
-                1,
-                format!(
-                    "cannot open {} for writing: No such device or address",
-                    filename.quote()
-                ),
-            ));
+    let tsize = match (reference_size, mode) {
+        (Some(rsize), None) => rsize,
+        (_, Some(TruncateMode::Absolute(size))) => *size,
+        (_, Some(mode)) => {
+            let fsize = match reference_size {
+                Some(rsize) => rsize,
+                None => f
+                    .metadata()
+                    .map_err_context(|| format!("cannot fstat {}", filename.quote()))?
+                    .len(),
+            };
+            mode.to_size(fsize).ok_or_else(|| {
+                USimpleError::new(
+                    1,
+                    format!("overflow extending size of file {}", filename.quote()),
+                )
+            })?
         }
-        file_truncate(filename, create, tsize)
-            .map_err_context(|| format!("cannot open {} for writing", filename.quote()))?;
-    }
-    Ok(())
+        (None, None) => unreachable!(), // clap requires either a size or a reference
+    };
+    f.set_len(tsize)
+        .map_err_context(|| format!("failed to truncate {} at {} bytes", filename.quote(), tsize))
 }
 
-/// Truncate files to a specified size.
+/// Truncate files to a size given by a reference file and/or a size string.
 ///
 /// `size_string` gives either an absolute size or a relative size. A
-/// relative size adjusts the size of each file relative to its current
-/// size. For example, "3K" means "set each file to be three kilobytes"
-/// whereas "+3K" means "set each file to be three kilobytes larger than
-/// its current size".
+/// relative size adjusts the size of each file relative to the size of
+/// `reference`, or to its current size if there is no reference file.
+/// For example, "3K" means "set each file to be three kilobytes" whereas
+/// "+3K" means "set each file to be three kilobytes larger". An absolute
+/// size cannot be combined with a reference file.
 ///
 /// If `create` is true, then each file will be created if it does not
 /// already exist.
 ///
 /// # Errors
 ///
-/// If any file could not be opened, or there was a problem setting
-/// the size of at least one file.
-///
-/// If at least one file is a named pipe (also known as a fifo).
-fn truncate_size_only(size_string: &str, filenames: &[String], create: bool) -> UResult<()> {
-    let mode = parse_mode_and_size(size_string)
-        .map_err(|e| USimpleError::new(1, format!("Invalid number: {e}")))?;
-    if let TruncateMode::RoundDown(0) | TruncateMode::RoundUp(0) = mode {
-        return Err(USimpleError::new(1, "division by zero"));
-    }
-    for filename in filenames {
-        let fsize = match metadata(filename) {
-            Ok(m) => {
-                #[cfg(unix)]
-                if m.file_type().is_fifo() {
-                    return Err(USimpleError::new(
-                        1,
-                        format!(
-                            "cannot open {} for writing: No such device or address",
-                            filename.quote()
-                        ),
-                    ));
-                }
-                m.len()
-            }
-            Err(_) => 0,
-        };
-        let tsize = mode.to_size(fsize);
-        match file_truncate(filename, create, tsize) {
-            Ok(_) => continue,
-            Err(e) if e.kind() == ErrorKind::NotFound && !create => continue,
-            Err(e) => {
-                return Err(
-                    e.map_err_context(|| format!("cannot open {} for writing", filename.quote()))
-                )
-            }
-        }
-    }
-    Ok(())
-}
-
+/// If the size string is invalid, or the reference file cannot be
+/// stat'ed. Errors on the files themselves are reported as they happen,
+/// and the remaining files are still truncated.
 fn truncate(
     no_create: bool,
     _: bool,
@@ -381,19 +289,28 @@ fn truncate(
     filenames: &[String],
 ) -> UResult<()> {
     let create = !no_create;
-    // There are four possibilities
-    // - reference file given and size given,
-    // - reference file given but no size given,
-    // - no reference file given but size given,
-    // - no reference file given and no size given,
-    match (reference, size) {
-        (Some(rfilename), Some(size_string)) => {
-            truncate_reference_and_size(&rfilename, &size_string, filenames, create)
+    let mode = size.as_deref().map(parse_mode_and_size).transpose()?;
+    let reference_size = match reference {
+        Some(rfilename) => {
+            if let Some(TruncateMode::Absolute(_)) = mode {
+                return Err(UUsageError::new(
+                    1,
+                    "you must specify a relative '--size' with '--reference'",
+                ));
+            }
+            let metadata = metadata(&rfilename)
+                .map_err_context(|| format!("cannot stat {}", rfilename.quote()))?;
+            Some(metadata.len())
+        }
+        None => None,
+    };
+
+    for filename in filenames {
+        if let Err(e) = truncate_file(filename, create, reference_size, mode.as_ref()) {
+            show!(e);
         }
-        (Some(rfilename), None) => truncate_reference_file_only(&rfilename, filenames, create),
-        (None, Some(size_string)) => truncate_size_only(&size_string, filenames, create),
-        (None, None) => unreachable!(), // this case cannot happen anymore because it's handled by clap
     }
+    Ok(())
 }
 
 /// Decide whether a character is one of the size modifiers, like '+' or '<'.
@@ -401,68 +318,129 @@ fn is_modifier(c: char) -> bool {
     c == '+' || c == '-' || c == '<' || c == '>' || c == '/' || c == '%'
 }
 
-/// Parse a size string with optional modifier symbol as its first character.
+/// Parse a size string with an optional modifier symbol in front of it.
 ///
-/// A size string is as described in [`parse_size`]. The first character
-/// of `size_string` might be a modifier symbol, like `'+'` or
-/// `'<'`. The first element of the pair returned by this function
-/// indicates which modifier symbol was present, or
-/// [`TruncateMode::Absolute`] if none.
+/// A size string is as described in [`parse_size`], optionally preceded
+/// by whitespace and a modifier symbol like `'<'` or `'/'`, and then by
+/// whitespace and a sign. A `'+'` or `'-'` sign is the modifier itself,
+/// so it cannot follow another modifier. The returned mode indicates which
+/// modifier symbol was present, or is [`TruncateMode::Absolute`] if none.
 ///
-/// # Panics
+/// # Errors
 ///
-/// If `size_string` is empty, or if no number could be parsed from the
-/// given string (for example, if the string were `"abc"`).
+/// If the number could not be parsed (for example, if the string were
+/// `"abc"`), if it does not fit in a file size, if there is more than
+/// one modifier, or if rounding to a multiple of zero is requested.
 ///
 /// # Examples
 ///
 /// ```rust,ignore
-/// assert_eq!(parse_mode_and_size("+123"), (TruncateMode::Extend, 123));
+/// assert_eq!(parse_mode_and_size("+123").ok(), Some(TruncateMode::Extend(123)));
 /// ```
-fn parse_mode_and_size(size_string: &str) -> Result<TruncateMode, ParseSizeError> {
-    // Trim any whitespace.
-    let mut size_string = size_string.trim();
+fn parse_mode_and_size(size_string: &str) -> UResult<TruncateMode> {
+    let mut size_string = size_string.trim_start();
 
     // Get the modifier character from the size string, if any. For
-    // example, if the argument is "+123", then the modifier is '+'.
+    // example, if the argument is "<123", then the modifier is '<'.
+    let mut modifier = None;
     if let Some(c) = size_string.chars().next() {
-        if is_modifier(c) {
-            size_string = &size_string[1..];
+        if is_modifier(c) && c != '+' && c != '-' {
+            modifier = Some(c);
+            size_string = size_string[1..].trim_start();
         }
-        parse_size(size_string).map(match c {
-            '+' => TruncateMode::Extend,
-            '-' => TruncateMode::Reduce,
-            '<' => TruncateMode::AtMost,
-            '>' => TruncateMode::AtLeast,
-            '/' => TruncateMode::RoundDown,
-            '%' => TruncateMode::RoundUp,
-            _ => TruncateMode::Absolute,
-        })
+    }
+    let number = size_string;
+    if let Some(sign) = number.chars().next().filter(|c| *c == '+' || *c == '-') {
+        if modifier.is_some() {
+            return Err(UUsageError::new(1, "multiple relative modifiers specified"));
+        }
+        modifier = Some(sign);
+        size_string = &size_string[1..];
+    }
+
+    let invalid_number = |e: ParseSizeError| {
+        let reason = match e {
+            ParseSizeError::SizeTooBig(_) => ": Value too large for defined data type",
+            _ => "",
+        };
+        USimpleError::new(1, format!("Invalid number: {}{}", number.quote(), reason))
+    };
+    if size_string.starts_with(|c: char| c.is_whitespace() || c == '+' || c == '-') {
+        return Err(invalid_number(ParseSizeError::ParseFailure(String::new())));
+    }
+    // The amount a file can be reduced by is the magnitude of the minimum of `off_t`.
+    let max = if modifier == Some('-') {
+        MAX_SIZE + 1
     } else {
-        Err(ParseSizeError::ParseFailure(size_string.to_string()))
+        MAX_SIZE
+    };
+    let size = parse_size(size_string)
+        .and_then(|size| {
+            if size > max {
+                Err(ParseSizeError::SizeTooBig(String::new()))
+            } else {
+                Ok(size)
+            }
+        })
+        .map_err(invalid_number)?;
+
+    let mode = match modifier {
+        Some('+') => TruncateMode::Extend(size),
+        Some('-') => TruncateMode::Reduce(size),
+        Some('<') => TruncateMode::AtMost(size),
+        Some('>') => TruncateMode::AtLeast(size),
+        Some('/') => TruncateMode::RoundDown(size),
+        Some('%') => TruncateMode::RoundUp(size),
+        _ => TruncateMode::Absolute(size),
+    };
+    if let TruncateMode::RoundDown(0) | TruncateMode::RoundUp(0) = mode {
+        return Err(USimpleError::new(1, "division by zero"));
     }
+    Ok(mode)
 }
 
 #[cfg(test)]
 mod tests {
     use crate::parse_mode_and_size;
     use crate::TruncateMode;
+    use crate::MAX_SIZE;
 
     #[test]
     fn test_parse_mode_and_size() {
-        assert_eq!(parse_mode_and_size("10"), Ok(TruncateMode::Absolute(10)));
-        assert_eq!(parse_mode_and_size("+10"), Ok(TruncateMode::Extend(10)));
-        assert_eq!(parse_mode_and_size("-10"), Ok(TruncateMode::Reduce(10)));
-        assert_eq!(parse_mode_and_size("<10"), Ok(TruncateMode::AtMost(10)));
-        assert_eq!(parse_mode_and_size(">10"), Ok(TruncateMode::AtLeast(10)));
-        assert_eq!(parse_mode_and_size("/10"), Ok(TruncateMode::RoundDown(10)));
-        assert_eq!(parse_mode_and_size("%10"), Ok(TruncateMode::RoundUp(10)));
+        let parse = |s| parse_mode_and_size(s).ok();
+        assert_eq!(parse("10"), Some(TruncateMode::Absolute(10)));
+        assert_eq!(parse("+10"), Some(TruncateMode::Extend(10)));
+        assert_eq!(parse("-10"), Some(TruncateMode::Reduce(10)));
+        assert_eq!(parse("<10"), Some(TruncateMode::AtMost(10)));
+        assert_eq!(parse(">10"), Some(TruncateMode::AtLeast(10)));
+        assert_eq!(parse("/10"), Some(TruncateMode::RoundDown(10)));
+        assert_eq!(parse("%10"), Some(TruncateMode::RoundUp(10)));
+        assert_eq!(parse(" < 10"), Some(TruncateMode::AtMost(10)));
+        assert_eq!(
+            parse("9223372036854775807"),
+            Some(TruncateMode::Absolute(MAX_SIZE))
+        );
+        assert_eq!(
+            parse("-9223372036854775808"),
+            Some(TruncateMode::Reduce(MAX_SIZE + 1))
+        );
+        assert_eq!(parse("9223372036854775808"), None);
+        assert_eq!(parse("<+10"), None);
+        assert_eq!(parse("+-10"), None);
+        assert_eq!(parse("+ 10"), None);
+        assert_eq!(parse("%0"), None);
     }
 
     #[test]
     fn test_to_size() {
-        assert_eq!(TruncateMode::Extend(5).to_size(10), 15);
-        assert_eq!(TruncateMode::Reduce(5).to_size(10), 5);
-        assert_eq!(TruncateMode::Reduce(5).to_size(3), 0);
+        assert_eq!(TruncateMode::Extend(5).to_size(10), Some(15));
+        assert_eq!(TruncateMode::Extend(MAX_SIZE).to_size(1), None);
+        assert_eq!(TruncateMode::Reduce(5).to_size(10), Some(5));
+        assert_eq!(TruncateMode::Reduce(5).to_size(3), Some(0));
+        assert_eq!(TruncateMode::RoundDown(4).to_size(10), Some(8));
+        assert_eq!(TruncateMode::RoundUp(4).to_size(10), Some(12));
+        assert_eq!(TruncateMode::RoundUp(4).to_size(12), Some(12));
+        assert_eq!(TruncateMode::RoundUp(MAX_SIZE).to_size(1), Some(MAX_SIZE));
+        assert_eq!(TruncateMode::RoundUp(MAX_SIZE - 1).to_size(MAX_SIZE), None);
     }
 }
@@ -10,7 +10,7 @@
 
 use clap::{crate_version, Arg, ArgAction, ArgMatches, Command};
 use std::fs::File;
-use std::io::{self, stdin, stdout, BufRead, BufReader, BufWriter, Read, Write};
+use std::io::{stdin, stdout, BufRead, BufReader, BufWriter, Read, Write};
 use std::path::Path;
 use std::str::FromStr;
 use strum_macros::{AsRefStr, EnumString};
@@ -22,6 +22,7 @@ static ABOUT: &str = "Report or omit repeated lines.";
 const USAGE: &str = "{} [OPTION]... [INPUT [OUTPUT]]...";
 pub mod options {
     pub static ALL_REPEATED: &str = "all-repeated";
+    pub static CHARACTERS: &str = "characters";
     pub static CHECK_CHARS: &str = "check-chars";
     pub static COUNT: &str = "count";
     pub static IGNORE_CASE: &str = "ignore-case";
@@ -55,6 +56,7 @@ struct Uniq {
     slice_start: Option<usize>,
     slice_stop: Option<usize>,
     ignore_case: bool,
+    characters: bool,
     zero_terminated: bool,
 }
 
@@ -75,7 +77,9 @@ impl Uniq {
         let mut first_line_printed = false;
         let mut group_count = 1;
         let line_terminator = self.get_line_terminator();
-        let mut lines = reader.split(line_terminator).map(get_line_string);
+        let mut lines = reader
+            .split(line_terminator)
+            .map(|line| line.map_err_context(|| "failed to split lines".to_string()));
         let mut line = match lines.next() {
             Some(l) => l?,
             None => return Ok(()),
@@ -115,23 +119,43 @@ impl Uniq {
         Ok(())
     }
 
-    fn skip_fields<'a>(&self, line: &'a str) -> &'a str {
-        if let Some(skip_fields) = self.skip_fields {
-            let mut i = 0;
-            let mut char_indices = line.char_indices();
-            for _ in 0..skip_fields {
-                if char_indices.all(|(_, c)| c.is_whitespace()) {
-                    return "";
-                }
-                match char_indices.find(|(_, c)| c.is_whitespace()) {
-                    None => return "",
-
-                    Some((next_field_i, _)) => i = next_field_i,
-                }
+    /// Returns the part of `line` after the first `skip_fields` fields. Fields are separated by
+    /// ASCII whitespace.
+    fn skip_fields<'a>(&self, line: &'a [u8]) -> &'a [u8] {
+        let mut i = 0;
+        for _ in 0..self.skip_fields.unwrap_or(0) {
+            while i < line.len() && line[i].is_ascii_whitespace() {
+                i += 1;
             }
-            &line[i..]
-        } else {
-            line
+            while i < line.len() && !line[i].is_ascii_whitespace() {
+                i += 1;
+            }
+        }
+        &line[i..]
+    }
+
+    /// Returns the byte offset of the `n`th byte, or the `n`th character with `--characters`,
+    /// in `s`. A character is a byte that doesn't continue a UTF-8 sequence, together with the
+    /// continuation bytes following it, so invalid UTF-8 counts one character per byte.
+    fn offset_of(&self, s: &[u8], n: usize) -> usize {
+        if !self.characters {
+            return n.min(s.len());
+        }
+        s.iter()
+            .enumerate()
+            .filter(|(_, &b)| b & 0xc0 != 0x80)
+            .nth(n)
+            .map_or(s.len(), |(i, _)| i)
+    }
+
+    /// Returns the part of `line` that is compared.
+    fn get_key<'a>(&self, line: &'a [u8]) -> &'a [u8] {
+        let fields_to_check = self.skip_fields(line);
+        let key =
+            &fields_to_check[self.offset_of(fields_to_check, self.slice_start.unwrap_or(0))..];
+        match self.slice_stop {
+            Some(slice_stop) => &key[..self.offset_of(key, slice_stop)],
+            None => key,
         }
     }
 
@@ -143,46 +167,23 @@ impl Uniq {
         }
     }
 
-    fn cmp_keys(&self, first: &str, second: &str) -> bool {
-        self.cmp_key(first, |first_iter| {
-            self.cmp_key(second, |second_iter| first_iter.ne(second_iter))
-        })
-    }
-
-    fn cmp_key<F>(&self, line: &str, mut closure: F) -> bool
-    where
-        F: FnMut(&mut dyn Iterator<Item = char>) -> bool,
-    {
-        let fields_to_check = self.skip_fields(line);
-        let len = fields_to_check.len();
-        let slice_start = self.slice_start.unwrap_or(0);
-        let slice_stop = self.slice_stop.unwrap_or(len);
-        if len > 0 {
-            // fast path: avoid doing any work if there is no need to skip or map to lower-case
-            if !self.ignore_case && slice_start == 0 && slice_stop == len {
-                return closure(&mut fields_to_check.chars());
-            }
-
-            // fast path: avoid skipping
-            if self.ignore_case && slice_start == 0 && slice_stop == len {
-                return closure(&mut fields_to_check.chars().flat_map(|c| c.to_uppercase()));
-            }
-
-            // fast path: we can avoid mapping chars to upper-case, if we don't want to ignore the case
-            if !self.ignore_case {
-                return closure(&mut fields_to_check.chars().skip(slice_start).take(slice_stop));
-            }
-
-            closure(
-                &mut fields_to_check
+    /// Returns whether the keys of `first` and `second` differ.
+    fn cmp_keys(&self, first: &[u8], second: &[u8]) -> bool {
+        let (first, second) = (self.get_key(first), self.get_key(second));
+        if !self.ignore_case {
+            return first != second;
+        }
+        if self.characters {
+            if let (Ok(first), Ok(second)) =
+                (std::str::from_utf8(first), std::str::from_utf8(second))
+            {
+                return first
                     .chars()
-                    .skip(slice_start)
-                    .take(slice_stop)
-                    .flat_map(|c| c.to_uppercase()),
-            )
-        } else {
-            closure(&mut fields_to_check.chars())
+                    .flat_map(char::to_uppercase)
+                    .ne(second.chars().flat_map(char::to_uppercase));
+            }
         }
+        !first.eq_ignore_ascii_case(second)
     }
 
     fn should_print_delimiter(&self, group_count: usize, first_line_printed: bool) -> bool {
@@ -201,7 +202,7 @@ impl Uniq {
     fn print_line<W: Write>(
         &self,
         writer: &mut BufWriter<W>,
-        line: &str,
+        line: &[u8],
         count: usize,
         first_line_printed: bool,
     ) -> UResult<()> {
@@ -212,9 +213,9 @@ impl Uniq {
         }
 
         if self.show_counts {
-            writer.write_all(format!("{count:7} {line}").as_bytes())
+            write!(writer, "{count:7} ").and_then(|_| writer.write_all(line))
         } else {
-            writer.write_all(line.as_bytes())
+            writer.write_all(line)
         }
         .map_err_context(|| "Failed to write line".to_string())?;
 
@@ -222,12 +223,6 @@ impl Uniq {
     }
 }
 
-fn get_line_string(io_line: io::Result<Vec<u8>>) -> UResult<String> {
-    let line_bytes = io_line.map_err_context(|| "failed to split lines".to_string())?;
-    String::from_utf8(line_bytes)
-        .map_err(|e| USimpleError::new(1, format!("failed to convert line to utf8: {e}")))
-}
-
 fn opt_parsed<T: FromStr>(opt_name: &str, matches: &ArgMatches) -> UResult<Option<T>> {
     Ok(match matches.get_one::<String>(opt_name) {
         Some(arg_str) => Some(arg_str.parse().map_err(|_| {
@@ -285,6 +280,7 @@ pub fn uumain(args: impl uucore::Args) -> UResult<()> {
         slice_start: opt_parsed(options::SKIP_CHARS, &matches)?,
         slice_stop: opt_parsed(options::CHECK_CHARS, &matches)?,
         ignore_case: matches.get_flag(options::IGNORE_CASE),
+        characters: matches.get_flag(options::CHARACTERS),
         zero_terminated: matches.get_flag(options::ZERO_TERMINATED),
     };
 
@@ -340,15 +336,25 @@ pub fn uu_app() -> Command {
                     options::REPEATED,
                     options::ALL_REPEATED,
                     options::UNIQUE,
+                    options::COUNT,
                 ]),
         )
         .arg(
             Arg::new(options::CHECK_CHARS)
                 .short('w')
                 .long(options::CHECK_CHARS)
-                .help("compare no more than N characters in lines")
+                .help("compare no more than N bytes or characters in lines")
                 .value_name("N"),
         )
+        .arg(
+            Arg::new(options::CHARACTERS)
+                .long(options::CHARACTERS)
+                .help(
+                    "count UTF-8 characters instead of bytes with -s and -w, \
+                     and ignore the case of non-ASCII characters with -i",
+                )
+                .action(ArgAction::SetTrue),
+        )
         .arg(
             Arg::new(options::COUNT)
                 .short('c')
@@ -374,7 +380,7 @@ pub fn uu_app() -> Command {
             Arg::new(options::SKIP_CHARS)
                 .short('s')
                 .long(options::SKIP_CHARS)
-                .help("avoid comparing the first N characters")
+                .help("avoid comparing the first N bytes or characters")
                 .value_name("N"),
         )
         .arg(
@@ -21,12 +21,16 @@ use word_count::{TitledWordCount, WordCount};
 use clap::{crate_version, Arg, ArgAction, ArgMatches, Command};
 
 use std::cmp::max;
+use std::collections::BTreeMap;
 use std::error::Error;
 use std::ffi::{OsStr, OsString};
 use std::fmt::Display;
 use std::fs::{self, File};
 use std::io::{self, Read, Write};
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{mpsc, Arc};
+use std::thread;
 
 use uucore::error::{UError, UResult, USimpleError};
 use uucore::quoting_style::{escape_name, QuotingStyle};
@@ -34,6 +38,7 @@ use uucore::quoting_style::{escape_name, QuotingStyle};
 /// The minimum character width for formatting counts when reading from stdin.
 const MINIMUM_WIDTH: usize = 7;
 
+#[derive(Clone)]
 struct Settings {
     show_bytes: bool,
     show_chars: bool,
@@ -114,6 +119,7 @@ pub mod options {
 static ARG_FILES: &str = "files";
 static STDIN_REPR: &str = "-";
 
+#[derive(Clone)]
 enum StdinKind {
     /// Stdin specified on command-line with "-".
     Explicit,
@@ -123,6 +129,7 @@ enum StdinKind {
 }
 
 /// Supported inputs.
+#[derive(Clone)]
 enum Input {
     /// A regular file.
     Path(PathBuf),
@@ -423,38 +430,40 @@ fn word_count_from_reader_specialized<
     while let Some(chunk) = reader.next_strict() {
         match chunk {
             Ok(text) => {
-                for ch in text.chars() {
-                    if SHOW_WORDS {
-                        if ch.is_whitespace() {
-                            in_word = false;
-                        } else if ch.is_ascii_control() {
-                            // These count as characters but do not affect the word state
-                        } else if !in_word {
-                            in_word = true;
-                            total.words += 1;
+                // Lines and characters are counted over the whole chunk at once.
+                if SHOW_LINES {
+                    total.lines += bytecount::count(text.as_bytes(), b'\n');
+                }
+                if SHOW_CHARS {
+                    total.chars += bytecount::num_chars(text.as_bytes());
+                }
+                if SHOW_WORDS || SHOW_MAX_LINE_LENGTH {
+                    if !SHOW_MAX_LINE_LENGTH && text.is_ascii() {
+                        total.words += count_ascii_words(text.as_bytes(), &mut in_word);
+                    } else if text.is_ascii() {
+                        // ASCII text doesn't need to be decoded, and its printable characters
+                        // are one column wide.
+                        for &byte in text.as_bytes() {
+                            let width = usize::from((b' '..=b'~').contains(&byte));
+                            count_char::<SHOW_MAX_LINE_LENGTH, SHOW_WORDS>(
+                                byte as char,
+                                width,
+                                &mut total,
+                                &mut in_word,
+                                &mut current_len,
+                            );
                         }
-                    }
-                    if SHOW_MAX_LINE_LENGTH {
-                        match ch {
-                            '\n' | '\r' | '\x0c' => {
-                                total.max_line_length = max(current_len, total.max_line_length);
-                                current_len = 0;
-                            }
-                            '\t' => {
-                                current_len -= current_len % 8;
-                                current_len += 8;
-                            }
-                            _ => {
-                                current_len += ch.width().unwrap_or(0);
-                            }
+                    } else {
+                        for ch in text.chars() {
+                            count_char::<SHOW_MAX_LINE_LENGTH, SHOW_WORDS>(
+                                ch,
+                                ch.width().unwrap_or(0),
+                                &mut total,
+                                &mut in_word,
+                                &mut current_len,
+                            );
                         }
                     }
-                    if SHOW_LINES && ch == '\n' {
-                        total.lines += 1;
-                    }
-                    if SHOW_CHARS {
-                        total.chars += 1;
-                    }
                 }
                 total.bytes += text.len();
             }
@@ -474,6 +483,91 @@ fn word_count_from_reader_specialized<
     (total, None)
 }
 
+/// Returns the number of words starting in the ASCII text `bytes`, which continues a word if
+/// `in_word`, and updates `in_word` for the text that follows.
+fn count_ascii_words(bytes: &[u8], in_word: &mut bool) -> usize {
+    let is_word = |byte: u8| byte > b' ' && byte != 0x7f;
+    let is_control = |byte: u8| (byte < b' ' && !matches!(byte, b'\t'..=b'\r')) || byte == 0x7f;
+    let mut words = 0;
+    // `fold` instead of `any` checks the whole text without branches, which is faster.
+    if bytes
+        .iter()
+        .fold(false, |found, &byte| found | is_control(byte))
+    {
+        // Control characters don't change whether a word is being read.
+        for &byte in bytes {
+            if is_word(byte) {
+                words += usize::from(!*in_word);
+                *in_word = true;
+            } else if !is_control(byte) {
+                *in_word = false;
+            }
+        }
+        return words;
+    }
+
+    // Otherwise a word starts at every word byte that doesn't follow one, which is counted
+    // without branches for blocks of 64 bytes, using a mask of their word bytes.
+    let mut prev_in_word = u64::from(*in_word);
+    let blocks = bytes.chunks_exact(64);
+    let rest = blocks.remainder();
+    for block in blocks {
+        let mut flags = [0u8; 64];
+        for (flag, &byte) in flags.iter_mut().zip(block) {
+            *flag = u8::from(is_word(byte));
+        }
+        let mask = flags
+            .iter()
+            .enumerate()
+            .fold(0u64, |mask, (i, &flag)| mask | u64::from(flag) << i);
+        words += (mask & !((mask << 1) | prev_in_word)).count_ones() as usize;
+        prev_in_word = mask >> 63;
+    }
+    for &byte in rest {
+        let word = u64::from(is_word(byte));
+        words += (word & !prev_in_word) as usize;
+        prev_in_word = word;
+    }
+    *in_word = prev_in_word == 1;
+    words
+}
+
+/// Counts `ch`, which is `width` columns wide, for the words and the maximum line length.
+#[inline(always)]
+fn count_char<const SHOW_MAX_LINE_LENGTH: bool, const SHOW_WORDS: bool>(
+    ch: char,
+    width: usize,
+    total: &mut WordCount,
+    in_word: &mut bool,
+    current_len: &mut usize,
+) {
+    if SHOW_WORDS {
+        if ch.is_whitespace() {
+            *in_word = false;
+        } else if ch.is_ascii_control() {
+            // These count as characters but do not affect the word state
+        } else if !*in_word {
+            *in_word = true;
+            total.words += 1;
+        }
+    }
+    if SHOW_MAX_LINE_LENGTH {
+        match ch {
+            '\n' | '\r' | '\x0c' => {
+                total.max_line_length = max(*current_len, total.max_line_length);
+                *current_len = 0;
+            }
+            '\t' => {
+                *current_len -= *current_len % 8;
+                *current_len += 8;
+            }
+            _ => {
+                *current_len += width;
+            }
+        }
+    }
+}
+
 enum CountResult {
     /// Nothing went wrong.
     Success(WordCount),
@@ -568,8 +662,8 @@ fn wc(inputs: &[Input], settings: &Settings) -> UResult<()> {
 
     let num_inputs = inputs.len();
 
-    for input in inputs {
-        let word_count = match word_count_from_input(input, settings) {
+    let mut show_result = |input: &Input, result: CountResult| {
+        let word_count = match result {
             CountResult::Success(word_count) => word_count,
             CountResult::Interrupted(word_count, error) => {
                 show!(USimpleError::new(
@@ -591,7 +685,7 @@ fn wc(inputs: &[Input], settings: &Settings) -> UResult<()> {
                         error
                     )
                 ));
-                continue;
+                return;
             }
         };
         total_word_count += word_count;
@@ -606,6 +700,16 @@ fn wc(inputs: &[Input], settings: &Settings) -> UResult<()> {
                 ),
             ));
         }
+    };
+
+    let jobs = thread::available_parallelism().map_or(1, usize::from);
+    let from_stdin = inputs.iter().any(|input| matches!(input, Input::Stdin(_)));
+    if num_inputs > 1 && jobs > 1 && !from_stdin {
+        count_in_parallel(inputs, settings, jobs.min(num_inputs), show_result);
+    } else {
+        for input in inputs {
+            show_result(input, word_count_from_input(input, settings));
+        }
     }
 
     if num_inputs > 1 {
@@ -623,6 +727,53 @@ fn wc(inputs: &[Input], settings: &Settings) -> UResult<()> {
     Ok(())
 }
 
+/// Counts `inputs` on `jobs` threads, and calls `show_result` for each of them in order, as soon
+/// as it and the inputs before it have been counted.
+///
+/// Standard input must not be one of the inputs, because it would be read by several threads.
+fn count_in_parallel(
+    inputs: &[Input],
+    settings: &Settings,
+    jobs: usize,
+    mut show_result: impl FnMut(&Input, CountResult),
+) {
+    let shared_inputs = Arc::new(inputs.to_vec());
+    let settings = Arc::new(settings.clone());
+    let next_input = Arc::new(AtomicUsize::new(0));
+    let (sender, receiver) = mpsc::channel();
+    for _ in 0..jobs {
+        let inputs = Arc::clone(&shared_inputs);
+        let settings = Arc::clone(&settings);
+        let next_input = Arc::clone(&next_input);
+        let sender = sender.clone();
+        thread::spawn(move || loop {
+            let i = next_input.fetch_add(1, Ordering::Relaxed);
+            let input = match inputs.get(i) {
+                Some(input) => input,
+                None => break,
+            };
+            if sender
+                .send((i, word_count_from_input(input, &settings)))
+                .is_err()
+            {
+                break;
+            }
+        });
+    }
+    drop(sender);
+
+    // The results of the inputs after the next one to show wait here.
+    let mut pending = BTreeMap::new();
+    let mut next_to_show = 0;
+    for (i, result) in receiver {
+        pending.insert(i, result);
+        while let Some(result) = pending.remove(&next_to_show) {
+            show_result(&inputs[next_to_show], result);
+            next_to_show += 1;
+        }
+    }
+}
+
 fn print_stats(
     settings: &Settings,
     result: &TitledWordCount,
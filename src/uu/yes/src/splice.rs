@@ -18,12 +18,16 @@
 
 use std::{io, os::unix::io::AsRawFd};
 
-use nix::{errno::Errno, libc::S_IFIFO, sys::stat::fstat};
+use nix::{
+    errno::Errno,
+    libc::{S_IFIFO, S_IFMT},
+    sys::stat::fstat,
+};
 
 use uucore::pipes::{pipe, splice_exact, vmsplice};
 
 pub(crate) fn splice_data(bytes: &[u8], out: &impl AsRawFd) -> Result<()> {
-    let is_pipe = fstat(out.as_raw_fd())?.st_mode as nix::libc::mode_t & S_IFIFO != 0;
+    let is_pipe = fstat(out.as_raw_fd())?.st_mode as nix::libc::mode_t & S_IFMT == S_IFIFO;
 
     if is_pipe {
         loop {
@@ -20,9 +20,14 @@ mod splice;
 const ABOUT: &str = "repeatedly display a line with STRING (or 'y')";
 const USAGE: &str = "{} [STRING]...";
 
-// it's possible that using a smaller or larger buffer might provide better performance on some
+// The size of the default pipe buffer on Linux, so that vmsplice fills the pipe in one go. It's
+// possible that using a smaller or larger buffer might provide better performance on some
 // systems, but honestly this is good enough
-const BUF_SIZE: usize = 16 * 1024;
+const BUF_SIZE: usize = 64 * 1024;
+
+/// The alignment of the buffer, so that vmsplice hands whole pages to the pipe. Systems with
+/// larger pages just split fewer of them.
+const PAGE_SIZE: usize = 4096;
 
 #[uucore::main]
 pub fn uumain(args: impl uucore::Args) -> UResult<()> {
@@ -37,7 +42,7 @@ pub fn uumain(args: impl uucore::Args) -> UResult<()> {
         Cow::from("y\n")
     };
 
-    let mut buffer = [0; BUF_SIZE];
+    let mut buffer = Vec::new();
     let bytes = prepare_buffer(&string, &mut buffer);
 
     match exec(bytes) {
@@ -55,18 +60,18 @@ pub fn uu_app() -> Command {
         .infer_long_args(true)
 }
 
-fn prepare_buffer<'a>(input: &'a str, buffer: &'a mut [u8; BUF_SIZE]) -> &'a [u8] {
-    if input.len() < BUF_SIZE / 2 {
-        let mut size = 0;
-        while size < BUF_SIZE - input.len() {
-            let (_, right) = buffer.split_at_mut(size);
-            right[..input.len()].copy_from_slice(input.as_bytes());
-            size += input.len();
-        }
-        &buffer[..size]
-    } else {
-        input.as_bytes()
+/// Fill `buffer` with as many whole copies of `input` as fit in [`BUF_SIZE`], or with one if
+/// it is longer, and return them, starting at a page boundary.
+fn prepare_buffer<'a>(input: &str, buffer: &'a mut Vec<u8>) -> &'a [u8] {
+    let copies = (BUF_SIZE / input.len()).max(1);
+    let size = copies * input.len();
+    buffer.resize(size + PAGE_SIZE, 0);
+    let start = buffer.as_ptr().align_offset(PAGE_SIZE).min(PAGE_SIZE);
+    let aligned = &mut buffer[start..start + size];
+    for chunk in aligned.chunks_exact_mut(input.len()) {
+        chunk.copy_from_slice(input.as_bytes());
     }
+    aligned
 }
 
 #[cfg(unix)]
@@ -2,18 +2,22 @@
 
 #[cfg(feature = "encoding")]
 pub mod encoding;
+#[cfg(feature = "format")]
+pub mod format;
 #[cfg(feature = "fs")]
 pub mod fs;
 #[cfg(feature = "fsext")]
 pub mod fsext;
 #[cfg(feature = "lines")]
 pub mod lines;
-#[cfg(feature = "memo")]
-pub mod memo;
+#[cfg(feature = "parse_datetime")]
+pub mod parse_datetime;
+#[cfg(feature = "progress")]
+pub mod progress;
 #[cfg(feature = "ringbuffer")]
 pub mod ringbuffer;
-#[cfg(feature = "memo")]
-mod tokenize;
+#[cfg(feature = "security_context")]
+pub mod security_context;
 
 // * (platform-specific) feature-gated modules
 // ** non-windows (i.e. Unix + Fuchsia)
@@ -0,0 +1,542 @@
+// This file is part of the uutils coreutils package.
+//
+// For the full copyright and license information, please view the LICENSE
+// file that was distributed with this source code.
+
+//! The arguments that the conversions of a format take, and how they are
+//! parsed as numbers
+//!
+//! Numbers are parsed like GNU printf does with `strtoimax`, `strtoumax` and
+//! `strtold`: integers may be octal or hexadecimal, a leading quote gives the
+//! value of the next character, and an argument that isn't a number or is
+//! only partly one is reported, with whatever could be parsed used instead
+//! and exit status 1.
+
+// spell-checker:ignore strtoimax strtoumax strtold POSIXLY
+
+use std::borrow::Cow;
+use std::env;
+use std::ffi::OsStr;
+use std::io::{stdout, Write};
+
+use crate::display::{os_str_as_bytes, Quotable};
+use crate::error::USimpleError;
+use crate::{show, show_warning};
+
+/// The arguments of a format, taken in order by its conversions.
+pub struct FormatArguments<'a> {
+    args: Vec<&'a OsStr>,
+    next: usize,
+}
+
+/// Why an argument is not a number, or only partly one.
+enum NumberError {
+    NotANumber,
+    NotCompletelyConverted,
+    OutOfRange,
+}
+
+/// The integer at the start of an argument.
+struct IntegerPrefix {
+    negative: bool,
+    magnitude: u64,
+    overflow: bool,
+    len: usize,
+}
+
+fn is_c_space(b: u8) -> bool {
+    matches!(b, b' ' | b'\t' | b'\n' | b'\x0b' | b'\x0c' | b'\r')
+}
+
+/// The length of the leading whitespace and sign of `bytes`, and whether the
+/// sign is negative.
+fn sign_prefix(bytes: &[u8]) -> (usize, bool) {
+    let spaces = bytes.iter().take_while(|&&b| is_c_space(b)).count();
+    match bytes.get(spaces) {
+        Some(b'-') => (spaces + 1, true),
+        Some(b'+') => (spaces + 1, false),
+        _ => (spaces, false),
+    }
+}
+
+/// Count the digits in `radix` at the start of `bytes`.
+fn count_digits(bytes: &[u8], radix: u32) -> usize {
+    bytes
+        .iter()
+        .take_while(|&&b| char::from(b).is_digit(radix))
+        .count()
+}
+
+/// Parse the integer at the start of `s`, which is hexadecimal after `0x`
+/// and octal after `0`, like `strtoimax` with base 0.
+fn integer_prefix(s: &str) -> Option<IntegerPrefix> {
+    let bytes = s.as_bytes();
+    let (mut start, negative) = sign_prefix(bytes);
+    let mut radix = 10;
+    if bytes[start..].starts_with(b"0x") || bytes[start..].starts_with(b"0X") {
+        if bytes.get(start + 2).map_or(false, u8::is_ascii_hexdigit) {
+            radix = 16;
+            start += 2;
+        }
+    } else if bytes.get(start) == Some(&b'0') {
+        radix = 8;
+    }
+    let len = count_digits(&bytes[start..], radix);
+    if len == 0 {
+        return None;
+    }
+    let mut magnitude: u64 = 0;
+    let mut overflow = false;
+    for &b in &bytes[start..start + len] {
+        let digit = u64::from(char::from(b).to_digit(radix).unwrap());
+        match magnitude
+            .checked_mul(u64::from(radix))
+            .and_then(|m| m.checked_add(digit))
+        {
+            Some(m) => magnitude = m,
+            None => overflow = true,
+        }
+    }
+    Some(IntegerPrefix {
+        negative,
+        magnitude,
+        overflow,
+        len: start + len,
+    })
+}
+
+/// The length of the floating point number at the start of `s`, which may be
+/// decimal, hexadecimal, infinity or not-a-number, like `strtold` takes.
+fn float_prefix_len(s: &str) -> Option<usize> {
+    let bytes = s.as_bytes();
+    let (start, _) = sign_prefix(bytes);
+    let rest = &bytes[start..];
+    let starts_with_ignore_case = |prefix: &[u8]| {
+        rest.len() >= prefix.len() && rest[..prefix.len()].eq_ignore_ascii_case(prefix)
+    };
+
+    if starts_with_ignore_case(b"infinity") {
+        return Some(start + 8);
+    }
+    if starts_with_ignore_case(b"inf") {
+        return Some(start + 3);
+    }
+    if starts_with_ignore_case(b"nan") {
+        // An optional sequence of digits, letters and underscores in
+        // parentheses may follow.
+        let len = rest[3..]
+            .strip_prefix(b"(")
+            .map(|tail| {
+                tail.iter()
+                    .take_while(|&&b| b.is_ascii_alphanumeric() || b == b'_')
+                    .count()
+            })
+            .filter(|&len| rest.get(4 + len) == Some(&b')'))
+            .map_or(0, |len| len + 2);
+        return Some(start + 3 + len);
+    }
+
+    let is_hex = (rest.starts_with(b"0x") || rest.starts_with(b"0X"))
+        && (rest.get(2).map_or(false, u8::is_ascii_hexdigit)
+            || (rest.get(2) == Some(&b'.') && rest.get(3).map_or(false, u8::is_ascii_hexdigit)));
+    let (radix, exponent_chars, mut len) = if is_hex {
+        (16, b"pP", 2)
+    } else {
+        (10, b"eE", 0)
+    };
+    let int_digits = count_digits(&rest[len..], radix);
+    len += int_digits;
+    let mut frac_digits = 0;
+    if rest.get(len) == Some(&b'.') {
+        frac_digits = count_digits(&rest[len + 1..], radix);
+        if int_digits + frac_digits > 0 {
+            len += 1 + frac_digits;
+        }
+    }
+    if int_digits + frac_digits == 0 {
+        return None;
+    }
+    // The exponent is decimal, and only taken if it has digits.
+    if rest.get(len).map_or(false, |b| exponent_chars.contains(b)) {
+        let sign_len = usize::from(matches!(rest.get(len + 1), Some(b'-' | b'+')));
+        let exponent_digits = count_digits(&rest[len + 1 + sign_len..], 10);
+        if exponent_digits > 0 {
+            len += 1 + sign_len + exponent_digits;
+        }
+    }
+    Some(start + len)
+}
+
+/// The value of a hexadecimal floating point number like `-0x1.8p3`, as
+/// found by [`float_prefix_len`].
+fn parse_hex_float(s: &str) -> f64 {
+    let (start, negative) = sign_prefix(s.as_bytes());
+    let s = &s[start + 2..];
+    let (digits, exponent) = match s.find(['p', 'P']) {
+        Some(pos) => {
+            let exponent = &s[pos + 1..];
+            // Exponents too large for an i64 are clamped below anyway.
+            let overflow = if exponent.starts_with('-') {
+                i64::MIN
+            } else {
+                i64::MAX
+            };
+            (&s[..pos], exponent.parse().unwrap_or(overflow))
+        }
+        None => (s, 0),
+    };
+    // Only the digits that fit are kept, as they are more than a float holds.
+    let mut mantissa: u64 = 0;
+    let mut exponent = exponent.clamp(-100_000, 100_000);
+    let mut past_point = false;
+    for c in digits.chars() {
+        match c.to_digit(16) {
+            Some(digit) if mantissa >> 60 == 0 => {
+                mantissa = mantissa << 4 | u64::from(digit);
+                if past_point {
+                    exponent -= 4;
+                }
+            }
+            Some(_) if !past_point => exponent += 4,
+            Some(_) => {}
+            None => past_point = true,
+        }
+    }
+    let mut value = mantissa as f64;
+    // Scale in steps that can't overflow or underflow on their own.
+    while exponent != 0 {
+        let step = exponent.clamp(-1000, 1000);
+        value *= 2f64.powi(step as i32);
+        exponent -= step;
+    }
+    if negative {
+        -value
+    } else {
+        value
+    }
+}
+
+impl<'a> FormatArguments<'a> {
+    pub fn new<T: AsRef<OsStr>>(args: &'a [T]) -> Self {
+        Self {
+            args: args.iter().map(AsRef::as_ref).collect(),
+            next: 0,
+        }
+    }
+
+    /// How many arguments were taken so far.
+    pub fn position(&self) -> usize {
+        self.next
+    }
+
+    /// The arguments that were not taken yet.
+    pub fn remaining(&self) -> &[&'a OsStr] {
+        &self.args[self.next..]
+    }
+
+    /// Take the next argument as it was given, if any is left.
+    pub fn next_os(&mut self) -> Option<&'a OsStr> {
+        let arg = *self.args.get(self.next)?;
+        self.next += 1;
+        Some(arg)
+    }
+
+    /// Take the next argument, if any is left, with invalid UTF-8 replaced.
+    pub fn next_arg(&mut self) -> Option<Cow<'a, str>> {
+        self.next_os().map(OsStr::to_string_lossy)
+    }
+
+    /// Take the next argument, or the empty string if none is left.
+    pub fn next_str(&mut self) -> Cow<'a, str> {
+        self.next_arg().unwrap_or_default()
+    }
+
+    /// Take the bytes of the next argument, or none if no argument is left.
+    pub fn next_bytes(&mut self) -> Cow<'a, [u8]> {
+        self.next_os().map_or(Cow::Borrowed(b""), os_str_as_bytes)
+    }
+
+    /// Take the next argument as a signed integer.
+    pub fn next_i64(&mut self) -> i64 {
+        parse_i64(&self.next_str())
+    }
+
+    /// Take the next argument as an unsigned integer, where negative values
+    /// wrap around.
+    pub fn next_u64(&mut self) -> u64 {
+        parse_u64(&self.next_str())
+    }
+
+    /// Take the next argument as a floating point number.
+    pub fn next_f64(&mut self) -> f64 {
+        parse_f64(&self.next_str())
+    }
+}
+
+/// Report that `arg` is not a number, or only partly one.
+fn report(arg: &str, error: &NumberError) {
+    // The output so far goes before the message, like with GNU printf.
+    let _ = stdout().flush();
+    let msg = match error {
+        NumberError::NotANumber => format!("{}: expected a numeric value", arg.quote()),
+        NumberError::NotCompletelyConverted => {
+            format!("{}: value not completely converted", arg.quote())
+        }
+        NumberError::OutOfRange => format!("{}: Numerical result out of range", arg.quote()),
+    };
+    show!(USimpleError::new(1, msg));
+}
+
+/// The value of the character after the quote that `arg` starts with, if it
+/// is a character constant like `'a` or `"a`.
+fn character_constant(arg: &str) -> Option<u32> {
+    let mut chars = arg.strip_prefix(['\'', '"'])?.chars();
+    let value = u32::from(chars.next()?);
+    let rest = chars.as_str();
+    if !rest.is_empty() && env::var_os("POSIXLY_CORRECT").is_none() {
+        let _ = stdout().flush();
+        show_warning!(
+            "{}: character(s) following character constant have been ignored",
+            rest
+        );
+    }
+    Some(value)
+}
+
+/// Check that the number at the start of `arg`, which is `len` long, is all
+/// of it.
+fn check_converted(arg: &str, len: Option<usize>) {
+    match len {
+        None if !arg.is_empty() => report(arg, &NumberError::NotANumber),
+        Some(len) if len < arg.len() => report(arg, &NumberError::NotCompletelyConverted),
+        _ => {}
+    }
+}
+
+fn parse_i64(arg: &str) -> i64 {
+    if let Some(value) = character_constant(arg) {
+        return value.into();
+    }
+    let prefix = integer_prefix(arg);
+    let value = prefix.as_ref().map_or(Some(0), |prefix| {
+        let magnitude = i64::try_from(prefix.magnitude).ok();
+        match (prefix.overflow, prefix.negative) {
+            (true, _) => None,
+            (false, false) => magnitude,
+            (false, true) => magnitude
+                .map(|m| -m)
+                .or_else(|| (prefix.magnitude == i64::MIN.unsigned_abs()).then(|| i64::MIN)),
+        }
+    });
+    match value {
+        Some(value) => {
+            check_converted(arg, prefix.map(|prefix| prefix.len));
+            value
+        }
+        None => {
+            report(arg, &NumberError::OutOfRange);
+            if prefix.map_or(false, |prefix| prefix.negative) {
+                i64::MIN
+            } else {
+                i64::MAX
+            }
+        }
+    }
+}
+
+fn parse_u64(arg: &str) -> u64 {
+    if let Some(value) = character_constant(arg) {
+        return value.into();
+    }
+    match integer_prefix(arg) {
+        Some(prefix) if prefix.overflow => {
+            report(arg, &NumberError::OutOfRange);
+            u64::MAX
+        }
+        Some(prefix) => {
+            check_converted(arg, Some(prefix.len));
+            if prefix.negative {
+                prefix.magnitude.wrapping_neg()
+            } else {
+                prefix.magnitude
+            }
+        }
+        None => {
+            check_converted(arg, None);
+            0
+        }
+    }
+}
+
+/// Move `value`, the float nearest to the decimal `number`, to the other side
+/// of `number` if the `long double` nearest to it is there.
+///
+/// GNU printf converts `long double`s, which round like the nearest float
+/// does unless a number is exactly halfway between two outputs, like
+/// 1.9999995 with `%f`. So that these round the same way, the side is found
+/// exactly for numbers of up to 19 significant digits with some after the
+/// point, which covers the arguments where this happens in practice.
+fn round_like_long_double(number: &str, value: f64) -> Option<f64> {
+    let unsigned = number.trim_start_matches(['-', '+']);
+    let (mantissa, exponent) = match unsigned.find(['e', 'E']) {
+        Some(pos) => (&unsigned[..pos], unsigned[pos + 1..].parse::<i64>().ok()?),
+        None => (unsigned, 0),
+    };
+
+    // The number is `digits / 10^scale`.
+    let mut digits: u128 = 0;
+    let mut scale = exponent.checked_neg()?;
+    let mut past_point = false;
+    for b in mantissa.bytes() {
+        if b == b'.' {
+            past_point = true;
+            continue;
+        }
+        if digits >= 10u128.pow(18) {
+            return None;
+        }
+        digits = digits * 10 + u128::from(b - b'0');
+        if past_point {
+            scale += 1;
+        }
+    }
+    while digits != 0 && digits % 10 == 0 && scale > 0 {
+        digits /= 10;
+        scale -= 1;
+    }
+    if digits == 0 || !(1..=19).contains(&scale) {
+        return None;
+    }
+    let divisor = 10u128.pow(scale as u32);
+
+    // Scale the number to 64 bits before the point, like the significand of
+    // a `long double`, keeping the remainder of the division.
+    let bit_len = |n: u128| 128 - n.leading_zeros();
+    let mut shift = 63 + bit_len(divisor) - bit_len(digits);
+    if (digits << shift) / divisor < 1 << 63 {
+        shift += 1;
+    }
+    let quotient = (digits << shift) / divisor;
+    let remainder = (digits << shift) % divisor;
+    if remainder == 0 {
+        return None;
+    }
+    let long_double_above = match (2 * remainder).cmp(&divisor) {
+        std::cmp::Ordering::Less => false,
+        std::cmp::Ordering::Greater => true,
+        std::cmp::Ordering::Equal => quotient & 1 == 1,
+    };
+
+    // The float, scaled the same way.
+    let bits = value.abs().to_bits();
+    let biased_exponent = (bits >> 52) as i64;
+    if biased_exponent == 0 {
+        return None;
+    }
+    let float_shift = biased_exponent - 1075 + i64::from(shift);
+    if !(0..64).contains(&float_shift) {
+        return None;
+    }
+    let significand = u128::from(bits & ((1 << 52) - 1) | 1 << 52);
+    let float_above = significand << float_shift > quotient;
+
+    if long_double_above == float_above {
+        return None;
+    }
+    let moved = f64::from_bits(if long_double_above {
+        bits + 1
+    } else {
+        bits - 1
+    });
+    Some(if value < 0.0 { -moved } else { moved })
+}
+
+fn parse_f64(arg: &str) -> f64 {
+    if let Some(value) = character_constant(arg) {
+        return value.into();
+    }
+    let len = float_prefix_len(arg);
+    let number = len.map_or("", |len| {
+        arg[..len].trim_start_matches(|c: char| c.is_ascii() && is_c_space(c as u8))
+    });
+    let unsigned = number.trim_start_matches(['-', '+']);
+    let value = if unsigned.starts_with("0x") || unsigned.starts_with("0X") {
+        parse_hex_float(number)
+    } else if unsigned
+        .get(..3)
+        .map_or(false, |s| s.eq_ignore_ascii_case("nan"))
+    {
+        if number.starts_with('-') {
+            -f64::NAN
+        } else {
+            f64::NAN
+        }
+    } else {
+        let value = number.parse().unwrap_or(0.0);
+        round_like_long_double(number, value).unwrap_or(value)
+    };
+    if value.is_infinite() && !unsigned.starts_with(['i', 'I']) {
+        report(arg, &NumberError::OutOfRange);
+    } else {
+        check_converted(arg, len);
+    }
+    value
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{float_prefix_len, parse_f64, parse_i64, parse_u64};
+
+    #[test]
+    fn test_parse_integers() {
+        assert_eq!(parse_i64("42"), 42);
+        assert_eq!(parse_i64(" -0x1F"), -31);
+        assert_eq!(parse_i64("+017"), 15);
+        assert_eq!(parse_i64("-9223372036854775808"), i64::MIN);
+        assert_eq!(parse_i64("99999999999999999999"), i64::MAX);
+        assert_eq!(parse_i64("'a"), 97);
+        assert_eq!(parse_i64("\"é"), 233);
+        assert_eq!(parse_i64("12abc"), 12);
+        assert_eq!(parse_i64("0x"), 0);
+        assert_eq!(parse_i64(""), 0);
+        assert_eq!(parse_u64("-1"), u64::MAX);
+        assert_eq!(parse_u64("0xffffffffffffffff"), u64::MAX);
+    }
+
+    #[test]
+    fn test_parse_floats() {
+        assert_eq!(parse_f64("1.5"), 1.5);
+        assert_eq!(parse_f64(" -.5e1"), -5.0);
+        assert_eq!(parse_f64("077"), 77.0);
+        assert_eq!(parse_f64("0x1.8p1"), 3.0);
+        assert_eq!(parse_f64("0xF1.1F"), 241.12109375);
+        assert_eq!(parse_f64("-inf"), f64::NEG_INFINITY);
+        assert!(parse_f64("nan(123)").is_nan());
+        assert!(parse_f64("-nan").is_sign_negative());
+        assert_eq!(parse_f64("1e"), 1.0);
+        assert_eq!(parse_f64("x"), 0.0);
+    }
+
+    #[test]
+    fn test_round_like_long_double() {
+        // The nearest float is below, and the nearest `long double` above.
+        assert!(parse_f64("1.9999995") > 1.999_999_5);
+        assert!(parse_f64("-1.9999995") < -1.999_999_5);
+        assert_eq!(format!("{:.6}", parse_f64("1.9999995")), "2.000000");
+        // Both are below.
+        assert_eq!(parse_f64("0.35"), 0.35);
+        assert_eq!(parse_f64("2.5"), 2.5);
+    }
+
+    #[test]
+    fn test_float_prefix_len() {
+        assert_eq!(float_prefix_len("1.5e+3x"), Some(6));
+        assert_eq!(float_prefix_len("1.e"), Some(2));
+        assert_eq!(float_prefix_len(".5"), Some(2));
+        assert_eq!(float_prefix_len("."), None);
+        assert_eq!(float_prefix_len("0x"), Some(1));
+        assert_eq!(float_prefix_len("0x.8p-1"), Some(7));
+        assert_eq!(float_prefix_len("infinit"), Some(3));
+        assert_eq!(float_prefix_len("nan(ab"), Some(3));
+    }
+}
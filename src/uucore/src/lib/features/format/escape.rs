@@ -0,0 +1,181 @@
+// This file is part of the uutils coreutils package.
+//
+// For the full copyright and license information, please view the LICENSE
+// file that was distributed with this source code.
+
+//! Backslash escape sequences, as printf replaces them in its format and in
+//! the arguments of `%b`, and as `echo -e` does
+
+use std::ops::ControlFlow;
+
+use super::FormatError;
+
+/// Which escape sequences are replaced, as they differ slightly between the
+/// format of printf, the arguments of `%b` and echo.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EscapeStyle {
+    /// The format of printf, where octal escapes have up to three digits.
+    Format,
+    /// The arguments of `%b`, where octal escapes may have an extra
+    /// leading `0`.
+    Argument,
+    /// The arguments of `echo -e`, which are like those of `%b` without
+    /// `\"`, `\u` and `\U`, and where `\x` without hexadecimal digits is
+    /// kept as it is.
+    Echo,
+}
+
+/// Take up to `max_digits` digits in `radix` from the start of `input`.
+fn take_digits(input: &mut &[u8], radix: u32, max_digits: usize) -> Option<u32> {
+    let count = input
+        .iter()
+        .take(max_digits)
+        .take_while(|&&b| char::from(b).is_digit(radix))
+        .count();
+    if count == 0 {
+        return None;
+    }
+    let value = input[..count].iter().fold(0, |value, &b| {
+        value * radix + char::from(b).to_digit(radix).unwrap()
+    });
+    *input = &input[count..];
+    Some(value)
+}
+
+/// Replace the escape sequence at the start of `input`, which follows a
+/// backslash, by what it stands for in `output`, and advance `input` past it.
+///
+/// `\c` breaks, as nothing is printed after it.
+pub(crate) fn unescape(
+    input: &mut &[u8],
+    style: EscapeStyle,
+    output: &mut Vec<u8>,
+) -> Result<ControlFlow<()>, FormatError> {
+    let (&first, rest) = match input.split_first() {
+        Some(split) => split,
+        None => {
+            output.push(b'\\');
+            return Ok(ControlFlow::Continue(()));
+        }
+    };
+    match first {
+        b'x' => {
+            *input = rest;
+            match take_digits(input, 16, 2) {
+                Some(value) => output.push(value as u8),
+                None if style == EscapeStyle::Echo => output.extend_from_slice(br"\x"),
+                None => return Err(FormatError::MissingHexadecimal),
+            }
+        }
+        b'0'..=b'7' => {
+            if style != EscapeStyle::Format && first == b'0' {
+                *input = rest;
+            }
+            // Values beyond a byte wrap around, like with putchar.
+            output.push(take_digits(input, 8, 3).unwrap_or(0) as u8);
+        }
+        b'u' | b'U' if style != EscapeStyle::Echo => {
+            *input = rest;
+            let len = if first == b'u' { 4 } else { 8 };
+            if input.len() < len || !input[..len].iter().all(u8::is_ascii_hexdigit) {
+                return Err(FormatError::MissingHexadecimal);
+            }
+            let value = take_digits(input, 16, len).unwrap();
+            // Characters of the basic character set, control characters and
+            // surrogates can't be given by their universal character name.
+            let c = char::from_u32(value)
+                .filter(|&c| matches!(c, '$' | '@' | '`' | '\u{a0}'..))
+                .ok_or(FormatError::InvalidCharacterName(char::from(first), value))?;
+            output.extend_from_slice(c.encode_utf8(&mut [0; 4]).as_bytes());
+        }
+        b'c' => return Ok(ControlFlow::Break(())),
+        _ => {
+            *input = rest;
+            match first {
+                b'"' if style != EscapeStyle::Echo => output.push(first),
+                b'\\' => output.push(first),
+                b'a' => output.push(b'\x07'),
+                b'b' => output.push(b'\x08'),
+                b'e' => output.push(b'\x1b'),
+                b'f' => output.push(b'\x0c'),
+                b'n' => output.push(b'\n'),
+                b'r' => output.push(b'\r'),
+                b't' => output.push(b'\t'),
+                b'v' => output.push(b'\x0b'),
+                _ => output.extend_from_slice(&[b'\\', first]),
+            }
+        }
+    }
+    Ok(ControlFlow::Continue(()))
+}
+
+/// Replace the escape sequences in `input` by what they stand for in
+/// `output`, until the end or until `\c`, which breaks.
+pub fn unescape_all(
+    mut input: &[u8],
+    style: EscapeStyle,
+    output: &mut Vec<u8>,
+) -> Result<ControlFlow<()>, FormatError> {
+    while let Some(pos) = input.iter().position(|&b| b == b'\\') {
+        output.extend_from_slice(&input[..pos]);
+        input = &input[pos + 1..];
+        if unescape(&mut input, style, output)?.is_break() {
+            return Ok(ControlFlow::Break(()));
+        }
+    }
+    output.extend_from_slice(input);
+    Ok(ControlFlow::Continue(()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{unescape_all, EscapeStyle};
+    use std::ops::ControlFlow;
+
+    fn unescaped_with(input: &str, style: EscapeStyle) -> Vec<u8> {
+        let mut output = Vec::new();
+        let _ = unescape_all(input.as_bytes(), style, &mut output).unwrap();
+        output
+    }
+
+    fn unescaped(input: &str) -> Vec<u8> {
+        unescaped_with(input, EscapeStyle::Argument)
+    }
+
+    #[test]
+    fn test_unescape() {
+        assert_eq!(unescaped(r"a\tb\\\n"), b"a\tb\\\n");
+        assert_eq!(unescaped(r"\x41\x4a\x4Z"), b"AJ\x04Z");
+        assert_eq!(unescaped(r"\101\0101\08\400"), b"AA\x008\x00");
+        assert_eq!(unescaped(r"é\U0001F600"), "é😀".as_bytes());
+        assert_eq!(unescaped(r#"\q\'\"\"#), br#"\q\'"\"#);
+        assert_eq!(unescaped_with(r"\101\0101", EscapeStyle::Format), b"A\x081");
+    }
+
+    #[test]
+    fn test_unescape_echo() {
+        let echo = |input| unescaped_with(input, EscapeStyle::Echo);
+        assert_eq!(echo(r"\101\0101\x41\t"), b"AAA\t");
+        assert_eq!(echo(r#"\x\xg\u0041\""#), br#"\x\xg\u0041\""#);
+    }
+
+    #[test]
+    fn test_unescape_stop() {
+        let mut output = Vec::new();
+        assert_eq!(
+            unescape_all(br"a\cb", EscapeStyle::Argument, &mut output).unwrap(),
+            ControlFlow::Break(())
+        );
+        assert_eq!(output, b"a");
+    }
+
+    #[test]
+    fn test_unescape_errors() {
+        for input in [r"\x", r"\xg", r"\u12", r"\u0041", r"\ud800", r"\U00110000"] {
+            assert!(
+                unescape_all(input.as_bytes(), EscapeStyle::Argument, &mut Vec::new()).is_err()
+            );
+        }
+        assert_eq!(unescaped(r"\u0024"), b"$");
+    }
+}
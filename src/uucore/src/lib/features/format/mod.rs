@@ -0,0 +1,301 @@
+// This file is part of the uutils coreutils package.
+//
+// For the full copyright and license information, please view the LICENSE
+// file that was distributed with this source code.
+
+//! The formats of printf, which seq uses as well.
+//!
+//! A format is made of text, backslash escapes and conversion specifications
+//! like `%5.2f`. [`parse_format`] splits a format into these items, which
+//! [`printf`] and [`sprintf`] then write with their arguments. Like with GNU
+//! printf, the format is reused as long as arguments remain.
+//!
+//! The backslash escapes are replaced by [`unescape_all`], which echo uses
+//! as well.
+
+// spell-checker:ignore datefmt
+
+mod argument;
+mod escape;
+mod num_format;
+mod spec;
+
+use std::error::Error;
+use std::ffi::OsStr;
+use std::fmt::{self, Display, Formatter};
+use std::io::{stdout, Write};
+use std::ops::ControlFlow;
+
+use crate::display::Quotable;
+use crate::error::{UError, UResult, USimpleError};
+use crate::show_warning;
+
+pub use argument::FormatArguments;
+pub use escape::{unescape_all, EscapeStyle};
+pub use num_format::{FloatStyle, IntegerBase};
+pub use spec::{CanAsterisk, Conversion, Spec};
+
+/// An item of a format.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum FormatItem {
+    /// Text to write as it is, with its escapes replaced.
+    Text(Vec<u8>),
+    /// A conversion specification.
+    Spec(Spec),
+    /// `\c`, after which nothing is written.
+    Stop,
+}
+
+/// Why a format can't be written.
+#[derive(Debug)]
+pub enum FormatError {
+    /// A conversion specification that isn't valid, up to its conversion
+    /// character.
+    InvalidSpec(String),
+    /// `\x` without hexadecimal digits.
+    MissingHexadecimal,
+    /// `\u` or `\U` for a character that can't be written that way, with
+    /// the value it was given.
+    InvalidCharacterName(char, u32),
+    /// A width taken from an argument that is out of range.
+    InvalidWidth(String),
+    /// A precision taken from an argument that is out of range.
+    InvalidPrecision(String),
+}
+
+impl Display for FormatError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Self::InvalidSpec(spec) => {
+                write!(f, "{spec}: invalid conversion specification")
+            }
+            Self::MissingHexadecimal => write!(f, "missing hexadecimal number in escape"),
+            Self::InvalidCharacterName(c, value) => {
+                let width = if *c == 'u' { 4 } else { 8 };
+                write!(f, "invalid universal character name \\{c}{value:0width$x}")
+            }
+            Self::InvalidWidth(arg) => write!(f, "invalid field width: {}", arg.quote()),
+            Self::InvalidPrecision(arg) => write!(f, "invalid precision: {}", arg.quote()),
+        }
+    }
+}
+
+impl Error for FormatError {}
+
+impl UError for FormatError {}
+
+/// Split `format` into its items, ending after the first error.
+///
+/// Backslash escapes in the text are only replaced if `escapes` is set.
+pub fn parse_format(
+    format: &str,
+    escapes: bool,
+) -> impl Iterator<Item = Result<FormatItem, FormatError>> + '_ {
+    let mut rest = format.as_bytes();
+    let mut done = false;
+    let mut stop = false;
+    std::iter::from_fn(move || {
+        if stop {
+            stop = false;
+            done = true;
+            return Some(Ok(FormatItem::Stop));
+        }
+        if done || rest.is_empty() {
+            return None;
+        }
+        let mut text = Vec::new();
+        while let Some((&b, after)) = rest.split_first() {
+            match b {
+                b'%' if after.first() == Some(&b'%') => {
+                    text.push(b'%');
+                    rest = &after[1..];
+                }
+                b'%' if text.is_empty() => {
+                    rest = after;
+                    let spec = Spec::parse(&mut rest);
+                    done = spec.is_err();
+                    return Some(spec.map(FormatItem::Spec));
+                }
+                b'%' => break,
+                b'\\' if escapes => {
+                    rest = after;
+                    match escape::unescape(&mut rest, EscapeStyle::Format, &mut text) {
+                        Ok(ControlFlow::Continue(())) => {}
+                        Ok(ControlFlow::Break(())) => {
+                            // The text before `\c` is still written.
+                            stop = true;
+                            return Some(Ok(FormatItem::Text(text)));
+                        }
+                        Err(e) => {
+                            done = true;
+                            return Some(Err(e));
+                        }
+                    }
+                }
+                _ => {
+                    text.push(b);
+                    rest = after;
+                }
+            }
+        }
+        Some(Ok(FormatItem::Text(text)))
+    })
+}
+
+/// Write `format` with `args` to `writer`, reusing the format as long as
+/// arguments remain.
+fn write_format<T: AsRef<OsStr>>(writer: &mut impl Write, format: &str, args: &[T]) -> UResult<()> {
+    let mut args = FormatArguments::new(args);
+    loop {
+        let start = args.position();
+        for item in parse_format(format, true) {
+            match item? {
+                FormatItem::Text(text) => writer.write_all(&text)?,
+                FormatItem::Spec(spec) => {
+                    if spec.write(writer, &mut args)?.is_break() {
+                        return Ok(());
+                    }
+                }
+                FormatItem::Stop => return Ok(()),
+            }
+        }
+        match args.remaining().first() {
+            None => return Ok(()),
+            Some(arg) if args.position() == start => {
+                writer.flush()?;
+                show_warning!("ignoring excess arguments, starting with {}", arg.quote());
+                return Ok(());
+            }
+            Some(_) => {}
+        }
+    }
+}
+
+/// Write a formatted string to stdout.
+///
+/// `format_string` contains the template and `args` contains the
+/// arguments to render into the template.
+///
+/// See also [`sprintf`], which creates a new formatted [`String`].
+///
+/// # Examples
+///
+/// ```rust
+/// use uucore::format::printf;
+///
+/// printf("hello %s", &["world".to_string()]).unwrap();
+/// // prints "hello world"
+/// ```
+pub fn printf<T: AsRef<OsStr>>(format_string: &str, args: &[T]) -> UResult<()> {
+    let mut writer = stdout().lock();
+    let result = write_format(&mut writer, format_string, args);
+    writer.flush()?;
+    result
+}
+
+/// Create a new formatted string.
+///
+/// `format_string` contains the template and `args` contains the
+/// arguments to render into the template.
+///
+/// See also [`printf`], which prints to stdout.
+///
+/// # Examples
+///
+/// ```rust
+/// use uucore::format::sprintf;
+///
+/// let s = sprintf("hello %s", &["world".to_string()]).unwrap();
+/// assert_eq!(s, "hello world".to_string());
+/// ```
+pub fn sprintf(format_string: &str, args: &[String]) -> UResult<String> {
+    let mut writer = Vec::new();
+    write_format(&mut writer, format_string, args)?;
+    match String::from_utf8(writer) {
+        Ok(s) => Ok(s),
+        Err(e) => Err(USimpleError::new(
+            1,
+            format!("failed to parse formatted string as UTF-8: {e}"),
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_format, sprintf, FormatItem};
+
+    fn format(format: &str, args: &[&str]) -> String {
+        let args: Vec<String> = args.iter().map(|s| s.to_string()).collect();
+        sprintf(format, &args).unwrap()
+    }
+
+    #[test]
+    fn test_sprintf_smoke() {
+        assert_eq!(sprintf("", &[]).unwrap(), "".to_string())
+    }
+
+    #[test]
+    fn test_sprintf_no_args() {
+        assert_eq!(
+            sprintf("hello world", &[]).unwrap(),
+            "hello world".to_string()
+        )
+    }
+
+    #[test]
+    fn test_sprintf_string() {
+        assert_eq!(
+            sprintf("hello %s", &["world".to_string()]).unwrap(),
+            "hello world".to_string()
+        )
+    }
+
+    #[test]
+    fn test_sprintf_conversions() {
+        assert_eq!(
+            format("%5d|%-5d|%05d", &["42", "42", "-42"]),
+            "   42|42   |-0042"
+        );
+        assert_eq!(format("%+.3d % d", &["7", "7"]), "+007  7");
+        assert_eq!(format("%#o %#x %X", &["8", "255", "255"]), "010 0xff FF");
+        assert_eq!(
+            format("%.2f %e %g", &["3.14159", "1000", "0.5"]),
+            "3.14 1.000000e+03 0.5"
+        );
+        assert_eq!(format("%08.3f|%-8g|", &["-1.5", "2"]), "-001.500|2       |");
+        assert_eq!(format("%c%c%.2s", &["hello", "", "world"]), "h\0wo");
+        assert_eq!(
+            format("%*d|%-*d|%.*f", &["4", "1", "3", "2", "1", "2.25"]),
+            "   1|2  |2.2"
+        );
+        assert_eq!(format("%*s|", &["-3", "a"]), "a  |");
+        assert_eq!(format("%b", &["a\\tb\\0101\\c"]), "a\tbA");
+        assert_eq!(format("%q", &["a b"]), "'a b'");
+        assert_eq!(format("%s\n", &["a", "b"]), "a\nb\n");
+        assert_eq!(format("%s %s\n", &["a", "b", "c"]), "a b\nc \n");
+        assert_eq!(format("%d", &["0x10"]), "16");
+        assert_eq!(format("%d", &["'A"]), "65");
+    }
+
+    #[test]
+    fn test_parse_format() {
+        let items: Vec<_> = parse_format("a\\x41%%%s\\cb", true)
+            .map(Result::unwrap)
+            .collect();
+        assert_eq!(items.len(), 4);
+        assert_eq!(items[0], FormatItem::Text(b"aA%".to_vec()));
+        assert!(matches!(items[1], FormatItem::Spec(_)));
+        assert_eq!(items[2], FormatItem::Text(Vec::new()));
+        assert_eq!(items[3], FormatItem::Stop);
+
+        let items: Vec<_> = parse_format("\\n%d", false).collect();
+        assert!(matches!(&items[0], Ok(FormatItem::Text(text)) if text == b"\\n"));
+
+        let errors: Vec<_> = parse_format("a%yb%d", true).collect();
+        assert_eq!(errors.len(), 2);
+        assert_eq!(
+            errors[1].as_ref().unwrap_err().to_string(),
+            "%y: invalid conversion specification"
+        );
+    }
+}
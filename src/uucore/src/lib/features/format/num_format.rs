@@ -0,0 +1,310 @@
+// This file is part of the uutils coreutils package.
+//
+// For the full copyright and license information, please view the LICENSE
+// file that was distributed with this source code.
+
+//! The digits of the numeric conversions, formatted like the C library does
+
+/// How a floating point number is written.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FloatStyle {
+    /// `%f`, like `123.456000`
+    Decimal,
+    /// `%e`, like `1.234560e+02`
+    Scientific,
+    /// `%g`, the shorter of decimal and scientific without trailing zeros,
+    /// like `123.456`
+    Shortest,
+    /// `%a`, like `0xf.6e978d4fdf3b646p+3`
+    Hexadecimal,
+}
+
+/// The base of an unsigned integer conversion.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum IntegerBase {
+    Decimal,
+    Octal,
+    Hexadecimal,
+}
+
+/// Write `magnitude` in `base` with at least `precision` digits, and return
+/// the prefix of the alternate form along with the digits.
+pub(crate) fn format_integer(
+    magnitude: u64,
+    base: IntegerBase,
+    upper: bool,
+    precision: Option<usize>,
+    alternate: bool,
+) -> (&'static str, String) {
+    let mut digits = match (base, upper) {
+        (IntegerBase::Decimal, _) => magnitude.to_string(),
+        (IntegerBase::Octal, _) => format!("{magnitude:o}"),
+        (IntegerBase::Hexadecimal, false) => format!("{magnitude:x}"),
+        (IntegerBase::Hexadecimal, true) => format!("{magnitude:X}"),
+    };
+    match precision {
+        // No digits at all are written for zero with a precision of zero.
+        Some(0) if magnitude == 0 => digits.clear(),
+        Some(precision) if digits.len() < precision => {
+            digits.insert_str(0, &"0".repeat(precision - digits.len()));
+        }
+        _ => {}
+    }
+    let prefix = match base {
+        IntegerBase::Octal if alternate && !digits.starts_with('0') => {
+            digits.insert(0, '0');
+            ""
+        }
+        IntegerBase::Hexadecimal if alternate && magnitude != 0 => {
+            if upper {
+                "0X"
+            } else {
+                "0x"
+            }
+        }
+        _ => "",
+    };
+    (prefix, digits)
+}
+
+/// Write `value`, which must not be negative, in `style`.
+///
+/// `precision` is the number of digits after the point, or of significant
+/// digits for [`FloatStyle::Shortest`], and `alternate` keeps the point and
+/// trailing zeros in all cases.
+pub(crate) fn format_float(
+    value: f64,
+    style: FloatStyle,
+    upper: bool,
+    precision: Option<usize>,
+    alternate: bool,
+) -> String {
+    let s = if !value.is_finite() {
+        if value.is_nan() { "nan" } else { "inf" }.to_string()
+    } else {
+        match style {
+            FloatStyle::Decimal => decimal(value, precision.unwrap_or(6), alternate),
+            FloatStyle::Scientific => scientific(value, precision.unwrap_or(6), alternate),
+            FloatStyle::Shortest => shortest(value, precision.unwrap_or(6), alternate),
+            FloatStyle::Hexadecimal => hexadecimal(value, precision, alternate),
+        }
+    };
+    if upper {
+        s.to_ascii_uppercase()
+    } else {
+        s
+    }
+}
+
+fn decimal(value: f64, precision: usize, alternate: bool) -> String {
+    let mut s = format!("{value:.precision$}");
+    if alternate && precision == 0 {
+        s.push('.');
+    }
+    s
+}
+
+/// Split `value` in scientific notation with `precision` digits after the
+/// point into the mantissa and the exponent.
+fn split_scientific(value: f64, precision: usize) -> (String, i32) {
+    let s = format!("{value:.precision$e}");
+    let (mantissa, exponent) = s.split_once('e').unwrap();
+    (mantissa.to_string(), exponent.parse().unwrap())
+}
+
+fn scientific(value: f64, precision: usize, alternate: bool) -> String {
+    let (mut mantissa, exponent) = split_scientific(value, precision);
+    if alternate && precision == 0 {
+        mantissa.push('.');
+    }
+    format_scientific(&mantissa, exponent)
+}
+
+fn format_scientific(mantissa: &str, exponent: i32) -> String {
+    let sign = if exponent < 0 { '-' } else { '+' };
+    format!("{mantissa}e{sign}{:02}", exponent.unsigned_abs())
+}
+
+/// Remove the trailing zeros after the point of `s`, and the point if
+/// nothing is left after it.
+fn strip_fraction_zeros(s: &str) -> &str {
+    if s.contains('.') {
+        s.trim_end_matches('0').trim_end_matches('.')
+    } else {
+        s
+    }
+}
+
+fn shortest(value: f64, precision: usize, alternate: bool) -> String {
+    let precision = precision.max(1);
+    // The exponent is the one after rounding to the significant digits.
+    let (mantissa, exponent) = split_scientific(value, precision - 1);
+    if exponent < -4 || exponent >= precision as i32 {
+        let mantissa = if alternate {
+            decimal_point(mantissa)
+        } else {
+            strip_fraction_zeros(&mantissa).to_string()
+        };
+        format_scientific(&mantissa, exponent)
+    } else {
+        let s = format!("{value:.0$}", (precision as i32 - 1 - exponent) as usize);
+        if alternate {
+            decimal_point(s)
+        } else {
+            strip_fraction_zeros(&s).to_string()
+        }
+    }
+}
+
+/// Add a point to `s` if it has none.
+fn decimal_point(mut s: String) -> String {
+    if !s.contains('.') {
+        s.push('.');
+    }
+    s
+}
+
+/// How many bits the digit before the point of `%a` holds.
+///
+/// The C library formats the `long double` that the number is converted to
+/// first, which stores the integer bit explicitly on x86, so the first digit
+/// holds the four most significant bits. With other formats, it is always 1.
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+const HEX_LEADING_BITS: u32 = 4;
+#[cfg(not(any(target_arch = "x86", target_arch = "x86_64")))]
+const HEX_LEADING_BITS: u32 = 1;
+
+fn hexadecimal(value: f64, precision: Option<usize>, alternate: bool) -> String {
+    if value == 0.0 {
+        let digits = "0".repeat(precision.unwrap_or(0));
+        return match (digits.is_empty(), alternate) {
+            (true, false) => "0x0p+0".to_string(),
+            _ => format!("0x0.{digits}p+0"),
+        };
+    }
+
+    // The significand left-aligned in 64 bits, with the binary exponent of
+    // its most significant bit.
+    let bits = value.to_bits();
+    let biased_exponent = ((bits >> 52) & 0x7ff) as i32;
+    let fraction = bits & ((1 << 52) - 1);
+    let (significand, exponent) = if biased_exponent == 0 {
+        (fraction, -1074)
+    } else {
+        (fraction | 1 << 52, biased_exponent - 1075)
+    };
+    let shift = significand.leading_zeros();
+    let significand = u128::from(significand << shift);
+    let exponent = exponent - shift as i32 + 63 - (HEX_LEADING_BITS as i32 - 1);
+
+    // The fraction bits, as 16 hexadecimal digits.
+    let fraction_bits = 64 - HEX_LEADING_BITS;
+    let mut leading = significand >> fraction_bits;
+    let mut fraction = (significand << HEX_LEADING_BITS) & u128::from(u64::MAX);
+    let digits = match precision {
+        Some(precision) if precision < 16 => {
+            // Round to `precision` digits, to even on ties.
+            let dropped_bits = 64 - 4 * precision as u32;
+            let dropped = fraction & ((1 << dropped_bits) - 1);
+            let half = 1 << (dropped_bits - 1);
+            fraction >>= dropped_bits;
+            let odd = if precision == 0 { leading } else { fraction } & 1 == 1;
+            if dropped > half || (dropped == half && odd) {
+                fraction += 1;
+                if fraction >> (4 * precision) != 0 {
+                    fraction = 0;
+                    leading += 1;
+                }
+            }
+            if precision == 0 {
+                String::new()
+            } else {
+                format!("{fraction:0precision$x}")
+            }
+        }
+        Some(precision) => format!("{fraction:016x}{}", "0".repeat(precision - 16)),
+        None => format!("{fraction:016x}").trim_end_matches('0').to_string(),
+    };
+    let point = if digits.is_empty() && !alternate {
+        ""
+    } else {
+        "."
+    };
+    let sign = if exponent < 0 { '-' } else { '+' };
+    format!(
+        "0x{leading:x}{point}{digits}p{sign}{}",
+        exponent.unsigned_abs()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{format_float, format_integer, FloatStyle, IntegerBase};
+
+    fn float(value: f64, style: FloatStyle, precision: Option<usize>) -> String {
+        format_float(value, style, false, precision, false)
+    }
+
+    #[test]
+    fn test_format_integer() {
+        let int = |magnitude, base, precision, alternate| {
+            let (prefix, digits) = format_integer(magnitude, base, false, precision, alternate);
+            format!("{prefix}{digits}")
+        };
+        assert_eq!(int(42, IntegerBase::Decimal, None, false), "42");
+        assert_eq!(int(42, IntegerBase::Decimal, Some(4), false), "0042");
+        assert_eq!(int(0, IntegerBase::Decimal, Some(0), false), "");
+        assert_eq!(int(8, IntegerBase::Octal, None, true), "010");
+        assert_eq!(int(0, IntegerBase::Octal, Some(0), true), "0");
+        assert_eq!(int(255, IntegerBase::Hexadecimal, None, true), "0xff");
+        assert_eq!(int(0, IntegerBase::Hexadecimal, None, true), "0");
+    }
+
+    #[test]
+    fn test_format_float() {
+        assert_eq!(float(1.5, FloatStyle::Decimal, None), "1.500000");
+        assert_eq!(float(2.5, FloatStyle::Decimal, Some(0)), "2");
+        assert_eq!(
+            float(0.000123, FloatStyle::Scientific, None),
+            "1.230000e-04"
+        );
+        assert_eq!(float(1e100, FloatStyle::Scientific, Some(1)), "1.0e+100");
+        assert_eq!(float(0.0001, FloatStyle::Shortest, None), "0.0001");
+        assert_eq!(float(0.00001, FloatStyle::Shortest, None), "1e-05");
+        assert_eq!(float(123456.0, FloatStyle::Shortest, None), "123456");
+        assert_eq!(float(1234567.0, FloatStyle::Shortest, None), "1.23457e+06");
+        assert_eq!(float(999999.5, FloatStyle::Shortest, None), "1e+06");
+        assert_eq!(float(100.0, FloatStyle::Shortest, Some(0)), "1e+02");
+        assert_eq!(
+            format_float(1.0, FloatStyle::Shortest, false, None, true),
+            "1.00000"
+        );
+        assert_eq!(
+            format_float(f64::INFINITY, FloatStyle::Decimal, true, None, false),
+            "INF"
+        );
+    }
+
+    #[test]
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    fn test_format_hexadecimal() {
+        assert_eq!(float(0.875, FloatStyle::Hexadecimal, None), "0xep-4");
+        assert_eq!(float(1.0, FloatStyle::Hexadecimal, None), "0x8p-3");
+        assert_eq!(
+            float(0.1, FloatStyle::Hexadecimal, None),
+            "0xc.cccccccccccdp-7"
+        );
+        assert_eq!(float(0.1, FloatStyle::Hexadecimal, Some(2)), "0xc.cdp-7");
+        assert_eq!(float(1.0, FloatStyle::Hexadecimal, Some(2)), "0x8.00p-3");
+        assert_eq!(float(0.0, FloatStyle::Hexadecimal, None), "0x0p+0");
+        assert_eq!(float(5e-324, FloatStyle::Hexadecimal, None), "0x8p-1077");
+    }
+
+    #[test]
+    #[cfg(not(any(target_arch = "x86", target_arch = "x86_64")))]
+    fn test_format_hexadecimal() {
+        assert_eq!(float(0.875, FloatStyle::Hexadecimal, None), "0x1.cp-1");
+        assert_eq!(float(1.0, FloatStyle::Hexadecimal, None), "0x1p+0");
+        assert_eq!(float(1.5, FloatStyle::Hexadecimal, Some(0)), "0x2p+0");
+    }
+}
@@ -0,0 +1,467 @@
+// This file is part of the uutils coreutils package.
+//
+// For the full copyright and license information, please view the LICENSE
+// file that was distributed with this source code.
+
+//! Conversion specifications like `%-10s`, and how they format their
+//! arguments
+
+// spell-checker:ignore strftime localtime
+
+use std::io::{self, Write};
+use std::ops::ControlFlow;
+
+use super::argument::FormatArguments;
+use super::escape::{unescape_all, EscapeStyle};
+use super::num_format::{format_float, format_integer, FloatStyle, IntegerBase};
+use super::FormatError;
+use crate::error::UResult;
+use crate::quoting_style::{escape_name, QuotingStyle};
+
+/// A width or precision, given in the format or taken from the arguments
+/// with `*`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CanAsterisk<T> {
+    Fixed(T),
+    Asterisk,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+struct Flags {
+    /// `-`: pad on the right
+    left: bool,
+    /// `+`: always write the sign
+    plus: bool,
+    /// ` `: write a space instead of a plus sign
+    space: bool,
+    /// `#`: the alternate form
+    alternate: bool,
+    /// `0`: pad numbers with zeros
+    zero: bool,
+}
+
+/// What a specification converts its argument to.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Conversion {
+    /// `%d` and `%i`
+    SignedInt,
+    /// `%u`, `%o`, `%x` and `%X`
+    UnsignedInt(IntegerBase, bool),
+    /// `%f`, `%e`, `%g` and `%a`, and their uppercase versions
+    Float(FloatStyle, bool),
+    /// `%c`
+    Char,
+    /// `%s`
+    String,
+    /// `%b`, a string with backslash escapes
+    EscapedString,
+    /// `%q`, a string quoted for the shell
+    Quoted,
+    /// `%(FORMAT)T`, a time formatted like strftime does
+    Time(String),
+}
+
+/// A conversion specification, like `%-5.2f`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Spec {
+    flags: Flags,
+    width: Option<CanAsterisk<usize>>,
+    precision: Option<CanAsterisk<usize>>,
+    conversion: Conversion,
+}
+
+/// Take the decimal digits from the start of `input`, with a value of 0 if
+/// there are none.
+fn take_number(input: &mut &[u8]) -> Option<usize> {
+    let len = input.iter().take_while(|b| b.is_ascii_digit()).count();
+    let digits = std::str::from_utf8(&input[..len]).unwrap();
+    *input = &input[len..];
+    if digits.is_empty() {
+        Some(0)
+    } else {
+        digits.parse().ok()
+    }
+}
+
+/// Take a width or precision from the start of `input`.
+fn take_can_asterisk(input: &mut &[u8]) -> Option<CanAsterisk<usize>> {
+    if let Some(rest) = input.strip_prefix(b"*") {
+        *input = rest;
+        Some(CanAsterisk::Asterisk)
+    } else {
+        take_number(input).map(CanAsterisk::Fixed)
+    }
+}
+
+impl Spec {
+    /// Parse the specification at the start of `input`, which follows a `%`,
+    /// and advance `input` past it.
+    pub(crate) fn parse(input: &mut &[u8]) -> Result<Self, FormatError> {
+        let start = *input;
+        let invalid = |rest: &[u8]| {
+            // The specification is reported up to its conversion character.
+            let len = start.len() - rest.len();
+            let end = std::str::from_utf8(rest)
+                .ok()
+                .and_then(|rest| rest.chars().next())
+                .map_or(0, char::len_utf8);
+            FormatError::InvalidSpec(format!("%{}", String::from_utf8_lossy(&start[..len + end])))
+        };
+
+        // Unlike the other conversions, `%b` and `%q` take no flags.
+        for (c, conversion) in [
+            (b'b', Conversion::EscapedString),
+            (b'q', Conversion::Quoted),
+        ] {
+            if let Some(rest) = input.strip_prefix(&[c]) {
+                *input = rest;
+                return Ok(Self {
+                    flags: Flags::default(),
+                    width: None,
+                    precision: None,
+                    conversion,
+                });
+            }
+        }
+
+        let mut flags = Flags::default();
+        let mut grouping = false;
+        while let Some((&c, rest)) = input.split_first() {
+            match c {
+                b'-' => flags.left = true,
+                b'+' => flags.plus = true,
+                b' ' => flags.space = true,
+                b'#' => flags.alternate = true,
+                b'0' => flags.zero = true,
+                // Digit grouping, which no locale does in the C locale.
+                b'\'' | b'I' => grouping = true,
+                _ => break,
+            }
+            *input = rest;
+        }
+        let width = match take_can_asterisk(input) {
+            Some(CanAsterisk::Fixed(0)) => None,
+            Some(width) => Some(width),
+            None => return Err(invalid(input)),
+        };
+        let precision = match input.strip_prefix(b".") {
+            Some(rest) => {
+                *input = rest;
+                Some(take_can_asterisk(input).ok_or_else(|| invalid(input))?)
+            }
+            None => None,
+        };
+        while let Some(rest) = input.strip_prefix(b"hlLjtz".as_slice()).or_else(|| {
+            input
+                .split_first()
+                .and_then(|(c, rest)| b"hlLjtz".contains(c).then(|| rest))
+        }) {
+            *input = rest;
+        }
+
+        let (&c, rest) = input.split_first().ok_or_else(|| invalid(input))?;
+        let conversion = match c {
+            b'd' | b'i' => Conversion::SignedInt,
+            b'u' => Conversion::UnsignedInt(IntegerBase::Decimal, false),
+            b'o' => Conversion::UnsignedInt(IntegerBase::Octal, false),
+            b'x' | b'X' => Conversion::UnsignedInt(IntegerBase::Hexadecimal, c == b'X'),
+            b'f' | b'F' => Conversion::Float(FloatStyle::Decimal, c == b'F'),
+            b'e' | b'E' => Conversion::Float(FloatStyle::Scientific, c == b'E'),
+            b'g' | b'G' => Conversion::Float(FloatStyle::Shortest, c == b'G'),
+            b'a' | b'A' => Conversion::Float(FloatStyle::Hexadecimal, c == b'A'),
+            b'c' => Conversion::Char,
+            b's' => Conversion::String,
+            b'(' if cfg!(unix) => {
+                let end = rest
+                    .iter()
+                    .position(|&b| b == b')')
+                    .filter(|&end| rest.get(end + 1) == Some(&b'T'))
+                    .ok_or_else(|| invalid(input))?;
+                let time_format = String::from_utf8_lossy(&rest[..end]).into_owned();
+                *input = &rest[end + 1..];
+                Conversion::Time(time_format)
+            }
+            _ => return Err(invalid(input)),
+        };
+        // Reject the flags that make no sense for the conversion, like GNU
+        // printf does.
+        let allowed = match c {
+            b'a' | b'A' | b'e' | b'E' | b'o' | b'x' | b'X' => !grouping,
+            b'd' | b'i' | b'u' => !flags.alternate,
+            b'c' => !grouping && !flags.alternate && !flags.zero && precision.is_none(),
+            b's' | b'(' => !grouping && !flags.alternate && !flags.zero,
+            _ => true,
+        };
+        if !allowed {
+            return Err(invalid(input));
+        }
+        *input = &input[1..];
+        Ok(Self {
+            flags,
+            width,
+            precision,
+            conversion,
+        })
+    }
+
+    /// Write the conversion of the arguments it takes from `args`.
+    ///
+    /// The flow breaks if `%b` found `\c` in its argument, after which
+    /// nothing more is printed.
+    pub(crate) fn write(
+        &self,
+        writer: &mut impl Write,
+        args: &mut FormatArguments,
+    ) -> UResult<ControlFlow<()>> {
+        let mut left = self.flags.left;
+        let width = match self.width {
+            Some(CanAsterisk::Fixed(width)) => width,
+            Some(CanAsterisk::Asterisk) => {
+                let arg = args.next_arg();
+                let width = arg.as_deref().map_or(0, args_value);
+                // A negative width pads on the right.
+                left |= width < 0;
+                usize::try_from(width.unsigned_abs())
+                    .ok()
+                    .filter(|_| i32::try_from(width).is_ok())
+                    .ok_or_else(|| FormatError::InvalidWidth(arg.unwrap().to_string()))?
+            }
+            None => 0,
+        };
+        let precision = match self.precision {
+            Some(CanAsterisk::Fixed(precision)) => Some(precision),
+            Some(CanAsterisk::Asterisk) => {
+                let arg = args.next_arg();
+                let precision = arg.as_deref().map_or(0, args_value);
+                // A negative precision is as if there were none.
+                if precision < 0 {
+                    None
+                } else if precision > i64::from(i32::MAX) {
+                    return Err(FormatError::InvalidPrecision(arg.unwrap().to_string()).into());
+                } else {
+                    Some(precision as usize)
+                }
+            }
+            None => None,
+        };
+        let pad = Padding { width, left };
+
+        match &self.conversion {
+            Conversion::SignedInt => {
+                let value = args.next_i64();
+                let (_, digits) = format_integer(
+                    value.unsigned_abs(),
+                    IntegerBase::Decimal,
+                    false,
+                    precision,
+                    false,
+                );
+                let sign = self.sign(value < 0);
+                let zero = self.flags.zero && precision.is_none();
+                pad.write_number(writer, sign, &digits, zero)?;
+            }
+            Conversion::UnsignedInt(base, upper) => {
+                let value = args.next_u64();
+                let (prefix, digits) =
+                    format_integer(value, *base, *upper, precision, self.flags.alternate);
+                let zero = self.flags.zero && precision.is_none();
+                pad.write_number(writer, prefix, &digits, zero)?;
+            }
+            Conversion::Float(..) => {
+                let value = args.next_f64();
+                self.write_float_with(writer, value, pad, precision)?;
+            }
+            Conversion::Char => {
+                // Only the first byte is written, like the C library does.
+                let byte = args.next_bytes().first().copied().unwrap_or(b'\0');
+                pad.write(writer, &[byte])?;
+            }
+            Conversion::String => {
+                let s = args.next_bytes();
+                pad.write(writer, &s[..precision.map_or(s.len(), |p| p.min(s.len()))])?;
+            }
+            Conversion::EscapedString => {
+                let mut unescaped = Vec::new();
+                let flow = unescape_all(&args.next_bytes(), EscapeStyle::Argument, &mut unescaped)?;
+                writer.write_all(&unescaped)?;
+                return Ok(flow);
+            }
+            Conversion::Quoted => {
+                let style = QuotingStyle::Shell {
+                    escape: true,
+                    always_quote: false,
+                    show_control: false,
+                };
+                let quoted = escape_name(args.next_os().unwrap_or_default(), &style);
+                writer.write_all(quoted.as_bytes())?;
+            }
+            Conversion::Time(time_format) => {
+                // No argument, -1 and -2 stand for the current time, where
+                // shells take -2 for the time they were started.
+                let time = args.next_arg().as_deref().map_or(-1, args_value);
+                let s = format_time(time_format, time);
+                let s = s.as_bytes();
+                pad.write(writer, &s[..precision.map_or(s.len(), |p| p.min(s.len()))])?;
+            }
+        }
+        Ok(ControlFlow::Continue(()))
+    }
+
+    /// Write `value` like a floating point conversion, with the width and
+    /// precision given in the format.
+    pub fn write_float(&self, writer: &mut impl Write, value: f64) -> io::Result<()> {
+        let width = match self.width {
+            Some(CanAsterisk::Fixed(width)) => width,
+            _ => 0,
+        };
+        let precision = match self.precision {
+            Some(CanAsterisk::Fixed(precision)) => Some(precision),
+            _ => None,
+        };
+        let pad = Padding {
+            width,
+            left: self.flags.left,
+        };
+        self.write_float_with(writer, value, pad, precision)
+    }
+
+    fn write_float_with(
+        &self,
+        writer: &mut impl Write,
+        value: f64,
+        pad: Padding,
+        precision: Option<usize>,
+    ) -> io::Result<()> {
+        let (style, upper) = match self.conversion {
+            Conversion::Float(style, upper) => (style, upper),
+            _ => (FloatStyle::Decimal, false),
+        };
+        let digits = format_float(value.abs(), style, upper, precision, self.flags.alternate);
+        let sign = self.sign(value.is_sign_negative());
+        // Infinity and not-a-number are padded with spaces.
+        let zero = self.flags.zero && value.is_finite();
+        match digits
+            .strip_prefix("0x")
+            .or_else(|| digits.strip_prefix("0X"))
+        {
+            // The zeros go after the prefix of hexadecimal numbers.
+            Some(rest) => pad.write_number(writer, &format!("{sign}{}", &digits[..2]), rest, zero),
+            None => pad.write_number(writer, sign, &digits, zero),
+        }
+    }
+
+    /// The sign to write before a number.
+    fn sign(&self, negative: bool) -> &'static str {
+        if negative {
+            "-"
+        } else if self.flags.plus {
+            "+"
+        } else if self.flags.space {
+            " "
+        } else {
+            ""
+        }
+    }
+}
+
+/// The value of a width, precision or time argument.
+fn args_value(arg: &str) -> i64 {
+    FormatArguments::new(&[arg.to_string()]).next_i64()
+}
+
+/// How a conversion is padded to its width.
+#[derive(Clone, Copy)]
+struct Padding {
+    width: usize,
+    left: bool,
+}
+
+impl Padding {
+    /// Write `s`, padded with spaces.
+    fn write(&self, writer: &mut impl Write, s: &[u8]) -> io::Result<()> {
+        let fill = " ".repeat(self.width.saturating_sub(s.len()));
+        if self.left {
+            writer.write_all(s)?;
+            writer.write_all(fill.as_bytes())
+        } else {
+            writer.write_all(fill.as_bytes())?;
+            writer.write_all(s)
+        }
+    }
+
+    /// Write a number, where padding with zeros goes between its sign or
+    /// prefix and its digits.
+    fn write_number(
+        &self,
+        writer: &mut impl Write,
+        prefix: &str,
+        digits: &str,
+        zero: bool,
+    ) -> io::Result<()> {
+        if zero && !self.left {
+            let len = prefix.len() + digits.len();
+            let zeros = "0".repeat(self.width.saturating_sub(len));
+            write!(writer, "{prefix}{zeros}{digits}")
+        } else {
+            self.write(writer, format!("{prefix}{digits}").as_bytes())
+        }
+    }
+}
+
+/// Format the time `time`, in seconds since the epoch or -1 or -2 for the
+/// current time, like strftime does with `time_format`, which defaults to the
+/// time of day.
+#[cfg(unix)]
+fn format_time(time_format: &str, time: i64) -> String {
+    use std::ffi::CString;
+    use std::mem::MaybeUninit;
+
+    let time = if time == -1 || time == -2 {
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs() as libc::time_t)
+    } else {
+        time as libc::time_t
+    };
+    let time_format = if time_format.is_empty() {
+        "%X"
+    } else {
+        time_format
+    };
+    // A leading space makes the result non-empty, so that strftime only
+    // returns 0 when the buffer is too small.
+    let time_format = match CString::new(format!(" {time_format}")) {
+        Ok(time_format) => time_format,
+        Err(_) => return String::new(),
+    };
+    let mut buffer = vec![0u8; 256];
+    // SAFETY: localtime_r fills in `tm` when it succeeds, and strftime
+    // writes at most `buffer.len()` bytes to `buffer`.
+    unsafe {
+        let mut tm = MaybeUninit::<libc::tm>::zeroed();
+        if libc::localtime_r(&time, tm.as_mut_ptr()).is_null() {
+            return String::new();
+        }
+        loop {
+            let len = libc::strftime(
+                buffer.as_mut_ptr().cast(),
+                buffer.len(),
+                time_format.as_ptr(),
+                tm.as_ptr(),
+            );
+            if len > 0 {
+                return String::from_utf8_lossy(&buffer[1..len]).into_owned();
+            }
+            if buffer.len() >= 1 << 20 {
+                return String::new();
+            }
+            buffer.resize(buffer.len() * 2, 0);
+        }
+    }
+}
+
+#[cfg(not(unix))]
+fn format_time(_time_format: &str, _time: i64) -> String {
+    // Specifications of times are only parsed on Unix.
+    unreachable!()
+}
@@ -123,7 +123,8 @@ pub use libc::statvfs as statfs_fn;
 
 pub trait BirthTime {
     fn pretty_birth(&self) -> String;
-    fn birth(&self) -> u64;
+    /// seconds and nanoseconds since the Epoch, if the birth time is known
+    fn birth(&self) -> Option<(u64, u32)>;
 }
 
 use std::fs::Metadata;
@@ -136,12 +137,11 @@ impl BirthTime for Metadata {
             .unwrap_or_else(|| "-".to_owned())
     }
 
-    fn birth(&self) -> u64 {
+    fn birth(&self) -> Option<(u64, u32)> {
         self.created()
             .ok()
             .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
-            .map(|e| e.as_secs())
-            .unwrap_or_default()
+            .map(|e| (e.as_secs(), e.subsec_nanos()))
     }
 }
 
@@ -9,125 +9,215 @@
 
 // spell-checker:ignore (vars) fperm srwx
 
+use crate::display::Quotable;
 use libc::{mode_t, umask, S_IRGRP, S_IROTH, S_IRUSR, S_IWGRP, S_IWOTH, S_IWUSR};
 
-pub fn parse_numeric(fperm: u32, mut mode: &str, considering_dir: bool) -> Result<u32, String> {
-    let (op, pos) = parse_op(mode).map_or_else(|_| (None, 0), |(op, pos)| (Some(op), pos));
-    mode = mode[pos..].trim();
-    let change = if mode.is_empty() {
-        0
-    } else {
-        u32::from_str_radix(mode, 8).map_err(|e| e.to_string())?
-    };
-    if change > 0o7777 {
-        Err(format!("mode is too large ({change} > 7777"))
-    } else {
-        Ok(match op {
-            Some('+') => fperm | change,
-            Some('-') => fperm & !change,
-            // If this is a directory, we keep the setgid and setuid bits,
-            // unless the mode contains 5 or more octal digits or the mode is "="
-            None if considering_dir && mode.len() < 5 => change | (fperm & (0o4000 | 0o2000)),
-            None | Some('=') => change,
-            Some(_) => unreachable!(),
-        })
-    }
+/// The bits that a MODE can change: the permission bits, set-user-ID,
+/// set-group-ID and sticky.
+const MODE_BITS: u32 = 0o7777;
+const SET_ID_BITS: u32 = 0o6000;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Flag {
+    /// Change exactly the bits in `value`.
+    Ordinary,
+    /// Change the bits in `value` that are set in the existing mode, copied
+    /// to all of user, group and other, as with `g=u`.
+    CopyExisting,
+    /// Also change the execute bits if the file is a directory or already has
+    /// one of them set, as with `X`.
+    XIfAnyX,
 }
 
-pub fn parse_symbolic(
-    mut fperm: u32,
-    mut mode: &str,
-    umask: u32,
-    considering_dir: bool,
-) -> Result<u32, String> {
-    let (mask, pos) = parse_levels(mode);
-    if pos == mode.len() {
-        return Err(format!("invalid mode ({mode})"));
-    }
-    let respect_umask = pos == 0;
-    mode = &mode[pos..];
-    while !mode.is_empty() {
-        let (op, pos) = parse_op(mode)?;
-        mode = &mode[pos..];
-        let (mut srwx, pos) = parse_change(mode, fperm, considering_dir);
-        if respect_umask {
-            srwx &= !umask;
-        }
-        mode = &mode[pos..];
-        match op {
-            '+' => fperm |= srwx & mask,
-            '-' => fperm &= !(srwx & mask),
-            '=' => {
-                if considering_dir {
-                    // keep the setgid and setuid bits for directories
-                    srwx |= fperm & (0o4000 | 0o2000);
-                }
-                fperm = (fperm & !mask) | (srwx & mask);
-            }
-            _ => unreachable!(),
-        }
-    }
-    Ok(fperm)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Change {
+    /// One of `+`, `-` and `=`.
+    op: char,
+    flag: Flag,
+    /// The bits selected with `[ugoa]`, or 0 to apply the umask instead.
+    affected: u32,
+    value: u32,
+    /// The bits asked for explicitly, which are the only set-ID bits that
+    /// are changed for directories.
+    mentioned: u32,
 }
 
-fn parse_levels(mode: &str) -> (u32, usize) {
-    let mut mask = 0;
-    let mut pos = 0;
-    for ch in mode.chars() {
-        mask |= match ch {
-            'u' => 0o4700,
-            'g' => 0o2070,
-            'o' => 0o1007,
-            'a' => 0o7777,
-            _ => break,
-        };
-        pos += 1;
-    }
-    if pos == 0 {
-        mask = 0o7777; // default to 'a'
-    }
-    (mask, pos)
+/// A parsed MODE, such as `u+x,go=rX` or `644`, that can be applied to
+/// existing file modes.
+///
+/// This follows gnulib's `mode_compile` and `mode_adjust`, which GNU chmod,
+/// install, mkdir, mkfifo and mknod all share.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Mode {
+    changes: Vec<Change>,
 }
 
-fn parse_op(mode: &str) -> Result<(char, usize), String> {
-    let ch = mode
-        .chars()
-        .next()
-        .ok_or_else(|| "unexpected end of mode".to_owned())?;
-    match ch {
-        '+' | '-' | '=' => Ok((ch, 1)),
-        _ => Err(format!(
-            "invalid operator (expected +, -, or =, but found {ch})"
-        )),
-    }
+/// Parse an octal number that fits in the mode bits, returning the rest of
+/// the string.
+fn parse_octal(s: &str) -> Option<(u32, &str)> {
+    let end = s.find(|c| !('0'..='7').contains(&c)).unwrap_or(s.len());
+    let value = u32::from_str_radix(&s[..end], 8).ok()?;
+    (value <= MODE_BITS).then(|| (value, &s[end..]))
 }
 
-fn parse_change(mode: &str, fperm: u32, considering_dir: bool) -> (u32, usize) {
-    let mut srwx = 0;
-    let mut pos = 0;
-    for ch in mode.chars() {
-        match ch {
-            'r' => srwx |= 0o444,
-            'w' => srwx |= 0o222,
-            'x' => srwx |= 0o111,
-            'X' => {
-                if considering_dir || (fperm & 0o0111) != 0 {
-                    srwx |= 0o111;
+impl Mode {
+    /// Parse a MODE of the form
+    /// `[ugoa]*([-+=]([rwxXst]*|[ugo]))+|[-+=]?[0-7]+`, with clauses
+    /// separated by commas.
+    ///
+    /// The error message reads like "invalid mode 'MODE'".
+    pub fn parse(mode: &str) -> Result<Self, String> {
+        let invalid = || format!("invalid mode {}", mode.quote());
+
+        if mode.starts_with(|c| ('0'..='7').contains(&c)) {
+            let value = match parse_octal(mode) {
+                Some((value, "")) => value,
+                _ => return Err(invalid()),
+            };
+            // Like GNU, the set-ID bits of directories are only cleared by
+            // an octal mode with five or more digits.
+            let mentioned = if mode.len() < 5 {
+                (value & SET_ID_BITS) | (MODE_BITS & !SET_ID_BITS)
+            } else {
+                MODE_BITS
+            };
+            let change = Change {
+                op: '=',
+                flag: Flag::Ordinary,
+                affected: MODE_BITS,
+                value,
+                mentioned,
+            };
+            return Ok(Self {
+                changes: vec![change],
+            });
+        }
+
+        let mut changes = Vec::new();
+        for clause in mode.split(',') {
+            let is_op = |c| matches!(c, '+' | '-' | '=');
+            let (who, mut rest) = clause.split_at(clause.find(is_op).ok_or_else(invalid)?);
+            let mut affected = 0;
+            for c in who.chars() {
+                affected |= match c {
+                    'u' => 0o4700,
+                    'g' => 0o2070,
+                    'o' => 0o1007,
+                    'a' => MODE_BITS,
+                    _ => return Err(invalid()),
+                };
+            }
+            while let Some(op) = rest.chars().next().filter(|&c| is_op(c)) {
+                rest = &rest[1..];
+                let mut flag = Flag::Ordinary;
+                let mut value = 0;
+                let mut mentioned = None;
+                match rest.chars().next() {
+                    Some('0'..='7') => {
+                        // An octal number is only allowed on its own, as in `+755`.
+                        match parse_octal(rest) {
+                            Some((octal, "")) if affected == 0 => value = octal,
+                            _ => return Err(invalid()),
+                        }
+                        rest = "";
+                        affected = MODE_BITS;
+                        mentioned = Some(MODE_BITS);
+                    }
+                    Some(c @ ('u' | 'g' | 'o')) => {
+                        rest = &rest[1..];
+                        flag = Flag::CopyExisting;
+                        value = match c {
+                            'u' => 0o700,
+                            'g' => 0o070,
+                            _ => 0o007,
+                        };
+                    }
+                    _ => {
+                        let end = rest.find(|c| !"rwxXst".contains(c)).unwrap_or(rest.len());
+                        for c in rest[..end].chars() {
+                            match c {
+                                'r' => value |= 0o444,
+                                'w' => value |= 0o222,
+                                'x' => value |= 0o111,
+                                'X' => flag = Flag::XIfAnyX,
+                                's' => value |= SET_ID_BITS,
+                                _ => value |= 0o1000,
+                            }
+                        }
+                        rest = &rest[end..];
+                    }
                 }
+                let mentioned = mentioned.unwrap_or(if affected == 0 {
+                    value
+                } else {
+                    affected & value
+                });
+                changes.push(Change {
+                    op,
+                    flag,
+                    affected,
+                    value,
+                    mentioned,
+                });
+            }
+            if !rest.is_empty() {
+                return Err(invalid());
             }
-            's' => srwx |= 0o4000 | 0o2000,
-            't' => srwx |= 0o1000,
-            'u' => srwx = (fperm & 0o700) | ((fperm >> 3) & 0o070) | ((fperm >> 6) & 0o007),
-            'g' => srwx = ((fperm << 3) & 0o700) | (fperm & 0o070) | ((fperm >> 3) & 0o007),
-            'o' => srwx = ((fperm << 6) & 0o700) | ((fperm << 3) & 0o070) | (fperm & 0o007),
-            _ => break,
-        };
-        pos += 1;
+        }
+        Ok(Self { changes })
     }
-    if pos == 0 {
-        srwx = 0;
+
+    /// Apply the MODE to the existing `mode` of a file, returning the new one.
+    ///
+    /// `umask` restricts the clauses that do not say which of `[ugoa]` they
+    /// change, like `+x`.
+    pub fn apply(&self, mode: u32, is_dir: bool, umask: u32) -> u32 {
+        let mut new_mode = mode & MODE_BITS;
+        for change in &self.changes {
+            let omitted = if is_dir {
+                SET_ID_BITS & !change.mentioned
+            } else {
+                0
+            };
+            let mut value = change.value;
+            match change.flag {
+                Flag::Ordinary => {}
+                Flag::CopyExisting => {
+                    value &= new_mode;
+                    for bits in [0o444, 0o222, 0o111] {
+                        if value & bits != 0 {
+                            value |= bits;
+                        }
+                    }
+                }
+                Flag::XIfAnyX => {
+                    if is_dir || new_mode & 0o111 != 0 {
+                        value |= 0o111;
+                    }
+                }
+            }
+            let affected = if change.affected == 0 {
+                !umask
+            } else {
+                change.affected
+            };
+            value &= affected & !omitted;
+            match change.op {
+                '+' => new_mode |= value,
+                '-' => new_mode &= !value,
+                _ => {
+                    // Without `[ugoa]`, `=` clears all bits not omitted.
+                    let preserved = if change.affected == 0 {
+                        omitted
+                    } else {
+                        !change.affected | omitted
+                    };
+                    new_mode = (new_mode & preserved) | value;
+                }
+            }
+        }
+        new_mode
     }
-    (srwx, pos)
 }
 
 pub fn parse_mode(mode: &str) -> Result<mode_t, String> {
@@ -140,13 +230,7 @@ pub fn parse_mode(mode: &str) -> Result<mode_t, String> {
     #[cfg(any(target_os = "freebsd", target_vendor = "apple", target_os = "android"))]
     let fperm = (S_IRUSR | S_IWUSR | S_IRGRP | S_IWGRP | S_IROTH | S_IWOTH) as u32;
 
-    let arr: &[char] = &['0', '1', '2', '3', '4', '5', '6', '7', '8', '9'];
-    let result = if mode.contains(arr) {
-        parse_numeric(fperm, mode, true)
-    } else {
-        parse_symbolic(fperm, mode, get_umask(), true)
-    };
-    result.map(|mode| mode as mode_t)
+    Mode::parse(mode).map(|m| m.apply(fperm, false, get_umask()) as mode_t)
 }
 
 pub fn get_umask() -> u32 {
@@ -212,4 +296,56 @@ mod test {
         assert_eq!(super::parse_mode("+100").unwrap(), 0o766);
         assert_eq!(super::parse_mode("-4").unwrap(), 0o662);
     }
+
+    fn apply(mode: &str, old: u32, is_dir: bool) -> u32 {
+        super::Mode::parse(mode).unwrap().apply(old, is_dir, 0o022)
+    }
+
+    #[test]
+    fn clauses() {
+        assert_eq!(apply("u=rwx,go=rx", 0, false), 0o755);
+        assert_eq!(apply("=+x,o-x", 0o644, false), 0o110);
+        assert_eq!(apply("a=,u+w", 0o777, false), 0o200);
+        assert_eq!(apply("u=rw,g=u", 0o600, false), 0o660);
+        assert_eq!(apply("go-u", 0o777, false), 0o700);
+        assert_eq!(apply("ug+s,+t", 0o660, false), 0o7660);
+        assert_eq!(apply("=755", 0, false), 0o755);
+        assert_eq!(apply("=", 0o777, false), 0);
+    }
+
+    #[test]
+    fn conditional_execute() {
+        assert_eq!(apply("a+X", 0o644, false), 0o644);
+        assert_eq!(apply("a+X", 0o744, false), 0o755);
+        assert_eq!(apply("a+X", 0o644, true), 0o755);
+        assert_eq!(apply("go=rX", 0o700, false), 0o755);
+        assert_eq!(apply("go=rX", 0o600, false), 0o644);
+        // The execute bits are checked clause by clause.
+        assert_eq!(apply("a-x,a+X", 0o755, false), 0o644);
+        assert_eq!(apply("u+x,go+X", 0o644, false), 0o755);
+    }
+
+    #[test]
+    fn directory_set_id_bits() {
+        assert_eq!(apply("755", 0o6755, true), 0o6755);
+        assert_eq!(apply("755", 0o6755, false), 0o755);
+        assert_eq!(apply("00755", 0o6755, true), 0o755);
+        assert_eq!(apply("u=rwx,go=rx", 0o2755, true), 0o2755);
+        assert_eq!(apply("g-s", 0o2755, true), 0o755);
+        assert_eq!(apply("=", 0o2755, true), 0o2000);
+    }
+
+    #[test]
+    fn invalid_modes() {
+        for mode in [
+            "", "u", "a+x,", ",a+x", "x+", "u+z", "u=gw", "755,u+s", "u=755", "+7777x", "8",
+            "17777",
+        ] {
+            assert_eq!(
+                super::Mode::parse(mode),
+                Err(format!("invalid mode '{mode}'")),
+                "{mode}"
+            );
+        }
+    }
 }
@@ -0,0 +1,1071 @@
+// This file is part of the uutils coreutils package.
+//
+// For the full copyright and license information, please view the LICENSE
+// file that was distributed with this source code.
+
+// spell-checker:ignore (words) fortnight fortnights wednes thur thurs sept meridian
+//! Parsing of human-readable date strings, like GNU's `parse_datetime`.
+//!
+//! Use [`parse_datetime`] to parse strings like "2 days ago", "next week",
+//! "2020-03-12 10:00 +0100" or "@1584000000" relative to the current time,
+//! or [`parse_datetime_at`] to parse them relative to another time.
+//!
+//! A date string is a sequence of items, separated by white space:
+//!
+//! * calendar dates, like `2020-03-12`, `3/12/2020`, `12 mar 2020` or
+//!   `March 12, 2020`;
+//! * times of day, like `10:00`, `10:00:30.5`, `10am` or `10:00+0100`;
+//! * time zones, like `UTC`, `EST`, `CEST DST` or the military `Z`, which
+//!   may be followed by an offset;
+//! * days of the week, like `thursday` or `next monday`;
+//! * relative items, like `-3 hours`, `2 days ago`, `last month` or
+//!   `tomorrow`;
+//! * plain numbers, which are read as a year, a date or a time of day
+//!   depending on what came before them.
+//!
+//! A string made of a single `@` and a number of seconds since the Epoch is
+//! also accepted. Anything in parentheses is a comment.
+
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+
+use time::{Date, Duration, Month, OffsetDateTime, PrimitiveDateTime, Time, UtcOffset};
+
+/// The error returned when a date string can't be parsed, or when the date
+/// it describes can't be represented.
+#[derive(Debug, PartialEq, Eq)]
+pub struct ParseDateTimeError;
+
+impl Display for ParseDateTimeError {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        write!(f, "invalid date")
+    }
+}
+
+impl Error for ParseDateTimeError {}
+
+type ParseResult<T> = Result<T, ParseDateTimeError>;
+
+/// Parse a date string relative to the current local time.
+///
+/// # Examples
+///
+/// ```rust
+/// use uucore::parse_datetime::parse_datetime;
+/// let date = parse_datetime("2020-03-12 10:00 +0100").unwrap();
+/// assert_eq!(date.unix_timestamp(), 1_584_003_600);
+/// assert!(parse_datetime("noon").is_err());
+/// ```
+pub fn parse_datetime(s: &str) -> ParseResult<OffsetDateTime> {
+    parse_datetime_at(s, OffsetDateTime::now_utc())
+}
+
+/// Parse a date string relative to `now`.
+///
+/// The fields that the string doesn't give, like the date in "10:00", come
+/// from `now` in the local time zone, and dates without a time zone are
+/// local times. The result is in the local time zone too.
+pub fn parse_datetime_at(s: &str, now: OffsetDateTime) -> ParseResult<OffsetDateTime> {
+    let tokens = tokenize(s)?;
+    if let [Token::Char('@'), Token::Number(n)] = &tokens[..] {
+        let nanos = n.total_nanos();
+        return OffsetDateTime::from_unix_timestamp_nanos(nanos)
+            .map(to_local)
+            .map_err(|_| ParseDateTimeError);
+    }
+
+    let mut parser = Parser {
+        tokens: &tokens,
+        pos: 0,
+        items: Items::default(),
+    };
+    while parser.pos < tokens.len() {
+        parser.parse_item()?;
+    }
+    parser.items.resolve(to_local(now))
+}
+
+/// Interpret a date and time in the local time zone.
+pub fn assume_local(dt: PrimitiveDateTime) -> OffsetDateTime {
+    // The offset depends on the instant, which depends on the offset; the
+    // offset at the time read as UTC is right except close to a change.
+    let guess = dt.assume_offset(local_offset_at(dt.assume_utc()));
+    dt.assume_offset(local_offset_at(guess))
+}
+
+fn local_offset_at(t: OffsetDateTime) -> UtcOffset {
+    UtcOffset::local_offset_at(t).unwrap_or(UtcOffset::UTC)
+}
+
+fn to_local(t: OffsetDateTime) -> OffsetDateTime {
+    t.to_offset(local_offset_at(t))
+}
+
+/// A number, with the sign it was written with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Number {
+    signed: bool,
+    negative: bool,
+    value: i64,
+    digits: usize,
+    /// the nanoseconds of a decimal fraction, if the number has one
+    nanos: Option<i64>,
+}
+
+impl Number {
+    fn signed_value(&self) -> i64 {
+        if self.negative {
+            -self.value
+        } else {
+            self.value
+        }
+    }
+
+    fn total_nanos(&self) -> i128 {
+        let nanos = i128::from(self.value) * 1_000_000_000 + i128::from(self.nanos.unwrap_or(0));
+        if self.negative {
+            -nanos
+        } else {
+            nanos
+        }
+    }
+
+    /// an unsigned integer, which most items are made of
+    fn is_plain(&self) -> bool {
+        !self.signed && self.nanos.is_none()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    Number(Number),
+    /// a word in lower case, without periods
+    Word(String),
+    Char(char),
+}
+
+fn tokenize(s: &str) -> ParseResult<Vec<Token>> {
+    let chars: Vec<char> = s.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if c.is_whitespace() {
+            i += 1;
+        } else if c == '(' {
+            // comments nest
+            let mut depth = 0;
+            while i < chars.len() {
+                match chars[i] {
+                    '(' => depth += 1,
+                    ')' => depth -= 1,
+                    _ => {}
+                }
+                i += 1;
+                if depth == 0 {
+                    break;
+                }
+            }
+        } else if c.is_ascii_digit() || c == '+' || c == '-' {
+            let signed = c == '+' || c == '-';
+            if signed {
+                i += 1;
+                while i < chars.len() && chars[i].is_whitespace() {
+                    i += 1;
+                }
+                // a sign that isn't followed by a number is ignored
+                if i == chars.len() || !chars[i].is_ascii_digit() {
+                    continue;
+                }
+            }
+            let start = i;
+            let mut value: i64 = 0;
+            while i < chars.len() && chars[i].is_ascii_digit() {
+                value = value
+                    .checked_mul(10)
+                    .and_then(|v| v.checked_add(i64::from(chars[i] as u8 - b'0')))
+                    .ok_or(ParseDateTimeError)?;
+                i += 1;
+            }
+            let digits = i - start;
+            let mut nanos = None;
+            if i + 1 < chars.len() && matches!(chars[i], '.' | ',') && chars[i + 1].is_ascii_digit()
+            {
+                i += 1;
+                let mut n = 0;
+                let mut scale = 100_000_000;
+                while i < chars.len() && chars[i].is_ascii_digit() {
+                    n += i64::from(chars[i] as u8 - b'0') * scale;
+                    scale /= 10;
+                    i += 1;
+                }
+                nanos = Some(n);
+            }
+            tokens.push(Token::Number(Number {
+                signed,
+                negative: c == '-',
+                value,
+                digits,
+                nanos,
+            }));
+        } else if c.is_alphabetic() {
+            let mut word = String::new();
+            while i < chars.len() && (chars[i].is_alphabetic() || chars[i] == '.') {
+                if chars[i] != '.' {
+                    word.extend(chars[i].to_lowercase());
+                }
+                i += 1;
+            }
+            tokens.push(Token::Word(word));
+        } else {
+            tokens.push(Token::Char(c));
+            i += 1;
+        }
+    }
+    Ok(tokens)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Unit {
+    Year,
+    Month,
+    Day(i64),
+    Second(i64),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Word {
+    Month(u8),
+    /// the day of the week, counted from Sunday
+    Weekday(u8),
+    Unit(Unit),
+    Ordinal(i64),
+    /// "tomorrow", "yesterday", "today" and "now"
+    DayShift(i64),
+    Meridian(Meridian),
+    /// a time zone, with its offset from UTC in seconds
+    Zone(i32),
+    /// "dst" after a time zone, which moves it an hour ahead
+    Dst,
+    Ago,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Meridian {
+    Am,
+    Pm,
+}
+
+const MONTHS: [&str; 12] = [
+    "january",
+    "february",
+    "march",
+    "april",
+    "may",
+    "june",
+    "july",
+    "august",
+    "september",
+    "october",
+    "november",
+    "december",
+];
+
+/// Time zone abbreviations and their offsets from UTC in minutes, as
+/// GNU knows them.
+const ZONES: [(&str, i32); 51] = [
+    ("gmt", 0),
+    ("ut", 0),
+    ("utc", 0),
+    ("wet", 0),
+    ("west", 60),
+    ("bst", 60),
+    ("art", -180),
+    ("brt", -180),
+    ("brst", -120),
+    ("nst", -210),
+    ("ndt", -150),
+    ("ast", -240),
+    ("adt", -180),
+    ("clt", -240),
+    ("clst", -180),
+    ("est", -300),
+    ("edt", -240),
+    ("cst", -360),
+    ("cdt", -300),
+    ("mst", -420),
+    ("mdt", -360),
+    ("pst", -480),
+    ("pdt", -420),
+    ("akst", -540),
+    ("akdt", -480),
+    ("hst", -600),
+    ("hast", -600),
+    ("hadt", -540),
+    ("sst", -720),
+    ("wat", 60),
+    ("cet", 60),
+    ("cest", 120),
+    ("met", 60),
+    ("mez", 60),
+    ("mest", 120),
+    ("mesz", 120),
+    ("eet", 120),
+    ("eest", 180),
+    ("cat", 120),
+    ("sast", 120),
+    ("eat", 180),
+    ("msk", 180),
+    ("msd", 240),
+    ("ist", 330),
+    ("sgt", 480),
+    ("kst", 540),
+    ("jst", 540),
+    ("gst", 600),
+    ("nzst", 720),
+    ("nzdt", 780),
+    ("z", 0),
+];
+
+/// The offset in hours of a military time zone, a single letter other than
+/// "j": "a" to "m" are east of UTC, "n" to "y" west of it and "z" is UTC.
+fn military_zone(word: &str) -> Option<i32> {
+    match word.as_bytes() {
+        [c @ b'a'..=b'i'] => Some(i32::from(c - b'a') + 1),
+        [c @ b'k'..=b'm'] => Some(i32::from(c - b'k') + 10),
+        [c @ b'n'..=b'y'] => Some(-(i32::from(c - b'n') + 1)),
+        _ => None,
+    }
+}
+
+const WEEKDAYS: [&str; 7] = [
+    "sunday",
+    "monday",
+    "tuesday",
+    "wednesday",
+    "thursday",
+    "friday",
+    "saturday",
+];
+
+fn lookup_word(word: &str) -> Option<Word> {
+    // names may be abbreviated to their first three letters
+    let is_name = |name: &str| word == name || (word.len() == 3 && name.starts_with(word));
+    if let Some(i) = MONTHS.iter().position(|m| is_name(m)) {
+        return Some(Word::Month(i as u8 + 1));
+    }
+    if word == "sept" {
+        return Some(Word::Month(9));
+    }
+    if let Some(i) = WEEKDAYS.iter().position(|d| is_name(d)) {
+        return Some(Word::Weekday(i as u8));
+    }
+    match word {
+        "tues" => return Some(Word::Weekday(2)),
+        "wednes" => return Some(Word::Weekday(3)),
+        "thur" | "thurs" => return Some(Word::Weekday(4)),
+        _ => {}
+    }
+
+    let unit = |word: &str| match word {
+        "year" => Some(Unit::Year),
+        "month" => Some(Unit::Month),
+        "fortnight" => Some(Unit::Day(14)),
+        "week" => Some(Unit::Day(7)),
+        "day" => Some(Unit::Day(1)),
+        "hour" => Some(Unit::Second(3600)),
+        "minute" | "min" => Some(Unit::Second(60)),
+        "second" | "sec" => Some(Unit::Second(1)),
+        _ => None,
+    };
+    if let Some(u) = unit(word).or_else(|| word.strip_suffix('s').and_then(unit)) {
+        return Some(Word::Unit(u));
+    }
+
+    let word = match word {
+        "am" => Word::Meridian(Meridian::Am),
+        "pm" => Word::Meridian(Meridian::Pm),
+        "tomorrow" => Word::DayShift(1),
+        "yesterday" => Word::DayShift(-1),
+        "today" | "now" => Word::DayShift(0),
+        "last" => Word::Ordinal(-1),
+        "this" => Word::Ordinal(0),
+        "next" | "first" => Word::Ordinal(1),
+        "third" => Word::Ordinal(3),
+        "fourth" => Word::Ordinal(4),
+        "fifth" => Word::Ordinal(5),
+        "sixth" => Word::Ordinal(6),
+        "seventh" => Word::Ordinal(7),
+        "eighth" => Word::Ordinal(8),
+        "ninth" => Word::Ordinal(9),
+        "tenth" => Word::Ordinal(10),
+        "eleventh" => Word::Ordinal(11),
+        "twelfth" => Word::Ordinal(12),
+        "ago" => Word::Ago,
+        "dst" => Word::Dst,
+        _ => {
+            if let Some((_, minutes)) = ZONES.iter().find(|(name, _)| *name == word) {
+                Word::Zone(minutes * 60)
+            } else {
+                Word::Zone(military_zone(word)? * 3600)
+            }
+        }
+    };
+    Some(word)
+}
+
+/// The distance of the relative items from the base date.
+#[derive(Debug, Default)]
+struct Relative {
+    years: i64,
+    months: i64,
+    days: i64,
+    seconds: i64,
+    nanos: i64,
+}
+
+/// What the items of a date string said.
+#[derive(Debug, Default)]
+struct Items {
+    /// the year, with the number of digits it was written with
+    year: Option<(i64, usize)>,
+    month: i64,
+    day: i64,
+    dates_seen: usize,
+    hour: i64,
+    minute: i64,
+    second: i64,
+    nanos: i64,
+    times_seen: usize,
+    /// the ordinal and the day of the week
+    weekday: Option<(i64, u8)>,
+    days_seen: usize,
+    /// the offset from UTC in seconds
+    zone: Option<i32>,
+    zones_seen: usize,
+    relative: Relative,
+    rels_seen: bool,
+}
+
+impl Items {
+    fn set_date(&mut self, year: Option<&Number>, month: i64, day: i64) {
+        self.year = year.map(|y| (y.value, y.digits));
+        self.month = month;
+        self.day = day;
+        self.dates_seen += 1;
+    }
+
+    fn set_time(&mut self, hour: i64, minute: i64, second: i64, nanos: i64) {
+        self.hour = hour;
+        self.minute = minute;
+        self.second = second;
+        self.nanos = nanos;
+        self.times_seen += 1;
+    }
+
+    fn add_relative(&mut self, unit: Unit, n: i64) -> ParseResult<()> {
+        let rel = &mut self.relative;
+        let (field, per_unit) = match unit {
+            Unit::Year => (&mut rel.years, 1),
+            Unit::Month => (&mut rel.months, 1),
+            Unit::Day(days) => (&mut rel.days, days),
+            Unit::Second(seconds) => (&mut rel.seconds, seconds),
+        };
+        *field = n
+            .checked_mul(per_unit)
+            .and_then(|n| field.checked_add(n))
+            .ok_or(ParseDateTimeError)?;
+        self.rels_seen = true;
+        Ok(())
+    }
+
+    /// Work out the time the items describe.
+    fn resolve(self, now: OffsetDateTime) -> ParseResult<OffsetDateTime> {
+        if self.dates_seen > 1 || self.times_seen > 1 || self.days_seen > 1 || self.zones_seen > 1 {
+            return Err(ParseDateTimeError);
+        }
+
+        let mut date = if self.dates_seen > 0 {
+            let year = match self.year {
+                // two-digit years are 1969 to 2068, as in POSIX
+                Some((y, 2)) if y < 69 => y + 2000,
+                Some((y, 2)) => y + 1900,
+                Some((y, _)) => y,
+                None => i64::from(now.year()),
+            };
+            let year = i32::try_from(year).map_err(|_| ParseDateTimeError)?;
+            let month = u8::try_from(self.month)
+                .ok()
+                .and_then(|m| Month::try_from(m).ok())
+                .ok_or(ParseDateTimeError)?;
+            let day = u8::try_from(self.day).map_err(|_| ParseDateTimeError)?;
+            Date::from_calendar_date(year, month, day).map_err(|_| ParseDateTimeError)?
+        } else {
+            now.date()
+        };
+
+        // a date without a time of day is at midnight, but relative items
+        // alone move from the current time
+        let time = if self.times_seen > 0 {
+            let t = |n: i64| u8::try_from(n).map_err(|_| ParseDateTimeError);
+            let nanos = u32::try_from(self.nanos).map_err(|_| ParseDateTimeError)?;
+            Time::from_hms_nano(t(self.hour)?, t(self.minute)?, t(self.second)?, nanos)
+                .map_err(|_| ParseDateTimeError)?
+        } else if self.rels_seen && self.dates_seen == 0 && self.days_seen == 0 {
+            now.time()
+        } else {
+            Time::MIDNIGHT
+        };
+
+        if let (Some((ordinal, weekday)), 0) = (self.weekday, self.dates_seen) {
+            let today = i64::from(date.weekday().number_days_from_sunday());
+            let weekday = i64::from(weekday);
+            let days = (weekday - today + 7) % 7
+                + 7 * (ordinal - i64::from(ordinal > 0 && today != weekday));
+            date = checked_add_days(date, days)?;
+        }
+
+        let rel = &self.relative;
+        if rel.years != 0 || rel.months != 0 || rel.days != 0 {
+            // like mktime, days past the end of the month spill into the next
+            let months = rel
+                .years
+                .checked_mul(12)
+                .and_then(|m| m.checked_add(rel.months))
+                .and_then(|m| m.checked_add(i64::from(date.year()) * 12))
+                .and_then(|m| m.checked_add(i64::from(u8::from(date.month())) - 1))
+                .ok_or(ParseDateTimeError)?;
+            let year = i32::try_from(months.div_euclid(12)).map_err(|_| ParseDateTimeError)?;
+            let month = Month::try_from(months.rem_euclid(12) as u8 + 1).unwrap();
+            let first = Date::from_calendar_date(year, month, 1).map_err(|_| ParseDateTimeError)?;
+            let days = rel
+                .days
+                .checked_add(i64::from(date.day()) - 1)
+                .ok_or(ParseDateTimeError)?;
+            date = checked_add_days(first, days)?;
+        }
+
+        let dt = PrimitiveDateTime::new(date, time);
+        let result = match self.zone {
+            Some(offset) => {
+                let offset =
+                    UtcOffset::from_whole_seconds(offset).map_err(|_| ParseDateTimeError)?;
+                dt.assume_offset(offset)
+            }
+            None => assume_local(dt),
+        };
+        let shift = Duration::seconds(rel.seconds) + Duration::nanoseconds(rel.nanos);
+        result
+            .checked_add(shift)
+            .map(to_local)
+            .ok_or(ParseDateTimeError)
+    }
+}
+
+fn checked_add_days(date: Date, days: i64) -> ParseResult<Date> {
+    let seconds = days.checked_mul(86_400).ok_or(ParseDateTimeError)?;
+    date.checked_add(Duration::seconds(seconds))
+        .ok_or(ParseDateTimeError)
+}
+
+struct Parser<'a> {
+    tokens: &'a [Token],
+    pos: usize,
+    items: Items,
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self, offset: usize) -> Option<&'a Token> {
+        self.tokens.get(self.pos + offset)
+    }
+
+    fn peek_number(&self, offset: usize) -> Option<&'a Number> {
+        match self.peek(offset) {
+            Some(Token::Number(n)) => Some(n),
+            _ => None,
+        }
+    }
+
+    fn peek_word(&self, offset: usize) -> Option<Word> {
+        match self.peek(offset) {
+            Some(Token::Word(w)) => lookup_word(w),
+            _ => None,
+        }
+    }
+
+    fn peek_char(&self, offset: usize, c: char) -> bool {
+        self.peek(offset) == Some(&Token::Char(c))
+    }
+
+    fn parse_item(&mut self) -> ParseResult<()> {
+        match self.peek(0) {
+            Some(Token::Number(n)) => self.parse_number_item(n),
+            Some(Token::Word(w)) => match lookup_word(w) {
+                Some(word) => self.parse_word_item(word),
+                None => Err(ParseDateTimeError),
+            },
+            _ => Err(ParseDateTimeError),
+        }
+    }
+
+    fn parse_number_item(&mut self, n: &'a Number) -> ParseResult<()> {
+        if n.is_plain() {
+            if self.peek_char(1, ':') {
+                return self.parse_time(n);
+            }
+            if self.peek_char(1, '/') {
+                return self.parse_slash_date(n);
+            }
+            if let (Some(month), Some(day)) = (self.peek_number(1), self.peek_number(2)) {
+                // year-month-day, as in ISO 8601
+                let is_part = |m: &Number| m.negative && m.nanos.is_none();
+                if is_part(month) && is_part(day) {
+                    self.items.set_date(Some(n), month.value, day.value);
+                    self.pos += 3;
+                    if matches!(self.peek(0), Some(Token::Word(w)) if w == "t") {
+                        self.pos += 1;
+                    }
+                    return Ok(());
+                }
+            }
+        }
+        match self.peek_word(1) {
+            Some(Word::Meridian(m)) if n.is_plain() => {
+                self.pos += 2;
+                let hour = to_hour(n.value, Some(m))?;
+                self.items.set_time(hour, 0, 0, 0);
+                Ok(())
+            }
+            Some(Word::Month(month)) if n.is_plain() => {
+                // a day of the month, like "12 mar 2020" or "12-mar-2020"
+                self.pos += 2;
+                let year = match self.peek_number(0) {
+                    Some(y)
+                        if y.nanos.is_none()
+                            && (!y.signed || y.negative)
+                            && !self.peek_char(1, ':') =>
+                    {
+                        self.pos += 1;
+                        Some(y)
+                    }
+                    _ => None,
+                };
+                self.items.set_date(year, i64::from(month), n.value);
+                Ok(())
+            }
+            Some(Word::Weekday(weekday)) if n.is_plain() => {
+                self.pos += 2;
+                self.set_weekday(n.value, weekday);
+                Ok(())
+            }
+            Some(Word::Unit(unit)) => {
+                self.pos += 2;
+                self.parse_relative(unit, n)
+            }
+            _ if n.is_plain() => {
+                self.pos += 1;
+                self.parse_plain_number(n);
+                Ok(())
+            }
+            _ => Err(ParseDateTimeError),
+        }
+    }
+
+    fn parse_word_item(&mut self, word: Word) -> ParseResult<()> {
+        self.pos += 1;
+        match word {
+            Word::Month(month) => {
+                // "mar 12", "march 12, 2020" or "mar-12-2020"
+                let day = match self.peek_number(0) {
+                    Some(d) if d.nanos.is_none() && (!d.signed || d.negative) => d,
+                    _ => return Err(ParseDateTimeError),
+                };
+                self.pos += 1;
+                let year = if day.negative {
+                    match self.peek_number(0) {
+                        Some(y) if y.negative && y.nanos.is_none() => {
+                            self.pos += 1;
+                            Some(y)
+                        }
+                        _ => return Err(ParseDateTimeError),
+                    }
+                } else if self.peek_char(0, ',') {
+                    match self.peek_number(1) {
+                        Some(y) if y.is_plain() => {
+                            self.pos += 2;
+                            Some(y)
+                        }
+                        _ => return Err(ParseDateTimeError),
+                    }
+                } else {
+                    None
+                };
+                self.items.set_date(year, i64::from(month), day.value);
+                Ok(())
+            }
+            Word::Weekday(weekday) => {
+                if self.peek_char(0, ',') {
+                    self.pos += 1;
+                }
+                self.set_weekday(0, weekday);
+                Ok(())
+            }
+            Word::Ordinal(ordinal) => match self.peek_word(0) {
+                Some(Word::Unit(unit)) => {
+                    self.pos += 1;
+                    self.add_relative(unit, ordinal)
+                }
+                Some(Word::Weekday(weekday)) => {
+                    self.pos += 1;
+                    self.set_weekday(ordinal, weekday);
+                    Ok(())
+                }
+                _ => Err(ParseDateTimeError),
+            },
+            Word::Unit(unit) => self.add_relative(unit, 1),
+            Word::DayShift(days) => self.items.add_relative(Unit::Day(1), days),
+            Word::Zone(offset) => {
+                let offset = if self.peek_word(0) == Some(Word::Dst) {
+                    self.pos += 1;
+                    offset + 3600
+                } else {
+                    offset
+                };
+                // the zone may be followed by an offset from it, like "UTC+1"
+                let offset = match self.peek_number(0) {
+                    Some(n) if n.signed && n.nanos.is_none() => {
+                        self.pos += 1;
+                        offset + self.parse_zone_offset(n)?
+                    }
+                    _ => offset,
+                };
+                self.set_zone(offset);
+                Ok(())
+            }
+            Word::Meridian(_) | Word::Dst | Word::Ago => Err(ParseDateTimeError),
+        }
+    }
+
+    /// A relative item, which "ago" after it turns around.
+    fn add_relative(&mut self, unit: Unit, n: i64) -> ParseResult<()> {
+        let n = if self.peek_word(0) == Some(Word::Ago) {
+            self.pos += 1;
+            -n
+        } else {
+            n
+        };
+        self.items.add_relative(unit, n)
+    }
+
+    fn parse_relative(&mut self, unit: Unit, n: &Number) -> ParseResult<()> {
+        match (unit, n.nanos) {
+            (_, None) => self.add_relative(unit, n.signed_value()),
+            // only seconds may have a fraction
+            (Unit::Second(1), Some(_)) => {
+                let ago = self.peek_word(0) == Some(Word::Ago);
+                if ago {
+                    self.pos += 1;
+                }
+                let nanos = if ago {
+                    -n.total_nanos()
+                } else {
+                    n.total_nanos()
+                };
+                let rel = &mut self.items.relative;
+                rel.seconds = i64::try_from(nanos / 1_000_000_000)
+                    .ok()
+                    .and_then(|s| rel.seconds.checked_add(s))
+                    .ok_or(ParseDateTimeError)?;
+                rel.nanos += (nanos % 1_000_000_000) as i64;
+                self.items.rels_seen = true;
+                Ok(())
+            }
+            _ => Err(ParseDateTimeError),
+        }
+    }
+
+    fn parse_time(&mut self, hour: &Number) -> ParseResult<()> {
+        let minute = match self.peek_number(2) {
+            Some(m) if m.is_plain() => m,
+            _ => return Err(ParseDateTimeError),
+        };
+        self.pos += 3;
+        let (mut second, mut nanos) = (0, 0);
+        if self.peek_char(0, ':') {
+            match self.peek_number(1) {
+                Some(s) if !s.signed => {
+                    second = s.value;
+                    nanos = s.nanos.unwrap_or(0);
+                    self.pos += 2;
+                }
+                _ => return Err(ParseDateTimeError),
+            }
+        }
+        let hour = match (self.peek_word(0), self.peek_number(0)) {
+            (Some(Word::Meridian(m)), _) => {
+                self.pos += 1;
+                to_hour(hour.value, Some(m))?
+            }
+            (_, Some(offset)) if offset.signed && offset.nanos.is_none() => {
+                // a time zone offset, like "10:00+0100"
+                self.pos += 1;
+                let offset = self.parse_zone_offset(offset)?;
+                self.set_zone(offset);
+                to_hour(hour.value, None)?
+            }
+            _ => to_hour(hour.value, None)?,
+        };
+        self.items.set_time(hour, minute.value, second, nanos);
+        Ok(())
+    }
+
+    /// A date with slashes: month/day, month/day/year or year/month/day.
+    fn parse_slash_date(&mut self, first: &'a Number) -> ParseResult<()> {
+        let second = match self.peek_number(2) {
+            Some(n) if n.is_plain() => n,
+            _ => return Err(ParseDateTimeError),
+        };
+        self.pos += 3;
+        if !self.peek_char(0, '/') {
+            self.items.set_date(None, first.value, second.value);
+            return Ok(());
+        }
+        let third = match self.peek_number(1) {
+            Some(n) if n.is_plain() => n,
+            _ => return Err(ParseDateTimeError),
+        };
+        self.pos += 2;
+        if first.digits >= 4 {
+            self.items.set_date(Some(first), second.value, third.value);
+        } else {
+            self.items.set_date(Some(third), first.value, second.value);
+        }
+        Ok(())
+    }
+
+    /// A number on its own is the year after a date without one, a date if
+    /// it has more than four digits, and a time of day otherwise.
+    fn parse_plain_number(&mut self, n: &Number) {
+        let items = &mut self.items;
+        if items.dates_seen > 0
+            && items.year.is_none()
+            && !items.rels_seen
+            && (items.times_seen > 0 || n.digits > 2)
+        {
+            items.year = Some((n.value, n.digits));
+        } else if n.digits > 4 {
+            let year = Number {
+                value: n.value / 10000,
+                digits: n.digits - 4,
+                ..*n
+            };
+            items.set_date(Some(&year), n.value / 100 % 100, n.value % 100);
+        } else if n.digits <= 2 {
+            items.set_time(n.value, 0, 0, 0);
+        } else {
+            items.set_time(n.value / 100, n.value % 100, 0, 0);
+        }
+    }
+
+    /// The offset of a time zone in seconds, from "+HH", "+HHMM" or "+HH:MM".
+    fn parse_zone_offset(&mut self, n: &Number) -> ParseResult<i32> {
+        let minutes = if self.peek_char(0, ':') {
+            match self.peek_number(1) {
+                Some(m) if m.is_plain() => {
+                    self.pos += 2;
+                    n.value * 60 + m.value
+                }
+                _ => return Err(ParseDateTimeError),
+            }
+        } else if n.digits <= 2 {
+            n.value * 60
+        } else {
+            n.value / 100 * 60 + n.value % 100
+        };
+        if minutes > 24 * 60 {
+            return Err(ParseDateTimeError);
+        }
+        let seconds = minutes as i32 * 60;
+        Ok(if n.negative { -seconds } else { seconds })
+    }
+
+    fn set_zone(&mut self, offset: i32) {
+        self.items.zone = Some(offset);
+        self.items.zones_seen += 1;
+    }
+
+    fn set_weekday(&mut self, ordinal: i64, weekday: u8) {
+        self.items.weekday = Some((ordinal, weekday));
+        self.items.days_seen += 1;
+    }
+}
+
+fn to_hour(hour: i64, meridian: Option<Meridian>) -> ParseResult<i64> {
+    match meridian {
+        None if (0..24).contains(&hour) => Ok(hour),
+        Some(m) if (1..=12).contains(&hour) => {
+            Ok(hour % 12 + if m == Meridian::Pm { 12 } else { 0 })
+        }
+        _ => Err(ParseDateTimeError),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_datetime_at, ParseDateTimeError};
+    use time::macros::datetime;
+    use time::OffsetDateTime;
+
+    // Thursday
+    const NOW: OffsetDateTime = datetime!(2020-03-12 10:27:18.5 UTC);
+
+    fn parse(s: &str) -> Result<OffsetDateTime, ParseDateTimeError> {
+        // the tests run in UTC, so that local times are UTC times
+        std::env::set_var("TZ", "UTC");
+        parse_datetime_at(s, NOW)
+    }
+
+    fn assert_parses(s: &str, expected: OffsetDateTime) {
+        assert_eq!(parse(s), Ok(expected), "{s:?}");
+    }
+
+    #[test]
+    fn test_calendar_dates() {
+        let date = datetime!(2020-03-12 00:00 UTC);
+        for s in [
+            "2020-03-12",
+            "2020-3-12",
+            "3/12/2020",
+            "2020/03/12",
+            "12 mar 2020",
+            "12-mar-2020",
+            "mar 12, 2020",
+            "March 12 2020",
+            "mar-12-2020",
+            "Thursday, March 12, 2020",
+            "20200312",
+            "12 mar",
+            "3/12",
+            "3/12/20",
+        ] {
+            assert_parses(s, date);
+        }
+        assert_parses("1/2/69", datetime!(1969-01-02 00:00 UTC));
+        assert_parses("sept 3", datetime!(2020-09-03 00:00 UTC));
+        assert_eq!(parse("2020-02-30"), Err(ParseDateTimeError));
+        assert_eq!(parse("2005-43-21"), Err(ParseDateTimeError));
+        assert_eq!(parse("march"), Err(ParseDateTimeError));
+    }
+
+    #[test]
+    fn test_times_of_day() {
+        assert_parses("10:00", datetime!(2020-03-12 10:00 UTC));
+        assert_parses("1030", datetime!(2020-03-12 10:30 UTC));
+        assert_parses("10:00:30.25", datetime!(2020-03-12 10:00:30.25 UTC));
+        assert_parses("10:00:30,25", datetime!(2020-03-12 10:00:30.25 UTC));
+        assert_parses("10pm", datetime!(2020-03-12 22:00 UTC));
+        assert_parses("10:00 a.m.", datetime!(2020-03-12 10:00 UTC));
+        assert_parses("12am", datetime!(2020-03-12 00:00 UTC));
+        assert_parses("12 pm", datetime!(2020-03-12 12:00 UTC));
+        assert_parses("10:00+0100", datetime!(2020-03-12 09:00 UTC));
+        assert_parses("10:00 -05:30", datetime!(2020-03-12 15:30 UTC));
+        assert_parses("10:00 UTC+1", datetime!(2020-03-12 09:00 UTC));
+        assert_parses("(a comment) 10:00 Z", datetime!(2020-03-12 10:00 UTC));
+        for s in [
+            "13pm",
+            "0am",
+            "24:00",
+            "10:60",
+            "10:00:60",
+            "10:00pm +0100",
+            "noon",
+        ] {
+            assert_eq!(parse(s), Err(ParseDateTimeError), "{s:?}");
+        }
+    }
+
+    #[test]
+    fn test_time_zones() {
+        assert_parses("10:00 EST", datetime!(2020-03-12 15:00 UTC));
+        assert_parses("10:00 cest", datetime!(2020-03-12 08:00 UTC));
+        assert_parses("10:00 IST", datetime!(2020-03-12 04:30 UTC));
+        assert_parses("10:00 EST DST", datetime!(2020-03-12 14:00 UTC));
+        assert_parses("10:00 PST+1", datetime!(2020-03-12 17:00 UTC));
+        assert_parses("10:00 A", datetime!(2020-03-12 09:00 UTC));
+        assert_parses("10:00 M", datetime!(2020-03-11 22:00 UTC));
+        assert_parses("10:00 N", datetime!(2020-03-12 11:00 UTC));
+        assert_parses("10:00 Y", datetime!(2020-03-12 22:00 UTC));
+        for s in ["10:00 J", "10:00 XYZ", "10:00 DST", "10:00 EST CET"] {
+            assert_eq!(parse(s), Err(ParseDateTimeError), "{s:?}");
+        }
+    }
+
+    #[test]
+    fn test_combined() {
+        assert_parses(
+            "2020-03-12T10:00:00.5+05:30",
+            datetime!(2020-03-12 04:30:00.5 UTC),
+        );
+        assert_parses("2020-03-12 t 10:00", datetime!(2020-03-12 10:00 UTC));
+        assert_parses("Tue Dec  3 10:00:00 2019", datetime!(2019-12-03 10:00 UTC));
+        assert_parses("2020-03-12 2021", datetime!(2020-03-12 20:21 UTC));
+        assert_parses("tomorrow 10:00", datetime!(2020-03-13 10:00 UTC));
+        assert_parses("next monday + 3 hours", datetime!(2020-03-16 03:00 UTC));
+        assert_parses("2020-01-31 +1 month", datetime!(2020-03-02 00:00 UTC));
+        assert_parses("2020-01-01 -3 hours", datetime!(2019-12-31 21:00 UTC));
+        // like GNU, the sign makes a time zone, which "hours" then adds to
+        assert_parses("10:00 +3 hours", datetime!(2020-03-12 08:00 UTC));
+        for s in [
+            "10:00 10:00",
+            "monday tuesday",
+            "1 2",
+            "2020-03-12 10:00 2021",
+        ] {
+            assert_eq!(parse(s), Err(ParseDateTimeError), "{s:?}");
+        }
+    }
+
+    #[test]
+    fn test_relative() {
+        assert_parses("", datetime!(2020-03-12 00:00 UTC));
+        assert_parses("now", NOW);
+        assert_parses("today", NOW);
+        assert_parses("yesterday", datetime!(2020-03-11 10:27:18.5 UTC));
+        assert_parses("2 days ago", datetime!(2020-03-10 10:27:18.5 UTC));
+        assert_parses("- 3 days", datetime!(2020-03-09 10:27:18.5 UTC));
+        assert_parses("next week", datetime!(2020-03-19 10:27:18.5 UTC));
+        assert_parses("last month", datetime!(2020-02-12 10:27:18.5 UTC));
+        assert_parses("fortnight ago", datetime!(2020-02-27 10:27:18.5 UTC));
+        assert_parses("1 year ago", datetime!(2019-03-12 10:27:18.5 UTC));
+        assert_parses("1 day 2 hours ago", datetime!(2020-03-13 08:27:18.5 UTC));
+        assert_parses("-1.5 sec", datetime!(2020-03-12 10:27:17 UTC));
+        assert_parses("third day", datetime!(2020-03-15 10:27:18.5 UTC));
+        for s in [
+            "ago",
+            "1.5 hours",
+            "2020-03-12 10:00 ago",
+            "2020-01-01 +0100",
+        ] {
+            assert_eq!(parse(s), Err(ParseDateTimeError), "{s:?}");
+        }
+    }
+
+    #[test]
+    fn test_weekdays() {
+        assert_parses("thurs", datetime!(2020-03-12 00:00 UTC));
+        assert_parses("monday", datetime!(2020-03-16 00:00 UTC));
+        assert_parses("next monday", datetime!(2020-03-16 00:00 UTC));
+        assert_parses("this monday", datetime!(2020-03-16 00:00 UTC));
+        assert_parses("last monday", datetime!(2020-03-09 00:00 UTC));
+        assert_parses("5 monday", datetime!(2020-04-13 00:00 UTC));
+        assert_parses("monday 10:00", datetime!(2020-03-16 10:00 UTC));
+    }
+
+    #[test]
+    fn test_timestamps() {
+        assert_parses("@0", datetime!(1970-01-01 00:00 UTC));
+        assert_parses("@1.5", datetime!(1970-01-01 00:00:01.5 UTC));
+        assert_parses("@-1.5", datetime!(1969-12-31 23:59:58.5 UTC));
+        assert_eq!(parse("@1 day"), Err(ParseDateTimeError));
+    }
+}
@@ -146,25 +146,30 @@ pub fn wrap_chown<P: AsRef<Path>>(
                 _ => (),
             };
         } else if verbosity.level == VerbosityLevel::Verbose {
-            out = if verbosity.groups_only {
-                format!(
-                    "group of {} retained as {}",
-                    path.quote(),
-                    entries::gid2grp(dest_gid).unwrap_or_default()
-                )
-            } else {
-                format!(
-                    "ownership of {} retained as {}:{}",
-                    path.quote(),
-                    entries::uid2usr(dest_uid).unwrap_or_else(|_| dest_uid.to_string()),
-                    entries::gid2grp(dest_gid).unwrap_or_else(|_| dest_gid.to_string())
-                )
-            };
+            out = retained_message(path, dest_uid, dest_gid, &verbosity);
         }
     }
     Ok(out)
 }
 
+/// The message printed with `--verbose` for a path whose owner is left as is
+fn retained_message(path: &Path, uid: u32, gid: u32, verbosity: &Verbosity) -> String {
+    if verbosity.groups_only {
+        format!(
+            "group of {} retained as {}",
+            path.quote(),
+            entries::gid2grp(gid).unwrap_or_default()
+        )
+    } else {
+        format!(
+            "ownership of {} retained as {}:{}",
+            path.quote(),
+            entries::uid2usr(uid).unwrap_or_else(|_| uid.to_string()),
+            entries::gid2grp(gid).unwrap_or_else(|_| gid.to_string())
+        )
+    }
+}
+
 pub enum IfFrom {
     All,
     User(u32),
@@ -237,31 +242,7 @@ impl ChownExecutor {
             }
         }
 
-        let ret = if self.matched(meta.uid(), meta.gid()) {
-            match wrap_chown(
-                path,
-                &meta,
-                self.dest_uid,
-                self.dest_gid,
-                self.dereference,
-                self.verbosity.clone(),
-            ) {
-                Ok(n) => {
-                    if !n.is_empty() {
-                        show_error!("{}", n);
-                    }
-                    0
-                }
-                Err(e) => {
-                    if self.verbosity.level != VerbosityLevel::Silent {
-                        show_error!("{}", e);
-                    }
-                    1
-                }
-            }
-        } else {
-            0
-        };
+        let ret = self.change_owner(path, &meta);
 
         if !self.recursive {
             ret
@@ -270,6 +251,41 @@ impl ChownExecutor {
         }
     }
 
+    /// Change the owner of `path` if it passes the `--from` filter, reporting
+    /// the outcome according to the verbosity
+    fn change_owner(&self, path: &Path, meta: &Metadata) -> i32 {
+        if !self.matched(meta.uid(), meta.gid()) {
+            if self.verbosity.level == VerbosityLevel::Verbose {
+                let uid = self.dest_uid.unwrap_or_else(|| meta.uid());
+                let gid = self.dest_gid.unwrap_or_else(|| meta.gid());
+                show_error!("{}", retained_message(path, uid, gid, &self.verbosity));
+            }
+            return 0;
+        }
+
+        match wrap_chown(
+            path,
+            meta,
+            self.dest_uid,
+            self.dest_gid,
+            self.dereference,
+            self.verbosity.clone(),
+        ) {
+            Ok(n) => {
+                if !n.is_empty() {
+                    show_error!("{}", n);
+                }
+                0
+            }
+            Err(e) => {
+                if self.verbosity.level != VerbosityLevel::Silent {
+                    show_error!("{}", e);
+                }
+                1
+            }
+        }
+    }
+
     fn dive_into<P: AsRef<Path>>(&self, root: P) -> i32 {
         let root = root.as_ref();
 
@@ -289,8 +305,14 @@ impl ChownExecutor {
                 Err(e) => {
                     ret = 1;
                     if let Some(path) = e.path() {
+                        // With -L, walkdir fails on the dangling symlinks it tries to follow
                         show_error!(
-                            "cannot access '{}': {}",
+                            "cannot {} '{}': {}",
+                            if path.is_symlink() {
+                                "dereference"
+                            } else {
+                                "access"
+                            },
                             path.display(),
                             if let Some(error) = e.io_error() {
                                 strip_errno(error)
@@ -319,31 +341,7 @@ impl ChownExecutor {
                 }
             };
 
-            if !self.matched(meta.uid(), meta.gid()) {
-                continue;
-            }
-
-            ret = match wrap_chown(
-                path,
-                &meta,
-                self.dest_uid,
-                self.dest_gid,
-                self.dereference,
-                self.verbosity.clone(),
-            ) {
-                Ok(n) => {
-                    if !n.is_empty() {
-                        show_error!("{}", n);
-                    }
-                    0
-                }
-                Err(e) => {
-                    if self.verbosity.level != VerbosityLevel::Silent {
-                        show_error!("{}", e);
-                    }
-                    1
-                }
-            }
+            ret |= self.change_owner(path, &meta);
         }
         ret
     }
@@ -489,7 +487,10 @@ pub fn chown_base(
     if recursive {
         if traverse_symlinks == TraverseSymlinks::None {
             if dereference == Some(true) {
-                return Err(USimpleError::new(1, "-R --dereference requires -H or -L"));
+                return Err(USimpleError::new(
+                    1,
+                    "-R --dereference requires either -H or -L",
+                ));
             }
             dereference = Some(false);
         }
@@ -7,11 +7,11 @@
 // that was distributed with this source code.
 
 // spell-checker:ignore (vars) cvar exitstatus
-// spell-checker:ignore (sys/unix) WIFSIGNALED
+// spell-checker:ignore (sys/unix) WIFSIGNALED getpriority setpriority PGRP
 
 //! Set of functions to manage IDs
 
-use libc::{gid_t, pid_t, uid_t};
+use libc::{gid_t, id_t, pid_t, uid_t};
 use std::io;
 use std::process::Child;
 use std::process::ExitStatus;
@@ -40,6 +40,61 @@ pub fn getuid() -> uid_t {
     unsafe { libc::getuid() }
 }
 
+/// What a niceness is read or set for, as with `getpriority(2)`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PriorityTarget {
+    /// A process, or the calling process for 0
+    Process(pid_t),
+    /// A process group, or the one of the calling process for 0
+    ProcessGroup(pid_t),
+    /// All processes whose real user ID is the given one
+    User(uid_t),
+}
+
+/// `getpriority()` returns the niceness of `target`, which is the lowest one
+/// of its processes for a group or a user.
+pub fn getpriority(target: PriorityTarget) -> io::Result<i32> {
+    // -1 is a valid niceness, so only errno tells about errors.
+    nix::errno::Errno::clear();
+    // SAFETY: getpriority has no memory safety requirements.
+    let niceness = unsafe {
+        match target {
+            PriorityTarget::Process(pid) => libc::getpriority(libc::PRIO_PROCESS, pid as id_t),
+            PriorityTarget::ProcessGroup(pgrp) => libc::getpriority(libc::PRIO_PGRP, pgrp as id_t),
+            PriorityTarget::User(uid) => libc::getpriority(libc::PRIO_USER, uid as id_t),
+        }
+    };
+    let error = io::Error::last_os_error();
+    if niceness == -1 && error.raw_os_error() != Some(0) {
+        Err(error)
+    } else {
+        Ok(niceness)
+    }
+}
+
+/// `setpriority()` sets the niceness of all processes of `target`.
+///
+/// The system clamps `niceness` to the range it supports.
+pub fn setpriority(target: PriorityTarget, niceness: i32) -> io::Result<()> {
+    // SAFETY: setpriority has no memory safety requirements.
+    let res = unsafe {
+        match target {
+            PriorityTarget::Process(pid) => {
+                libc::setpriority(libc::PRIO_PROCESS, pid as id_t, niceness)
+            }
+            PriorityTarget::ProcessGroup(pgrp) => {
+                libc::setpriority(libc::PRIO_PGRP, pgrp as id_t, niceness)
+            }
+            PriorityTarget::User(uid) => libc::setpriority(libc::PRIO_USER, uid as id_t, niceness),
+        }
+    };
+    if res == -1 {
+        Err(io::Error::last_os_error())
+    } else {
+        Ok(())
+    }
+}
+
 /// Missing methods for Child objects
 pub trait ChildExt {
     /// Send a signal to a Child process.
@@ -0,0 +1,49 @@
+// This file is part of the uutils coreutils package.
+//
+// For the full copyright and license information, please view the LICENSE
+// file that was distributed with this source code.
+
+//! Progress bars for utilities copying files, like `cp --progress` and `mv --progress`.
+
+use std::path::Path;
+
+use indicatif::{HumanBytes, ProgressBar, ProgressStyle};
+
+use crate::display::Quotable;
+
+/// Returns the style of progress bars counting copied bytes.
+///
+/// Next to the bar are the copied and total bytes, the throughput and the estimated remaining
+/// time. Below it, the message shows the file being copied.
+pub fn bytes_style() -> ProgressStyle {
+    ProgressStyle::with_template(
+        "{prefix}: [{elapsed_precise}] {wide_bar} {bytes:>7}/{total_bytes:7} \
+         {binary_bytes_per_sec:>11} ETA {eta}\n{wide_msg}",
+    )
+    .unwrap()
+}
+
+/// Returns a progress bar for copying `total_bytes`, prefixed with the name of the utility.
+pub fn bytes_progress_bar(total_bytes: u64) -> ProgressBar {
+    ProgressBar::new(total_bytes)
+        .with_style(bytes_style())
+        .with_prefix(crate::util_name())
+}
+
+/// Shows on `bar` that the file at `path`, which is `len` bytes long, is being copied.
+pub fn set_current_file(bar: &ProgressBar, path: &Path, len: u64) {
+    bar.set_message(format!("{} ({})", path.quote(), HumanBytes(len)));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_set_current_file() {
+        let bar = bytes_progress_bar(4096);
+        set_current_file(&bar, Path::new("some file"), 2048);
+        assert_eq!(bar.message(), "'some file' (2.00 KiB)");
+        assert_eq!(bar.length(), Some(4096));
+    }
+}
@@ -0,0 +1,125 @@
+// This file is part of the uutils coreutils package.
+//
+// For the full copyright and license information, please view the LICENSE
+// file that was distributed with this source code.
+
+//! Set the security context of created files, as with `-Z`/`--context`
+//!
+//! SELinux contexts need the `selinux` feature, and thus `libselinux` at
+//! build time. SMACK labels are plain extended attributes, so they can be set
+//! on any Linux kernel with SMACK enabled.
+
+// spell-checker:ignore smackfs
+
+use std::io;
+use std::path::Path;
+
+/// Whether the running kernel enforces SELinux.
+#[cfg(feature = "selinux")]
+pub fn selinux_enabled() -> bool {
+    selinux::kernel_support() != selinux::KernelSupport::Unsupported
+}
+
+/// Whether the running kernel enforces SELinux.
+#[cfg(not(feature = "selinux"))]
+pub fn selinux_enabled() -> bool {
+    false
+}
+
+/// Whether the running kernel enforces SMACK, i.e. smackfs is mounted.
+#[cfg(target_os = "linux")]
+pub fn smack_enabled() -> bool {
+    Path::new("/sys/fs/smackfs/load2").exists()
+}
+
+/// Whether the running kernel enforces SMACK, i.e. smackfs is mounted.
+#[cfg(not(target_os = "linux"))]
+pub fn smack_enabled() -> bool {
+    false
+}
+
+/// Whether security contexts can be set at all.
+pub fn is_supported() -> bool {
+    selinux_enabled() || smack_enabled()
+}
+
+/// Set the security context of `path` to `context`, or to the default one
+/// for its path and type when `context` is `None`.
+///
+/// SMACK has no notion of a default context, so a file keeps the label it
+/// was created with if no `context` is given. Nothing is done when neither
+/// SELinux nor SMACK is enabled.
+pub fn set_security_context(path: &Path, context: Option<&str>) -> io::Result<()> {
+    if selinux_enabled() {
+        set_selinux_context(path, context)
+    } else if smack_enabled() {
+        match context {
+            Some(label) => set_smack_label(path, label),
+            None => Ok(()),
+        }
+    } else {
+        Ok(())
+    }
+}
+
+#[cfg(feature = "selinux")]
+fn set_selinux_context(path: &Path, context: Option<&str>) -> io::Result<()> {
+    use selinux::label::{back_end::File, Labeler};
+    use selinux::{FileAccessMode, SecurityContext};
+    use std::ffi::CString;
+    use std::os::unix::fs::MetadataExt;
+
+    fn to_io_error(e: impl std::fmt::Display) -> io::Error {
+        io::Error::new(io::ErrorKind::Other, e.to_string())
+    }
+
+    match context {
+        Some(context) => {
+            let context = CString::new(context)?;
+            SecurityContext::from_c_str(&context, false)
+                .set_for_path(path, false, false)
+                .map_err(to_io_error)
+        }
+        None => {
+            let mode = std::fs::symlink_metadata(path)?.mode();
+            let labeler = Labeler::<File>::new(&[], false).map_err(to_io_error)?;
+            labeler
+                .look_up_by_path(path, FileAccessMode::new(mode))
+                .and_then(|context| context.set_for_path(path, false, false))
+                .map_err(to_io_error)
+        }
+    }
+}
+
+#[cfg(not(feature = "selinux"))]
+fn set_selinux_context(_path: &Path, _context: Option<&str>) -> io::Result<()> {
+    Ok(())
+}
+
+#[cfg(target_os = "linux")]
+fn set_smack_label(path: &Path, label: &str) -> io::Result<()> {
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+
+    let path = CString::new(path.as_os_str().as_bytes())?;
+    // SAFETY: both names are NUL-terminated and the value is `label.len()` bytes long.
+    let res = unsafe {
+        libc::setxattr(
+            path.as_ptr(),
+            b"security.SMACK64\0".as_ptr().cast(),
+            label.as_ptr().cast(),
+            label.len(),
+            0,
+        )
+    };
+    if res == 0 {
+        Ok(())
+    } else {
+        Err(io::Error::last_os_error())
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn set_smack_label(_path: &Path, _label: &str) -> io::Result<()> {
+    Ok(())
+}
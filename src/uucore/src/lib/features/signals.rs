@@ -5,8 +5,8 @@
 // For the full copyright and license information, please view the LICENSE file
 // that was distributed with this source code.
 
-// spell-checker:ignore (vars/api) fcntl setrlimit setitimer
-// spell-checker:ignore (vars/signals) ABRT ALRM CHLD SEGV SIGABRT SIGALRM SIGBUS SIGCHLD SIGCONT SIGEMT SIGFPE SIGHUP SIGILL SIGINFO SIGINT SIGIO SIGIOT SIGKILL SIGPIPE SIGPROF SIGPWR SIGQUIT SIGSEGV SIGSTOP SIGSYS SIGTERM SIGTRAP SIGTSTP SIGTHR SIGTTIN SIGTTOU SIGURG SIGUSR SIGVTALRM SIGWINCH SIGXCPU SIGXFSZ STKFLT PWR THR TSTP TTIN TTOU VTALRM XCPU XFSZ
+// spell-checker:ignore (vars/api) fcntl setrlimit setitimer strsignal
+// spell-checker:ignore (vars/signals) RTMAX RTMIN SIGRTMAX SIGRTMIN ABRT ALRM CHLD SEGV SIGABRT SIGALRM SIGBUS SIGCHLD SIGCONT SIGEMT SIGFPE SIGHUP SIGILL SIGINFO SIGINT SIGIO SIGIOT SIGKILL SIGPIPE SIGPROF SIGPWR SIGQUIT SIGSEGV SIGSTOP SIGSYS SIGTERM SIGTRAP SIGTSTP SIGTHR SIGTTIN SIGTTOU SIGURG SIGUSR SIGVTALRM SIGWINCH SIGXCPU SIGXFSZ STKFLT PWR THR TSTP TTIN TTOU VTALRM XCPU XFSZ
 
 pub static DEFAULT_SIGNAL: usize = 15;
 
@@ -175,25 +175,104 @@ pub static ALL_SIGNALS: [&str; 33] = [
     "XCPU", "XFSZ", "VTALRM", "PROF", "WINCH", "INFO", "USR1", "USR2", "THR",
 ];
 
+/// The lowest and highest real-time signal, as far as the C library leaves
+/// them to programs.
+#[cfg(any(target_os = "linux", target_os = "android"))]
+fn real_time_signals() -> Option<(usize, usize)> {
+    Some((libc::SIGRTMIN() as usize, libc::SIGRTMAX() as usize))
+}
+
+/// The lowest and highest real-time signal, as far as the C library leaves
+/// them to programs.
+#[cfg(not(any(target_os = "linux", target_os = "android")))]
+fn real_time_signals() -> Option<(usize, usize)> {
+    None
+}
+
+/// The highest signal number, including the real-time signals.
+pub fn max_signal() -> usize {
+    real_time_signals().map_or(ALL_SIGNALS.len() - 1, |(_, max)| max)
+}
+
+/// Parse the name of a real-time signal, like `RTMIN`, `RTMIN+1` or `RTMAX-2`.
+fn real_time_signal_by_name(signal_name: &str) -> Option<usize> {
+    let (min, max) = real_time_signals()?;
+    let (base, offset) = match signal_name.strip_prefix("RTMIN") {
+        Some(offset) => (min, offset),
+        None => (max, signal_name.strip_prefix("RTMAX")?),
+    };
+    let offset: isize = if offset.is_empty() {
+        0
+    } else {
+        offset.parse().ok()?
+    };
+    // Offsets count up from RTMIN, and down from RTMAX.
+    if (base == min && offset < 0) || (base == max && offset > 0) {
+        return None;
+    }
+    let value = usize::try_from((base as isize).checked_add(offset)?).ok()?;
+    (min..=max).contains(&value).then(|| value)
+}
+
+/// Parse a signal number, or a signal name with or without the `SIG` prefix
+/// in any case, like `9`, `KILL`, `SIGKILL`, `kill` or `RTMIN+1`.
 pub fn signal_by_name_or_value(signal_name_or_value: &str) -> Option<usize> {
-    if let Ok(value) = signal_name_or_value.parse() {
-        if is_signal(value) {
-            return Some(value);
-        } else {
-            return None;
-        }
+    if signal_name_or_value.starts_with(|c: char| c.is_ascii_digit()) {
+        return signal_name_or_value
+            .parse()
+            .ok()
+            .filter(|&value| is_signal(value));
     }
-    let signal_name = signal_name_or_value.trim_start_matches("SIG");
+    let signal_name = signal_name_or_value.to_ascii_uppercase();
+    let signal_name = signal_name.strip_prefix("SIG").unwrap_or(&signal_name);
 
-    ALL_SIGNALS.iter().position(|&s| s == signal_name)
+    ALL_SIGNALS
+        .iter()
+        .position(|&s| s == signal_name)
+        .or_else(|| real_time_signal_by_name(signal_name))
 }
 
+/// Whether `num` is a signal with a name, which the C library does not use
+/// for itself.
 pub fn is_signal(num: usize) -> bool {
     num < ALL_SIGNALS.len()
+        || real_time_signals().map_or(false, |(min, max)| (min..=max).contains(&num))
 }
 
-pub fn signal_name_by_value(signal_value: usize) -> Option<&'static str> {
-    ALL_SIGNALS.get(signal_value).copied()
+/// The name of a signal without the `SIG` prefix, where the real-time
+/// signals are named relative to the closer of `RTMIN` and `RTMAX`.
+pub fn signal_name_by_value(signal_value: usize) -> Option<String> {
+    if let Some(name) = ALL_SIGNALS.get(signal_value) {
+        return Some((*name).to_string());
+    }
+    let (min, max) = real_time_signals()?;
+    if !(min..=max).contains(&signal_value) {
+        return None;
+    }
+    let (base_name, base) = if signal_value <= min + (max - min) / 2 {
+        ("RTMIN", min)
+    } else {
+        ("RTMAX", max)
+    };
+    Some(match signal_value as isize - base as isize {
+        0 => base_name.to_string(),
+        offset => format!("{base_name}{offset:+}"),
+    })
+}
+
+/// The description of a signal by the C library, like `Hangup` for `SIGHUP`.
+pub fn signal_description(signal_value: usize) -> Option<String> {
+    let signal_value = libc::c_int::try_from(signal_value).ok()?;
+    // SAFETY: strsignal returns either null or a nul-terminated string, which
+    // is copied before anything else could change it.
+    unsafe {
+        let description = libc::strsignal(signal_value);
+        (!description.is_null()).then(|| {
+            std::ffi::CStr::from_ptr(description)
+                .to_string_lossy()
+                .into_owned()
+        })
+    }
 }
 
 #[test]
@@ -224,6 +303,31 @@ fn signal_by_long_name() {
 #[test]
 fn name() {
     for (value, signal) in ALL_SIGNALS.iter().enumerate() {
-        assert_eq!(signal_name_by_value(value), Some(*signal));
+        assert_eq!(signal_name_by_value(value).as_deref(), Some(*signal));
+    }
+}
+
+#[test]
+fn signal_by_lowercase_name() {
+    assert_eq!(signal_by_name_or_value("kill"), Some(9));
+    assert_eq!(signal_by_name_or_value("sigKill"), Some(9));
+    assert_eq!(signal_by_name_or_value("SIGSIGKILL"), None);
+    assert_eq!(signal_by_name_or_value("+9"), None);
+}
+
+#[test]
+#[cfg(any(target_os = "linux", target_os = "android"))]
+fn real_time_signals_round_trip() {
+    let (min, max) = real_time_signals().unwrap();
+    assert_eq!(signal_by_name_or_value("RTMIN"), Some(min));
+    assert_eq!(signal_by_name_or_value("SIGRTMIN+1"), Some(min + 1));
+    assert_eq!(signal_by_name_or_value("rtmax-1"), Some(max - 1));
+    assert_eq!(signal_by_name_or_value("RTMAX+1"), None);
+    assert_eq!(signal_by_name_or_value("RTMIN-1"), None);
+    assert_eq!(signal_name_by_value(max).as_deref(), Some("RTMAX"));
+    for value in min..=max {
+        let name = signal_name_by_value(value).unwrap();
+        assert_eq!(signal_by_name_or_value(&name), Some(value), "{name}");
     }
+    assert_eq!(max_signal(), max);
 }
@@ -1,4 +0,0 @@
-pub mod format_field;
-mod formatter;
-mod formatters;
-pub mod num_format;
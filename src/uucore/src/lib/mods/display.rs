@@ -19,6 +19,7 @@
 /// println_verbatim(path)?; // Prints "foo/bar.baz"
 /// # Ok::<(), std::io::Error>(())
 /// ```
+use std::borrow::Cow;
 use std::ffi::OsStr;
 use std::io::{self, Write as IoWrite};
 
@@ -67,3 +68,20 @@ pub fn print_verbatim<S: AsRef<OsStr>>(text: S) -> io::Result<()> {
         write!(stdout, "{}", std::path::Path::new(text.as_ref()).display())
     }
 }
+
+/// The bytes of `text`, as `print_verbatim` would write them.
+///
+/// FIXME: This is lossy on Windows, like `println_verbatim`.
+pub fn os_str_as_bytes(text: &OsStr) -> Cow<'_, [u8]> {
+    #[cfg(any(unix, target_os = "wasi"))]
+    {
+        Cow::Borrowed(text.as_bytes())
+    }
+    #[cfg(not(any(unix, target_os = "wasi")))]
+    {
+        match text.to_string_lossy() {
+            Cow::Borrowed(s) => Cow::Borrowed(s.as_bytes()),
+            Cow::Owned(s) => Cow::Owned(s.into_bytes()),
+        }
+    }
+}
@@ -1,11 +1,14 @@
 use std::char::from_digit;
 use std::ffi::OsStr;
 
+use crate::display::os_str_as_bytes;
+
 // These are characters with special meaning in the shell (e.g. bash).
 // The first const contains characters that only have a special meaning when they appear at the beginning of a name.
 const SPECIAL_SHELL_CHARS_START: &[char] = &['~', '#'];
 const SPECIAL_SHELL_CHARS: &str = "`$&*()|[]{};\\'\"<>?! ";
 
+#[derive(Clone)]
 pub enum QuotingStyle {
     Shell {
         escape: bool,
@@ -207,14 +210,48 @@ fn shell_without_escape(name: &str, quotes: Quotes, show_control_chars: bool) ->
     (escaped_str, must_quote)
 }
 
-fn shell_with_escape(name: &str, quotes: Quotes) -> (String, bool) {
+// The characters of `bytes`, and the bytes that are not valid UTF-8
+fn decode_utf8(mut bytes: &[u8]) -> Vec<Result<char, u8>> {
+    let mut decoded = Vec::with_capacity(bytes.len());
+    loop {
+        match std::str::from_utf8(bytes) {
+            Ok(s) => {
+                decoded.extend(s.chars().map(Ok));
+                return decoded;
+            }
+            Err(e) => {
+                let (valid, rest) = bytes.split_at(e.valid_up_to());
+                // The bytes up to the error are valid UTF-8
+                decoded.extend(std::str::from_utf8(valid).unwrap().chars().map(Ok));
+                let invalid = e.error_len().unwrap_or(rest.len());
+                decoded.extend(rest[..invalid].iter().map(|&b| Err(b)));
+                bytes = &rest[invalid..];
+            }
+        }
+    }
+}
+
+fn shell_with_escape(name: &OsStr, quotes: Quotes) -> (String, bool) {
     // We need to keep track of whether we are in a dollar expression
     // because e.g. \b\n is escaped as $'\b\n' and not like $'b'$'n'
     let mut in_dollar = false;
     let mut must_quote = false;
     let mut escaped_str = String::with_capacity(name.len());
 
-    for c in name.chars() {
+    for c in decode_utf8(&os_str_as_bytes(name)) {
+        // Bytes that are not valid UTF-8 are written in octal, like control characters
+        let c = match c {
+            Ok(c) => c,
+            Err(byte) => {
+                if !in_dollar {
+                    escaped_str.push_str("'$'");
+                    in_dollar = true;
+                }
+                must_quote = true;
+                escaped_str.push_str(&format!("\\{byte:03o}"));
+                continue;
+            }
+        };
         let escaped = EscapedChar::new_shell(c, true, quotes);
         match escaped.state {
             EscapeState::Char(x) => {
@@ -252,7 +289,10 @@ fn shell_with_escape(name: &str, quotes: Quotes) -> (String, bool) {
             }
         }
     }
-    must_quote = must_quote || name.starts_with(SPECIAL_SHELL_CHARS_START);
+    must_quote = must_quote
+        || name
+            .to_string_lossy()
+            .starts_with(SPECIAL_SHELL_CHARS_START);
     (escaped_str, must_quote)
 }
 
@@ -286,21 +326,21 @@ pub fn escape_name(name: &OsStr, style: &QuotingStyle) -> String {
             always_quote,
             show_control,
         } => {
-            let name = name.to_string_lossy();
-            let (quotes, must_quote) = if name.contains(&['"', '`', '$', '\\'][..]) {
+            let name_str = name.to_string_lossy();
+            let (quotes, must_quote) = if name_str.contains(&['"', '`', '$', '\\'][..]) {
                 (Quotes::Single, true)
-            } else if name.contains('\'') {
+            } else if name_str.contains('\'') {
                 (Quotes::Double, true)
-            } else if *always_quote {
+            } else if *always_quote || name.is_empty() {
                 (Quotes::Single, true)
             } else {
                 (Quotes::Single, false)
             };
 
             let (escaped_str, contains_quote_chars) = if *escape {
-                shell_with_escape(&name, quotes)
+                shell_with_escape(name, quotes)
             } else {
-                shell_without_escape(&name, quotes, *show_control)
+                shell_without_escape(&name_str, quotes, *show_control)
             };
 
             match (must_quote | contains_quote_chars, quotes) {
@@ -728,4 +768,26 @@ mod tests {
             ],
         );
     }
+
+    #[test]
+    fn test_empty_name() {
+        check_names("", &[("''", "shell"), ("''", "shell-escape")]);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_invalid_utf8() {
+        use std::ffi::OsStr;
+        use std::os::unix::ffi::OsStrExt;
+
+        let style = get_style("shell-escape");
+        assert_eq!(
+            escape_name(OsStr::from_bytes(b"a\xffb"), &style),
+            "'a'$'\\377''b'"
+        );
+        assert_eq!(
+            escape_name(OsStr::from_bytes(b"\xc3\x28\n"), &style),
+            "''$'\\303''('$'\\n'"
+        );
+    }
 }
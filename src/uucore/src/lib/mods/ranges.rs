@@ -12,10 +12,14 @@ use std::str::FromStr;
 
 use crate::display::Quotable;
 
-#[derive(PartialEq, Eq, PartialOrd, Ord, Debug)]
+#[derive(PartialEq, Eq, PartialOrd, Ord, Debug, Clone, Copy)]
 pub struct Range {
     pub low: usize,
     pub high: usize,
+    /// The distance between the selected positions, 1 for all of them
+    ///
+    /// `high` is always one of the selected positions.
+    pub step: usize,
 }
 
 impl FromStr for Range {
@@ -26,10 +30,10 @@ impl FromStr for Range {
     /// ```
     /// use std::str::FromStr;
     /// use uucore::ranges::Range;
-    /// assert_eq!(Range::from_str("5"), Ok(Range { low: 5, high: 5 }));
-    /// assert_eq!(Range::from_str("4-"), Ok(Range { low: 4, high: usize::MAX - 1 }));
-    /// assert_eq!(Range::from_str("-4"), Ok(Range { low: 1, high: 4 }));
-    /// assert_eq!(Range::from_str("2-4"), Ok(Range { low: 2, high: 4 }));
+    /// assert_eq!(Range::from_str("5"), Ok(Range { low: 5, high: 5, step: 1 }));
+    /// assert_eq!(Range::from_str("4-"), Ok(Range { low: 4, high: usize::MAX - 1, step: 1 }));
+    /// assert_eq!(Range::from_str("-4"), Ok(Range { low: 1, high: 4, step: 1 }));
+    /// assert_eq!(Range::from_str("2-4"), Ok(Range { low: 2, high: 4, step: 1 }));
     /// assert!(Range::from_str("0-4").is_err());
     /// assert!(Range::from_str("4-2").is_err());
     /// assert!(Range::from_str("-").is_err());
@@ -48,21 +52,15 @@ impl FromStr for Range {
         Ok(match s.split_once('-') {
             None => {
                 let n = parse(s)?;
-                Self { low: n, high: n }
+                Self::new(n, n)
             }
             Some(("", "")) => return Err("invalid range with no endpoint"),
-            Some((low, "")) => Self {
-                low: parse(low)?,
-                high: usize::MAX - 1,
-            },
-            Some(("", high)) => Self {
-                low: 1,
-                high: parse(high)?,
-            },
+            Some((low, "")) => Self::new(parse(low)?, usize::MAX - 1),
+            Some(("", high)) => Self::new(1, parse(high)?),
             Some((low, high)) => {
                 let (low, high) = (parse(low)?, parse(high)?);
                 if low <= high {
-                    Self { low, high }
+                    Self::new(low, high)
                 } else {
                     return Err("high end of range less than low end");
                 }
@@ -72,22 +70,93 @@ impl FromStr for Range {
 }
 
 impl Range {
+    /// The range of all positions from `low` to `high`
+    pub fn new(low: usize, high: usize) -> Self {
+        Self { low, high, step: 1 }
+    }
+
     pub fn from_list(list: &str) -> Result<Vec<Self>, String> {
+        Self::from_list_unordered(list).map(Self::merge)
+    }
+
+    /// Parse a list of ranges, keeping them in the order they were given
+    ///
+    /// Unlike [`Range::from_list`], overlapping ranges are neither merged
+    /// nor sorted.
+    ///
+    /// An item may have a step suffix, as in `2-10:3`, to select every third
+    /// position of the range. Such an item requires an upper bound, which is
+    /// lowered to the last selected position.
+    ///
+    /// ```
+    /// use uucore::ranges::Range;
+    /// assert_eq!(
+    ///     Range::from_list_unordered("2-9:3"),
+    ///     Ok(vec![Range { low: 2, high: 8, step: 3 }])
+    /// );
+    /// assert!(Range::from_list_unordered("2-:3").is_err());
+    /// assert!(Range::from_list_unordered("2-8:0").is_err());
+    /// ```
+    ///
+    /// ```
+    /// use uucore::ranges::Range;
+    /// assert_eq!(
+    ///     Range::from_list_unordered("3,1-2,2"),
+    ///     Ok(vec![Range::new(3, 3), Range::new(1, 2), Range::new(2, 2)])
+    /// );
+    /// ```
+    pub fn from_list_unordered(list: &str) -> Result<Vec<Self>, String> {
         let mut ranges = Vec::new();
 
         for item in list.split(&[',', ' ']) {
-            let range_item = FromStr::from_str(item)
+            let range = Self::parse_stepped(item)
                 .map_err(|e| format!("range {} was invalid: {}", item.quote(), e))?;
-            ranges.push(range_item);
+            ranges.push(range);
         }
 
-        Ok(Self::merge(ranges))
+        Ok(ranges)
+    }
+
+    /// Parse a range with an optional `:step` suffix
+    fn parse_stepped(item: &str) -> Result<Self, &'static str> {
+        let (range, step) = match item.split_once(':') {
+            None => return Self::from_str(item),
+            Some((range, step)) => (Self::from_str(range)?, step),
+        };
+        let step = match step.parse::<usize>() {
+            Ok(0) => return Err("step must be greater than 0"),
+            Ok(n) => n,
+            Err(_) => return Err("failed to parse step"),
+        };
+        if step > 1 && range.high == usize::MAX - 1 {
+            return Err("a range with a step needs an upper bound");
+        }
+        let high = range.high - (range.high - range.low) % step;
+        Ok(Self {
+            low: range.low,
+            high,
+            step: if high == range.low { 1 } else { step },
+        })
+    }
+
+    /// Test if `n` is one of the positions of the range
+    pub fn contains(&self, n: usize) -> bool {
+        n >= self.low && n <= self.high && (n - self.low) % self.step == 0
+    }
+
+    /// Test if the ranges are sorted and pairwise disjoint, as returned by
+    /// [`Range::from_list`]
+    pub fn is_ascending(ranges: &[Self]) -> bool {
+        ranges.windows(2).all(|w| w[0].high < w[1].low)
     }
 
-    /// Merge any overlapping ranges
+    /// Merge any overlapping ranges without a step
     ///
-    /// Is guaranteed to return only disjoint ranges in a sorted order.
-    fn merge(mut ranges: Vec<Self>) -> Vec<Self> {
+    /// Is guaranteed to return the ranges in a sorted order, and those
+    /// without a step disjoint. Ranges with a step are kept as they are, see
+    /// [`Runs`] to iterate over the positions of all of them.
+    fn merge(ranges: Vec<Self>) -> Vec<Self> {
+        let (mut ranges, stepped): (Vec<_>, Vec<_>) = ranges.into_iter().partition(|r| r.step == 1);
         ranges.sort();
 
         // merge overlapping ranges
@@ -102,32 +171,150 @@ impl Range {
                 ranges[i].high = max(ranges[i].high, j_high);
             }
         }
+        ranges.extend(stepped);
+        ranges.sort();
         ranges
     }
 }
 
-pub fn complement(ranges: &[Range]) -> Vec<Range> {
-    let mut prev_high = 0;
-    let mut complements = Vec::with_capacity(ranges.len() + 1);
-
-    for range in ranges {
-        if range.low > prev_high + 1 {
-            complements.push(Range {
-                low: prev_high + 1,
-                high: range.low - 1,
-            });
+/// An iterator over the positions of ranges, or of their complement, as
+/// sorted and disjoint ranges without a step
+///
+/// The positions are found as they are needed, so that a range with a huge
+/// number of positions, like `1-100000000000:2`, is never expanded in full.
+///
+/// ```
+/// use uucore::ranges::{Range, Runs};
+///
+/// let ranges = Range::from_list("1-9:4,2-3").unwrap();
+/// let runs: Vec<_> = Runs::new(&ranges, false).collect();
+/// assert_eq!(runs, vec![Range::new(1, 3), Range::new(5, 5), Range::new(9, 9)]);
+///
+/// let mut complement = Runs::new(&ranges, true);
+/// assert_eq!(complement.next(), Some(Range::new(4, 4)));
+/// ```
+pub struct Runs<'a> {
+    ranges: &'a [Range],
+    /// The next position of each range with a step, or `None` once it has
+    /// none left; empty if no range has a step
+    next: Vec<Option<usize>>,
+    /// The index of the next range without a step
+    index: usize,
+    /// With `complement`, the first position that may not be selected, or
+    /// `None` once all positions have been seen
+    complement: Option<Option<usize>>,
+}
+
+impl<'a> Runs<'a> {
+    /// Iterate over the positions of `ranges` as returned by
+    /// [`Range::from_list`], or over those that are not in them if
+    /// `complement` is set.
+    pub fn new(ranges: &'a [Range], complement: bool) -> Self {
+        let next = if ranges.iter().any(|r| r.step > 1) {
+            ranges.iter().map(|r| (r.step > 1).then(|| r.low)).collect()
+        } else {
+            Vec::new()
+        };
+        Self {
+            ranges,
+            next,
+            index: 0,
+            complement: complement.then(|| Some(1)),
         }
-        prev_high = range.high;
     }
 
-    if prev_high < usize::MAX - 1 {
-        complements.push(Range {
-            low: prev_high + 1,
-            high: usize::MAX - 1,
-        });
+    /// The next range without a step that has not been used yet.
+    fn next_plain(&mut self) -> Option<&'a Range> {
+        while let Some(range) = self.ranges.get(self.index) {
+            if range.step == 1 {
+                return Some(range);
+            }
+            self.index += 1;
+        }
+        None
     }
 
-    complements
+    /// The next run of selected positions.
+    fn next_selected(&mut self) -> Option<Range> {
+        // The ranges without a step are sorted and disjoint, so only those
+        // with a step can overlap.
+        if self.next.is_empty() {
+            let range = *self.ranges.get(self.index)?;
+            self.index += 1;
+            return Some(range);
+        }
+
+        let low = self
+            .next_plain()
+            .map(|r| r.low)
+            .into_iter()
+            .chain(self.next.iter().flatten().copied())
+            .min()?;
+        // Extend the run as long as another range continues it.
+        let mut high = low;
+        let mut extended = true;
+        while extended {
+            extended = false;
+            while let Some(range) = self.next_plain() {
+                if range.low > high.saturating_add(1) {
+                    break;
+                }
+                high = high.max(range.high);
+                self.index += 1;
+                extended = true;
+            }
+            for (range, next) in self.ranges.iter().zip(&mut self.next) {
+                let position = match *next {
+                    Some(position) if position <= high.saturating_add(1) => position,
+                    _ => continue,
+                };
+                if position > high {
+                    high = position;
+                    extended = true;
+                }
+                // Skip the positions that are already in the run.
+                *next = ((high - position) / range.step + 1)
+                    .checked_mul(range.step)
+                    .and_then(|distance| position.checked_add(distance))
+                    .filter(|&position| position <= range.high);
+            }
+        }
+        Some(Range::new(low, high))
+    }
+}
+
+impl Iterator for Runs<'_> {
+    type Item = Range;
+
+    fn next(&mut self) -> Option<Range> {
+        let mut start = match self.complement {
+            None => return self.next_selected(),
+            Some(start) => start?,
+        };
+        loop {
+            match self.next_selected() {
+                Some(run) if run.low > start => {
+                    self.complement = Some(run.high.checked_add(1));
+                    return Some(Range::new(start, run.low - 1));
+                }
+                Some(run) => match run.high.checked_add(1) {
+                    Some(next) => start = next,
+                    None => {
+                        self.complement = Some(None);
+                        return None;
+                    }
+                },
+                None => {
+                    self.complement = Some(None);
+                    return (start < usize::MAX).then(|| Range::new(start, usize::MAX - 1));
+                }
+            }
+        }
+    }
+}
+
+pub fn complement(ranges: &[Range]) -> Vec<Range> {
+    Runs::new(ranges, true).collect()
 }
 
 /// Test if at least one of the given Ranges contain the supplied value.
@@ -135,7 +322,7 @@ pub fn complement(ranges: &[Range]) -> Vec<Range> {
 /// Examples:
 ///
 /// ```
-/// let ranges = uucore::ranges::Range::from_list("11,2,6-8").unwrap();
+/// let ranges = uucore::ranges::Range::from_list("11,2,6-8,20-30:5").unwrap();
 ///
 /// assert!(!uucore::ranges::contain(&ranges, 0));
 /// assert!(!uucore::ranges::contain(&ranges, 1));
@@ -147,27 +334,31 @@ pub fn complement(ranges: &[Range]) -> Vec<Range> {
 /// assert!(uucore::ranges::contain(&ranges, 7));
 /// assert!(uucore::ranges::contain(&ranges, 8));
 /// assert!(uucore::ranges::contain(&ranges, 11));
+/// assert!(uucore::ranges::contain(&ranges, 25));
+/// assert!(!uucore::ranges::contain(&ranges, 26));
 /// ```
 pub fn contain(ranges: &[Range], n: usize) -> bool {
-    for range in ranges {
-        if n >= range.low && n <= range.high {
-            return true;
-        }
-    }
-
-    false
+    ranges.iter().any(|range| range.contains(n))
 }
 
 #[cfg(test)]
 mod test {
-    use super::{complement, Range};
+    use super::{complement, Range, Runs};
 
     fn m(a: Vec<Range>, b: &[Range]) {
         assert_eq!(Range::merge(a), b);
     }
 
     fn r(low: usize, high: usize) -> Range {
-        Range { low, high }
+        Range::new(low, high)
+    }
+
+    fn s(low: usize, high: usize, step: usize) -> Range {
+        Range { low, high, step }
+    }
+
+    fn runs(list: &str, complement: bool) -> Vec<Range> {
+        Runs::new(&Range::from_list(list).unwrap(), complement).collect()
     }
 
     #[test]
@@ -231,4 +422,73 @@ mod test {
         // With start and end
         assert_eq!(complement(&[r(1, 4), r(6, usize::MAX - 1)]), vec![r(5, 5)]);
     }
+
+    #[test]
+    fn ascending() {
+        assert!(Range::is_ascending(&[]));
+        assert!(Range::is_ascending(&[r(1, 2), r(4, 5)]));
+        assert!(!Range::is_ascending(&[r(4, 5), r(1, 2)]));
+        assert!(!Range::is_ascending(&[r(1, 3), r(2, 4)]));
+        assert!(!Range::is_ascending(&[r(2, 2), r(2, 2)]));
+    }
+
+    #[test]
+    fn stepped() {
+        assert_eq!(Range::from_list("1-7:3").unwrap(), vec![s(1, 7, 3)]);
+        // the last step may not reach the upper bound
+        assert_eq!(Range::from_list("-6:4").unwrap(), vec![s(1, 5, 4)]);
+        assert_eq!(Range::from_list("2-4:3").unwrap(), vec![r(2, 2)]);
+        // a step of one is a plain range
+        assert_eq!(Range::from_list("2-5:1").unwrap(), vec![r(2, 5)]);
+        assert_eq!(
+            Range::from_list("3-:1").unwrap(),
+            vec![r(3, usize::MAX - 1)]
+        );
+        // stepped ranges are kept apart from the merged plain ones
+        assert_eq!(
+            Range::from_list("1-5:2,2,3-4").unwrap(),
+            vec![s(1, 5, 2), r(2, 4)]
+        );
+        assert_eq!(
+            complement(&Range::from_list("2-6:2").unwrap()),
+            vec![r(1, 1), r(3, 3), r(5, 5), r(7, usize::MAX - 1)]
+        );
+        assert!(Range::from_list("3-9:3").unwrap()[0].contains(6));
+        assert!(!Range::from_list("3-9:3").unwrap()[0].contains(7));
+        assert!(!Range::from_list("3-9:3").unwrap()[0].contains(12));
+
+        assert!(Range::from_list("1-5:0").is_err());
+        assert!(Range::from_list("1-5:x").is_err());
+        assert!(Range::from_list("1-5:").is_err());
+        assert!(Range::from_list("3-:2").is_err());
+    }
+
+    #[test]
+    fn running() {
+        assert_eq!(runs("1-3,5-", false), vec![r(1, 3), r(5, usize::MAX - 1)]);
+        assert_eq!(runs("1-3,5-", true), vec![r(4, 4)]);
+        assert_eq!(runs("1-", true), vec![]);
+        assert_eq!(runs("1-5:2,2", false), vec![r(1, 3), r(5, 5)]);
+        assert_eq!(
+            runs("1-9:4,2-3,6-7", false),
+            vec![r(1, 3), r(5, 7), r(9, 9)]
+        );
+        assert_eq!(runs("1-9:2,2-10:2", false), vec![r(1, 10)]);
+        assert_eq!(runs("1-9:2,2-10:2", true), vec![r(11, usize::MAX - 1)]);
+        assert_eq!(
+            runs("2-8:3,3-9:3", true),
+            vec![r(1, 1), r(4, 4), r(7, 7), r(10, usize::MAX - 1)]
+        );
+        assert_eq!(runs("4-8:2,6-", false), vec![r(4, 4), r(6, usize::MAX - 1)]);
+
+        // huge stepped ranges are never expanded
+        let ranges = Range::from_list("1-100000000000:2").unwrap();
+        assert_eq!(ranges, vec![s(1, 99_999_999_999, 2)]);
+        let mut selected = Runs::new(&ranges, false);
+        assert_eq!(selected.next(), Some(r(1, 1)));
+        assert_eq!(selected.next(), Some(r(3, 3)));
+        let mut complemented = Runs::new(&ranges, true);
+        assert_eq!(complemented.next(), Some(r(2, 2)));
+        assert_eq!(complemented.next(), Some(r(4, 4)));
+    }
 }
@@ -28,9 +28,11 @@ use crate::display::Quotable;
 /// * "h" for hours,
 /// * "d" for days.
 ///
-/// This function uses [`Duration::saturating_mul`] to compute the
-/// number of seconds, so it does not overflow. If overflow would have
-/// occurred, [`Duration::MAX`] is returned instead.
+/// The number is decimal, possibly with an exponent like in "1.5e3", or
+/// "inf" or "infinity". It is converted exactly, and rounded up to the next
+/// nanosecond, so that durations like "0.1m" or "1e-10d" mean what they
+/// say. Durations too long for a [`Duration`], including infinite ones, are
+/// [`Duration::MAX`].
 ///
 /// # Errors
 ///
@@ -45,40 +47,95 @@ use crate::display::Quotable;
 /// use uucore::parse_time::from_str;
 /// assert_eq!(from_str("123"), Ok(Duration::from_secs(123)));
 /// assert_eq!(from_str("2d"), Ok(Duration::from_secs(60 * 60 * 24 * 2)));
+/// assert_eq!(from_str("0.1m"), Ok(Duration::from_secs(6)));
 /// ```
 pub fn from_str(string: &str) -> Result<Duration, String> {
-    let len = string.len();
-    if len == 0 {
-        return Err("empty string".to_owned());
+    let (numstr, times) = match string.char_indices().next_back() {
+        None => return Err("empty string".to_owned()),
+        Some((i, 's')) => (&string[..i], 1),
+        Some((i, 'm')) => (&string[..i], 60),
+        Some((i, 'h')) => (&string[..i], 60 * 60),
+        Some((i, 'd')) => (&string[..i], 60 * 60 * 24),
+        Some(_) => (string, 1),
+    };
+    let nanos = parse_nanos(numstr, times)
+        .ok_or_else(|| format!("invalid time interval {}", string.quote()))?;
+
+    const NANOS_PER_SEC: u128 = 1_000_000_000;
+    Ok(match u64::try_from(nanos / NANOS_PER_SEC) {
+        Ok(secs) => Duration::new(secs, (nanos % NANOS_PER_SEC) as u32),
+        Err(_) => Duration::MAX,
+    })
+}
+
+/// The number of nanoseconds in `times` times the number of seconds in
+/// `numstr`, rounded up and saturating at [`u128::MAX`].
+///
+/// Returns `None` if `numstr` is not a number or is negative.
+fn parse_nanos(numstr: &str, times: u128) -> Option<u128> {
+    // The most decimal digits that always fit in a `u128`.
+    const MAX_DIGITS: usize = 38;
+
+    let (negative, unsigned) = match numstr.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, numstr.strip_prefix('+').unwrap_or(numstr)),
+    };
+    if unsigned.eq_ignore_ascii_case("inf") || unsigned.eq_ignore_ascii_case("infinity") {
+        return (!negative).then(|| u128::MAX);
     }
-    let slice = &string[..len - 1];
-    let (numstr, times) = match string.chars().next_back().unwrap() {
-        's' => (slice, 1),
-        'm' => (slice, 60),
-        'h' => (slice, 60 * 60),
-        'd' => (slice, 60 * 60 * 24),
-        val if !val.is_alphabetic() => (string, 1),
-        _ => {
-            if string == "inf" || string == "infinity" {
-                ("inf", 1)
-            } else {
-                return Err(format!("invalid time interval {}", string.quote()));
+
+    let (mantissa, exponent) = match unsigned.find(['e', 'E']) {
+        Some(pos) => {
+            let exponent = &unsigned[pos + 1..];
+            let digits = exponent.trim_start_matches(['-', '+']);
+            if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+                return None;
             }
+            // Exponents this large make any number zero or too long anyway.
+            let exponent = exponent.parse::<i64>().unwrap_or(0).clamp(-1000, 1000);
+            (&unsigned[..pos], exponent)
         }
+        None => (unsigned, 0),
     };
-    let num = numstr
-        .parse::<f64>()
-        .map_err(|e| format!("invalid time interval {}: {}", string.quote(), e))?;
-
-    if num < 0. {
-        return Err(format!("invalid time interval {}", string.quote()));
+    let (integer, fraction) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+    let all_digits = |s: &str| s.bytes().all(|b| b.is_ascii_digit());
+    if integer.len() + fraction.len() == 0 || !all_digits(integer) || !all_digits(fraction) {
+        return None;
     }
 
-    const NANOS_PER_SEC: u32 = 1_000_000_000;
-    let whole_secs = num.trunc();
-    let nanos = (num.fract() * (NANOS_PER_SEC as f64)).trunc();
-    let duration = Duration::new(whole_secs as u64, nanos as u32);
-    Ok(duration.saturating_mul(times))
+    // The number is `digits * 10^(scale - 9)` nanoseconds, where only the
+    // leading significant digits are kept, but the others round up.
+    let significant = format!("{integer}{fraction}");
+    let significant = significant.trim_start_matches('0');
+    if significant.is_empty() {
+        return Some(0);
+    }
+    if negative {
+        return None;
+    }
+    let kept = significant.len().min(MAX_DIGITS);
+    let inexact = significant[kept..].bytes().any(|b| b != b'0');
+    let digits: u128 = significant[..kept].parse().unwrap();
+    let scale = exponent + 9 - fraction.len() as i64 + (significant.len() - kept) as i64;
+
+    let value = digits.saturating_mul(times);
+    let nanos = if scale >= 0 {
+        u32::try_from(scale)
+            .ok()
+            .and_then(|scale| 10u128.checked_pow(scale))
+            .and_then(|power| value.checked_mul(power))
+            .unwrap_or(u128::MAX)
+    } else {
+        match u32::try_from(-scale)
+            .ok()
+            .and_then(|scale| 10u128.checked_pow(scale))
+        {
+            Some(divisor) => value / divisor + u128::from(value % divisor != 0 || inexact),
+            // Less than a nanosecond.
+            None => 1,
+        }
+    };
+    Some(nanos)
 }
 
 #[cfg(test)]
@@ -120,6 +177,40 @@ mod tests {
     #[test]
     fn test_negative() {
         assert!(from_str("-1").is_err());
+        assert!(from_str("-inf").is_err());
+        assert_eq!(from_str("-0"), Ok(Duration::ZERO));
+    }
+
+    #[test]
+    fn test_exact_fractions() {
+        assert_eq!(from_str("0.1m"), Ok(Duration::from_secs(6)));
+        assert_eq!(from_str("1.5h"), Ok(Duration::from_secs(5400)));
+        assert_eq!(from_str(".000001d"), Ok(Duration::from_micros(86_400)));
+        assert_eq!(from_str("1e-10d"), Ok(Duration::from_nanos(8_640)));
+        assert_eq!(from_str("2.5e2s"), Ok(Duration::from_secs(250)));
+        assert_eq!(from_str("+1."), Ok(Duration::from_secs(1)));
+        // Fractions of nanoseconds round up.
+        assert_eq!(from_str("1e-10"), Ok(Duration::from_nanos(1)));
+        assert_eq!(from_str("0.0000000011"), Ok(Duration::from_nanos(2)));
+        assert_eq!(
+            from_str("0.000000001000000000000000000000000000000000001"),
+            Ok(Duration::from_nanos(2))
+        );
+    }
+
+    #[test]
+    fn test_infinity() {
+        assert_eq!(from_str("inf"), Ok(Duration::MAX));
+        assert_eq!(from_str("Infinityd"), Ok(Duration::MAX));
+        assert_eq!(from_str("1e1000"), Ok(Duration::MAX));
+        assert_eq!(from_str("1e-1000"), Ok(Duration::from_nanos(1)));
+    }
+
+    #[test]
+    fn test_error_invalid_number() {
+        for input in [".", "s", "1e", "1e+", "0x10", "1..2", "nan", "1 "] {
+            assert!(from_str(input).is_err(), "{input}");
+        }
     }
 
     /// Test that capital letters are not allowed in suffixes.
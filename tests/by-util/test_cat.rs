@@ -134,6 +134,32 @@ fn test_piped_to_regular_file() {
     }
 }
 
+#[test]
+#[cfg(unix)]
+fn test_files_to_regular_file() {
+    // Lines that don't end at the end of a file must not be left behind when the output is
+    // written differently for the next file.
+    let content: String = (0..100_000).map(|i| format!("line {i}\n")).collect();
+    let content = format!("{content}no newline");
+
+    for append in [true, false] {
+        let s = TestScenario::new(util_name!());
+        s.fixtures.write("in", &content);
+        let file_path = s.fixtures.plus("out");
+        {
+            let file = OpenOptions::new()
+                .create_new(true)
+                .write(true)
+                .append(append)
+                .open(&file_path)
+                .unwrap();
+
+            s.ucmd().set_stdout(file).args(&["in", "in"]).succeeds();
+        }
+        assert_eq!(s.fixtures.read("out"), content.repeat(2));
+    }
+}
+
 #[test]
 #[cfg(unix)]
 fn test_piped_to_dev_null() {
@@ -378,6 +404,44 @@ fn test_non_blank_overrides_number() {
     }
 }
 
+#[test]
+fn test_carriage_returns_with_numbering_and_squeezing() {
+    new_ucmd!()
+        .args(&["-n"])
+        .pipe_in("\r\nx\r\n\n\r\n")
+        .succeeds()
+        .stdout_only("     1\t\r\n     2\tx\r\n     3\t\n     4\t\r\n");
+    new_ucmd!()
+        .args(&["-s"])
+        .pipe_in("a\r\n\n\n\nb\rc\n")
+        .succeeds()
+        .stdout_only("a\r\n\nb\rc\n");
+}
+
+#[test]
+fn test_show_all_long_line() {
+    // The line is formatted without being collected.
+    let line = "\x01a\t".repeat(100_000);
+    new_ucmd!()
+        .arg("-A")
+        .pipe_in(line)
+        .succeeds()
+        .stdout_only("^Aa^I".repeat(100_000));
+}
+
+#[test]
+#[cfg(any(target_os = "linux", target_os = "freebsd", target_os = "netbsd"))]
+fn test_show_all_to_dev_full() {
+    let dev_full = OpenOptions::new().write(true).open("/dev/full").unwrap();
+
+    new_ucmd!()
+        .arg("-A")
+        .set_stdout(dev_full)
+        .pipe_in("a\tb\n")
+        .fails()
+        .stderr_contains("No space left on device");
+}
+
 #[test]
 fn test_squeeze_blank_before_numbering() {
     for same_param in ["-s", "--squeeze-blank"] {
@@ -454,6 +518,20 @@ fn test_dev_full_show_all() {
     proc.kill();
 }
 
+#[test]
+fn test_output_not_held_back_while_reading() {
+    let mut proc = new_ucmd!()
+        .arg("-n")
+        .set_stdin(Stdio::piped())
+        .set_stdout(Stdio::piped())
+        .run_no_wait();
+    // The line must come out while stdin is still open.
+    proc.write_in("a\n");
+    assert_eq!(proc.stdout_exact_bytes(9), b"     1\ta\n");
+    proc.close_stdin();
+    proc.wait().unwrap().success().no_stderr();
+}
+
 #[test]
 #[cfg(unix)]
 #[ignore]
@@ -277,6 +277,57 @@ fn test_chmod_many_options() {
     }
 }
 
+#[test]
+fn test_chmod_conditional_execute() {
+    let tests = vec![
+        TestCase {
+            args: vec!["a+X", TEST_FILE],
+            before: 0o100644,
+            after: 0o100644,
+        },
+        TestCase {
+            args: vec!["a+X", TEST_FILE],
+            before: 0o100744,
+            after: 0o100755,
+        },
+        TestCase {
+            args: vec!["go=rX", TEST_FILE],
+            before: 0o100700,
+            after: 0o100755,
+        },
+        // Each clause sees the execute bits left by the previous ones.
+        TestCase {
+            args: vec!["u+x,go+X", TEST_FILE],
+            before: 0o100644,
+            after: 0o100755,
+        },
+        TestCase {
+            args: vec!["a-x,a+X", TEST_FILE],
+            before: 0o100755,
+            after: 0o100644,
+        },
+    ];
+    run_tests(tests);
+
+    let (at, mut ucmd) = at_and_ucmd!();
+    at.mkdir("dir");
+    set_permissions(at.plus("dir"), Permissions::from_mode(0o600)).unwrap();
+    ucmd.args(&["go=rX,u+X", "dir"]).succeeds().no_stderr();
+    assert_eq!(at.metadata("dir").permissions().mode(), 0o40755);
+}
+
+#[test]
+fn test_chmod_invalid_mode() {
+    for mode in ["u+z", "u=gw", "755,u+s", "a+x,", "u=755", "x+"] {
+        let (at, mut ucmd) = at_and_ucmd!();
+        at.touch(TEST_FILE);
+        ucmd.args(&[mode, TEST_FILE])
+            .fails()
+            .code_is(1)
+            .usage_error(format!("invalid mode: '{mode}'"));
+    }
+}
+
 #[test]
 #[allow(clippy::unreadable_literal)]
 fn test_chmod_reference_file() {
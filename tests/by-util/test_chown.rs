@@ -679,6 +679,127 @@ fn test_chown_recursive() {
     result.stderr_contains("ownership of 'z/y' retained as");
 }
 
+#[test]
+fn test_chown_recursive_dereference_requires_traversal() {
+    new_ucmd!()
+        .arg("-R")
+        .arg("--dereference")
+        .arg("0")
+        .arg(".")
+        .fails()
+        .stderr_is("chown: -R --dereference requires either -H or -L\n");
+}
+
+#[cfg(unix)]
+#[test]
+fn test_chown_recursive_dangling_symlink() {
+    let scene = TestScenario::new(util_name!());
+    let at = &scene.fixtures;
+
+    let result = scene.cmd("whoami").run();
+    if skipping_test_is_okay(&result, "whoami: cannot find name for user ID") {
+        return;
+    }
+    let user_name = String::from(result.stdout_str().trim());
+    assert!(!user_name.is_empty());
+
+    at.mkdir("a");
+    at.touch("a/file");
+    at.symlink_file("nowhere", "a/dangling");
+
+    // -P changes the symlink itself
+    scene
+        .ucmd()
+        .arg("-R")
+        .arg(&user_name)
+        .arg("a")
+        .succeeds()
+        .no_stderr();
+    // the error within the tree must not be hidden by the files after it
+    for arg in ["-H", "-L"] {
+        scene
+            .ucmd()
+            .arg("-R")
+            .arg(arg)
+            .arg(&user_name)
+            .arg("a")
+            .fails()
+            .stderr_contains("cannot dereference 'a/dangling'");
+    }
+}
+
+#[test]
+fn test_chown_from_excluded_verbose() {
+    let scene = TestScenario::new(util_name!());
+    let at = &scene.fixtures;
+
+    let result = scene.cmd("whoami").run();
+    if skipping_test_is_okay(&result, "whoami: cannot find name for user ID") {
+        return;
+    }
+    let user_name = String::from(result.stdout_str().trim());
+    assert!(!user_name.is_empty());
+
+    at.touch("file");
+    // This assumes that no file is owned by the user ID 12345.
+    scene
+        .ucmd()
+        .arg("--verbose")
+        .arg("--from=12345")
+        .arg(&user_name)
+        .arg("file")
+        .succeeds()
+        .stderr_contains(format!("ownership of 'file' retained as {}:", user_name));
+    scene
+        .ucmd()
+        .arg("--changes")
+        .arg("--from=12345")
+        .arg(&user_name)
+        .arg("file")
+        .succeeds()
+        .no_stderr();
+}
+
+#[cfg(unix)]
+#[test]
+fn test_chown_login_group() {
+    use std::os::unix::fs::MetadataExt;
+
+    let scene = TestScenario::new(util_name!());
+    let at = &scene.fixtures;
+
+    let result = scene.cmd("whoami").run();
+    if skipping_test_is_okay(&result, "whoami: cannot find name for user ID") {
+        return;
+    }
+    let user_name = String::from(result.stdout_str().trim());
+    assert!(!user_name.is_empty());
+    let result = scene.cmd_keepenv("id").arg("-g").run();
+    if skipping_test_is_okay(&result, "id: cannot find name for group ID") {
+        return;
+    }
+    let login_gid: u32 = result.stdout_str().trim().parse().unwrap();
+
+    at.touch("file");
+    scene
+        .ucmd()
+        .arg(format!("{}:", user_name))
+        .arg("file")
+        .succeeds()
+        .no_stderr();
+    assert_eq!(at.metadata("file").gid(), login_gid);
+
+    // "OWNER:" in --from only matches files in the login group
+    scene
+        .ucmd()
+        .arg("--verbose")
+        .arg(format!("--from={}:", user_name))
+        .arg(&user_name)
+        .arg("file")
+        .succeeds()
+        .stderr_contains("retained as");
+}
+
 #[test]
 fn test_root_preserve() {
     let scene = TestScenario::new(util_name!());
@@ -87,22 +87,64 @@ fn output_delimiter_nul() {
         .stdout_only_fixture("ab_delimiter_nul.expected");
 }
 
-// even though (info) documentation suggests this is an option
-// in latest GNU Coreutils comm, it actually is not.
-// this test is essentially an alarm in case some well-intending
-// developer implements it.
-//marked as unimplemented as error message not set yet.
-#[cfg_attr(not(feature = "test_unimplemented"), ignore)]
+#[test]
+fn total_with_output_delimiter() {
+    new_ucmd!()
+        .args(&["--total", "--output-delimiter=word", "a", "b"])
+        .succeeds()
+        .stdout_only("a\nwordb\nwordwordz\n1word1word1wordtotal\n");
+
+    // The columns are separated by NULs, but the totals aren't separated.
+    new_ucmd!()
+        .args(&["--total", "--output-delimiter=", "a", "b"])
+        .succeeds()
+        .stdout_only("a\n\0b\n\0\0z\n111total\n");
+}
+
+#[test]
+fn multiple_output_delimiters() {
+    new_ucmd!()
+        .args(&["--output-delimiter=,", "--output-delimiter=,", "a", "b"])
+        .succeeds()
+        .stdout_only("a\n,b\n,,z\n");
+
+    new_ucmd!()
+        .args(&["--output-delimiter=,", "--output-delimiter=;", "a", "b"])
+        .fails()
+        .code_is(1)
+        .stderr_only("comm: multiple output delimiters specified\n");
+}
+
 #[test]
 fn zero_terminated() {
     for param in ["-z", "--zero-terminated"] {
-        new_ucmd!()
-            .args(&[param, "a", "b"])
-            .fails()
-            .stderr_only("error to be defined");
+        let (at, mut ucmd) = at_and_ucmd!();
+        at.write("a_nul", "a\0z\0");
+        at.write("b_nul", "b\0z\0");
+        ucmd.args(&[param, "--total", "a_nul", "b_nul"])
+            .succeeds()
+            .stdout_only("a\0\tb\0\t\tz\x001\t1\t1\ttotal\0");
     }
 }
 
+#[test]
+fn missing_final_newline() {
+    let (at, mut ucmd) = at_and_ucmd!();
+    at.write("c", "a\nz");
+    ucmd.args(&["c", "b"])
+        .succeeds()
+        .stdout_only("a\n\tb\n\t\tz\n");
+}
+
+#[test]
+fn non_utf8_lines() {
+    let (at, mut ucmd) = at_and_ucmd!();
+    at.write_bytes("c", b"a\xff\nz\n");
+    ucmd.args(&["c", "b"])
+        .succeeds()
+        .stdout_is_bytes(b"a\xff\n\tb\n\t\tz\n");
+}
+
 #[cfg_attr(not(feature = "test_unimplemented"), ignore)]
 #[test]
 fn check_order() {
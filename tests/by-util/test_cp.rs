@@ -961,6 +961,50 @@ fn test_cp_preserve_xattr() {
     }
 }
 
+#[test]
+#[cfg(target_os = "linux")]
+fn test_cp_preserve_xattr_copies_attributes() {
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+
+    fn path(at: &AtPath, name: &str) -> CString {
+        CString::new(at.plus(name).as_os_str().as_bytes()).unwrap()
+    }
+
+    let scene = TestScenario::new(util_name!());
+    let at = &scene.fixtures;
+    at.touch("a");
+    let name = CString::new("user.test").unwrap();
+    let set = unsafe {
+        libc::setxattr(
+            path(at, "a").as_ptr(),
+            name.as_ptr(),
+            b"value".as_ptr().cast(),
+            5,
+            0,
+        )
+    };
+    if set != 0 {
+        // The file system does not support user attributes.
+        return;
+    }
+
+    for (arg, dest) in [("--preserve=xattr", "b"), ("-a", "c")] {
+        scene.ucmd().args(&[arg, "a", dest]).succeeds().no_output();
+        let mut value = [0u8; 16];
+        let len = unsafe {
+            libc::getxattr(
+                path(at, dest).as_ptr(),
+                name.as_ptr(),
+                value.as_mut_ptr().cast(),
+                value.len(),
+            )
+        };
+        assert_eq!(len, 5);
+        assert_eq!(&value[..5], b"value");
+    }
+}
+
 #[test]
 #[cfg(all(target_os = "linux", not(feature = "feat_selinux")))]
 fn test_cp_preserve_all_context_fails_on_non_selinux() {
@@ -1430,6 +1474,53 @@ fn test_cp_one_file_system() {
     }
 }
 
+#[test]
+fn test_cp_jobs() {
+    let scene = TestScenario::new(util_name!());
+    let at = &scene.fixtures;
+    for dir in ["src", "src/a", "src/a/b", "src/c"] {
+        at.mkdir(dir);
+        for i in 0..20 {
+            at.write(&format!("{dir}/{i}"), &format!("{dir} {i}"));
+        }
+    }
+    #[cfg(unix)]
+    at.relative_symlink_file("0", "src/a/link");
+
+    for (jobs, dest) in [("-j4", "dest1"), ("--jobs=0", "dest2")] {
+        scene
+            .ucmd()
+            .args(&["-r", jobs, "src", dest])
+            .succeeds()
+            .no_output();
+        for dir in ["src", "src/a", "src/a/b", "src/c"] {
+            for i in 0..20 {
+                let copy = format!("{dest}{}/{i}", &dir[3..]);
+                assert_eq!(at.read(&copy), format!("{dir} {i}"));
+            }
+        }
+        #[cfg(unix)]
+        assert!(at.is_symlink(&format!("{dest}/a/link")));
+    }
+
+    scene
+        .ucmd()
+        .args(&["-r", "-j", "x", "src", "dest3"])
+        .fails()
+        .stderr_contains("Invalid value 'x' for '--jobs <N>'");
+}
+
+#[test]
+fn test_cp_progress() {
+    let (at, mut ucmd) = at_and_ucmd!();
+    at.mkdir("dir");
+    at.write("dir/a", "hello");
+    ucmd.args(&["-g", "-r", "dir", "copy"])
+        .succeeds()
+        .no_stdout();
+    assert_eq!(at.read("copy/a"), "hello");
+}
+
 #[test]
 #[cfg(any(target_os = "linux", target_os = "android", target_os = "macos"))]
 fn test_cp_reflink_always() {
@@ -1444,7 +1535,11 @@ fn test_cp_reflink_always() {
         // Check the content of the destination file
         assert_eq!(at.read(TEST_EXISTING_FILE), "Hello, World!\n");
     } else {
-        // Older Linux versions do not support cloning.
+        // Older Linux versions and many file systems do not support cloning.
+        result.stderr_only(format!(
+            "cp: failed to clone '{TEST_EXISTING_FILE}' from '{TEST_HELLO_WORLD_SOURCE}': \
+             Operation not supported\n"
+        ));
     }
 }
 
@@ -2460,6 +2555,39 @@ fn test_reflink_never_sparse_always() {
     assert_eq!(dest_metadata.len(), 1024 * 1024);
 }
 
+#[cfg(target_os = "linux")]
+#[test]
+fn test_sparse_auto_keeps_holes() {
+    use std::os::unix::fs::FileExt;
+
+    let scene = TestScenario::new(util_name!());
+    let at = &scene.fixtures;
+
+    // A one megabyte file with a single block of data in the middle.
+    let file = std::fs::File::create(at.plus("src")).unwrap();
+    file.set_len(1024 * 1024).unwrap();
+    file.write_all_at(b"data", 512 * 1024).unwrap();
+    let src_metadata = file.metadata().unwrap();
+    if src_metadata.blocks() * 512 >= src_metadata.len() {
+        // The file system does not support holes.
+        return;
+    }
+
+    for args in [&["src", "dest1"][..], &["--reflink=never", "src", "dest2"]] {
+        scene.ucmd().args(args).succeeds().no_output();
+        let dest = args[args.len() - 1];
+        assert_eq!(at.read_bytes(dest), at.read_bytes("src"));
+        assert_eq!(at.metadata(dest).blocks(), src_metadata.blocks());
+    }
+
+    scene
+        .ucmd()
+        .args(&["--sparse=never", "src", "dest3"])
+        .succeeds();
+    assert_eq!(at.read_bytes("dest3"), at.read_bytes("src"));
+    assert_eq!(at.metadata("dest3").blocks() * 512, 1024 * 1024);
+}
+
 /// Test for preserving attributes of a hard link in a directory.
 #[test]
 #[cfg(not(target_os = "android"))]
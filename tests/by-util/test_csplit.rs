@@ -453,14 +453,14 @@ fn test_up_to_match_offset_option_suppress_matched() {
     let (at, mut ucmd) = at_and_ucmd!();
     ucmd.args(&["numbers50.txt", "--suppress-matched", "/10/+4"])
         .succeeds()
-        .stdout_only("27\n111\n");
+        .stdout_only("30\n108\n");
 
     let count = glob(&at.plus_as_string("xx*"))
         .expect("there should be splits created")
         .count();
     assert_eq!(count, 2);
-    assert_eq!(at.read("xx00"), generate(1, 10) + &generate(11, 14));
-    assert_eq!(at.read("xx01"), generate(14, 51));
+    assert_eq!(at.read("xx00"), generate(1, 14));
+    assert_eq!(at.read("xx01"), generate(15, 51));
 }
 
 #[test]
@@ -468,14 +468,14 @@ fn test_up_to_match_negative_offset_option_suppress_matched() {
     let (at, mut ucmd) = at_and_ucmd!();
     ucmd.args(&["numbers50.txt", "--suppress-matched", "/10/-4"])
         .succeeds()
-        .stdout_only("10\n128\n");
+        .stdout_only("10\n129\n");
 
     let count = glob(&at.plus_as_string("xx*"))
         .expect("there should be splits created")
         .count();
     assert_eq!(count, 2);
     assert_eq!(at.read("xx00"), generate(1, 6));
-    assert_eq!(at.read("xx01"), generate(6, 10) + &generate(11, 51));
+    assert_eq!(at.read("xx01"), generate(7, 51));
 }
 
 #[test]
@@ -571,8 +571,7 @@ fn test_skip_to_match_context_underflow() {
     let (at, mut ucmd) = at_and_ucmd!();
     ucmd.args(&["numbers50.txt", "%5%-10"])
         .fails()
-        .stdout_is("141\n")
-        .stderr_is("csplit: '%5%-10': line number out of range\n");
+        .stderr_only("csplit: '%5%-10': line number out of range\n");
 
     let count = glob(&at.plus_as_string("xx*"))
         .expect("counting splits")
@@ -582,14 +581,12 @@ fn test_skip_to_match_context_underflow() {
     let (at, mut ucmd) = at_and_ucmd!();
     ucmd.args(&["numbers50.txt", "%5%-10", "-k"])
         .fails()
-        .stdout_is("141\n")
-        .stderr_is("csplit: '%5%-10': line number out of range\n");
+        .stderr_only("csplit: '%5%-10': line number out of range\n");
 
     let count = glob(&at.plus_as_string("xx*"))
         .expect("counting splits")
         .count();
-    assert_eq!(count, 1);
-    assert_eq!(at.read("xx00"), generate(1, 51));
+    assert_eq!(count, 0);
 }
 
 #[test]
@@ -1221,13 +1218,12 @@ fn test_corner_case4() {
     assert_eq!(at.read("xx02"), generate(26, 51));
 }
 
-// NOTE: differs from gnu's output: the empty split is not written
 #[test]
 fn test_up_to_match_context_underflow() {
     let (at, mut ucmd) = at_and_ucmd!();
     ucmd.args(&["numbers50.txt", "/5/-10"])
         .fails()
-        .stdout_is("0\n141\n")
+        .stdout_is("0\n")
         .stderr_is("csplit: '/5/-10': line number out of range\n");
 
     let count = glob(&at.plus_as_string("xx*"))
@@ -1238,26 +1234,24 @@ fn test_up_to_match_context_underflow() {
     let (at, mut ucmd) = at_and_ucmd!();
     ucmd.args(&["numbers50.txt", "/5/-10", "-k"])
         .fails()
-        .stdout_is("0\n141\n")
+        .stdout_is("0\n")
         .stderr_is("csplit: '/5/-10': line number out of range\n");
 
     let count = glob(&at.plus_as_string("xx*"))
         .expect("counting splits")
         .count();
-    assert_eq!(count, 2);
+    assert_eq!(count, 1);
     assert_eq!(at.read("xx00"), "");
-    assert_eq!(at.read("xx01"), generate(1, 51));
 }
 
 // the offset is out of range because of the first pattern
-// NOTE: output different than gnu's: the empty split is written but the rest of the input file is not
 #[test]
 fn test_line_num_range_with_up_to_match1() {
     let (at, mut ucmd) = at_and_ucmd!();
     ucmd.args(&["numbers50.txt", "10", "/12/-5"])
         .fails()
         .stderr_is("csplit: '/12/-5': line number out of range\n")
-        .stdout_is("18\n0\n123\n");
+        .stdout_is("18\n0\n");
 
     let count = glob(&at.plus_as_string("xx*"))
         .expect("there should be splits created")
@@ -1268,26 +1262,24 @@ fn test_line_num_range_with_up_to_match1() {
     ucmd.args(&["numbers50.txt", "10", "/12/-5", "-k"])
         .fails()
         .stderr_is("csplit: '/12/-5': line number out of range\n")
-        .stdout_is("18\n0\n123\n");
+        .stdout_is("18\n0\n");
 
     let count = glob(&at.plus_as_string("xx*"))
         .expect("there should be splits created")
         .count();
-    assert_eq!(count, 3);
+    assert_eq!(count, 2);
     assert_eq!(at.read("xx00"), generate(1, 10));
     assert_eq!(at.read("xx01"), "");
-    assert_eq!(at.read("xx02"), generate(10, 51));
 }
 
 // the offset is out of range because more lines are needed than physically available
-// NOTE: output different than gnu's: the empty split is not written but the rest of the input file is
 #[test]
 fn test_line_num_range_with_up_to_match2() {
     let (at, mut ucmd) = at_and_ucmd!();
     ucmd.args(&["numbers50.txt", "10", "/12/-15"])
         .fails()
         .stderr_is("csplit: '/12/-15': line number out of range\n")
-        .stdout_is("18\n0\n123\n");
+        .stdout_is("18\n0\n");
 
     let count = glob(&at.plus_as_string("xx*"))
         .expect("there should be splits created")
@@ -1298,32 +1290,31 @@ fn test_line_num_range_with_up_to_match2() {
     ucmd.args(&["numbers50.txt", "10", "/12/-15", "-k"])
         .fails()
         .stderr_is("csplit: '/12/-15': line number out of range\n")
-        .stdout_is("18\n0\n123\n");
+        .stdout_is("18\n0\n");
 
     let count = glob(&at.plus_as_string("xx*"))
         .expect("there should be splits created")
         .count();
-    assert_eq!(count, 3);
+    assert_eq!(count, 2);
     assert_eq!(at.read("xx00"), generate(1, 10));
     assert_eq!(at.read("xx01"), "");
-    assert_eq!(at.read("xx02"), generate(10, 51));
 }
 
-// NOTE: output different than gnu's: the pattern /10/ is matched but should not
+// the line matching /10/ has not been split yet by the first pattern
 #[test]
 fn test_line_num_range_with_up_to_match3() {
     let (at, mut ucmd) = at_and_ucmd!();
-    ucmd.args(&["numbers50.txt", "10", "/10/", "-k"])
-        .fails()
-        .stderr_is("csplit: '/10/': match not found\n")
-        .stdout_is("18\n123\n");
+    ucmd.args(&["numbers50.txt", "10", "/10/"])
+        .succeeds()
+        .stdout_only("18\n0\n123\n");
 
     let count = glob(&at.plus_as_string("xx*"))
         .expect("there should be splits created")
         .count();
-    assert_eq!(count, 2);
+    assert_eq!(count, 3);
     assert_eq!(at.read("xx00"), generate(1, 10));
-    assert_eq!(at.read("xx01"), generate(10, 51));
+    assert_eq!(at.read("xx01"), "");
+    assert_eq!(at.read("xx02"), generate(10, 51));
 
     let (at, mut ucmd) = at_and_ucmd!();
     ucmd.args(&["numbers50.txt", "/10/", "10"])
@@ -1338,3 +1329,60 @@ fn test_line_num_range_with_up_to_match3() {
     assert_eq!(at.read("xx01"), "");
     assert_eq!(at.read("xx02"), generate(10, 51));
 }
+
+#[test]
+fn test_up_to_match_negative_offset_repeat_always_suppress_matched() {
+    let (at, mut ucmd) = at_and_ucmd!();
+    ucmd.args(&["numbers50.txt", "--suppress-matched", "/0$/-2", "{*}"])
+        .succeeds()
+        .stdout_only("14\n26\n27\n27\n27\n6\n");
+
+    let count = glob(&at.plus_as_string("xx*"))
+        .expect("there should be splits created")
+        .count();
+    assert_eq!(count, 6);
+    assert_eq!(at.read("xx00"), generate(1, 8));
+    assert_eq!(at.read("xx01"), generate(9, 18));
+    assert_eq!(at.read("xx02"), generate(19, 28));
+    assert_eq!(at.read("xx03"), generate(29, 38));
+    assert_eq!(at.read("xx04"), generate(39, 48));
+    assert_eq!(at.read("xx05"), generate(49, 51));
+}
+
+#[test]
+fn test_skip_to_match_offset_repeat_always() {
+    let (at, mut ucmd) = at_and_ucmd!();
+    ucmd.args(&["numbers50.txt", "%0$%+1", "{*}"])
+        .succeeds()
+        .no_stdout();
+
+    let count = glob(&at.plus_as_string("xx*"))
+        .expect("counting splits")
+        .count();
+    assert_eq!(count, 0);
+}
+
+// the lines split by the line number pattern are not searched by the regular expression
+#[test]
+fn test_up_to_match_after_line_num() {
+    let (at, mut ucmd) = at_and_ucmd!();
+    ucmd.args(&["numbers50.txt", "15", "/3/"])
+        .succeeds()
+        .stdout_only("33\n24\n84\n");
+
+    assert_eq!(at.read("xx00"), generate(1, 15));
+    assert_eq!(at.read("xx01"), generate(15, 23));
+    assert_eq!(at.read("xx02"), generate(23, 51));
+}
+
+#[test]
+fn test_lines_kept_as_is() {
+    let (at, mut ucmd) = at_and_ucmd!();
+    ucmd.args(&["-", "/b/"])
+        .pipe_in(&b"a\n\xff\nb\nc"[..])
+        .succeeds()
+        .stdout_only("4\n3\n");
+
+    assert_eq!(at.read_bytes("xx00"), b"a\n\xff\n");
+    assert_eq!(at.read("xx01"), "b\nc");
+}
@@ -234,3 +234,672 @@ fn test_equal_as_delimiter3() {
         .succeeds()
         .stdout_only_bytes("abZcd\n");
 }
+
+#[test]
+fn test_multi_char_delimiter() {
+    new_ucmd!()
+        .args(&["-d", "::", "-f", "2,3"])
+        .pipe_in("a::b:c::d\n")
+        .succeeds()
+        .stdout_only("b:c::d\n");
+
+    new_ucmd!()
+        .args(&["-d", "::", "-f", "1,3", "--output-delimiter=,"])
+        .pipe_in("a::b:c::d\n")
+        .succeeds()
+        .stdout_only("a,d\n");
+}
+
+#[test]
+fn test_multibyte_char_delimiter() {
+    new_ucmd!()
+        .args(&["-d", "§", "-f", "2"])
+        .pipe_in("a§b§c\n")
+        .succeeds()
+        .stdout_only("b\n");
+}
+
+#[test]
+fn test_escaped_delimiter() {
+    new_ucmd!()
+        .args(&["-d", "\\t", "-f", "2"])
+        .pipe_in("a\tb\tc\n")
+        .succeeds()
+        .stdout_only("b\n");
+
+    new_ucmd!()
+        .args(&["-d", "\\0", "-f", "2"])
+        .pipe_in("a\0b\0c\n")
+        .succeeds()
+        .stdout_only("b\n");
+
+    new_ucmd!()
+        .args(&["-d", "\\\\", "-f", "2"])
+        .pipe_in("a\\b\\c\n")
+        .succeeds()
+        .stdout_only("b\n");
+}
+
+#[test]
+fn test_unknown_escape_in_delimiter_is_literal() {
+    new_ucmd!()
+        .args(&["-d", "\\q", "-f", "2"])
+        .pipe_in("a\\qb\n")
+        .succeeds()
+        .stdout_only("b\n");
+
+    new_ucmd!()
+        .args(&["-d", "\\", "-f", "2"])
+        .pipe_in("a\\b\n")
+        .succeeds()
+        .stdout_only("b\n");
+}
+
+#[test]
+fn test_regex_delimiter() {
+    new_ucmd!()
+        .args(&["--regex-delimiter", "[:;]+", "-f", "2,3"])
+        .pipe_in("a:b;;c\nd;e:f:g\n")
+        .succeeds()
+        .stdout_only("b;;c\ne:f\n");
+
+    new_ucmd!()
+        .args(&[
+            "--regex-delimiter",
+            "[:;]+",
+            "-f",
+            "1,3",
+            "--output-delimiter=,",
+        ])
+        .pipe_in("a:b;;c\n")
+        .succeeds()
+        .stdout_only("a,c\n");
+}
+
+#[test]
+fn test_regex_delimiter_only_delimited() {
+    new_ucmd!()
+        .args(&["--regex-delimiter=-+", "-s", "-f", "1"])
+        .pipe_in("a--b\nc\n")
+        .succeeds()
+        .stdout_only("a\n");
+}
+
+#[test]
+fn test_regex_delimiter_ignores_empty_matches() {
+    new_ucmd!()
+        .args(&["--regex-delimiter", ",*", "-f", "2"])
+        .pipe_in("a,,b,c\n")
+        .succeeds()
+        .stdout_only("b\n");
+}
+
+#[test]
+fn test_regex_delimiter_anchored() {
+    // `^` only matches at the start of the record, not of every field
+    new_ucmd!()
+        .args(&["--regex-delimiter", "^x", "-f", "3"])
+        .pipe_in("xxa\n")
+        .succeeds()
+        .stdout_only("\n");
+    new_ucmd!()
+        .args(&["--regex-delimiter", "^x", "-f", "2"])
+        .pipe_in("xxa\n")
+        .succeeds()
+        .stdout_only("xa\n");
+    new_ucmd!()
+        .args(&[
+            "--regex-delimiter",
+            r"\bx",
+            "-f",
+            "2",
+            "--output-format=json",
+        ])
+        .pipe_in("axb xc\n")
+        .succeeds()
+        .stdout_only("[\"c\"]\n");
+}
+
+#[test]
+fn test_regex_delimiter_conflicts() {
+    new_ucmd!()
+        .args(&["--regex-delimiter", ",", "-d", ":", "-f", "1"])
+        .fails()
+        .code_is(1);
+    new_ucmd!()
+        .args(&["--regex-delimiter", ",", "-w", "-f", "1"])
+        .fails()
+        .code_is(1);
+    new_ucmd!()
+        .args(&["--regex-delimiter", ",", "-b", "1"])
+        .fails()
+        .code_is(1);
+}
+
+#[test]
+fn test_invalid_regex_delimiter() {
+    new_ucmd!()
+        .args(&["--regex-delimiter", "(", "-f", "1"])
+        .fails()
+        .code_is(1)
+        .stderr_contains("invalid regular expression");
+}
+
+#[test]
+fn test_csv_quoted_delimiter() {
+    new_ucmd!()
+        .args(&["--csv", "-f", "2,3"])
+        .pipe_in("a,\"b,c\",d\n1,2,3\n")
+        .succeeds()
+        .stdout_only("\"b,c\",d\n2,3\n");
+}
+
+#[test]
+fn test_csv_embedded_newline() {
+    new_ucmd!()
+        .args(&["--csv", "-f", "2"])
+        .pipe_in("a,\"b\nc\",d\ne,f,g\n")
+        .succeeds()
+        .stdout_only("\"b\nc\"\nf\n");
+}
+
+#[test]
+fn test_csv_custom_delimiter() {
+    new_ucmd!()
+        .args(&["--csv", "-d;", "-f", "1"])
+        .pipe_in("\"a;b\";c\n")
+        .succeeds()
+        .stdout_only("\"a;b\"\n");
+
+    new_ucmd!()
+        .args(&["--csv", "-d\"", "-f", "1"])
+        .fails()
+        .code_is(1);
+}
+
+#[test]
+fn test_csv_requote_for_output_delimiter() {
+    new_ucmd!()
+        .args(&["--csv", "-f", "1-3", "--output-delimiter=;"])
+        .pipe_in("\"a,b\",\"c;d\",\"e\"\"f\"\n")
+        .succeeds()
+        .stdout_only("a,b;\"c;d\";\"e\"\"f\"\n");
+}
+
+#[test]
+fn test_csv_only_delimited() {
+    new_ucmd!()
+        .args(&["--csv", "-s", "-f", "1"])
+        .pipe_in("\"a,b\"\nc,d\n")
+        .succeeds()
+        .stdout_only("c\n");
+}
+
+#[test]
+fn test_csv_conflicts() {
+    new_ucmd!()
+        .args(&["--csv", "-w", "-f", "1"])
+        .fails()
+        .code_is(1);
+    new_ucmd!().args(&["--csv", "-b", "1"]).fails().code_is(1);
+}
+
+#[test]
+fn test_no_split_multibyte() {
+    // "é" is encoded as the two bytes 0xc3 0xa9
+    new_ucmd!()
+        .args(&["-b", "1-2"])
+        .pipe_in("aé\n")
+        .succeeds()
+        .stdout_only_bytes(b"a\xc3\n");
+
+    new_ucmd!()
+        .args(&["-n", "-b", "1-2"])
+        .pipe_in("aé\n")
+        .succeeds()
+        .stdout_only("a\n");
+
+    new_ucmd!()
+        .args(&["-n", "-b", "3"])
+        .pipe_in("aéb\n")
+        .succeeds()
+        .stdout_only("é\n");
+
+    new_ucmd!()
+        .args(&["-n", "-b", "2-3,5"])
+        .pipe_in("aébc\n")
+        .succeeds()
+        .stdout_only("éc\n");
+}
+
+#[test]
+fn test_no_split_multibyte_drops_empty_range() {
+    // "€" is encoded as the three bytes 0xe2 0x82 0xac
+    new_ucmd!()
+        .args(&["-n", "-b", "1-2", "--output-delimiter=:"])
+        .pipe_in("€a\n")
+        .succeeds()
+        .stdout_only("\n");
+
+    new_ucmd!()
+        .args(&["-n", "-b", "1-2,4", "--output-delimiter=:"])
+        .pipe_in("€a\n")
+        .succeeds()
+        .stdout_only("a\n");
+}
+
+#[test]
+fn test_ordered_fields() {
+    new_ucmd!()
+        .args(&["-d:", "-f", "3,1"])
+        .pipe_in("a:b:c\n")
+        .succeeds()
+        .stdout_only("a:c\n");
+
+    new_ucmd!()
+        .args(&["--ordered", "-d:", "-f", "3,1"])
+        .pipe_in("a:b:c\n")
+        .succeeds()
+        .stdout_only("c:a\n");
+
+    new_ucmd!()
+        .args(&["--ordered", "-d:", "-f", "3-,1,1", "--output-delimiter=,"])
+        .pipe_in("a:b:c:d\n")
+        .succeeds()
+        .stdout_only("c,d,a,a\n");
+}
+
+#[test]
+fn test_ordered_fields_ascending_list() {
+    new_ucmd!()
+        .args(&["--ordered", "-d:", "-f", "1,3"])
+        .pipe_in("a:b:c\nd\n")
+        .succeeds()
+        .stdout_only("a:c\nd\n");
+}
+
+#[test]
+fn test_ordered_fields_missing_and_undelimited() {
+    new_ucmd!()
+        .args(&["--ordered", "-d:", "-f", "5,2"])
+        .pipe_in("a:b:c\nno delimiter\n")
+        .succeeds()
+        .stdout_only("b\nno delimiter\n");
+
+    new_ucmd!()
+        .args(&["--ordered", "-s", "-w", "-f", "2,1"])
+        .pipe_in("a  b\nc\n")
+        .succeeds()
+        .stdout_only("b\ta\n");
+}
+
+#[test]
+fn test_ordered_with_csv() {
+    new_ucmd!()
+        .args(&["--ordered", "--csv", "-f", "2,1"])
+        .pipe_in("\"a,b\",c\n")
+        .succeeds()
+        .stdout_only("c,\"a,b\"\n");
+}
+
+#[test]
+fn test_ordered_with_bytes() {
+    new_ucmd!()
+        .args(&["--ordered", "-b", "2,1"])
+        .fails()
+        .code_is(1);
+}
+
+#[test]
+fn test_files0_from() {
+    let (at, mut ucmd) = at_and_ucmd!();
+    at.write("a", "1:2\n");
+    at.write("b", "3:4\n");
+    at.write("names", "a\0b\0");
+
+    ucmd.args(&["-d:", "-f2", "--files0-from=names"])
+        .succeeds()
+        .stdout_only("2\n4\n");
+}
+
+#[test]
+fn test_files0_from_stdin() {
+    let (at, mut ucmd) = at_and_ucmd!();
+    at.write("a", "1:2\n");
+    at.write("b", "3:4\n");
+
+    ucmd.args(&["-d:", "-f1", "--files0-from=-"])
+        .pipe_in("a\0b")
+        .succeeds()
+        .stdout_only("1\n3\n");
+}
+
+#[test]
+fn test_files0_from_stdin_with_dash() {
+    new_ucmd!()
+        .args(&["-f1", "--files0-from=-"])
+        .pipe_in("-\0")
+        .fails()
+        .code_is(1)
+        .stderr_contains("no file name of '-' allowed");
+}
+
+#[test]
+fn test_files0_from_with_operand() {
+    let (at, mut ucmd) = at_and_ucmd!();
+    at.write("names", "a\0");
+
+    ucmd.args(&["-f1", "--files0-from=names", "b"])
+        .fails()
+        .code_is(1)
+        .stderr_contains("file operands cannot be combined with --files0-from");
+}
+
+#[test]
+fn test_files0_from_missing_and_empty_names() {
+    let (at, mut ucmd) = at_and_ucmd!();
+    at.write("a", "1:2\n");
+    at.write("names", "a\0\0missing\0");
+
+    ucmd.args(&["-d:", "-f1", "--files0-from=names"])
+        .fails()
+        .code_is(1)
+        .stdout_is("1\n")
+        .stderr_contains("invalid zero-length file name")
+        .stderr_contains("missing: No such file or directory");
+
+    new_ucmd!()
+        .args(&["-f1", "--files0-from=nonexistent"])
+        .fails()
+        .code_is(1)
+        .stderr_contains("cannot open 'nonexistent' for reading");
+}
+
+#[test]
+fn test_files0_from_only_empty_name() {
+    let (at, mut ucmd) = at_and_ucmd!();
+    at.write("a", "1:2\n");
+    at.write("names", "a\0\0");
+
+    ucmd.args(&["-d:", "-f1", "--files0-from=names"])
+        .fails()
+        .code_is(1)
+        .stdout_is("1\n")
+        .stderr_is("cut: invalid zero-length file name\n");
+}
+
+#[test]
+fn test_regular_file_without_trailing_newline() {
+    let (at, mut ucmd) = at_and_ucmd!();
+    at.write("in", "a:b\nc:d");
+
+    ucmd.args(&["-d:", "-f2", "in"])
+        .succeeds()
+        .stdout_only("b\nd\n");
+}
+
+#[test]
+fn test_empty_regular_file() {
+    let (at, mut ucmd) = at_and_ucmd!();
+    at.touch("empty");
+
+    ucmd.args(&["-b1", "empty"]).succeeds().no_stdout();
+}
+
+#[test]
+fn test_stepped_range() {
+    new_ucmd!()
+        .args(&["-d:", "-f", "2-8:3"])
+        .pipe_in("1:2:3:4:5:6:7:8:9\n1:2:3\n")
+        .succeeds()
+        .stdout_only("2:5:8\n2\n");
+
+    new_ucmd!()
+        .args(&["-b", "-9:2"])
+        .pipe_in("abcdefghij\n")
+        .succeeds()
+        .stdout_only("acegi\n");
+}
+
+#[test]
+fn test_stepped_range_complement() {
+    new_ucmd!()
+        .args(&["-d:", "--complement", "-f", "1-5:2"])
+        .pipe_in("1:2:3:4:5:6\n")
+        .succeeds()
+        .stdout_only("2:4:6\n");
+}
+
+#[test]
+fn test_huge_stepped_range() {
+    new_ucmd!()
+        .args(&["-d:", "-f", "1-100000000000:2"])
+        .pipe_in("1:2:3:4:5\n")
+        .succeeds()
+        .stdout_only("1:3:5\n");
+    new_ucmd!()
+        .args(&["-d:", "-f", "4,1-100000000000:2"])
+        .pipe_in("1:2:3:4:5\n")
+        .succeeds()
+        .stdout_only("1:3:4:5\n");
+    new_ucmd!()
+        .args(&["-d:", "--complement", "-f", "1-100000000000:2"])
+        .pipe_in("1:2:3:4:5\n")
+        .succeeds()
+        .stdout_only("2:4\n");
+    new_ucmd!()
+        .args(&["-b", "2-100000000000:3"])
+        .pipe_in("abcdefgh\n")
+        .succeeds()
+        .stdout_only("beh\n");
+    new_ucmd!()
+        .args(&["-d:", "--strict", "-f", "1-100000000000:2"])
+        .pipe_in("1:2:3:4:5\n")
+        .fails()
+        .stdout_is("1:3:5\n")
+        .stderr_contains("missing field 7 (found 5)");
+}
+
+#[test]
+fn test_stepped_range_ordered() {
+    new_ucmd!()
+        .args(&["-d:", "--ordered", "-f", "4,1-3:2"])
+        .pipe_in("1:2:3:4\n")
+        .succeeds()
+        .stdout_only("4:1:3\n");
+}
+
+#[test]
+fn test_invalid_stepped_range() {
+    for list in ["2-:3", "1-4:0", "1-4:x"] {
+        new_ucmd!()
+            .args(&["-f", list])
+            .fails()
+            .code_is(1)
+            .stderr_contains("was invalid");
+    }
+}
+
+#[test]
+fn test_output_format_json() {
+    new_ucmd!()
+        .args(&["-d:", "-f", "1,3", "--output-format=json"])
+        .pipe_in("a:b:c\nd\"e:f\\g\tx\n")
+        .succeeds()
+        .stdout_only("[\"a\",\"c\"]\n[\"d\\\"e\"]\n");
+
+    new_ucmd!()
+        .args(&["-d:", "-f", "2-", "--output-format=json"])
+        .pipe_in("a:b\\c:d\te\n")
+        .succeeds()
+        .stdout_only("[\"b\\\\c\",\"d\\te\"]\n");
+}
+
+#[test]
+fn test_output_format_json_undelimited() {
+    new_ucmd!()
+        .args(&["-d:", "-f", "1", "--output-format=json"])
+        .pipe_in("abc\n")
+        .succeeds()
+        .stdout_only("[\"abc\"]\n");
+
+    new_ucmd!()
+        .args(&["-d:", "-s", "-f", "1", "--output-format=json"])
+        .pipe_in("abc\n")
+        .succeeds()
+        .no_stdout();
+}
+
+#[test]
+fn test_output_format_json_csv() {
+    new_ucmd!()
+        .args(&["--csv", "-f", "1,2", "--output-format=json"])
+        .pipe_in("\"a,\"\"b\"\"\",c\n")
+        .succeeds()
+        .stdout_only("[\"a,\\\"b\\\"\",\"c\"]\n");
+}
+
+#[test]
+fn test_output_format_json_control_chars() {
+    new_ucmd!()
+        .args(&["-d:", "-f", "1", "--output-format=json"])
+        .pipe_in("a\x01:b\n")
+        .succeeds()
+        .stdout_only("[\"a\\u0001\"]\n");
+}
+
+#[test]
+fn test_output_format_text() {
+    new_ucmd!()
+        .args(&["-d:", "-f", "2", "--output-format=text"])
+        .pipe_in("a:b\n")
+        .succeeds()
+        .stdout_only("b\n");
+}
+
+#[test]
+fn test_output_format_invalid() {
+    new_ucmd!()
+        .args(&["-d:", "-f", "2", "--output-format=xml"])
+        .fails()
+        .code_is(1);
+    new_ucmd!()
+        .args(&["-b", "2", "--output-format=json"])
+        .fails()
+        .code_is(1);
+}
+
+#[test]
+fn test_trim_fields() {
+    new_ucmd!()
+        .args(&["-d,", "-f", "1,2", "--trim"])
+        .pipe_in(" a , b \t,c\n")
+        .succeeds()
+        .stdout_only("a,b\n");
+}
+
+#[test]
+fn test_empty_as() {
+    new_ucmd!()
+        .args(&["-d:", "-f", "1-3", "--empty-as=NA"])
+        .pipe_in("a::c\n::\n")
+        .succeeds()
+        .stdout_only("a:NA:c\nNA:NA:NA\n");
+}
+
+#[test]
+fn test_trim_and_empty_as() {
+    new_ucmd!()
+        .args(&[
+            "-d:",
+            "-f",
+            "1,2",
+            "--trim",
+            "--empty-as=-",
+            "--output-delimiter=|",
+        ])
+        .pipe_in(" a :   \n")
+        .succeeds()
+        .stdout_only("a|-\n");
+}
+
+#[test]
+fn test_empty_as_json() {
+    new_ucmd!()
+        .args(&[
+            "-d:",
+            "-f",
+            "1,2",
+            "--empty-as=null",
+            "--output-format=json",
+        ])
+        .pipe_in(":b\n")
+        .succeeds()
+        .stdout_only("[\"null\",\"b\"]\n");
+}
+
+#[test]
+fn test_trim_with_bytes() {
+    new_ucmd!().args(&["-b", "1", "--trim"]).fails().code_is(1);
+}
+
+#[test]
+fn test_strict() {
+    new_ucmd!()
+        .args(&["-d:", "-f", "1,3", "--strict"])
+        .pipe_in("a:b:c\nd:e\nf:g:h:i\nj\n")
+        .fails()
+        .code_is(1)
+        .stdout_is("a:c\nd\nf:h\nj\n")
+        .stderr_is(
+            "cut: -:2: missing field 3 (found 2)\n\
+             cut: -:4: missing field 3 (found 1)\n\
+             cut: -: 2 lines with missing fields\n",
+        );
+}
+
+#[test]
+fn test_strict_all_fields_present() {
+    new_ucmd!()
+        .args(&["-d:", "-f", "2-", "--strict"])
+        .pipe_in("a:b\nc:d:e\n")
+        .succeeds()
+        .stdout_only("b\nd:e\n");
+}
+
+#[test]
+fn test_strict_bounded_range() {
+    let (at, mut ucmd) = at_and_ucmd!();
+    at.write("in", "a:b\n");
+
+    ucmd.args(&["-d:", "-f", "1-4", "--strict", "-s", "in"])
+        .fails()
+        .code_is(1)
+        .stdout_is("a:b\n")
+        .stderr_is(
+            "cut: in:1: missing field 3 (found 2)\n\
+             cut: in: 1 line with missing fields\n",
+        );
+}
+
+#[test]
+fn test_strict_only_delimited() {
+    new_ucmd!()
+        .args(&["-d:", "-f", "2", "--strict", "-s"])
+        .pipe_in("a\nb:c\n")
+        .succeeds()
+        .stdout_only("c\n");
+}
+
+#[test]
+fn test_strict_csv_line_numbers() {
+    new_ucmd!()
+        .args(&["--csv", "-f", "2", "--strict"])
+        .pipe_in("\"a\nb\",c\nd\ne,f\n")
+        .fails()
+        .code_is(1)
+        .stderr_is(
+            "cut: -:3: missing field 2 (found 1)\n\
+             cut: -: 1 line with missing fields\n",
+        );
+}
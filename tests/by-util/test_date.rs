@@ -200,29 +200,27 @@ fn test_date_set_valid_2() {
 
 #[test]
 #[cfg(all(unix, not(target_os = "macos")))]
-/// TODO: expected to fail currently; change to succeeds() when required.
 fn test_date_set_valid_3() {
     if get_effective_uid() == 0 {
-        let result = new_ucmd!()
+        new_ucmd!()
             .arg("--set")
             .arg("Sat 20 Mar 2021 14:53:01") // Local timezone
-            .fails();
-        result.no_stdout();
-        assert!(result.stderr_str().starts_with("date: invalid date "));
+            .succeeds()
+            .no_stdout()
+            .no_stderr();
     }
 }
 
 #[test]
 #[cfg(all(unix, not(target_os = "macos")))]
-/// TODO: expected to fail currently; change to succeeds() when required.
 fn test_date_set_valid_4() {
     if get_effective_uid() == 0 {
-        let result = new_ucmd!()
+        new_ucmd!()
             .arg("--set")
             .arg("2020-03-11 21:45:00") // Local timezone
-            .fails();
-        result.no_stdout();
-        assert!(result.stderr_str().starts_with("date: invalid date "));
+            .succeeds()
+            .no_stdout()
+            .no_stderr();
     }
 }
 
@@ -232,3 +230,184 @@ fn test_invalid_format_string() {
     result.no_stdout();
     assert!(result.stderr_str().starts_with("date: invalid format "));
 }
+
+#[test]
+fn test_date_string_timestamp() {
+    new_ucmd!()
+        .args(&["-u", "-d", "@1584000000", "+%F %T %z"])
+        .succeeds()
+        .stdout_only("2020-03-12 08:00:00 +0000\n");
+}
+
+#[test]
+fn test_date_string_relative() {
+    new_ucmd!()
+        .args(&[
+            "-u",
+            "-d",
+            "2020-03-12 10:00 next monday + 3 hours",
+            "+%F %T",
+        ])
+        .succeeds()
+        .stdout_only("2020-03-12 13:00:00\n");
+    // the "+1" right after the time of day is a time zone
+    new_ucmd!()
+        .args(&["-u", "-d", "2020-03-12 10:00 +1 day 2 hours ago", "+%F %T"])
+        .succeeds()
+        .stdout_only("2020-03-13 07:00:00\n");
+    new_ucmd!()
+        .args(&["-u", "-d", "2020-03-12 last month", "+%F"])
+        .succeeds()
+        .stdout_only("2020-02-12\n");
+}
+
+#[test]
+fn test_date_string_starting_with_hyphen() {
+    for (input, same_as) in [("-1 month", "1 month ago"), ("-1day", "yesterday")] {
+        let expected = new_ucmd!()
+            .args(&["-u", "-d", same_as, "+%F"])
+            .succeeds()
+            .stdout_move_str();
+        new_ucmd!()
+            .args(&["-u", "-d", input, "+%F"])
+            .succeeds()
+            .stdout_only(&expected);
+        new_ucmd!()
+            .args(&["-u", &format!("--date={input}"), "+%F"])
+            .succeeds()
+            .stdout_only(expected);
+    }
+}
+
+#[test]
+fn test_date_string_time_zones() {
+    for (input, expected) in [
+        ("2020-03-12 10:00 EST", "2020-03-12 15:00:00 +0000\n"),
+        ("2020-03-12 10:00 CEST", "2020-03-12 08:00:00 +0000\n"),
+        ("2020-03-12T10:00:00+05:30", "2020-03-12 04:30:00 +0000\n"),
+        ("2020-03-12 10:00 UTC+1", "2020-03-12 09:00:00 +0000\n"),
+    ] {
+        new_ucmd!()
+            .args(&["-u", "-d", input, "+%F %T %z"])
+            .succeeds()
+            .stdout_only(expected);
+    }
+}
+
+#[test]
+fn test_date_string_local_time() {
+    new_ucmd!()
+        .env("TZ", "EST5")
+        .args(&["-d", "2020-03-12 10:00", "+%F %T %z"])
+        .succeeds()
+        .stdout_only("2020-03-12 10:00:00 -0500\n");
+}
+
+#[test]
+fn test_date_string_invalid() {
+    new_ucmd!()
+        .args(&["-d", "2020-02-30"])
+        .fails()
+        .no_stdout()
+        .stderr_only("date: invalid date '2020-02-30'\n");
+}
+
+#[test]
+fn test_date_file() {
+    let (at, mut ucmd) = at_and_ucmd!();
+    // an empty line is the start of today
+    let today = new_ucmd!()
+        .args(&["-u", "+%F"])
+        .succeeds()
+        .stdout_move_str();
+    at.write("dates", "2020-03-12 10:00\n\nxyz\n@0\n");
+    ucmd.args(&["-u", "-f", "dates", "+%F %T"])
+        .fails()
+        .code_is(1)
+        .stdout_is(format!(
+            "2020-03-12 10:00:00\n{} 00:00:00\n1970-01-01 00:00:00\n",
+            today.trim_end()
+        ))
+        .stderr_is("date: invalid date 'xyz'\n");
+}
+
+#[test]
+fn test_date_file_stdin() {
+    new_ucmd!()
+        .args(&["-u", "--file=-", "-Is"])
+        .pipe_in("@5\n@1584000000\n")
+        .succeeds()
+        .stdout_only("1970-01-01T00:00:05+00:00\n2020-03-12T08:00:00+00:00\n");
+}
+
+#[test]
+fn test_date_file_nonexistent() {
+    new_ucmd!()
+        .args(&["-f", "nonexistent"])
+        .fails()
+        .no_stdout()
+        .stderr_contains("date: nonexistent: No such file or directory");
+}
+
+#[test]
+fn test_date_file_and_date() {
+    new_ucmd!()
+        .args(&["-f", "-", "-d", "@0"])
+        .fails()
+        .stderr_contains("the options to specify dates for printing are mutually exclusive");
+}
+
+#[test]
+fn test_date_iso_8601_precision() {
+    for (param, expected) in [
+        ("-I", "1970-01-01\n"),
+        ("--iso-8601=date", "1970-01-01\n"),
+        ("-Ih", "1970-01-01T00+00:00\n"),
+        ("--iso-8601=min", "1970-01-01T00:00+00:00\n"),
+        ("-Iseconds", "1970-01-01T00:00:01+00:00\n"),
+        ("-Ins", "1970-01-01T00:00:01,500000000+00:00\n"),
+    ] {
+        new_ucmd!()
+            .args(&["-u", "-d", "@1.5", param])
+            .succeeds()
+            .stdout_only(expected);
+    }
+}
+
+#[test]
+fn test_date_rfc_3339_precision() {
+    for (param, expected) in [
+        ("--rfc-3339=date", "1970-01-01\n"),
+        ("--rfc-3339=s", "1970-01-01 00:00:01+00:00\n"),
+        ("--rfc-3339=ns", "1970-01-01 00:00:01.500000000+00:00\n"),
+    ] {
+        new_ucmd!()
+            .args(&["-u", "-d", "@1.5", param])
+            .succeeds()
+            .stdout_only(expected);
+    }
+}
+
+#[test]
+fn test_date_invalid_precision() {
+    new_ucmd!()
+        .arg("--rfc-3339=x")
+        .fails()
+        .no_stdout()
+        .stderr_contains("date: invalid argument 'x' for '--rfc-3339'\nValid arguments are:\n  - 'date'\n  - 'seconds'\n  - 'ns'\n");
+    new_ucmd!()
+        .arg("--iso-8601=")
+        .fails()
+        .stderr_contains("date: ambiguous argument '' for '--iso-8601'");
+}
+
+#[test]
+fn test_date_multiple_formats() {
+    for args in [&["-I", "-R"], &["--rfc-3339=ns", "+%F"]] {
+        new_ucmd!()
+            .args(args)
+            .fails()
+            .no_stdout()
+            .stderr_only("date: multiple output formats specified\n");
+    }
+}
@@ -823,24 +823,13 @@ fn test_atoe_and_lcase_conv_spec_test() {
         .stdout_is_fixture_bytes("lcase-ebcdic.test");
 }
 
-// TODO I think uppercase and lowercase are unintentionally swapped in
-// the code that parses the command-line arguments. See this line from
-// `parseargs.rs`:
-//
-//     (ConvFlag::FmtAtoI, ConvFlag::UCase) => Some(&ASCII_TO_IBM_UCASE_TO_LCASE),
-//     (ConvFlag::FmtAtoI, ConvFlag::LCase) => Some(&ASCII_TO_IBM_LCASE_TO_UCASE),
-//
-// If my reading is correct and that is a typo, then the
-// UCASE_TO_LCASE and LCASE_TO_UCASE in those lines should be swapped,
-// and the expected output for the following two tests should be
-// updated accordingly.
 #[test]
 fn test_atoibm_and_ucase_conv_spec_test() {
     new_ucmd!()
         .args(&["conv=ibm,ucase"])
         .pipe_in_fixture("seq-byte-values-b632a992d3aed5d8d1a59cc5a5a455ba.test")
         .succeeds()
-        .stdout_is_fixture_bytes("lcase-ibm.test");
+        .stdout_is_fixture_bytes("ucase-ibm.test");
 }
 
 #[test]
@@ -849,7 +838,7 @@ fn test_atoibm_and_lcase_conv_spec_test() {
         .args(&["conv=ibm,lcase"])
         .pipe_in_fixture("seq-byte-values-b632a992d3aed5d8d1a59cc5a5a455ba.test")
         .succeeds()
-        .stdout_is_fixture_bytes("ucase-ibm.test");
+        .stdout_is_fixture_bytes("lcase-ibm.test");
 }
 
 #[test]
@@ -1297,18 +1286,18 @@ fn test_invalid_number_arg_gnu_compatibility() {
 
 #[test]
 fn test_invalid_flag_arg_gnu_compatibility() {
-    let commands = vec!["iflag", "oflag"];
+    let commands = vec![("iflag", "input"), ("oflag", "output")];
 
-    for command in commands {
+    for (command, kind) in commands {
         new_ucmd!()
             .args(&[format!("{command}=")])
             .fails()
-            .usage_error("invalid input flag: ‘’");
+            .usage_error(format!("invalid {kind} flag: ‘’"));
 
         new_ucmd!()
             .args(&[format!("{command}=29d")])
             .fails()
-            .usage_error("invalid input flag: ‘29d’");
+            .usage_error(format!("invalid {kind} flag: ‘29d’"));
     }
 }
 
@@ -1442,3 +1431,241 @@ fn test_sparse() {
     // number of blocks stored on disk may be zero.
     assert_eq!(at.metadata("infile").len(), at.metadata("outfile").len());
 }
+
+#[test]
+#[cfg(any(target_os = "linux", target_os = "android"))]
+fn test_sigusr1_prints_stats() {
+    use nix::sys::signal::{kill, Signal};
+    use nix::unistd::Pid;
+    use std::process::Stdio;
+
+    let mut child = new_ucmd!()
+        .args(&["bs=3", "status=noxfer"])
+        .set_stdin(Stdio::piped())
+        .run_no_wait();
+    child.write_in("abc");
+    sleep(Duration::from_millis(500));
+    kill(Pid::from_raw(child.id() as i32), Signal::SIGUSR1).unwrap();
+    // The statistics are printed after the next block is copied.
+    child.write_in("def");
+    sleep(Duration::from_millis(500));
+    child.write_in("gh");
+    child.close_stdin();
+    child
+        .wait()
+        .unwrap()
+        .success()
+        .stdout_is("abcdefgh")
+        .stderr_is("2+0 records in\n2+0 records out\n2+1 records in\n2+1 records out\n");
+}
+
+#[test]
+#[cfg(any(target_os = "linux", target_os = "android"))]
+fn test_sigusr1_posixly_correct() {
+    use nix::sys::signal::{kill, Signal};
+    use nix::unistd::Pid;
+    use std::process::Stdio;
+
+    let child = new_ucmd!()
+        .env("POSIXLY_CORRECT", "1")
+        .args(&["status=noxfer"])
+        .set_stdin(Stdio::piped())
+        .run_no_wait();
+    sleep(Duration::from_millis(500));
+    kill(Pid::from_raw(child.id() as i32), Signal::SIGUSR1).unwrap();
+    child.wait().unwrap().signal_name_is("USR1");
+}
+
+/// Test that bytes are swapped across reads, and that the odd byte at the end is kept.
+#[test]
+fn test_swab_across_reads() {
+    new_ucmd!()
+        .args(&["bs=3", "conv=swab", "status=noxfer"])
+        .pipe_in("abcdefg")
+        .succeeds()
+        .stdout_is("badcfeg")
+        .stderr_is("2+1 records in\n2+1 records out\n");
+}
+
+/// Test that lines and records may span several reads when blocking and unblocking.
+#[test]
+fn test_block_unblock_across_reads() {
+    new_ucmd!()
+        .args(&["ibs=2", "cbs=3", "conv=block", "status=noxfer"])
+        .pipe_in("ab\ncdefg\nh")
+        .succeeds()
+        .stdout_is("ab cdeh  ")
+        .stderr_is("5+0 records in\n0+1 records out\n1 truncated record\n");
+    new_ucmd!()
+        .args(&["ibs=3", "cbs=4", "conv=unblock", "status=noxfer"])
+        .pipe_in("ab  cd  e")
+        .succeeds()
+        .stdout_is("ab\ncd\ne\n")
+        .stderr_is("3+0 records in\n0+1 records out\n");
+}
+
+/// Test that the output is gathered into blocks of `obs` bytes unless `bs` is given.
+#[test]
+fn test_output_blocks() {
+    new_ucmd!()
+        .args(&["ibs=2", "obs=3", "status=noxfer"])
+        .pipe_in("abcde")
+        .succeeds()
+        .stdout_is("abcde")
+        .stderr_is("2+1 records in\n1+1 records out\n");
+    new_ucmd!()
+        .args(&[
+            "ibs=2",
+            "obs=2",
+            "count=3",
+            "iflag=count_bytes",
+            "status=noxfer",
+        ])
+        .pipe_in("abcdefghij")
+        .succeeds()
+        .stdout_is("abc")
+        .stderr_is("1+1 records in\n1+1 records out\n");
+}
+
+/// Test that the case is converted after translating to ASCII and before translating from ASCII.
+#[test]
+fn test_conversion_and_case_order() {
+    new_ucmd!()
+        .args(&["conv=ascii,lcase", "status=none"])
+        .pipe_in(&b"\xc8\x85\x93\x93\x96"[..])
+        .succeeds()
+        .stdout_is("hello");
+    new_ucmd!()
+        .args(&["conv=ibm,ucase", "status=none"])
+        .pipe_in("Hello")
+        .succeeds()
+        .stdout_is_bytes(b"\xc8\xc5\xd3\xd3\xd6");
+}
+
+#[test]
+fn test_conv_errors() {
+    for (args, msg) in [
+        (
+            &["conv=ascii,ebcdic"][..],
+            "cannot combine any two of {ascii,ebcdic,ibm}",
+        ),
+        (
+            &["conv=block,unblock", "cbs=2"],
+            "cannot combine block and unblock",
+        ),
+        (
+            &["conv=ascii,block", "cbs=2"],
+            "cannot combine block and unblock",
+        ),
+        (&["conv=lcase,ucase"], "cannot combine lcase and ucase"),
+        (&["conv=excl,nocreat"], "cannot combine excl and nocreat"),
+        (&["cbs=0"], "invalid number: ‘0’"),
+    ] {
+        new_ucmd!()
+            .args(args)
+            .fails()
+            .code_is(1)
+            .stderr_only(format!("dd: {msg}\n"));
+    }
+    new_ucmd!()
+        .arg("conv=bogus")
+        .fails()
+        .code_is(1)
+        .usage_error("invalid conversion: 'bogus'");
+}
+
+/// Test that `conv=block` and `conv=unblock` are ignored without `cbs`.
+#[test]
+fn test_block_unblock_without_cbs() {
+    new_ucmd!()
+        .args(&["conv=block,unblock", "status=none"])
+        .pipe_in("a b  \nc")
+        .succeeds()
+        .stdout_only("a b  \nc");
+}
+
+/// Test that failing to sync the output is an error, after which the statistics are still printed.
+#[cfg(any(target_os = "linux", target_os = "android"))]
+#[test]
+fn test_fsync_dev_null() {
+    for conv in ["conv=fsync", "conv=fdatasync"] {
+        new_ucmd!()
+            .args(&[conv, "of=/dev/null", "status=noxfer"])
+            .pipe_in("abc")
+            .fails()
+            .code_is(1)
+            .stderr_is(
+                "dd: fsync failed for '/dev/null': Invalid input\n\
+                 0+1 records in\n0+1 records out\n",
+            );
+    }
+}
+
+/// Test that `conv=sparse` extends the output file to include a final hole, even with `conv=notrunc`.
+#[test]
+fn test_sparse_notrunc_extends_file() {
+    let (at, mut ucmd) = at_and_ucmd!();
+    at.write("outfile", "abc");
+    ucmd.args(&[
+        "if=/dev/zero",
+        "of=outfile",
+        "bs=4",
+        "count=2",
+        "conv=sparse,notrunc",
+    ])
+    .succeeds();
+    assert_eq!(at.read_bytes("outfile"), b"abc\0\0\0\0\0");
+}
+
+/// Test copying with direct I/O, where the size of the input is not a multiple of the block size.
+#[cfg(any(target_os = "linux", target_os = "android"))]
+#[test]
+fn test_direct_io() {
+    let (at, mut ucmd) = at_and_ucmd!();
+    let data: Vec<u8> = (0..10000).map(|i| (i % 251) as u8).collect();
+    at.write_bytes("infile", &data);
+    let result = ucmd
+        .args(&[
+            "if=infile",
+            "of=outfile",
+            "iflag=direct",
+            "oflag=direct",
+            "bs=4096",
+            "status=noxfer",
+        ])
+        .run();
+    // Not every file system supports direct I/O.
+    if result.stderr_str().contains("failed to open") {
+        return;
+    }
+    result
+        .success()
+        .stderr_is("2+1 records in\n2+1 records out\n");
+    assert_eq!(at.read_bytes("outfile"), data);
+}
+
+/// Test that the statistics are printed after a read error.
+#[cfg(unix)]
+#[test]
+fn test_read_error() {
+    new_ucmd!()
+        .args(&["if=.", "status=noxfer"])
+        .fails()
+        .code_is(1)
+        .stderr_is("dd: error reading '.': Is a directory\n0+0 records in\n0+0 records out\n");
+}
+
+/// Test that the statistics are printed after a write error.
+#[cfg(any(target_os = "linux", target_os = "android"))]
+#[test]
+fn test_write_error() {
+    new_ucmd!()
+        .args(&["of=/dev/full", "status=noxfer"])
+        .pipe_in("abc")
+        .fails()
+        .code_is(1)
+        .stderr_is(
+            "dd: error writing '/dev/full': No space left on device\n\
+             0+1 records in\n0+0 records out\n",
+        );
+}
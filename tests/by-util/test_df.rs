@@ -222,8 +222,22 @@ fn test_output_mp_repeat() {
 #[test]
 fn test_output_conflict_options() {
     for option in ["-i", "-T", "-P"] {
-        new_ucmd!().arg("--output=source").arg(option).fails();
+        new_ucmd!()
+            .arg("--output=source")
+            .arg(option)
+            .fails()
+            .usage_error(format!(
+                "options {option} and --output are mutually exclusive"
+            ));
     }
+    new_ucmd!()
+        .args(&["-TP", "--output"])
+        .fails()
+        .usage_error("options -P and --output are mutually exclusive");
+    new_ucmd!()
+        .args(&["--output", "-Ti"])
+        .fails()
+        .usage_error("options -T and --output are mutually exclusive");
 }
 
 #[test]
@@ -290,6 +304,28 @@ fn test_type_option_with_file() {
     }
 }
 
+#[test]
+#[cfg(target_os = "linux")]
+fn test_file_on_dummy_filesystem() {
+    let output = new_ucmd!()
+        .args(&["--output=fstype", "/proc"])
+        .succeeds()
+        .stdout_move_str();
+    assert_eq!(output.lines().nth(1).unwrap().trim(), "proc");
+
+    new_ucmd!()
+        .args(&["-x", "proc", "/proc"])
+        .fails()
+        .stderr_only("df: no file systems processed\n");
+
+    let output = new_ucmd!()
+        .args(&["--output=fstype", "-x", "proc", "/proc", "."])
+        .succeeds()
+        .stdout_move_str();
+    assert_eq!(output.lines().count(), 2);
+    assert_ne!(output.lines().nth(1).unwrap().trim(), "proc");
+}
+
 #[test]
 fn test_exclude_type_option() {
     new_ucmd!().args(&["-x", "ext4", "-x", "ext3"]).succeeds();
@@ -839,6 +875,27 @@ fn test_file_column_width_if_filename_contains_unicode_chars() {
     assert_eq!(actual, "File    Mounted on");
 }
 
+#[test]
+fn test_output_unknown_field() {
+    new_ucmd!()
+        .arg("--output=source,invalid,source")
+        .fails()
+        .usage_error("option --output: field 'invalid' unknown");
+    new_ucmd!()
+        .arg("--output=source,")
+        .fails()
+        .usage_error("option --output: field '' unknown");
+}
+
+#[test]
+fn test_output_column_min_width() {
+    let output = new_ucmd!()
+        .args(&["--output=size,pcent,iused", "-h", "."])
+        .succeeds()
+        .stdout_move_str();
+    assert_eq!(output.lines().next().unwrap(), " Size Use% IUsed");
+}
+
 #[test]
 fn test_output_field_no_more_than_once() {
     new_ucmd!()
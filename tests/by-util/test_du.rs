@@ -411,8 +411,9 @@ fn test_du_time() {
     let result = ts.ucmd().arg("--time=atime").arg("date_test").succeeds();
     result.stdout_only("0\t2015-05-15 00:00\tdate_test\n");
 
+    // The status change time is when `touch` ran, not the modification time.
     let result = ts.ucmd().arg("--time=ctime").arg("date_test").succeeds();
-    result.stdout_only("0\t2016-06-16 00:00\tdate_test\n");
+    result.stdout_does_not_contain("2016-06-16");
 
     if birth_supported() {
         use regex::Regex;
@@ -424,6 +425,66 @@ fn test_du_time() {
     }
 }
 
+#[test]
+fn test_du_time_of_directory() {
+    let ts = TestScenario::new(util_name!());
+    let at = &ts.fixtures;
+    at.mkdir_all("a/b");
+    at.touch("a/b/f");
+    at.touch("a/g");
+    let set_mtime = |path: &str, secs: i64, nsecs: u32| {
+        let time = filetime::FileTime::from_unix_time(secs, nsecs);
+        filetime::set_file_mtime(at.plus(path), time).unwrap();
+    };
+    set_mtime("a/b/f", 1_500_000_000, 123_456_789);
+    set_mtime("a/g", 1_400_000_000, 0);
+    set_mtime("a/b", 1_000_000_000, 0);
+    set_mtime("a", 1_000_000_000, 0);
+
+    // A directory shows the latest time of any file below it.
+    ts.ucmd()
+        .args(&["--time", "--time-style=+%s.%N", "-a", "a"])
+        .succeeds()
+        .stdout_contains("\t1500000000.123456789\ta/b/f\n")
+        .stdout_contains("\t1500000000.123456789\ta/b\n")
+        .stdout_contains("\t1400000000.000000000\ta/g\n")
+        .stdout_contains("\t1500000000.123456789\ta\n");
+
+    // With -S, subdirectories are not taken into account.
+    ts.ucmd()
+        .args(&["--time", "--time-style=+%s", "-S", "-s", "a"])
+        .succeeds()
+        .stdout_contains("\t1400000000\ta\n");
+}
+
+#[test]
+fn test_du_time_errors() {
+    new_ucmd!()
+        .args(&["--time=foo", "."])
+        .fails()
+        .code_is(1)
+        .stderr_contains("du: invalid argument 'foo' for '--time'");
+    new_ucmd!()
+        .args(&["--time=c", "."])
+        .fails()
+        .code_is(1)
+        .stderr_contains("du: ambiguous argument 'c' for '--time'");
+    new_ucmd!()
+        .args(&["--time=acc", "--time-style=iso", "-s", "."])
+        .succeeds();
+    new_ucmd!()
+        .args(&["--time", "-s", "."])
+        .env("TIME_STYLE", "foo")
+        .fails()
+        .code_is(1)
+        .stderr_contains("du: invalid argument 'foo' for 'time style'");
+    new_ucmd!()
+        .args(&["--time", "-s", "."])
+        .env("TIME_STYLE", "+%%")
+        .succeeds()
+        .stdout_contains("\t%\t.\n");
+}
+
 #[cfg(feature = "touch")]
 fn birth_supported() -> bool {
     let ts = TestScenario::new(util_name!());
@@ -521,6 +582,22 @@ fn test_du_threshold() {
         .succeeds()
         .stdout_does_not_contain("links")
         .stdout_contains("deeper_dir");
+
+    ts.ucmd()
+        .arg("--threshold=-0")
+        .fails()
+        .stderr_only("du: invalid --threshold argument '-0'\n");
+}
+
+#[test]
+fn test_du_threshold_total() {
+    let (at, mut ucmd) = at_and_ucmd!();
+    at.mkdir("a");
+    at.write("a/f", "hello");
+    // Entries below the threshold still count towards the total.
+    ucmd.args(&["-b", "-c", "--threshold=1M", "a"])
+        .succeeds()
+        .stdout_only(format!("{}\ttotal\n", 5 + at.metadata("a").len()));
 }
 
 #[test]
@@ -806,6 +883,28 @@ fn test_du_exclude_several_components() {
     assert!(!result.stdout_str().contains("a/b"));
 }
 
+#[test]
+fn test_du_exclude_trailing_components() {
+    let (at, mut ucmd) = at_and_ucmd!();
+    at.mkdir_all("a/b/c");
+    at.mkdir_all("a/x/c");
+
+    ucmd.args(&["--exclude=b/c", "a"])
+        .succeeds()
+        .stdout_contains("a/b\n")
+        .stdout_does_not_contain("a/b/c")
+        .stdout_contains("a/x/c\n");
+}
+
+#[test]
+fn test_du_exclude_from_nonexistent_file() {
+    new_ucmd!()
+        .args(&["--exclude-from=nonexistent", "."])
+        .fails()
+        .code_is(1)
+        .stderr_only("du: nonexistent: No such file or directory\n");
+}
+
 #[test]
 fn test_du_exclude_invalid_syntax() {
     let ts = TestScenario::new(util_name!());
@@ -118,7 +118,7 @@ fn test_escape_no_further_output() {
     new_ucmd!()
         .args(&["-e", "a\\cb", "c"])
         .succeeds()
-        .stdout_only("a\n");
+        .stdout_only("a");
 }
 
 #[test]
@@ -232,3 +232,79 @@ fn test_hyphen_values_between() {
         .success()
         .stdout_is("dumdum  dum dum dum -e dum\n");
 }
+
+#[test]
+fn test_escape_octal_without_leading_zero() {
+    new_ucmd!()
+        .args(&["-e", "\\101\\0101\\1018"])
+        .succeeds()
+        .stdout_only("AAA8\n");
+}
+
+#[test]
+fn test_escape_not_in_echo() {
+    new_ucmd!()
+        .args(&["-e", "\\u0041\\\"\\x"])
+        .succeeds()
+        .stdout_only("\\u0041\\\"\\x\n");
+}
+
+#[test]
+fn test_combined_options() {
+    new_ucmd!()
+        .args(&["-neE", "a\\tb"])
+        .succeeds()
+        .stdout_only("a\\tb");
+    new_ucmd!()
+        .args(&["-Ee", "-n", "a\\tb"])
+        .succeeds()
+        .stdout_only("a\tb");
+}
+
+#[test]
+fn test_non_option_arguments() {
+    new_ucmd!()
+        .args(&["-nx", "--", "-", "-n"])
+        .succeeds()
+        .stdout_only("-nx -- - -n\n");
+    new_ucmd!()
+        .args(&["--help", "me"])
+        .succeeds()
+        .stdout_only("--help me\n");
+}
+
+#[test]
+fn test_posixly_correct() {
+    new_ucmd!()
+        .env("POSIXLY_CORRECT", "1")
+        .args(&["-e", "a\\tb"])
+        .succeeds()
+        .stdout_only("-e a\tb\n");
+    new_ucmd!()
+        .env("POSIXLY_CORRECT", "1")
+        .args(&["-n", "-E", "a\\tb"])
+        .succeeds()
+        .stdout_only("a\tb");
+    new_ucmd!()
+        .env("POSIXLY_CORRECT", "1")
+        .arg("--help")
+        .succeeds()
+        .stdout_only("--help\n");
+}
+
+#[cfg(unix)]
+#[test]
+fn test_invalid_utf8() {
+    use std::ffi::OsStr;
+    use std::os::unix::ffi::OsStrExt;
+
+    new_ucmd!()
+        .arg(OsStr::from_bytes(b"a\xffb"))
+        .succeeds()
+        .stdout_only_bytes(b"a\xffb\n");
+    new_ucmd!()
+        .arg("-e")
+        .arg(OsStr::from_bytes(b"\xfe\\x41"))
+        .succeeds()
+        .stdout_only_bytes(b"\xfeA\n");
+}
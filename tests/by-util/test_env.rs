@@ -242,3 +242,127 @@ fn test_fail_change_directory() {
         .stderr_move_str();
     assert!(out.contains("env: cannot change directory to "));
 }
+
+#[cfg(unix)]
+#[test]
+fn test_split_string() {
+    new_ucmd!()
+        .arg(r#"-S printf [%s] a\_b "c d" 'e\nf' "g\nh" #comment"#)
+        .arg("i")
+        .succeeds()
+        .stdout_is("[a][b][c d][e\\nf][g\nh][i]");
+    new_ucmd!()
+        .arg("-Sprintf [%s] x\\cy")
+        .arg("z")
+        .succeeds()
+        .stdout_is("[x][z]");
+}
+
+#[cfg(unix)]
+#[test]
+fn test_split_string_options() {
+    // The split arguments are parsed as options in place of -S.
+    new_ucmd!()
+        .env("FOO", "foo")
+        .arg("-S-u FOO BAR=${FOO}")
+        .arg("printenv")
+        .arg("FOO")
+        .arg("BAR")
+        .fails()
+        .code_is(1)
+        .stdout_is("foo\n");
+    new_ucmd!()
+        .env("FOO", "foo")
+        .arg("-iS")
+        .arg("printenv")
+        .succeeds()
+        .no_stdout();
+    new_ucmd!()
+        .arg("--split-string=A=1 printenv A")
+        .succeeds()
+        .stdout_is("1\n");
+}
+
+#[test]
+fn test_split_string_errors() {
+    for (arg, msg) in [
+        (r"-Sa\q", r"env: invalid sequence '\q' in -S"),
+        (r"-Sa\", "env: invalid backslash at end of string in -S"),
+        ("-S'a", "env: no terminating quote in -S string"),
+        (
+            r#"-S"\c""#,
+            r"env: '\c' must not appear in double-quoted -S string",
+        ),
+        (
+            "-Sa$FOO",
+            "env: only ${VARNAME} expansion is supported, error at: $FOO",
+        ),
+    ] {
+        new_ucmd!()
+            .arg(arg)
+            .fails()
+            .code_is(125)
+            .stderr_only(format!("{}\n", msg));
+    }
+}
+
+#[test]
+fn test_command_not_found() {
+    new_ucmd!()
+        .arg("no such command")
+        .fails()
+        .code_is(127)
+        .stderr_is(
+            "env: 'no such command': No such file or directory\n\
+             env: use -[v]S to pass options in shebang lines\n",
+        );
+}
+
+/// The mask of the standard signals in the `field` line of the status of the
+/// command run by env.
+#[cfg(target_os = "linux")]
+fn signal_mask_of_command(args: &[&str], field: &str) -> u64 {
+    let out = new_ucmd!()
+        .args(args)
+        .args(&["cat", "/proc/self/status"])
+        .succeeds()
+        .stdout_move_str();
+    let line = out
+        .lines()
+        .find_map(|line| line.strip_prefix(field))
+        .unwrap();
+    // The real-time signals reserved by the C library can't be changed.
+    u64::from_str_radix(line.trim(), 16).unwrap() & 0x7fff_ffff
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn test_signal_options() {
+    let args = [
+        "--default-signal",
+        "--ignore-signal=int,TERM",
+        "--block-signal=USR1",
+    ];
+    assert_eq!(signal_mask_of_command(&args, "SigIgn:"), 0x4002);
+    assert_eq!(signal_mask_of_command(&args, "SigBlk:"), 0x200);
+    // The last option given for a signal wins.
+    let args = ["--ignore-signal=INT", "--default-signal"];
+    assert_eq!(signal_mask_of_command(&args, "SigIgn:"), 0);
+}
+
+#[cfg(unix)]
+#[test]
+fn test_signal_options_errors() {
+    new_ucmd!()
+        .arg("--ignore-signal=FOO")
+        .arg("true")
+        .fails()
+        .code_is(125)
+        .stderr_contains("env: 'FOO': invalid signal");
+    new_ucmd!()
+        .arg("--default-signal=KILL")
+        .arg("true")
+        .fails()
+        .code_is(125)
+        .stderr_only("env: failed to set signal action for signal 9: Invalid argument\n");
+}
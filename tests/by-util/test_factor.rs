@@ -115,6 +115,48 @@ fn test_cli_args() {
         .stdout_contains("9: 3 3");
 }
 
+#[test]
+fn test_above_u64() {
+    new_ucmd!()
+        .args(&[
+            "18446744073709551616",
+            "18446744073709551617",
+            "170141183460469231731687303715884105727",
+            "99999999999999999999999999999999999999999999",
+        ])
+        .succeeds()
+        .stdout_only(
+            "18446744073709551616: 2 2 2 2 2 2 2 2 2 2 2 2 2 2 2 2 2 2 2 2 2 2 2 2 2 2 2 2 2 2 2 2 \
+             2 2 2 2 2 2 2 2 2 2 2 2 2 2 2 2 2 2 2 2 2 2 2 2 2 2 2 2 2 2 2 2\n\
+             18446744073709551617: 274177 67280421310721\n\
+             170141183460469231731687303715884105727: 170141183460469231731687303715884105727\n\
+             99999999999999999999999999999999999999999999: \
+             3 3 11 11 23 89 101 4093 8779 21649 513239 1052788969 1056689261\n",
+        );
+}
+
+#[test]
+fn test_above_u64_medium_factors() {
+    // (2⁶⁴ + 13) (10¹² + 39)
+    new_ucmd!()
+        .pipe_in("18446744073709551629 18446744074428974647874672513531\n")
+        .succeeds()
+        .stdout_only(
+            "18446744073709551629: 18446744073709551629\n\
+             18446744074428974647874672513531: 1000000000039 18446744073709551629\n",
+        );
+}
+
+#[test]
+fn test_exponents() {
+    for arg in ["-h", "--exponents"] {
+        new_ucmd!()
+            .args(&[arg, "1", "17", "1024", "360", "36893488147419103232"])
+            .succeeds()
+            .stdout_only("1:\n17: 17\n1024: 2^10\n360: 2^3 3^2 5\n36893488147419103232: 2^65\n");
+    }
+}
+
 #[test]
 fn test_random() {
     use conv::prelude::*;
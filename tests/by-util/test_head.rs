@@ -181,6 +181,44 @@ fn test_negative_zero_bytes() {
         .succeeds()
         .stdout_is("qwerty");
 }
+#[test]
+fn test_negative_lines_of_file() {
+    let (at, mut ucmd) = at_and_ucmd!();
+    at.write("unterminated", "a\nb\nc");
+    ucmd.args(&["-n", "-1", "unterminated"])
+        .succeeds()
+        .stdout_only("a\nb\n");
+
+    let scene = TestScenario::new(util_name!());
+    let data = "line\n".repeat(100_000);
+    scene.fixtures.write("big", &data);
+    scene
+        .ucmd()
+        .args(&["-n", "-99000", "big"])
+        .succeeds()
+        .stdout_only(&data[..5000]);
+    scene
+        .ucmd()
+        .args(&["-n", "-200000", "big"])
+        .succeeds()
+        .no_output();
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn test_negative_counts_of_file_without_size() {
+    // The files in /proc have a size of 0.
+    let version = std::fs::read_to_string("/proc/version").unwrap();
+    new_ucmd!()
+        .args(&["-c", "-1", "/proc/version"])
+        .succeeds()
+        .stdout_only(&version[..version.len() - 1]);
+    new_ucmd!()
+        .args(&["-n", "-1", "/proc/version"])
+        .succeeds()
+        .no_output();
+}
+
 #[test]
 fn test_no_such_file_or_directory() {
     new_ucmd!()
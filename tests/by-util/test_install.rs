@@ -66,7 +66,7 @@ fn test_install_unimplemented_arg() {
     let (at, mut ucmd) = at_and_ucmd!();
     let dir = "target_dir";
     let file = "source_file";
-    let context_arg = "--context";
+    let context_arg = "--preserve-context";
 
     at.touch(file);
     at.mkdir(dir);
@@ -187,10 +187,14 @@ fn test_install_mode_numeric() {
     let permissions = at.metadata(dest_file).permissions();
     assert_eq!(0o100_333_u32, PermissionsExt::mode(&permissions));
 
-    let mode_arg = "-m 0333";
     at.mkdir(dir2);
 
-    scene.ucmd().arg(mode_arg).arg(file).arg(dir2).succeeds();
+    scene
+        .ucmd()
+        .args(&["-m", "0333"])
+        .arg(file)
+        .arg(dir2)
+        .succeeds();
 
     let dest_file = &format!("{dir2}/{file}");
     assert!(at.file_exists(file));
@@ -230,7 +234,7 @@ fn test_install_mode_failing() {
         .arg(dir)
         .arg(mode_arg)
         .fails()
-        .stderr_contains("Invalid mode string: invalid digit found in string");
+        .stderr_contains("invalid mode '999'");
 
     let dest_file = &format!("{dir}/{file}");
     assert!(at.file_exists(file));
@@ -573,6 +577,68 @@ fn test_install_copy_then_compare_file_with_extra_mode() {
     assert!(after_install_sticky != after_install_sticky_again);
 }
 
+/// Install `source` to `target` with -C, after making `target` look old, and
+/// return whether it was copied again.
+fn install_compare_copies(scene: &TestScenario, source: &str, target: &str) -> bool {
+    let at = &scene.fixtures;
+    let old = FileTime::from_unix_time(0, 0);
+    filetime::set_symlink_file_times(at.plus(target), old, old).unwrap();
+    scene
+        .ucmd()
+        .args(&["-C", source, target])
+        .succeeds()
+        .no_stderr();
+    FileTime::from_last_modification_time(&at.symlink_metadata(target)) != old
+}
+
+#[test]
+fn test_install_compare_same_file() {
+    let scene = TestScenario::new(util_name!());
+    let at = &scene.fixtures;
+    at.write("source", "abc");
+    at.write("target", "abc");
+    at.set_mode("target", 0o755);
+
+    assert!(!install_compare_copies(&scene, "source", "target"));
+}
+
+#[test]
+fn test_install_compare_different_content() {
+    let scene = TestScenario::new(util_name!());
+    let at = &scene.fixtures;
+    at.write("source", "abc");
+    at.write("target", "abd");
+    at.set_mode("target", 0o755);
+
+    assert!(install_compare_copies(&scene, "source", "target"));
+    assert_eq!(at.read("target"), "abc");
+}
+
+#[test]
+fn test_install_compare_different_mode() {
+    let scene = TestScenario::new(util_name!());
+    let at = &scene.fixtures;
+    at.write("source", "abc");
+    at.write("target", "abc");
+    at.set_mode("target", 0o644);
+
+    assert!(install_compare_copies(&scene, "source", "target"));
+    assert_eq!(at.metadata("target").permissions().mode() & 0o7777, 0o755);
+}
+
+#[test]
+fn test_install_compare_symlink() {
+    let scene = TestScenario::new(util_name!());
+    let at = &scene.fixtures;
+    at.write("source", "abc");
+    at.write("copy", "abc");
+    at.set_mode("copy", 0o755);
+    at.symlink_file("copy", "target");
+
+    assert!(install_compare_copies(&scene, "source", "target"));
+    assert!(!at.is_symlink("target"));
+}
+
 const STRIP_TARGET_FILE: &str = "helloworld_installed";
 #[cfg(not(any(windows, target_os = "freebsd")))]
 const SYMBOL_DUMP_PROGRAM: &str = "objdump";
@@ -675,6 +741,52 @@ fn test_install_and_strip_with_non_existent_program() {
     assert!(!at.file_exists(STRIP_TARGET_FILE));
 }
 
+#[test]
+#[cfg(not(windows))]
+fn test_install_and_strip_with_program_arguments() {
+    let (at, mut ucmd) = at_and_ucmd!();
+    at.touch("source");
+
+    // the "strip program" sets the time of the installed file
+    ucmd.env("TZ", "UTC")
+        .args(&[
+            "-s",
+            "--strip-program=touch -t 200001010000",
+            "source",
+            "target",
+        ])
+        .succeeds()
+        .no_stderr();
+    assert_eq!(
+        FileTime::from_last_modification_time(&at.metadata("target")),
+        FileTime::from_unix_time(946_684_800, 0)
+    );
+}
+
+#[test]
+#[cfg(not(feature = "feat_selinux"))]
+fn test_install_default_context_without_selinux() {
+    let (at, mut ucmd) = at_and_ucmd!();
+    at.touch("source");
+
+    ucmd.args(&["-Z", "source", "target"])
+        .succeeds()
+        .no_stderr();
+    assert!(at.file_exists("target"));
+}
+
+#[test]
+#[cfg(not(feature = "feat_selinux"))]
+fn test_install_context_without_selinux() {
+    let (at, mut ucmd) = at_and_ucmd!();
+    at.touch("source");
+
+    ucmd.args(&["--context=system_u:object_r:bin_t:s0", "source", "target"])
+        .succeeds()
+        .stderr_is("install: warning: ignoring --context; it requires an SELinux-enabled kernel\n");
+    assert!(at.file_exists("target"));
+}
+
 #[test]
 fn test_install_creating_leading_dirs() {
     let scene = TestScenario::new(util_name!());
@@ -375,6 +375,60 @@ fn both_files_wrong_line_order() {
         ));
 }
 
+#[test]
+fn pairable_lines_wrong_line_order() {
+    let ts = TestScenario::new(util_name!());
+    let at = &ts.fixtures;
+    at.write("a", "b 1\na 2\n");
+    at.write("b", "b x\na y\n");
+    // Out of order input is only diagnosed by default once a line could not be paired.
+    ts.ucmd()
+        .arg("a")
+        .arg("b")
+        .succeeds()
+        .stdout_only("b 1 x\na 2 y\n");
+
+    ts.ucmd()
+        .arg("--check-order")
+        .arg("a")
+        .arg("b")
+        .fails()
+        .no_stdout()
+        .stderr_is(format!("{}: a:2: is not sorted: a 2\n", ts.util_name));
+}
+
+#[test]
+fn check_order_last_option_wins() {
+    new_ucmd!()
+        .arg("--check-order")
+        .arg("--nocheck-order")
+        .arg("fields_2.txt")
+        .arg("fields_4.txt")
+        .succeeds()
+        .stdout_contains("7 g f 4 fg");
+
+    new_ucmd!()
+        .arg("--nocheck-order")
+        .arg("--check-order")
+        .arg("fields_2.txt")
+        .arg("fields_4.txt")
+        .fails()
+        .stdout_does_not_contain("7 g f 4 fg")
+        .stderr_contains("fields_4.txt:5: is not sorted: 11 g 5 gh");
+}
+
+#[test]
+fn case_insensitive_keys_of_each_line() {
+    let (at, mut ucmd) = at_and_ucmd!();
+    at.write("a", "a 1\nB 2\nb 3\n");
+    at.write("b", "A x\nb y\n");
+    ucmd.arg("-i")
+        .arg("a")
+        .arg("b")
+        .succeeds()
+        .stdout_only("a 1 x\nB 2 y\nb 3 y\n");
+}
+
 #[test]
 fn headers() {
     new_ucmd!()
@@ -397,6 +451,48 @@ fn headers_autoformat() {
         .stdout_only_fixture("header_autoformat.expected");
 }
 
+#[test]
+fn headers_outer() {
+    new_ucmd!()
+        .arg("header_1.txt")
+        .arg("header_2.txt")
+        .arg("--header")
+        .arg("-a")
+        .arg("1")
+        .arg("-a")
+        .arg("2")
+        .succeeds()
+        .stdout_only_fixture("header_outer.expected");
+
+    new_ucmd!()
+        .arg("header_1.txt")
+        .arg("header_2.txt")
+        .arg("--header")
+        .arg("-a")
+        .arg("1")
+        .arg("-a")
+        .arg("2")
+        .arg("-e")
+        .arg("-")
+        .arg("-o")
+        .arg("auto")
+        .succeeds()
+        .stdout_only("id field count\n1 a 10\n2 b 25\n3 c -\n4 d 17\n5 c -\n7 - 18\n");
+}
+
+#[test]
+fn headers_are_not_order_checked() {
+    let (at, mut ucmd) = at_and_ucmd!();
+    at.write("a", "z name\n1 a\n2 b\n");
+    at.write("b", "y value\n1 x\n2 y\n");
+    ucmd.arg("a")
+        .arg("b")
+        .arg("--header")
+        .arg("--check-order")
+        .succeeds()
+        .stdout_only("z name value\n1 a x\n2 b y\n");
+}
+
 #[test]
 fn single_file_with_header() {
     new_ucmd!()
@@ -412,6 +508,15 @@ fn single_file_with_header() {
         .arg("--header")
         .succeeds()
         .stdout_is("A 1\n");
+
+    new_ucmd!()
+        .arg("header_1.txt")
+        .arg("empty.txt")
+        .arg("--header")
+        .arg("-a")
+        .arg("1")
+        .succeeds()
+        .stdout_only_fixture("header_1.txt");
 }
 
 #[test]
@@ -95,7 +95,77 @@ fn test_kill_set_bad_signal_name() {
         .arg("-s")
         .arg("IAMNOTASIGNAL") // spell-checker:disable-line
         .fails()
-        .stderr_contains("unknown signal");
+        .stderr_is("kill: 'IAMNOTASIGNAL': invalid signal\n"); // spell-checker:disable-line
+}
+
+#[test]
+fn test_kill_list_one_signal_per_line() {
+    let result = new_ucmd!().arg("-l").succeeds();
+    let mut lines = result.stdout_str().lines();
+    assert_eq!(lines.next(), Some("HUP"));
+    assert!(lines.any(|line| line == "KILL"));
+}
+
+#[test]
+fn test_kill_list_convert_signals() {
+    new_ucmd!()
+        .args(&["-l", "9", "KILL", "SIGKILL", "kill", "137", "265"])
+        .succeeds()
+        .stdout_is("KILL\n9\n9\n9\nKILL\nKILL\n");
+}
+
+#[test]
+fn test_kill_list_convert_invalid_signals() {
+    new_ucmd!()
+        .args(&["-l", "KILL", "IAMNOTASIGNAL", "127", "9"]) // spell-checker:disable-line
+        .fails()
+        .code_is(1)
+        .stdout_is("9\nKILL\n")
+        .stderr_is(
+            "kill: 'IAMNOTASIGNAL': invalid signal\n\
+             kill: '127': invalid signal\n", // spell-checker:disable-line
+        );
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn test_kill_list_real_time_signals() {
+    let rtmin = libc::SIGRTMIN();
+    let rtmax = libc::SIGRTMAX();
+    new_ucmd!()
+        .args(&["-l", "RTMIN+1", "rtmax"])
+        .arg(format!("{rtmin}"))
+        .arg(format!("{}", rtmax - 1))
+        .succeeds()
+        .stdout_is(format!("{}\n{}\nRTMIN\nRTMAX-1\n", rtmin + 1, rtmax));
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn test_kill_table_rows() {
+    new_ucmd!()
+        .args(&["-t", "1", "SIGKILL"])
+        .succeeds()
+        .stdout_is(" 1 HUP      Hangup\n 9 KILL     Killed\n");
+    new_ucmd!()
+        .arg("-L")
+        .succeeds()
+        .stdout_contains("\n15 TERM     Terminated\n");
+}
+
+#[test]
+fn test_kill_table_invalid_signal() {
+    new_ucmd!()
+        .args(&["-t", "IAMNOTASIGNAL"]) // spell-checker:disable-line
+        .fails()
+        .code_is(1)
+        .no_stdout()
+        .stderr_is("kill: 'IAMNOTASIGNAL': invalid signal\n"); // spell-checker:disable-line
+}
+
+#[test]
+fn test_kill_list_with_signal() {
+    new_ucmd!().args(&["-l", "-s", "KILL"]).fails().code_is(1);
 }
 
 #[test]
@@ -167,3 +237,44 @@ fn test_kill_with_signal_prefixed_name_new_form() {
         .succeeds();
     assert_eq!(target.wait_for_signal(), Some(libc::SIGKILL));
 }
+
+#[test]
+fn test_kill_with_signal_lowercase_name_new_form() {
+    let mut target = Target::new();
+    new_ucmd!()
+        .arg("-s")
+        .arg("sigkill")
+        .arg(format!("{}", target.pid()))
+        .succeeds();
+    assert_eq!(target.wait_for_signal(), Some(libc::SIGKILL));
+}
+
+#[test]
+fn test_kill_with_signal_exit_status_old_form() {
+    let mut target = Target::new();
+    new_ucmd!()
+        .arg("-137")
+        .arg(format!("{}", target.pid()))
+        .succeeds();
+    assert_eq!(target.wait_for_signal(), Some(libc::SIGKILL));
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn test_kill_with_real_time_signal() {
+    let mut target = Target::new();
+    new_ucmd!()
+        .arg("-RTMIN+1")
+        .arg(format!("{}", target.pid()))
+        .succeeds();
+    assert_eq!(target.wait_for_signal(), Some(libc::SIGRTMIN() + 1));
+}
+
+#[test]
+fn test_kill_with_null_signal() {
+    let target = Target::new();
+    new_ucmd!()
+        .args(&["-s", "0"])
+        .arg(format!("{}", target.pid()))
+        .succeeds();
+}
@@ -588,7 +588,38 @@ fn test_symlink_no_deref_file() {
 
 #[test]
 fn test_relative_requires_symbolic() {
-    new_ucmd!().args(&["-r", "foo", "bar"]).fails();
+    new_ucmd!()
+        .args(&["-r", "foo", "bar"])
+        .fails()
+        .code_is(1)
+        .stderr_only("ln: cannot do --relative without --symbolic\n");
+}
+
+#[test]
+fn test_relative_through_symlinked_dir() {
+    let scene = TestScenario::new(util_name!());
+    let at = &scene.fixtures;
+    at.mkdir_all("a/b");
+    at.touch("a/b/f");
+    at.mkdir("c");
+    at.symlink_dir("a/b", "linkdir");
+    scene.ucmd().args(&["-sr", "linkdir/f", "c/l1"]).succeeds();
+    assert_eq!(at.resolve_link("c/l1"), "../a/b/f");
+
+    // the link's directory is resolved too
+    scene
+        .ucmd()
+        .args(&["-sr", "a/b/f", "linkdir/l2"])
+        .succeeds();
+    assert_eq!(at.resolve_link("a/b/l2"), "f");
+}
+
+#[test]
+fn test_relative_missing_source() {
+    let (at, mut ucmd) = at_and_ucmd!();
+    at.mkdir("c");
+    ucmd.args(&["-sr", "missing/x", "c/l"]).succeeds();
+    assert_eq!(at.resolve_link("c/l"), "../missing/x");
 }
 
 #[test]
@@ -703,6 +734,43 @@ fn test_hard_logical_dir_fail() {
         .stderr_contains("failed to create hard link 'link-to-dir'");
 }
 
+#[test]
+fn test_hard_link_to_dir_symlink() {
+    let scene = TestScenario::new(util_name!());
+    let at = &scene.fixtures;
+    at.mkdir("d");
+    at.symlink_dir("d", "link-to-dir");
+
+    scene
+        .ucmd()
+        .args(&["-L", "link-to-dir", "hard"])
+        .fails()
+        .stderr_only("ln: link-to-dir: hard link not allowed for directory\n");
+    scene
+        .ucmd()
+        .args(&["d", "hard"])
+        .fails()
+        .stderr_only("ln: d: hard link not allowed for directory\n");
+
+    // -P, the default, links to the symlink itself
+    scene
+        .ucmd()
+        .args(&["-L", "-P", "link-to-dir", "hard"])
+        .succeeds();
+    assert!(at.is_symlink("hard"));
+    assert_eq!(at.resolve_link("hard"), "d");
+}
+
+#[test]
+fn test_symlink_missing_dir() {
+    new_ucmd!()
+        .args(&["-s", "target", "missing/link"])
+        .fails()
+        .stderr_contains(
+            "ln: failed to create symbolic link 'missing/link': No such file or directory",
+        );
+}
+
 #[test]
 fn test_symlink_remove_existing_same_src_and_dest() {
     let (at, mut ucmd) = at_and_ucmd!();
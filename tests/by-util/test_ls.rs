@@ -1612,6 +1612,72 @@ fn test_ls_long_ctime() {
     }
 }
 
+#[test]
+fn test_ls_order_time_ties_by_name() {
+    let scene = TestScenario::new(util_name!());
+    let at = &scene.fixtures;
+    let time = filetime::FileTime::from_unix_time(1_000_000_000, 0);
+    for name in ["b", "c", "a"] {
+        at.touch(name);
+        filetime::set_file_mtime(at.plus(name), time).unwrap();
+    }
+    scene.ucmd().arg("-1t").succeeds().stdout_only("a\nb\nc\n");
+    scene
+        .ucmd()
+        .args(&["-1", "--sort=time", "-r"])
+        .succeeds()
+        .stdout_only("c\nb\na\n");
+
+    filetime::set_file_mtime(at.plus("c"), filetime::FileTime::now()).unwrap();
+    scene.ucmd().arg("-1t").succeeds().stdout_only("c\na\nb\n");
+}
+
+#[test]
+fn test_ls_huge_directory() {
+    // Big enough for the metadata to be fetched in parallel.
+    let scene = TestScenario::new(util_name!());
+    let at = &scene.fixtures;
+    at.mkdir("dir");
+    for i in 0..1200 {
+        at.write(&format!("dir/{i:04}"), &"x".repeat(i));
+    }
+    let expected: String = (0..1200).rev().map(|i| format!("{i:04}\n")).collect();
+    scene
+        .ucmd()
+        .args(&["-1S", "dir"])
+        .succeeds()
+        .stdout_only(expected);
+
+    let result = scene.ucmd().args(&["-l", "dir"]).succeeds();
+    assert_eq!(result.stdout_str().lines().count(), 1201);
+    result.stdout_contains(" 1199 ");
+
+    #[cfg(unix)]
+    {
+        at.symlink_file("missing", "dir/dangling");
+        scene
+            .ucmd()
+            .args(&["-lL", "dir"])
+            .fails()
+            .code_is(1)
+            .stderr_contains("dangling");
+    }
+}
+
+#[test]
+fn test_ls_time_birth() {
+    let scene = TestScenario::new(util_name!());
+    let at = &scene.fixtures;
+    at.touch("a");
+    for args in [
+        &["-l", "--time=birth", "a"][..],
+        &["-l", "--time=creation", "--sort=time", "a"][..],
+        &["-1", "--sort=time", "--time=birth", "a"][..],
+    ] {
+        scene.ucmd().args(args).succeeds().stdout_contains("a\n");
+    }
+}
+
 #[test]
 #[ignore]
 fn test_ls_order_birthtime() {
@@ -1865,6 +1931,145 @@ fn test_ls_recursive() {
     result.stdout_contains("a\\b:\nb");
 }
 
+#[test]
+#[cfg(unix)]
+fn test_ls_hyperlink() {
+    let scene = TestScenario::new(util_name!());
+    let at = &scene.fixtures;
+    at.mkdir("dir");
+    at.touch("dir/a b");
+    at.touch("dir/c");
+    let path = at.plus("dir").canonicalize().unwrap();
+    let path = path.to_str().unwrap();
+
+    let re = Regex::new(&format!(
+        "^'\x1b]8;;file://[^/]*{}/a%20b\x07a b\x1b]8;;\x07'\n\
+         \x1b]8;;file://[^/]*{}/c\x07c\x1b]8;;\x07$",
+        regex::escape(path),
+        regex::escape(path),
+    ))
+    .unwrap();
+    for arg in ["--hyperlink", "--hyperlink=always", "--hyperlink=yes"] {
+        scene
+            .ucmd()
+            .arg(arg)
+            .arg("dir")
+            .succeeds()
+            .stdout_matches(&re);
+    }
+
+    for arg in ["--hyperlink=never", "--hyperlink=auto"] {
+        scene
+            .ucmd()
+            .arg(arg)
+            .arg("dir")
+            .succeeds()
+            .stdout_is("'a b'\nc\n");
+    }
+}
+
+#[test]
+#[cfg(unix)]
+fn test_ls_format_json() {
+    let scene = TestScenario::new(util_name!());
+    let at = &scene.fixtures;
+    at.mkdir("dir");
+    at.mkdir("empty");
+    at.write("dir/a\"b", "hello");
+    at.set_mode("dir/a\"b", 0o644);
+    at.relative_symlink_file("a\"b", "dir/link");
+
+    let result = scene
+        .ucmd()
+        .arg("-n")
+        .arg("--format=json")
+        .arg("dir")
+        .succeeds();
+    let lines: Vec<_> = result.stdout_str().lines().collect();
+    assert_eq!(lines.len(), 4);
+    assert_eq!(lines[0], "[");
+    assert!(lines[1].starts_with(r#"  {"name": "a\"b", "path": "dir/a\"b", "type": "file", "size": 5, "mode": "-rw-r--r--", "links": 1, "#));
+    assert!(lines[1].ends_with("},"));
+    assert!(lines[2].starts_with(r#"  {"name": "link", "path": "dir/link", "type": "symlink", "#));
+    assert!(lines[2].ends_with(r#", "target": "a\"b"}"#));
+    assert_eq!(lines[3], "]");
+
+    let re = Regex::new(&format!(
+        r#""owner": "{}", "group": "{}", "modified": "\d{{4}}-\d\d-\d\dT\d\d:\d\d:\d\d\.\d{{9}}Z""#,
+        nix::unistd::getuid(),
+        nix::unistd::getgid()
+    ))
+    .unwrap();
+    assert!(re.is_match(lines[1]));
+
+    scene
+        .ucmd()
+        .arg("--format=json")
+        .arg("empty")
+        .succeeds()
+        .stdout_only("[]\n");
+
+    // The entries of all directories end up in the same array, without headings or totals.
+    scene
+        .ucmd()
+        .arg("--format=json")
+        .arg("-Rs")
+        .arg("dir")
+        .arg("empty")
+        .succeeds()
+        .stdout_does_not_contain("total")
+        .stdout_does_not_contain("dir:")
+        .stdout_matches(
+            &Regex::new(r#"^\[\n  \{"name": "a\\"b", .*\},\n  \{"name": "link", .*\}\n\]$"#)
+                .unwrap(),
+        );
+}
+
+#[test]
+fn test_ls_dired() {
+    let scene = TestScenario::new(util_name!());
+    let at = &scene.fixtures;
+    at.mkdir("dir");
+    at.mkdir("dir/sub");
+    at.touch("dir/a b");
+    at.touch("dir/c");
+
+    let result = scene.ucmd().args(&["--dired", "-R", "dir"]).succeeds();
+    let output = result.stdout_str();
+    let names_at = |label: &str| -> Vec<&str> {
+        let line = output
+            .lines()
+            .find_map(|line| line.strip_prefix(label))
+            .unwrap();
+        let offsets: Vec<usize> = line
+            .split_whitespace()
+            .map(|n| n.parse().unwrap())
+            .collect();
+        offsets
+            .chunks(2)
+            .map(|range| &output[range[0]..range[1]])
+            .collect()
+    };
+    assert_eq!(names_at("//DIRED//"), ["'a b'", "c", "sub"]);
+    #[cfg(not(windows))]
+    assert_eq!(names_at("//SUBDIRED//"), ["dir", "dir/sub"]);
+    assert!(output.starts_with("  dir:\n  total "));
+    assert!(output.ends_with("\n//DIRED-OPTIONS// --quoting-style=shell-escape\n"));
+
+    // --dired implies the long format.
+    scene
+        .ucmd()
+        .args(&["--dired", "-1", "--quoting-style=literal", "dir/c"])
+        .succeeds()
+        .stdout_contains("  -")
+        .stdout_matches(
+            &Regex::new(
+                r"^-.* dir/c\n//DIRED// \d+ \d+\n//DIRED-OPTIONS// --quoting-style=literal$",
+            )
+            .unwrap(),
+        );
+}
+
 #[test]
 fn test_ls_color() {
     let scene = TestScenario::new(util_name!());
@@ -3199,6 +3404,28 @@ fn test_ls_context_format() {
     }
 }
 
+#[test]
+#[cfg(all(unix, not(feature = "feat_selinux")))]
+fn test_ls_context_without_selinux() {
+    let scene = TestScenario::new(util_name!());
+    let at = &scene.fixtures;
+    at.touch("a");
+    at.touch("bb");
+    scene
+        .ucmd()
+        .args(&["-Z", "a", "bb"])
+        .succeeds()
+        .stdout_only("? a\n? bb\n");
+
+    // the context column is placed before the size
+    let re = Regex::new(r"^-\S+ 1 \S+ \S+ \? 0 .* a$").unwrap();
+    scene
+        .ucmd()
+        .args(&["-l", "--context", "a"])
+        .succeeds()
+        .stdout_matches(&re);
+}
+
 #[test]
 #[allow(non_snake_case)]
 fn test_ls_a_A() {
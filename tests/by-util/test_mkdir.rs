@@ -100,7 +100,10 @@ fn test_symbolic_alteration() {
 
     ucmd.arg("-m").arg("-w").arg(TEST_DIR1).succeeds();
     let perms = at.metadata(TEST_DIR1).permissions().mode();
-    assert_eq!(perms, 0o40555);
+    // Like GNU, MODE applies to a=rwx, and only clears the write bits that
+    // the umask does not.
+    let umask = uucore::mode::get_umask();
+    assert_eq!(perms, 0o40777 & !(0o222 & !umask));
 }
 
 #[test]
@@ -185,3 +188,25 @@ fn test_umask_compliance() {
         test_single_case(i as mode_t);
     }
 }
+
+#[test]
+#[cfg(not(feature = "feat_selinux"))]
+fn test_mkdir_default_context_without_selinux() {
+    let (at, mut ucmd) = at_and_ucmd!();
+
+    ucmd.args(&["-pZ", "a/b"]).succeeds().no_stderr();
+    assert!(at.dir_exists("a/b"));
+}
+
+#[test]
+#[cfg(not(feature = "feat_selinux"))]
+fn test_mkdir_context_without_selinux() {
+    let (at, mut ucmd) = at_and_ucmd!();
+
+    ucmd.args(&["--context=system_u:object_r:user_tmp_t:s0", "a"])
+        .succeeds()
+        .stderr_is(
+            "mkdir: warning: ignoring --context; it requires an SELinux/SMACK-enabled kernel\n",
+        );
+    assert!(at.dir_exists("a"));
+}
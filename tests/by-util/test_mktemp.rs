@@ -702,16 +702,18 @@ fn test_tmpdir_env_var() {
     assert_suffix_matches_template!("tmp.XXXXXXXXXX", filename);
     assert!(at.file_exists(filename));
 
-    // FIXME This is not working because --tmpdir is configured to
-    // require a value.
-    //
-    // // `TMPDIR=. mktemp --tmpdir`
-    // let (at, mut ucmd) = at_and_ucmd!();
-    // let result = ucmd.env(TMPDIR, ".").arg("--tmpdir").succeeds();
-    // let filename = result.no_stderr().stdout_str().trim_end();
-    // let template = format!(".{}tmp.XXXXXXXXXX", MAIN_SEPARATOR);
-    // assert_matches_template!(&template, filename);
-    // assert!(at.file_exists(filename));
+    // `TMPDIR=. mktemp --tmpdir`
+    let (at, mut ucmd) = at_and_ucmd!();
+    let result = ucmd.env(TMPDIR, ".").arg("--tmpdir").succeeds();
+    let filename = result.no_stderr().stdout_str().trim_end();
+    #[cfg(not(windows))]
+    {
+        let template = format!(".{MAIN_SEPARATOR}tmp.XXXXXXXXXX");
+        assert_matches_template!(&template, filename);
+    }
+    #[cfg(windows)]
+    assert_suffix_matches_template!("tmp.XXXXXXXXXX", filename);
+    assert!(at.file_exists(filename));
 
     // `TMPDIR=. mktemp --tmpdir XXX`
     let (at, mut ucmd) = at_and_ucmd!();
@@ -826,3 +828,93 @@ fn test_default_missing_value() {
     let scene = TestScenario::new(util_name!());
     scene.ucmd().arg("-d").arg("--tmpdir").succeeds();
 }
+
+#[test]
+#[cfg(not(feature = "feat_selinux"))]
+fn test_default_context_without_selinux() {
+    let (at, mut ucmd) = at_and_ucmd!();
+    let result = ucmd.args(&["-Z", "fooXXX"]).succeeds();
+    result.no_stderr();
+    assert!(at.file_exists(result.stdout_str().trim_end()));
+}
+
+#[test]
+#[cfg(not(feature = "feat_selinux"))]
+fn test_context_without_selinux() {
+    let (at, mut ucmd) = at_and_ucmd!();
+    let result = ucmd
+        .args(&["-d", "--context=system_u:object_r:user_tmp_t:s0", "fooXXX"])
+        .succeeds();
+    result.stderr_is(
+        "mktemp: warning: ignoring --context; it requires an SELinux/SMACK-enabled kernel\n",
+    );
+    assert!(at.dir_exists(result.stdout_str().trim_end()));
+}
+
+/// Test that `-t` prefers `TMPDIR` to the directory given with `-p`,
+/// while `-p` is preferred to `TMPDIR` without `-t`.
+#[cfg(unix)]
+#[test]
+fn test_tmpdir_precedence() {
+    let scene = TestScenario::new(util_name!());
+    let at = &scene.fixtures;
+    at.mkdir("env");
+    at.mkdir("arg");
+
+    let result = scene
+        .ucmd()
+        .env(TMPDIR, "env")
+        .args(&["-t", "-p", "arg", "XXX"])
+        .succeeds();
+    let filename = result.no_stderr().stdout_str().trim_end();
+    assert_matches_template!("env/XXX", filename);
+    assert!(at.file_exists(filename));
+
+    let result = scene
+        .ucmd()
+        .env(TMPDIR, "env")
+        .args(&["-p", "arg", "XXX"])
+        .succeeds();
+    let filename = result.no_stderr().stdout_str().trim_end();
+    assert_matches_template!("arg/XXX", filename);
+    assert!(at.file_exists(filename));
+
+    // An empty directory is as good as none.
+    let result = scene
+        .ucmd()
+        .env(TMPDIR, "env")
+        .args(&["-p", "", "XXX"])
+        .succeeds();
+    let filename = result.no_stderr().stdout_str().trim_end();
+    assert_matches_template!("env/XXX", filename);
+    assert!(at.file_exists(filename));
+
+    let result = scene
+        .ucmd()
+        .env(TMPDIR, "")
+        .args(&["-u", "-t", "XXX"])
+        .succeeds();
+    assert_matches_template!("/tmp/XXX", result.stdout_str().trim_end());
+}
+
+/// Test that the Xs have to end the template, apart from the implied suffix.
+#[test]
+fn test_suffix_starts_after_last_x() {
+    new_ucmd!()
+        .arg("aXXXbXc")
+        .fails()
+        .stderr_only("mktemp: too few X's in template 'aXXXbXc'\n");
+}
+
+/// Test that Xs in the directory part of the template are kept.
+#[cfg(unix)]
+#[test]
+fn test_xs_in_directory() {
+    let (at, mut ucmd) = at_and_ucmd!();
+    at.mkdir("dXXX");
+    let result = ucmd.arg("dXXX/fooXXXbar").succeeds();
+    let filename = result.no_stderr().stdout_str().trim_end();
+    assert_eq!(&filename[..8], "dXXX/foo");
+    assert_matches_template!("dXXX/fooXXXbar", filename);
+    assert!(at.file_exists(filename));
+}
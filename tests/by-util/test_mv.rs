@@ -897,6 +897,117 @@ fn test_mv_into_self_data() {
     assert!(at.file_exists(file2));
     assert!(!at.file_exists(file1));
 }
+#[test]
+#[cfg(target_os = "linux")]
+fn test_mv_across_devices_resumes_partial_copy() {
+    use std::os::unix::fs::MetadataExt;
+
+    let (at, mut ucmd) = at_and_ucmd!();
+    let other_fs = std::path::Path::new("/dev/shm");
+    if !other_fs.is_dir() || other_fs.metadata().unwrap().dev() == at.metadata(".").dev() {
+        println!("test skipped: no other file system to move to");
+        return;
+    }
+    let dir = tempfile::Builder::new()
+        .prefix("test_mv")
+        .tempdir_in(other_fs)
+        .unwrap();
+    let content: Vec<u8> = (0..200_000u32).map(|i| (i % 251) as u8).collect();
+    at.write_bytes("file", &content);
+    // The data of an interrupted earlier move, which is kept.
+    let metadata = at.metadata("file");
+    let partial = dir.path().join(format!(
+        ".file.{}-{}-{}-{}.{:09}.mv-partial",
+        metadata.dev(),
+        metadata.ino(),
+        metadata.size(),
+        metadata.mtime(),
+        metadata.mtime_nsec()
+    ));
+    std::fs::write(&partial, &content[..100_000]).unwrap();
+
+    ucmd.arg("file").arg(dir.path()).succeeds().no_stderr();
+
+    assert!(!at.file_exists("file"));
+    assert_eq!(std::fs::read(dir.path().join("file")).unwrap(), content);
+    assert!(!partial.exists());
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn test_mv_across_devices_ignores_other_files() {
+    use std::os::unix::fs::MetadataExt;
+
+    let (at, mut ucmd) = at_and_ucmd!();
+    let other_fs = std::path::Path::new("/dev/shm");
+    if !other_fs.is_dir() || other_fs.metadata().unwrap().dev() == at.metadata(".").dev() {
+        println!("test skipped: no other file system to move to");
+        return;
+    }
+    let dir = tempfile::Builder::new()
+        .prefix("test_mv")
+        .tempdir_in(other_fs)
+        .unwrap();
+    let mut content = b"NEWDATA!".to_vec();
+    content.resize(200_000, 0);
+    at.write_bytes("file", &content);
+    // Neither the partial copy of an earlier version of the file nor a file of the user is used.
+    let mut stale = b"OLDDATA!".to_vec();
+    stale.resize(150_000, 0);
+    std::fs::write(dir.path().join(".file.mv-partial"), &stale).unwrap();
+
+    ucmd.arg("file").arg(dir.path()).succeeds().no_stderr();
+
+    assert!(!at.file_exists("file"));
+    assert_eq!(std::fs::read(dir.path().join("file")).unwrap(), content);
+    assert_eq!(
+        std::fs::read(dir.path().join(".file.mv-partial")).unwrap(),
+        stale
+    );
+    assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 2);
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn test_mv_exchange() {
+    let (at, mut ucmd) = at_and_ucmd!();
+    at.write("a", "first\n");
+    at.mkdir("b");
+    at.write("b/file", "second\n");
+
+    ucmd.args(&["--exchange", "-v", "a", "b"])
+        .succeeds()
+        .stdout_only("'a' <-> 'b'\n");
+
+    assert_eq!(at.read("a/file"), "second\n");
+    assert_eq!(at.read("b"), "first\n");
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn test_mv_exchange_missing_file() {
+    let (at, mut ucmd) = at_and_ucmd!();
+    at.write("a", "first\n");
+
+    ucmd.args(&["--exchange", "a", "b"])
+        .fails()
+        .stderr_is("mv: cannot exchange 'a' and 'b': No such file or directory\n");
+
+    assert_eq!(at.read("a"), "first\n");
+}
+
+#[test]
+fn test_mv_exchange_extra_operand() {
+    let (at, mut ucmd) = at_and_ucmd!();
+    at.touch("a");
+    at.touch("b");
+    at.touch("c");
+
+    ucmd.args(&["--exchange", "a", "b", "c"])
+        .fails()
+        .stderr_contains("extra operand 'c'");
+}
+
 // Todo:
 
 // $ at.touch a b
@@ -82,3 +82,44 @@ fn test_trailing_empty_adjustment() {
         "error: The argument '--adjustment <adjustment>' requires a value but none was supplied",
     );
 }
+
+#[test]
+#[cfg(target_os = "linux")]
+fn test_adjust_running_process() {
+    let mut child = std::process::Command::new("sleep")
+        .arg("30")
+        .spawn()
+        .unwrap();
+    let pid = child.id().to_string();
+
+    new_ucmd!()
+        .args(&["-n", "3", "-p", &pid])
+        .succeeds()
+        .stdout_only(format!(
+            "{pid} (process ID) old priority 0, new priority 3\n"
+        ));
+    new_ucmd!()
+        .args(&["--pid", &pid, "--pid=999999999"])
+        .fails()
+        .code_is(1)
+        .stdout_is(format!(
+            "{pid} (process ID) old priority 3, new priority 13\n"
+        ))
+        .stderr_is("nice: failed to get priority for 999999999 (process ID): No such process\n");
+
+    child.kill().unwrap();
+    child.wait().unwrap();
+}
+
+#[test]
+fn test_adjust_running_process_errors() {
+    new_ucmd!()
+        .args(&["-p", "123", "true"])
+        .fails()
+        .usage_error("A command can't be given with --pid, --pgrp or --user.");
+    new_ucmd!()
+        .args(&["-p", "abc", "-u", "no-such-user"])
+        .fails()
+        .code_is(1)
+        .stderr_is("nice: invalid ID: 'abc'\nnice: invalid user: 'no-such-user'\n");
+}
@@ -27,3 +27,37 @@ fn test_nohup_multiple_args_and_flags() {
     assert!(at.file_exists("file1"));
     assert!(at.file_exists("file2"));
 }
+
+#[test]
+fn test_nohup_command_not_found() {
+    new_ucmd!()
+        .arg("no-such-command")
+        .fails()
+        .code_is(127)
+        .stderr_is("nohup: failed to run command 'no-such-command': No such file or directory\n");
+}
+
+#[test]
+fn test_nohup_command_not_executable() {
+    let (at, mut ucmd) = at_and_ucmd!();
+    at.mkdir("dir");
+
+    ucmd.arg("./dir")
+        .fails()
+        .code_is(126)
+        .stderr_is("nohup: failed to run command './dir': Permission denied\n");
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn test_nohup_setsid() {
+    // The command leads its own session, whose id is its pid.
+    new_ucmd!()
+        .args(&["--setsid", "sh", "-c"])
+        .arg("read -r pid comm state ppid pgrp sid rest < /proc/$$/stat; [ $sid = $$ ]")
+        .succeeds();
+    new_ucmd!()
+        .args(&["--setsid", "sh", "-c", "exit 3"])
+        .fails()
+        .code_is(3);
+}
@@ -403,6 +403,14 @@ fn test_format_selected_field_range() {
         .stdout_only("1K 2000 3000 4000 5000 6K\n");
 }
 
+#[test]
+fn test_format_selected_field_ranges() {
+    new_ucmd!()
+        .args(&["--from=auto", "--field=2-4,7-", "1K 2K 3K 4K 5K 6K 7K 8K"])
+        .succeeds()
+        .stdout_only("1K 2000 3000 4000 5K 6K 7000 8000\n");
+}
+
 #[test]
 fn test_format_all_fields() {
     let all_fields_patterns = vec!["-", "-,3", "3,-", "1,-,3", "- 3"];
@@ -841,6 +849,22 @@ fn test_format_with_zero_padding_and_suffix() {
         .stdout_is("001234 ?\n");
 }
 
+#[test]
+fn test_format_with_zero_padding_and_unit_suffix() {
+    new_ucmd!()
+        .args(&["--format=%08.1f", "--to=si", "--suffix=B", "5000"])
+        .succeeds()
+        .stdout_is("000005.0KB\n");
+}
+
+#[test]
+fn test_format_with_zero_padding_and_negative_number() {
+    new_ucmd!()
+        .args(&["--format=%08.1f", "--to=si", "--", "-5000"])
+        .succeeds()
+        .stdout_is("-00005.0K\n");
+}
+
 #[test]
 fn test_format_with_precision() {
     let values = vec![("0.99", "1.0"), ("1", "1.0"), ("1.01", "1.1")];
@@ -987,3 +1011,59 @@ fn test_format_grouping_conflicts_with_to_option() {
         .code_is(1)
         .stderr_contains("grouping cannot be combined with --to");
 }
+
+#[test]
+fn test_invalid_abort() {
+    new_ucmd!()
+        .args(&["--from=si", "--invalid=abort", "1K", "x", "2K"])
+        .fails()
+        .code_is(2)
+        .stdout_is("1000\n")
+        .stderr_is("numfmt: invalid suffix in input: 'x'\n");
+
+    new_ucmd!()
+        .args(&["--from=si", "--invalid=abort"])
+        .pipe_in("1K\nx\n2K\n")
+        .fails()
+        .code_is(2)
+        .stdout_is("1000\n")
+        .stderr_is("numfmt: invalid suffix in input: 'x'\n");
+}
+
+#[test]
+fn test_invalid_fail() {
+    new_ucmd!()
+        .args(&["--from=si", "--invalid=fail", "--field=1,3"])
+        .pipe_in("1K x 2K\n  abc  3K\n5K\n")
+        .fails()
+        .code_is(2)
+        .stdout_is("1000 x 2000\n  abc  3K\n5000\n")
+        .stderr_is("numfmt: invalid suffix in input: 'abc'\n");
+}
+
+#[test]
+fn test_invalid_warn() {
+    new_ucmd!()
+        .args(&["--from=si", "--invalid=warn", "--padding=5", "x", "1K"])
+        .succeeds()
+        .stdout_is("x\n 1000\n")
+        .stderr_is("numfmt: invalid suffix in input: 'x'\n");
+}
+
+#[test]
+fn test_invalid_ignore() {
+    new_ucmd!()
+        .args(&["--from=si", "--invalid=ignore", "-d:", "--field=-"])
+        .pipe_in("1K:x:2K\n")
+        .succeeds()
+        .stdout_only("1000:x:2000\n");
+}
+
+#[test]
+fn test_invalid_mode() {
+    new_ucmd!()
+        .args(&["--invalid=bogus", "1"])
+        .fails()
+        .code_is(1)
+        .stderr_contains("'bogus' isn't a valid value for '--invalid <MODE>'");
+}
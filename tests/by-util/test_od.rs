@@ -230,6 +230,33 @@ fn test_hex32() {
         .stdout_is(expected_output);
 }
 
+#[test]
+fn test_128_bits() {
+    let mut input = [0xff; 32];
+    input[16..].copy_from_slice(&[0x01, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]);
+
+    let expected_output = unindent(
+        "
+        0000000         ffffffffffffffffffffffffffffffff
+                                                      -1
+                    ffffffffffffffff    ffffffffffffffff
+        0000020         00000000000000000000000000000001
+                                                       1
+                    0000000000000001    0000000000000000
+        0000040
+        ",
+    );
+    new_ucmd!()
+        .arg("--endian=little")
+        .arg("-tx16")
+        .arg("-td16")
+        .arg("-tx8")
+        .run_piped_stdin(&input[..])
+        .success()
+        .no_stderr()
+        .stdout_is(expected_output);
+}
+
 #[test]
 fn test_f16() {
     let input: [u8; 14] = [
@@ -467,6 +494,28 @@ fn test_big_endian() {
         .stdout_is(expected_output);
 }
 
+#[test]
+fn test_big_endian_f16_and_integers() {
+    let input: [u8; 4] = [0x3c, 0x00, 0xff, 0xfe]; // 0x3C00 1.0, 0xFFFE -2
+
+    let expected_output = unindent(
+        "
+        0000000     1.000       NaN
+                    15360        -2
+        0000004
+        ",
+    );
+
+    new_ucmd!()
+        .arg("--endian=big")
+        .arg("-tf2")
+        .arg("-td2")
+        .run_piped_stdin(&input[..])
+        .no_stderr()
+        .success()
+        .stdout_is(expected_output);
+}
+
 #[test]
 #[allow(non_snake_case)]
 fn test_alignment_Xxa() {
@@ -638,6 +687,45 @@ fn test_skip_bytes_error() {
         .failure();
 }
 
+#[test]
+fn test_skip_bytes_across_files() {
+    let (at, mut ucmd) = at_and_ucmd!();
+    at.write("a", "abc");
+    at.write("b", "defgh");
+    ucmd.args(&["-c", "-j", "4", "a", "b"])
+        .succeeds()
+        .no_stderr()
+        .stdout_is(unindent(
+            "
+            0000004   e   f   g   h
+            0000010
+            ",
+        ));
+}
+
+#[test]
+fn test_skip_bytes_seeks_in_large_file() {
+    let (at, mut ucmd) = at_and_ucmd!();
+    let file = at.make_file("sparse");
+    file.set_len(1 << 40).unwrap();
+    ucmd.args(&["-c", "-j", "1099511627774", "sparse"])
+        .succeeds()
+        .no_stderr()
+        .stdout_is(unindent(
+            "
+            17777777777776  \\0  \\0
+            20000000000000
+            ",
+        ));
+}
+
+#[test]
+fn test_skip_bytes_past_end_of_files() {
+    let (at, mut ucmd) = at_and_ucmd!();
+    at.write("a", "abc");
+    ucmd.args(&["-j", "4", "a"]).fails();
+}
+
 #[test]
 fn test_read_bytes() {
     let input = "abcdefghijklmnopqrstuvwxyz\n12345678"; // spell-checker:disable-line
@@ -841,12 +929,7 @@ fn test_od_invalid_bytes() {
     // According to the man page it should be valid in the same way it is valid for
     // '--read-bytes' and '--skip-bytes'.
 
-    let options = [
-        "--read-bytes",
-        "--skip-bytes",
-        "--width",
-        // "--strings", // TODO: consider testing here once '--strings' is implemented
-    ];
+    let options = ["--read-bytes", "--skip-bytes", "--width", "--strings"];
     for option in &options {
         new_ucmd!()
             .arg(format!("{option}={INVALID_SIZE}"))
@@ -873,3 +956,245 @@ fn test_od_invalid_bytes() {
             .stderr_only(format!("od: {option} argument '{BIG_SIZE}' too large\n"));
     }
 }
+
+#[test]
+fn test_strings() {
+    new_ucmd!()
+        .arg("-S")
+        .arg("3")
+        .pipe_in(&b"hello\0ab\0\x01world!\0tail"[..])
+        .succeeds()
+        .stdout_only("0000000 hello\n0000012 world!\n");
+}
+
+#[test]
+fn test_strings_default_length() {
+    new_ucmd!()
+        .arg("--strings")
+        .pipe_in(&b"abc\0de\0fghi\n\0"[..])
+        .succeeds()
+        .stdout_only("0000000 abc\n");
+}
+
+#[test]
+fn test_strings_skip_and_read_bytes() {
+    new_ucmd!()
+        .args(&["-S", "2", "-j", "2", "-N", "9", "-A", "x"])
+        .pipe_in(&b"abcd\0efgh\0ij\0"[..])
+        .succeeds()
+        .stdout_only("000002 cd\n000005 efgh\n");
+
+    new_ucmd!()
+        .args(&["-S", "2", "-A", "n"])
+        .pipe_in(&b"ab\0cd\0"[..])
+        .succeeds()
+        .stdout_only("ab\ncd\n");
+}
+
+#[test]
+fn test_strings_cut_off_by_read_bytes() {
+    new_ucmd!()
+        .args(&["-S", "3", "-N", "6"])
+        .pipe_in(&b"abcdefgh\0"[..])
+        .succeeds()
+        .stdout_only("0000000 abcdef\n");
+
+    new_ucmd!()
+        .args(&["-S", "3", "-j", "3", "-N", "7"])
+        .pipe_in(&b"xx\0abcdefgh\0"[..])
+        .succeeds()
+        .stdout_only("0000003 abcdefg\n");
+
+    // a string of just the minimum length is not printed
+    new_ucmd!()
+        .args(&["-S", "3", "-N", "3"])
+        .pipe_in(&b"abcdefgh\0"[..])
+        .succeeds()
+        .no_stdout();
+}
+
+#[test]
+fn test_color() {
+    new_ucmd!()
+        .args(&["-An", "-tx1", "--color=always"])
+        .pipe_in(&b"a\0\n\xff"[..])
+        .succeeds()
+        .stdout_only(" \x1b[36m61\x1b[0m \x1b[90m00\x1b[0m \x1b[32m0a\x1b[0m \x1b[33mff\x1b[0m\n");
+
+    new_ucmd!()
+        .args(&["-tx2", "--color"])
+        .pipe_in(&b"a\0"[..])
+        .succeeds()
+        .stdout_only("\x1b[34m0000000\x1b[0m \x1b[32m0061\x1b[0m\n\x1b[34m0000002\x1b[0m\n");
+}
+
+#[test]
+fn test_color_never_and_auto() {
+    for arg in ["--color=never", "--color=auto"] {
+        new_ucmd!()
+            .args(&["-An", "-tx1", arg])
+            .pipe_in(&b"a\0"[..])
+            .succeeds()
+            .stdout_only(" 61 00\n");
+    }
+}
+
+#[test]
+fn test_unicode_code_points() {
+    new_ucmd!()
+        .arg("-tU")
+        .pipe_in("aé\n💖")
+        .succeeds()
+        .stdout_only(unindent(
+            "
+            0000000        a   U+00E9       **       \\n  U+1F496       **       **       **
+            0000010
+            ",
+        ));
+
+    // a character can span two output lines
+    new_ucmd!()
+        .args(&["-An", "-w2", "-tUx1"])
+        .pipe_in("aé")
+        .succeeds()
+        .stdout_only("        a   U+00E9\n       61       c3\n       **\n       a9\n");
+}
+
+#[test]
+fn test_duplicate_suppression_across_batches() {
+    // Only regular files are read in batches.
+    let scene = TestScenario::new(util_name!());
+    let mut input = vec![0; 16 * 5000];
+    input.push(b'a');
+    scene.fixtures.write_bytes("input", &input);
+    scene.ucmd().arg("input").succeeds().stdout_only(unindent(
+        "
+            0000000 000000 000000 000000 000000 000000 000000 000000 000000
+            *
+            0234200 000141
+            0234201
+            ",
+    ));
+
+    let output = scene
+        .ucmd()
+        .args(&["-v", "input"])
+        .succeeds()
+        .stdout_move_str();
+    let lines: Vec<&str> = output.lines().collect();
+    assert_eq!(lines.len(), 5002);
+    assert_eq!(
+        lines[4096],
+        "0200000 000000 000000 000000 000000 000000 000000 000000 000000"
+    );
+}
+
+#[test]
+fn test_width_auto() {
+    let input: Vec<u8> = (0..24).collect();
+    new_ucmd!()
+        .env("COLUMNS", "80")
+        .arg("--width=auto")
+        .arg("-tx1")
+        .run_piped_stdin(input.clone())
+        .success()
+        .stdout_is(unindent(
+            "
+            0000000 00 01 02 03 04 05 06 07 08 09 0a 0b 0c 0d 0e 0f 10 11 12 13 14 15 16 17
+            0000030
+            ",
+        ));
+    new_ucmd!()
+        .env("COLUMNS", "40")
+        .arg("--width=auto")
+        .arg("-An")
+        .arg("-tx1z")
+        .run_piped_stdin(input)
+        .success()
+        .stdout_is(concat!(
+            " 00 01 02 03 04 05 06 07 08  >.........<\n",
+            " 09 0a 0b 0c 0d 0e 0f 10 11  >.........<\n",
+            " 12 13 14 15 16 17           >......<\n",
+        ));
+}
+
+#[test]
+fn test_width_auto_keeps_blocks_whole() {
+    new_ucmd!()
+        .env("COLUMNS", "30")
+        .arg("-w")
+        .arg("auto")
+        .arg("-tx8")
+        .run_piped_stdin(vec![0u8; 16])
+        .success()
+        .stdout_is(unindent(
+            "
+            0000000 0000000000000000
+            *
+            0000020
+            ",
+        ));
+}
+
+#[test]
+fn test_diffable() {
+    let input = [0u8; 48];
+    new_ucmd!()
+        .arg("--diffable")
+        .arg("-tx8")
+        .arg("-Ax")
+        .run_piped_stdin(&input[..])
+        .success()
+        .stdout_is(concat!(
+            " 0000000000000000 0000000000000000\n",
+            " 0000000000000000 0000000000000000\n",
+            " 0000000000000000 0000000000000000\n",
+        ));
+}
+
+#[test]
+fn test_diffable_items() {
+    new_ucmd!()
+        .arg("--diffable=items")
+        .arg("-tx2")
+        .arg("--endian=big")
+        .run_piped_stdin(&b"\x00\x01\x00\x01\x02\x03"[..])
+        .success()
+        .stdout_is(" 0001\n 0001\n 0203\n");
+    new_ucmd!()
+        .arg("--diffable=items")
+        .arg("-tx1")
+        .arg("-tc")
+        .run_piped_stdin(&b"ab"[..])
+        .success()
+        .stdout_is("  61\n   a\n  62\n   b\n");
+    new_ucmd!()
+        .arg("--diffable=lines")
+        .arg("-tx1")
+        .arg("-w2")
+        .run_piped_stdin(&b"abc"[..])
+        .success()
+        .stdout_is(" 61 62\n 63\n");
+}
+
+#[test]
+fn test_output_not_held_back_while_reading() {
+    let mut child = new_ucmd!()
+        .arg("-c")
+        .set_stdin(std::process::Stdio::piped())
+        .set_stdout(std::process::Stdio::piped())
+        .run_no_wait();
+    // The first line must come out while stdin is still open.
+    child.write_in("a".repeat(20));
+    assert_eq!(
+        child.stdout_exact_bytes(72),
+        format!("0000000{}\n", "   a".repeat(16)).as_bytes()
+    );
+    child.close_stdin();
+    child.wait().unwrap().success().stdout_only(unindent(
+        "
+        0000020   a   a   a   a
+        0000024
+        ",
+    ));
+}
@@ -126,6 +126,54 @@ static EXAMPLE_DATA: &[TestData] = &[
         ins: &["1 \na \n", "2\t\nb\t\n"],
         out: "1 |2\t\na |b\t\n",
     },
+    TestData {
+        name: "delimiter-list",
+        args: &["-d", ",;"],
+        ins: &["1\n", "2\n", "3\n", "4\n"],
+        out: "1,2;3,4\n",
+    },
+    TestData {
+        name: "delimiter-list-serial",
+        args: &["-s", "-d", ",;"],
+        ins: &["1\n2\n3\n4\n", "5\n6\n"],
+        out: "1,2;3,4\n5,6\n",
+    },
+    TestData {
+        name: "delimiter-escapes",
+        args: &["-d", "\\n\\t\\\\\\x"],
+        ins: &["1\n", "2\n", "3\n", "4\n", "5\n"],
+        out: "1\n2\t3\\4x5\n",
+    },
+    TestData {
+        name: "empty-delimiter",
+        args: &["-d", ",\\0"],
+        ins: &["1\n", "2\n", "3\n", "4\n"],
+        out: "1,23,4\n",
+    },
+    TestData {
+        name: "empty-delimiter-list",
+        args: &["-d", ""],
+        ins: &["1\na\n", "2\nb\n"],
+        out: "12\nab\n",
+    },
+    TestData {
+        name: "finished-files",
+        args: &["-d", ",;"],
+        ins: &["1\n2\n", "", "a\n"],
+        out: "1,;a\n2,;\n",
+    },
+    TestData {
+        name: "empty-file-serial",
+        args: &["-s"],
+        ins: &["", "1\n2\n"],
+        out: "\n1\t2\n",
+    },
+    TestData {
+        name: "zno-delimiter-list-serial",
+        args: &["-zs", "-d", ",\\n"],
+        ins: &["1\x002\x003\x00"],
+        out: "1,2\n3\x00",
+    },
 ];
 
 #[test]
@@ -173,3 +221,40 @@ fn test_data() {
             .stdout_is(example.out);
     }
 }
+
+#[test]
+fn test_delimiter_list_ending_with_backslash() {
+    new_ucmd!()
+        .args(&["-d", "a\\", "-"])
+        .fails()
+        .code_is(1)
+        .stderr_only("paste: delimiter list ends with an unescaped backslash: a\\\n");
+}
+
+#[test]
+fn test_nonexistent_file() {
+    new_ucmd!()
+        .args(&["nonexistent", "-"])
+        .fails()
+        .code_is(1)
+        .stderr_only("paste: nonexistent: No such file or directory\n");
+
+    // Serially, the other files are still pasted.
+    new_ucmd!()
+        .args(&["-s", "nonexistent", "-"])
+        .pipe_in("1\n2\n")
+        .fails()
+        .code_is(1)
+        .stdout_is("1\t2\n")
+        .stderr_is("paste: nonexistent: No such file or directory\n");
+}
+
+#[test]
+fn test_non_utf8_input() {
+    let (at, mut ucmd) = at_and_ucmd!();
+    at.write_bytes("a", b"1\xff\n2\n");
+    at.write_bytes("b", b"\xfe\n");
+    ucmd.args(&["a", "b"])
+        .succeeds()
+        .stdout_is_bytes(b"1\xff\t\xfe\n2\t\n");
+}
@@ -292,7 +292,7 @@ fn sub_num_dec_trunc() {
         .stdout_only("pi is ~ 3.14159");
 }
 
-#[cfg_attr(not(feature = "test_unimplemented"), ignore)]
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
 #[test]
 fn sub_num_hex_float_lower() {
     new_ucmd!()
@@ -301,7 +301,7 @@ fn sub_num_hex_float_lower() {
         .stdout_only("0xep-4");
 }
 
-#[cfg_attr(not(feature = "test_unimplemented"), ignore)]
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
 #[test]
 fn sub_num_hex_float_upper() {
     new_ucmd!()
@@ -530,3 +530,197 @@ fn sub_general_round_float_leading_zeroes() {
         .succeeds()
         .stdout_only("1.00001");
 }
+
+#[test]
+fn escaped_unicode() {
+    new_ucmd!()
+        .args(&["\\u00e9\\U0001F600\\u0024"])
+        .succeeds()
+        .stdout_only("é😀$");
+}
+
+#[test]
+fn escaped_unicode_invalid() {
+    new_ucmd!()
+        .args(&["\\u0061"])
+        .fails()
+        .code_is(1)
+        .stderr_only("printf: invalid universal character name \\u0061\n");
+}
+
+#[test]
+fn escaped_hex_missing() {
+    new_ucmd!()
+        .args(&["a\\xz"])
+        .fails()
+        .code_is(1)
+        .stdout_is("")
+        .stderr_is("printf: missing hexadecimal number in escape\n");
+}
+
+#[test]
+fn sub_b_string_octal_and_stop() {
+    new_ucmd!()
+        .args(&["%b-%s", "\\0101\\101\\c ignored", "B"])
+        .succeeds()
+        .stdout_only("AA");
+}
+
+#[test]
+fn sub_q_string() {
+    new_ucmd!()
+        .args(&["%q %q %q", "simple", "it's here", "a$b"])
+        .succeeds()
+        .stdout_only("simple \"it's here\" 'a$b'");
+}
+
+#[test]
+fn sub_q_empty_string() {
+    new_ucmd!()
+        .args(&["%q|%q\n", ""])
+        .succeeds()
+        .stdout_only("''|''\n");
+}
+
+#[cfg(unix)]
+#[test]
+fn sub_q_invalid_utf8() {
+    use std::ffi::OsStr;
+    use std::os::unix::ffi::OsStrExt;
+
+    new_ucmd!()
+        .arg("%q %q\n")
+        .arg(OsStr::from_bytes(b"a\xffb"))
+        .arg(OsStr::from_bytes(b"\xe9 x"))
+        .succeeds()
+        .stdout_only("'a'$'\\377''b' ''$'\\351'' x'\n");
+}
+
+#[cfg(unix)]
+#[test]
+fn sub_s_invalid_utf8() {
+    use std::ffi::OsStr;
+    use std::os::unix::ffi::OsStrExt;
+
+    new_ucmd!()
+        .arg("%s\n")
+        .arg(OsStr::from_bytes(b"a\xffb"))
+        .succeeds()
+        .stdout_only_bytes(b"a\xffb\n");
+}
+
+#[test]
+fn sub_width_and_precision_from_arguments() {
+    new_ucmd!()
+        .args(&[
+            "%*d|%-*s|%.*f|%*s|",
+            "4",
+            "1",
+            "3",
+            "a",
+            "2",
+            "1.25",
+            "-3",
+            "b",
+        ])
+        .succeeds()
+        .stdout_only("   1|a  |1.25|b  |");
+}
+
+#[test]
+fn sub_width_from_argument_out_of_range() {
+    new_ucmd!()
+        .args(&["%*d", "99999999999", "1"])
+        .fails()
+        .code_is(1)
+        .stderr_only("printf: invalid field width: '99999999999'\n");
+}
+
+#[test]
+fn sub_num_invalid() {
+    new_ucmd!()
+        .args(&["%d %d %d|", "abc", "12x", "99999999999999999999"])
+        .fails()
+        .code_is(1)
+        .stdout_is("0 12 9223372036854775807|")
+        .stderr_is(
+            "printf: 'abc': expected a numeric value\n\
+             printf: '12x': value not completely converted\n\
+             printf: '99999999999999999999': Numerical result out of range\n",
+        );
+}
+
+#[test]
+fn sub_num_invalid_float() {
+    new_ucmd!()
+        .args(&["%.1f", "1.5x"])
+        .fails()
+        .code_is(1)
+        .stdout_is("1.5")
+        .stderr_is("printf: '1.5x': value not completely converted\n");
+}
+
+#[test]
+fn sub_num_bases_and_character_constants() {
+    new_ucmd!()
+        .args(&["%d %d %d %x", "0x1F", "010", "'A", "-1"])
+        .succeeds()
+        .stdout_only("31 8 65 ffffffffffffffff");
+}
+
+#[test]
+fn sub_num_character_constant_trailing() {
+    new_ucmd!()
+        .args(&["%d", "'AB"])
+        .succeeds()
+        .stdout_is("65")
+        .stderr_is(
+            "printf: warning: B: character(s) following character constant have been ignored\n",
+        );
+    new_ucmd!()
+        .env("POSIXLY_CORRECT", "1")
+        .args(&["%d", "'AB"])
+        .succeeds()
+        .stdout_only("65");
+}
+
+#[test]
+fn sub_invalid_specification() {
+    new_ucmd!()
+        .args(&["a%yb", "1"])
+        .fails()
+        .code_is(1)
+        .stdout_is("a")
+        .stderr_is("printf: %y: invalid conversion specification\n");
+    new_ucmd!()
+        .args(&["%#s", "1"])
+        .fails()
+        .stderr_is("printf: %#s: invalid conversion specification\n");
+}
+
+#[test]
+fn excess_arguments_without_specifications() {
+    new_ucmd!()
+        .args(&["hello\\n", "world"])
+        .succeeds()
+        .stdout_is("hello\n")
+        .stderr_is("printf: warning: ignoring excess arguments, starting with 'world'\n");
+}
+
+#[test]
+fn format_reused_for_remaining_arguments() {
+    new_ucmd!()
+        .args(&["%s=%d\\n", "a", "1", "b"])
+        .succeeds()
+        .stdout_only("a=1\nb=0\n");
+}
+
+#[cfg(unix)]
+#[test]
+fn sub_time() {
+    new_ucmd!()
+        .env("TZ", "UTC")
+        .args(&["%(%Y-%m-%d %H:%M:%S)T|%12(%H)T|%()T", "86400", "3600", "0"])
+        .succeeds()
+        .stdout_only("1970-01-02 00:00:00|          01|00:00:00");
+}
@@ -349,6 +349,213 @@ fn test_rm_interactive_never() {
     assert!(!at.file_exists(file_2));
 }
 
+#[test]
+fn test_rm_interactive_without_when_prompts_always() {
+    let (at, mut ucmd) = at_and_ucmd!();
+    at.touch("a");
+    at.touch("b");
+
+    ucmd.args(&["--interactive", "a", "b"])
+        .pipe_in("n\ny\n")
+        .succeeds()
+        .stderr_is("rm: remove regular empty file 'a'? rm: remove regular empty file 'b'? ");
+
+    assert!(at.file_exists("a"));
+    assert!(!at.file_exists("b"));
+}
+
+#[test]
+fn test_rm_interactive_when_synonyms() {
+    let scene = TestScenario::new(util_name!());
+    let at = &scene.fixtures;
+    at.touch("a");
+
+    scene
+        .ucmd()
+        .args(&["--interactive=yes", "a"])
+        .pipe_in("n\n")
+        .succeeds()
+        .stderr_is("rm: remove regular empty file 'a'? ");
+    assert!(at.file_exists("a"));
+
+    scene
+        .ucmd()
+        .args(&["--interactive=none", "a"])
+        .succeeds()
+        .no_stderr();
+    assert!(!at.file_exists("a"));
+}
+
+#[test]
+fn test_rm_interactive_invalid_when() {
+    new_ucmd!()
+        .args(&["--interactive=sometimes", "a"])
+        .fails()
+        .code_is(1)
+        .stderr_contains("invalid argument 'sometimes' for '--interactive'")
+        .stderr_contains("- 'always', 'yes'");
+}
+
+#[test]
+fn test_rm_prompt_once() {
+    let scene = TestScenario::new(util_name!());
+    let at = &scene.fixtures;
+    for file in ["a", "b", "c", "d"] {
+        at.touch(file);
+    }
+
+    scene
+        .ucmd()
+        .args(&["-I", "a", "b", "c", "d"])
+        .pipe_in("n\n")
+        .succeeds()
+        .stderr_is("rm: remove 4 arguments? ");
+    assert!(at.file_exists("a"));
+
+    scene
+        .ucmd()
+        .args(&["--interactive=once", "a", "b", "c", "d"])
+        .pipe_in("y\n")
+        .succeeds()
+        .stderr_is("rm: remove 4 arguments? ");
+    assert!(!at.file_exists("a"));
+    assert!(!at.file_exists("d"));
+}
+
+#[test]
+fn test_rm_prompt_once_recursive() {
+    let (at, mut ucmd) = at_and_ucmd!();
+    at.mkdir("dir");
+    at.touch("dir/file");
+
+    ucmd.args(&["-I", "-r", "dir"])
+        .pipe_in("y\n")
+        .succeeds()
+        .stderr_is("rm: remove 1 argument recursively? ");
+    assert!(!at.dir_exists("dir"));
+}
+
+#[test]
+fn test_rm_prompt_once_few_files() {
+    let (at, mut ucmd) = at_and_ucmd!();
+    at.touch("a");
+    at.touch("b");
+
+    ucmd.args(&["-I", "a", "b"]).succeeds().no_stderr();
+    assert!(!at.file_exists("a"));
+    assert!(!at.file_exists("b"));
+}
+
+#[test]
+fn test_rm_recursive_does_not_follow_symlinks() {
+    let (at, mut ucmd) = at_and_ucmd!();
+    at.mkdir_all("tree/sub");
+    at.mkdir("outside");
+    at.touch("outside/file");
+    at.relative_symlink_dir("../../outside", "tree/sub/link");
+
+    ucmd.args(&["-r", "tree"]).succeeds().no_stderr();
+
+    assert!(!at.dir_exists("tree"));
+    assert!(at.file_exists("outside/file"));
+}
+
+#[test]
+fn test_rm_recursive_deep_tree() {
+    let (at, mut ucmd) = at_and_ucmd!();
+    let deepest = format!("tree{}", "/d".repeat(100));
+    at.mkdir_all(&deepest);
+    at.touch(&format!("{deepest}/file"));
+
+    ucmd.args(&["-r", "tree"]).succeeds().no_stderr();
+
+    assert!(!at.dir_exists("tree"));
+}
+
+#[test]
+fn test_rm_recursive_jobs() {
+    let scene = TestScenario::new(util_name!());
+    let at = &scene.fixtures;
+    for jobs in ["4", "0"] {
+        for i in 0..20 {
+            at.mkdir_all(&format!("tree/{i}/a/b"));
+            at.touch(&format!("tree/{i}/file"));
+            at.touch(&format!("tree/{i}/a/b/file"));
+        }
+
+        scene
+            .ucmd()
+            .args(&["-r", "--jobs", jobs, "tree"])
+            .succeeds()
+            .no_stderr();
+
+        assert!(!at.dir_exists("tree"));
+    }
+}
+
+#[test]
+#[cfg(all(unix, not(target_os = "macos")))]
+fn test_rm_trash() {
+    let scene = TestScenario::new(util_name!());
+    let at = &scene.fixtures;
+    at.write("file", "content");
+
+    scene
+        .ucmd()
+        .env("XDG_DATA_HOME", at.plus("data"))
+        .args(&["--trash", "file"])
+        .succeeds()
+        .no_output();
+
+    assert!(!at.file_exists("file"));
+    assert_eq!(at.read("data/Trash/files/file"), "content");
+    let info = at.read("data/Trash/info/file.trashinfo");
+    let path = std::fs::canonicalize(at.plus("."))
+        .unwrap()
+        .join("file")
+        .to_str()
+        .unwrap()
+        .to_string();
+    assert!(info.starts_with(&format!("[Trash Info]\nPath={path}\nDeletionDate=")));
+
+    // A file with the same name is trashed under another name.
+    at.write("file", "other content");
+    scene
+        .ucmd()
+        .env("XDG_DATA_HOME", at.plus("data"))
+        .args(&["--trash", "file"])
+        .succeeds();
+    assert_eq!(at.read("data/Trash/files/file"), "content");
+    assert_eq!(at.read("data/Trash/files/file.1"), "other content");
+    assert!(at.file_exists("data/Trash/info/file.1.trashinfo"));
+}
+
+#[test]
+#[cfg(all(unix, not(target_os = "macos")))]
+fn test_rm_trash_directory() {
+    let scene = TestScenario::new(util_name!());
+    let at = &scene.fixtures;
+    at.mkdir("dir");
+    at.touch("dir/file");
+
+    scene
+        .ucmd()
+        .env("XDG_DATA_HOME", at.plus("data"))
+        .args(&["--trash", "dir"])
+        .fails()
+        .stderr_is("rm: cannot remove 'dir': Is a directory\n");
+    assert!(at.file_exists("dir/file"));
+
+    scene
+        .ucmd()
+        .env("XDG_DATA_HOME", at.plus("data"))
+        .args(&["--trash", "-r", "dir"])
+        .succeeds()
+        .no_output();
+    assert!(!at.dir_exists("dir"));
+    assert!(at.file_exists("data/Trash/files/dir/file"));
+}
+
 #[test]
 fn test_rm_descend_directory() {
     // This test descends into each directory and deletes the files and folders inside of them
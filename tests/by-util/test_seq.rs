@@ -230,6 +230,58 @@ fn test_zero_step() {
     new_ucmd!().args(&["10", "0", "32"]).fails();
 }
 
+#[test]
+fn test_i64_boundary() {
+    new_ucmd!()
+        .args(&["9223372036854775805", "2", "9223372036854775811"])
+        .succeeds()
+        .stdout_is(
+            "9223372036854775805\n9223372036854775807\n9223372036854775809\n9223372036854775811\n",
+        );
+    new_ucmd!()
+        .args(&["-w", "-9223372036854775807", "-1", "-9223372036854775809"])
+        .succeeds()
+        .stdout_is("-9223372036854775807\n-9223372036854775808\n-9223372036854775809\n");
+    new_ucmd!()
+        .args(&[
+            "9223372036854775806",
+            "9223372036854775807",
+            "9223372036854775807",
+        ])
+        .succeeds()
+        .stdout_is("9223372036854775806\n");
+}
+
+#[test]
+fn test_exact_decimal_steps() {
+    let expected: String = (1..=100)
+        .map(|i| format!("{}.{}\n", i / 10, i % 10))
+        .collect();
+    new_ucmd!()
+        .args(&["0.1", "0.1", "10"])
+        .succeeds()
+        .stdout_only(expected);
+}
+
+#[test]
+fn test_many_numbers() {
+    let expected: String = (7..=100_000).map(|i| format!("{i}, ")).collect();
+    new_ucmd!()
+        .args(&["-s", ", ", "-t", "\n", "7", "100000"])
+        .succeeds()
+        .stdout_only(format!("{}\n", expected.strip_suffix(", ").unwrap()));
+}
+
+#[test]
+fn test_big_numbers_carry() {
+    new_ucmd!()
+        .args(&["99999999999999999999998", "100000000000000000000001"])
+        .succeeds()
+        .stdout_only(
+            "99999999999999999999998\n99999999999999999999999\n100000000000000000000000\n100000000000000000000001\n",
+        );
+}
+
 #[test]
 fn test_big_numbers() {
     new_ucmd!()
@@ -619,6 +671,15 @@ fn test_inf() {
     run(&["inf"], b"1\n2\n3\n");
 }
 
+#[test]
+fn test_inf_carry() {
+    run(&["-s", ",", "98", "inf"], b"98,99,100,101,");
+    run(
+        &["99999999999999999999", "inf"],
+        b"99999999999999999999\n100000000000000000000\n",
+    );
+}
+
 #[test]
 fn test_inf_width() {
     run(
@@ -739,6 +800,44 @@ fn test_format_option() {
         .stdout_only("0.00\n0.10\n0.20\n0.30\n0.40\n0.50\n");
 }
 
+#[test]
+fn test_format_option_text_and_flags() {
+    new_ucmd!()
+        .args(&["-f", "%%%-6.1e|%%", "1", "2"])
+        .succeeds()
+        .stdout_only("%1.0e+00|%\n%2.0e+00|%\n");
+    new_ucmd!()
+        .args(&["-f", "%'08.3Lf", "-1", "0"])
+        .succeeds()
+        .stdout_only("-001.000\n0000.000\n");
+    new_ucmd!()
+        .args(&["-f", "%g", "-0", "1"])
+        .succeeds()
+        .stdout_only("-0\n1\n");
+}
+
+#[test]
+fn test_invalid_format() {
+    for (format, message) in [
+        ("x", "format 'x' has no % directive"),
+        ("x%%", "format 'x%%' has no % directive"),
+        ("x%", "format 'x%' ends in %"),
+        ("%5.", "format '%5.' ends in %"),
+        ("%d", "format '%d' has unknown %d directive"),
+        ("%5%", "format '%5%' has unknown %% directive"),
+        ("%.*f", "format '%.*f' has unknown %* directive"),
+        ("%f%g", "format '%f%g' has too many % directives"),
+        ("%f %", "format '%f %' has too many % directives"),
+    ] {
+        new_ucmd!()
+            .args(&["-f", format, "1"])
+            .fails()
+            .code_is(1)
+            .no_stdout()
+            .stderr_only(format!("seq: {message}\n"));
+    }
+}
+
 #[test]
 fn test_invalid_zero_increment_value() {
     new_ucmd!()
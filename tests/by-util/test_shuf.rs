@@ -217,3 +217,89 @@ fn test_shuf_multiple_input_line_count() {
         .count();
     assert_eq!(result_count, 5, "Output should have 5 items");
 }
+
+fn random_source_bytes() -> Vec<u8> {
+    (0..1000u32).map(|i| ((i * 113 + 7) % 256) as u8).collect()
+}
+
+#[test]
+fn test_random_source_is_reproducible() {
+    // The expected outputs are those of GNU shuf with the same random bytes.
+    let (at, mut ucmd) = at_and_ucmd!();
+    at.write_bytes("random", &random_source_bytes());
+    ucmd.args(&["--random-source=random", "-e"])
+        .args(&["1", "2", "3", "4", "5", "6", "7", "8", "9", "10"])
+        .succeeds()
+        .stdout_only("8\n2\n3\n5\n7\n9\n6\n10\n1\n4\n");
+
+    let (at, mut ucmd) = at_and_ucmd!();
+    at.write_bytes("random", &random_source_bytes());
+    at.write(
+        "input",
+        &(1..=20).map(|i| format!("{i}\n")).collect::<String>(),
+    );
+    ucmd.args(&["--random-source=random", "-n", "3", "input"])
+        .succeeds()
+        .stdout_only("8\n1\n9\n");
+
+    let (at, mut ucmd) = at_and_ucmd!();
+    at.write_bytes("random", &random_source_bytes());
+    ucmd.args(&["--random-source=random", "-r", "-n", "5", "-i", "1-1000"])
+        .succeeds()
+        .stdout_only("913\n490\n91\n204\n534\n");
+
+    let (at, mut ucmd) = at_and_ucmd!();
+    at.write_bytes("random", &random_source_bytes());
+    ucmd.args(&["--random-source=random", "-n", "3", "-i", "1-1000000"])
+        .succeeds()
+        .stdout_only("489706\n23245\n976487\n");
+}
+
+#[test]
+fn test_head_count_from_pipe() {
+    // Lines from a pipe go through reservoir sampling, which consumes
+    // random bytes differently.
+    let (at, mut ucmd) = at_and_ucmd!();
+    at.write_bytes("random", &random_source_bytes());
+    ucmd.args(&["--random-source=random", "-n", "3"])
+        .pipe_in((1..=20).map(|i| format!("{i}\n")).collect::<String>())
+        .succeeds()
+        .stdout_only("18\n19\n20\n");
+
+    // Lines are added a separator, and fewer lines than requested are all output.
+    let result = new_ucmd!().args(&["-n", "5"]).pipe_in("a\nb").succeeds();
+    let mut lines = result.stdout_str().lines().collect::<Vec<_>>();
+    lines.sort_unstable();
+    assert_eq!(lines, ["a", "b"]);
+}
+
+#[test]
+fn test_random_source_errors() {
+    let (at, mut ucmd) = at_and_ucmd!();
+    at.touch("empty");
+    ucmd.args(&["--random-source=empty", "-e", "a", "b"])
+        .fails()
+        .code_is(1)
+        .stderr_only("shuf: 'empty': end of file\n");
+
+    new_ucmd!()
+        .args(&["--random-source=nonexistent", "-e", "a", "b"])
+        .fails()
+        .code_is(1)
+        .stderr_only("shuf: nonexistent: No such file or directory\n");
+}
+
+#[test]
+fn test_echo_lines_are_not_split() {
+    let result = new_ucmd!().args(&["-e", "a\nb", "c"]).succeeds();
+    assert!(["a\nb\nc\n", "c\na\nb\n"].contains(&result.stdout_str()));
+}
+
+#[test]
+fn test_repeat_no_lines() {
+    new_ucmd!()
+        .args(&["-r", "-e"])
+        .fails()
+        .code_is(1)
+        .stderr_only("shuf: no lines to repeat\n");
+}
@@ -161,3 +161,53 @@ fn test_negative_interval() {
         .fails()
         .usage_error("invalid time interval '-1'");
 }
+
+#[test]
+fn test_sleep_fractional_suffixes() {
+    let before_test = Instant::now();
+
+    new_ucmd!()
+        .args(&["0.001m", "1e-5h", "1e-6d"])
+        .succeeds()
+        .stdout_only("");
+
+    let duration = before_test.elapsed();
+    assert!(duration >= Duration::from_nanos(60_000_000 + 36_000_000 + 86_400_000));
+}
+
+#[test]
+fn test_sleep_sum_of_exact_fractions() {
+    let before_test = Instant::now();
+
+    new_ucmd!()
+        .args(&["0.1", "0.1", "0.1"])
+        .succeeds()
+        .stdout_only("");
+
+    let duration = before_test.elapsed();
+    assert!(duration >= Duration::from_millis(300));
+}
+
+#[test]
+fn test_sleep_clock() {
+    for clock in ["monotonic", "realtime"] {
+        let before_test = Instant::now();
+
+        new_ucmd!()
+            .args(&["--clock", clock, "0.1"])
+            .succeeds()
+            .stdout_only("");
+
+        let duration = before_test.elapsed();
+        assert!(duration >= Duration::from_millis(100));
+    }
+}
+
+#[test]
+fn test_sleep_invalid_clock() {
+    new_ucmd!()
+        .args(&["--clock=boottime", "1"])
+        .fails()
+        .code_is(1)
+        .stderr_contains("'boottime' isn't a valid value for '--clock <CLOCK>'");
+}
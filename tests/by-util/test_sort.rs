@@ -124,6 +124,126 @@ fn test_ext_sort_zero_terminated() {
         .stdout_is_fixture("zero-terminated.expected");
 }
 
+#[test]
+fn test_ext_sort_temporary_directory() {
+    let ts = TestScenario::new(util_name!());
+    let at = &ts.fixtures;
+    let input: String = (1..=20000).rev().map(|n| format!("{n}\n")).collect();
+    let expected: String = (1..=20000).map(|n| format!("{n}\n")).collect();
+    at.write("input.txt", &input);
+    at.mkdir("tmp");
+    ts.ucmd()
+        .args(&["-n", "-S", "10K", "-T", "tmp", "input.txt"])
+        .succeeds()
+        .stdout_only(expected);
+    // the run files are removed once they are merged
+    assert!(std::fs::read_dir(at.plus("tmp")).unwrap().next().is_none());
+
+    ts.ucmd()
+        .args(&["-n", "-S", "10K", "-T", "nonexistent", "input.txt"])
+        .fails()
+        .code_is(2)
+        .stderr_only("sort: could not create temporary directory\n");
+}
+
+#[test]
+fn test_parallel() {
+    let input: String = (1..=20000).rev().map(|n| format!("{n}\n")).collect();
+    let expected: String = (1..=20000).map(|n| format!("{n}\n")).collect();
+    for threads in ["1", "2", "8"] {
+        new_ucmd!()
+            .arg("-n")
+            .arg(format!("--parallel={threads}"))
+            .pipe_in(input.as_str())
+            .succeeds()
+            .stdout_only(expected.as_str());
+        new_ucmd!()
+            .args(&["-n", "-S", "10K", "--parallel", threads])
+            .pipe_in(input.as_str())
+            .succeeds()
+            .stdout_only(expected.as_str());
+    }
+}
+
+#[test]
+fn test_invalid_parallel() {
+    new_ucmd!()
+        .arg("--parallel=abc")
+        .fails()
+        .code_is(2)
+        .stderr_contains("invalid --parallel argument 'abc'");
+    new_ucmd!()
+        .arg("--parallel=0")
+        .fails()
+        .code_is(2)
+        .stderr_contains("number in parallel must be nonzero");
+}
+
+#[test]
+fn test_debug_warnings() {
+    let cases: &[(&[&str], &str)] = &[
+        (&[], ""),
+        (
+            &["-k2,2"],
+            "sort: leading blanks are significant in key 1; consider also specifying 'b'\n",
+        ),
+        (&["-k2b,2n"], ""),
+        (
+            &["-k2n"],
+            "sort: key 1 is numeric and spans multiple fields\n",
+        ),
+        (
+            &["-k2,1"],
+            "sort: key 1 has zero width and will be ignored\n",
+        ),
+        (&["-t", ",", "-k2,2"], ""),
+        (&["-bf", "-k2,2n"], "sort: options '-bf' are ignored\n"),
+        (
+            &["-f", "-k2,2"],
+            "sort: leading blanks are significant in key 1; consider also specifying 'b'\n",
+        ),
+        (&["-s", "-r", "-k1,1bn"], "sort: option '-r' is ignored\n"),
+        (
+            &["-r", "-k1,1bn"],
+            "sort: option '-r' only applies to last-resort comparison\n",
+        ),
+    ];
+    for (args, warnings) in cases {
+        new_ucmd!()
+            .arg("--debug")
+            .args(args)
+            .pipe_in("a 1\n")
+            .succeeds()
+            .stderr_is(format!(
+                "sort: text ordering performed using simple byte comparison\n{warnings}"
+            ));
+    }
+}
+
+#[cfg(feature = "feat_collate")]
+#[test]
+fn test_collate() {
+    new_ucmd!()
+        .arg("--collate=C")
+        .arg("--debug")
+        .pipe_in("b\nB\n")
+        .succeeds()
+        .stdout_is("B\n_\nb\n_\n")
+        .stderr_is("sort: text ordering performed using simple byte comparison\n");
+    new_ucmd!()
+        .arg("--collate")
+        .arg("-f")
+        .env("LC_ALL", "C")
+        .pipe_in("b\nA\na\0c\na\0b\n")
+        .succeeds()
+        .stdout_is("A\na\0b\na\0c\nb\n");
+    new_ucmd!()
+        .arg("--collate=nonexistent")
+        .fails()
+        .code_is(2)
+        .stderr_only("sort: invalid locale 'nonexistent'\n");
+}
+
 #[test]
 fn test_months_whitespace() {
     test_helper("months-whitespace", &["-M", "--month-sort", "--sort=month"]);
@@ -239,6 +359,60 @@ fn test_random_shuffle_two_runs_not_the_same() {
     }
 }
 
+#[test]
+fn test_random_seed() {
+    const FILE: &str = "default_unsorted_ints.expected";
+    for arg in ["-R", "-k1,1R"] {
+        let result = new_ucmd!()
+            .args(&[arg, "--seed=1", FILE])
+            .succeeds()
+            .stdout_move_str();
+        new_ucmd!()
+            .args(&[arg, "--seed=1", FILE])
+            .succeeds()
+            .stdout_only(&result);
+        let other = new_ucmd!()
+            .args(&[arg, "--seed=2", FILE])
+            .succeeds()
+            .stdout_move_str();
+        assert_ne!(result, other);
+    }
+    // equal keys still compare equal
+    let result = new_ucmd!()
+        .args(&["-R", "--seed=1"])
+        .pipe_in("a\nb\na\nb\n")
+        .succeeds()
+        .stdout_move_str();
+    assert!(result == "a\na\nb\nb\n" || result == "b\nb\na\na\n");
+}
+
+#[test]
+fn test_random_source() {
+    let (at, _ucmd) = at_and_ucmd!();
+    at.write("random", "0123456789abcdef");
+    at.write("short", "0123");
+    let result = new_ucmd!()
+        .args(&["-R", "--random-source", &at.plus_as_string("random")])
+        .arg("default_unsorted_ints.expected")
+        .succeeds()
+        .stdout_move_str();
+    new_ucmd!()
+        .args(&["-R", "--random-source", &at.plus_as_string("random")])
+        .arg("default_unsorted_ints.expected")
+        .succeeds()
+        .stdout_only(result);
+    new_ucmd!()
+        .args(&["-R", "--random-source", &at.plus_as_string("short")])
+        .pipe_in("a\n")
+        .fails()
+        .code_is(2)
+        .stderr_contains("short: end of file");
+    new_ucmd!()
+        .args(&["-R", "--random-source=a", "--seed=b"])
+        .fails()
+        .code_is(2);
+}
+
 #[test]
 fn test_random_ignore_case() {
     let input = "ABC\nABc\nAbC\nAbc\naBC\naBc\nabC\nabc\n";
@@ -914,6 +1088,27 @@ fn test_compress_merge() {
         .stdout_only_fixture("merge_ints_interleaved.expected");
 }
 
+#[test]
+#[cfg(unix)]
+fn test_compress_program_compresses_run_files() {
+    let (at, mut ucmd) = at_and_ucmd!();
+    at.write(
+        "compress.sh",
+        "#!/bin/sh\necho \"run $*\" >> calls.log\nexec gzip \"$@\"\n",
+    );
+    at.set_mode("compress.sh", 0o755);
+    let input: String = (1..=1000).rev().map(|n| format!("{n}\n")).collect();
+    let expected: String = (1..=1000).map(|n| format!("{n}\n")).collect();
+    ucmd.args(&["-n", "--compress-program", "./compress.sh", "-S", "1K"])
+        .pipe_in(input)
+        .succeeds()
+        .stdout_only(expected);
+    // the run files were compressed, and decompressed again with -d
+    let calls = at.read("calls.log");
+    assert!(calls.lines().any(|line| line == "run "));
+    assert!(calls.lines().any(|line| line == "run -d"));
+}
+
 #[test]
 fn test_compress_fail() {
     #[cfg(not(windows))]
@@ -302,6 +302,28 @@ fn test_filter_command_fails() {
         .fails();
 }
 
+#[test]
+#[cfg(unix)]
+fn test_filter_exit_status() {
+    new_ucmd!()
+        .args(&["--filter=exit 3", "-l", "1"])
+        .pipe_in("a\nb\n")
+        .fails()
+        .code_is(3)
+        .stderr_only("split: with FILE=xaa, exit 3 from command: exit 3\n");
+}
+
+#[test]
+#[cfg(unix)]
+fn test_filter_not_reading_all_input() {
+    // the rest of a chunk is discarded when the command stops reading it
+    new_ucmd!()
+        .args(&["--filter=head -n 1", "-l", "50000"])
+        .pipe_in((1..=100_000).map(|i| format!("{i}\n")).collect::<String>())
+        .succeeds()
+        .stdout_only("1\n50001\n");
+}
+
 #[test]
 fn test_split_lines_number() {
     // Test if stdout/stderr for '--lines' option is correct
@@ -365,11 +387,13 @@ fn test_split_chunks_num_chunks_oversized_32() {
 
 #[test]
 fn test_split_stdin_num_chunks() {
-    new_ucmd!()
-        .args(&["--number=1"])
-        .fails()
-        .code_is(1)
-        .stderr_only("split: -: cannot determine file size\n");
+    // the size of stdin is known once it has been read
+    let (at, mut ucmd) = at_and_ucmd!();
+    ucmd.args(&["--number=1"])
+        .succeeds()
+        .no_stdout()
+        .no_stderr();
+    assert_eq!(at.read("xaa"), "");
 }
 
 fn file_read(at: &AtPath, filename: &str) -> String {
@@ -478,6 +502,16 @@ creating file 'xaf'
         );
 }
 
+#[test]
+fn test_verbose_number() {
+    for number in ["2", "l/2", "r/2"] {
+        new_ucmd!()
+            .args(&["-n", number, "--verbose", "asciilowercase.txt"])
+            .succeeds()
+            .stdout_only("creating file 'xaa'\ncreating file 'xab'\n");
+    }
+}
+
 #[test]
 fn test_number() {
     let (at, mut ucmd) = at_and_ucmd!();
@@ -494,6 +528,41 @@ fn test_number() {
     assert_eq!(file_read("xae"), "uvwxyz\n");
 }
 
+#[test]
+fn test_number_kth() {
+    new_ucmd!()
+        .args(&["-n", "3/5", "asciilowercase.txt"])
+        .succeeds()
+        .stdout_only("klmno");
+    new_ucmd!()
+        .args(&["-n", "5/5", "asciilowercase.txt"])
+        .succeeds()
+        .stdout_only("uvwxyz\n");
+}
+
+#[test]
+fn test_number_from_stdin() {
+    let (at, mut ucmd) = at_and_ucmd!();
+    ucmd.args(&["-n", "2"])
+        .pipe_in("abcdefg")
+        .succeeds()
+        .no_stdout();
+    assert_eq!(at.read("xaa"), "abc");
+    assert_eq!(at.read("xab"), "defg");
+}
+
+#[test]
+fn test_number_invalid_chunk_number() {
+    new_ucmd!()
+        .args(&["-n", "4/3", "asciilowercase.txt"])
+        .fails()
+        .stderr_only("split: invalid chunk number: 4\n");
+    new_ucmd!()
+        .args(&["-n", "l/0", "asciilowercase.txt"])
+        .fails()
+        .stderr_only("split: invalid number of chunks: 0\n");
+}
+
 #[test]
 fn test_split_number_with_io_blksize() {
     let (at, mut ucmd) = at_and_ucmd!();
@@ -606,6 +675,49 @@ fn test_lines() {
     assert_eq!(file_read("xab"), "4\n5\n");
 }
 
+#[test]
+fn test_lines_spanning_chunks() {
+    // A line goes into the chunk in which it starts, so the chunks
+    // spanned by "bb\n" and "ccc\n" are empty.
+    let (at, mut ucmd) = at_and_ucmd!();
+    ucmd.args(&["-n", "l/5"])
+        .pipe_in("a\nbb\nccc\n")
+        .succeeds()
+        .no_stdout();
+    assert_eq!(at.read("xaa"), "a\n");
+    assert_eq!(at.read("xab"), "");
+    assert_eq!(at.read("xac"), "bb\n");
+    assert_eq!(at.read("xad"), "");
+    assert_eq!(at.read("xae"), "ccc\n");
+}
+
+#[test]
+fn test_lines_elide_empty_files() {
+    let (at, mut ucmd) = at_and_ucmd!();
+    ucmd.args(&["-e", "-n", "l/5"])
+        .pipe_in("a\nbb\nccc\n")
+        .succeeds()
+        .no_stdout();
+    assert_eq!(at.read("xaa"), "a\n");
+    assert_eq!(at.read("xab"), "bb\n");
+    assert_eq!(at.read("xac"), "ccc\n");
+    assert!(!at.plus("xad").exists());
+}
+
+#[test]
+fn test_lines_kth_spanning_chunks() {
+    new_ucmd!()
+        .args(&["-n", "l/3/5"])
+        .pipe_in("a\nbb\nccc\n")
+        .succeeds()
+        .stdout_only("bb\n");
+    new_ucmd!()
+        .args(&["-n", "l/4/5"])
+        .pipe_in("a\nbb\nccc\n")
+        .succeeds()
+        .no_stdout();
+}
+
 #[test]
 fn test_lines_kth() {
     new_ucmd!()
@@ -614,6 +726,20 @@ fn test_lines_kth() {
         .stdout_only("20\n21\n22\n23\n24\n25\n26\n27\n28\n29\n");
 }
 
+#[test]
+fn test_lines_across_reads() {
+    // the start of a line read with the end of the previous chunk goes
+    // into the next chunk
+    let (at, mut ucmd) = at_and_ucmd!();
+    let input: String = (1..=3000).map(|i| format!("{i}\n")).collect();
+    ucmd.args(&["-l", "1", "-d", "-a", "4"])
+        .pipe_in(input)
+        .succeeds();
+    for i in 0..3000 {
+        assert_eq!(at.read(&format!("x{i:04}")), format!("{}\n", i + 1));
+    }
+}
+
 #[test]
 fn test_line_bytes() {
     let (at, mut ucmd) = at_and_ucmd!();
@@ -723,7 +849,7 @@ fn test_multiple_of_input_chunk() {
 #[test]
 fn test_numeric_suffix() {
     let (at, mut ucmd) = at_and_ucmd!();
-    ucmd.args(&["-n", "4", "--numeric-suffixes", "9", "threebytes.txt"])
+    ucmd.args(&["-n", "4", "--numeric-suffixes=9", "threebytes.txt"])
         .succeeds()
         .no_stdout()
         .no_stderr();
@@ -736,7 +862,7 @@ fn test_numeric_suffix() {
 #[test]
 fn test_hex_suffix() {
     let (at, mut ucmd) = at_and_ucmd!();
-    ucmd.args(&["-n", "4", "--hex-suffixes", "9", "threebytes.txt"])
+    ucmd.args(&["-n", "4", "--hex-suffixes=9", "threebytes.txt"])
         .succeeds()
         .no_stdout()
         .no_stderr();
@@ -746,6 +872,75 @@ fn test_hex_suffix() {
     assert_eq!(at.read("x0c"), "");
 }
 
+#[test]
+fn test_numeric_suffix_without_start_value() {
+    // the start value is optional, so it must be given with '='
+    let (at, mut ucmd) = at_and_ucmd!();
+    ucmd.args(&["-l", "2", "--numeric-suffixes", "fivelines.txt"])
+        .succeeds()
+        .no_stdout()
+        .no_stderr();
+    assert_eq!(at.read("x00"), "1\n2\n");
+    assert_eq!(at.read("x01"), "3\n4\n");
+    assert_eq!(at.read("x02"), "5\n");
+}
+
+#[test]
+fn test_suffix_start_disables_auto_widening() {
+    // with a start value, the suffixes don't grow once exhausted
+    let (at, mut ucmd) = at_and_ucmd!();
+    ucmd.args(&["-l", "1", "--numeric-suffixes=98", "fivelines.txt"])
+        .fails()
+        .code_is(1)
+        .stderr_only("split: output file suffixes exhausted\n");
+    assert_eq!(at.read("x98"), "1\n");
+    assert_eq!(at.read("x99"), "2\n");
+    assert!(!at.plus("x9900").exists());
+}
+
+#[test]
+fn test_suffix_length_from_number_of_chunks() {
+    let (at, mut ucmd) = at_and_ucmd!();
+    ucmd.args(&["-n", "101", "-d", "asciilowercase.txt"])
+        .succeeds()
+        .no_stdout()
+        .no_stderr();
+    assert_eq!(at.read("x000"), "a");
+    assert_eq!(at.read("x100"), "");
+
+    // the start value counts when it is smaller than the number of chunks
+    let scene = TestScenario::new(util_name!());
+    scene
+        .ucmd()
+        .args(&["-n", "100", "--numeric-suffixes=5", "-a", "2"])
+        .arg("asciilowercase.txt")
+        .fails()
+        .stderr_only("split: the suffix length needs to be at least 3\n");
+    scene
+        .ucmd()
+        .args(&["-n", "10", "--numeric-suffixes=5", "-a", "2"])
+        .arg("asciilowercase.txt")
+        .succeeds();
+    assert_eq!(scene.fixtures.read("x05"), "ab");
+    assert_eq!(scene.fixtures.read("x14"), "stuvwxyz\n");
+}
+
+#[test]
+fn test_invalid_suffix_start() {
+    new_ucmd!()
+        .args(&["--numeric-suffixes=a", "fivelines.txt"])
+        .fails()
+        .stderr_contains("split: 'a': invalid start value for numerical suffix");
+    new_ucmd!()
+        .args(&["--hex-suffixes=-1", "fivelines.txt"])
+        .fails()
+        .stderr_contains("split: '-1': invalid start value for hexadecimal suffix");
+    new_ucmd!()
+        .args(&["-a", "1", "--numeric-suffixes=10", "fivelines.txt"])
+        .fails()
+        .stderr_contains("split: numerical suffix start value is too large for the suffix length");
+}
+
 #[test]
 fn test_round_robin() {
     let (at, mut ucmd) = at_and_ucmd!();
@@ -761,3 +956,23 @@ fn test_round_robin() {
     assert_eq!(file_read("xaa"), "1\n3\n5\n");
     assert_eq!(file_read("xab"), "2\n4\n");
 }
+
+#[test]
+fn test_round_robin_kth() {
+    new_ucmd!()
+        .args(&["-n", "r/2/2", "fivelines.txt"])
+        .succeeds()
+        .stdout_only("2\n4\n");
+}
+
+#[test]
+fn test_round_robin_elide_empty_files() {
+    let (at, mut ucmd) = at_and_ucmd!();
+    ucmd.args(&["-e", "-n", "r/4"])
+        .pipe_in("1\n2")
+        .succeeds()
+        .no_stdout();
+    assert_eq!(at.read("xaa"), "1\n");
+    assert_eq!(at.read("xab"), "2");
+    assert!(!at.plus("xac").exists());
+}
@@ -327,3 +327,67 @@ fn test_stdin_redirect() {
         .stdout_contains("File: -")
         .succeeded();
 }
+
+#[test]
+#[cfg(any(target_os = "linux", target_os = "android"))]
+fn test_device_numbers() {
+    new_ucmd!()
+        .args(&["-c", "%Hr,%Lr %r %R %t,%T", "/dev/null"])
+        .succeeds()
+        .stdout_only("1,3 259 103 1,3\n");
+
+    let args = ["-c", "%Hd %Ld %d %D|%Hx|%H", "/dev/null"];
+    let ts = TestScenario::new(util_name!());
+    let expected_stdout = unwrap_or_return!(expected_result(&ts, &args)).stdout_move_str();
+    ts.ucmd().args(&args).succeeds().stdout_is(expected_stdout);
+}
+
+#[test]
+#[cfg(unix)]
+fn test_timestamp_precision() {
+    let (at, mut ucmd) = at_and_ucmd!();
+    at.touch("f");
+    let time = filetime::FileTime::from_unix_time(978_307_200, 123_456_789);
+    filetime::set_file_mtime(at.plus("f"), time).unwrap();
+    ucmd.args(&["-c", "%Y|%.Y|%.3Y|%.0Y|%.12Y|%15.3Y|%-15.3Y|%015.3Y", "f"])
+        .succeeds()
+        .stdout_only(
+            "978307200|978307200.123456789|978307200.123|978307200|978307200.123456789000|\
+         \x20 978307200.123|978307200.123  |00978307200.123\n",
+        );
+}
+
+#[test]
+#[cfg(unix)]
+fn test_quoted_file_name() {
+    let (at, mut ucmd) = at_and_ucmd!();
+    at.touch("f");
+    at.relative_symlink_file("f", "link");
+    ucmd.args(&["-c", "%N|%-7N|%.2N", "f", "link"])
+        .succeeds()
+        .stdout_only("'f'|'f'    |'f\n'link' -> 'f'|'link'  -> 'f'    |'l -> 'f\n");
+
+    // the default format shows the names as they are
+    new_ucmd!()
+        .arg(at.plus_as_string("link"))
+        .succeeds()
+        .stdout_contains(format!("File: {} -> f\n", at.plus_as_string("link")));
+}
+
+#[test]
+fn test_invalid_directive() {
+    new_ucmd!()
+        .args(&["-c", "%5%", "."])
+        .fails()
+        .code_is(1)
+        .stderr_only("stat: '%5%': invalid directive\n");
+}
+
+#[test]
+fn test_nonexistent_file() {
+    new_ucmd!()
+        .arg("nonexistent")
+        .fails()
+        .code_is(1)
+        .stderr_only("stat: cannot stat 'nonexistent': No such file or directory\n");
+}
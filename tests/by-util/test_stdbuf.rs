@@ -80,3 +80,41 @@ fn test_stdbuf_invalid_mode_fails() {
         }
     }
 }
+
+#[cfg(not(target_os = "windows"))]
+#[test]
+fn test_stdbuf_command_not_found() {
+    new_ucmd!()
+        .args(&["-o0", "no-such-command"])
+        .fails()
+        .code_is(127)
+        .stderr_only(
+            "stdbuf: failed to run command 'no-such-command': No such file or directory\n",
+        );
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn test_stdbuf_changes_command_buffering() {
+    // awk writes to a pipe through a full buffer by default, and to stderr
+    // unbuffered, so the lines only alternate if stdout is line buffered.
+    let script = r#"{ print; print "err" > "/dev/stderr" }"#;
+    new_ucmd!()
+        .args(&["-oL", "sh", "-c"])
+        .arg(format!("awk '{}' 2>&1", script))
+        .pipe_in("a\nb\n")
+        .succeeds()
+        .stdout_is("a\nerr\nb\nerr\n");
+}
+
+#[cfg(all(target_os = "linux", target_env = "gnu"))]
+#[test]
+fn test_stdbuf_keeps_preloaded_libraries() {
+    let result = new_ucmd!()
+        .env("LD_PRELOAD", "libc.so.6")
+        .args(&["-o0", "printenv", "LD_PRELOAD"])
+        .succeeds();
+    let preload = result.stdout_str().trim_end();
+    assert!(preload.starts_with("libc.so.6:"), "{}", preload);
+    assert!(preload.ends_with("/libstdbuf.so"), "{}", preload);
+}
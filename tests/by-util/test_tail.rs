@@ -602,6 +602,59 @@ fn test_follow_stdin_pipe() {
         .no_stderr();
 }
 
+#[test]
+#[cfg(all(
+    not(target_vendor = "apple"),
+    not(target_os = "windows"),
+    not(target_os = "android"),
+    not(target_os = "freebsd")
+))] // FIXME: for currently not working platforms
+fn test_follow_with_multiple_pids() {
+    use std::process::Command;
+
+    let (at, mut ucmd) = at_and_ucmd!();
+    at.write("data", "a\n");
+
+    let mut dummy1 = Command::new("sh").spawn().unwrap();
+    let mut dummy2 = Command::new("sh").spawn().unwrap();
+
+    let mut child = ucmd
+        .arg("-f")
+        .arg("-s.1")
+        .arg(format!("--pid={}", dummy1.id()))
+        .arg(format!("--pid={}", dummy2.id()))
+        .arg("data")
+        .run_no_wait();
+
+    child
+        .make_assertion_with_delay(500)
+        .is_alive()
+        .with_current_output()
+        .stdout_only("a\n");
+
+    // tail keeps following as long as one of the processes is alive
+    dummy1.kill().unwrap();
+    let _ = dummy1.wait();
+    child.delay(500);
+    at.append("data", "b\n");
+
+    child
+        .make_assertion_with_delay(500)
+        .is_alive()
+        .with_current_output()
+        .stdout_only("b\n");
+
+    dummy2.kill().unwrap();
+    let _ = dummy2.wait();
+
+    child
+        .make_assertion_with_delay(500)
+        .is_not_alive()
+        .with_current_output()
+        .no_output()
+        .success();
+}
+
 #[test]
 #[cfg(not(target_os = "windows"))] // FIXME: for currently not working platforms
 fn test_follow_invalid_pid() {
@@ -780,6 +833,29 @@ fn test_bytes_big() {
     }
 }
 
+#[test]
+fn test_lines_of_file_with_size_multiple_of_block_size() {
+    // The file is read backwards in blocks of 64 KiB.
+    let (at, mut ucmd) = at_and_ucmd!();
+    let line = format!("{}\n", "x".repeat(31));
+    at.write("data", &line.repeat(2 * 2048));
+
+    ucmd.args(&["-n", "2", "data"])
+        .succeeds()
+        .stdout_only(line.repeat(2));
+}
+
+#[test]
+fn test_lines_counted_from_start_of_big_file() {
+    let (at, mut ucmd) = at_and_ucmd!();
+    let long_line = format!("{}\n", "x".repeat(100_000));
+    at.write("data", &format!("{long_line}{long_line}end\n"));
+
+    ucmd.args(&["-n", "+3", "data"])
+        .succeeds()
+        .stdout_only("end\n");
+}
+
 #[test]
 fn test_lines_with_size_suffix() {
     const FILE: &str = "test_lines_with_size_suffix.txt";
@@ -2108,6 +2184,42 @@ fn test_follow_name_move_create1() {
         .stdout_is(expected_stdout);
 }
 
+#[test]
+#[cfg(all(
+    not(target_vendor = "apple"),
+    not(target_os = "windows"),
+    not(target_os = "freebsd")
+))] // FIXME: for currently not working platforms
+fn test_follow_name_rotate_appended_lines() {
+    // The lines appended to a file right before it is rotated must not get lost.
+    // tail -F file & echo b >> file && mv file backup && echo c > file
+
+    for disable_inotify in ["", "---disable-inotify"] {
+        let ts = TestScenario::new(util_name!());
+        let at = &ts.fixtures;
+        at.write("file", "a\n");
+
+        let delay = 1500;
+        let mut args = vec!["-F", "-s.1", "--max-unchanged-stats=1", "file"];
+        if !disable_inotify.is_empty() {
+            args.push(disable_inotify);
+        }
+        let mut p = ts.ucmd().args(&args).run_no_wait();
+        p.make_assertion_with_delay(delay).is_alive();
+
+        at.append("file", "b\n");
+        at.rename("file", "backup");
+        at.write("file", "c\n");
+        p.delay(delay);
+
+        p.make_assertion().is_alive();
+        p.kill()
+            .make_assertion()
+            .with_all_output()
+            .stdout_is("a\nb\nc\n");
+    }
+}
+
 #[test]
 #[cfg(all(
     not(target_vendor = "apple"),
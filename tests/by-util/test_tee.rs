@@ -65,6 +65,50 @@ fn test_tee_append() {
     assert_eq!(at.read(file), content.repeat(2));
 }
 
+#[test]
+#[cfg(unix)]
+fn test_tee_output_not_openable() {
+    let (at, mut ucmd) = at_and_ucmd!();
+    let content = "tee_sample_content";
+    let file_out = "tee_file_out";
+
+    ucmd.arg("no_such_dir/file")
+        .arg(file_out)
+        .pipe_in(content)
+        .fails()
+        .code_is(1)
+        .stdout_is(content)
+        .stderr_is("tee: no_such_dir/file: No such file or directory\n");
+    assert_eq!(at.read(file_out), content);
+}
+
+#[test]
+#[cfg(unix)]
+fn test_tee_output_not_openable_exit() {
+    let (at, mut ucmd) = at_and_ucmd!();
+    let file_out = "tee_file_out";
+
+    ucmd.arg("--output-error=exit")
+        .arg("no_such_dir/file")
+        .arg(file_out)
+        .pipe_in("tee_sample_content")
+        .ignore_stdin_write_error()
+        .fails()
+        .code_is(1)
+        .no_stdout()
+        .stderr_only("tee: no_such_dir/file: No such file or directory\n");
+    assert!(!at.file_exists(file_out));
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn test_tee_read_error() {
+    new_ucmd!()
+        .set_stdin(std::fs::File::open(".").unwrap())
+        .fails()
+        .stderr_only("tee: read error: Is a directory\n");
+}
+
 #[test]
 #[cfg(target_os = "linux")]
 fn test_tee_no_more_writeable_1() {
@@ -113,20 +157,21 @@ mod linux_only {
     use std::fs::File;
     use std::process::{Output, Stdio};
 
-    fn make_broken_pipe() -> File {
-        use libc::c_int;
+    /// Create a pipe whose ends are not inherited by the children spawned
+    /// meanwhile by other tests.
+    fn make_pipe() -> (File, File) {
+        use nix::fcntl::OFlag;
+        use nix::unistd::pipe2;
         use std::os::unix::io::FromRawFd;
 
-        let mut fds: [c_int; 2] = [0, 0];
-        if unsafe { libc::pipe(&mut fds as *mut c_int) } != 0 {
-            panic!("Failed to create pipe");
-        }
+        let (reader, writer) = pipe2(OFlag::O_CLOEXEC).expect("Failed to create pipe");
+        unsafe { (File::from_raw_fd(reader), File::from_raw_fd(writer)) }
+    }
 
+    fn make_broken_pipe() -> File {
         // Drop the read end of the pipe
-        let _ = unsafe { File::from_raw_fd(fds[0]) };
-
-        // Make the write end of the pipe into a Rust File
-        unsafe { File::from_raw_fd(fds[1]) }
+        let (_, writer) = make_pipe();
+        writer
     }
 
     fn run_tee(proc: &mut UCommand) -> (String, Output) {
@@ -207,6 +252,79 @@ mod linux_only {
                 "Expected truncated output to be a prefix of the correct output, but it isn't.\n Correct: {contents}\n Compare: {compare}");
     }
 
+    /// Create a pipe whose buffer is already full, returning its ends and
+    /// the number of bytes in it.
+    fn make_full_pipe() -> (File, File, usize) {
+        use std::io::Write;
+        use std::os::unix::io::AsRawFd;
+
+        let (reader, mut writer) = make_pipe();
+
+        let flags = unsafe { libc::fcntl(writer.as_raw_fd(), libc::F_GETFL) };
+        unsafe { libc::fcntl(writer.as_raw_fd(), libc::F_SETFL, flags | libc::O_NONBLOCK) };
+        let mut filled = 0;
+        while let Ok(n) = writer.write(&[b'x'; 4096]) {
+            filled += n;
+        }
+        unsafe { libc::fcntl(writer.as_raw_fd(), libc::F_SETFL, flags) };
+        (reader, writer, filled)
+    }
+
+    #[test]
+    fn test_stalled_output_does_not_block_others() {
+        use std::io::Read;
+        use std::time::{Duration, Instant};
+
+        let (at, mut ucmd) = at_and_ucmd!();
+        let file_out_a = "tee_file_out_a";
+        let (mut reader, writer, filled) = make_full_pipe();
+
+        let mut child = ucmd
+            .arg(file_out_a)
+            .set_stdin(Stdio::piped())
+            .set_stdout(writer)
+            .run_no_wait();
+        // Kill tee if the test fails, as it would otherwise wait on its full
+        // stdout forever.
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            child.write_in("hello\n");
+            // Nothing reads the full pipe on stdout, but the file still gets the input.
+            let start = Instant::now();
+            while !at.file_exists(file_out_a) || at.read(file_out_a) != "hello\n" {
+                assert!(
+                    start.elapsed() < Duration::from_secs(10),
+                    "tee did not write to the file while stdout was stalled"
+                );
+                std::thread::sleep(Duration::from_millis(10));
+            }
+
+            let mut stdout = vec![0; filled + "hello\n".len()];
+            reader.read_exact(&mut stdout).unwrap();
+            assert!(stdout.ends_with(b"xhello\n"));
+        }));
+        if let Err(e) = result {
+            child.kill();
+            std::panic::resume_unwind(e);
+        }
+        child.close_stdin();
+        child.wait().unwrap().success().no_stderr();
+    }
+
+    #[test]
+    fn test_lockstep() {
+        let (at, mut ucmd) = at_and_ucmd!();
+
+        let file_out_a = "tee_file_out_a";
+
+        let proc = ucmd.arg("--lockstep").arg(file_out_a);
+
+        let (content, output) = run_tee(proc);
+
+        expect_success(&output);
+        assert_eq!(std::str::from_utf8(&output.stdout).unwrap(), content);
+        expect_correct(file_out_a, &at, content.as_str());
+    }
+
     #[test]
     fn test_pipe_error_default() {
         let (at, mut ucmd) = at_and_ucmd!();
@@ -427,6 +545,22 @@ mod linux_only {
         expect_short(file_out_a, &at, content.as_str());
     }
 
+    #[test]
+    fn test_space_error_stdout() {
+        let (at, mut ucmd) = at_and_ucmd!();
+
+        let file_out_a = "tee_file_out_a";
+
+        let proc = ucmd
+            .arg(file_out_a)
+            .set_stdout(File::create("/dev/full").unwrap());
+
+        let (content, output) = run_tee(proc);
+
+        expect_failure(&output, "tee: 'standard output': No space left on device\n");
+        expect_correct(file_out_a, &at, content.as_str());
+    }
+
     #[test]
     fn test_space_error_exit_nopipe() {
         let (at, mut ucmd) = at_and_ucmd!();
@@ -71,8 +71,12 @@ fn test_double_not_is_false() {
 }
 
 #[test]
-fn test_and_not_is_false() {
-    new_ucmd!().args(&["-a", "!"]).run().code_is(1);
+fn test_and_is_not_a_unary_operator() {
+    new_ucmd!()
+        .args(&["-a", "!"])
+        .run()
+        .code_is(2)
+        .stderr_is("test: '-a': unary operator expected\n");
 }
 
 #[test]
@@ -373,8 +377,6 @@ fn test_same_device_inode() {
 }
 
 #[test]
-#[cfg(not(any(target_env = "musl", target_os = "android")))]
-// musl: creation time is not available on this platform currently
 fn test_newer_file() {
     let scenario = TestScenario::new(util_name!());
 
@@ -388,8 +390,16 @@ fn test_newer_file() {
         .succeeds();
     scenario
         .ucmd()
-        .args(&["newer_file", "-ot", "regular_file"])
+        .args(&["regular_file", "-ot", "newer_file"])
         .succeeds();
+    scenario
+        .ucmd()
+        .args(&["regular_file", "-nt", "newer_file"])
+        .fails();
+    scenario
+        .ucmd()
+        .args(&["newer_file", "-ot", "regular_file"])
+        .fails();
 }
 
 #[test]
@@ -932,3 +942,149 @@ fn test_long_integer() {
         ])
         .fails();
 }
+
+#[test]
+fn test_integers_of_any_size() {
+    let scenario = TestScenario::new(util_name!());
+    let tests = [
+        [
+            "100000000000000000000000000",
+            "-gt",
+            "99999999999999999999999999",
+        ],
+        [
+            "-100000000000000000000000000",
+            "-lt",
+            "-99999999999999999999999999",
+        ],
+        ["-0", "-eq", "+0"],
+        [" +42\t", "-eq", "042"],
+    ];
+
+    for test in &tests {
+        scenario.ucmd().args(&test[..]).succeeds();
+    }
+
+    for invalid in ["1 2", "0x10", "+", "- 1"] {
+        scenario
+            .ucmd()
+            .args(&[invalid, "-eq", "0"])
+            .run()
+            .code_is(2)
+            .stderr_is(format!("test: invalid integer '{invalid}'\n"));
+    }
+}
+
+#[test]
+fn test_string_length_operand() {
+    let scenario = TestScenario::new(util_name!());
+    scenario.ucmd().args(&["-l", "abc", "-eq", "3"]).succeeds();
+    scenario.ucmd().args(&["2", "-lt", "-l", "abc"]).succeeds();
+    scenario
+        .ucmd()
+        .args(&["-l", "", "-eq", "0", "-a", "-l", "ab", "-ne", "-l", "a"])
+        .succeeds();
+    scenario
+        .ucmd()
+        .args(&["a", "-nt", "-l", "b"])
+        .run()
+        .code_is(2)
+        .stderr_is("test: -nt does not accept -l\n");
+}
+
+#[test]
+fn test_terminal_fd() {
+    let scenario = TestScenario::new(util_name!());
+    scenario
+        .ucmd()
+        .args(&["-t", "99999999999999999999"])
+        .run()
+        .code_is(1);
+    scenario
+        .ucmd()
+        .args(&["-t", "fd"])
+        .run()
+        .code_is(2)
+        .stderr_is("test: invalid integer 'fd'\n");
+}
+
+#[test]
+fn test_files_compared_with_nonexistent_file() {
+    let scenario = TestScenario::new(util_name!());
+    scenario.fixtures.touch("regular_file");
+
+    // An existing file is newer than one that doesn't exist.
+    scenario
+        .ucmd()
+        .args(&["regular_file", "-nt", "nonexistent_file"])
+        .succeeds();
+    scenario
+        .ucmd()
+        .args(&["nonexistent_file", "-ot", "regular_file"])
+        .succeeds();
+    for test in [
+        ["nonexistent_file", "-nt", "regular_file"],
+        ["regular_file", "-ot", "nonexistent_file"],
+        ["nonexistent_file", "-ef", "nonexistent_file"],
+    ] {
+        scenario.ucmd().args(&test).run().code_is(1).no_stderr();
+    }
+}
+
+#[test]
+fn test_three_arguments() {
+    let scenario = TestScenario::new(util_name!());
+    // A binary operator in the middle is always an operator.
+    scenario.ucmd().args(&["!", "=", "!"]).succeeds();
+    scenario.ucmd().args(&["-z", "=", "-z"]).succeeds();
+    scenario.ucmd().args(&["", "-o", "x"]).succeeds();
+    scenario.ucmd().args(&["(", "-a", ")"]).succeeds();
+    scenario.ucmd().args(&["!", "-a", ""]).run().code_is(1);
+    // Otherwise `!` negates the rest, and parentheses group one argument.
+    scenario.ucmd().args(&["!", "-z", "x"]).succeeds();
+    scenario.ucmd().args(&["(", "", ")"]).run().code_is(1);
+    scenario
+        .ucmd()
+        .args(&["a", "b", "c"])
+        .run()
+        .code_is(2)
+        .stderr_is("test: 'b': binary operator expected\n");
+}
+
+#[test]
+fn test_four_arguments() {
+    let scenario = TestScenario::new(util_name!());
+    scenario.ucmd().args(&["!", "a", "=", "b"]).succeeds();
+    scenario.ucmd().args(&["!", "!", "-n", ""]).run().code_is(1);
+    scenario.ucmd().args(&["(", "-z", "", ")"]).succeeds();
+    scenario.ucmd().args(&["(", "!", "", ")"]).succeeds();
+    scenario.ucmd().args(&["x", "-a", "-z", ""]).succeeds();
+    scenario
+        .ucmd()
+        .args(&["(", "a", "b", ")"])
+        .run()
+        .code_is(2)
+        .stderr_is("test: missing argument after ')'\n");
+}
+
+#[test]
+fn test_syntax_errors() {
+    let scenario = TestScenario::new(util_name!());
+    let tests = [
+        (&["-q", "x"][..], "'-q': unary operator expected"),
+        (&["(", "a", "-a", "b", "c", "d"], "')' expected, found 'c'"),
+        (&["(", "a", "-a", "b", "-a", "c"], "')' expected"),
+        (&["a", "=", "b", "c"], "extra argument 'c'"),
+        (&["a", "-a", "b", "-o"], "missing argument after '-o'"),
+        (&["!", "!", "!", "!", "!"], "missing argument after '!'"),
+    ];
+
+    for (args, error) in tests {
+        scenario
+            .ucmd()
+            .args(args)
+            .run()
+            .code_is(2)
+            .stderr_is(format!("test: {error}\n"));
+    }
+}
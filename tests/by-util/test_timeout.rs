@@ -130,3 +130,71 @@ fn test_kill_after_long() {
         .no_stdout()
         .no_stderr();
 }
+
+#[test]
+fn test_preserve_status_waits_for_command() {
+    // The command handles the signal and exits on its own terms.
+    new_ucmd!()
+        .args(&[
+            "--preserve-status",
+            ".1",
+            "sh",
+            "-c",
+            "trap 'exit 7' TERM; sleep 10 & wait",
+        ])
+        .fails()
+        .code_is(7)
+        .no_output();
+    // Without --preserve-status, a timeout is a timeout.
+    new_ucmd!()
+        .args(&[".1", "sh", "-c", "trap '' TERM; sleep .5; exit 4"])
+        .fails()
+        .code_is(124)
+        .no_output();
+}
+
+#[test]
+fn test_kill_after_escalation() {
+    let command = ["sh", "-c", "trap '' TERM; sleep 10"];
+    // The KILL signal goes to the whole process group, timeout included.
+    new_ucmd!()
+        .args(&["-k", ".1", ".1"])
+        .args(&command)
+        .fails()
+        .signal_name_is("KILL");
+    for args in [
+        &["--foreground", "-k", ".1", ".1"][..],
+        &["--foreground", "--preserve-status", "-k", ".1", ".1"],
+    ] {
+        new_ucmd!()
+            .args(args)
+            .args(&command)
+            .fails()
+            // 128 + SIGKILL = 128 + 9
+            .code_is(128 + 9);
+    }
+}
+
+#[test]
+fn test_command_signaled_without_timeout() {
+    // timeout dies of the same signal, as the command was not timed out
+    new_ucmd!()
+        .args(&["10", "sh", "-c", "kill -USR1 $$"])
+        .fails()
+        .signal_name_is("USR1");
+}
+
+#[test]
+fn test_command_cannot_run() {
+    new_ucmd!()
+        .args(&["1", "/nonexistent"])
+        .fails()
+        .code_is(127)
+        .stderr_only("timeout: failed to run command '/nonexistent': No such file or directory\n");
+    let (at, mut ucmd) = at_and_ucmd!();
+    at.touch("not_executable");
+    ucmd.args(&["1", "./not_executable"])
+        .fails()
+        .code_is(126)
+        .stderr_only("timeout: failed to run command './not_executable': Permission denied\n");
+}
@@ -438,6 +438,7 @@ fn test_touch_set_date4() {
     let file = "test_touch_set_date";
 
     ucmd.args(&["-d", "1970-01-01 18:43:33", file])
+        .env("TZ", "UTC")
         .succeeds()
         .no_stderr();
 
@@ -456,6 +457,7 @@ fn test_touch_set_date5() {
     let file = "test_touch_set_date";
 
     ucmd.args(&["-d", "1970-01-01 18:43:33.023456789", file])
+        .env("TZ", "UTC")
         .succeeds()
         .no_stderr();
 
@@ -480,6 +482,7 @@ fn test_touch_set_date6() {
     let file = "test_touch_set_date";
 
     ucmd.args(&["-d", "2000-01-01 00:00", file])
+        .env("TZ", "UTC")
         .succeeds()
         .no_stderr();
 
@@ -611,7 +614,73 @@ fn test_touch_set_date_wrong_format() {
 
     ucmd.args(&["-d", "2005-43-21", file])
         .fails()
-        .stderr_contains("Unable to parse date: 2005-43-21");
+        .stderr_contains("touch: invalid date format '2005-43-21'");
+}
+
+#[test]
+fn test_touch_set_date_relative_to_now() {
+    for (date, offset) in [
+        ("2 days ago", -2 * 86400),
+        ("next week", 7 * 86400),
+        ("last fortnight", -14 * 86400),
+        ("1 day 2 hours ago", 86400 - 2 * 3600),
+    ] {
+        let (at, mut ucmd) = at_and_ucmd!();
+        let before = FileTime::now().unix_seconds();
+        ucmd.args(&["-d", date, "f"])
+            .env("TZ", "UTC")
+            .succeeds()
+            .no_output();
+        let after = FileTime::now().unix_seconds();
+        let (_, mtime) = get_file_times(&at, "f");
+        assert!(
+            (before + offset..=after + offset).contains(&mtime.unix_seconds()),
+            "{date:?}"
+        );
+    }
+}
+
+#[test]
+fn test_touch_set_date_iso_variants() {
+    for date in [
+        "2020-03-12T10:00:00Z",
+        "2020-03-12 11:00:00+01:00",
+        "2020-03-12 10:00 UTC",
+        "Thu, Mar 12, 2020 10:00",
+        "3/12/2020 10am",
+        "12 mar 2020 10:00",
+    ] {
+        let (at, mut ucmd) = at_and_ucmd!();
+        ucmd.args(&["-d", date, "f"])
+            .env("TZ", "UTC")
+            .succeeds()
+            .no_output();
+        let (_, mtime) = get_file_times(&at, "f");
+        assert_eq!(
+            mtime,
+            FileTime::from_unix_time(1_584_007_200, 0),
+            "{date:?}"
+        );
+    }
+}
+
+#[test]
+fn test_touch_set_two_digit_year() {
+    let (at, mut ucmd) = at_and_ucmd!();
+    ucmd.args(&["-t", "6901011200", "f"])
+        .env("TZ", "UTC")
+        .succeeds()
+        .no_output();
+    let (_, mtime) = get_file_times(&at, "f");
+    assert_eq!(mtime, FileTime::from_unix_time(-31_492_800, 0));
+}
+
+#[test]
+fn test_touch_set_timestamp_wrong_format() {
+    new_ucmd!()
+        .args(&["-t", "1301011299", "f"])
+        .fails()
+        .stderr_only("touch: invalid date format '1301011299'\n");
 }
 
 #[test]
@@ -27,12 +27,41 @@ fn test_small_set2() {
 #[test]
 fn test_unicode() {
     new_ucmd!()
-        .args(&[", ┬─┬", "╯︵┻━┻"])
+        .args(&["--utf8", ", ┬─┬", "╯︵┻━┻"])
         .pipe_in("(,°□°）, ┬─┬")
         .run()
         .stdout_is("(╯°□°）╯︵┻━┻");
 }
 
+#[test]
+fn test_bytes() {
+    // without --utf8, every byte of a character is translated on its own
+    new_ucmd!()
+        .args(&["é", "xy"])
+        .pipe_in("aé")
+        .succeeds()
+        .stdout_is("axy");
+    new_ucmd!()
+        .args(&["-d", "\\200-\\377"])
+        .pipe_in(&b"a\xc3\xa9\xffb"[..])
+        .succeeds()
+        .stdout_is("ab");
+    new_ucmd!()
+        .args(&["-c", "\\000-\\177", "?"])
+        .pipe_in(&b"a\xffb\n"[..])
+        .succeeds()
+        .stdout_is("a?b\n");
+}
+
+#[test]
+fn test_utf8_invalid_input() {
+    new_ucmd!()
+        .args(&["--utf8", "a", "é"])
+        .pipe_in(&b"a\xffb"[..])
+        .succeeds()
+        .stdout_is("é\u{FFFD}b");
+}
+
 #[test]
 fn test_delete() {
     new_ucmd!()
@@ -729,10 +758,10 @@ fn check_against_gnu_tr_tests_w() {
     //  {IN=>"\300\301\377\345\345\350\345"},
     //  {OUT=>"\300\301\377\345"}],
     new_ucmd!()
-        .args(&["-ds", "\u{350}", "\u{345}"])
-        .pipe_in("\u{300}\u{301}\u{377}\u{345}\u{345}\u{350}\u{345}")
+        .args(&["-ds", "\\350", "\\345"])
+        .pipe_in(&b"\xc0\xc1\xff\xe5\xe5\xe8\xe5"[..])
         .succeeds()
-        .stdout_is("\u{300}\u{301}\u{377}\u{345}");
+        .stdout_is_bytes(b"\xc0\xc1\xff\xe5");
 }
 
 #[test]
@@ -777,10 +806,7 @@ fn check_against_gnu_tr_tests_range_a_a() {
         .stdout_is("zbc");
 }
 
-// FIXME: Since pr https://github.com/uutils/coreutils/pull/4261:
-// stderr ends with 2 newlines but expected is only 1.
 #[test]
-#[cfg(disabled_until_fixed)]
 fn check_against_gnu_tr_tests_null() {
     // ['null', qw(a ''), {IN=>''}, {OUT=>''}, {EXIT=>1},
     //  {ERR=>"$prog: when not truncating set1, string2 must be non-empty\n"}],
@@ -855,10 +881,7 @@ fn check_against_gnu_tr_tests_rep_3() {
         .stdout_is("1x2");
 }
 
-// FIXME: Since pr https://github.com/uutils/coreutils/pull/4261:
-// stderr ends with 2 newlines but expected is only 1.
 #[test]
-#[cfg(disabled_until_fixed)]
 fn check_against_gnu_tr_tests_o_rep_1() {
     // # Another couple octal repeat count tests.
     // ['o-rep-1', qw('[b*08]' '[x*]'), {IN=>''}, {OUT=>''}, {EXIT=>1},
@@ -935,7 +958,6 @@ fn check_against_gnu_tr_tests_bs_at_end() {
 }
 
 #[test]
-#[ignore = "not sure why GNU bails here. `[Y*]` should be able to generate all the mapping"]
 fn check_against_gnu_tr_tests_ross_0a() {
     // # From Ross
     // ['ross-0a', qw(-cs '[:upper:]' 'X[Y*]'), {IN=>''}, {OUT=>''}, {EXIT=>1},
@@ -948,7 +970,6 @@ fn check_against_gnu_tr_tests_ross_0a() {
 }
 
 #[test]
-#[ignore = "not sure why GNU bails here. `[Y*]` should be able to generate all the mapping"]
 fn check_against_gnu_tr_tests_ross_0b() {
     // ['ross-0b', qw(-cs '[:cntrl:]' 'X[Y*]'), {IN=>''}, {OUT=>''}, {EXIT=>1},
     //  {ERR=>$map_all_to_1}],
@@ -956,7 +977,7 @@ fn check_against_gnu_tr_tests_ross_0b() {
         .args(&["-cs", "[:cntrl:]", "X[Y*]"])
         .pipe_in("")
         .fails()
-        .stderr_is("tr: when translating with complemented character classes,\nstring2 must map all characters in the domain to one");
+        .stderr_is("tr: when translating with complemented character classes,\nstring2 must map all characters in the domain to one\n");
 }
 
 #[test]
@@ -1032,10 +1053,6 @@ fn check_against_gnu_tr_tests_ross_6() {
         .stdout_is("");
 }
 
-// FIXME: Since pr https://github.com/uutils/coreutils/pull/4261:
-// stderr ends with 2 newlines but expected is only 1.
-#[test]
-#[cfg(disabled_until_fixed)]
 #[test]
 fn check_against_gnu_tr_tests_empty_eq() {
     // # Ensure that these fail.
@@ -1049,10 +1066,6 @@ fn check_against_gnu_tr_tests_empty_eq() {
         .stderr_is("tr: missing equivalence class character '[==]'\n");
 }
 
-// FIXME: Since pr https://github.com/uutils/coreutils/pull/4261:
-// stderr ends with 2 newlines but expected is only 1.
-#[test]
-#[cfg(disabled_until_fixed)]
 #[test]
 fn check_against_gnu_tr_tests_empty_cc() {
     // ['empty-cc', qw('[::]' x), {IN=>''}, {OUT=>''}, {EXIT=>1},
@@ -1138,3 +1151,126 @@ fn check_against_gnu_tr_tests_no_abort_1() {
         .succeeds()
         .stdout_is("abb");
 }
+
+#[test]
+fn test_graph_and_print_classes() {
+    new_ucmd!()
+        .args(&["[:graph:]", "x"])
+        .pipe_in("a b,\t1\n")
+        .succeeds()
+        .stdout_is("x xx\tx\n");
+    new_ucmd!()
+        .args(&["[:print:]", "x"])
+        .pipe_in("a b,\t1\n")
+        .succeeds()
+        .stdout_is("xxxx\tx\n");
+    // the characters are in ascending order
+    new_ucmd!()
+        .args(&["[:graph:]", "[:upper:][:lower:][x*]"])
+        .pipe_in("! #")
+        .fails()
+        .stderr_is("tr: misaligned [:upper:] and/or [:lower:] construct\n");
+}
+
+#[test]
+fn test_reversed_range() {
+    new_ucmd!()
+        .args(&["z-a", "x"])
+        .fails()
+        .stderr_is("tr: range-endpoints of 'z-a' are in reverse collating sequence order\n");
+}
+
+#[test]
+fn test_invalid_class() {
+    new_ucmd!()
+        .args(&["[:foo:]", "x"])
+        .fails()
+        .stderr_is("tr: invalid character class 'foo'\n");
+}
+
+#[test]
+fn test_equivalence_class() {
+    new_ucmd!()
+        .args(&["-d", "[=a=][=\\n=]"])
+        .pipe_in("abc\n")
+        .succeeds()
+        .stdout_is("bc");
+    new_ucmd!()
+        .args(&["[=ab=]", "x"])
+        .fails()
+        .stderr_is("tr: ab: equivalence class operand must be a single character\n");
+    new_ucmd!()
+        .args(&["a", "[=b=]"])
+        .fails()
+        .stderr_is("tr: [=c=] expressions may not appear in string2 when translating\n");
+}
+
+#[test]
+fn test_class_in_set2() {
+    new_ucmd!().args(&["abc", "[:digit:]"]).fails().stderr_is(
+        "tr: when translating, the only character classes that may appear in\n\
+             string2 are 'upper' and 'lower'\n",
+    );
+    new_ucmd!()
+        .args(&["a-c", "[:upper:]"])
+        .fails()
+        .stderr_is("tr: misaligned [:upper:] and/or [:lower:] construct\n");
+    new_ucmd!()
+        .args(&["-c", "a", "[:upper:]"])
+        .fails()
+        .stderr_is(
+            "tr: when translating with string1 longer than string2,\n\
+             the latter string must not end with a character class\n",
+        );
+    // any class is fine for squeezing
+    new_ucmd!()
+        .args(&["-ds", "a", "[:digit:]"])
+        .pipe_in("a1122")
+        .succeeds()
+        .stdout_is("12");
+}
+
+#[test]
+fn test_invalid_repeat_count() {
+    new_ucmd!()
+        .args(&["abc", "[x*9a]"])
+        .fails()
+        .stderr_is("tr: invalid repeat count '9a' in [c*n] construct\n");
+}
+
+#[test]
+fn test_delete_with_two_sets() {
+    new_ucmd!().args(&["-d", "a", "b"]).fails().stderr_contains(
+        "tr: extra operand 'b'\n\
+             Only one string may be given when deleting without squeezing repeats.\n",
+    );
+}
+
+#[test]
+fn test_octal_escape_of_a_byte() {
+    // \400 is not a byte, so it is \40 followed by 0
+    new_ucmd!()
+        .args(&["\\400", "xy"])
+        .pipe_in("a 0")
+        .succeeds()
+        .stdout_is("axy");
+}
+
+#[test]
+fn test_escaped_backslash_before_digits() {
+    new_ucmd!()
+        .args(&["\\\\101", "xyzw"])
+        .pipe_in("\\1A")
+        .succeeds()
+        .stdout_is("xwA");
+}
+
+#[test]
+fn test_squeeze_across_blocks() {
+    let input = "a".repeat(100_000) + "b";
+    new_ucmd!()
+        .args(&["-s", "a"])
+        .pipe_in(input)
+        .succeeds()
+        .stdout_is("ab");
+}
@@ -368,3 +368,101 @@ fn test_fifo_error_reference_and_size() {
         .no_stdout()
         .stderr_contains("cannot open 'fifo' for writing: No such device or address");
 }
+
+#[test]
+fn test_round_up_exact_multiple() {
+    let (at, mut ucmd) = at_and_ucmd!();
+    at.write(FILE1, "12345678");
+    ucmd.args(&["--size", "%4", FILE1]).succeeds();
+    assert_eq!(at.metadata(FILE1).len(), 8);
+}
+
+#[test]
+fn test_reference_and_rounding() {
+    let scene = TestScenario::new(util_name!());
+    let at = &scene.fixtures;
+    at.write(FILE1, "1234567");
+    for (size, expected) in [("%4", 8), ("/4", 4), ("<5", 5), (">10", 10), ("-20", 0)] {
+        at.write(FILE2, "1234567890");
+        scene
+            .ucmd()
+            .args(&["-r", FILE1, "-s", size, FILE2])
+            .succeeds()
+            .no_stderr();
+        assert_eq!(at.metadata(FILE2).len(), expected, "for size {size}");
+    }
+}
+
+#[test]
+fn test_size_with_whitespace_after_modifier() {
+    let (at, mut ucmd) = at_and_ucmd!();
+    at.write(FILE1, "1234567890");
+    ucmd.args(&["-s", " < 4", FILE1]).succeeds().no_stderr();
+    assert_eq!(at.metadata(FILE1).len(), 4);
+}
+
+#[test]
+fn test_invalid_relative_sizes() {
+    new_ucmd!()
+        .args(&["-s", "<+3", FILE1])
+        .fails()
+        .code_is(1)
+        .stderr_contains("multiple relative modifiers specified")
+        .stderr_contains("for more information");
+    new_ucmd!()
+        .args(&["-s", "+-3", FILE1])
+        .fails()
+        .stderr_only("truncate: Invalid number: '+-3'\n");
+    new_ucmd!()
+        .args(&["-s", "+ 3", FILE1])
+        .fails()
+        .stderr_only("truncate: Invalid number: '+ 3'\n");
+    new_ucmd!()
+        .args(&["-s", "9223372036854775808", FILE1])
+        .fails()
+        .stderr_only(
+            "truncate: Invalid number: '9223372036854775808': \
+            Value too large for defined data type\n",
+        );
+}
+
+#[test]
+fn test_reference_requires_relative_size() {
+    let (at, mut ucmd) = at_and_ucmd!();
+    at.touch(FILE1);
+    ucmd.args(&["-r", FILE1, "-s", "10", FILE2])
+        .fails()
+        .code_is(1)
+        .stderr_contains("you must specify a relative '--size' with '--reference'")
+        .stderr_contains("for more information");
+    assert!(!at.file_exists(FILE2));
+}
+
+#[test]
+fn test_overflow_extending_size() {
+    let (at, mut ucmd) = at_and_ucmd!();
+    at.write(FILE1, "1");
+    ucmd.args(&["-s", "+9223372036854775807", FILE1])
+        .fails()
+        .stderr_only(format!(
+            "truncate: overflow extending size of file '{FILE1}'\n"
+        ));
+    assert_eq!(at.metadata(FILE1).len(), 1);
+}
+
+#[test]
+fn test_error_does_not_stop_other_files() {
+    let (at, mut ucmd) = at_and_ucmd!();
+    ucmd.args(&["-s", "3", "a/b", FILE1])
+        .fails()
+        .stderr_only("truncate: cannot open 'a/b' for writing: No such file or directory\n");
+    assert_eq!(at.metadata(FILE1).len(), 3);
+}
+
+#[test]
+fn test_reference_creates_files() {
+    let (at, mut ucmd) = at_and_ucmd!();
+    at.write(FILE1, "12345");
+    ucmd.args(&["-r", FILE1, FILE2]).succeeds().no_stderr();
+    assert_eq!(at.metadata(FILE2).len(), 5);
+}
@@ -163,13 +163,61 @@ fn test_stdin_zero_terminated() {
 
 #[test]
 fn test_invalid_utf8() {
+    let (at, mut ucmd) = at_and_ucmd!();
+    let mut expected = at.read_bytes("not-utf8-sequence.txt");
+    expected.push(b'\n');
+    ucmd.arg("not-utf8-sequence.txt")
+        .succeeds()
+        .stdout_is_bytes(expected);
+}
+
+#[test]
+fn test_invalid_utf8_keys() {
     new_ucmd!()
-        .arg("not-utf8-sequence.txt")
-        .run()
-        .failure()
-        .stderr_only(
-        "uniq: failed to convert line to utf8: invalid utf-8 sequence of 1 bytes from index 0\n",
-    );
+        .args(&["-c", "-f1"])
+        .pipe_in(&b"a \xcc\xcd\nb \xcc\xcd\nc \xcc\n"[..])
+        .succeeds()
+        .stdout_is_bytes(b"      2 a \xcc\xcd\n      1 c \xcc\n");
+    new_ucmd!()
+        .args(&["-i", "-w2"])
+        .pipe_in(&b"A\xffx\na\xffy\n"[..])
+        .succeeds()
+        .stdout_is_bytes(b"A\xffx\n");
+}
+
+#[test]
+fn test_skip_and_check_bytes_or_characters() {
+    // "é" is two bytes long
+    new_ucmd!()
+        .args(&["-s1", "-w1"])
+        .pipe_in("éa\néb\n")
+        .succeeds()
+        .stdout_is("éa\n");
+    new_ucmd!()
+        .args(&["--characters", "-s1", "-w1"])
+        .pipe_in("éa\néb\n")
+        .succeeds()
+        .stdout_is("éa\néb\n");
+    new_ucmd!()
+        .args(&["-w1"])
+        .pipe_in("éa\nè\n")
+        .succeeds()
+        .stdout_is("éa\n");
+    new_ucmd!()
+        .args(&["--characters", "-w1"])
+        .pipe_in("éa\nè\n")
+        .succeeds()
+        .stdout_is("éa\nè\n");
+    new_ucmd!()
+        .args(&["--characters", "-i"])
+        .pipe_in("É\né\n")
+        .succeeds()
+        .stdout_is("É\n");
+    new_ucmd!()
+        .args(&["-i"])
+        .pipe_in("É\né\n")
+        .succeeds()
+        .stdout_is("É\né\n");
 }
 
 #[test]
@@ -231,6 +279,18 @@ fn test_group_separate() {
         .stdout_is_fixture("group.expected");
 }
 
+#[test]
+fn test_group_conflicts() {
+    for arg in ["-c", "-d", "-D", "-u"] {
+        new_ucmd!()
+            .args(&["--group", arg])
+            .pipe_in("a\n")
+            .fails()
+            .code_is(1)
+            .stderr_contains("cannot be used with");
+    }
+}
+
 #[test]
 fn test_case2() {
     new_ucmd!().pipe_in("a\na\n").run().stdout_is("a\n");
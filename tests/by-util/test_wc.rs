@@ -70,6 +70,31 @@ fn test_utf8_words() {
         .stdout_is("87\n");
 }
 
+#[test]
+fn test_words_with_control_chars() {
+    // Control characters are neither part of a word nor separate words.
+    new_ucmd!()
+        .arg("-w")
+        .pipe_in("a\x01b c\x7f d\x01 \x01e\n")
+        .run()
+        .stdout_is("4\n");
+}
+
+#[test]
+fn test_words_long_input() {
+    // The words cross the boundaries of the blocks that are counted at once.
+    new_ucmd!()
+        .arg("-lw")
+        .pipe_in("abcdefg hij\t".repeat(10_000))
+        .run()
+        .stdout_is("      0   20000\n");
+    new_ucmd!()
+        .arg("-wL")
+        .pipe_in("ab \u{e9}t\u{e9}\n".repeat(10_000))
+        .run()
+        .stdout_is("  20000       6\n");
+}
+
 #[test]
 fn test_utf8_line_length_words() {
     new_ucmd!()
@@ -276,6 +301,29 @@ fn test_multiple_default() {
         );
 }
 
+/// Test that the counts of many files are printed in order, even if they are counted at once.
+#[test]
+fn test_many_files_in_order() {
+    let (at, mut ucmd) = at_and_ucmd!();
+    let mut args = Vec::new();
+    let mut expected = String::new();
+    for i in 0..50 {
+        let name = format!("f{i}");
+        at.write(&name, &"x\n".repeat(i * 100));
+        expected.push_str(&format!("{:6} {name}\n", i * 100));
+        args.push(name);
+        if i == 20 {
+            args.push("missing".to_string());
+        }
+    }
+    expected.push_str(&format!("{:6} total\n", 49 * 50 / 2 * 100));
+    ucmd.arg("-l")
+        .args(&args)
+        .fails()
+        .stdout_is(expected)
+        .stderr_contains("wc: missing: No such file or directory");
+}
+
 /// Test for an empty file.
 #[test]
 fn test_file_empty() {
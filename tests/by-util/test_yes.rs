@@ -68,6 +68,25 @@ fn test_long_input() {
     run(&[&arg[..arg.len() - 1]], expected_out.as_bytes());
 }
 
+/// Test that an input longer than the 64 KiB buffer is written in whole lines.
+#[test]
+#[cfg(not(windows))]
+fn test_input_longer_than_buffer() {
+    let arg = "0123456789".repeat(7000);
+    let expected_out = (arg.clone() + "\n").repeat(5);
+    run(&[&arg], expected_out.as_bytes());
+}
+
+/// Test that lines whose length doesn't divide the buffer size are never split between
+/// buffers.
+#[test]
+fn test_input_not_dividing_buffer() {
+    for arg in ["abcdefghij", &"x".repeat(999), &"y".repeat(40000)] {
+        let expected_out = (arg.to_string() + "\n").repeat(300 * 1024 / (arg.len() + 1));
+        run(&[arg], expected_out.as_bytes());
+    }
+}
+
 #[test]
 #[cfg(any(target_os = "linux", target_os = "freebsd", target_os = "netbsd"))]
 fn test_piped_to_dev_full() {
@@ -2810,6 +2810,8 @@ mod tests {
             .signal_is(9)
             .signal_name_is("SIGKILL")
             .signal_name_is("KILL")
+            .signal_name_is("sigkill")
+            .signal_name_is("kill")
             .signal_name_is("9")
             .signal()
             .expect("Signal was none");
@@ -2818,8 +2820,6 @@ mod tests {
     #[cfg(feature = "sleep")]
     #[cfg(unix)]
     #[rstest]
-    #[case::signal_full_name_lower_case("sigkill")]
-    #[case::signal_short_name_lower_case("kill")]
     #[case::signal_only_part_of_name("IGKILL")] // spell-checker: disable-line
     #[case::signal_just_sig("SIG")]
     #[case::signal_value_too_high("100")]